@@ -5,7 +5,7 @@ use crate::agent::memory_loader::{DefaultMemoryLoader, MemoryLoader};
 use crate::agent::prompt::{PromptContext, SystemPromptBuilder};
 use crate::config::Config;
 use crate::memory::{self, Memory, MemoryCategory};
-use crate::observability::{self, Observer, ObserverEvent};
+use crate::observability::{self, Observer, ObserverEvent, TraceContext};
 use crate::providers::{self, ChatMessage, ChatRequest, ConversationMessage, Provider};
 use crate::runtime;
 use crate::security::SecurityPolicy;
@@ -25,6 +25,7 @@ pub struct Agent {
     tool_dispatcher: Box<dyn ToolDispatcher>,
     memory_loader: Box<dyn MemoryLoader>,
     config: crate::config::AgentConfig,
+    provider_name: String,
     model_name: String,
     temperature: f64,
     workspace_dir: std::path::PathBuf,
@@ -46,6 +47,7 @@ pub struct AgentBuilder {
     tool_dispatcher: Option<Box<dyn ToolDispatcher>>,
     memory_loader: Option<Box<dyn MemoryLoader>>,
     config: Option<crate::config::AgentConfig>,
+    provider_name: Option<String>,
     model_name: Option<String>,
     temperature: Option<f64>,
     workspace_dir: Option<std::path::PathBuf>,
@@ -68,6 +70,7 @@ impl AgentBuilder {
             tool_dispatcher: None,
             memory_loader: None,
             config: None,
+            provider_name: None,
             model_name: None,
             temperature: None,
             workspace_dir: None,
@@ -120,6 +123,12 @@ impl AgentBuilder {
         self
     }
 
+    /// Provider label reported to the observer.
+    pub fn provider_name(mut self, provider_name: String) -> Self {
+        self.provider_name = Some(provider_name);
+        self
+    }
+
     pub fn model_name(mut self, model_name: String) -> Self {
         self.model_name = Some(model_name);
         self
@@ -199,6 +208,7 @@ impl AgentBuilder {
                 .memory_loader
                 .unwrap_or_else(|| Box::new(DefaultMemoryLoader::default())),
             config: self.config.unwrap_or_default(),
+            provider_name: self.provider_name.unwrap_or_else(|| "unknown".into()),
             model_name: self
                 .model_name
                 .unwrap_or_else(|| "anthropic/claude-sonnet-4-20250514".into()),
@@ -231,8 +241,15 @@ impl Agent {
     }
 
     pub fn from_config(config: &Config) -> Result<Self> {
-        let observer: Arc<dyn Observer> =
-            Arc::from(observability::create_observer(&config.observability));
+        let base_observer = observability::create_observer(&config.observability);
+        let observer: Arc<dyn Observer> = if config.runtime.show_reasoning {
+            Arc::new(observability::MultiObserver::new(vec![
+                base_observer,
+                Box::new(observability::ReasoningEchoObserver),
+            ]))
+        } else {
+            Arc::from(base_observer)
+        };
         let runtime: Arc<dyn runtime::RuntimeAdapter> =
             Arc::from(runtime::create_runtime(&config.runtime)?);
         let security = Arc::new(SecurityPolicy::from_config(
@@ -282,13 +299,22 @@ impl Agent {
             .unwrap_or("anthropic/claude-sonnet-4-20250514")
             .to_string();
 
-        let provider: Box<dyn Provider> = providers::create_routed_provider(
+        let provider_runtime_options = providers::ProviderRuntimeOptions {
+            auth_profile_override: None,
+            zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
+            secrets_encrypt: config.secrets.encrypt,
+            reasoning: config.runtime.effective_reasoning(),
+            cassette: config.cassette.clone(),
+        };
+
+        let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
             provider_name,
             config.api_key.as_deref(),
             config.api_url.as_deref(),
             &config.reliability,
             &config.model_routes,
             &model_name,
+            &provider_runtime_options,
         )?;
 
         let dispatcher_choice = config.agent.tool_dispatcher.as_str();
@@ -314,6 +340,7 @@ impl Agent {
             )))
             .prompt_builder(SystemPromptBuilder::with_defaults())
             .config(config.agent.clone())
+            .provider_name(provider_name.to_string())
             .model_name(model_name)
             .temperature(config.default_temperature)
             .workspace_dir(config.workspace_dir.clone())
@@ -374,27 +401,24 @@ impl Agent {
         let start = Instant::now();
 
         let result = if let Some(tool) = self.tools.iter().find(|t| t.name() == call.name) {
-            match tool.execute(call.arguments.clone()).await {
+            let tool_span = TraceContext::child_of_current();
+            let outcome = tool_span.scope(tool.execute(call.arguments.clone())).await;
+            tool_span.sync_scope(|| {
+                self.observer.record_event(&ObserverEvent::ToolCall {
+                    tool: call.name.clone(),
+                    duration: start.elapsed(),
+                    success: outcome.as_ref().is_ok_and(|r| r.success),
+                });
+            });
+            match outcome {
                 Ok(r) => {
-                    self.observer.record_event(&ObserverEvent::ToolCall {
-                        tool: call.name.clone(),
-                        duration: start.elapsed(),
-                        success: r.success,
-                    });
                     if r.success {
                         r.output
                     } else {
                         format!("Error: {}", r.error.unwrap_or(r.output))
                     }
                 }
-                Err(e) => {
-                    self.observer.record_event(&ObserverEvent::ToolCall {
-                        tool: call.name.clone(),
-                        duration: start.elapsed(),
-                        success: false,
-                    });
-                    format!("Error executing {}: {e}", call.name)
-                }
+                Err(e) => format!("Error executing {}: {e}", call.name),
             }
         } else {
             format!("Unknown tool: {}", call.name)
@@ -479,6 +503,8 @@ impl Agent {
                         } else {
                             None
                         },
+                        response_format: None,
+                        reasoning: None,
                     },
                     &effective_model,
                     self.temperature,
@@ -489,6 +515,14 @@ impl Agent {
                 Err(err) => return Err(err),
             };
 
+            if let Some(reasoning) = response.reasoning_text() {
+                self.observer.record_event(&ObserverEvent::Reasoning {
+                    provider: self.provider_name.clone(),
+                    model: effective_model.clone(),
+                    text: reasoning,
+                });
+            }
+
             let (text, calls) = self.tool_dispatcher.parse_response(&response);
             if calls.is_empty() {
                 let final_text = if text.is_empty() {
@@ -518,6 +552,7 @@ impl Agent {
             self.history.push(ConversationMessage::AssistantToolCalls {
                 text: response.text.clone(),
                 tool_calls: response.tool_calls.clone(),
+                reasoning: response.reasoning.clone(),
             });
 
             let results = self.execute_tools(&calls).await;
@@ -650,6 +685,8 @@ mod tests {
                 return Ok(crate::providers::ChatResponse {
                     text: Some("done".into()),
                     tool_calls: vec![],
+                    reasoning: Vec::new(),
+                    usage: None,
                 });
             }
             Ok(guard.remove(0))
@@ -687,6 +724,8 @@ mod tests {
             responses: Mutex::new(vec![crate::providers::ChatResponse {
                 text: Some("hello".into()),
                 tool_calls: vec![],
+                reasoning: Vec::new(),
+                usage: None,
             }]),
         });
 
@@ -725,10 +764,14 @@ mod tests {
                         name: "echo".into(),
                         arguments: "{}".into(),
                     }],
+                    reasoning: Vec::new(),
+                    usage: None,
                 },
                 crate::providers::ChatResponse {
                     text: Some("done".into()),
                     tool_calls: vec![],
+                    reasoning: Vec::new(),
+                    usage: None,
                 },
             ]),
         });
@@ -1,13 +1,16 @@
 use crate::approval::{ApprovalManager, ApprovalRequest, ApprovalResponse};
 use crate::config::Config;
+use crate::cost::TokenUsage;
 use crate::memory::{self, Memory, MemoryCategory};
 use crate::multimodal;
-use crate::observability::{self, Observer, ObserverEvent};
+use crate::observability::traits::ObserverMetric;
+use crate::observability::{self, Observer, ObserverEvent, TraceContext};
 use crate::providers::{
     self, ChatMessage, ChatRequest, Provider, ProviderCapabilityError, ToolCall,
 };
 use crate::runtime;
 use crate::security::SecurityPolicy;
+use crate::tools::selection::{self, ToolSelector};
 use crate::tools::{self, Tool};
 use crate::util::truncate_with_ellipsis;
 use anyhow::Result;
@@ -219,7 +222,7 @@ async fn build_context(mem: &dyn Memory, user_msg: &str, min_relevance_score: f6
     let mut context = String::new();
 
     // Pull relevant memories for this message
-    if let Ok(entries) = mem.recall(user_msg, 5, None).await {
+    if let Ok(entries) = memory::access::recall_current(mem, user_msg, 5).await {
         let relevant: Vec<_> = entries
             .iter()
             .filter(|e| match e.score {
@@ -893,7 +896,11 @@ fn parse_structured_tool_calls(tool_calls: &[ToolCall]) -> Vec<ParsedToolCall> {
 /// Build assistant history entry in JSON format for native tool-call APIs.
 /// `convert_messages` in the OpenRouter provider parses this JSON to reconstruct
 /// the proper `NativeMessage` with structured `tool_calls`.
-fn build_native_assistant_history(text: &str, tool_calls: &[ToolCall]) -> String {
+fn build_native_assistant_history(
+    text: &str,
+    tool_calls: &[ToolCall],
+    reasoning: &[crate::providers::ReasoningBlock],
+) -> String {
     let calls_json: Vec<serde_json::Value> = tool_calls
         .iter()
         .map(|tc| {
@@ -911,11 +918,16 @@ fn build_native_assistant_history(text: &str, tool_calls: &[ToolCall]) -> String
         serde_json::Value::String(text.trim().to_string())
     };
 
-    serde_json::json!({
+    let mut history = serde_json::json!({
         "content": content,
         "tool_calls": calls_json,
-    })
-    .to_string()
+    });
+    // Kept so providers that require it (Anthropic) can replay signed
+    // thinking ahead of the tool calls; others ignore the field.
+    if !reasoning.is_empty() {
+        history["reasoning"] = serde_json::to_value(reasoning).unwrap_or_default();
+    }
+    history.to_string()
 }
 
 fn build_assistant_history_with_tool_calls(text: &str, tool_calls: &[ToolCall]) -> String {
@@ -968,6 +980,7 @@ pub(crate) async fn agent_turn(
     provider: &dyn Provider,
     history: &mut Vec<ChatMessage>,
     tools_registry: &[Box<dyn Tool>],
+    tool_selector: Option<&ToolSelector>,
     observer: &dyn Observer,
     provider_name: &str,
     model: &str,
@@ -980,6 +993,7 @@ pub(crate) async fn agent_turn(
         provider,
         history,
         tools_registry,
+        tool_selector,
         observer,
         provider_name,
         model,
@@ -1011,7 +1025,12 @@ async fn execute_one_tool(
     });
     let start = Instant::now();
 
-    let tool_future = tool.execute(call_arguments);
+    // Only the flight recorder needs the arguments after the call.
+    let recorded_arguments = observer.wants_content().then(|| call_arguments.clone());
+
+    // Run the tool in its own span so delegated sub-agents nest under it.
+    let tool_span = TraceContext::child_of_current();
+    let tool_future = tool_span.scope(tool.execute(call_arguments));
     let tool_result = if let Some(token) = cancellation_token {
         tokio::select! {
             () = token.cancelled() => return Err(ToolLoopCancelled.into()),
@@ -1021,27 +1040,38 @@ async fn execute_one_tool(
         tool_future.await
     };
 
-    match tool_result {
-        Ok(r) => {
-            observer.record_event(&ObserverEvent::ToolCall {
+    let success = tool_result.as_ref().is_ok_and(|r| r.success);
+    tool_span.sync_scope(|| {
+        if let Some(arguments) = recorded_arguments {
+            let output = match &tool_result {
+                Ok(r) if r.success => r.output.clone(),
+                Ok(r) => r.error.clone().unwrap_or_else(|| r.output.clone()),
+                Err(e) => e.to_string(),
+            };
+            observer.record_event(&ObserverEvent::ToolExchange {
                 tool: call_name.to_string(),
+                arguments,
+                output,
+                success,
                 duration: start.elapsed(),
-                success: r.success,
             });
+        }
+        observer.record_event(&ObserverEvent::ToolCall {
+            tool: call_name.to_string(),
+            duration: start.elapsed(),
+            success,
+        });
+    });
+
+    match tool_result {
+        Ok(r) => {
             if r.success {
                 Ok(scrub_credentials(&r.output))
             } else {
                 Ok(format!("Error: {}", r.error.unwrap_or_else(|| r.output)))
             }
         }
-        Err(e) => {
-            observer.record_event(&ObserverEvent::ToolCall {
-                tool: call_name.to_string(),
-                duration: start.elapsed(),
-                success: false,
-            });
-            Ok(format!("Error executing {call_name}: {e}"))
-        }
+        Err(e) => Ok(format!("Error executing {call_name}: {e}")),
     }
 }
 
@@ -1153,6 +1183,7 @@ pub(crate) async fn run_tool_call_loop(
     provider: &dyn Provider,
     history: &mut Vec<ChatMessage>,
     tools_registry: &[Box<dyn Tool>],
+    tool_selector: Option<&ToolSelector>,
     observer: &dyn Observer,
     provider_name: &str,
     model: &str,
@@ -1208,20 +1239,32 @@ pub(crate) async fn run_tool_call_loop(
 
         // Unified path via Provider::chat so provider-specific native tool logic
         // (OpenAI/Anthropic/OpenRouter/compatible adapters) is honored.
+        // With a selector, advertise only the tools relevant to this turn.
+        let selected_specs = match tool_selector {
+            Some(selector) if use_native_tools => Some(
+                selector
+                    .select_specs(tools_registry, selection::latest_user_message(history))
+                    .await,
+            ),
+            _ => None,
+        };
         let request_tools = if use_native_tools {
-            Some(tool_specs.as_slice())
+            Some(selected_specs.as_deref().unwrap_or(&tool_specs))
         } else {
             None
         };
 
-        let chat_future = provider.chat(
+        let llm_span = TraceContext::child_of_current();
+        let chat_future = llm_span.scope(provider.chat(
             ChatRequest {
                 messages: &prepared_messages.messages,
                 tools: request_tools,
+                response_format: None,
+                reasoning: None,
             },
             model,
             temperature,
-        );
+        ));
 
         let chat_result = if let Some(token) = cancellation_token.as_ref() {
             tokio::select! {
@@ -1235,13 +1278,51 @@ pub(crate) async fn run_tool_call_loop(
         let (response_text, parsed_text, tool_calls, assistant_history_content, native_tool_calls) =
             match chat_result {
                 Ok(resp) => {
-                    observer.record_event(&ObserverEvent::LlmResponse {
-                        provider: provider_name.to_string(),
-                        model: model.to_string(),
-                        duration: llm_started_at.elapsed(),
-                        success: true,
-                        error_message: None,
+                    let usage = resp.usage;
+                    llm_span.sync_scope(|| {
+                        if observer.wants_content() {
+                            observer.record_event(&ObserverEvent::LlmExchange {
+                                provider: provider_name.to_string(),
+                                model: model.to_string(),
+                                temperature,
+                                messages: prepared_messages.messages.clone(),
+                                response: Some(resp.text_or_empty().to_string()),
+                                tool_calls: resp.tool_calls.clone(),
+                                error_message: None,
+                                duration: llm_started_at.elapsed(),
+                            });
+                        }
+                        observer.record_event(&ObserverEvent::LlmResponse {
+                            provider: provider_name.to_string(),
+                            model: model.to_string(),
+                            duration: llm_started_at.elapsed(),
+                            success: true,
+                            error_message: None,
+                            input_tokens: usage.map(|u| u.input_tokens),
+                            output_tokens: usage.map(|u| u.output_tokens),
+                            cost_usd: usage.and_then(|u| {
+                                TokenUsage::from_default_pricing(
+                                    model,
+                                    u.input_tokens,
+                                    u.output_tokens,
+                                )
+                                .map(|priced| priced.cost())
+                            }),
+                        });
                     });
+                    if let Some(usage) = usage {
+                        observer.record_metric(&ObserverMetric::TokensUsed(
+                            usage.input_tokens.saturating_add(usage.output_tokens),
+                        ));
+                    }
+
+                    if let Some(text) = resp.reasoning_text() {
+                        observer.record_event(&ObserverEvent::Reasoning {
+                            provider: provider_name.to_string(),
+                            model: model.to_string(),
+                            text,
+                        });
+                    }
 
                     let response_text = resp.text_or_empty().to_string();
                     // First try native structured tool calls (OpenAI-format).
@@ -1264,7 +1345,11 @@ pub(crate) async fn run_tool_call_loop(
                     let assistant_history_content = if resp.tool_calls.is_empty() {
                         response_text.clone()
                     } else {
-                        build_native_assistant_history(&response_text, &resp.tool_calls)
+                        build_native_assistant_history(
+                            &response_text,
+                            &resp.tool_calls,
+                            &resp.reasoning,
+                        )
                     };
 
                     let native_calls = resp.tool_calls;
@@ -1277,12 +1362,33 @@ pub(crate) async fn run_tool_call_loop(
                     )
                 }
                 Err(e) => {
-                    observer.record_event(&ObserverEvent::LlmResponse {
-                        provider: provider_name.to_string(),
-                        model: model.to_string(),
-                        duration: llm_started_at.elapsed(),
-                        success: false,
-                        error_message: Some(crate::providers::sanitize_api_error(&e.to_string())),
+                    llm_span.sync_scope(|| {
+                        if observer.wants_content() {
+                            observer.record_event(&ObserverEvent::LlmExchange {
+                                provider: provider_name.to_string(),
+                                model: model.to_string(),
+                                temperature,
+                                messages: prepared_messages.messages.clone(),
+                                response: None,
+                                tool_calls: Vec::new(),
+                                error_message: Some(crate::providers::sanitize_api_error(
+                                    &e.to_string(),
+                                )),
+                                duration: llm_started_at.elapsed(),
+                            });
+                        }
+                        observer.record_event(&ObserverEvent::LlmResponse {
+                            provider: provider_name.to_string(),
+                            model: model.to_string(),
+                            duration: llm_started_at.elapsed(),
+                            success: false,
+                            error_message: Some(crate::providers::sanitize_api_error(
+                                &e.to_string(),
+                            )),
+                            input_tokens: None,
+                            output_tokens: None,
+                            cost_usd: None,
+                        });
                     });
                     return Err(e);
                 }
@@ -1386,9 +1492,53 @@ pub(crate) async fn run_tool_call_loop(
     anyhow::bail!("Agent exceeded maximum tool iterations ({max_iterations})")
 }
 
+/// Run `turn` as one traced unit of work under `span`, closing it with a
+/// `TurnEnd` event so LLM and tool spans recorded inside nest under it.
+pub(crate) async fn run_traced_turn<T>(
+    observer: &dyn Observer,
+    source: &str,
+    span: TraceContext,
+    turn: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    let started_at = Instant::now();
+    let result = span.scope(turn).await;
+    span.sync_scope(|| {
+        observer.record_event(&ObserverEvent::TurnEnd {
+            source: source.to_string(),
+            duration: started_at.elapsed(),
+            success: result.is_ok(),
+        });
+    });
+    result
+}
+
+/// Record how a turn's prompt was assembled for content-consuming observers
+/// (the flight recorder). Call inside the turn's trace scope.
+pub(crate) fn record_prompt_assembled(
+    observer: &dyn Observer,
+    source: &str,
+    system_prompt: &str,
+    memory_context: &str,
+    user_message: &str,
+) {
+    if observer.wants_content() {
+        observer.record_event(&ObserverEvent::PromptAssembled {
+            source: source.to_string(),
+            system_prompt: system_prompt.to_string(),
+            memory_context: memory_context.to_string(),
+            user_message: user_message.to_string(),
+        });
+    }
+}
+
 /// Build the tool instruction block for the system prompt so the LLM knows
 /// how to invoke tools.
-pub(crate) fn build_tool_instructions(tools_registry: &[Box<dyn Tool>]) -> String {
+/// With a selector, only pinned tools are listed and the rest are left to
+/// `tool_search`.
+pub(crate) fn build_tool_instructions(
+    tools_registry: &[Box<dyn Tool>],
+    tool_selector: Option<&ToolSelector>,
+) -> String {
     let mut instructions = String::new();
     instructions.push_str("\n## Tool Use Protocol\n\n");
     instructions.push_str("To use a tool, wrap a JSON object in <tool_call></tool_call> tags:\n\n");
@@ -1402,8 +1552,16 @@ pub(crate) fn build_tool_instructions(tools_registry: &[Box<dyn Tool>]) -> Strin
     instructions
         .push_str("Continue reasoning with the results until you can give a final answer.\n\n");
     instructions.push_str("### Available Tools\n\n");
+    if tool_selector.is_some() {
+        instructions.push_str(
+            "Only core tools are listed. Call `tool_search` with a short description of the task to find more; the tools it returns can be called directly.\n\n",
+        );
+    }
 
-    for tool in tools_registry {
+    for tool in tools_registry
+        .iter()
+        .filter(|tool| tool_selector.is_none_or(|selector| selector.is_pinned(tool.name())))
+    {
         let _ = writeln!(
             instructions,
             "**{}**: {}\nParameters: `{}`\n",
@@ -1433,7 +1591,14 @@ pub async fn run(
 ) -> Result<String> {
     // ── Wire up agnostic subsystems ──────────────────────────────
     let base_observer = observability::create_observer(&config.observability);
-    let observer: Arc<dyn Observer> = Arc::from(base_observer);
+    let observer: Arc<dyn Observer> = if config.runtime.show_reasoning {
+        Arc::new(observability::MultiObserver::new(vec![
+            base_observer,
+            Box::new(observability::ReasoningEchoObserver),
+        ]))
+    } else {
+        Arc::from(base_observer)
+    };
     let runtime: Arc<dyn runtime::RuntimeAdapter> =
         Arc::from(runtime::create_runtime(&config.runtime)?);
     let security = Arc::new(SecurityPolicy::from_config(
@@ -1488,6 +1653,7 @@ pub async fn run(
         tracing::info!(count = peripheral_tools.len(), "Peripheral tools added");
         tools_registry.extend(peripheral_tools);
     }
+    let tool_selector = tools::selection::attach(&config, &mut tools_registry);
 
     // ── Resolve provider ─────────────────────────────────────────
     let provider_name = provider_override
@@ -1504,7 +1670,8 @@ pub async fn run(
         auth_profile_override: None,
         zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
         secrets_encrypt: config.secrets.encrypt,
-        reasoning_enabled: config.runtime.reasoning_enabled,
+        reasoning: config.runtime.effective_reasoning(),
+        cassette: config.cassette.clone(),
     };
 
     let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
@@ -1557,6 +1724,10 @@ pub async fn run(
             "file_write",
             "Write file contents. Use when: applying focused edits, scaffolding files, updating docs/code. Don't use when: side effects are unclear or file ownership is uncertain.",
         ),
+        (
+            "file_edit",
+            "Edit part of a file via search/replace, line range or unified diff. Use when: changing existing code/docs; pass the file_read hash. Don't use when: creating a new file (use file_write).",
+        ),
         (
             "memory_store",
             "Save to memory. Use when: preserving durable preferences, decisions, key context. Don't use when: information is transient/noisy/sensitive without need.",
@@ -1667,7 +1838,10 @@ pub async fn run(
 
     // Append structured tool-use instructions with schemas (only for non-native providers)
     if !native_tools {
-        system_prompt.push_str(&build_tool_instructions(&tools_registry));
+        system_prompt.push_str(&build_tool_instructions(
+            &tools_registry,
+            tool_selector.as_deref(),
+        ));
     }
 
     // ── Approval manager (supervised mode) ───────────────────────
@@ -1707,23 +1881,33 @@ pub async fn run(
             ChatMessage::user(&enriched),
         ];
 
-        let response = run_tool_call_loop(
-            provider.as_ref(),
-            &mut history,
-            &tools_registry,
-            observer.as_ref(),
-            provider_name,
-            model_name,
-            temperature,
-            false,
-            Some(&approval_manager),
-            "cli",
-            &config.multimodal,
-            config.agent.max_tool_iterations,
-            None,
-            None,
-        )
-        .await?;
+        let turn = async {
+            record_prompt_assembled(observer.as_ref(), "cli", &system_prompt, &context, &msg);
+            run_tool_call_loop(
+                provider.as_ref(),
+                &mut history,
+                &tools_registry,
+                tool_selector.as_deref(),
+                observer.as_ref(),
+                provider_name,
+                model_name,
+                temperature,
+                false,
+                Some(&approval_manager),
+                "cli",
+                &config.multimodal,
+                config.agent.max_tool_iterations,
+                None,
+                None,
+            )
+            .await
+        };
+        // Callers such as cron open the turn span themselves.
+        let response = if TraceContext::current().is_some() {
+            turn.await?
+        } else {
+            run_traced_turn(observer.as_ref(), "cli", TraceContext::root(), turn).await?
+        };
         final_output = response.clone();
         println!("{response}");
         observer.record_event(&ObserverEvent::TurnComplete);
@@ -1826,30 +2010,42 @@ pub async fn run(
 
             history.push(ChatMessage::user(&enriched));
 
-            let response = match run_tool_call_loop(
-                provider.as_ref(),
-                &mut history,
-                &tools_registry,
-                observer.as_ref(),
-                provider_name,
-                model_name,
-                temperature,
-                false,
-                Some(&approval_manager),
-                "cli",
-                &config.multimodal,
-                config.agent.max_tool_iterations,
-                None,
-                None,
-            )
-            .await
-            {
-                Ok(resp) => resp,
-                Err(e) => {
-                    eprintln!("\nError: {e}\n");
-                    continue;
-                }
-            };
+            let response =
+                match run_traced_turn(observer.as_ref(), "cli", TraceContext::root(), async {
+                    record_prompt_assembled(
+                        observer.as_ref(),
+                        "cli",
+                        &system_prompt,
+                        &context,
+                        &user_input,
+                    );
+                    run_tool_call_loop(
+                        provider.as_ref(),
+                        &mut history,
+                        &tools_registry,
+                        tool_selector.as_deref(),
+                        observer.as_ref(),
+                        provider_name,
+                        model_name,
+                        temperature,
+                        false,
+                        Some(&approval_manager),
+                        "cli",
+                        &config.multimodal,
+                        config.agent.max_tool_iterations,
+                        None,
+                        None,
+                    )
+                    .await
+                })
+                .await
+                {
+                    Ok(resp) => resp,
+                    Err(e) => {
+                        eprintln!("\nError: {e}\n");
+                        continue;
+                    }
+                };
             final_output = response.clone();
             if let Err(e) = crate::channels::Channel::send(
                 &cli,
@@ -1935,6 +2131,7 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
     let peripheral_tools: Vec<Box<dyn Tool>> =
         crate::peripherals::create_peripheral_tools(&config.peripherals).await?;
     tools_registry.extend(peripheral_tools);
+    let tool_selector = tools::selection::attach(&config, &mut tools_registry);
 
     let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
     let model_name = config
@@ -1945,7 +2142,8 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         auth_profile_override: None,
         zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
         secrets_encrypt: config.secrets.encrypt,
-        reasoning_enabled: config.runtime.reasoning_enabled,
+        reasoning: config.runtime.effective_reasoning(),
+        cassette: config.cassette.clone(),
     };
     let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
         provider_name,
@@ -1977,6 +2175,7 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         ("shell", "Execute terminal commands."),
         ("file_read", "Read file contents."),
         ("file_write", "Write file contents."),
+        ("file_edit", "Edit part of a file."),
         ("memory_store", "Save to memory."),
         ("memory_recall", "Search memory."),
         ("memory_forget", "Delete a memory entry."),
@@ -2033,7 +2232,10 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         config.skills.prompt_injection_mode,
     );
     if !native_tools {
-        system_prompt.push_str(&build_tool_instructions(&tools_registry));
+        system_prompt.push_str(&build_tool_instructions(
+            &tools_registry,
+            tool_selector.as_deref(),
+        ));
     }
 
     let mem_context = build_context(mem.as_ref(), message, config.memory.min_relevance_score).await;
@@ -2058,6 +2260,7 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         provider.as_ref(),
         &mut history,
         &tools_registry,
+        tool_selector.as_deref(),
         observer.as_ref(),
         provider_name,
         &model_name,
@@ -2164,6 +2367,8 @@ mod tests {
             Ok(ChatResponse {
                 text: Some("vision-ok".to_string()),
                 tool_calls: Vec::new(),
+                reasoning: Vec::new(),
+                usage: None,
             })
         }
     }
@@ -2179,6 +2384,8 @@ mod tests {
                 .map(|text| ChatResponse {
                     text: Some(text.to_string()),
                     tool_calls: Vec::new(),
+                    reasoning: Vec::new(),
+                    usage: None,
                 })
                 .collect();
             Self {
@@ -2300,6 +2507,7 @@ mod tests {
             &provider,
             &mut history,
             &tools_registry,
+            None,
             &observer,
             "mock-provider",
             "mock-model",
@@ -2344,6 +2552,7 @@ mod tests {
             &provider,
             &mut history,
             &tools_registry,
+            None,
             &observer,
             "mock-provider",
             "mock-model",
@@ -2382,6 +2591,7 @@ mod tests {
             &provider,
             &mut history,
             &tools_registry,
+            None,
             &observer,
             "mock-provider",
             "mock-model",
@@ -2502,6 +2712,7 @@ mod tests {
             &provider,
             &mut history,
             &tools_registry,
+            None,
             &observer,
             "mock-provider",
             "mock-model",
@@ -2912,7 +3123,7 @@ Done."#;
             std::path::Path::new("/tmp"),
         ));
         let tools = tools::default_tools(security);
-        let instructions = build_tool_instructions(&tools);
+        let instructions = build_tool_instructions(&tools, None);
 
         assert!(instructions.contains("## Tool Use Protocol"));
         assert!(instructions.contains("<tool_call>"));
@@ -2921,6 +3132,29 @@ Done."#;
         assert!(instructions.contains("file_write"));
     }
 
+    #[test]
+    fn build_tool_instructions_with_selector_lists_only_pinned_tools() {
+        use crate::security::SecurityPolicy;
+        let security = Arc::new(SecurityPolicy::from_config(
+            &crate::config::AutonomyConfig::default(),
+            std::path::Path::new("/tmp"),
+        ));
+        let tools = tools::default_tools(security);
+        let selector = ToolSelector::new(
+            crate::config::ToolSelectionConfig {
+                pinned: vec!["shell".into()],
+                ..Default::default()
+            },
+            Arc::new(crate::memory::embeddings::NoopEmbedding),
+            &tools,
+        );
+        let instructions = build_tool_instructions(&tools, Some(&selector));
+
+        assert!(instructions.contains("**shell**"));
+        assert!(!instructions.contains("**file_read**"));
+        assert!(instructions.contains("tool_search"));
+    }
+
     #[test]
     fn tools_to_openai_format_produces_valid_schema() {
         use crate::security::SecurityPolicy;
@@ -1,4 +1,4 @@
-use crate::memory::{self, Memory};
+use crate::memory::{self, access, Memory};
 use async_trait::async_trait;
 use std::fmt::Write;
 
@@ -38,7 +38,7 @@ impl MemoryLoader for DefaultMemoryLoader {
         memory: &dyn Memory,
         user_message: &str,
     ) -> anyhow::Result<String> {
-        let entries = memory.recall(user_message, self.limit, None).await?;
+        let entries = access::recall_current(memory, user_message, self.limit).await?;
         if entries.is_empty() {
             return Ok(String::new());
         }
@@ -53,7 +53,12 @@ impl MemoryLoader for DefaultMemoryLoader {
                     continue;
                 }
             }
-            let _ = writeln!(context, "- {}: {}", entry.key, entry.content);
+            let _ = writeln!(
+                context,
+                "- {}: {}",
+                memory::unscoped_key(&entry.key),
+                entry.content
+            );
         }
 
         // If all entries were below threshold, return empty
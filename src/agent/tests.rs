@@ -93,6 +93,8 @@ impl Provider for ScriptedProvider {
             return Ok(ChatResponse {
                 text: Some("done".into()),
                 tool_calls: vec![],
+                reasoning: Vec::new(),
+                usage: None,
             });
         }
         Ok(guard.remove(0))
@@ -328,6 +330,8 @@ fn tool_response(calls: Vec<ToolCall>) -> ChatResponse {
     ChatResponse {
         text: Some(String::new()),
         tool_calls: calls,
+        reasoning: Vec::new(),
+        usage: None,
     }
 }
 
@@ -336,6 +340,8 @@ fn text_response(text: &str) -> ChatResponse {
     ChatResponse {
         text: Some(text.into()),
         tool_calls: vec![],
+        reasoning: Vec::new(),
+        usage: None,
     }
 }
 
@@ -346,6 +352,8 @@ fn xml_tool_response(name: &str, args: &str) -> ChatResponse {
             "<tool_call>\n{{\"name\": \"{name}\", \"arguments\": {args}}}\n</tool_call>"
         )),
         tool_calls: vec![],
+        reasoning: Vec::new(),
+        usage: None,
     }
 }
 
@@ -734,6 +742,8 @@ async fn turn_handles_empty_text_response() {
     let provider = Box::new(ScriptedProvider::new(vec![ChatResponse {
         text: Some(String::new()),
         tool_calls: vec![],
+        reasoning: Vec::new(),
+        usage: None,
     }]));
 
     let mut agent = build_agent_with(provider, vec![], Box::new(NativeToolDispatcher));
@@ -747,6 +757,8 @@ async fn turn_handles_none_text_response() {
     let provider = Box::new(ScriptedProvider::new(vec![ChatResponse {
         text: None,
         tool_calls: vec![],
+        reasoning: Vec::new(),
+        usage: None,
     }]));
 
     let mut agent = build_agent_with(provider, vec![], Box::new(NativeToolDispatcher));
@@ -770,6 +782,8 @@ async fn turn_preserves_text_alongside_tool_calls() {
                 name: "echo".into(),
                 arguments: r#"{"message": "hi"}"#.into(),
             }],
+            reasoning: Vec::new(),
+            usage: None,
         },
         text_response("Here are the results"),
     ]));
@@ -1006,6 +1020,8 @@ async fn native_dispatcher_handles_stringified_arguments() {
             name: "echo".into(),
             arguments: r#"{"message": "hello"}"#.into(),
         }],
+        reasoning: Vec::new(),
+        usage: None,
     };
 
     let (_, calls) = dispatcher.parse_response(&response);
@@ -1031,6 +1047,8 @@ fn xml_dispatcher_handles_nested_json() {
                 .into(),
         ),
         tool_calls: vec![],
+        reasoning: Vec::new(),
+        usage: None,
     };
 
     let dispatcher = XmlToolDispatcher;
@@ -1048,6 +1066,8 @@ fn xml_dispatcher_handles_empty_tool_call_tag() {
     let response = ChatResponse {
         text: Some("<tool_call>\n</tool_call>\nSome text".into()),
         tool_calls: vec![],
+        reasoning: Vec::new(),
+        usage: None,
     };
 
     let dispatcher = XmlToolDispatcher;
@@ -1061,6 +1081,8 @@ fn xml_dispatcher_handles_unclosed_tool_call() {
     let response = ChatResponse {
         text: Some("Before\n<tool_call>\n{\"name\": \"shell\"}".into()),
         tool_calls: vec![],
+        reasoning: Vec::new(),
+        usage: None,
     };
 
     let dispatcher = XmlToolDispatcher;
@@ -1086,6 +1108,7 @@ fn conversation_message_serialization_roundtrip() {
                 name: "shell".into(),
                 arguments: "{}".into(),
             }],
+            reasoning: Vec::new(),
         },
         ConversationMessage::ToolResults(vec![ToolResultMessage {
             tool_call_id: "tc1".into(),
@@ -1108,10 +1131,12 @@ fn conversation_message_serialization_roundtrip() {
                 ConversationMessage::AssistantToolCalls {
                     text: a_text,
                     tool_calls: a_calls,
+                    ..
                 },
                 ConversationMessage::AssistantToolCalls {
                     text: b_text,
                     tool_calls: b_calls,
+                    ..
                 },
             ) => {
                 assert_eq!(a_text, b_text);
@@ -1208,6 +1233,7 @@ fn xml_dispatcher_converts_history_to_provider_messages() {
                 name: "shell".into(),
                 arguments: "{}".into(),
             }],
+            reasoning: Vec::new(),
         },
         ConversationMessage::ToolResults(vec![ToolResultMessage {
             tool_call_id: "tc1".into(),
@@ -1310,3 +1336,99 @@ async fn run_single_delegates_to_turn() {
         "Expected non-empty response from run_single"
     );
 }
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 26. Cassette record → replay
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn recorded_tool_loop_replays_offline() {
+    use crate::providers::cassette::{RecordingProvider, ReplayProvider};
+
+    let dir = tempfile::tempdir().unwrap();
+    let cassette = dir.path().join("tool_loop.jsonl");
+    let script = || {
+        ScriptedProvider::new(vec![
+            tool_response(vec![ToolCall {
+                id: "tc1".into(),
+                name: "echo".into(),
+                arguments: r#"{"message": "from cassette"}"#.into(),
+            }]),
+            text_response("echo said: from cassette"),
+        ])
+    };
+
+    let recorder = Box::new(RecordingProvider::new(Box::new(script()), &cassette));
+    let mut agent = build_agent_with(
+        recorder,
+        vec![Box::new(EchoTool)],
+        Box::new(NativeToolDispatcher),
+    );
+    let recorded = agent.turn("run echo").await.unwrap();
+
+    let replay = Box::new(ReplayProvider::open(&cassette, true).unwrap());
+    let mut agent = build_agent_with(
+        replay,
+        vec![Box::new(EchoTool)],
+        Box::new(NativeToolDispatcher),
+    );
+    assert_eq!(agent.turn("run echo").await.unwrap(), recorded);
+    assert!(agent
+        .history()
+        .iter()
+        .any(|message| matches!(message, ConversationMessage::ToolResults(_))));
+
+    // Strict replay refuses a conversation that was never recorded.
+    let replay = Box::new(ReplayProvider::open(&cassette, true).unwrap());
+    let mut agent = build_agent_with(replay, vec![], Box::new(NativeToolDispatcher));
+    assert!(agent.turn("something else").await.is_err());
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 27. Simulated peripheral board
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn turn_drives_simulated_board_over_device_protocol() {
+    use crate::config::{PeripheralBoardConfig, SimulatedBoardConfig};
+    use crate::peripherals::simulated::SimulatedPeripheral;
+    use crate::peripherals::Peripheral;
+
+    let config = PeripheralBoardConfig {
+        board: "simulated".into(),
+        transport: "in-process".into(),
+        simulation: Some(SimulatedBoardConfig {
+            sensors: std::collections::BTreeMap::from([("temperature".to_string(), 30.0)]),
+            ..SimulatedBoardConfig::default()
+        }),
+        ..PeripheralBoardConfig::default()
+    };
+    let peripheral = SimulatedPeripheral::connect_from_config(&config)
+        .await
+        .unwrap();
+
+    let provider = Box::new(ScriptedProvider::new(vec![
+        tool_response(vec![ToolCall {
+            id: "tc1".into(),
+            name: "sensor_read".into(),
+            arguments: r#"{"sensor": "temperature"}"#.into(),
+        }]),
+        tool_response(vec![ToolCall {
+            id: "tc2".into(),
+            name: "gpio_write".into(),
+            arguments: r#"{"pin": 13, "value": 1}"#.into(),
+        }]),
+        text_response("Too warm, fan on"),
+    ]));
+    let mut agent = build_agent_with(provider, peripheral.tools(), Box::new(NativeToolDispatcher));
+
+    assert_eq!(
+        agent.turn("check the room").await.unwrap(),
+        "Too warm, fan on"
+    );
+    assert_eq!(peripheral.board().gpio(13), Some(1));
+    assert_eq!(
+        peripheral.board().received_commands(),
+        ["sensor_read", "gpio_write"]
+    );
+}
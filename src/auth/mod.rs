@@ -39,6 +39,10 @@ impl AuthService {
         }
     }
 
+    pub fn profiles_path(&self) -> &Path {
+        self.store.path()
+    }
+
     pub async fn load_profiles(&self) -> Result<AuthProfilesData> {
         self.store.load().await
     }
@@ -33,11 +33,14 @@ pub mod signal;
 pub mod slack;
 pub mod telegram;
 pub mod traits;
+pub mod websocket;
 pub mod whatsapp;
 #[cfg(feature = "whatsapp-web")]
 pub mod whatsapp_storage;
 #[cfg(feature = "whatsapp-web")]
 pub mod whatsapp_web;
+pub mod xmpp;
+pub mod zulip;
 
 pub use cli::CliChannel;
 pub use dingtalk::DingTalkChannel;
@@ -57,19 +60,22 @@ pub use signal::SignalChannel;
 pub use slack::SlackChannel;
 pub use telegram::TelegramChannel;
 pub use traits::{Channel, SendMessage};
+pub use websocket::WebSocketChannel;
 pub use whatsapp::WhatsAppChannel;
 #[cfg(feature = "whatsapp-web")]
 pub use whatsapp_web::WhatsAppWebChannel;
+pub use xmpp::XmppChannel;
+pub use zulip::ZulipChannel;
 
-use crate::agent::loop_::{build_tool_instructions, run_tool_call_loop};
+use crate::agent::loop_::{build_tool_instructions, record_prompt_assembled, run_tool_call_loop};
 use crate::config::Config;
 use crate::identity;
 use crate::memory::{self, Memory};
-use crate::observability::{self, Observer};
+use crate::observability::{self, Observer, ObserverEvent, TraceContext};
 use crate::providers::{self, ChatMessage, Provider};
 use crate::runtime;
 use crate::security::SecurityPolicy;
-use crate::tools::{self, Tool};
+use crate::tools::{self, Tool, ToolSelector};
 use crate::util::truncate_with_ellipsis;
 use anyhow::{Context, Result};
 use serde::Deserialize;
@@ -178,8 +184,11 @@ struct RuntimeConfigState {
     last_applied_stamp: Option<ConfigFileStamp>,
 }
 
-fn runtime_config_store() -> &'static Mutex<HashMap<PathBuf, RuntimeConfigState>> {
-    static STORE: OnceLock<Mutex<HashMap<PathBuf, RuntimeConfigState>>> = OnceLock::new();
+/// Config file path plus the `[[profiles]]` entry (if any) a runtime serves.
+type RuntimeConfigKey = (PathBuf, Option<String>);
+
+fn runtime_config_store() -> &'static Mutex<HashMap<RuntimeConfigKey, RuntimeConfigState>> {
+    static STORE: OnceLock<Mutex<HashMap<RuntimeConfigKey, RuntimeConfigState>>> = OnceLock::new();
     STORE.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
@@ -195,11 +204,15 @@ struct ChannelRuntimeContext {
     default_provider: Arc<String>,
     memory: Arc<dyn Memory>,
     tools_registry: Arc<Vec<Box<dyn Tool>>>,
+    tool_selector: Option<Arc<ToolSelector>>,
     observer: Arc<dyn Observer>,
     system_prompt: Arc<String>,
     model: Arc<String>,
     temperature: f64,
     auto_save_memory: bool,
+    memory_scopes: Arc<crate::config::MemoryScopesConfig>,
+    /// `[[profiles]]` entry this runtime serves; `None` for the top-level agent
+    profile: Option<String>,
     max_tool_iterations: usize,
     min_relevance_score: f64,
     conversation_histories: ConversationHistoryMap,
@@ -252,6 +265,20 @@ fn conversation_memory_key(msg: &traits::ChannelMessage) -> String {
     format!("{}_{}_{}", msg.channel, msg.sender, msg.id)
 }
 
+/// Memory scopes a message's conversation may read and write.
+fn conversation_memory_access(
+    ctx: &ChannelRuntimeContext,
+    msg: &traits::ChannelMessage,
+) -> memory::MemoryAccess {
+    let thread = msg.thread_ts.as_deref().unwrap_or(&msg.reply_target);
+    memory::MemoryAccess::for_message(
+        ctx.memory_scopes.rule_for(&msg.channel),
+        &msg.channel,
+        &msg.sender,
+        thread,
+    )
+}
+
 fn conversation_history_key(msg: &traits::ChannelMessage) -> String {
     format!("{}_{}", msg.channel, msg.sender)
 }
@@ -411,12 +438,16 @@ fn runtime_config_path(ctx: &ChannelRuntimeContext) -> Option<PathBuf> {
         .map(|dir| dir.join("config.toml"))
 }
 
+fn runtime_config_key(ctx: &ChannelRuntimeContext) -> Option<RuntimeConfigKey> {
+    runtime_config_path(ctx).map(|path| (path, ctx.profile.clone()))
+}
+
 fn runtime_defaults_snapshot(ctx: &ChannelRuntimeContext) -> ChannelRuntimeDefaults {
-    if let Some(config_path) = runtime_config_path(ctx) {
+    if let Some(key) = runtime_config_key(ctx) {
         let store = runtime_config_store()
             .lock()
             .unwrap_or_else(|e| e.into_inner());
-        if let Some(state) = store.get(&config_path) {
+        if let Some(state) = store.get(&key) {
             return state.defaults.clone();
         }
     }
@@ -457,7 +488,10 @@ fn decrypt_optional_secret_for_runtime_reload(
     Ok(())
 }
 
-async fn load_runtime_defaults_from_config_file(path: &Path) -> Result<ChannelRuntimeDefaults> {
+async fn load_runtime_defaults_from_config_file(
+    path: &Path,
+    profile: Option<&str>,
+) -> Result<ChannelRuntimeDefaults> {
     let contents = tokio::fs::read_to_string(path)
         .await
         .with_context(|| format!("Failed to read {}", path.display()))?;
@@ -466,20 +500,36 @@ async fn load_runtime_defaults_from_config_file(path: &Path) -> Result<ChannelRu
     parsed.config_path = path.to_path_buf();
 
     if let Some(zeroclaw_dir) = path.parent() {
-        let store = crate::security::SecretStore::new(zeroclaw_dir, parsed.secrets.encrypt);
+        let store = crate::security::SecretStore::from_config(zeroclaw_dir, &parsed.secrets);
         decrypt_optional_secret_for_runtime_reload(&store, &mut parsed.api_key, "config.api_key")?;
+        for entry in &mut parsed.profiles {
+            decrypt_optional_secret_for_runtime_reload(
+                &store,
+                &mut entry.api_key,
+                "config.profiles.*.api_key",
+            )?;
+        }
     }
 
     parsed.apply_env_overrides();
+    if let Some(name) = profile {
+        let entry = parsed
+            .profiles
+            .iter()
+            .find(|entry| entry.name == name)
+            .with_context(|| format!("Profile '{name}' is no longer in {}", path.display()))?;
+        parsed = parsed.for_profile(entry);
+    }
     Ok(runtime_defaults_from_config(&parsed))
 }
 
 async fn maybe_apply_runtime_config_update(ctx: &ChannelRuntimeContext) -> Result<()> {
-    let Some(config_path) = runtime_config_path(ctx) else {
+    let Some(key) = runtime_config_key(ctx) else {
         return Ok(());
     };
+    let config_path = &key.0;
 
-    let Some(stamp) = config_file_stamp(&config_path).await else {
+    let Some(stamp) = config_file_stamp(config_path).await else {
         return Ok(());
     };
 
@@ -487,14 +537,15 @@ async fn maybe_apply_runtime_config_update(ctx: &ChannelRuntimeContext) -> Resul
         let store = runtime_config_store()
             .lock()
             .unwrap_or_else(|e| e.into_inner());
-        if let Some(state) = store.get(&config_path) {
+        if let Some(state) = store.get(&key) {
             if state.last_applied_stamp == Some(stamp) {
                 return Ok(());
             }
         }
     }
 
-    let next_defaults = load_runtime_defaults_from_config_file(&config_path).await?;
+    let next_defaults =
+        load_runtime_defaults_from_config_file(config_path, ctx.profile.as_deref()).await?;
     let next_default_provider = providers::create_resilient_provider_with_options(
         &next_defaults.default_provider,
         next_defaults.api_key.as_deref(),
@@ -525,7 +576,7 @@ async fn maybe_apply_runtime_config_update(ctx: &ChannelRuntimeContext) -> Resul
             .lock()
             .unwrap_or_else(|e| e.into_inner());
         store.insert(
-            config_path.clone(),
+            key.clone(),
             RuntimeConfigState {
                 defaults: next_defaults.clone(),
                 last_applied_stamp: Some(stamp),
@@ -886,7 +937,7 @@ async fn build_memory_context(
 ) -> String {
     let mut context = String::new();
 
-    if let Ok(entries) = mem.recall(user_msg, 5, None).await {
+    if let Ok(entries) = memory::access::recall_current(mem, user_msg, 5).await {
         let mut included = 0usize;
         let mut used_chars = 0usize;
 
@@ -908,7 +959,7 @@ async fn build_memory_context(
                 entry.content.clone()
             };
 
-            let line = format!("- {}: {}\n", entry.key, content);
+            let line = format!("- {}: {}\n", memory::unscoped_key(&entry.key), content);
             let line_chars = line.chars().count();
             if used_chars + line_chars > MEMORY_CONTEXT_MAX_CHARS {
                 break;
@@ -1192,12 +1243,14 @@ fn strip_isolated_tool_json_artifacts(message: &str, known_tool_names: &HashSet<
 fn spawn_supervised_listener(
     ch: Arc<dyn Channel>,
     tx: tokio::sync::mpsc::Sender<traits::ChannelMessage>,
+    component: String,
     initial_backoff_secs: u64,
     max_backoff_secs: u64,
 ) -> tokio::task::JoinHandle<()> {
     spawn_supervised_listener_with_health_interval(
         ch,
         tx,
+        component,
         initial_backoff_secs,
         max_backoff_secs,
         Duration::from_secs(CHANNEL_HEALTH_HEARTBEAT_SECS),
@@ -1207,6 +1260,7 @@ fn spawn_supervised_listener(
 fn spawn_supervised_listener_with_health_interval(
     ch: Arc<dyn Channel>,
     tx: tokio::sync::mpsc::Sender<traits::ChannelMessage>,
+    component: String,
     initial_backoff_secs: u64,
     max_backoff_secs: u64,
     health_interval: Duration,
@@ -1218,7 +1272,6 @@ fn spawn_supervised_listener_with_health_interval(
     };
 
     tokio::spawn(async move {
-        let component = format!("channel:{}", ch.name());
         let mut backoff = initial_backoff_secs.max(1);
         let max_backoff = max_backoff_secs.max(backoff);
 
@@ -1356,15 +1409,16 @@ async fn process_channel_message(
             return;
         }
     };
+    let memory_access = conversation_memory_access(ctx.as_ref(), &msg);
     if ctx.auto_save_memory && msg.content.chars().count() >= AUTOSAVE_MIN_MESSAGE_CHARS {
         let autosave_key = conversation_memory_key(&msg);
         let _ = ctx
             .memory
-            .store(
+            .store_scoped(
                 &autosave_key,
                 &msg.content,
                 crate::memory::MemoryCategory::Conversation,
-                None,
+                &memory_access.write,
             )
             .await;
     }
@@ -1394,9 +1448,16 @@ async fn process_channel_message(
 
     // Only enrich with memory context when there is no prior conversation
     // history. Follow-up turns already include context from previous messages.
+    let mut memory_context = String::new();
     if !had_prior_history {
-        let memory_context =
-            build_memory_context(ctx.memory.as_ref(), &msg.content, ctx.min_relevance_score).await;
+        memory_context = memory_access
+            .clone()
+            .scope(build_memory_context(
+                ctx.memory.as_ref(),
+                &msg.content,
+                ctx.min_relevance_score,
+            ))
+            .await;
         if let Some(last_turn) = prior_turns.last_mut() {
             if last_turn.role == "user" && !memory_context.is_empty() {
                 last_turn.content = format!("{memory_context}{}", msg.content);
@@ -1405,7 +1466,7 @@ async fn process_channel_message(
     }
 
     let system_prompt = build_channel_system_prompt(ctx.system_prompt.as_str(), &msg.channel);
-    let mut history = vec![ChatMessage::system(system_prompt)];
+    let mut history = vec![ChatMessage::system(system_prompt.as_str())];
     history.extend(prior_turns);
     let use_streaming = target_channel
         .as_ref()
@@ -1483,28 +1544,52 @@ async fn process_channel_message(
 
     let timeout_budget_secs =
         channel_message_timeout_budget_secs(ctx.message_timeout_secs, ctx.max_tool_iterations);
+    // One trace per inbound message; LLM and tool spans nest under it.
+    let turn_span = TraceContext::root();
+    turn_span.sync_scope(|| {
+        record_prompt_assembled(
+            ctx.observer.as_ref(),
+            &msg.channel,
+            &system_prompt,
+            &memory_context,
+            &msg.content,
+        );
+    });
+    // Tools activated through `tool_search` stay with this conversation.
+    let turn = Box::pin(tools::selection::in_conversation(
+        conversation_history_key(&msg),
+        run_tool_call_loop(
+            active_provider.as_ref(),
+            &mut history,
+            ctx.tools_registry.as_ref(),
+            ctx.tool_selector.as_deref(),
+            ctx.observer.as_ref(),
+            route.provider.as_str(),
+            route.model.as_str(),
+            runtime_defaults.temperature,
+            true,
+            None,
+            msg.channel.as_str(),
+            &ctx.multimodal,
+            ctx.max_tool_iterations,
+            Some(cancellation_token.clone()),
+            delta_tx,
+        ),
+    ));
     let llm_result = tokio::select! {
         () = cancellation_token.cancelled() => LlmExecutionResult::Cancelled,
         result = tokio::time::timeout(
             Duration::from_secs(timeout_budget_secs),
-            run_tool_call_loop(
-                active_provider.as_ref(),
-                &mut history,
-                ctx.tools_registry.as_ref(),
-                ctx.observer.as_ref(),
-                route.provider.as_str(),
-                route.model.as_str(),
-                runtime_defaults.temperature,
-                true,
-                None,
-                msg.channel.as_str(),
-                &ctx.multimodal,
-                ctx.max_tool_iterations,
-                Some(cancellation_token.clone()),
-                delta_tx,
-            ),
+            turn_span.scope(memory_access.scope(turn)),
         ) => LlmExecutionResult::Completed(result),
     };
+    turn_span.sync_scope(|| {
+        ctx.observer.record_event(&ObserverEvent::TurnEnd {
+            source: msg.channel.clone(),
+            duration: started_at.elapsed(),
+            success: matches!(llm_result, LlmExecutionResult::Completed(Ok(Ok(_)))),
+        });
+    });
 
     if let Some(handle) = draft_updater {
         let _ = handle.await;
@@ -2216,6 +2301,9 @@ pub async fn handle_command(command: crate::ChannelCommands, config: &Config) ->
                 ),
                 ("DingTalk", config.channels_config.dingtalk.is_some()),
                 ("QQ", config.channels_config.qq.is_some()),
+                ("WebSocket", config.channels_config.websocket.is_some()),
+                ("XMPP", config.channels_config.xmpp.is_some()),
+                ("Zulip", config.channels_config.zulip.is_some()),
             ] {
                 println!("  {} {name}", if configured { "✅" } else { "❌" });
             }
@@ -2482,6 +2570,21 @@ pub async fn doctor_channels(config: Config) -> Result<()> {
         ));
     }
 
+    if let Some(ref ws) = config.channels_config.websocket {
+        channels.push((
+            "WebSocket",
+            Arc::new(WebSocketChannel::from_config(&config, ws)),
+        ));
+    }
+
+    if let Some(ref xm) = config.channels_config.xmpp {
+        channels.push(("XMPP", Arc::new(XmppChannel::from_config(xm))));
+    }
+
+    if let Some(ref zl) = config.channels_config.zulip {
+        channels.push(("Zulip", Arc::new(ZulipChannel::from_config(zl))));
+    }
+
     if channels.is_empty() {
         println!("No real-time channels configured. Run `zeroclaw onboard` first.");
         return Ok(());
@@ -2531,7 +2634,8 @@ pub async fn start_channels(config: Config) -> Result<()> {
         auth_profile_override: None,
         zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
         secrets_encrypt: config.secrets.encrypt,
-        reasoning_enabled: config.runtime.reasoning_enabled,
+        reasoning: config.runtime.effective_reasoning(),
+        cassette: config.cassette.clone(),
     };
     let provider: Arc<dyn Provider> = Arc::from(
         create_resilient_provider_nonblocking(
@@ -2556,7 +2660,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
             .lock()
             .unwrap_or_else(|e| e.into_inner());
         store.insert(
-            config.config_path.clone(),
+            (config.config_path.clone(), config.profile.clone()),
             RuntimeConfigState {
                 defaults: runtime_defaults_from_config(&config),
                 last_applied_stamp: initial_stamp,
@@ -2590,7 +2694,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
     };
     // Build system prompt from workspace identity files + skills
     let workspace = config.workspace_dir.clone();
-    let tools_registry = Arc::new(tools::all_tools_with_runtime(
+    let mut tools_registry = tools::all_tools_with_runtime(
         Arc::new(config.clone()),
         &security,
         runtime,
@@ -2603,7 +2707,9 @@ pub async fn start_channels(config: Config) -> Result<()> {
         &config.agents,
         config.api_key.as_deref(),
         &config,
-    ));
+    );
+    let tool_selector = tools::selection::attach(&config, &mut tools_registry);
+    let tools_registry = Arc::new(tools_registry);
 
     let skills = crate::skills::load_skills_with_config(&workspace, &config);
 
@@ -2621,6 +2727,10 @@ pub async fn start_channels(config: Config) -> Result<()> {
             "file_write",
             "Write file contents. Use when: applying focused edits, scaffolding files, updating docs/code. Don't use when: side effects are unclear or file ownership is uncertain.",
         ),
+        (
+            "file_edit",
+            "Edit part of a file via search/replace, line range or unified diff. Use when: changing existing code/docs; pass the file_read hash. Don't use when: creating a new file (use file_write).",
+        ),
         (
             "memory_store",
             "Save to memory. Use when: preserving durable preferences, decisions, key context. Don't use when: information is transient/noisy/sensitive without need.",
@@ -2679,7 +2789,10 @@ pub async fn start_channels(config: Config) -> Result<()> {
         config.skills.prompt_injection_mode,
     );
     if !native_tools {
-        system_prompt.push_str(&build_tool_instructions(tools_registry.as_ref()));
+        system_prompt.push_str(&build_tool_instructions(
+            tools_registry.as_ref(),
+            tool_selector.as_deref(),
+        ));
     }
 
     if !skills.is_empty() {
@@ -2878,6 +2991,18 @@ pub async fn start_channels(config: Config) -> Result<()> {
         )));
     }
 
+    if let Some(ref ws) = config.channels_config.websocket {
+        channels.push(Arc::new(WebSocketChannel::from_config(&config, ws)));
+    }
+
+    if let Some(ref xm) = config.channels_config.xmpp {
+        channels.push(Arc::new(XmppChannel::from_config(xm)));
+    }
+
+    if let Some(ref zl) = config.channels_config.zulip {
+        channels.push(Arc::new(ZulipChannel::from_config(zl)));
+    }
+
     if channels.is_empty() {
         println!("No channels configured. Run `zeroclaw onboard` to set up channels.");
         return Ok(());
@@ -2906,7 +3031,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
     println!("  Listening for messages... (Ctrl+C to stop)");
     println!();
 
-    crate::health::mark_component_ok("channels");
+    crate::health::mark_component_ok(&config.health_component("channels"));
 
     let initial_backoff_secs = config
         .reliability
@@ -2926,6 +3051,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
         handles.push(spawn_supervised_listener(
             ch.clone(),
             tx.clone(),
+            config.health_component(&format!("channel:{}", ch.name())),
             initial_backoff_secs,
             max_backoff_secs,
         ));
@@ -2958,11 +3084,14 @@ pub async fn start_channels(config: Config) -> Result<()> {
         default_provider: Arc::new(provider_name),
         memory: Arc::clone(&mem),
         tools_registry: Arc::clone(&tools_registry),
+        tool_selector,
         observer,
         system_prompt: Arc::new(system_prompt),
         model: Arc::new(model.clone()),
         temperature,
         auto_save_memory: config.memory.auto_save,
+        memory_scopes: Arc::new(config.memory.scopes.clone()),
+        profile: config.profile.clone(),
         max_tool_iterations: config.agent.max_tool_iterations,
         min_relevance_score: config.memory.min_relevance_score,
         conversation_histories: Arc::new(Mutex::new(HashMap::new())),
@@ -3133,11 +3262,14 @@ mod tests {
             default_provider: Arc::new("test-provider".to_string()),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![]),
+            tool_selector: None,
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("system".to_string()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_scopes: Arc::default(),
+            profile: None,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(histories)),
@@ -3580,11 +3712,14 @@ BTC is currently around $65,000 based on latest tool output."#
             default_provider: Arc::new("test-provider".to_string()),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
+            tool_selector: None,
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_scopes: Arc::default(),
+            profile: None,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
@@ -3637,11 +3772,14 @@ BTC is currently around $65,000 based on latest tool output."#
             default_provider: Arc::new("test-provider".to_string()),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
+            tool_selector: None,
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_scopes: Arc::default(),
+            profile: None,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
@@ -3694,11 +3832,14 @@ BTC is currently around $65,000 based on latest tool output."#
             default_provider: Arc::new("test-provider".to_string()),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
+            tool_selector: None,
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_scopes: Arc::default(),
+            profile: None,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
@@ -3760,11 +3901,14 @@ BTC is currently around $65,000 based on latest tool output."#
             default_provider: Arc::new("test-provider".to_string()),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![]),
+            tool_selector: None,
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
             model: Arc::new("default-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_scopes: Arc::default(),
+            profile: None,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
@@ -3847,11 +3991,14 @@ BTC is currently around $65,000 based on latest tool output."#
             default_provider: Arc::new("test-provider".to_string()),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![]),
+            tool_selector: None,
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
             model: Arc::new("default-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_scopes: Arc::default(),
+            profile: None,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
@@ -3916,11 +4063,14 @@ BTC is currently around $65,000 based on latest tool output."#
             default_provider: Arc::new("test-provider".to_string()),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![]),
+            tool_selector: None,
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
             model: Arc::new("default-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_scopes: Arc::default(),
+            profile: None,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
@@ -3976,7 +4126,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 .lock()
                 .unwrap_or_else(|e| e.into_inner());
             store.insert(
-                config_path.clone(),
+                (config_path.clone(), None),
                 RuntimeConfigState {
                     defaults: ChannelRuntimeDefaults {
                         default_provider: "test-provider".to_string(),
@@ -3997,11 +4147,14 @@ BTC is currently around $65,000 based on latest tool output."#
             default_provider: Arc::new("test-provider".to_string()),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![]),
+            tool_selector: None,
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
             model: Arc::new("startup-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_scopes: Arc::default(),
+            profile: None,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
@@ -4039,7 +4192,7 @@ BTC is currently around $65,000 based on latest tool output."#
             let mut store = runtime_config_store()
                 .lock()
                 .unwrap_or_else(|e| e.into_inner());
-            store.remove(&config_path);
+            store.remove(&(config_path.clone(), None));
         }
 
         assert_eq!(provider_impl.call_count.load(Ordering::SeqCst), 1);
@@ -4069,11 +4222,14 @@ BTC is currently around $65,000 based on latest tool output."#
             default_provider: Arc::new("test-provider".to_string()),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
+            tool_selector: None,
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_scopes: Arc::default(),
+            profile: None,
             max_tool_iterations: 12,
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
@@ -4127,11 +4283,14 @@ BTC is currently around $65,000 based on latest tool output."#
             default_provider: Arc::new("test-provider".to_string()),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
+            tool_selector: None,
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_scopes: Arc::default(),
+            profile: None,
             max_tool_iterations: 3,
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
@@ -4296,11 +4455,14 @@ BTC is currently around $65,000 based on latest tool output."#
             default_provider: Arc::new("test-provider".to_string()),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![]),
+            tool_selector: None,
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_scopes: Arc::default(),
+            profile: None,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
@@ -4374,11 +4536,14 @@ BTC is currently around $65,000 based on latest tool output."#
             default_provider: Arc::new("test-provider".to_string()),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![]),
+            tool_selector: None,
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_scopes: Arc::default(),
+            profile: None,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
@@ -4464,11 +4629,14 @@ BTC is currently around $65,000 based on latest tool output."#
             default_provider: Arc::new("test-provider".to_string()),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![]),
+            tool_selector: None,
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_scopes: Arc::default(),
+            profile: None,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
@@ -4536,11 +4704,14 @@ BTC is currently around $65,000 based on latest tool output."#
             default_provider: Arc::new("test-provider".to_string()),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![]),
+            tool_selector: None,
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_scopes: Arc::default(),
+            profile: None,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
@@ -4623,7 +4794,7 @@ BTC is currently around $65,000 based on latest tool output."#
             "build_system_prompt should not emit protocol block directly"
         );
 
-        prompt.push_str(&build_tool_instructions(&[]));
+        prompt.push_str(&build_tool_instructions(&[], None));
 
         assert_eq!(
             prompt.matches("## Tool Use Protocol").count(),
@@ -5022,6 +5193,31 @@ BTC is currently around $65,000 based on latest tool output."#
         assert!(context.contains("Age is 45"));
     }
 
+    #[tokio::test]
+    async fn build_memory_context_respects_sender_scope() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let rule = crate::config::MemoryScopeRule::default();
+        let alice = memory::MemoryAccess::for_message(&rule, "telegram", "alice", "group");
+        let bob = memory::MemoryAccess::for_message(&rule, "telegram", "bob", "group");
+        mem.store_scoped(
+            "age_fact",
+            "Alice's age is 45",
+            MemoryCategory::Conversation,
+            &alice.write,
+        )
+        .await
+        .unwrap();
+
+        let for_bob = bob.scope(build_memory_context(&mem, "age", 0.0)).await;
+        assert!(!for_bob.contains("45"), "{for_bob}");
+        let for_alice = alice.scope(build_memory_context(&mem, "age", 0.0)).await;
+        assert!(
+            for_alice.contains("- age_fact: Alice's age is 45"),
+            "{for_alice}"
+        );
+    }
+
     #[tokio::test]
     async fn process_channel_message_restores_per_sender_history_on_follow_ups() {
         let channel_impl = Arc::new(RecordingChannel::default());
@@ -5038,11 +5234,14 @@ BTC is currently around $65,000 based on latest tool output."#
             default_provider: Arc::new("test-provider".to_string()),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![]),
+            tool_selector: None,
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_scopes: Arc::default(),
+            profile: None,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
@@ -5121,11 +5320,14 @@ BTC is currently around $65,000 based on latest tool output."#
             default_provider: Arc::new("test-provider".to_string()),
             memory: Arc::new(RecallMemory),
             tools_registry: Arc::new(vec![]),
+            tool_selector: None,
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_scopes: Arc::default(),
+            profile: None,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
@@ -5204,11 +5406,14 @@ BTC is currently around $65,000 based on latest tool output."#
             default_provider: Arc::new("test-provider".to_string()),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![]),
+            tool_selector: None,
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_scopes: Arc::default(),
+            profile: None,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(histories)),
@@ -5583,7 +5788,13 @@ This is an example JSON object for profile settings."#;
         });
 
         let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(1);
-        let handle = spawn_supervised_listener(channel, tx, 1, 1);
+        let handle = spawn_supervised_listener(
+            channel,
+            tx,
+            "channel:test-supervised-fail".to_string(),
+            1,
+            1,
+        );
 
         tokio::time::sleep(Duration::from_millis(80)).await;
         drop(rx);
@@ -5615,6 +5826,7 @@ This is an example JSON object for profile settings."#;
         let handle = spawn_supervised_listener_with_health_interval(
             channel,
             tx,
+            component_name.clone(),
             1,
             1,
             Duration::from_millis(20),
@@ -0,0 +1,682 @@
+//! Local WebSocket ingress channel.
+//!
+//! Lets custom UIs talk to the agent over a single WebSocket per session,
+//! reusing the gateway's pairing tokens for auth. Frames are JSON objects
+//! tagged by `type`:
+//!
+//! Client → server:
+//! - `{"type":"message","content":"...","id":"optional client id"}`
+//! - `{"type":"ping"}`
+//!
+//! Server → client:
+//! - `{"type":"session","session_id":"..."}` — sent once after connect
+//! - `{"type":"typing","active":true}`
+//! - `{"type":"draft","message_id":"...","content":"..."}` — full text so far
+//! - `{"type":"draft_cancel","message_id":"..."}`
+//! - `{"type":"message","content":"...","message_id":"...","reply_to":"..."}`
+//! - `{"type":"pong"}` / `{"type":"error","error":"..."}`
+
+use crate::channels::traits::{Channel, ChannelMessage, SendMessage};
+use crate::config::WebSocketConfig;
+use crate::gateway::{
+    SlidingWindowRateLimiter, RATE_LIMIT_MAX_KEYS_DEFAULT, RATE_LIMIT_WINDOW_SECS,
+};
+use crate::security::pairing::{is_public_bind, PairingGuard};
+use async_trait::async_trait;
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        ConnectInfo, Query, State,
+    },
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use futures_util::{SinkExt, StreamExt};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::mpsc;
+
+/// Largest inbound frame accepted from a client.
+const MAX_FRAME_BYTES: usize = 64 * 1024;
+
+/// Outbound frames buffered per session before sends start failing.
+const SESSION_BUFFER: usize = 64;
+
+/// Upgrades with a bad token a peer may attempt per window before it is
+/// refused outright.
+const MAX_FAILED_UPGRADES_PER_WINDOW: u32 = 10;
+
+/// Frames a client may send.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientFrame {
+    Message {
+        content: String,
+        #[serde(default)]
+        id: Option<String>,
+    },
+    Ping,
+}
+
+/// Frames the server sends to a client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerFrame {
+    Session {
+        session_id: String,
+    },
+    Message {
+        content: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message_id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reply_to: Option<String>,
+    },
+    Draft {
+        message_id: String,
+        content: String,
+    },
+    DraftCancel {
+        message_id: String,
+    },
+    Typing {
+        active: bool,
+    },
+    Pong,
+    Error {
+        error: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct ConnectQuery {
+    token: Option<String>,
+}
+
+struct Shared {
+    require_pairing: bool,
+    pairing: RwLock<PairingGuard>,
+    /// Config file to re-read `gateway.paired_tokens` from when a client
+    /// presents a token paired after this channel started.
+    config_path: Option<PathBuf>,
+    /// Modification time of `config_path` when tokens were last read, so an
+    /// unknown token only triggers a re-parse after the file changes.
+    tokens_modified: Mutex<Option<SystemTime>>,
+    failed_upgrades: SlidingWindowRateLimiter,
+    allowed_origins: Vec<String>,
+    max_connections: usize,
+    sessions: Mutex<HashMap<String, mpsc::Sender<ServerFrame>>>,
+}
+
+/// WebSocket channel: one session per connection, replies routed back to
+/// the session that sent the message.
+pub struct WebSocketChannel {
+    host: String,
+    port: u16,
+    allow_public_bind: bool,
+    shared: Arc<Shared>,
+    draft_seq: AtomicU64,
+}
+
+impl WebSocketChannel {
+    pub fn new(
+        config: &WebSocketConfig,
+        require_pairing: bool,
+        paired_tokens: &[String],
+        config_path: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            host: config.host.clone(),
+            port: config.port,
+            allow_public_bind: false,
+            shared: Arc::new(Shared {
+                require_pairing,
+                pairing: RwLock::new(PairingGuard::new(require_pairing, paired_tokens)),
+                config_path,
+                tokens_modified: Mutex::new(None),
+                failed_upgrades: SlidingWindowRateLimiter::new(
+                    MAX_FAILED_UPGRADES_PER_WINDOW,
+                    Duration::from_secs(RATE_LIMIT_WINDOW_SECS),
+                    RATE_LIMIT_MAX_KEYS_DEFAULT,
+                ),
+                allowed_origins: config.allowed_origins.clone(),
+                max_connections: config.max_connections.max(1),
+                sessions: Mutex::new(HashMap::new()),
+            }),
+            draft_seq: AtomicU64::new(0),
+        }
+    }
+
+    /// Build from the full runtime config, sharing the gateway's pairing state.
+    pub fn from_config(config: &crate::config::Config, ws: &WebSocketConfig) -> Self {
+        let mut channel = Self::new(
+            ws,
+            config.gateway.require_pairing,
+            &config.gateway.paired_tokens,
+            Some(config.config_path.clone()),
+        );
+        channel.allow_public_bind =
+            config.gateway.allow_public_bind || config.tunnel.provider != "none";
+        channel
+    }
+
+    fn push(&self, session_id: &str, frame: ServerFrame) -> anyhow::Result<()> {
+        let sender = self
+            .shared
+            .sessions
+            .lock()
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("websocket session {session_id} is not connected"))?;
+        sender
+            .try_send(frame)
+            .map_err(|e| anyhow::anyhow!("websocket session {session_id}: {e}"))
+    }
+}
+
+impl Shared {
+    /// Check an upgrade from `peer`, refusing peers that have sent too many
+    /// bad tokens recently.
+    fn authorize(&self, peer: &str, token: &str) -> Result<(), StatusCode> {
+        if self.failed_upgrades.is_limited(peer) {
+            return Err(StatusCode::TOO_MANY_REQUESTS);
+        }
+        if self.is_authorized(token) {
+            return Ok(());
+        }
+        self.failed_upgrades.allow(peer);
+        Err(StatusCode::UNAUTHORIZED)
+    }
+
+    fn is_authorized(&self, token: &str) -> bool {
+        if !self.require_pairing {
+            return true;
+        }
+        if token.is_empty() {
+            return false;
+        }
+        if self.pairing.read().is_authenticated(token) {
+            return true;
+        }
+        // The token may have been paired through the gateway after startup.
+        let Some(path) = self.config_path.as_deref() else {
+            return false;
+        };
+        let Ok(modified) = std::fs::metadata(path).and_then(|meta| meta.modified()) else {
+            return false;
+        };
+        {
+            let mut last = self.tokens_modified.lock();
+            if *last == Some(modified) {
+                return false;
+            }
+            *last = Some(modified);
+        }
+        let Some(tokens) = read_paired_tokens(path) else {
+            return false;
+        };
+        let guard = PairingGuard::new(true, &tokens);
+        let ok = guard.is_authenticated(token);
+        *self.pairing.write() = guard;
+        ok
+    }
+
+    fn origin_allowed(&self, headers: &HeaderMap) -> bool {
+        if self.allowed_origins.is_empty() {
+            return true;
+        }
+        headers
+            .get("origin")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|origin| self.allowed_origins.iter().any(|o| o == origin))
+    }
+}
+
+fn read_paired_tokens(path: &std::path::Path) -> Option<Vec<String>> {
+    let raw = std::fs::read_to_string(path).ok()?;
+    let value: toml::Value = toml::from_str(&raw).ok()?;
+    let tokens = value.get("gateway")?.get("paired_tokens")?.as_array()?;
+    Some(
+        tokens
+            .iter()
+            .filter_map(|t| t.as_str().map(str::to_string))
+            .collect(),
+    )
+}
+
+#[derive(Clone)]
+struct WsState {
+    shared: Arc<Shared>,
+    tx: mpsc::Sender<ChannelMessage>,
+}
+
+async fn handle_upgrade(
+    State(state): State<WsState>,
+    ConnectInfo(peer_addr): ConnectInfo<SocketAddr>,
+    Query(query): Query<ConnectQuery>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> Response {
+    if !state.shared.origin_allowed(&headers) {
+        return (StatusCode::FORBIDDEN, "origin not allowed").into_response();
+    }
+    let bearer = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|auth| auth.strip_prefix("Bearer "));
+    let token = bearer.or(query.token.as_deref()).unwrap_or("").trim();
+    match state.shared.authorize(&peer_addr.ip().to_string(), token) {
+        Ok(()) => {}
+        Err(StatusCode::TOO_MANY_REQUESTS) => {
+            tracing::warn!(
+                "WebSocket upgrade rate limit exceeded for {}",
+                peer_addr.ip()
+            );
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many failed connection attempts. Please retry later.",
+            )
+                .into_response();
+        }
+        Err(status) => {
+            return (
+                status,
+                "Unauthorized — pair via the gateway, then connect with Authorization: Bearer <token> or ?token=<token>",
+            )
+                .into_response();
+        }
+    }
+    if state.shared.sessions.lock().len() >= state.shared.max_connections {
+        return (StatusCode::SERVICE_UNAVAILABLE, "too many connections").into_response();
+    }
+
+    ws.max_message_size(MAX_FRAME_BYTES)
+        .on_upgrade(move |socket| run_session(socket, state))
+}
+
+async fn run_session(socket: WebSocket, state: WsState) {
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let (out_tx, mut out_rx) = mpsc::channel::<ServerFrame>(SESSION_BUFFER);
+    state
+        .shared
+        .sessions
+        .lock()
+        .insert(session_id.clone(), out_tx.clone());
+    tracing::info!("WebSocket session {session_id} connected");
+
+    let (mut sink, mut stream) = socket.split();
+    let writer = tokio::spawn(async move {
+        while let Some(frame) = out_rx.recv().await {
+            let Ok(text) = serde_json::to_string(&frame) else {
+                continue;
+            };
+            if sink.send(Message::Text(text.into())).await.is_err() {
+                break;
+            }
+        }
+        let _ = sink.close().await;
+    });
+
+    let _ = out_tx
+        .send(ServerFrame::Session {
+            session_id: session_id.clone(),
+        })
+        .await;
+
+    while let Some(Ok(message)) = stream.next().await {
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            Message::Binary(_) => {
+                let _ = out_tx
+                    .send(ServerFrame::Error {
+                        error: "binary frames are not supported".into(),
+                    })
+                    .await;
+                continue;
+            }
+            Message::Ping(_) | Message::Pong(_) => continue,
+        };
+
+        match serde_json::from_str::<ClientFrame>(&text) {
+            Ok(ClientFrame::Ping) => {
+                let _ = out_tx.send(ServerFrame::Pong).await;
+            }
+            Ok(ClientFrame::Message { content, id }) => {
+                if content.trim().is_empty() {
+                    continue;
+                }
+                let msg = ChannelMessage {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    sender: session_id.clone(),
+                    reply_target: session_id.clone(),
+                    content,
+                    channel: "websocket".to_string(),
+                    timestamp: std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs(),
+                    thread_ts: id,
+                };
+                if state.tx.send(msg).await.is_err() {
+                    break;
+                }
+            }
+            Err(e) => {
+                let _ = out_tx
+                    .send(ServerFrame::Error {
+                        error: format!("invalid frame: {e}"),
+                    })
+                    .await;
+            }
+        }
+    }
+
+    state.shared.sessions.lock().remove(&session_id);
+    drop(out_tx);
+    let _ = writer.await;
+    tracing::info!("WebSocket session {session_id} disconnected");
+}
+
+#[async_trait]
+impl Channel for WebSocketChannel {
+    fn name(&self) -> &str {
+        "websocket"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        self.push(
+            &message.recipient,
+            ServerFrame::Message {
+                content: message.content.clone(),
+                message_id: None,
+                reply_to: message.thread_ts.clone(),
+            },
+        )
+    }
+
+    async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        if is_public_bind(&self.host) && !self.allow_public_bind {
+            anyhow::bail!(
+                "Refusing to bind the websocket channel to {} — set [gateway] allow_public_bind = true or configure a tunnel",
+                self.host
+            );
+        }
+        if self.shared.require_pairing && !self.shared.pairing.read().is_paired() {
+            tracing::warn!(
+                "WebSocket channel requires pairing but no tokens are paired yet; pair via `zeroclaw gateway` first"
+            );
+        }
+
+        let addr: SocketAddr = format!("{}:{}", self.host, self.port).parse()?;
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        tracing::info!("WebSocket channel listening on ws://{addr}/ws");
+
+        let app = Router::new()
+            .route("/ws", get(handle_upgrade))
+            .with_state(WsState {
+                shared: Arc::clone(&self.shared),
+                tx,
+            });
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn health_check(&self) -> bool {
+        format!("{}:{}", self.host, self.port)
+            .parse::<SocketAddr>()
+            .is_ok()
+    }
+
+    async fn start_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        self.push(recipient, ServerFrame::Typing { active: true })
+    }
+
+    async fn stop_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        self.push(recipient, ServerFrame::Typing { active: false })
+    }
+
+    fn supports_draft_updates(&self) -> bool {
+        true
+    }
+
+    async fn send_draft(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        let message_id = format!("draft-{}", self.draft_seq.fetch_add(1, Ordering::Relaxed));
+        self.push(
+            &message.recipient,
+            ServerFrame::Draft {
+                message_id: message_id.clone(),
+                content: message.content.clone(),
+            },
+        )?;
+        Ok(Some(message_id))
+    }
+
+    async fn update_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        self.push(
+            recipient,
+            ServerFrame::Draft {
+                message_id: message_id.to_string(),
+                content: text.to_string(),
+            },
+        )
+    }
+
+    async fn finalize_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        self.push(
+            recipient,
+            ServerFrame::Message {
+                content: text.to_string(),
+                message_id: Some(message_id.to_string()),
+                reply_to: None,
+            },
+        )
+    }
+
+    async fn cancel_draft(&self, recipient: &str, message_id: &str) -> anyhow::Result<()> {
+        self.push(
+            recipient,
+            ServerFrame::DraftCancel {
+                message_id: message_id.to_string(),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(require_pairing: bool, tokens: &[String]) -> WebSocketChannel {
+        WebSocketChannel::new(&WebSocketConfig::default(), require_pairing, tokens, None)
+    }
+
+    fn attach(channel: &WebSocketChannel, session: &str) -> mpsc::Receiver<ServerFrame> {
+        let (tx, rx) = mpsc::channel(8);
+        channel
+            .shared
+            .sessions
+            .lock()
+            .insert(session.to_string(), tx);
+        rx
+    }
+
+    #[test]
+    fn frames_round_trip_documented_protocol() {
+        let frame: ClientFrame =
+            serde_json::from_str(r#"{"type":"message","content":"hi","id":"c1"}"#).unwrap();
+        assert_eq!(
+            frame,
+            ClientFrame::Message {
+                content: "hi".into(),
+                id: Some("c1".into())
+            }
+        );
+        let ping: ClientFrame = serde_json::from_str(r#"{"type":"ping"}"#).unwrap();
+        assert_eq!(ping, ClientFrame::Ping);
+        assert!(serde_json::from_str::<ClientFrame>(r#"{"type":"bogus"}"#).is_err());
+
+        let draft = serde_json::to_value(ServerFrame::Draft {
+            message_id: "d1".into(),
+            content: "partial".into(),
+        })
+        .unwrap();
+        assert_eq!(
+            draft,
+            serde_json::json!({"type": "draft", "message_id": "d1", "content": "partial"})
+        );
+        let reply = serde_json::to_value(ServerFrame::Message {
+            content: "done".into(),
+            message_id: None,
+            reply_to: None,
+        })
+        .unwrap();
+        assert_eq!(
+            reply,
+            serde_json::json!({"type": "message", "content": "done"})
+        );
+    }
+
+    #[test]
+    fn pairing_tokens_gate_connections() {
+        let open = channel(false, &[]);
+        assert!(open.shared.is_authorized(""));
+
+        let token = "zc_test_token".to_string();
+        let locked = channel(true, std::slice::from_ref(&token));
+        assert!(locked.shared.is_authorized(&token));
+        assert!(!locked.shared.is_authorized("zc_other"));
+        assert!(!locked.shared.is_authorized(""));
+    }
+
+    #[test]
+    fn paired_tokens_are_reloaded_from_config_file() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        let mut channel = channel(true, &[]);
+        Arc::get_mut(&mut channel.shared).unwrap().config_path = Some(path.clone());
+        assert!(!channel.shared.is_authorized("zc_late"));
+
+        let hash = PairingGuard::new(true, &["zc_late".to_string()]).tokens();
+        std::fs::write(
+            &path,
+            format!("[gateway]\npaired_tokens = [\"{}\"]\n", hash[0]),
+        )
+        .unwrap();
+        assert!(channel.shared.is_authorized("zc_late"));
+    }
+
+    #[test]
+    fn repeated_bad_tokens_are_throttled_per_peer() {
+        let token = "zc_test_token".to_string();
+        let channel = channel(true, std::slice::from_ref(&token));
+        for _ in 0..MAX_FAILED_UPGRADES_PER_WINDOW {
+            assert_eq!(
+                channel.shared.authorize("10.0.0.1", "zc_guess"),
+                Err(StatusCode::UNAUTHORIZED)
+            );
+        }
+        assert_eq!(
+            channel.shared.authorize("10.0.0.1", "zc_guess"),
+            Err(StatusCode::TOO_MANY_REQUESTS)
+        );
+        // Even the right token is refused until the window passes...
+        assert_eq!(
+            channel.shared.authorize("10.0.0.1", &token),
+            Err(StatusCode::TOO_MANY_REQUESTS)
+        );
+        // ...but other peers are unaffected.
+        assert_eq!(channel.shared.authorize("10.0.0.2", &token), Ok(()));
+    }
+
+    #[test]
+    fn origin_allowlist_is_enforced_when_set() {
+        let mut config = WebSocketConfig::default();
+        config.allowed_origins = vec!["http://localhost:5173".into()];
+        let channel = WebSocketChannel::new(&config, false, &[], None);
+        let mut headers = HeaderMap::new();
+        assert!(!channel.shared.origin_allowed(&headers));
+        headers.insert("origin", "http://evil.example".parse().unwrap());
+        assert!(!channel.shared.origin_allowed(&headers));
+        headers.insert("origin", "http://localhost:5173".parse().unwrap());
+        assert!(channel.shared.origin_allowed(&headers));
+    }
+
+    #[tokio::test]
+    async fn drafts_and_replies_reach_the_session() {
+        let channel = channel(false, &[]);
+        let mut rx = attach(&channel, "s1");
+
+        channel.start_typing("s1").await.unwrap();
+        let draft_id = channel
+            .send_draft(&SendMessage::new("...", "s1"))
+            .await
+            .unwrap()
+            .unwrap();
+        channel
+            .update_draft("s1", &draft_id, "Hello")
+            .await
+            .unwrap();
+        channel
+            .finalize_draft("s1", &draft_id, "Hello there")
+            .await
+            .unwrap();
+        channel
+            .send(&SendMessage::new("bye", "s1").in_thread(Some("c1".into())))
+            .await
+            .unwrap();
+
+        assert_eq!(rx.recv().await, Some(ServerFrame::Typing { active: true }));
+        assert!(matches!(rx.recv().await, Some(ServerFrame::Draft { .. })));
+        assert_eq!(
+            rx.recv().await,
+            Some(ServerFrame::Draft {
+                message_id: draft_id.clone(),
+                content: "Hello".into()
+            })
+        );
+        assert_eq!(
+            rx.recv().await,
+            Some(ServerFrame::Message {
+                content: "Hello there".into(),
+                message_id: Some(draft_id),
+                reply_to: None
+            })
+        );
+        assert_eq!(
+            rx.recv().await,
+            Some(ServerFrame::Message {
+                content: "bye".into(),
+                message_id: None,
+                reply_to: Some("c1".into())
+            })
+        );
+
+        assert!(channel
+            .send(&SendMessage::new("lost", "gone"))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,937 @@
+use crate::channels::traits::{Channel, ChannelMessage, SendMessage};
+use crate::config::XmppConfig;
+use async_trait::async_trait;
+use base64::Engine;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::{mpsc, Mutex};
+use tokio_rustls::rustls;
+
+/// Read timeout while negotiating the stream. Once connected, whitespace
+/// keepalives are sent instead so idle streams are not torn down.
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Interval between whitespace keepalives on an established stream.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Upper bound on buffered, not-yet-complete stanza data.
+const MAX_STANZA_BYTES: usize = 1024 * 1024;
+
+/// Maximum element nesting accepted from the server.
+const MAX_DEPTH: usize = 32;
+
+/// Resource requested when binding the session.
+const RESOURCE: &str = "zeroclaw";
+
+const NS_CHATSTATES: &str = "http://jabber.org/protocol/chatstates";
+
+/// Monotonic counter for outbound stanza ids.
+static STANZA_SEQ: AtomicU64 = AtomicU64::new(0);
+
+trait XmppIo: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> XmppIo for T {}
+
+type XmppStream = Box<dyn XmppIo>;
+type WriteHalf = tokio::io::WriteHalf<XmppStream>;
+
+/// XMPP client channel.
+///
+/// Connects with STARTTLS and SASL PLAIN, answers direct chats and joins the
+/// configured multi-user chat rooms. Replies carry the inbound `<thread>` id,
+/// which is surfaced as `thread_ts`.
+pub struct XmppChannel {
+    jid: String,
+    password: String,
+    server: String,
+    port: u16,
+    rooms: Vec<String>,
+    nickname: String,
+    allowed_users: Vec<String>,
+    mention_only: bool,
+    starttls: bool,
+    writer: Arc<Mutex<Option<WriteHalf>>>,
+}
+
+impl XmppChannel {
+    pub fn from_config(config: &XmppConfig) -> Self {
+        let jid = bare_jid(config.jid.trim()).to_string();
+        let (local, domain) = jid.split_once('@').unwrap_or(("", jid.as_str()));
+        let nickname = config
+            .nickname
+            .clone()
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or_else(|| local.to_string());
+        let server = config
+            .server
+            .clone()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| domain.to_string());
+        Self {
+            server,
+            port: config.port,
+            password: config.password.clone(),
+            rooms: config
+                .rooms
+                .iter()
+                .map(|r| bare_jid(r).to_string())
+                .collect(),
+            nickname,
+            allowed_users: config.allowed_users.clone(),
+            mention_only: config.mention_only,
+            starttls: config.starttls,
+            jid,
+            writer: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn domain(&self) -> &str {
+        self.jid
+            .split_once('@')
+            .map_or(self.jid.as_str(), |(_, d)| d)
+    }
+
+    fn local_part(&self) -> &str {
+        self.jid.split_once('@').map_or("", |(l, _)| l)
+    }
+
+    fn is_room(&self, jid: &str) -> bool {
+        let bare = bare_jid(jid);
+        self.rooms.iter().any(|r| r.eq_ignore_ascii_case(bare))
+    }
+
+    /// Allowlist entries match a bare JID, a room occupant
+    /// (`room@conference.example.com/nick`) or a whole room.
+    fn is_sender_allowed(&self, candidates: &[&str]) -> bool {
+        self.allowed_users.iter().any(|entry| {
+            entry == "*"
+                || candidates
+                    .iter()
+                    .any(|candidate| entry.eq_ignore_ascii_case(candidate))
+        })
+    }
+
+    async fn connect(&self) -> anyhow::Result<(XmppStream, StanzaReader, String)> {
+        if !self.starttls && !is_loopback_host(&self.server) {
+            anyhow::bail!(
+                "XMPP starttls = false is only allowed for a loopback server, not {}",
+                self.server
+            );
+        }
+        let tcp = tokio::net::TcpStream::connect((self.server.as_str(), self.port)).await?;
+        let mut reader = StanzaReader::default();
+        let domain = self.domain().to_string();
+
+        let mut stream: XmppStream = if self.starttls {
+            let mut tcp = tcp;
+            let features = open_stream(&mut tcp, &mut reader, &domain).await?;
+            if features.child("starttls").is_none() {
+                anyhow::bail!("XMPP server {} does not offer STARTTLS", self.server);
+            }
+            write_raw(
+                &mut tcp,
+                "<starttls xmlns='urn:ietf:params:xml:ns:xmpp-tls'/>",
+            )
+            .await?;
+            let reply = reader.next_stanza(&mut tcp).await?;
+            if reply.local_name() != "proceed" {
+                anyhow::bail!("XMPP STARTTLS was refused by {}", self.server);
+            }
+            reader.clear();
+
+            let root_store: rustls::RootCertStore =
+                webpki_roots::TLS_SERVER_ROOTS.iter().cloned().collect();
+            let tls_config = rustls::ClientConfig::builder()
+                .with_root_certificates(root_store)
+                .with_no_client_auth();
+            let connector = tokio_rustls::TlsConnector::from(Arc::new(tls_config));
+            let name = rustls::pki_types::ServerName::try_from(domain.clone())?;
+            Box::new(connector.connect(name, tcp).await?)
+        } else {
+            Box::new(tcp)
+        };
+
+        // ── SASL PLAIN ──
+        let features = open_stream(&mut stream, &mut reader, &domain).await?;
+        let offers_plain = features.child("mechanisms").is_some_and(|m| {
+            m.children
+                .iter()
+                .any(|mech| mech.text.trim().eq_ignore_ascii_case("PLAIN"))
+        });
+        if !offers_plain {
+            anyhow::bail!("XMPP server does not offer SASL PLAIN");
+        }
+        let credentials = base64::engine::general_purpose::STANDARD.encode(format!(
+            "\0{}\0{}",
+            self.local_part(),
+            self.password
+        ));
+        write_raw(
+            &mut stream,
+            &format!(
+                "<auth xmlns='urn:ietf:params:xml:ns:xmpp-sasl' mechanism='PLAIN'>{credentials}</auth>"
+            ),
+        )
+        .await?;
+        let reply = reader.next_stanza(&mut stream).await?;
+        if reply.local_name() != "success" {
+            let reason = reply.children.first().map_or("unknown", |c| c.local_name());
+            anyhow::bail!("XMPP authentication failed ({reason})");
+        }
+        reader.clear();
+
+        // ── Resource binding ──
+        open_stream(&mut stream, &mut reader, &domain).await?;
+        write_raw(
+            &mut stream,
+            &format!(
+                "<iq type='set' id='bind_1'><bind xmlns='urn:ietf:params:xml:ns:xmpp-bind'>\
+                 <resource>{RESOURCE}</resource></bind></iq>"
+            ),
+        )
+        .await?;
+        let bound = loop {
+            let stanza = reader.next_stanza(&mut stream).await?;
+            if stanza.local_name() == "iq" && stanza.attr("id") == Some("bind_1") {
+                if stanza.attr("type") != Some("result") {
+                    anyhow::bail!("XMPP resource binding failed");
+                }
+                break stanza
+                    .child("bind")
+                    .and_then(|b| b.child("jid"))
+                    .map(|j| j.text.trim().to_string())
+                    .unwrap_or_else(|| format!("{}/{RESOURCE}", self.jid));
+            }
+        };
+
+        Ok((stream, reader, bound))
+    }
+
+    fn parse_message(&self, stanza: &XmlElement) -> Option<ChannelMessage> {
+        let kind = stanza.attr("type").unwrap_or("normal");
+        let from = stanza.attr("from")?;
+        let body = stanza.child("body")?.text.trim();
+        if body.is_empty() || matches!(kind, "error" | "headline") {
+            return None;
+        }
+
+        let (sender, reply_target, content) = if kind == "groupchat" {
+            let (room, nick) = from.split_once('/')?;
+            // Skip room history replays and our own echoes.
+            if nick.is_empty()
+                || nick.eq_ignore_ascii_case(&self.nickname)
+                || stanza.child("delay").is_some()
+            {
+                return None;
+            }
+            if !self.is_sender_allowed(&[from, room]) {
+                tracing::warn!("XMPP: ignoring message from unauthorized occupant {from}");
+                return None;
+            }
+            let content = if self.mention_only {
+                strip_mention(body, &self.nickname)?
+            } else {
+                body.to_string()
+            };
+            (from.to_string(), room.to_string(), content)
+        } else {
+            let bare = bare_jid(from);
+            if bare.eq_ignore_ascii_case(&self.jid) {
+                return None;
+            }
+            if !self.is_sender_allowed(&[bare]) {
+                tracing::warn!("XMPP: ignoring message from unauthorized user {bare}");
+                return None;
+            }
+            (bare.to_string(), bare.to_string(), body.to_string())
+        };
+
+        let id = stanza.attr("id").map_or_else(
+            || STANZA_SEQ.fetch_add(1, Ordering::Relaxed).to_string(),
+            str::to_string,
+        );
+        Some(ChannelMessage {
+            id: format!("xmpp_{id}"),
+            sender,
+            reply_target,
+            content,
+            channel: "xmpp".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            thread_ts: stanza
+                .child("thread")
+                .map(|t| t.text.trim().to_string())
+                .filter(|t| !t.is_empty()),
+        })
+    }
+
+    /// Build the reply (if any) to an inbound `<iq>`: pings get a result,
+    /// other requests get `service-unavailable` as RFC 6120 requires.
+    fn iq_response(stanza: &XmlElement) -> Option<String> {
+        let kind = stanza.attr("type")?;
+        if kind != "get" && kind != "set" {
+            return None;
+        }
+        let id = escape_xml(stanza.attr("id").unwrap_or(""));
+        let to = stanza
+            .attr("from")
+            .map(|from| format!(" to='{}'", escape_xml(from)))
+            .unwrap_or_default();
+        if kind == "get" && stanza.child("ping").is_some() {
+            return Some(format!("<iq type='result' id='{id}'{to}/>"));
+        }
+        Some(format!(
+            "<iq type='error' id='{id}'{to}><error type='cancel'>\
+             <service-unavailable xmlns='urn:ietf:params:xml:ns:xmpp-stanzas'/></error></iq>"
+        ))
+    }
+
+    fn message_type(&self, recipient: &str) -> &'static str {
+        if self.is_room(recipient) {
+            "groupchat"
+        } else {
+            "chat"
+        }
+    }
+
+    async fn write_shared(&self, data: &str) -> anyhow::Result<()> {
+        let mut guard = self.writer.lock().await;
+        let writer = guard
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("XMPP not connected"))?;
+        write_raw(writer, data).await
+    }
+
+    async fn send_chat_state(&self, recipient: &str, state: &str) -> anyhow::Result<()> {
+        self.write_shared(&format!(
+            "<message to='{}' type='{}'><{state} xmlns='{NS_CHATSTATES}'/></message>",
+            escape_xml(recipient),
+            self.message_type(recipient),
+        ))
+        .await
+    }
+}
+
+#[async_trait]
+impl Channel for XmppChannel {
+    fn name(&self) -> &str {
+        "xmpp"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let id = STANZA_SEQ.fetch_add(1, Ordering::Relaxed);
+        let thread = message
+            .thread_ts
+            .as_deref()
+            .map(|t| format!("<thread>{}</thread>", escape_xml(t)))
+            .unwrap_or_default();
+        self.write_shared(&format!(
+            "<message to='{}' type='{}' id='zc_{id}'><body>{}</body>{thread}</message>",
+            escape_xml(&message.recipient),
+            self.message_type(&message.recipient),
+            escape_xml(&message.content),
+        ))
+        .await
+    }
+
+    async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        tracing::info!(
+            "XMPP channel connecting to {}:{} as {}...",
+            self.server,
+            self.port,
+            self.jid
+        );
+        let (stream, mut reader, bound) = self.connect().await?;
+        let (mut rd, mut wr) = tokio::io::split(stream);
+
+        write_raw(&mut wr, "<presence/>").await?;
+        for room in &self.rooms {
+            write_raw(
+                &mut wr,
+                &format!(
+                    "<presence to='{}/{}'><x xmlns='http://jabber.org/protocol/muc'>\
+                     <history maxstanzas='0'/></x></presence>",
+                    escape_xml(room),
+                    escape_xml(&self.nickname)
+                ),
+            )
+            .await?;
+        }
+        *self.writer.lock().await = Some(wr);
+        tracing::info!("XMPP channel connected as {bound}");
+
+        let mut keepalive = tokio::time::interval(KEEPALIVE_INTERVAL);
+        keepalive.tick().await;
+        let result = loop {
+            tokio::select! {
+                event = reader.next_event(&mut rd, None) => {
+                    let stanza = match event {
+                        Ok(StreamEvent::Stanza(stanza)) => stanza,
+                        Ok(StreamEvent::Open) => continue,
+                        Ok(StreamEvent::Close) => {
+                            break Err(anyhow::anyhow!("XMPP server closed the stream"))
+                        }
+                        Err(e) => break Err(e),
+                    };
+                    match stanza.local_name() {
+                        "message" => {
+                            if let Some(msg) = self.parse_message(&stanza) {
+                                if tx.send(msg).await.is_err() {
+                                    break Ok(());
+                                }
+                            }
+                        }
+                        "iq" => {
+                            if let Some(reply) = Self::iq_response(&stanza) {
+                                if let Err(e) = self.write_shared(&reply).await {
+                                    break Err(e);
+                                }
+                            }
+                        }
+                        "error" => {
+                            break Err(anyhow::anyhow!("XMPP stream error: {}", stanza.text.trim()))
+                        }
+                        _ => {}
+                    }
+                }
+                _ = keepalive.tick() => {
+                    if let Err(e) = self.write_shared(" ").await {
+                        break Err(e);
+                    }
+                }
+            }
+        };
+
+        *self.writer.lock().await = None;
+        result
+    }
+
+    async fn health_check(&self) -> bool {
+        let connect = tokio::net::TcpStream::connect((self.server.as_str(), self.port));
+        matches!(
+            tokio::time::timeout(Duration::from_secs(5), connect).await,
+            Ok(Ok(_))
+        )
+    }
+
+    async fn start_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        self.send_chat_state(recipient, "composing").await
+    }
+
+    async fn stop_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        self.send_chat_state(recipient, "active").await
+    }
+}
+
+fn bare_jid(jid: &str) -> &str {
+    jid.split_once('/').map_or(jid, |(bare, _)| bare)
+}
+
+fn is_loopback_host(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// In rooms with `mention_only`, keep only messages naming the bot and drop a
+/// leading `nick:` / `nick,` / `@nick` address.
+fn strip_mention(body: &str, nickname: &str) -> Option<String> {
+    let lower = body.to_lowercase();
+    let nick = nickname.to_lowercase();
+    if nick.is_empty() || !lower.contains(&nick) {
+        return None;
+    }
+    let rest = body.strip_prefix('@').unwrap_or(body);
+    if rest.len() >= nickname.len()
+        && rest.is_char_boundary(nickname.len())
+        && rest[..nickname.len()].eq_ignore_ascii_case(nickname)
+    {
+        let tail = rest[nickname.len()..].trim_start_matches([':', ',']).trim();
+        if !tail.is_empty() {
+            return Some(tail.to_string());
+        }
+    }
+    Some(body.to_string())
+}
+
+async fn write_raw<W: AsyncWrite + Unpin + ?Sized>(
+    writer: &mut W,
+    data: &str,
+) -> anyhow::Result<()> {
+    writer.write_all(data.as_bytes()).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Open (or restart) the stream and return the server's `<stream:features>`.
+async fn open_stream<S: AsyncRead + AsyncWrite + Unpin + ?Sized>(
+    stream: &mut S,
+    reader: &mut StanzaReader,
+    domain: &str,
+) -> anyhow::Result<XmlElement> {
+    write_raw(
+        stream,
+        &format!(
+            "<?xml version='1.0'?><stream:stream to='{}' version='1.0' \
+             xmlns='jabber:client' xmlns:stream='http://etherx.jabber.org/streams'>",
+            escape_xml(domain)
+        ),
+    )
+    .await?;
+    loop {
+        let stanza = reader.next_stanza(stream).await?;
+        match stanza.local_name() {
+            "features" => return Ok(stanza),
+            "error" => anyhow::bail!("XMPP stream error: {}", stanza.text.trim()),
+            _ => {}
+        }
+    }
+}
+
+// ── Minimal XML stanza parsing ──────────────────────────────────
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct XmlElement {
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<XmlElement>,
+    text: String,
+}
+
+impl XmlElement {
+    fn local_name(&self) -> &str {
+        self.name.rsplit(':').next().unwrap_or(&self.name)
+    }
+
+    fn attr(&self, key: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn child(&self, local_name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|c| c.local_name() == local_name)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum StreamEvent {
+    Open,
+    Stanza(XmlElement),
+    Close,
+}
+
+/// Incremental reader that splits the XMPP stream into top-level stanzas.
+#[derive(Default)]
+struct StanzaReader {
+    buf: Vec<u8>,
+}
+
+impl StanzaReader {
+    fn clear(&mut self) {
+        self.buf.clear();
+    }
+
+    async fn next_stanza<R: AsyncRead + Unpin + ?Sized>(
+        &mut self,
+        reader: &mut R,
+    ) -> anyhow::Result<XmlElement> {
+        loop {
+            match self.next_event(reader, Some(READ_TIMEOUT)).await? {
+                StreamEvent::Stanza(stanza) => return Ok(stanza),
+                StreamEvent::Open => {}
+                StreamEvent::Close => anyhow::bail!("XMPP server closed the stream"),
+            }
+        }
+    }
+
+    async fn next_event<R: AsyncRead + Unpin + ?Sized>(
+        &mut self,
+        reader: &mut R,
+        timeout: Option<Duration>,
+    ) -> anyhow::Result<StreamEvent> {
+        loop {
+            let text = match std::str::from_utf8(&self.buf) {
+                Ok(text) => text,
+                Err(e) => std::str::from_utf8(&self.buf[..e.valid_up_to()]).unwrap_or_default(),
+            };
+            if let Some((event, consumed)) = parse_event(text)? {
+                self.buf.drain(..consumed);
+                return Ok(event);
+            }
+            if self.buf.len() > MAX_STANZA_BYTES {
+                anyhow::bail!("XMPP stanza exceeds {MAX_STANZA_BYTES} bytes");
+            }
+
+            let mut chunk = [0_u8; 4096];
+            let n = match timeout {
+                Some(limit) => tokio::time::timeout(limit, reader.read(&mut chunk))
+                    .await
+                    .map_err(|_| anyhow::anyhow!("XMPP read timed out"))??,
+                None => reader.read(&mut chunk).await?,
+            };
+            if n == 0 {
+                anyhow::bail!("XMPP server closed the connection");
+            }
+            self.buf.extend_from_slice(&chunk[..n]);
+        }
+    }
+}
+
+/// Parse the next stream-level event from `input`. Returns `None` when more
+/// data is needed, otherwise the event and the number of bytes it consumed.
+fn parse_event(input: &str) -> anyhow::Result<Option<(StreamEvent, usize)>> {
+    let start = input.len() - input.trim_start().len();
+    let rest = &input[start..];
+    if rest.is_empty() {
+        return Ok(None);
+    }
+    if rest.starts_with("<?") {
+        let Some(end) = rest.find("?>") else {
+            return Ok(None);
+        };
+        let skipped = start + end + 2;
+        return Ok(parse_event(&input[skipped..])?.map(|(event, n)| (event, skipped + n)));
+    }
+    if rest.starts_with("</") {
+        let Some(end) = rest.find('>') else {
+            return Ok(None);
+        };
+        return Ok(Some((StreamEvent::Close, start + end + 1)));
+    }
+    if rest.starts_with("<stream:stream") {
+        return Ok(parse_tag(rest)?.map(|(_, _, _, n)| (StreamEvent::Open, start + n)));
+    }
+    if !rest.starts_with('<') {
+        anyhow::bail!("unexpected text at XMPP stream level");
+    }
+    Ok(parse_element(rest, 0)?.map(|(el, n)| (StreamEvent::Stanza(el), start + n)))
+}
+
+type Tag = (String, Vec<(String, String)>, bool, usize);
+
+/// Parse a start tag at the beginning of `input` (which starts with `<`).
+fn parse_tag(input: &str) -> anyhow::Result<Option<Tag>> {
+    let bytes = input.as_bytes();
+    let mut i = 1;
+    while i < bytes.len() && !bytes[i].is_ascii_whitespace() && !matches!(bytes[i], b'/' | b'>') {
+        i += 1;
+    }
+    if i >= bytes.len() {
+        return Ok(None);
+    }
+    let name = input[1..i].to_string();
+    if name.is_empty() {
+        anyhow::bail!("empty XML tag name");
+    }
+
+    let mut attrs = Vec::new();
+    loop {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let Some(&b) = bytes.get(i) else {
+            return Ok(None);
+        };
+        match b {
+            b'>' => return Ok(Some((name, attrs, false, i + 1))),
+            b'/' => {
+                return match bytes.get(i + 1) {
+                    None => Ok(None),
+                    Some(b'>') => Ok(Some((name, attrs, true, i + 2))),
+                    Some(_) => anyhow::bail!("malformed XML tag <{name}>"),
+                }
+            }
+            _ => {}
+        }
+
+        let key_start = i;
+        while i < bytes.len()
+            && !matches!(bytes[i], b'=' | b'>' | b'/')
+            && !bytes[i].is_ascii_whitespace()
+        {
+            i += 1;
+        }
+        let key = input[key_start..i].to_string();
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        match bytes.get(i) {
+            None => return Ok(None),
+            Some(b'=') => i += 1,
+            Some(_) => anyhow::bail!("XML attribute '{key}' has no value"),
+        }
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let Some(&quote) = bytes.get(i) else {
+            return Ok(None);
+        };
+        if quote != b'\'' && quote != b'"' {
+            anyhow::bail!("XML attribute '{key}' is not quoted");
+        }
+        let Some(len) = input[i + 1..].find(quote as char) else {
+            return Ok(None);
+        };
+        attrs.push((key, unescape_xml(&input[i + 1..i + 1 + len])));
+        i += len + 2;
+    }
+}
+
+/// Parse one complete element at the beginning of `input`.
+fn parse_element(input: &str, depth: usize) -> anyhow::Result<Option<(XmlElement, usize)>> {
+    if depth > MAX_DEPTH {
+        anyhow::bail!("XMPP stanza nested too deeply");
+    }
+    let Some((name, attrs, self_closing, mut i)) = parse_tag(input)? else {
+        return Ok(None);
+    };
+    let mut element = XmlElement {
+        name,
+        attrs,
+        ..XmlElement::default()
+    };
+    if self_closing {
+        return Ok(Some((element, i)));
+    }
+
+    loop {
+        let rest = &input[i..];
+        let Some(lt) = rest.find('<') else {
+            return Ok(None);
+        };
+        element.text.push_str(&unescape_xml(&rest[..lt]));
+        i += lt;
+        let rest = &input[i..];
+
+        if rest.starts_with("</") {
+            let Some(gt) = rest.find('>') else {
+                return Ok(None);
+            };
+            if rest[2..gt].trim() != element.name {
+                anyhow::bail!("mismatched closing tag for <{}>", element.name);
+            }
+            return Ok(Some((element, i + gt + 1)));
+        } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let Some(end) = cdata.find("]]>") else {
+                return Ok(None);
+            };
+            element.text.push_str(&cdata[..end]);
+            i += "<![CDATA[".len() + end + 3;
+        } else if rest.starts_with("<!--") || rest.starts_with("<?") {
+            let terminator = if rest.starts_with("<!--") {
+                "-->"
+            } else {
+                "?>"
+            };
+            let Some(end) = rest.find(terminator) else {
+                return Ok(None);
+            };
+            i += end + terminator.len();
+        } else {
+            let Some((child, n)) = parse_element(rest, depth + 1)? else {
+                return Ok(None);
+            };
+            element.children.push(child);
+            i += n;
+        }
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn unescape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse::<u32>))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(allowed: &[&str], mention_only: bool) -> XmppChannel {
+        XmppChannel::from_config(&XmppConfig {
+            jid: "bot@example.com".into(),
+            password: "secret".into(),
+            server: None,
+            port: 5222,
+            rooms: vec!["team@conference.example.com".into()],
+            nickname: Some("zc".into()),
+            allowed_users: allowed.iter().map(|s| s.to_string()).collect(),
+            mention_only,
+            starttls: true,
+        })
+    }
+
+    fn stanza(xml: &str) -> XmlElement {
+        match parse_event(xml).unwrap().unwrap().0 {
+            StreamEvent::Stanza(el) => el,
+            other => panic!("expected stanza, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_event_waits_for_complete_stanzas() {
+        assert_eq!(parse_event("<message to='a'><bo").unwrap(), None);
+        assert_eq!(parse_event("  ").unwrap(), None);
+
+        let input = "<?xml version='1.0'?><stream:stream from='example.com' id='1'>";
+        let (event, n) = parse_event(input).unwrap().unwrap();
+        assert_eq!(event, StreamEvent::Open);
+        assert_eq!(n, input.len());
+
+        let (event, _) = parse_event(" </stream:stream>").unwrap().unwrap();
+        assert_eq!(event, StreamEvent::Close);
+
+        assert!(parse_event("<a><b></a>").is_err());
+    }
+
+    #[test]
+    fn parse_element_handles_attributes_entities_and_children() {
+        let el = stanza(
+            "<message from=\"a@b/c\" type='chat'><body>1 &lt; 2 &amp;&#x20;ok&apos;</body>\
+             <thread>t-1</thread><x:y xmlns:x='urn:x'/><![CDATA[<raw>]]></message>",
+        );
+        assert_eq!(el.attr("from"), Some("a@b/c"));
+        assert_eq!(el.child("body").unwrap().text, "1 < 2 & ok'");
+        assert_eq!(el.child("thread").unwrap().text, "t-1");
+        assert_eq!(el.child("y").unwrap().name, "x:y");
+        assert_eq!(el.text, "<raw>");
+        assert_eq!(escape_xml("<a & 'b'>"), "&lt;a &amp; &apos;b&apos;&gt;");
+    }
+
+    #[test]
+    fn parse_element_rejects_deep_nesting() {
+        let xml = format!("{}{}", "<a>".repeat(40), "</a>".repeat(40));
+        assert!(parse_event(&xml).is_err());
+    }
+
+    #[test]
+    fn direct_messages_respect_allowlist_and_thread() {
+        let ch = channel(&["alice@example.com"], true);
+        let msg = ch
+            .parse_message(&stanza(
+                "<message from='alice@example.com/phone' type='chat' id='m1'>\
+                 <body>hello</body><thread>th</thread></message>",
+            ))
+            .unwrap();
+        assert_eq!(msg.sender, "alice@example.com");
+        assert_eq!(msg.reply_target, "alice@example.com");
+        assert_eq!(msg.thread_ts.as_deref(), Some("th"));
+        assert_eq!(msg.id, "xmpp_m1");
+        assert_eq!(msg.channel, "xmpp");
+
+        assert!(ch
+            .parse_message(&stanza(
+                "<message from='mallory@example.com' type='chat'><body>hi</body></message>"
+            ))
+            .is_none());
+    }
+
+    #[test]
+    fn room_messages_apply_mention_only_and_skip_self_and_history() {
+        let ch = channel(&["team@conference.example.com"], true);
+        let room_msg = |from: &str, body: &str| {
+            stanza(&format!(
+                "<message from='{from}' type='groupchat'><body>{body}</body></message>"
+            ))
+        };
+
+        assert!(ch
+            .parse_message(&room_msg("team@conference.example.com/bob", "no ping"))
+            .is_none());
+        let msg = ch
+            .parse_message(&room_msg("team@conference.example.com/bob", "zc: status?"))
+            .unwrap();
+        assert_eq!(msg.content, "status?");
+        assert_eq!(msg.reply_target, "team@conference.example.com");
+        assert_eq!(msg.sender, "team@conference.example.com/bob");
+
+        assert!(ch
+            .parse_message(&room_msg("team@conference.example.com/zc", "zc: echo"))
+            .is_none());
+        assert!(ch
+            .parse_message(&stanza(
+                "<message from='team@conference.example.com/bob' type='groupchat'>\
+                 <body>zc: old</body><delay xmlns='urn:xmpp:delay'/></message>"
+            ))
+            .is_none());
+
+        assert_eq!(ch.message_type("team@conference.example.com"), "groupchat");
+        assert_eq!(ch.message_type("alice@example.com"), "chat");
+    }
+
+    #[test]
+    fn iq_requests_are_answered() {
+        let ping =
+            stanza("<iq type='get' id='p1' from='example.com'><ping xmlns='urn:xmpp:ping'/></iq>");
+        assert_eq!(
+            XmppChannel::iq_response(&ping).unwrap(),
+            "<iq type='result' id='p1' to='example.com'/>"
+        );
+        let unknown = stanza("<iq type='get' id='q1'><query xmlns='jabber:iq:version'/></iq>");
+        assert!(XmppChannel::iq_response(&unknown)
+            .unwrap()
+            .contains("service-unavailable"));
+        assert!(XmppChannel::iq_response(&stanza("<iq type='result' id='r'/>")).is_none());
+    }
+
+    #[test]
+    fn plaintext_requires_loopback_server() {
+        assert!(is_loopback_host("127.0.0.1"));
+        assert!(is_loopback_host("localhost"));
+        assert!(is_loopback_host("::1"));
+        assert!(!is_loopback_host("xmpp.example.com"));
+    }
+}
@@ -0,0 +1,449 @@
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use crate::config::ZulipConfig;
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Topic used when replying into a stream without an inbound topic.
+const DEFAULT_TOPIC: &str = "general chat";
+
+/// Zulip's maximum message length in bytes.
+const MAX_MESSAGE_BYTES: usize = 10_000;
+
+/// Seconds the server may hold an event long-poll open, plus slack.
+const EVENTS_TIMEOUT_SECS: u64 = 120;
+
+/// Zulip channel — long-polls the real-time events API and replies through
+/// the REST messages API. Stream topics map to `thread_ts`.
+pub struct ZulipChannel {
+    base_url: String,
+    bot_email: String,
+    api_key: String,
+    streams: Vec<String>,
+    allowed_users: Vec<String>,
+    mention_only: bool,
+}
+
+impl ZulipChannel {
+    pub fn new(
+        base_url: String,
+        bot_email: String,
+        api_key: String,
+        streams: Vec<String>,
+        allowed_users: Vec<String>,
+        mention_only: bool,
+    ) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            bot_email,
+            api_key,
+            streams,
+            allowed_users,
+            mention_only,
+        }
+    }
+
+    pub fn from_config(config: &ZulipConfig) -> Self {
+        Self::new(
+            config.url.clone(),
+            config.bot_email.clone(),
+            config.api_key.clone(),
+            config.streams.clone(),
+            config.allowed_users.clone(),
+            config.mention_only,
+        )
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        crate::config::build_runtime_proxy_client("channel.zulip")
+    }
+
+    /// Check if a sender email is in the allowlist.
+    /// Empty list means deny everyone. "*" means allow everyone.
+    fn is_user_allowed(&self, email: &str) -> bool {
+        self.allowed_users
+            .iter()
+            .any(|u| u == "*" || u.eq_ignore_ascii_case(email))
+    }
+
+    fn is_stream_watched(&self, stream: &str) -> bool {
+        self.streams.is_empty() || self.streams.iter().any(|s| s.eq_ignore_ascii_case(stream))
+    }
+
+    /// Decode a Zulip API response, surfacing `{"result":"error"}` bodies.
+    async fn decode(resp: reqwest::Response, what: &str) -> Result<Value> {
+        let status = resp.status();
+        let body: Value = resp.json().await.unwrap_or(Value::Null);
+        if !status.is_success() || body.get("result").and_then(Value::as_str) != Some("success") {
+            let msg = body.get("msg").and_then(Value::as_str).unwrap_or("");
+            let code = body.get("code").and_then(Value::as_str).unwrap_or("");
+            bail!("Zulip {what} failed ({status} {code}): {msg}");
+        }
+        Ok(body)
+    }
+
+    async fn bot_identity(&self) -> Result<(i64, String)> {
+        let resp = self
+            .http_client()
+            .get(format!("{}/api/v1/users/me", self.base_url))
+            .basic_auth(&self.bot_email, Some(&self.api_key))
+            .send()
+            .await?;
+        let me = Self::decode(resp, "users/me").await?;
+        let id = me.get("user_id").and_then(Value::as_i64).unwrap_or(-1);
+        let name = me
+            .get("full_name")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string();
+        Ok((id, name))
+    }
+
+    async fn register_queue(&self) -> Result<(String, i64)> {
+        let resp = self
+            .http_client()
+            .post(format!("{}/api/v1/register", self.base_url))
+            .basic_auth(&self.bot_email, Some(&self.api_key))
+            .form(&[("event_types", r#"["message"]"#)])
+            .send()
+            .await?;
+        let body = Self::decode(resp, "register").await?;
+        let queue_id = body
+            .get("queue_id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Zulip register returned no queue_id"))?
+            .to_string();
+        let last_event_id = body
+            .get("last_event_id")
+            .and_then(Value::as_i64)
+            .unwrap_or(-1);
+        Ok((queue_id, last_event_id))
+    }
+
+    fn parse_event(&self, event: &Value, bot_id: i64, bot_name: &str) -> Option<ChannelMessage> {
+        if event.get("type").and_then(Value::as_str) != Some("message") {
+            return None;
+        }
+        let message = event.get("message")?;
+        let sender_id = message.get("sender_id").and_then(Value::as_i64)?;
+        let sender_email = message.get("sender_email").and_then(Value::as_str)?;
+        let content = message.get("content").and_then(Value::as_str)?.trim();
+        if sender_id == bot_id || content.is_empty() {
+            return None;
+        }
+        if !self.is_user_allowed(sender_email) {
+            tracing::warn!("Zulip: ignoring message from unauthorized user: {sender_email}");
+            return None;
+        }
+
+        let (reply_target, thread_ts, content) =
+            match message.get("type").and_then(Value::as_str)? {
+                "stream" => {
+                    let stream = message.get("display_recipient").and_then(Value::as_str)?;
+                    if !self.is_stream_watched(stream) {
+                        return None;
+                    }
+                    let mentioned = event
+                        .get("flags")
+                        .and_then(Value::as_array)
+                        .is_some_and(|flags| flags.iter().any(|f| f == "mentioned"));
+                    if self.mention_only && !mentioned {
+                        return None;
+                    }
+                    let topic = message
+                        .get("subject")
+                        .and_then(Value::as_str)
+                        .filter(|t| !t.is_empty())
+                        .unwrap_or(DEFAULT_TOPIC);
+                    (
+                        format!("stream:{stream}"),
+                        Some(topic.to_string()),
+                        strip_bot_mention(content, bot_name),
+                    )
+                }
+                _ => {
+                    // Direct (1:1 or group) message: reply to everyone but the bot.
+                    let recipients: Vec<&str> = message
+                        .get("display_recipient")
+                        .and_then(Value::as_array)
+                        .map(|users| {
+                            users
+                                .iter()
+                                .filter(|u| u.get("id").and_then(Value::as_i64) != Some(bot_id))
+                                .filter_map(|u| u.get("email").and_then(Value::as_str))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    let to = if recipients.is_empty() {
+                        sender_email.to_string()
+                    } else {
+                        recipients.join(",")
+                    };
+                    (format!("private:{to}"), None, content.to_string())
+                }
+            };
+
+        if content.is_empty() {
+            return None;
+        }
+
+        let id = message.get("id").and_then(Value::as_i64).unwrap_or(0);
+        #[allow(clippy::cast_sign_loss)]
+        let timestamp = message
+            .get("timestamp")
+            .and_then(Value::as_i64)
+            .unwrap_or(0)
+            .max(0) as u64;
+        Some(ChannelMessage {
+            id: format!("zulip_{id}"),
+            sender: sender_email.to_string(),
+            reply_target,
+            content,
+            channel: "zulip".to_string(),
+            timestamp,
+            thread_ts,
+        })
+    }
+
+    async fn post_message(&self, form: &[(&str, &str)]) -> Result<()> {
+        let resp = self
+            .http_client()
+            .post(format!("{}/api/v1/messages", self.base_url))
+            .basic_auth(&self.bot_email, Some(&self.api_key))
+            .form(form)
+            .send()
+            .await?;
+        Self::decode(resp, "send message").await.map(|_| ())
+    }
+}
+
+#[async_trait]
+impl Channel for ZulipChannel {
+    fn name(&self) -> &str {
+        "zulip"
+    }
+
+    async fn send(&self, message: &SendMessage) -> Result<()> {
+        // Recipient is "stream:<name>" or "private:<email>[,<email>...]".
+        let (kind, target) = message
+            .recipient
+            .split_once(':')
+            .unwrap_or(("private", message.recipient.as_str()));
+
+        for chunk in split_message(&message.content, MAX_MESSAGE_BYTES) {
+            if kind == "stream" {
+                let topic = message.thread_ts.as_deref().unwrap_or(DEFAULT_TOPIC);
+                self.post_message(&[
+                    ("type", "stream"),
+                    ("to", target),
+                    ("topic", topic),
+                    ("content", chunk),
+                ])
+                .await?;
+            } else {
+                let to = serde_json::to_string(&target.split(',').collect::<Vec<_>>())?;
+                self.post_message(&[("type", "private"), ("to", &to), ("content", chunk)])
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> Result<()> {
+        let (bot_id, bot_name) = self.bot_identity().await?;
+        let (queue_id, mut last_event_id) = self.register_queue().await?;
+        tracing::info!("Zulip channel listening as {} ({bot_name})", self.bot_email);
+
+        let client = crate::config::build_runtime_proxy_client_with_timeouts(
+            "channel.zulip",
+            EVENTS_TIMEOUT_SECS,
+            10,
+        );
+        loop {
+            let resp = client
+                .get(format!("{}/api/v1/events", self.base_url))
+                .basic_auth(&self.bot_email, Some(&self.api_key))
+                .query(&[
+                    ("queue_id", queue_id.clone()),
+                    ("last_event_id", last_event_id.to_string()),
+                ])
+                .send()
+                .await?;
+            // An expired queue surfaces as BAD_EVENT_QUEUE_ID; returning the
+            // error lets the supervisor reconnect and register a fresh one.
+            let body = Self::decode(resp, "events").await?;
+
+            let events = body
+                .get("events")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default();
+            for event in &events {
+                if let Some(id) = event.get("id").and_then(Value::as_i64) {
+                    last_event_id = last_event_id.max(id);
+                }
+                if let Some(msg) = self.parse_event(event, bot_id, &bot_name) {
+                    if tx.send(msg).await.is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    async fn health_check(&self) -> bool {
+        self.bot_identity().await.is_ok()
+    }
+}
+
+/// Remove the `@**Bot Name**` mention Zulip inserts when the bot is pinged.
+fn strip_bot_mention(content: &str, bot_name: &str) -> String {
+    if bot_name.is_empty() {
+        return content.to_string();
+    }
+    content
+        .replace(&format!("@**{bot_name}**"), "")
+        .replace(&format!("@_**{bot_name}**"), "")
+        .trim()
+        .to_string()
+}
+
+/// Split `text` into chunks of at most `max_bytes`, on char boundaries and
+/// preferring line breaks.
+fn split_message(text: &str, max_bytes: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while rest.len() > max_bytes {
+        let mut cut = max_bytes;
+        while !rest.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        if let Some(newline) = rest[..cut].rfind('\n') {
+            if newline > 0 {
+                cut = newline + 1;
+            }
+        }
+        chunks.push(&rest[..cut]);
+        rest = &rest[cut..];
+    }
+    if !rest.is_empty() || chunks.is_empty() {
+        chunks.push(rest);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn channel(streams: &[&str], mention_only: bool) -> ZulipChannel {
+        ZulipChannel::new(
+            "https://chat.example.com/".into(),
+            "bot@example.com".into(),
+            "key".into(),
+            streams.iter().map(|s| s.to_string()).collect(),
+            vec!["alice@example.com".into()],
+            mention_only,
+        )
+    }
+
+    fn stream_event(stream: &str, topic: &str, content: &str, flags: &[&str]) -> Value {
+        json!({
+            "type": "message",
+            "id": 7,
+            "flags": flags,
+            "message": {
+                "id": 42,
+                "sender_id": 5,
+                "sender_email": "alice@example.com",
+                "type": "stream",
+                "display_recipient": stream,
+                "subject": topic,
+                "content": content,
+                "timestamp": 1_700_000_000
+            }
+        })
+    }
+
+    #[test]
+    fn stream_messages_map_topic_to_thread() {
+        let ch = channel(&[], false);
+        assert_eq!(ch.base_url, "https://chat.example.com");
+        let msg = ch
+            .parse_event(&stream_event("ops", "deploys", "status?", &[]), 1, "Bot")
+            .unwrap();
+        assert_eq!(msg.reply_target, "stream:ops");
+        assert_eq!(msg.thread_ts.as_deref(), Some("deploys"));
+        assert_eq!(msg.sender, "alice@example.com");
+        assert_eq!(msg.id, "zulip_42");
+        assert_eq!(msg.timestamp, 1_700_000_000);
+    }
+
+    #[test]
+    fn mention_only_and_stream_filter_apply_to_streams() {
+        let ch = channel(&["ops"], true);
+        assert!(ch
+            .parse_event(&stream_event("ops", "t", "no ping", &[]), 1, "Bot")
+            .is_none());
+        assert!(ch
+            .parse_event(
+                &stream_event("random", "t", "@**Bot** hi", &["mentioned"]),
+                1,
+                "Bot"
+            )
+            .is_none());
+        let msg = ch
+            .parse_event(
+                &stream_event("ops", "t", "@**Bot** hi", &["mentioned"]),
+                1,
+                "Bot",
+            )
+            .unwrap();
+        assert_eq!(msg.content, "hi");
+    }
+
+    #[test]
+    fn direct_messages_reply_to_other_participants() {
+        let ch = channel(&[], true);
+        let event = json!({
+            "type": "message",
+            "id": 8,
+            "flags": [],
+            "message": {
+                "id": 43,
+                "sender_id": 5,
+                "sender_email": "alice@example.com",
+                "type": "private",
+                "display_recipient": [
+                    {"id": 1, "email": "bot@example.com"},
+                    {"id": 5, "email": "alice@example.com"}
+                ],
+                "subject": "",
+                "content": "hello",
+                "timestamp": 1
+            }
+        });
+        let msg = ch.parse_event(&event, 1, "Bot").unwrap();
+        assert_eq!(msg.reply_target, "private:alice@example.com");
+        assert!(msg.thread_ts.is_none());
+
+        let mut own = event.clone();
+        own["message"]["sender_id"] = json!(1);
+        assert!(ch.parse_event(&own, 1, "Bot").is_none());
+
+        let mut stranger = event;
+        stranger["message"]["sender_email"] = json!("mallory@example.com");
+        assert!(ch.parse_event(&stranger, 1, "Bot").is_none());
+    }
+
+    #[test]
+    fn split_message_respects_limit_and_char_boundaries() {
+        assert_eq!(split_message("short", 100), vec!["short"]);
+        let text = "é".repeat(10);
+        let chunks = split_message(&text, 5);
+        assert!(chunks.iter().all(|c| c.len() <= 5));
+        assert_eq!(chunks.concat(), text);
+        assert_eq!(split_message("ab\ncd", 4), vec!["ab\n", "cd"]);
+    }
+}
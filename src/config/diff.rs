@@ -0,0 +1,146 @@
+//! Semantic comparison of two configs.
+//!
+//! Both sides go through [`Config`] and are serialized again before they are
+//! compared, so formatting, key order, legacy aliases, includes and values
+//! spelled out at their defaults never show up as differences.
+
+use super::layers::{collect_leaves, display_value};
+use super::Config;
+use anyhow::Result;
+use std::collections::BTreeMap;
+use toml::Value;
+
+/// One leaf that differs between two configs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigChange {
+    Added {
+        path: String,
+        value: String,
+    },
+    Removed {
+        path: String,
+        value: String,
+    },
+    Changed {
+        path: String,
+        from: String,
+        to: String,
+    },
+}
+
+impl ConfigChange {
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Added { path, .. } | Self::Removed { path, .. } | Self::Changed { path, .. } => {
+                path
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Added { path, value } => write!(f, "+ {path} = {value}"),
+            Self::Removed { path, value } => write!(f, "- {path} = {value}"),
+            Self::Changed { path, from, to } => write!(f, "~ {path} = {from} -> {to}"),
+        }
+    }
+}
+
+/// Leaf-level differences from `left` to `right`, sorted by path. Secret
+/// values are masked.
+pub fn diff(left: &Config, right: &Config) -> Result<Vec<ConfigChange>> {
+    let left = toml::Table::try_from(left)?;
+    let right = toml::Table::try_from(right)?;
+    let left = leaves(&left);
+    let mut right = leaves(&right);
+
+    let mut changes = Vec::new();
+    for (path, (key, before)) in left {
+        match right.remove(&path) {
+            None => changes.push(ConfigChange::Removed {
+                value: display_value(key, before),
+                path,
+            }),
+            Some((_, after)) if after != before => changes.push(ConfigChange::Changed {
+                from: display_value(key, before),
+                to: display_value(key, after),
+                path,
+            }),
+            Some(_) => {}
+        }
+    }
+    changes.extend(
+        right
+            .into_iter()
+            .map(|(path, (key, value))| ConfigChange::Added {
+                value: display_value(key, value),
+                path,
+            }),
+    );
+    changes.sort_by(|a, b| a.path().cmp(b.path()));
+    Ok(changes)
+}
+
+fn leaves(table: &toml::Table) -> BTreeMap<String, (&str, &Value)> {
+    let mut leaves = Vec::new();
+    collect_leaves(table, "", &mut leaves);
+    leaves
+        .into_iter()
+        .map(|(path, key, value)| (path, (key, value)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DelegateAgentConfig;
+
+    #[test]
+    fn diff_reports_changed_added_and_removed_leaves_with_secrets_masked() {
+        let left = Config {
+            api_key: Some("sk-left".into()),
+            ..Config::default()
+        };
+        let mut right = Config {
+            api_key: Some("sk-right".into()),
+            default_temperature: 0.2,
+            ..Config::default()
+        };
+        right.agents.insert(
+            "research".into(),
+            DelegateAgentConfig {
+                provider: "ollama".into(),
+                model: "llama3".into(),
+                system_prompt: None,
+                api_key: None,
+                temperature: None,
+                max_depth: 3,
+                agentic: false,
+                allowed_tools: Vec::new(),
+                max_iterations: 10,
+                reasoning: None,
+            },
+        );
+        let mut right_without_key = right.clone();
+        right_without_key.api_key = None;
+
+        let changes = diff(&left, &right).unwrap();
+        let rendered: Vec<String> = changes.iter().map(ToString::to_string).collect();
+        assert!(rendered.contains(&"~ default_temperature = 0.7 -> 0.2".to_string()));
+        assert!(rendered.contains(&"~ api_key = \"********\" -> \"********\"".to_string()));
+        assert!(rendered.contains(&"+ agents.research.model = \"llama3\"".to_string()));
+        assert!(!rendered.iter().any(|line| line.contains("sk-")));
+
+        let removed = diff(&right, &right_without_key).unwrap();
+        assert_eq!(
+            removed,
+            vec![ConfigChange::Removed {
+                path: "api_key".into(),
+                value: "\"********\"".into(),
+            }]
+        );
+        assert!(diff(&left, &left.clone()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,654 @@
+//! Layered config loading.
+//!
+//! `config.toml` is assembled at the TOML level before it is deserialized
+//! into [`Config`](super::Config):
+//!
+//! 1. `include = ["providers.toml", ...]` fragments are merged first, in
+//!    order, with the including file winning over its fragments.
+//! 2. The `[profile.<name>]` table selected with `--profile` (or
+//!    `ZEROCLAW_PROFILE`) is overlaid on the merged result.
+//! 3. `${VAR}`, `${VAR:-default}` and `${file:/path}` references in string
+//!    values are interpolated; `$${` is a literal `${`.
+//!
+//! Every leaf remembers which layer set it so `config show --resolved` can
+//! explain where a value came from.
+
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Environment variable carrying the selected `[profile.<name>]`.
+pub const PROFILE_ENV: &str = "ZEROCLAW_PROFILE";
+
+/// Guard against runaway include chains that are not strictly cyclic.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Placeholder shown instead of secret values.
+const MASKED: &str = "\"********\"";
+
+/// Where a resolved config value came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueOrigin {
+    /// Built-in default; no layer set it.
+    Default,
+    /// `config.toml` or one of its includes.
+    File(PathBuf),
+    /// A `[profile.<name>]` table, defined in `file`.
+    Profile { name: String, file: PathBuf },
+    /// A `ZEROCLAW_*` environment override applied after loading.
+    Env,
+}
+
+impl fmt::Display for ValueOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => f.write_str("default"),
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Profile { name, file } => write!(f, "profile {name} ({})", file.display()),
+            Self::Env => f.write_str("environment"),
+        }
+    }
+}
+
+/// The merged TOML table plus per-leaf provenance.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLayers {
+    /// Merged table with `include` and `profile` removed and references
+    /// interpolated; ready to deserialize.
+    pub table: Table,
+    /// Dotted leaf path → layer that set it.
+    pub origins: BTreeMap<String, ValueOrigin>,
+    /// Dotted leaf path → `${...}` references interpolated into it.
+    pub interpolated: BTreeMap<String, Vec<String>>,
+    /// Files that contributed, in merge order (includes before includers).
+    pub files: Vec<PathBuf>,
+    /// Selected `[profile.<name>]`, if any.
+    pub profile: Option<String>,
+}
+
+/// Profile requested through `ZEROCLAW_PROFILE` (set by `--profile`).
+pub fn selected_profile() -> Option<String> {
+    std::env::var(PROFILE_ENV)
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// Load `path` with its includes, overlay `profile` and interpolate.
+pub fn load(path: &Path, profile: Option<&str>) -> Result<ConfigLayers> {
+    let mut layers = ConfigLayers {
+        profile: profile.map(str::to_string),
+        ..ConfigLayers::default()
+    };
+    load_file(path, &mut layers, &mut Vec::new())?;
+
+    let profiles = layers.table.remove("profile");
+    let defined = layers.origins.clone();
+    layers
+        .origins
+        .retain(|key, _| key != "profile" && !key.starts_with("profile."));
+
+    if let Some(name) = profile {
+        let mut profiles = match profiles {
+            Some(Value::Table(profiles)) => profiles,
+            Some(_) => bail!("`profile` in {} must be a table", path.display()),
+            None => Table::new(),
+        };
+        let overlay = match profiles.remove(name) {
+            Some(Value::Table(overlay)) => overlay,
+            Some(_) => bail!("`profile.{name}` in {} must be a table", path.display()),
+            None => {
+                let available: Vec<&str> = profiles.keys().map(String::as_str).collect();
+                bail!(
+                    "Config profile `{name}` not found in {} (available: {})",
+                    path.display(),
+                    if available.is_empty() {
+                        "none".to_string()
+                    } else {
+                        available.join(", ")
+                    }
+                );
+            }
+        };
+        let profile_prefix = join_path("profile", name);
+        merge_table(
+            &mut layers.table,
+            overlay,
+            "",
+            &mut layers.origins,
+            &|leaf| ValueOrigin::Profile {
+                name: name.to_string(),
+                file: match defined.get(&format!("{profile_prefix}.{leaf}")) {
+                    Some(ValueOrigin::File(file)) => file.clone(),
+                    _ => path.to_path_buf(),
+                },
+            },
+        );
+    }
+
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    for (key, value) in &mut layers.table {
+        interpolate_value(value, key, base_dir, &mut layers.interpolated)?;
+    }
+    Ok(layers)
+}
+
+/// Whether a raw config table relies on includes, profiles or
+/// interpolation, i.e. cannot be rewritten from a resolved [`Config`].
+///
+/// [`Config`]: super::Config
+pub fn uses_layering(table: &Table) -> bool {
+    fn has_reference(value: &Value) -> bool {
+        match value {
+            Value::String(s) => s.contains("${"),
+            Value::Array(items) => items.iter().any(has_reference),
+            Value::Table(table) => table.values().any(has_reference),
+            _ => false,
+        }
+    }
+    table.contains_key("include")
+        || table.contains_key("profile")
+        || table.values().any(has_reference)
+}
+
+fn load_file(path: &Path, layers: &mut ConfigLayers, stack: &mut Vec<PathBuf>) -> Result<()> {
+    let canonical = path
+        .canonicalize()
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    if stack.contains(&canonical) {
+        let chain: Vec<String> = stack
+            .iter()
+            .chain(std::iter::once(&canonical))
+            .map(|file| file.display().to_string())
+            .collect();
+        bail!("Config include cycle: {}", chain.join(" -> "));
+    }
+    if stack.len() >= MAX_INCLUDE_DEPTH {
+        bail!(
+            "Config includes nested deeper than {MAX_INCLUDE_DEPTH} levels at {}",
+            path.display()
+        );
+    }
+
+    let contents = std::fs::read_to_string(&canonical)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    let mut table: Table = contents
+        .parse()
+        .with_context(|| format!("Failed to parse config file {}", path.display()))?;
+    // Older fragments load as-is; `config migrate` rewrites them on disk.
+    super::migrate::upgrade(&mut table)
+        .with_context(|| format!("Failed to migrate config file {}", path.display()))?;
+    table.remove(super::migrate::VERSION_KEY);
+
+    let includes = match table.remove("include") {
+        None => Vec::new(),
+        Some(Value::String(include)) => vec![include],
+        Some(Value::Array(items)) => items
+            .into_iter()
+            .map(|item| match item {
+                Value::String(include) => Ok(include),
+                other => bail!(
+                    "`include` entries in {} must be strings, found {}",
+                    path.display(),
+                    other.type_str()
+                ),
+            })
+            .collect::<Result<_>>()?,
+        Some(other) => bail!(
+            "`include` in {} must be a string or an array of strings, found {}",
+            path.display(),
+            other.type_str()
+        ),
+    };
+
+    let dir = canonical
+        .parent()
+        .map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+    stack.push(canonical);
+    for include in includes {
+        let include_path = dir.join(shellexpand::tilde(&include).as_ref());
+        load_file(&include_path, layers, stack).with_context(|| {
+            format!("Failed to load include `{include}` from {}", path.display())
+        })?;
+    }
+    stack.pop();
+
+    let file = path.to_path_buf();
+    layers.files.push(file.clone());
+    merge_table(&mut layers.table, table, "", &mut layers.origins, &|_| {
+        ValueOrigin::File(file.clone())
+    });
+    Ok(())
+}
+
+/// Deep-merge `overlay` into `base`. Tables merge key by key; any other
+/// value (arrays included) replaces what was there.
+fn merge_table(
+    base: &mut Table,
+    overlay: Table,
+    prefix: &str,
+    origins: &mut BTreeMap<String, ValueOrigin>,
+    origin: &dyn Fn(&str) -> ValueOrigin,
+) {
+    for (key, value) in overlay {
+        let path = join_path(prefix, &key);
+        if let (Some(Value::Table(existing)), Value::Table(_)) = (base.get_mut(&key), &value) {
+            let Value::Table(incoming) = value else {
+                unreachable!()
+            };
+            merge_table(existing, incoming, &path, origins, origin);
+            continue;
+        }
+        let nested = format!("{path}.");
+        origins.retain(|leaf, _| *leaf != path && !leaf.starts_with(&nested));
+        record_leaves(&value, &path, origins, origin);
+        base.insert(key, value);
+    }
+}
+
+fn record_leaves(
+    value: &Value,
+    path: &str,
+    origins: &mut BTreeMap<String, ValueOrigin>,
+    origin: &dyn Fn(&str) -> ValueOrigin,
+) {
+    match value {
+        Value::Table(table) if !table.is_empty() => {
+            for (key, item) in table {
+                record_leaves(item, &join_path(path, key), origins, origin);
+            }
+        }
+        _ => {
+            origins.insert(path.to_string(), origin(path));
+        }
+    }
+}
+
+fn interpolate_value(
+    value: &mut Value,
+    path: &str,
+    base_dir: &Path,
+    interpolated: &mut BTreeMap<String, Vec<String>>,
+) -> Result<()> {
+    match value {
+        Value::String(s) if s.contains('$') => {
+            let (resolved, references) = interpolate_str(s, base_dir)
+                .with_context(|| format!("Failed to interpolate config key `{path}`"))?;
+            *s = resolved;
+            if !references.is_empty() {
+                interpolated
+                    .entry(path.to_string())
+                    .or_default()
+                    .extend(references);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                interpolate_value(item, path, base_dir, interpolated)?;
+            }
+        }
+        Value::Table(table) => {
+            for (key, item) in table {
+                interpolate_value(item, &join_path(path, key), base_dir, interpolated)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Expand `${...}` references in `input`, returning the result and the
+/// references that were expanded.
+fn interpolate_str(input: &str, base_dir: &Path) -> Result<(String, Vec<String>)> {
+    let mut out = String::with_capacity(input.len());
+    let mut references = Vec::new();
+    let mut rest = input;
+    while let Some(idx) = rest.find('$') {
+        out.push_str(&rest[..idx]);
+        let tail = &rest[idx..];
+        if let Some(after) = tail.strip_prefix("$${") {
+            out.push_str("${");
+            rest = after;
+        } else if let Some(body) = tail.strip_prefix("${") {
+            let end = body
+                .find('}')
+                .context("Unterminated `${` reference (use `$${` for a literal)")?;
+            let expr = &body[..end];
+            out.push_str(&resolve_reference(expr, base_dir)?);
+            references.push(format!("${{{expr}}}"));
+            rest = &body[end + 1..];
+        } else {
+            out.push('$');
+            rest = &tail[1..];
+        }
+    }
+    out.push_str(rest);
+    Ok((out, references))
+}
+
+fn resolve_reference(expr: &str, base_dir: &Path) -> Result<String> {
+    if let Some(file) = expr.strip_prefix("file:") {
+        let file = file.trim();
+        let path = base_dir.join(shellexpand::tilde(file).as_ref());
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read ${{file:{file}}} ({})", path.display()))?;
+        return Ok(contents.trim_end_matches(['\r', '\n']).to_string());
+    }
+
+    let expr = expr.strip_prefix("env:").unwrap_or(expr);
+    let (name, default) = match expr.split_once(":-") {
+        Some((name, default)) => (name.trim(), Some(default)),
+        None => (expr.trim(), None),
+    };
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        bail!("Invalid environment variable name `{name}`");
+    }
+    match (std::env::var(name), default) {
+        (Ok(value), Some(default)) if value.is_empty() => Ok(default.to_string()),
+        (Ok(value), _) => Ok(value),
+        (Err(_), Some(default)) => Ok(default.to_string()),
+        (Err(_), None) => bail!("Environment variable `{name}` is not set"),
+    }
+}
+
+/// `gateway` + `port` → `gateway.port`; keys that are not bare TOML keys
+/// are quoted.
+fn join_path(prefix: &str, key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    let key = if bare {
+        key.to_string()
+    } else {
+        format!("{key:?}")
+    };
+    if prefix.is_empty() {
+        key
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+/// Key names whose string values are masked in `config show` output.
+pub(crate) fn is_secret_key(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    [
+        "api_key",
+        "apikey",
+        "token",
+        "secret",
+        "password",
+        "db_url",
+        "private_key",
+    ]
+    .iter()
+    .any(|suffix| name.ends_with(suffix))
+}
+
+/// Replace every string under a secret-looking key, at any depth.
+pub fn mask_secrets(value: &mut Value) {
+    match value {
+        Value::Table(table) => {
+            for (key, item) in table.iter_mut() {
+                if item.is_str() && is_secret_key(key) {
+                    *item = Value::String("********".into());
+                } else {
+                    mask_secrets(item);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(mask_secrets),
+        _ => {}
+    }
+}
+
+/// Render the fully resolved config as `path = value  # origin` lines.
+///
+/// `loaded` is the config as deserialized from the layers and `resolved` the
+/// same config after environment overrides; leaves that differ between the
+/// two are attributed to the environment.
+pub fn render_resolved(layers: Option<&ConfigLayers>, loaded: &Table, resolved: &Table) -> String {
+    use std::fmt::Write as _;
+
+    let mut out = String::new();
+    if let Some(layers) = layers {
+        let _ = writeln!(
+            out,
+            "# profile: {}",
+            layers.profile.as_deref().unwrap_or("(none)")
+        );
+        for file in &layers.files {
+            let _ = writeln!(out, "# file: {}", file.display());
+        }
+    }
+
+    let mut before = Vec::new();
+    collect_leaves(loaded, "", &mut before);
+    let before: std::collections::HashMap<String, &Value> = before
+        .into_iter()
+        .map(|(path, _, value)| (path, value))
+        .collect();
+
+    let mut leaves = Vec::new();
+    collect_leaves(resolved, "", &mut leaves);
+    for (path, key, value) in leaves {
+        let origin = if before.get(&path) == Some(&value) {
+            layers
+                .and_then(|layers| layers.origins.get(&path))
+                .cloned()
+                .unwrap_or(ValueOrigin::Default)
+        } else {
+            ValueOrigin::Env
+        };
+        let _ = write!(out, "{path} = {}  # {origin}", display_value(key, value));
+        if let Some(references) = layers.and_then(|layers| layers.interpolated.get(&path)) {
+            let _ = write!(out, " via {}", references.join(", "));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Inline TOML for `value` stored under `key`, with secrets masked.
+pub(crate) fn display_value(key: &str, value: &Value) -> String {
+    if value.is_str() && is_secret_key(key) {
+        MASKED.to_string()
+    } else {
+        let mut value = value.clone();
+        mask_secrets(&mut value);
+        value.to_string()
+    }
+}
+
+/// Flatten `table` into `(dotted path, key, value)` leaves.
+pub(crate) fn collect_leaves<'a>(
+    table: &'a Table,
+    prefix: &str,
+    out: &mut Vec<(String, &'a str, &'a Value)>,
+) {
+    for (key, value) in table {
+        let path = join_path(prefix, key);
+        match value {
+            Value::Table(nested) if !nested.is_empty() => {
+                collect_leaves(nested, &path, out);
+            }
+            _ => out.push((path, key.as_str(), value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &TempDir, name: &str, contents: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn includes_merge_under_the_including_file() {
+        let dir = TempDir::new().unwrap();
+        write(
+            &dir,
+            "providers.toml",
+            "default_model = \"from-include\"\n[gateway]\nport = 4000\nhost = \"0.0.0.0\"\n",
+        );
+        let main = write(
+            &dir,
+            "config.toml",
+            "include = [\"providers.toml\"]\n[gateway]\nport = 5000\n",
+        );
+
+        let layers = load(&main, None).unwrap();
+        assert!(!layers.table.contains_key("include"));
+        assert_eq!(layers.table["default_model"].as_str(), Some("from-include"));
+        assert_eq!(layers.table["gateway"]["port"].as_integer(), Some(5000));
+        assert_eq!(layers.table["gateway"]["host"].as_str(), Some("0.0.0.0"));
+        assert_eq!(
+            layers.origins["gateway.port"],
+            ValueOrigin::File(main.clone())
+        );
+        assert_eq!(
+            layers.origins["gateway.host"],
+            ValueOrigin::File(dir.path().join("providers.toml").canonicalize().unwrap())
+        );
+        assert_eq!(layers.files.last(), Some(&main));
+    }
+
+    #[test]
+    fn include_cycles_are_rejected() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "a.toml", "include = \"b.toml\"\n");
+        write(&dir, "b.toml", "include = \"a.toml\"\n");
+        let main = write(&dir, "config.toml", "include = \"a.toml\"\n");
+
+        let err = format!("{:#}", load(&main, None).unwrap_err());
+        assert!(err.contains("include cycle"), "{err}");
+    }
+
+    #[test]
+    fn selected_profile_overlays_and_unselected_profiles_are_dropped() {
+        let dir = TempDir::new().unwrap();
+        let main = write(
+            &dir,
+            "config.toml",
+            r#"
+default_model = "small"
+[gateway]
+port = 3000
+
+[profile.prod]
+default_model = "large"
+gateway = { port = 443 }
+
+[profile.dev.gateway]
+host = "${ZEROCLAW_LAYERS_TEST_UNSET}"
+"#,
+        );
+
+        let base = load(&main, None).unwrap();
+        assert!(!base.table.contains_key("profile"));
+        assert_eq!(base.table["default_model"].as_str(), Some("small"));
+
+        let prod = load(&main, Some("prod")).unwrap();
+        assert_eq!(prod.table["default_model"].as_str(), Some("large"));
+        assert_eq!(prod.table["gateway"]["port"].as_integer(), Some(443));
+        assert_eq!(
+            prod.origins["gateway.port"],
+            ValueOrigin::Profile {
+                name: "prod".into(),
+                file: main.clone()
+            }
+        );
+        assert!(!prod.origins.keys().any(|key| key.starts_with("profile.")));
+
+        let err = load(&main, Some("staging")).unwrap_err().to_string();
+        assert!(err.contains("available: prod, dev") || err.contains("available: dev, prod"));
+    }
+
+    #[test]
+    fn interpolates_env_file_defaults_and_escapes() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "key.txt", "sk-from-file\n");
+        std::env::set_var("ZEROCLAW_LAYERS_TEST_MODEL", "env-model");
+        let main = write(
+            &dir,
+            "config.toml",
+            r#"
+api_key = "${file:key.txt}"
+default_model = "${ZEROCLAW_LAYERS_TEST_MODEL}"
+default_provider = "${env:ZEROCLAW_LAYERS_TEST_UNSET:-openrouter}"
+[identity]
+format = "cost $5, literal $${HOME}"
+"#,
+        );
+
+        let layers = load(&main, None).unwrap();
+        std::env::remove_var("ZEROCLAW_LAYERS_TEST_MODEL");
+        assert_eq!(layers.table["api_key"].as_str(), Some("sk-from-file"));
+        assert_eq!(layers.table["default_model"].as_str(), Some("env-model"));
+        assert_eq!(
+            layers.table["default_provider"].as_str(),
+            Some("openrouter")
+        );
+        assert_eq!(
+            layers.table["identity"]["format"].as_str(),
+            Some("cost $5, literal ${HOME}")
+        );
+        assert_eq!(
+            layers.interpolated["api_key"],
+            vec!["${file:key.txt}".to_string()]
+        );
+
+        let missing = write(
+            &dir,
+            "missing.toml",
+            "api_key = \"${ZEROCLAW_LAYERS_TEST_UNSET}\"\n",
+        );
+        let err = format!("{:#}", load(&missing, None).unwrap_err());
+        assert!(
+            err.contains("api_key") && err.contains("is not set"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn render_resolved_masks_secrets_and_reports_origins() {
+        let dir = TempDir::new().unwrap();
+        let main = write(
+            &dir,
+            "config.toml",
+            "api_key = \"sk-live\"\ndefault_model = \"m\"\n[[profiles]]\nname = \"ops\"\napi_key = \"sk-ops\"\n",
+        );
+        let layers = load(&main, None).unwrap();
+        let mut loaded = layers.table.clone();
+        loaded.insert("default_temperature".into(), Value::Float(0.7));
+        let mut resolved = loaded.clone();
+        resolved.insert("default_model".into(), Value::String("env-model".into()));
+
+        let rendered = render_resolved(Some(&layers), &loaded, &resolved);
+        assert!(!rendered.contains("sk-live") && !rendered.contains("sk-ops"));
+        assert!(rendered.contains(&format!("api_key = {MASKED}  # {}", main.display())));
+        assert!(rendered.contains("default_model = \"env-model\"  # environment"));
+        assert!(rendered.contains("default_temperature = 0.7  # default"));
+    }
+
+    #[test]
+    fn uses_layering_detects_includes_profiles_and_references() {
+        let plain: Table = "default_model = \"m\"\n".parse().unwrap();
+        assert!(!uses_layering(&plain));
+        for raw in [
+            "include = [\"a.toml\"]\n",
+            "[profile.dev]\ndefault_model = \"m\"\n",
+            "[gateway]\nhost = \"${HOST}\"\n",
+        ] {
+            assert!(uses_layering(&raw.parse().unwrap()), "{raw}");
+        }
+    }
+}
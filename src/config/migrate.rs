@@ -0,0 +1,310 @@
+//! Versioned config format.
+//!
+//! Every file written by [`Config::save`](super::Config::save) starts with
+//! `config_version = N`. Files without it predate versioning and are treated
+//! as version 0. Older files are upgraded step by step through [`MIGRATIONS`]
+//! at the TOML level, so includes, profiles and `${...}` references survive
+//! a rewrite untouched.
+
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Config format version written by this build.
+pub const CURRENT_CONFIG_VERSION: u32 = 1;
+
+/// Top-level key carrying the format version.
+pub const VERSION_KEY: &str = "config_version";
+
+/// One step in the migration chain, upgrading `from` to `from + 1`.
+struct Migration {
+    from: u32,
+    description: &'static str,
+    /// Returns whether the table changed.
+    apply: fn(&mut Table) -> bool,
+}
+
+/// Ordered migration chain; `MIGRATIONS[n].from == n`.
+const MIGRATIONS: &[Migration] = &[Migration {
+    from: 0,
+    description: "rename legacy key aliases (composio.enable, storage.provider.config.dbURL/database_url/databaseUrl)",
+    apply: rename_legacy_aliases,
+}];
+
+/// What [`upgrade`] did to a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Upgrade {
+    pub from: u32,
+    /// Descriptions of the steps that changed something.
+    pub applied: Vec<&'static str>,
+}
+
+impl Upgrade {
+    pub fn changed(&self) -> bool {
+        !self.applied.is_empty()
+    }
+}
+
+/// How [`migrate_file`] treats the file on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationWrite {
+    /// Report only.
+    DryRun,
+    /// Rewrite only when a migration step changed the contents; used on
+    /// startup so unchanged legacy files keep their comments.
+    IfChanged,
+    /// Rewrite whenever the file is behind, stamping the current version.
+    Always,
+}
+
+/// Result of [`migrate_file`].
+#[derive(Debug, Clone)]
+pub struct MigrationReport {
+    pub path: PathBuf,
+    pub upgrade: Upgrade,
+    /// Copy of the original file, when it was rewritten.
+    pub backup: Option<PathBuf>,
+}
+
+/// Format version declared by `table` (0 when absent).
+pub fn table_version(table: &Table) -> Result<u32> {
+    match table.get(VERSION_KEY) {
+        None => Ok(0),
+        Some(Value::Integer(version)) => u32::try_from(*version)
+            .with_context(|| format!("`{VERSION_KEY}` must be a non-negative integer")),
+        Some(other) => bail!(
+            "`{VERSION_KEY}` must be an integer, found {}",
+            other.type_str()
+        ),
+    }
+}
+
+/// Format version of the file at `path` (0 when absent).
+pub fn file_version(path: &Path) -> Result<u32> {
+    table_version(&read_table(path)?)
+}
+
+/// Upgrade `table` in place to [`CURRENT_CONFIG_VERSION`] and stamp it.
+pub fn upgrade(table: &mut Table) -> Result<Upgrade> {
+    let from = table_version(table)?;
+    if from > CURRENT_CONFIG_VERSION {
+        bail!(
+            "config_version {from} is newer than this build supports ({CURRENT_CONFIG_VERSION}); \
+             upgrade zeroclaw"
+        );
+    }
+    let applied = MIGRATIONS
+        .iter()
+        .skip_while(|migration| migration.from < from)
+        .filter_map(|migration| {
+            let mut changed = (migration.apply)(table);
+            // `[profile.<name>]` overlays use the same key layout.
+            if let Some(Value::Table(profiles)) = table.get_mut("profile") {
+                for (_, overlay) in profiles.iter_mut() {
+                    if let Value::Table(overlay) = overlay {
+                        changed |= (migration.apply)(overlay);
+                    }
+                }
+            }
+            changed.then_some(migration.description)
+        })
+        .collect();
+    table.insert(
+        VERSION_KEY.into(),
+        Value::Integer(CURRENT_CONFIG_VERSION.into()),
+    );
+    Ok(Upgrade { from, applied })
+}
+
+/// Upgrade the file at `path`, keeping the original as
+/// `<name>.v<from>.bak` when it is rewritten.
+pub fn migrate_file(path: &Path, write: MigrationWrite) -> Result<MigrationReport> {
+    let mut table = read_table(path)?;
+    let upgrade = upgrade(&mut table)
+        .with_context(|| format!("Failed to migrate config file {}", path.display()))?;
+    let needs_write = match write {
+        MigrationWrite::DryRun => false,
+        MigrationWrite::IfChanged => upgrade.changed(),
+        MigrationWrite::Always => upgrade.from < CURRENT_CONFIG_VERSION,
+    };
+    let backup = if needs_write {
+        Some(rewrite(path, table, upgrade.from)?)
+    } else {
+        None
+    };
+    Ok(MigrationReport {
+        path: path.to_path_buf(),
+        upgrade,
+        backup,
+    })
+}
+
+/// Prefix serialized config with the current version; it stays the first
+/// line regardless of how the rest is ordered.
+pub fn stamp_version(toml: &str) -> String {
+    format!("{VERSION_KEY} = {CURRENT_CONFIG_VERSION}\n{toml}")
+}
+
+fn read_table(path: &Path) -> Result<Table> {
+    std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?
+        .parse()
+        .with_context(|| format!("Failed to parse config file {}", path.display()))
+}
+
+fn rewrite(path: &Path, mut table: Table, from: u32) -> Result<PathBuf> {
+    let dir = path
+        .parent()
+        .context("Config path must have a parent directory")?;
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("config.toml");
+    let backup = dir.join(format!("{file_name}.v{from}.bak"));
+    std::fs::copy(path, &backup)
+        .with_context(|| format!("Failed to back up {}", path.display()))?;
+
+    table.remove(VERSION_KEY);
+    let contents = stamp_version(&toml::to_string_pretty(&table)?);
+    let tmp = dir.join(format!(".{file_name}.migrate-tmp"));
+    std::fs::write(&tmp, contents)?;
+    // Keep the original mode: config files may hold secrets.
+    std::fs::set_permissions(&tmp, std::fs::metadata(path)?.permissions())?;
+    std::fs::rename(&tmp, path)?;
+    Ok(backup)
+}
+
+fn table_at<'a>(table: &'a mut Table, path: &[&str]) -> Option<&'a mut Table> {
+    path.iter()
+        .try_fold(table, |table, key| match table.get_mut(*key) {
+            Some(Value::Table(nested)) => Some(nested),
+            _ => None,
+        })
+}
+
+/// Move `aliases` to `canonical`; an existing canonical key wins and the
+/// aliases are dropped, since serde rejects both being present.
+fn rename_keys(table: &mut Table, canonical: &str, aliases: &[&str]) -> bool {
+    let mut changed = false;
+    for alias in aliases {
+        if let Some(value) = table.remove(*alias) {
+            if !table.contains_key(canonical) {
+                table.insert(canonical.to_string(), value);
+            }
+            changed = true;
+        }
+    }
+    changed
+}
+
+fn rename_legacy_aliases(table: &mut Table) -> bool {
+    let mut changed = false;
+    if let Some(composio) = table_at(table, &["composio"]) {
+        changed |= rename_keys(composio, "enabled", &["enable"]);
+    }
+    if let Some(storage) = table_at(table, &["storage", "provider", "config"]) {
+        changed |= rename_keys(storage, "db_url", &["dbURL", "database_url", "databaseUrl"]);
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn migrations_are_contiguous_and_end_at_current_version() {
+        for (idx, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.from as usize, idx);
+        }
+        assert_eq!(
+            u32::try_from(MIGRATIONS.len()).ok(),
+            Some(CURRENT_CONFIG_VERSION)
+        );
+    }
+
+    #[test]
+    fn upgrade_renames_legacy_aliases_and_stamps_version() {
+        let mut table: Table = r#"
+[composio]
+enable = true
+
+[profile.prod.composio]
+enable = false
+
+[storage.provider.config]
+provider = "postgres"
+dbURL = "postgres://old"
+database_url = "postgres://older"
+"#
+        .parse()
+        .unwrap();
+
+        let upgrade = upgrade(&mut table).unwrap();
+        assert_eq!(upgrade.from, 0);
+        assert!(upgrade.changed());
+        assert_eq!(table[VERSION_KEY].as_integer(), Some(1));
+        assert_eq!(table["composio"]["enabled"].as_bool(), Some(true));
+        assert!(table["composio"].get("enable").is_none());
+        assert_eq!(
+            table["profile"]["prod"]["composio"]["enabled"].as_bool(),
+            Some(false)
+        );
+        let storage = &table["storage"]["provider"]["config"];
+        assert_eq!(storage["db_url"].as_str(), Some("postgres://old"));
+        assert!(storage.get("database_url").is_none());
+
+        // Already current: nothing to do.
+        let again = super::upgrade(&mut table).unwrap();
+        assert_eq!(again.from, CURRENT_CONFIG_VERSION);
+        assert!(!again.changed());
+    }
+
+    #[test]
+    fn upgrade_rejects_newer_versions() {
+        let mut table: Table = "config_version = 99\n".parse().unwrap();
+        let err = upgrade(&mut table).unwrap_err().to_string();
+        assert!(err.contains("newer"), "{err}");
+    }
+
+    #[test]
+    fn migrate_file_backs_up_and_preserves_layering_syntax() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        let original =
+            "include = [\"extra.toml\"]\napi_key = \"${KEY}\"\n[composio]\nenable = true\n";
+        std::fs::write(&path, original).unwrap();
+
+        let dry = migrate_file(&path, MigrationWrite::DryRun).unwrap();
+        assert!(dry.upgrade.changed() && dry.backup.is_none());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+
+        let report = migrate_file(&path, MigrationWrite::IfChanged).unwrap();
+        let backup = report.backup.unwrap();
+        assert_eq!(backup, dir.path().join("config.toml.v0.bak"));
+        assert_eq!(std::fs::read_to_string(backup).unwrap(), original);
+
+        let migrated = std::fs::read_to_string(&path).unwrap();
+        assert!(migrated.starts_with("config_version = 1\n"));
+        let table: Table = migrated.parse().unwrap();
+        assert_eq!(table["api_key"].as_str(), Some("${KEY}"));
+        assert_eq!(table["include"].as_array().map(Vec::len), Some(1));
+        assert_eq!(table["composio"]["enabled"].as_bool(), Some(true));
+    }
+
+    #[test]
+    fn unchanged_legacy_files_are_only_rewritten_on_request() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "# tuned by hand\ndefault_model = \"m\"\n").unwrap();
+
+        let report = migrate_file(&path, MigrationWrite::IfChanged).unwrap();
+        assert!(report.backup.is_none());
+        assert_eq!(file_version(&path).unwrap(), 0);
+
+        let report = migrate_file(&path, MigrationWrite::Always).unwrap();
+        assert!(report.backup.is_some());
+        assert_eq!(file_version(&path).unwrap(), CURRENT_CONFIG_VERSION);
+    }
+}
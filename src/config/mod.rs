@@ -1,21 +1,27 @@
+pub mod diff;
+pub mod layers;
+pub mod migrate;
 pub mod schema;
 
 #[allow(unused_imports)]
 pub use schema::{
     apply_runtime_proxy_to_builder, build_runtime_proxy_client,
     build_runtime_proxy_client_with_timeouts, runtime_proxy_config, set_runtime_proxy_config,
-    AgentConfig, AuditConfig, AutonomyConfig, BrowserComputerUseConfig, BrowserConfig,
-    ChannelsConfig, ClassificationRule, ComposioConfig, Config, CostConfig, CronConfig,
-    DelegateAgentConfig, DeployConfig, DeploymentSettingsConfig, DeploymentTargetConfig,
-    DiscordConfig, DockerRuntimeConfig, EmbeddingRouteConfig, GatewayConfig, HardwareConfig,
-    HardwareTransport, HeartbeatConfig, HttpRequestConfig, IMessageConfig, IdentityConfig,
-    LarkConfig, MatrixConfig, MemoryConfig, ModelRouteConfig, MultimodalConfig,
-    NextcloudTalkConfig, ObservabilityConfig, PeripheralBoardConfig, PeripheralsConfig,
-    ProxyConfig, ProxyScope, QueryClassificationConfig, ReliabilityConfig, ResourceLimitsConfig,
-    RuntimeConfig, SandboxBackend, SandboxConfig, SchedulerConfig, SecretsConfig, SecurityConfig,
-    SkillsConfig, SkillsPromptInjectionMode, SlackConfig, StorageConfig, StorageProviderConfig,
-    StorageProviderSection, StreamMode, TelegramConfig, TunnelConfig, WebSearchConfig,
-    WebhookConfig,
+    AgentConfig, AgentProfileConfig, AuditConfig, AutonomyConfig, BrowserComputerUseConfig,
+    BrowserConfig, CassetteConfig, CassetteMode, ChannelsConfig, ClassificationRule,
+    ComposioConfig, Config, CostConfig, CronConfig, DelegateAgentConfig, DeployConfig,
+    DeploymentSettingsConfig, DeploymentTargetConfig, DiscordConfig, DockerRuntimeConfig,
+    EmbeddingRouteConfig, FlightRecorderConfig, GatewayConfig, HardwareConfig, HardwareTransport,
+    HeartbeatConfig, HttpRequestConfig, IMessageConfig, IdentityConfig, LarkConfig, MatrixConfig,
+    MemoryConfig, MemoryConsolidationConfig, MemoryScopeKind, MemoryScopeRule, MemoryScopesConfig,
+    ModelRouteConfig, MultimodalConfig, NextcloudTalkConfig, ObservabilityConfig,
+    PeripheralBoardConfig, PeripheralsConfig, ProxyConfig, ProxyScope, QueryClassificationConfig,
+    ReliabilityConfig, ResourceLimitsConfig, RuntimeConfig, SandboxBackend, SandboxConfig,
+    SchedulerConfig, SecretKeySource, SecretsConfig, SecurityConfig, SimulatedBoardConfig,
+    SimulatedFaultConfig, SimulatedFaultKind, SkillsConfig, SkillsPromptInjectionMode, SlackConfig,
+    StorageConfig, StorageProviderConfig, StorageProviderSection, StreamMode, TelegramConfig,
+    ToolSelectionConfig, TunnelConfig, WebSearchConfig, WebSocketConfig, WebhookConfig, XmppConfig,
+    ZulipConfig,
 };
 
 #[cfg(test)]
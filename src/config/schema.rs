@@ -1,10 +1,10 @@
-use crate::providers::{is_glm_alias, is_zai_alias};
+use crate::providers::{is_glm_alias, is_zai_alias, ReasoningLevel};
 use crate::security::AutonomyLevel;
 use anyhow::{Context, Result};
 use directories::UserDirs;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::{OnceLock, RwLock};
 #[cfg(unix)]
@@ -32,6 +32,7 @@ const SUPPORTED_PROXY_SERVICE_KEYS: &[&str] = &[
     "channel.slack",
     "channel.telegram",
     "channel.whatsapp",
+    "channel.zulip",
     "tool.browser",
     "tool.composio",
     "tool.http_request",
@@ -79,6 +80,10 @@ pub struct Config {
     #[serde(default)]
     pub autonomy: AutonomyConfig,
 
+    /// Sandboxing, resource limits and audit logging (`[security]`).
+    #[serde(default)]
+    pub security: SecurityConfig,
+
     /// Runtime adapter configuration (`[runtime]`). Controls native vs Docker execution.
     #[serde(default)]
     pub runtime: RuntimeConfig,
@@ -87,6 +92,10 @@ pub struct Config {
     #[serde(default)]
     pub reliability: ReliabilityConfig,
 
+    /// Provider record/replay for offline tests (`[cassette]`).
+    #[serde(default)]
+    pub cassette: CassetteConfig,
+
     /// Scheduler configuration for periodic task execution (`[scheduler]`).
     #[serde(default)]
     pub scheduler: SchedulerConfig,
@@ -190,6 +199,15 @@ pub struct Config {
     /// Hardware configuration (wizard-driven physical world setup).
     #[serde(default)]
     pub hardware: HardwareConfig,
+
+    /// Additional isolated agents hosted by the same daemon (`[[profiles]]`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<AgentProfileConfig>,
+
+    /// Name of the profile this config was derived from; `None` for the
+    /// top-level agent. Computed by [`Config::for_profile`], not serialized.
+    #[serde(skip)]
+    pub profile: Option<String>,
 }
 
 // ── Delegate Agents ──────────────────────────────────────────────
@@ -222,6 +240,9 @@ pub struct DelegateAgentConfig {
     /// Maximum tool-call iterations in agentic mode.
     #[serde(default = "default_max_tool_iterations")]
     pub max_iterations: usize,
+    /// Reasoning level for this agent; overrides `[runtime] reasoning`.
+    #[serde(default)]
+    pub reasoning: Option<ReasoningLevel>,
 }
 
 fn default_max_depth() -> u32 {
@@ -232,6 +253,61 @@ fn default_max_tool_iterations() -> usize {
     10
 }
 
+// ── Agent Profiles ───────────────────────────────────────────────
+
+/// An isolated agent persona hosted by the same daemon and gateway (`[[profiles]]`).
+///
+/// Unset keys inherit from the top-level config, except channels: a profile
+/// only listens on the channels it declares, so bot credentials are never
+/// shared between personas.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AgentProfileConfig {
+    /// Profile name, used in gateway routes (`/profiles/<name>/...`) and health components
+    pub name: String,
+    /// Workspace directory; relative paths resolve against the config directory.
+    /// Default: `profiles/<name>/workspace` next to `config.toml`.
+    #[serde(default)]
+    pub workspace_dir: Option<PathBuf>,
+    /// API key for this profile's provider
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Provider override; without `api_key` the provider resolves its own credential
+    #[serde(default)]
+    pub default_provider: Option<String>,
+    /// Model override
+    #[serde(default)]
+    pub default_model: Option<String>,
+    /// Temperature override
+    #[serde(default)]
+    pub default_temperature: Option<f64>,
+    /// Identity override (`[profiles.identity]`)
+    #[serde(default)]
+    pub identity: Option<IdentityConfig>,
+    /// Autonomy policy override (`[profiles.autonomy]`)
+    #[serde(default)]
+    pub autonomy: Option<AutonomyConfig>,
+    /// Memory backend override (`[profiles.memory]`); storage always lives in the profile workspace
+    #[serde(default)]
+    pub memory: Option<MemoryConfig>,
+    /// Channels this profile listens on (`[profiles.channels_config]`)
+    #[serde(default = "default_profile_channels")]
+    pub channels_config: ChannelsConfig,
+}
+
+fn default_profile_channels() -> ChannelsConfig {
+    ChannelsConfig {
+        cli: false,
+        ..ChannelsConfig::default()
+    }
+}
+
+fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
 // ── Hardware Config (wizard-driven) ─────────────────────────────
 
 /// Hardware transport mode.
@@ -321,6 +397,9 @@ pub struct AgentConfig {
     /// Tool dispatch strategy (e.g. `"auto"`). Default: `"auto"`.
     #[serde(default = "default_agent_tool_dispatcher")]
     pub tool_dispatcher: String,
+    /// Per-turn tool retrieval for large registries (`[agent.tool_selection]`).
+    #[serde(default)]
+    pub tool_selection: ToolSelectionConfig,
 }
 
 fn default_agent_max_tool_iterations() -> usize {
@@ -343,6 +422,61 @@ impl Default for AgentConfig {
             max_history_messages: default_agent_max_history_messages(),
             parallel_tools: false,
             tool_dispatcher: default_agent_tool_dispatcher(),
+            tool_selection: ToolSelectionConfig::default(),
+        }
+    }
+}
+
+/// Tool retrieval configuration (`[agent.tool_selection]` section).
+///
+/// When enabled and the registry is larger than `min_registry_size`, each
+/// request carries only the pinned tools plus the `top_k` tools most relevant
+/// to the latest user message. The `tool_search` meta-tool lets the model
+/// discover the rest.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ToolSelectionConfig {
+    /// Enable per-turn tool selection. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Relevant tools sent per request, besides pinned ones. Default: `8`.
+    #[serde(default = "default_tool_selection_top_k")]
+    pub top_k: usize,
+    /// Tools always sent regardless of relevance.
+    #[serde(default = "default_tool_selection_pinned")]
+    pub pinned: Vec<String>,
+    /// Registries with at most this many tools are sent whole. Default: `16`.
+    #[serde(default = "default_tool_selection_min_registry_size")]
+    pub min_registry_size: usize,
+}
+
+fn default_tool_selection_top_k() -> usize {
+    8
+}
+
+fn default_tool_selection_pinned() -> Vec<String> {
+    [
+        "shell",
+        "file_read",
+        "file_write",
+        "file_edit",
+        "memory_recall",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn default_tool_selection_min_registry_size() -> usize {
+    16
+}
+
+impl Default for ToolSelectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            top_k: default_tool_selection_top_k(),
+            pinned: default_tool_selection_pinned(),
+            min_registry_size: default_tool_selection_min_registry_size(),
         }
     }
 }
@@ -631,17 +765,117 @@ pub struct PeripheralsConfig {
 /// Configuration for a single peripheral board (e.g. STM32, RPi GPIO).
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PeripheralBoardConfig {
-    /// Board type: "nucleo-f401re", "rpi-gpio", "esp32", etc.
+    /// Board type: "nucleo-f401re", "rpi-gpio", "esp32", "simulated", etc.
     pub board: String,
-    /// Transport: "serial", "native", "websocket"
+    /// Transport: "serial", "native", "tcp", "websocket"; for "simulated": "in-process" or "pty"
     #[serde(default = "default_peripheral_transport")]
     pub transport: String,
-    /// Path for serial: "/dev/ttyACM0", "/dev/ttyUSB0"
+    /// Path for serial: "/dev/ttyACM0", "/dev/ttyUSB0"; address for network
+    /// transports: "192.168.1.50:7070" (tcp) or "ws://esp32.local:7070/zeroclaw" (websocket)
     #[serde(default)]
     pub path: Option<String>,
     /// Baud rate for serial (default: 115200)
     #[serde(default = "default_peripheral_baud")]
     pub baud: u32,
+    /// Hosts a "tcp"/"websocket" board may be reached at. Empty denies all.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_hosts: Vec<String>,
+    /// Pre-shared key for the "tcp"/"websocket" handshake (encrypted at rest).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared_secret: Option<String>,
+    /// Initial state and fault script for `board = "simulated"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub simulation: Option<SimulatedBoardConfig>,
+}
+
+/// Scripted state of a simulated board (`[peripherals.boards.simulation]`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SimulatedBoardConfig {
+    /// GPIO pins the board reports (default: 0–13).
+    #[serde(default = "default_simulated_gpio_pins")]
+    pub gpio_pins: Vec<u8>,
+    /// Pin reported as `led_pin` by `capabilities` (default: 13).
+    #[serde(default = "default_simulated_led_pin")]
+    pub led_pin: u8,
+    /// Pins that start high; all others start low.
+    #[serde(default)]
+    pub gpio_high: Vec<u8>,
+    /// Raw ADC readings, indexed by channel.
+    #[serde(default)]
+    pub adc: Vec<u16>,
+    /// Named sensor readings (e.g. `temperature = 21.5`).
+    #[serde(default)]
+    pub sensors: BTreeMap<String, f64>,
+    /// Faults injected into replies, consumed in order.
+    #[serde(default)]
+    pub faults: Vec<SimulatedFaultConfig>,
+    /// Delay before every reply, in milliseconds (default: 0).
+    #[serde(default)]
+    pub latency_ms: u64,
+    /// How long the host waits for a reply, in milliseconds (default: 5000).
+    #[serde(default = "default_simulated_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_simulated_gpio_pins() -> Vec<u8> {
+    (0..=13).collect()
+}
+
+fn default_simulated_led_pin() -> u8 {
+    13
+}
+
+fn default_simulated_timeout_ms() -> u64 {
+    5_000
+}
+
+impl Default for SimulatedBoardConfig {
+    fn default() -> Self {
+        Self {
+            gpio_pins: default_simulated_gpio_pins(),
+            led_pin: default_simulated_led_pin(),
+            gpio_high: Vec::new(),
+            adc: Vec::new(),
+            sensors: BTreeMap::new(),
+            faults: Vec::new(),
+            latency_ms: 0,
+            timeout_ms: default_simulated_timeout_ms(),
+        }
+    }
+}
+
+/// One injected fault on a simulated board.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SimulatedFaultConfig {
+    /// Only requests for this command trigger the fault; any command when unset.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// What goes wrong.
+    pub kind: SimulatedFaultKind,
+    /// How many matching requests are affected (default: 1).
+    #[serde(default = "default_simulated_fault_count")]
+    pub count: u32,
+    /// Error text for `kind = "error"`.
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+fn default_simulated_fault_count() -> u32 {
+    1
+}
+
+/// Failure modes a simulated board can inject.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SimulatedFaultKind {
+    /// Never reply, so the host request times out.
+    Timeout,
+    /// Reply with a line that is not JSON.
+    Malformed,
+    /// Reply with the wrong request id.
+    WrongId,
+    /// Reply with `ok: false` and `message`.
+    Error,
 }
 
 fn default_peripheral_transport() -> String {
@@ -659,6 +893,9 @@ impl Default for PeripheralBoardConfig {
             transport: default_peripheral_transport(),
             path: None,
             baud: default_peripheral_baud(),
+            allowed_hosts: Vec::new(),
+            shared_secret: None,
+            simulation: None,
         }
     }
 }
@@ -796,17 +1033,35 @@ impl Default for ComposioConfig {
 
 // ── Secrets (encrypted credential store) ────────────────────────
 
+/// Where the secret store's encryption key comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretKeySource {
+    /// Random key kept in `~/.zeroclaw/.secret_key` (default).
+    #[default]
+    File,
+    /// Key derived with Argon2id from a passphrase that is never written to disk.
+    Passphrase,
+}
+
 /// Secrets encryption configuration (`[secrets]` section).
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SecretsConfig {
     /// Enable encryption for API keys and tokens in config.toml
     #[serde(default = "default_true")]
     pub encrypt: bool,
+    /// Key source for new keys: `file` or `passphrase`. Switching an existing
+    /// install takes effect after `zeroclaw secrets rotate`.
+    #[serde(default)]
+    pub key_source: SecretKeySource,
 }
 
 impl Default for SecretsConfig {
     fn default() -> Self {
-        Self { encrypt: true }
+        Self {
+            encrypt: true,
+            key_source: SecretKeySource::File,
+        }
     }
 }
 
@@ -1548,7 +1803,8 @@ pub struct MemoryConfig {
     /// For sqlite backend: prune conversation rows older than this many days
     #[serde(default = "default_conversation_retention_days")]
     pub conversation_retention_days: u32,
-    /// Embedding provider: "none" | "openai" | "custom:URL"
+    /// Embedding provider: "none" | "openai" | "openrouter" | "ollama" | "ollama:URL" |
+    /// "gemini" | "custom:URL". Unknown names fail memory startup.
     #[serde(default = "default_embedding_provider")]
     pub embedding_provider: String,
     /// Embedding model name (e.g. "text-embedding-3-small")
@@ -1557,6 +1813,12 @@ pub struct MemoryConfig {
     /// Embedding vector dimensions
     #[serde(default = "default_embedding_dims")]
     pub embedding_dimensions: usize,
+    /// Max texts sent in a single embedding request
+    #[serde(default = "default_embedding_batch_size")]
+    pub embedding_batch_size: usize,
+    /// Max embedding requests in flight at once
+    #[serde(default = "default_embedding_max_concurrency")]
+    pub embedding_max_concurrency: usize,
     /// Weight for vector similarity in hybrid search (0.0–1.0)
     #[serde(default = "default_vector_weight")]
     pub vector_weight: f64,
@@ -1602,6 +1864,153 @@ pub struct MemoryConfig {
     /// None = wait indefinitely (default). Recommended max: 300.
     #[serde(default)]
     pub sqlite_open_timeout_secs: Option<u64>,
+
+    // ── Scopes ────────────────────────────────────────────────
+    /// Which memory scopes channel conversations may read and write
+    #[serde(default)]
+    pub scopes: MemoryScopesConfig,
+
+    // ── Consolidation ─────────────────────────────────────────
+    /// Periodic LLM consolidation of daily/conversation entries and decay-aware recall
+    #[serde(default)]
+    pub consolidation: MemoryConsolidationConfig,
+}
+
+/// LLM-driven memory consolidation (`[memory.consolidation]`).
+///
+/// When enabled, the daemon periodically distils related daily/conversation
+/// entries into core facts and recall ranks results by importance and recency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct MemoryConsolidationConfig {
+    /// Run consolidation passes and apply decay scoring to recall (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Hours between consolidation passes (default: 24)
+    #[serde(default = "default_consolidation_interval_hours")]
+    pub interval_hours: u32,
+    /// Provider used to merge entries (default: `default_provider`)
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model used to merge entries (default: `default_model`)
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Only consolidate entries older than this many hours (default: 24)
+    #[serde(default = "default_consolidation_min_age_hours")]
+    pub min_age_hours: u32,
+    /// Max daily/conversation entries considered per pass (default: 200)
+    #[serde(default = "default_consolidation_max_entries")]
+    pub max_entries: usize,
+    /// Min related entries before a cluster is sent to the model (default: 2)
+    #[serde(default = "default_consolidation_min_cluster_size")]
+    pub min_cluster_size: usize,
+    /// Word-overlap similarity (0.0–1.0) for two entries to share a cluster (default: 0.2)
+    #[serde(default = "default_consolidation_similarity")]
+    pub similarity_threshold: f64,
+    /// Days for the recency weight of an unimportant memory to halve (default: 30)
+    #[serde(default = "default_consolidation_half_life_days")]
+    pub decay_half_life_days: f64,
+}
+
+fn default_consolidation_interval_hours() -> u32 {
+    24
+}
+
+fn default_consolidation_min_age_hours() -> u32 {
+    24
+}
+
+fn default_consolidation_max_entries() -> usize {
+    200
+}
+
+fn default_consolidation_min_cluster_size() -> usize {
+    2
+}
+
+fn default_consolidation_similarity() -> f64 {
+    0.2
+}
+
+fn default_consolidation_half_life_days() -> f64 {
+    30.0
+}
+
+impl Default for MemoryConsolidationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: default_consolidation_interval_hours(),
+            provider: None,
+            model: None,
+            min_age_hours: default_consolidation_min_age_hours(),
+            max_entries: default_consolidation_max_entries(),
+            min_cluster_size: default_consolidation_min_cluster_size(),
+            similarity_threshold: default_consolidation_similarity(),
+            decay_half_life_days: default_consolidation_half_life_days(),
+        }
+    }
+}
+
+/// Memory namespace kind a channel conversation can be granted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryScopeKind {
+    /// Shared by every conversation
+    Global,
+    /// Every conversation on the same channel
+    Channel,
+    /// The message sender on this channel
+    Sender,
+    /// The thread, or the chat when the platform has no threads
+    Thread,
+}
+
+/// Read/write grant for channel conversations (`[memory.scopes.default]`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct MemoryScopeRule {
+    /// Scopes recalled into prompts and by `memory_recall` (default: global, sender)
+    #[serde(default = "default_memory_scope_read")]
+    pub read: Vec<MemoryScopeKind>,
+    /// Scope auto-save and `memory_store` write to (default: sender)
+    #[serde(default = "default_memory_scope_write")]
+    pub write: MemoryScopeKind,
+}
+
+fn default_memory_scope_read() -> Vec<MemoryScopeKind> {
+    vec![MemoryScopeKind::Global, MemoryScopeKind::Sender]
+}
+
+fn default_memory_scope_write() -> MemoryScopeKind {
+    MemoryScopeKind::Sender
+}
+
+impl Default for MemoryScopeRule {
+    fn default() -> Self {
+        Self {
+            read: default_memory_scope_read(),
+            write: default_memory_scope_write(),
+        }
+    }
+}
+
+/// Memory scope policy for channel conversations (`[memory.scopes]`).
+///
+/// CLI sessions are unscoped and see every memory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct MemoryScopesConfig {
+    /// Rule for channels without their own entry
+    #[serde(default)]
+    pub default: MemoryScopeRule,
+    /// Per-channel rules keyed by channel name (e.g. `telegram`, `slack`)
+    #[serde(default)]
+    pub channels: HashMap<String, MemoryScopeRule>,
+}
+
+impl MemoryScopesConfig {
+    /// Rule that applies to `channel`.
+    pub fn rule_for(&self, channel: &str) -> &MemoryScopeRule {
+        self.channels.get(channel).unwrap_or(&self.default)
+    }
 }
 
 fn default_embedding_provider() -> String {
@@ -1625,6 +2034,12 @@ fn default_embedding_model() -> String {
 fn default_embedding_dims() -> usize {
     1536
 }
+fn default_embedding_batch_size() -> usize {
+    64
+}
+fn default_embedding_max_concurrency() -> usize {
+    4
+}
 fn default_vector_weight() -> f64 {
     0.7
 }
@@ -1659,6 +2074,8 @@ impl Default for MemoryConfig {
             embedding_provider: default_embedding_provider(),
             embedding_model: default_embedding_model(),
             embedding_dimensions: default_embedding_dims(),
+            embedding_batch_size: default_embedding_batch_size(),
+            embedding_max_concurrency: default_embedding_max_concurrency(),
             vector_weight: default_vector_weight(),
             keyword_weight: default_keyword_weight(),
             min_relevance_score: default_min_relevance_score(),
@@ -1671,6 +2088,8 @@ impl Default for MemoryConfig {
             snapshot_on_hygiene: false,
             auto_hydrate: true,
             sqlite_open_timeout_secs: None,
+            scopes: MemoryScopesConfig::default(),
+            consolidation: MemoryConsolidationConfig::default(),
         }
     }
 }
@@ -1690,6 +2109,15 @@ pub struct ObservabilityConfig {
     /// Service name reported to the OTel collector. Defaults to "zeroclaw".
     #[serde(default)]
     pub otel_service_name: Option<String>,
+
+    /// Forward model reasoning text to the backend as `reasoning` events.
+    /// Off by default because thinking output can echo prompt contents.
+    #[serde(default)]
+    pub log_reasoning: bool,
+
+    /// Local flight recorder (`[observability.flight_recorder]`).
+    #[serde(default)]
+    pub flight_recorder: FlightRecorderConfig,
 }
 
 impl Default for ObservabilityConfig {
@@ -1698,6 +2126,62 @@ impl Default for ObservabilityConfig {
             backend: "none".into(),
             otel_endpoint: None,
             otel_service_name: None,
+            log_reasoning: false,
+            flight_recorder: FlightRecorderConfig::default(),
+        }
+    }
+}
+
+/// Per-turn JSONL flight recorder (`[observability.flight_recorder]`).
+///
+/// Runs alongside the configured backend and writes prompts, provider
+/// requests/responses and tool payloads to disk for post-mortem debugging
+/// with `zeroclaw trace`. Off by default because records contain
+/// conversation content.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FlightRecorderConfig {
+    /// Enable the flight recorder. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Directory for turn records (`~` is expanded). Default: `~/.zeroclaw/traces`.
+    #[serde(default = "default_flight_recorder_dir")]
+    pub dir: String,
+
+    /// Maximum number of turn records kept; oldest are pruned first. Default: `200`.
+    #[serde(default = "default_flight_recorder_max_turns")]
+    pub max_turns: usize,
+
+    /// Maximum total size of all records in MB. Default: `100`.
+    #[serde(default = "default_flight_recorder_max_total_mb")]
+    pub max_total_mb: u64,
+
+    /// Extra regexes whose matches are replaced with `[REDACTED]`, on top of
+    /// the built-in credential patterns.
+    #[serde(default)]
+    pub redact_patterns: Vec<String>,
+}
+
+fn default_flight_recorder_dir() -> String {
+    "~/.zeroclaw/traces".to_string()
+}
+
+fn default_flight_recorder_max_turns() -> usize {
+    200
+}
+
+fn default_flight_recorder_max_total_mb() -> u64 {
+    100
+}
+
+impl Default for FlightRecorderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: default_flight_recorder_dir(),
+            max_turns: default_flight_recorder_max_turns(),
+            max_total_mb: default_flight_recorder_max_total_mb(),
+            redact_patterns: Vec::new(),
         }
     }
 }
@@ -1814,8 +2298,27 @@ pub struct RuntimeConfig {
     /// - `None`: provider default behavior
     /// - `Some(true)`: request reasoning/thinking when supported
     /// - `Some(false)`: disable reasoning/thinking when supported
+    ///
+    /// Superseded by `reasoning`, which wins when both are set.
     #[serde(default)]
     pub reasoning_enabled: Option<bool>,
+
+    /// Provider-neutral reasoning level: `"off"`, `"low"`, `"medium"`,
+    /// `"high"`, or a thinking token budget. `None` keeps provider defaults.
+    #[serde(default)]
+    pub reasoning: Option<ReasoningLevel>,
+
+    /// Print model reasoning to stderr in interactive CLI sessions.
+    #[serde(default)]
+    pub show_reasoning: bool,
+}
+
+impl RuntimeConfig {
+    /// Reasoning level after folding in the legacy `reasoning_enabled` toggle.
+    pub fn effective_reasoning(&self) -> Option<ReasoningLevel> {
+        self.reasoning
+            .or_else(|| self.reasoning_enabled.map(ReasoningLevel::from_enabled))
+    }
 }
 
 /// Docker runtime configuration (`[runtime.docker]` section).
@@ -1890,6 +2393,8 @@ impl Default for RuntimeConfig {
             kind: default_runtime_kind(),
             docker: DockerRuntimeConfig::default(),
             reasoning_enabled: None,
+            reasoning: None,
+            show_reasoning: false,
         }
     }
 }
@@ -1930,6 +2435,10 @@ pub struct ReliabilityConfig {
     /// Max retries for cron job execution attempts.
     #[serde(default = "default_scheduler_retries")]
     pub scheduler_retries: u32,
+    /// Times a reply that fails its requested JSON schema is sent back to the
+    /// model with the validation errors before giving up.
+    #[serde(default = "default_structured_output_repairs")]
+    pub structured_output_repairs: u32,
 }
 
 fn default_provider_retries() -> u32 {
@@ -1956,6 +2465,10 @@ fn default_scheduler_retries() -> u32 {
     2
 }
 
+fn default_structured_output_repairs() -> u32 {
+    2
+}
+
 impl Default for ReliabilityConfig {
     fn default() -> Self {
         Self {
@@ -1968,6 +2481,49 @@ impl Default for ReliabilityConfig {
             channel_max_backoff_secs: default_channel_backoff_max_secs(),
             scheduler_poll_secs: default_scheduler_poll_secs(),
             scheduler_retries: default_scheduler_retries(),
+            structured_output_repairs: default_structured_output_repairs(),
+        }
+    }
+}
+
+// ── Provider cassettes ──────────────────────────────────────────
+
+/// Whether provider traffic is recorded to, or replayed from, a cassette file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CassetteMode {
+    /// Talk to the configured provider normally (default).
+    #[default]
+    Off,
+    /// Call the configured provider and append every exchange to the cassette.
+    Record,
+    /// Serve responses from the cassette without contacting any provider.
+    Replay,
+}
+
+/// Provider record/replay configuration (`[cassette]` section).
+///
+/// See [`crate::providers::cassette`] for the file format and request matching.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CassetteConfig {
+    /// `off`, `record` or `replay`.
+    #[serde(default)]
+    pub mode: CassetteMode,
+    /// Cassette file (JSON lines). Required unless `mode = "off"`.
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    /// In replay mode, fail requests with no recorded match instead of serving
+    /// the next unused exchange.
+    #[serde(default = "default_true")]
+    pub strict: bool,
+}
+
+impl Default for CassetteConfig {
+    fn default() -> Self {
+        Self {
+            mode: CassetteMode::Off,
+            path: None,
+            strict: true,
         }
     }
 }
@@ -2038,6 +2594,10 @@ pub struct ModelRouteConfig {
     /// Optional API key override for this route's provider
     #[serde(default)]
     pub api_key: Option<String>,
+    /// Reasoning level for requests sent through this route; overrides
+    /// `[runtime] reasoning`.
+    #[serde(default)]
+    pub reasoning: Option<ReasoningLevel>,
 }
 
 // ── Embedding routing ───────────────────────────────────────────
@@ -2058,7 +2618,7 @@ pub struct ModelRouteConfig {
 pub struct EmbeddingRouteConfig {
     /// Route hint name (e.g. "semantic", "archive", "faq")
     pub hint: String,
-    /// Embedding provider (`none`, `openai`, or `custom:<url>`)
+    /// Embedding provider (`none`, `openai`, `ollama`, `gemini`, or `custom:<url>`; see `[memory] embedding_provider`)
     pub provider: String,
     /// Embedding model to use with that provider
     pub model: String,
@@ -2249,7 +2809,7 @@ pub struct DeploymentTargetConfig {
     pub host: String,
 
     /// SSH port (default: 22).
-    #[serde(default)]
+    #[serde(default = "default_deploy_ssh_port")]
     pub port: u16,
 
     /// SSH username.
@@ -2287,10 +2847,24 @@ pub struct DeploymentSettingsConfig {
     #[serde(default)]
     pub auto_start: bool,
 
-    /// Health check interval in seconds.
+    /// Seconds between health probes after a release is switched in.
     #[serde(default = "default_health_check_interval")]
     pub health_check_interval_secs: u64,
 
+    /// Health probes before a release is declared unhealthy and rolled back.
+    #[serde(default = "default_health_check_retries")]
+    pub health_check_retries: u32,
+
+    /// Shell command run on the target as the health probe; exit 0 means
+    /// healthy. Defaults to a per-mode process/service check.
+    #[serde(default)]
+    pub health_command: Option<String>,
+
+    /// Shell command run on the target to restart the service after a
+    /// switch. Defaults to a per-mode restart.
+    #[serde(default)]
+    pub restart_command: Option<String>,
+
     /// Restart on failure.
     #[serde(default)]
     pub restart_on_failure: bool,
@@ -2316,6 +2890,14 @@ fn default_health_check_interval() -> u64 {
     30
 }
 
+fn default_health_check_retries() -> u32 {
+    3
+}
+
+fn default_deploy_ssh_port() -> u16 {
+    22
+}
+
 impl Default for DeployConfig {
     fn default() -> Self {
         Self {
@@ -2334,6 +2916,9 @@ impl Default for DeploymentSettingsConfig {
             working_dir: default_deploy_working_dir(),
             auto_start: true,
             health_check_interval_secs: default_health_check_interval(),
+            health_check_retries: default_health_check_retries(),
+            health_command: None,
+            restart_command: None,
             restart_on_failure: true,
             max_restarts: 3,
             use_sudo: true,
@@ -2383,6 +2968,12 @@ pub struct ChannelsConfig {
     pub dingtalk: Option<DingTalkConfig>,
     /// QQ Official Bot channel configuration.
     pub qq: Option<QQConfig>,
+    /// Local WebSocket ingress channel configuration.
+    pub websocket: Option<WebSocketConfig>,
+    /// XMPP (Jabber) channel configuration.
+    pub xmpp: Option<XmppConfig>,
+    /// Zulip bot channel configuration.
+    pub zulip: Option<ZulipConfig>,
     /// Base timeout in seconds for processing a single channel message (LLM + tools).
     /// Runtime uses this as a per-turn budget that scales with tool-loop depth
     /// (up to 4x, capped) so one slow/retried model call does not consume the
@@ -2416,6 +3007,9 @@ impl Default for ChannelsConfig {
             lark: None,
             dingtalk: None,
             qq: None,
+            websocket: None,
+            xmpp: None,
+            zulip: None,
             message_timeout_secs: default_channel_message_timeout_secs(),
         }
     }
@@ -2642,6 +3236,107 @@ pub struct NextcloudTalkConfig {
     pub allowed_users: Vec<String>,
 }
 
+/// XMPP channel configuration (direct chats plus multi-user chat rooms).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct XmppConfig {
+    /// Bot JID (e.g. `"bot@example.com"`).
+    pub jid: String,
+    /// Account password, sent with SASL PLAIN over TLS.
+    pub password: String,
+    /// Server host to connect to. Defaults to the JID domain.
+    #[serde(default)]
+    pub server: Option<String>,
+    /// Client-to-server port. Default: `5222`.
+    #[serde(default = "default_xmpp_port")]
+    pub port: u16,
+    /// Multi-user chat rooms to join (e.g. `"team@conference.example.com"`).
+    #[serde(default)]
+    pub rooms: Vec<String>,
+    /// Nickname used in rooms. Defaults to the JID local part.
+    #[serde(default)]
+    pub nickname: Option<String>,
+    /// Allowed bare JIDs, room occupants (`room@host/nick`) or whole rooms
+    /// (`[]` = deny all, `"*"` = allow all).
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// When true, only respond in rooms when the message names the bot.
+    /// Direct messages are always processed.
+    #[serde(default)]
+    pub mention_only: bool,
+    /// Upgrade the connection with STARTTLS. Default: `true`.
+    /// Disabling it is only allowed for a loopback server.
+    #[serde(default = "default_true")]
+    pub starttls: bool,
+}
+
+fn default_xmpp_port() -> u16 {
+    5222
+}
+
+/// Zulip bot channel configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ZulipConfig {
+    /// Zulip server URL (e.g. `"https://chat.example.com"`).
+    pub url: String,
+    /// Bot email address (from the bot's settings page).
+    pub bot_email: String,
+    /// Bot API key.
+    pub api_key: String,
+    /// Streams to respond in (`[]` = every stream the bot is subscribed to).
+    #[serde(default)]
+    pub streams: Vec<String>,
+    /// Allowed sender emails (`[]` = deny all, `"*"` = allow all).
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// When true, only respond in streams when the bot is @-mentioned.
+    /// Direct messages are always processed.
+    #[serde(default)]
+    pub mention_only: bool,
+}
+
+/// Local WebSocket ingress channel (`[channels_config.websocket]`).
+///
+/// Clients authenticate with a gateway pairing token when
+/// `gateway.require_pairing` is enabled.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WebSocketConfig {
+    /// Bind address. Default: `127.0.0.1`.
+    #[serde(default = "default_websocket_host")]
+    pub host: String,
+    /// Listen port. Default: `3001`.
+    #[serde(default = "default_websocket_port")]
+    pub port: u16,
+    /// Browser origins allowed to connect (`[]` = any origin).
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    /// Maximum concurrent sessions. Default: `16`.
+    #[serde(default = "default_websocket_max_connections")]
+    pub max_connections: usize,
+}
+
+fn default_websocket_host() -> String {
+    "127.0.0.1".into()
+}
+
+fn default_websocket_port() -> u16 {
+    3001
+}
+
+fn default_websocket_max_connections() -> usize {
+    16
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self {
+            host: default_websocket_host(),
+            port: default_websocket_port(),
+            allowed_origins: Vec::new(),
+            max_connections: default_websocket_max_connections(),
+        }
+    }
+}
+
 impl WhatsAppConfig {
     /// Detect which backend to use based on config fields.
     /// Returns "cloud" if phone_number_id is set, "web" if session_path is set.
@@ -2944,11 +3639,13 @@ impl Default for Config {
             autonomy: AutonomyConfig::default(),
             runtime: RuntimeConfig::default(),
             reliability: ReliabilityConfig::default(),
+            cassette: CassetteConfig::default(),
             scheduler: SchedulerConfig::default(),
             agent: AgentConfig::default(),
             skills: SkillsConfig::default(),
             model_routes: Vec::new(),
             embedding_routes: Vec::new(),
+            security: SecurityConfig::default(),
             heartbeat: HeartbeatConfig::default(),
             cron: CronConfig::default(),
             channels_config: ChannelsConfig::default(),
@@ -2969,6 +3666,8 @@ impl Default for Config {
             deploy: DeployConfig::default(),
             agents: HashMap::new(),
             hardware: HardwareConfig::default(),
+            profiles: Vec::new(),
+            profile: None,
             query_classification: QueryClassificationConfig::default(),
         }
     }
@@ -3266,12 +3965,24 @@ fn has_ollama_cloud_credential(config_api_key: Option<&str>) -> bool {
 
 impl Config {
     pub async fn load_or_init() -> Result<Self> {
+        let (mut config, _) = Self::load_or_init_layered().await?;
+        config.apply_env_overrides();
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load (or initialize) config.toml with its includes, selected
+    /// `[profile.<name>]` and `${...}` references resolved, but before
+    /// environment overrides and validation. Also returns the layers the
+    /// config was assembled from; `None` when a default config was created.
+    pub async fn load_or_init_layered() -> Result<(Self, Option<super::layers::ConfigLayers>)> {
         let (default_zeroclaw_dir, default_workspace_dir) = default_config_and_workspace_dirs()?;
 
         let (zeroclaw_dir, workspace_dir, resolution_source) =
             resolve_runtime_config_dirs(&default_zeroclaw_dir, &default_workspace_dir).await?;
 
         let config_path = zeroclaw_dir.join("config.toml");
+        let profile = super::layers::selected_profile();
 
         fs::create_dir_all(&zeroclaw_dir)
             .await
@@ -3298,20 +4009,25 @@ impl Config {
                 }
             }
 
-            let contents = fs::read_to_string(&config_path)
-                .await
-                .context("Failed to read config file")?;
+            let migration = super::migrate::migrate_file(
+                &config_path,
+                super::migrate::MigrationWrite::IfChanged,
+            )?;
+            if let Some(backup) = &migration.backup {
+                tracing::warn!(
+                    from = migration.upgrade.from,
+                    to = super::migrate::CURRENT_CONFIG_VERSION,
+                    backup = %backup.display(),
+                    "Migrated config file: {}",
+                    migration.upgrade.applied.join("; ")
+                );
+            }
+
+            let layers = super::layers::load(&config_path, profile.as_deref())?;
 
             // Track ignored/unknown config keys to warn users about silent misconfigurations
             // (e.g., using [providers.ollama] which doesn't exist instead of top-level api_url)
-            let mut ignored_paths: Vec<String> = Vec::new();
-            let mut config: Config = serde_ignored::deserialize(
-                toml::de::Deserializer::parse(&contents).context("Failed to parse config file")?,
-                |path| {
-                    ignored_paths.push(path.to_string());
-                },
-            )
-            .context("Failed to deserialize config file")?;
+            let (mut config, ignored_paths) = Self::from_table(layers.table.clone())?;
 
             // Warn about each unknown config key
             for path in ignored_paths {
@@ -3323,7 +4039,7 @@ impl Config {
             // Set computed paths that are skipped during serialization
             config.config_path = config_path.clone();
             config.workspace_dir = workspace_dir;
-            let store = crate::security::SecretStore::new(&zeroclaw_dir, config.secrets.encrypt);
+            let store = crate::security::SecretStore::from_config(&zeroclaw_dir, &config.secrets);
             decrypt_optional_secret(&store, &mut config.api_key, "config.api_key")?;
             decrypt_optional_secret(
                 &store,
@@ -3352,17 +4068,32 @@ impl Config {
             for agent in config.agents.values_mut() {
                 decrypt_optional_secret(&store, &mut agent.api_key, "config.agents.*.api_key")?;
             }
-            config.apply_env_overrides();
-            config.validate()?;
+            for board in &mut config.peripherals.boards {
+                decrypt_optional_secret(
+                    &store,
+                    &mut board.shared_secret,
+                    "config.peripherals.boards.*.shared_secret",
+                )?;
+            }
+            for profile in &mut config.profiles {
+                decrypt_optional_secret(&store, &mut profile.api_key, "config.profiles.*.api_key")?;
+            }
             tracing::info!(
                 path = %config.config_path.display(),
                 workspace = %config.workspace_dir.display(),
                 source = resolution_source.as_str(),
+                profile = profile.as_deref().unwrap_or("none"),
                 initialized = false,
                 "Config loaded"
             );
-            Ok(config)
+            Ok((config, Some(layers)))
         } else {
+            if let Some(profile) = &profile {
+                anyhow::bail!(
+                    "Config profile `{profile}` requested but {} does not exist yet",
+                    config_path.display()
+                );
+            }
             let mut config = Config::default();
             config.config_path = config_path.clone();
             config.workspace_dir = workspace_dir;
@@ -3375,8 +4106,6 @@ impl Config {
                 let _ = fs::set_permissions(&config_path, Permissions::from_mode(0o600)).await;
             }
 
-            config.apply_env_overrides();
-            config.validate()?;
             tracing::info!(
                 path = %config.config_path.display(),
                 workspace = %config.workspace_dir.display(),
@@ -3384,10 +4113,35 @@ impl Config {
                 initialized = true,
                 "Config loaded"
             );
-            Ok(config)
+            Ok((config, None))
         }
     }
 
+    /// Deserialize a merged config table, collecting keys no field claimed.
+    fn from_table(table: toml::Table) -> Result<(Self, Vec<String>)> {
+        let mut ignored_paths: Vec<String> = Vec::new();
+        let config: Config = serde_ignored::deserialize(toml::Value::Table(table), |path| {
+            ignored_paths.push(path.to_string());
+        })
+        .context("Failed to deserialize config file")?;
+        Ok((config, ignored_paths))
+    }
+
+    /// Load an arbitrary config file with its includes, the selected
+    /// `[profile.<name>]` and `${...}` references resolved, without touching
+    /// the active config. Secrets stay as written and environment overrides
+    /// are not applied. Also returns the unknown keys that were ignored.
+    pub fn load_file(path: &Path, profile: Option<&str>) -> Result<(Self, Vec<String>)> {
+        let layers = super::layers::load(path, profile)?;
+        let (mut config, ignored_paths) = Self::from_table(layers.table)?;
+        config.config_path = path.to_path_buf();
+        config.workspace_dir = path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join("workspace");
+        Ok((config, ignored_paths))
+    }
+
     /// Validate configuration values that would cause runtime failures.
     ///
     /// Called after TOML deserialization and env-override application to catch
@@ -3460,12 +4214,102 @@ impl Config {
             }
         }
 
+        // Agent profiles
+        let mut profile_names = HashSet::new();
+        let mut profile_workspaces = HashSet::from([self.workspace_dir.clone()]);
+        for (i, profile) in self.profiles.iter().enumerate() {
+            if !is_valid_profile_name(&profile.name) {
+                anyhow::bail!(
+                    "profiles[{i}].name must be non-empty and contain only letters, digits, '-' or '_'"
+                );
+            }
+            if !profile_names.insert(profile.name.as_str()) {
+                anyhow::bail!("profiles[{i}].name '{}' is used twice", profile.name);
+            }
+            if !profile_workspaces.insert(self.profile_workspace_dir(profile)) {
+                anyhow::bail!(
+                    "profiles[{i}] ('{}') shares its workspace with another agent",
+                    profile.name
+                );
+            }
+        }
+
         // Proxy (delegate to existing validation)
         self.proxy.validate()?;
 
         Ok(())
     }
 
+    /// Workspace owned by `profile`.
+    pub fn profile_workspace_dir(&self, profile: &AgentProfileConfig) -> PathBuf {
+        let config_dir = self.config_path.parent().unwrap_or_else(|| Path::new("."));
+        match &profile.workspace_dir {
+            Some(dir) if dir.is_absolute() => dir.clone(),
+            Some(dir) => config_dir.join(dir),
+            None => config_dir
+                .join("profiles")
+                .join(&profile.name)
+                .join("workspace"),
+        }
+    }
+
+    /// Effective config of one `[[profiles]]` entry: this config with the
+    /// profile's overrides, workspace and channels applied.
+    pub fn for_profile(&self, profile: &AgentProfileConfig) -> Self {
+        let mut config = self.clone();
+        config.profiles = Vec::new();
+        config.profile = Some(profile.name.clone());
+        config.workspace_dir = self.profile_workspace_dir(profile);
+
+        if let Some(provider) = &profile.default_provider {
+            // The top-level key belongs to the top-level provider.
+            if self.default_provider.as_ref() != Some(provider) {
+                config.api_key = None;
+            }
+            config.default_provider = Some(provider.clone());
+        }
+        if profile.api_key.is_some() {
+            config.api_key.clone_from(&profile.api_key);
+        }
+        if profile.default_model.is_some() {
+            config.default_model.clone_from(&profile.default_model);
+        }
+        if let Some(temperature) = profile.default_temperature {
+            config.default_temperature = temperature;
+        }
+        if let Some(identity) = &profile.identity {
+            config.identity = identity.clone();
+        }
+        if let Some(autonomy) = &profile.autonomy {
+            config.autonomy = autonomy.clone();
+        }
+        if let Some(memory) = &profile.memory {
+            config.memory = memory.clone();
+        }
+        config.channels_config = profile.channels_config.clone();
+        config
+    }
+
+    /// The top-level agent followed by every profile, each as a full config.
+    pub fn agent_configs(&self) -> Vec<Self> {
+        std::iter::once(self.clone())
+            .chain(
+                self.profiles
+                    .iter()
+                    .map(|profile| self.for_profile(profile)),
+            )
+            .collect()
+    }
+
+    /// Health registry name of `component` for this agent (`<profile>/<component>`
+    /// for profiles, unchanged for the top-level agent).
+    pub fn health_component(&self, component: &str) -> String {
+        match &self.profile {
+            Some(profile) => format!("{profile}/{component}"),
+            None => component.to_string(),
+        }
+    }
+
     /// Apply environment variable overrides to config
     pub fn apply_env_overrides(&mut self) {
         // API Key: ZEROCLAW_API_KEY or API_KEY (generic)
@@ -3604,6 +4448,36 @@ impl Config {
             }
         }
 
+        // Provider cassette: ZEROCLAW_CASSETTE_MODE / ZEROCLAW_CASSETTE / ZEROCLAW_CASSETTE_STRICT
+        if let Ok(mode) = std::env::var("ZEROCLAW_CASSETTE_MODE") {
+            match mode.trim().to_ascii_lowercase().as_str() {
+                "off" | "" => self.cassette.mode = CassetteMode::Off,
+                "record" => self.cassette.mode = CassetteMode::Record,
+                "replay" => self.cassette.mode = CassetteMode::Replay,
+                other => tracing::warn!("Ignoring ZEROCLAW_CASSETTE_MODE: unknown mode '{other}'"),
+            }
+        }
+        if let Ok(path) = std::env::var("ZEROCLAW_CASSETTE") {
+            if !path.trim().is_empty() {
+                self.cassette.path = Some(PathBuf::from(path.trim()));
+            }
+        }
+        if let Ok(flag) = std::env::var("ZEROCLAW_CASSETTE_STRICT") {
+            match flag.trim().to_ascii_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => self.cassette.strict = true,
+                "0" | "false" | "no" | "off" => self.cassette.strict = false,
+                _ => {}
+            }
+        }
+
+        // Reasoning level: ZEROCLAW_REASONING (off/low/medium/high or a token budget)
+        if let Ok(level) = std::env::var("ZEROCLAW_REASONING") {
+            match level.parse::<ReasoningLevel>() {
+                Ok(level) => self.runtime.reasoning = Some(level),
+                Err(e) => tracing::warn!("Ignoring ZEROCLAW_REASONING: {e}"),
+            }
+        }
+
         // Web search enabled: ZEROCLAW_WEB_SEARCH_ENABLED or WEB_SEARCH_ENABLED
         if let Ok(enabled) = std::env::var("ZEROCLAW_WEB_SEARCH_ENABLED")
             .or_else(|_| std::env::var("WEB_SEARCH_ENABLED"))
@@ -3748,13 +4622,28 @@ impl Config {
     }
 
     pub async fn save(&self) -> Result<()> {
+        // A layered file would be flattened into its resolved values, leaking
+        // interpolated secrets and dropping includes and profiles.
+        if let Ok(existing) = fs::read_to_string(&self.config_path).await {
+            if existing
+                .parse::<toml::Table>()
+                .is_ok_and(|table| super::layers::uses_layering(&table))
+            {
+                anyhow::bail!(
+                    "{} uses include, [profile.*] or ${{...}} layering; refusing to overwrite it \
+                     with resolved values. Edit the file directly instead.",
+                    self.config_path.display()
+                );
+            }
+        }
+
         // Encrypt secrets before serialization
         let mut config_to_save = self.clone();
         let zeroclaw_dir = self
             .config_path
             .parent()
             .context("Config path must have a parent directory")?;
-        let store = crate::security::SecretStore::new(zeroclaw_dir, self.secrets.encrypt);
+        let store = crate::security::SecretStore::from_config(zeroclaw_dir, &self.secrets);
 
         encrypt_optional_secret(&store, &mut config_to_save.api_key, "config.api_key")?;
         encrypt_optional_secret(
@@ -3785,8 +4674,21 @@ impl Config {
             encrypt_optional_secret(&store, &mut agent.api_key, "config.agents.*.api_key")?;
         }
 
-        let toml_str =
-            toml::to_string_pretty(&config_to_save).context("Failed to serialize config")?;
+        for board in &mut config_to_save.peripherals.boards {
+            encrypt_optional_secret(
+                &store,
+                &mut board.shared_secret,
+                "config.peripherals.boards.*.shared_secret",
+            )?;
+        }
+
+        for profile in &mut config_to_save.profiles {
+            encrypt_optional_secret(&store, &mut profile.api_key, "config.profiles.*.api_key")?;
+        }
+
+        let toml_str = super::migrate::stamp_version(
+            &toml::to_string_pretty(&config_to_save).context("Failed to serialize config")?,
+        );
 
         let parent_dir = self
             .config_path
@@ -4084,10 +4986,12 @@ default_temperature = 0.7
                 ..RuntimeConfig::default()
             },
             reliability: ReliabilityConfig::default(),
+            cassette: CassetteConfig::default(),
             scheduler: SchedulerConfig::default(),
             skills: SkillsConfig::default(),
             model_routes: Vec::new(),
             embedding_routes: Vec::new(),
+            security: SecurityConfig::default(),
             query_classification: QueryClassificationConfig::default(),
             heartbeat: HeartbeatConfig {
                 enabled: true,
@@ -4119,6 +5023,9 @@ default_temperature = 0.7
                 lark: None,
                 dingtalk: None,
                 qq: None,
+                websocket: None,
+                xmpp: None,
+                zulip: None,
                 message_timeout_secs: 300,
             },
             memory: MemoryConfig::default(),
@@ -4139,6 +5046,8 @@ default_temperature = 0.7
             deploy: DeployConfig::default(),
             agents: HashMap::new(),
             hardware: HardwareConfig::default(),
+            profiles: Vec::new(),
+            profile: None,
         };
 
         let toml_str = toml::to_string_pretty(&config).unwrap();
@@ -4283,10 +5192,12 @@ tool_dispatcher = "xml"
             autonomy: AutonomyConfig::default(),
             runtime: RuntimeConfig::default(),
             reliability: ReliabilityConfig::default(),
+            cassette: CassetteConfig::default(),
             scheduler: SchedulerConfig::default(),
             skills: SkillsConfig::default(),
             model_routes: Vec::new(),
             embedding_routes: Vec::new(),
+            security: SecurityConfig::default(),
             query_classification: QueryClassificationConfig::default(),
             heartbeat: HeartbeatConfig::default(),
             cron: CronConfig::default(),
@@ -4309,6 +5220,8 @@ tool_dispatcher = "xml"
             deploy: DeployConfig::default(),
             agents: HashMap::new(),
             hardware: HardwareConfig::default(),
+            profiles: Vec::new(),
+            profile: None,
         };
 
         config.save().await.unwrap();
@@ -4345,6 +5258,16 @@ tool_dispatcher = "xml"
         config.browser.computer_use.api_key = Some("browser-credential".into());
         config.web_search.brave_api_key = Some("brave-credential".into());
         config.storage.provider.config.db_url = Some("postgres://user:pw@host/db".into());
+        config.peripherals.boards.push(PeripheralBoardConfig {
+            board: "esp32".into(),
+            transport: "tcp".into(),
+            shared_secret: Some("board-credential".into()),
+            ..PeripheralBoardConfig::default()
+        });
+        config.profiles.push(AgentProfileConfig {
+            api_key: Some("profile-credential".into()),
+            ..test_profile("support")
+        });
 
         config.agents.insert(
             "worker".into(),
@@ -4358,6 +5281,7 @@ tool_dispatcher = "xml"
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                reasoning: None,
             },
         );
 
@@ -4412,9 +5336,94 @@ tool_dispatcher = "xml"
             "postgres://user:pw@host/db"
         );
 
+        let board_secret = stored.peripherals.boards[0]
+            .shared_secret
+            .as_deref()
+            .unwrap();
+        assert!(crate::security::SecretStore::is_encrypted(board_secret));
+        assert_eq!(store.decrypt(board_secret).unwrap(), "board-credential");
+
+        let profile_key = stored.profiles[0].api_key.as_deref().unwrap();
+        assert!(crate::security::SecretStore::is_encrypted(profile_key));
+        assert_eq!(store.decrypt(profile_key).unwrap(), "profile-credential");
+
         let _ = fs::remove_dir_all(&dir).await;
     }
 
+    fn test_profile(name: &str) -> AgentProfileConfig {
+        toml::from_str(&format!("name = \"{name}\"")).unwrap()
+    }
+
+    #[test]
+    async fn profile_inherits_unset_keys_but_not_channels() {
+        let mut config = Config {
+            config_path: PathBuf::from("/etc/zeroclaw/config.toml"),
+            workspace_dir: PathBuf::from("/etc/zeroclaw/workspace"),
+            api_key: Some("root-key".into()),
+            default_provider: Some("openrouter".into()),
+            default_model: Some("root-model".into()),
+            ..Config::default()
+        };
+        config.channels_config.telegram = Some(TelegramConfig {
+            bot_token: "root-bot".into(),
+            allowed_users: Vec::new(),
+            stream_mode: StreamMode::default(),
+            draft_update_interval_ms: 1000,
+            interrupt_on_new_message: false,
+            mention_only: false,
+        });
+
+        let mut ops = test_profile("ops");
+        ops.default_model = Some("ops-model".into());
+        ops.autonomy = Some(AutonomyConfig {
+            max_actions_per_hour: 5,
+            ..AutonomyConfig::default()
+        });
+        let ops = config.for_profile(&ops);
+        assert_eq!(ops.profile.as_deref(), Some("ops"));
+        assert_eq!(
+            ops.workspace_dir,
+            PathBuf::from("/etc/zeroclaw/profiles/ops/workspace")
+        );
+        assert_eq!(ops.api_key.as_deref(), Some("root-key"));
+        assert_eq!(ops.default_model.as_deref(), Some("ops-model"));
+        assert_eq!(ops.autonomy.max_actions_per_hour, 5);
+        assert!(ops.channels_config.telegram.is_none());
+        assert!(!ops.channels_config.cli);
+        assert_eq!(ops.health_component("channels"), "ops/channels");
+        assert_eq!(config.health_component("channels"), "channels");
+
+        // Another provider must not receive the top-level key.
+        let mut local = test_profile("local");
+        local.default_provider = Some("ollama".into());
+        local.workspace_dir = Some(PathBuf::from("/srv/local"));
+        let local = config.for_profile(&local);
+        assert_eq!(local.api_key, None);
+        assert_eq!(local.workspace_dir, PathBuf::from("/srv/local"));
+    }
+
+    #[test]
+    async fn validate_rejects_duplicate_or_invalid_profiles() {
+        let mut config = Config {
+            config_path: PathBuf::from("/etc/zeroclaw/config.toml"),
+            workspace_dir: PathBuf::from("/etc/zeroclaw/workspace"),
+            ..Config::default()
+        };
+        config.profiles = vec![test_profile("support"), test_profile("ops")];
+        assert!(config.validate().is_ok());
+
+        config.profiles.push(test_profile("support"));
+        assert!(config.validate().is_err());
+
+        config.profiles = vec![test_profile("bad/name")];
+        assert!(config.validate().is_err());
+
+        let mut shared = test_profile("shared");
+        shared.workspace_dir = Some(PathBuf::from("workspace"));
+        config.profiles = vec![shared];
+        assert!(config.validate().is_err());
+    }
+
     #[tokio::test]
     async fn config_save_atomic_cleanup() {
         let dir =
@@ -4665,6 +5674,9 @@ allowed_users = ["@ops:matrix.org"]
             lark: None,
             dingtalk: None,
             qq: None,
+            websocket: None,
+            xmpp: None,
+            zulip: None,
             message_timeout_secs: 300,
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
@@ -4875,6 +5887,9 @@ channel_id = "C123"
             lark: None,
             dingtalk: None,
             qq: None,
+            websocket: None,
+            xmpp: None,
+            zulip: None,
             message_timeout_secs: 300,
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
@@ -5086,10 +6101,14 @@ enable = true
 
     #[test]
     async fn secrets_config_serde_roundtrip() {
-        let s = SecretsConfig { encrypt: false };
+        let s = SecretsConfig {
+            encrypt: false,
+            key_source: SecretKeySource::Passphrase,
+        };
         let toml_str = toml::to_string(&s).unwrap();
         let parsed: SecretsConfig = toml::from_str(&toml_str).unwrap();
         assert!(!parsed.encrypt);
+        assert_eq!(parsed.key_source, SecretKeySource::Passphrase);
     }
 
     #[test]
@@ -5657,6 +6676,52 @@ default_model = "legacy-model"
         let _ = fs::remove_dir_all(temp_home).await;
     }
 
+    #[test]
+    async fn load_or_init_applies_layers_and_save_refuses_to_flatten_them() {
+        let _env_guard = env_override_lock().await;
+        let config_dir =
+            std::env::temp_dir().join(format!("zeroclaw_test_layers_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&config_dir).await.unwrap();
+        fs::write(
+            config_dir.join("models.toml"),
+            "default_model = \"base-model\"\n",
+        )
+        .await
+        .unwrap();
+        fs::write(
+            config_dir.join("config.toml"),
+            r#"include = ["models.toml"]
+default_temperature = 0.7
+api_key = "${ZEROCLAW_TEST_LAYERED_KEY}"
+
+[profile.prod]
+default_model = "prod-model"
+"#,
+        )
+        .await
+        .unwrap();
+
+        std::env::set_var("ZEROCLAW_CONFIG_DIR", &config_dir);
+        std::env::set_var("ZEROCLAW_TEST_LAYERED_KEY", "sk-layered");
+        std::env::set_var(super::super::layers::PROFILE_ENV, "prod");
+
+        let config = Config::load_or_init().await.unwrap();
+        assert_eq!(config.default_model.as_deref(), Some("prod-model"));
+        assert_eq!(config.api_key.as_deref(), Some("sk-layered"));
+
+        let err = config.save().await.unwrap_err().to_string();
+        assert!(err.contains("refusing to overwrite"), "{err}");
+        let raw = fs::read_to_string(config_dir.join("config.toml"))
+            .await
+            .unwrap();
+        assert!(!raw.contains("sk-layered"));
+
+        std::env::remove_var(super::super::layers::PROFILE_ENV);
+        std::env::remove_var("ZEROCLAW_TEST_LAYERED_KEY");
+        std::env::remove_var("ZEROCLAW_CONFIG_DIR");
+        let _ = fs::remove_dir_all(config_dir).await;
+    }
+
     #[test]
     async fn load_or_init_uses_persisted_active_workspace_marker() {
         let _env_guard = env_override_lock().await;
@@ -6137,6 +7202,9 @@ default_model = "legacy-model"
                 transport: "serial".into(),
                 path: Some("/dev/ttyACM0".into()),
                 baud: 115_200,
+                allowed_hosts: Vec::new(),
+                shared_secret: None,
+                simulation: None,
             }],
             datasheet_dir: None,
         };
@@ -1,2 +1,6 @@
+pub mod tracker;
+pub mod types;
+
 pub use tracker::CostTracker;
+#[allow(unused_imports)]
 pub use types::{BudgetCheck, CostRecord, CostSummary, ModelStats, TokenUsage, UsagePeriod};
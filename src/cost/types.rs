@@ -1,4 +1,7 @@
+use crate::config::schema::{CostConfig, ModelPricing};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::LazyLock;
 
 /// Token usage information from a single API call.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,6 +61,34 @@ impl TokenUsage {
     pub fn cost(&self) -> f64 {
         self.cost_usd
     }
+
+    /// Price a call against the built-in pricing table.
+    ///
+    /// `model` may be given with or without its vendor prefix
+    /// (`"gpt-4o"` matches `"openai/gpt-4o"`). Returns `None` for unknown models.
+    pub fn from_default_pricing(
+        model: &str,
+        input_tokens: u64,
+        output_tokens: u64,
+    ) -> Option<Self> {
+        static PRICES: LazyLock<HashMap<String, ModelPricing>> =
+            LazyLock::new(|| CostConfig::default().prices);
+
+        let suffix = format!("/{model}");
+        let pricing = PRICES.get(model).or_else(|| {
+            PRICES
+                .iter()
+                .find(|(key, _)| key.ends_with(&suffix))
+                .map(|(_, pricing)| pricing)
+        })?;
+        Some(Self::new(
+            model,
+            input_tokens,
+            output_tokens,
+            pricing.input,
+            pricing.output,
+        ))
+    }
 }
 
 /// Time period for cost aggregation.
@@ -181,6 +212,15 @@ mod tests {
         assert_eq!(usage.total_tokens, 2000);
     }
 
+    #[test]
+    fn default_pricing_matches_with_or_without_vendor_prefix() {
+        let prefixed = TokenUsage::from_default_pricing("openai/gpt-4o", 1000, 1000).unwrap();
+        let bare = TokenUsage::from_default_pricing("gpt-4o", 1000, 1000).unwrap();
+        assert!(prefixed.cost_usd > 0.0);
+        assert!((prefixed.cost_usd - bare.cost_usd).abs() < f64::EPSILON);
+        assert!(TokenUsage::from_default_pricing("no-such-model", 10, 10).is_none());
+    }
+
     #[test]
     fn cost_record_creation() {
         let usage = TokenUsage::new("test/model", 100, 50, 1.0, 2.0);
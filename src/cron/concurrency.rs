@@ -0,0 +1,146 @@
+use crate::cron::ConcurrencyPolicy;
+use futures_util::future::AbortHandle;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+struct Slot<T> {
+    ticket: u64,
+    abort: AbortHandle,
+    queued: Option<T>,
+}
+
+/// Runs currently in flight, keyed by job id, used to enforce each job's
+/// `ConcurrencyPolicy`. `T` is whatever the caller needs to start a queued run.
+pub(crate) struct RunningJobs<T> {
+    next_ticket: AtomicU64,
+    slots: Mutex<HashMap<String, Slot<T>>>,
+}
+
+impl<T> RunningJobs<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            next_ticket: AtomicU64::new(1),
+            slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Try to start a run of `job_id`. Returns the run's ticket and payload
+    /// when it should start now; `None` when it was dropped or queued behind
+    /// the current run. Under `Replace` the current run is aborted through
+    /// its `AbortHandle` and the new one takes over the slot.
+    pub(crate) fn admit(
+        &self,
+        job_id: &str,
+        policy: ConcurrencyPolicy,
+        abort: AbortHandle,
+        run: T,
+    ) -> Option<(u64, T)> {
+        let mut slots = self.slots.lock();
+        if let Some(slot) = slots.get_mut(job_id) {
+            match policy {
+                ConcurrencyPolicy::NoOverlap => {
+                    tracing::info!("Cron job '{job_id}' is still running; skipping overlap");
+                    return None;
+                }
+                ConcurrencyPolicy::Queue => {
+                    if slot.queued.replace(run).is_some() {
+                        tracing::info!("Cron job '{job_id}' already has a queued run; coalescing");
+                    }
+                    return None;
+                }
+                ConcurrencyPolicy::Replace => {
+                    tracing::info!("Cron job '{job_id}' is still running; replacing it");
+                    slot.abort.abort();
+                }
+            }
+        }
+
+        let ticket = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+        slots.insert(
+            job_id.to_string(),
+            Slot {
+                ticket,
+                abort,
+                queued: None,
+            },
+        );
+        Some((ticket, run))
+    }
+
+    /// Called when the run holding `ticket` completes. Returns the queued run
+    /// to execute next (keeping the slot), or releases the slot.
+    pub(crate) fn finish(&self, job_id: &str, ticket: u64) -> Option<T> {
+        let mut slots = self.slots.lock();
+        let slot = slots.get_mut(job_id).filter(|slot| slot.ticket == ticket)?;
+        let queued = slot.queued.take();
+        if queued.is_none() {
+            slots.remove(job_id);
+        }
+        queued
+    }
+
+    #[cfg(test)]
+    fn is_running(&self, job_id: &str) -> bool {
+        self.slots.lock().contains_key(job_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handle() -> AbortHandle {
+        AbortHandle::new_pair().0
+    }
+
+    #[test]
+    fn no_overlap_drops_while_running() {
+        let running = RunningJobs::new();
+        let (ticket, _) = running
+            .admit("job", ConcurrencyPolicy::NoOverlap, handle(), 1)
+            .unwrap();
+        assert!(running
+            .admit("job", ConcurrencyPolicy::NoOverlap, handle(), 2)
+            .is_none());
+        assert_eq!(running.finish("job", ticket), None);
+        assert!(!running.is_running("job"));
+    }
+
+    #[test]
+    fn queue_coalesces_to_latest_pending_run() {
+        let running = RunningJobs::new();
+        let (ticket, _) = running
+            .admit("job", ConcurrencyPolicy::Queue, handle(), 1)
+            .unwrap();
+        assert!(running
+            .admit("job", ConcurrencyPolicy::Queue, handle(), 2)
+            .is_none());
+        assert!(running
+            .admit("job", ConcurrencyPolicy::Queue, handle(), 3)
+            .is_none());
+
+        assert_eq!(running.finish("job", ticket), Some(3));
+        assert!(running.is_running("job"));
+        assert_eq!(running.finish("job", ticket), None);
+        assert!(!running.is_running("job"));
+    }
+
+    #[test]
+    fn replace_aborts_current_run_and_ignores_its_finish() {
+        let running = RunningJobs::new();
+        let first_abort = handle();
+        let (first, _) = running
+            .admit("job", ConcurrencyPolicy::Replace, first_abort.clone(), 1)
+            .unwrap();
+        let (second, _) = running
+            .admit("job", ConcurrencyPolicy::Replace, handle(), 2)
+            .unwrap();
+        assert!(first_abort.is_aborted());
+
+        assert_eq!(running.finish("job", first), None);
+        assert!(running.is_running("job"), "stale finish must not free slot");
+        assert_eq!(running.finish("job", second), None);
+        assert!(!running.is_running("job"));
+    }
+}
@@ -1,23 +1,34 @@
 use crate::config::Config;
 use crate::security::SecurityPolicy;
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 
+mod concurrency;
 mod schedule;
 mod store;
 mod types;
+mod watch;
+mod workflow;
 
 pub mod scheduler;
 
 #[allow(unused_imports)]
 pub use schedule::{
-    next_run_for_schedule, normalize_expression, schedule_cron_expression, validate_schedule,
+    issue_webhook_token, next_run_for_schedule, normalize_expression, plan_due_run,
+    schedule_cron_expression, validate_schedule, verify_webhook_token, MAX_CATCH_UP_RUNS,
 };
 #[allow(unused_imports)]
 pub use store::{
-    add_agent_job, add_job, add_shell_job, due_jobs, get_job, list_jobs, list_runs,
-    record_last_run, record_run, remove_job, reschedule_after_run, update_job,
+    add_agent_job, add_job, add_shell_job, add_workflow_job, dependent_jobs, due_jobs, get_job,
+    list_jobs, list_runs, record_last_run, record_run, record_step_run, remove_job,
+    reschedule_after_run, set_next_run, update_job,
 };
-pub use types::{CronJob, CronJobPatch, CronRun, DeliveryConfig, JobType, Schedule, SessionTarget};
+#[allow(unused_imports)]
+pub use types::{
+    ConcurrencyPolicy, CronJob, CronJobPatch, CronRun, DeliveryConfig, JobPolicy, JobType,
+    MisfirePolicy, RunCondition, Schedule, SessionTarget, WorkflowDefinition, WorkflowStep,
+};
+#[allow(unused_imports)]
+pub use workflow::{validate_workflow, workflow_order};
 
 #[allow(clippy::needless_pass_by_value)]
 pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<()> {
@@ -37,13 +48,18 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
                     .last_run
                     .map_or_else(|| "never".into(), |d| d.to_rfc3339());
                 let last_status = job.last_status.unwrap_or_else(|| "n/a".into());
+                let next = match &job.schedule {
+                    Schedule::After { job_id, on } => format!("after {job_id} ({})", on.as_str()),
+                    Schedule::Webhook { .. } => format!("on POST /cron/{}", job.id),
+                    Schedule::Watch { glob } => format!("on change {glob}"),
+                    Schedule::At { .. } if job.next_run == schedule::event_driven_next_run() => {
+                        "done".to_string()
+                    }
+                    _ => job.next_run.to_rfc3339(),
+                };
                 println!(
                     "- {} | {:?} | next={} | last={} ({})",
-                    job.id,
-                    job.schedule,
-                    job.next_run.to_rfc3339(),
-                    last_run,
-                    last_status,
+                    job.id, job.schedule, next, last_run, last_status,
                 );
                 if !job.command.is_empty() {
                     println!("    cmd: {}", job.command);
@@ -51,6 +67,20 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
                 if let Some(prompt) = &job.prompt {
                     println!("    prompt: {prompt}");
                 }
+                println!("    policy: {}", job.policy.describe());
+                for step in &job.steps {
+                    let deps = if step.depends_on.is_empty() {
+                        String::new()
+                    } else {
+                        format!(
+                            " after [{}] ({})",
+                            step.depends_on.join(", "),
+                            step.run_if.as_str()
+                        )
+                    };
+                    let action = step.prompt.as_deref().unwrap_or(step.command.as_str());
+                    println!("    step {}{deps}: {action}", step.id);
+                }
             }
             Ok(())
         }
@@ -90,6 +120,66 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
             println!("  Cmd      : {}", job.command);
             Ok(())
         }
+        crate::CronCommands::AddAfter {
+            upstream_id,
+            on,
+            command,
+        } => {
+            let on = RunCondition::try_from(on.as_str()).map_err(|e| anyhow::anyhow!(e))?;
+            let schedule = Schedule::After {
+                job_id: upstream_id,
+                on,
+            };
+            let job = add_shell_job(config, None, schedule, &command)?;
+            println!("✅ Added dependent cron job {}", job.id);
+            if let Schedule::After { job_id, on } = &job.schedule {
+                println!("  After: {job_id} ({})", on.as_str());
+            }
+            println!("  Cmd  : {}", job.command);
+            Ok(())
+        }
+        crate::CronCommands::AddWebhook { command, name } => {
+            let mut schedule = Schedule::Webhook {
+                token_hash: String::new(),
+            };
+            let token =
+                issue_webhook_token(&mut schedule).context("Failed to issue webhook token")?;
+            let job = add_shell_job(config, name, schedule, &command)?;
+            println!("✅ Added webhook cron job {}", job.id);
+            println!("  Route: POST /cron/{}", job.id);
+            println!("  Token: {token}");
+            println!("  Cmd  : {}", job.command);
+            println!("  (the token is shown only once; store it now)");
+            Ok(())
+        }
+        crate::CronCommands::AddWatch { glob, command } => {
+            let schedule = Schedule::Watch { glob };
+            let job = add_shell_job(config, None, schedule, &command)?;
+            println!("✅ Added watch cron job {}", job.id);
+            if let Schedule::Watch { glob } = &job.schedule {
+                println!("  Watch: {glob}");
+            }
+            println!("  Cmd  : {}", job.command);
+            Ok(())
+        }
+        crate::CronCommands::AddWorkflow {
+            expression,
+            file,
+            tz,
+            name,
+        } => {
+            let definition = load_workflow_definition(&file)?;
+            let schedule = Schedule::Cron {
+                expr: expression,
+                tz,
+            };
+            let job = add_workflow_job(config, name, schedule, definition.steps, None)?;
+            println!("✅ Added workflow cron job {}", job.id);
+            println!("  Expr : {}", job.expression);
+            println!("  Next : {}", job.next_run.to_rfc3339());
+            println!("  Steps: {}", job.steps.len());
+            Ok(())
+        }
         crate::CronCommands::Once { delay, command } => {
             let job = add_once(config, &delay, &command)?;
             println!("✅ Added one-shot cron job {}", job.id);
@@ -149,6 +239,48 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
             println!("  Cmd : {}", job.command);
             Ok(())
         }
+        crate::CronCommands::Policy {
+            id,
+            misfire,
+            concurrency,
+            timeout_secs,
+            max_retries,
+        } => {
+            if misfire.is_none()
+                && concurrency.is_none()
+                && timeout_secs.is_none()
+                && max_retries.is_none()
+            {
+                bail!(
+                    "At least one of --misfire, --concurrency, --timeout-secs, or --max-retries must be provided"
+                );
+            }
+
+            let mut policy = get_job(config, &id)?.policy;
+            if let Some(misfire) = misfire {
+                policy.misfire =
+                    MisfirePolicy::try_from(misfire.as_str()).map_err(|e| anyhow::anyhow!(e))?;
+            }
+            if let Some(concurrency) = concurrency {
+                policy.concurrency = ConcurrencyPolicy::try_from(concurrency.as_str())
+                    .map_err(|e| anyhow::anyhow!(e))?;
+            }
+            if timeout_secs.is_some() {
+                policy.timeout_secs = timeout_secs;
+            }
+            if max_retries.is_some() {
+                policy.max_retries = max_retries;
+            }
+
+            let patch = CronJobPatch {
+                policy: Some(policy),
+                ..CronJobPatch::default()
+            };
+            let job = update_job(config, &id, patch)?;
+            println!("\u{2705} Updated cron job {} policy", job.id);
+            println!("  {}", job.policy.describe());
+            Ok(())
+        }
         crate::CronCommands::Remove { id } => remove_job(config, &id),
         crate::CronCommands::Pause { id } => {
             pause_job(config, &id)?;
@@ -200,6 +332,22 @@ pub fn resume_job(config: &Config, id: &str) -> Result<CronJob> {
     )
 }
 
+/// Parse a workflow definition file; `.toml` files are read as TOML,
+/// everything else as JSON.
+fn load_workflow_definition(path: &std::path::Path) -> Result<WorkflowDefinition> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read workflow file: {}", path.display()))?;
+    let is_toml = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+    if is_toml {
+        toml::from_str(&raw).with_context(|| format!("Invalid workflow TOML: {}", path.display()))
+    } else {
+        serde_json::from_str(&raw)
+            .with_context(|| format!("Invalid workflow JSON: {}", path.display()))
+    }
+}
+
 fn parse_delay(input: &str) -> Result<chrono::Duration> {
     let input = input.trim();
     if input.is_empty() {
@@ -413,4 +561,34 @@ mod tests {
         let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
         assert!(security.is_command_allowed("echo safe"));
     }
+
+    #[test]
+    fn policy_merges_flags_with_existing_policy() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let job = make_job(&config, "*/5 * * * *", None, "echo test");
+
+        let policy = |misfire: Option<&str>, concurrency: Option<&str>, timeout: Option<u64>| {
+            handle_command(
+                crate::CronCommands::Policy {
+                    id: job.id.clone(),
+                    misfire: misfire.map(Into::into),
+                    concurrency: concurrency.map(Into::into),
+                    timeout_secs: timeout,
+                    max_retries: None,
+                },
+                &config,
+            )
+        };
+
+        policy(Some("skip"), None, None).unwrap();
+        policy(None, Some("queue"), Some(30)).unwrap();
+        let updated = get_job(&config, &job.id).unwrap();
+        assert_eq!(updated.policy.misfire, MisfirePolicy::Skip);
+        assert_eq!(updated.policy.concurrency, ConcurrencyPolicy::Queue);
+        assert_eq!(updated.policy.timeout_secs, Some(30));
+
+        assert!(policy(None, None, None).is_err());
+        assert!(policy(Some("never"), None, None).is_err());
+    }
 }
@@ -1,9 +1,19 @@
-use crate::cron::Schedule;
+use crate::cron::{MisfirePolicy, Schedule};
+use crate::security::pairing::constant_time_eq;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Duration as ChronoDuration, Utc};
 use cron::Schedule as CronExprSchedule;
 use std::str::FromStr;
 
+/// `next_run` stored for schedules that never fire on the clock (dependency
+/// triggers). Kept as a far-future RFC3339 value so `due_jobs` ordering and
+/// text comparison continue to work without special cases.
+pub(crate) fn event_driven_next_run() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("9999-12-31T23:59:59Z")
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
 pub fn next_run_for_schedule(schedule: &Schedule, from: DateTime<Utc>) -> Result<DateTime<Utc>> {
     match schedule {
         Schedule::Cron { expr, tz } => {
@@ -35,9 +45,50 @@ pub fn next_run_for_schedule(schedule: &Schedule, from: DateTime<Utc>) -> Result
             from.checked_add_signed(delta)
                 .ok_or_else(|| anyhow::anyhow!("every_ms overflowed DateTime"))
         }
+        Schedule::After { .. } | Schedule::Webhook { .. } | Schedule::Watch { .. } => {
+            Ok(event_driven_next_run())
+        }
     }
 }
 
+/// Upper bound on back-to-back catch-up runs under `MisfirePolicy::RunAll`.
+pub const MAX_CATCH_UP_RUNS: u32 = 24;
+
+/// Decide how a due job runs at `now`: how many times to run it and the
+/// `next_run` to claim before it starts. Occurrences later than `grace` past
+/// their due time count as missed and are handled per `policy`. One-shot and
+/// event-driven schedules always run once and are not due again on the clock.
+pub fn plan_due_run(
+    schedule: &Schedule,
+    policy: MisfirePolicy,
+    due_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+    grace: ChronoDuration,
+) -> Result<(u32, DateTime<Utc>)> {
+    if !matches!(schedule, Schedule::Cron { .. } | Schedule::Every { .. }) {
+        return Ok((1, event_driven_next_run()));
+    }
+
+    let next_run = next_run_for_schedule(schedule, now)?;
+    let runs = match policy {
+        MisfirePolicy::RunOnce => 1,
+        MisfirePolicy::Skip => u32::from(now - due_at <= grace),
+        MisfirePolicy::RunAll => {
+            let mut runs = 1;
+            let mut cursor = due_at;
+            while runs < MAX_CATCH_UP_RUNS {
+                cursor = next_run_for_schedule(schedule, cursor)?;
+                if cursor > now {
+                    break;
+                }
+                runs += 1;
+            }
+            runs
+        }
+    };
+    Ok((runs, next_run))
+}
+
 pub fn validate_schedule(schedule: &Schedule, now: DateTime<Utc>) -> Result<()> {
     match schedule {
         Schedule::Cron { expr, .. } => {
@@ -57,7 +108,53 @@ pub fn validate_schedule(schedule: &Schedule, now: DateTime<Utc>) -> Result<()>
             }
             Ok(())
         }
+        Schedule::After { job_id, .. } => {
+            if job_id.trim().is_empty() {
+                anyhow::bail!("Invalid schedule: 'after' requires an upstream job_id");
+            }
+            Ok(())
+        }
+        Schedule::Webhook { token_hash } => {
+            let is_hash =
+                token_hash.len() == 64 && token_hash.chars().all(|c| c.is_ascii_hexdigit());
+            if !is_hash {
+                anyhow::bail!("Invalid schedule: webhook token has not been issued");
+            }
+            Ok(())
+        }
+        Schedule::Watch { glob } => crate::cron::watch::validate_watch_glob(glob),
+    }
+}
+
+/// Issue a fresh token for a webhook schedule that does not have one yet.
+/// Only the SHA-256 hash is kept in the schedule; the returned plaintext is
+/// the caller's one chance to show it to the user.
+pub fn issue_webhook_token(schedule: &mut Schedule) -> Option<String> {
+    let Schedule::Webhook { token_hash } = schedule else {
+        return None;
+    };
+    if !token_hash.trim().is_empty() {
+        return None;
     }
+    let bytes: [u8; 32] = rand::random();
+    let token = format!("zcw_{}", hex::encode(bytes));
+    *token_hash = hash_webhook_token(&token);
+    Some(token)
+}
+
+/// Check a presented token against a webhook schedule in constant time.
+pub fn verify_webhook_token(schedule: &Schedule, token: &str) -> bool {
+    match schedule {
+        Schedule::Webhook { token_hash } if !token_hash.is_empty() && !token.is_empty() => {
+            constant_time_eq(&hash_webhook_token(token), token_hash)
+        }
+        _ => false,
+    }
+}
+
+fn hash_webhook_token(token: &str) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(token.trim().as_bytes()))
 }
 
 pub fn schedule_cron_expression(schedule: &Schedule) -> Option<String> {
@@ -111,4 +208,72 @@ mod tests {
         let next = next_run_for_schedule(&schedule, from).unwrap();
         assert_eq!(next, Utc.with_ymd_and_hms(2026, 2, 16, 17, 0, 0).unwrap());
     }
+
+    #[test]
+    fn after_schedule_never_fires_on_the_clock() {
+        let schedule = Schedule::After {
+            job_id: "upstream".into(),
+            on: crate::cron::RunCondition::Success,
+        };
+        let next = next_run_for_schedule(&schedule, Utc::now()).unwrap();
+        assert_eq!(next, event_driven_next_run());
+        assert!(validate_schedule(&schedule, Utc::now()).is_ok());
+
+        let empty = Schedule::After {
+            job_id: " ".into(),
+            on: crate::cron::RunCondition::Always,
+        };
+        assert!(validate_schedule(&empty, Utc::now()).is_err());
+    }
+
+    #[test]
+    fn webhook_token_is_issued_once_and_verified() {
+        let mut schedule = Schedule::Webhook {
+            token_hash: String::new(),
+        };
+        assert!(validate_schedule(&schedule, Utc::now()).is_err());
+
+        let token = issue_webhook_token(&mut schedule).unwrap();
+        assert!(token.starts_with("zcw_"));
+        assert!(validate_schedule(&schedule, Utc::now()).is_ok());
+        assert!(issue_webhook_token(&mut schedule).is_none());
+
+        assert!(verify_webhook_token(&schedule, &token));
+        assert!(!verify_webhook_token(&schedule, "zcw_wrong"));
+        assert!(!verify_webhook_token(&schedule, ""));
+        assert_eq!(
+            next_run_for_schedule(&schedule, Utc::now()).unwrap(),
+            event_driven_next_run()
+        );
+    }
+
+    #[test]
+    fn plan_due_run_applies_misfire_policy() {
+        let now = Utc::now();
+        let grace = ChronoDuration::seconds(60);
+        let every = Schedule::Every { every_ms: 60_000 };
+        let late = now - ChronoDuration::minutes(10) - ChronoDuration::seconds(30);
+
+        let (runs, next) = plan_due_run(&every, MisfirePolicy::RunOnce, late, now, grace).unwrap();
+        assert_eq!(runs, 1);
+        assert!(next > now);
+
+        let (runs, _) = plan_due_run(&every, MisfirePolicy::RunAll, late, now, grace).unwrap();
+        assert_eq!(runs, 11);
+
+        let (runs, _) = plan_due_run(&every, MisfirePolicy::Skip, late, now, grace).unwrap();
+        assert_eq!(runs, 0);
+        let on_time = now - ChronoDuration::seconds(5);
+        let (runs, _) = plan_due_run(&every, MisfirePolicy::Skip, on_time, now, grace).unwrap();
+        assert_eq!(runs, 1);
+
+        let ancient = now - ChronoDuration::days(30);
+        let (runs, _) = plan_due_run(&every, MisfirePolicy::RunAll, ancient, now, grace).unwrap();
+        assert_eq!(runs, MAX_CATCH_UP_RUNS);
+
+        let at = Schedule::At { at: late };
+        let (runs, next) = plan_due_run(&at, MisfirePolicy::Skip, late, now, grace).unwrap();
+        assert_eq!(runs, 1);
+        assert_eq!(next, event_driven_next_run());
+    }
 }
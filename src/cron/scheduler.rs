@@ -1,23 +1,80 @@
+use crate::agent::loop_::run_traced_turn;
 use crate::channels::{
     Channel, DiscordChannel, MattermostChannel, SendMessage, SlackChannel, TelegramChannel,
 };
 use crate::config::Config;
+use crate::cron::concurrency::RunningJobs;
+use crate::cron::watch::FileWatcher;
 use crate::cron::{
-    due_jobs, next_run_for_schedule, record_last_run, record_run, remove_job, reschedule_after_run,
-    update_job, CronJob, CronJobPatch, DeliveryConfig, JobType, Schedule, SessionTarget,
+    dependent_jobs, due_jobs, list_jobs, next_run_for_schedule, plan_due_run, record_last_run,
+    record_run, record_step_run, remove_job, reschedule_after_run, set_next_run, update_job,
+    workflow_order, CronJob, CronJobPatch, DeliveryConfig, JobType, Schedule, SessionTarget,
+    WorkflowStep,
 };
+use crate::observability::TraceContext;
 use crate::security::SecurityPolicy;
 use anyhow::Result;
-use chrono::{DateTime, Utc};
-use futures_util::{stream, StreamExt};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use futures_util::future::{AbortHandle, Abortable};
+use futures_util::stream::FuturesUnordered;
+use futures_util::StreamExt;
+use std::collections::HashMap;
 use std::process::Stdio;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
 use tokio::process::Command;
+use tokio::sync::Semaphore;
 use tokio::time::{self, Duration};
 
 const MIN_POLL_SECONDS: u64 = 5;
 const SHELL_JOB_TIMEOUT_SECS: u64 = 120;
 const SCHEDULER_COMPONENT: &str = "scheduler";
+/// Upper bound on `After` chains triggered by a single run, guarding against
+/// cycles introduced by direct DB edits.
+const MAX_DEPENDENCY_DEPTH: usize = 16;
+const MAX_UPSTREAM_OUTPUT_CHARS: usize = 16 * 1024;
+
+/// In-flight runs across the scheduler loop, gateway webhooks and watches.
+static RUNNING: LazyLock<RunningJobs<PendingRun>> = LazyLock::new(RunningJobs::new);
+
+/// Outcome of an upstream job or workflow step (or the payload of the event
+/// that fired a job), handed on as prompt context (agent) or environment
+/// variables (shell).
+#[derive(Debug, Clone)]
+struct UpstreamOutput {
+    source: String,
+    success: bool,
+    output: String,
+}
+
+impl UpstreamOutput {
+    fn status(&self) -> &'static str {
+        if self.success {
+            "ok"
+        } else {
+            "error"
+        }
+    }
+}
+
+/// A job ready to run: its trigger input, how many back-to-back runs the
+/// misfire policy asked for and how deep in an `After` chain it sits.
+struct PendingRun {
+    job: CronJob,
+    trigger: Vec<UpstreamOutput>,
+    repeat: u32,
+    depth: usize,
+}
+
+impl PendingRun {
+    fn once(job: CronJob, trigger: Vec<UpstreamOutput>) -> Self {
+        Self {
+            job,
+            trigger,
+            repeat: 1,
+            depth: 0,
+        }
+    }
+}
 
 pub async fn run(config: Config) -> Result<()> {
     let poll_secs = config.reliability.scheduler_poll_secs.max(MIN_POLL_SECONDS);
@@ -28,44 +85,195 @@ pub async fn run(config: Config) -> Result<()> {
         &config.workspace_dir,
     ));
 
-    crate::health::mark_component_ok(SCHEDULER_COMPONENT);
+    let component = config.health_component(SCHEDULER_COMPONENT);
+    crate::health::mark_component_ok(&component);
+    let mut watcher = FileWatcher::new();
+    let permits = run_permits(&config);
 
     loop {
         interval.tick().await;
-        // Keep scheduler liveness fresh even when there are no due jobs.
-        crate::health::mark_component_ok(SCHEDULER_COMPONENT);
-
-        let jobs = match due_jobs(&config, Utc::now()) {
-            Ok(jobs) => jobs,
-            Err(e) => {
-                crate::health::mark_component_error(SCHEDULER_COMPONENT, e.to_string());
-                tracing::warn!("Scheduler query failed: {e}");
-                continue;
-            }
-        };
 
-        process_due_jobs(&config, &security, jobs, SCHEDULER_COMPONENT).await;
+        // Runs happen in the background so a slow job cannot stall the loop,
+        // but share one set of permits across ticks so
+        // `scheduler.max_concurrent` bounds everything in flight.
+        let runs = poll_tick(&config, &component, &mut watcher);
+        if !runs.is_empty() {
+            let config = config.clone();
+            let security = Arc::clone(&security);
+            let component = component.clone();
+            let permits = Arc::clone(&permits);
+            tokio::spawn(async move {
+                process_jobs(&config, &security, runs, &component, &permits).await;
+            });
+        }
+    }
+}
+
+/// One scheduler poll: refresh scheduler health, then claim due occurrences
+/// (before dispatching, so the next tick does not pick the same jobs up again)
+/// and changed watches.
+fn poll_tick(config: &Config, component: &str, watcher: &mut FileWatcher) -> Vec<PendingRun> {
+    // Keep scheduler liveness fresh even when there are no due jobs.
+    crate::health::mark_component_ok(component);
+
+    let jobs = match due_jobs(config, Utc::now()) {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            crate::health::mark_component_error(component, e.to_string());
+            tracing::warn!("Scheduler query failed: {e}");
+            return Vec::new();
+        }
+    };
+
+    let mut runs = claim_due_runs(config, jobs, Utc::now());
+    runs.extend(poll_watch_jobs(config, watcher));
+    runs
+}
+
+/// Permits for concurrently running scheduled jobs (`scheduler.max_concurrent`).
+fn run_permits(config: &Config) -> Arc<Semaphore> {
+    Arc::new(Semaphore::new(config.scheduler.max_concurrent.max(1)))
+}
+
+/// How late a clock-driven occurrence may start before it counts as missed.
+fn misfire_grace(config: &Config) -> ChronoDuration {
+    let poll_secs = config.reliability.scheduler_poll_secs.max(MIN_POLL_SECONDS);
+    ChronoDuration::seconds(i64::try_from(poll_secs.saturating_mul(2).max(60)).unwrap_or(i64::MAX))
+}
+
+/// Advance each due job's `next_run` according to its misfire policy and
+/// return the runs to start now. Skipped occurrences are recorded as such.
+fn claim_due_runs(config: &Config, jobs: Vec<CronJob>, now: DateTime<Utc>) -> Vec<PendingRun> {
+    let grace = misfire_grace(config);
+    let mut runs = Vec::with_capacity(jobs.len());
+
+    for mut job in jobs {
+        let (repeat, next_run) =
+            match plan_due_run(&job.schedule, job.policy.misfire, job.next_run, now, grace) {
+                Ok(plan) => plan,
+                Err(e) => {
+                    tracing::warn!("Failed to plan cron job '{}': {e}", job.id);
+                    continue;
+                }
+            };
+        if let Err(e) = set_next_run(config, &job.id, next_run) {
+            tracing::warn!("Failed to claim cron job '{}': {e}", job.id);
+            continue;
+        }
+        job.next_run = next_run;
+
+        if repeat == 0 {
+            tracing::info!(
+                "Cron job '{}' missed its run; skipped by misfire policy",
+                job.id
+            );
+            let _ = record_run(
+                config,
+                &job.id,
+                now,
+                now,
+                "skipped",
+                Some("missed occurrence skipped by misfire policy"),
+                0,
+            );
+            continue;
+        }
+        runs.push(PendingRun {
+            job,
+            trigger: Vec::new(),
+            repeat,
+            depth: 0,
+        });
+    }
+
+    runs
+}
+
+fn poll_watch_jobs(config: &Config, watcher: &mut FileWatcher) -> Vec<PendingRun> {
+    let jobs = match list_jobs(config) {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            tracing::warn!("Scheduler watch query failed: {e}");
+            return Vec::new();
+        }
+    };
+
+    watcher
+        .poll(&config.workspace_dir, &jobs)
+        .into_iter()
+        .map(|(job, changes)| {
+            let trigger = UpstreamOutput {
+                source: "watch".to_string(),
+                success: true,
+                output: changes.join("\n"),
+            };
+            PendingRun::once(job, vec![trigger])
+        })
+        .collect()
+}
+
+/// Run a job fired by an external event (gateway webhook) with `input` as its
+/// trigger context, persist the result and fire any dependents.
+pub(crate) async fn run_triggered_job(config: &Config, job: &CronJob, source: &str, input: String) {
+    let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+    let trigger = UpstreamOutput {
+        source: source.to_string(),
+        success: true,
+        output: input,
+    };
+    let run = PendingRun::once(job.clone(), vec![trigger]);
+    let component = config.health_component(SCHEDULER_COMPONENT);
+    if let Some((job_id, false)) = dispatch_run(config, &security, run, &component).await {
+        tracing::warn!("Triggered cron job '{job_id}' failed");
     }
 }
 
 pub async fn execute_job_now(config: &Config, job: &CronJob) -> (bool, String) {
     let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
-    execute_job_with_retry(config, &security, job).await
+    execute_job_with_retry(config, &security, job, &[]).await
 }
 
 async fn execute_job_with_retry(
     config: &Config,
     security: &SecurityPolicy,
     job: &CronJob,
+    upstream: &[UpstreamOutput],
+) -> (bool, String) {
+    match job.job_type {
+        // Workflows retry per step so completed steps are not re-run.
+        JobType::Workflow => run_workflow_job(config, security, job, upstream).await,
+        JobType::Shell | JobType::Agent => {
+            execute_single_job_with_retry(config, security, job, upstream).await
+        }
+    }
+}
+
+async fn execute_single_job_with_retry(
+    config: &Config,
+    security: &SecurityPolicy,
+    job: &CronJob,
+    upstream: &[UpstreamOutput],
 ) -> (bool, String) {
     let mut last_output = String::new();
-    let retries = config.reliability.scheduler_retries;
+    let retries = job
+        .policy
+        .max_retries
+        .unwrap_or(config.reliability.scheduler_retries);
     let mut backoff_ms = config.reliability.provider_backoff_ms.max(200);
 
     for attempt in 0..=retries {
         let (success, output) = match job.job_type {
-            JobType::Shell => run_job_command(config, security, job).await,
-            JobType::Agent => run_agent_job(config, security, job).await,
+            JobType::Shell => run_job_command(config, security, job, upstream).await,
+            JobType::Agent => match job.policy.timeout_secs {
+                Some(secs) => time::timeout(
+                    Duration::from_secs(secs),
+                    run_agent_job(config, security, job, upstream),
+                )
+                .await
+                .unwrap_or_else(|_| (false, format!("job timed out after {secs}s"))),
+                None => run_agent_job(config, security, job, upstream).await,
+            },
+            JobType::Workflow => (false, "nested workflows are not supported".to_string()),
         };
         last_output = output;
 
@@ -88,57 +296,311 @@ async fn execute_job_with_retry(
     (false, last_output)
 }
 
-async fn process_due_jobs(
+async fn process_jobs(
     config: &Config,
     security: &Arc<SecurityPolicy>,
-    jobs: Vec<CronJob>,
+    runs: Vec<PendingRun>,
     component: &str,
+    permits: &Arc<Semaphore>,
 ) {
-    // Refresh scheduler health on every successful poll cycle, including idle cycles.
-    crate::health::mark_component_ok(component);
-
-    let max_concurrent = config.scheduler.max_concurrent.max(1);
-    let mut in_flight =
-        stream::iter(
-            jobs.into_iter().map(|job| {
-                let config = config.clone();
-                let security = Arc::clone(security);
-                let component = component.to_owned();
-                async move {
-                    execute_and_persist_job(&config, security.as_ref(), &job, &component).await
-                }
-            }),
-        )
-        .buffer_unordered(max_concurrent);
+    let mut in_flight: FuturesUnordered<_> = runs
+        .into_iter()
+        .map(|run| {
+            let config = config.clone();
+            let security = Arc::clone(security);
+            let component = component.to_owned();
+            let permits = Arc::clone(permits);
+            async move {
+                let _permit = permits.acquire_owned().await.ok()?;
+                dispatch_run(&config, security.as_ref(), run, &component).await
+            }
+        })
+        .collect();
 
-    while let Some((job_id, success)) = in_flight.next().await {
-        if !success {
+    while let Some(outcome) = in_flight.next().await {
+        if let Some((job_id, false)) = outcome {
             tracing::warn!("Scheduler job '{job_id}' failed");
         }
     }
 }
 
+/// Run a job under its concurrency policy, then any run queued behind it.
+/// Returns `None` when the run was dropped, queued or replaced.
+async fn dispatch_run(
+    config: &Config,
+    security: &SecurityPolicy,
+    run: PendingRun,
+    component: &str,
+) -> Option<(String, bool)> {
+    let job_id = run.job.id.clone();
+    let (abort, registration) = AbortHandle::new_pair();
+    let (ticket, run) = RUNNING.admit(&job_id, run.job.policy.concurrency, abort, run)?;
+
+    let work = async {
+        let mut next = Some(run);
+        let mut success = true;
+        while let Some(run) = next {
+            for _ in 0..run.repeat.max(1) {
+                let (_, ok) = Box::pin(execute_and_persist_job(
+                    config,
+                    security,
+                    &run.job,
+                    &run.trigger,
+                    run.depth,
+                    component,
+                ))
+                .await;
+                success &= ok;
+            }
+            next = RUNNING.finish(&job_id, ticket);
+        }
+        success
+    };
+
+    match Abortable::new(work, registration).await {
+        Ok(success) => Some((job_id, success)),
+        Err(_aborted) => {
+            let now = Utc::now();
+            let _ = record_run(
+                config,
+                &job_id,
+                now,
+                now,
+                "cancelled",
+                Some("replaced by a newer run"),
+                0,
+            );
+            None
+        }
+    }
+}
+
 async fn execute_and_persist_job(
     config: &Config,
     security: &SecurityPolicy,
     job: &CronJob,
+    trigger: &[UpstreamOutput],
+    depth: usize,
     component: &str,
 ) -> (String, bool) {
     crate::health::mark_component_ok(component);
     warn_if_high_frequency_agent_job(job);
 
     let started_at = Utc::now();
-    let (success, output) = execute_job_with_retry(config, security, job).await;
+    let (success, output) = execute_job_with_retry(config, security, job, trigger).await;
     let finished_at = Utc::now();
     let success = persist_job_result(config, job, success, &output, started_at, finished_at).await;
+    run_dependent_jobs(config, security, job, success, &output, depth, component).await;
 
     (job.id.clone(), success)
 }
 
+/// Run every job chained (directly or transitively) behind `root` through
+/// `Schedule::After`, passing each upstream's output along.
+pub(crate) async fn trigger_dependents(
+    config: &Config,
+    root: &CronJob,
+    success: bool,
+    output: &str,
+) {
+    let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+    let component = config.health_component(SCHEDULER_COMPONENT);
+    run_dependent_jobs(config, &security, root, success, output, 0, &component).await;
+}
+
+/// Dispatch the direct dependents of `root` whose run condition holds. Each
+/// goes through `dispatch_run`, so its own concurrency policy applies and its
+/// dependents follow in turn.
+async fn run_dependent_jobs(
+    config: &Config,
+    security: &SecurityPolicy,
+    root: &CronJob,
+    success: bool,
+    output: &str,
+    depth: usize,
+    component: &str,
+) {
+    let dependents = match dependent_jobs(config, &root.id) {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            tracing::warn!("Failed to load dependents of cron job '{}': {e}", root.id);
+            return;
+        }
+    };
+    let upstream = UpstreamOutput {
+        source: root.id.clone(),
+        success,
+        output: output.to_string(),
+    };
+
+    for job in dependents {
+        let Schedule::After { on, .. } = &job.schedule else {
+            continue;
+        };
+        if !on.is_satisfied(&[success]) {
+            continue;
+        }
+        if depth >= MAX_DEPENDENCY_DEPTH {
+            tracing::warn!(
+                "Cron dependency chain exceeded {MAX_DEPENDENCY_DEPTH} levels at job '{}'",
+                job.id
+            );
+            continue;
+        }
+
+        let run = PendingRun {
+            job,
+            trigger: vec![upstream.clone()],
+            repeat: 1,
+            depth: depth + 1,
+        };
+        if let Some((job_id, false)) = dispatch_run(config, security, run, component).await {
+            tracing::warn!("Dependent cron job '{job_id}' failed");
+        }
+    }
+}
+
+/// Execute a workflow's steps in dependency order. Each step runs only when
+/// its `run_if` condition holds for its (non-skipped) dependencies; every
+/// step outcome is recorded in `cron_runs` under its step id.
+async fn run_workflow_job(
+    config: &Config,
+    security: &SecurityPolicy,
+    job: &CronJob,
+    upstream: &[UpstreamOutput],
+) -> (bool, String) {
+    let order = match workflow_order(&job.steps) {
+        Ok(order) => order,
+        Err(e) => return (false, format!("workflow job failed: {e}")),
+    };
+
+    let mut results: HashMap<&str, Option<UpstreamOutput>> = HashMap::new();
+    let mut summary = Vec::with_capacity(order.len());
+    let mut all_ok = true;
+
+    for idx in order {
+        let step = &job.steps[idx];
+        let started_at = Utc::now();
+
+        // Skipped dependencies propagate the skip; root steps see the
+        // workflow's own upstream (if it is itself a dependent job).
+        let deps: Vec<Option<UpstreamOutput>> = step
+            .depends_on
+            .iter()
+            .map(|dep| results.get(dep.trim()).cloned().flatten())
+            .collect();
+        let step_upstream: Vec<UpstreamOutput> = if step.depends_on.is_empty() {
+            upstream.to_vec()
+        } else {
+            deps.iter().flatten().cloned().collect()
+        };
+        let outcomes: Vec<bool> = step_upstream.iter().map(|u| u.success).collect();
+        let runnable = deps.iter().all(Option::is_some) && step.run_if.is_satisfied(&outcomes);
+
+        if !runnable {
+            let _ = record_step_run(
+                config, &job.id, &step.id, started_at, started_at, "skipped", None, 0,
+            );
+            summary.push(format!("step {}: skipped", step.id));
+            results.insert(step.id.trim(), None);
+            continue;
+        }
+
+        let step_job = workflow_step_job(job, step);
+        let (success, output) =
+            execute_single_job_with_retry(config, security, &step_job, &step_upstream).await;
+        let finished_at = Utc::now();
+        let status = if success { "ok" } else { "error" };
+        let _ = record_step_run(
+            config,
+            &job.id,
+            &step.id,
+            started_at,
+            finished_at,
+            status,
+            Some(&output),
+            (finished_at - started_at).num_milliseconds(),
+        );
+
+        all_ok &= success;
+        summary.push(format!("step {}: {status}", step.id));
+        results.insert(
+            step.id.trim(),
+            Some(UpstreamOutput {
+                source: step.id.clone(),
+                success,
+                output,
+            }),
+        );
+    }
+
+    (all_ok, summary.join("\n"))
+}
+
+/// Project a workflow step onto a standalone job so it reuses the shell/agent
+/// runners (and their security checks) unchanged.
+fn workflow_step_job(job: &CronJob, step: &WorkflowStep) -> CronJob {
+    let workflow_name = job.name.as_deref().unwrap_or("workflow");
+    CronJob {
+        id: format!("{}:{}", job.id, step.id),
+        name: Some(format!("{workflow_name}/{}", step.id)),
+        job_type: step.job_type.clone(),
+        command: step.command.clone(),
+        prompt: step.prompt.clone(),
+        model: step.model.clone().or_else(|| job.model.clone()),
+        steps: Vec::new(),
+        ..job.clone()
+    }
+}
+
+fn upstream_env(upstream: &[UpstreamOutput]) -> Vec<(&'static str, String)> {
+    if upstream.is_empty() {
+        return Vec::new();
+    }
+
+    let status = if upstream.iter().all(|u| u.success) {
+        "ok"
+    } else {
+        "error"
+    };
+    let output = if let [single] = upstream {
+        crate::util::truncate_with_ellipsis(&single.output, MAX_UPSTREAM_OUTPUT_CHARS)
+    } else {
+        render_upstream(upstream)
+    };
+    let ids = upstream
+        .iter()
+        .map(|u| u.source.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    vec![
+        ("ZEROCLAW_UPSTREAM_IDS", ids),
+        ("ZEROCLAW_UPSTREAM_STATUS", status.to_string()),
+        ("ZEROCLAW_UPSTREAM_OUTPUT", output),
+    ]
+}
+
+fn render_upstream(upstream: &[UpstreamOutput]) -> String {
+    upstream
+        .iter()
+        .map(|u| {
+            format!(
+                "[{} {}]\n{}",
+                u.source,
+                u.status(),
+                crate::util::truncate_with_ellipsis(&u.output, MAX_UPSTREAM_OUTPUT_CHARS)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
 async fn run_agent_job(
     config: &Config,
     security: &SecurityPolicy,
     job: &CronJob,
+    upstream: &[UpstreamOutput],
 ) -> (bool, String) {
     if !security.can_act() {
         return (
@@ -162,19 +624,29 @@ async fn run_agent_job(
     }
     let name = job.name.clone().unwrap_or_else(|| "cron-job".to_string());
     let prompt = job.prompt.clone().unwrap_or_default();
-    let prefixed_prompt = format!("[cron:{} {name}] {prompt}", job.id);
+    let mut prefixed_prompt = format!("[cron:{} {name}] {prompt}", job.id);
+    if !upstream.is_empty() {
+        prefixed_prompt.push_str("\n\n[Trigger input]\n");
+        prefixed_prompt.push_str(&render_upstream(upstream));
+    }
     let model_override = job.model.clone();
 
+    let observer = crate::observability::create_observer(&config.observability);
     let run_result = match job.session_target {
         SessionTarget::Main | SessionTarget::Isolated => {
-            crate::agent::run(
-                config.clone(),
-                Some(prefixed_prompt),
-                None,
-                model_override,
-                config.default_temperature,
-                vec![],
-            )
+            Box::pin(run_traced_turn(
+                observer.as_ref(),
+                "cron",
+                TraceContext::child_of_current(),
+                crate::agent::run(
+                    config.clone(),
+                    Some(prefixed_prompt),
+                    None,
+                    model_override,
+                    config.default_temperature,
+                    vec![],
+                ),
+            ))
             .await
         }
     };
@@ -242,7 +714,13 @@ async fn persist_job_result(
         return success;
     }
 
-    if let Err(e) = reschedule_after_run(config, job, success, output) {
+    // Clock-driven jobs already had their next occurrence claimed at dispatch.
+    let persisted = if job.schedule.is_event_driven() {
+        reschedule_after_run(config, job, success, output)
+    } else {
+        record_last_run(config, &job.id, finished_at, success, output)
+    };
+    if let Err(e) = persisted {
         tracing::warn!("Failed to persist scheduler run result: {e}");
     }
 
@@ -269,7 +747,10 @@ fn warn_if_high_frequency_agent_job(job: &CronJob) {
                 _ => false,
             }
         }
-        Schedule::At { .. } => false,
+        Schedule::At { .. }
+        | Schedule::After { .. }
+        | Schedule::Webhook { .. }
+        | Schedule::Watch { .. } => false,
     };
 
     if too_frequent {
@@ -421,12 +902,15 @@ async fn run_job_command(
     config: &Config,
     security: &SecurityPolicy,
     job: &CronJob,
+    upstream: &[UpstreamOutput],
 ) -> (bool, String) {
+    let timeout_secs = job.policy.timeout_secs.unwrap_or(SHELL_JOB_TIMEOUT_SECS);
     run_job_command_with_timeout(
         config,
         security,
         job,
-        Duration::from_secs(SHELL_JOB_TIMEOUT_SECS),
+        upstream,
+        Duration::from_secs(timeout_secs),
     )
     .await
 }
@@ -435,6 +919,7 @@ async fn run_job_command_with_timeout(
     config: &Config,
     security: &SecurityPolicy,
     job: &CronJob,
+    upstream: &[UpstreamOutput],
     timeout: Duration,
 ) -> (bool, String) {
     if !security.can_act() {
@@ -479,6 +964,7 @@ async fn run_job_command_with_timeout(
         .arg("-lc")
         .arg(&job.command)
         .current_dir(&config.workspace_dir)
+        .envs(upstream_env(upstream))
         .stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -552,6 +1038,8 @@ mod tests {
             last_run: None,
             last_status: None,
             last_output: None,
+            steps: Vec::new(),
+            policy: crate::cron::JobPolicy::default(),
         }
     }
 
@@ -566,7 +1054,7 @@ mod tests {
         let job = test_job("echo scheduler-ok");
         let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
 
-        let (success, output) = run_job_command(&config, &security, &job).await;
+        let (success, output) = run_job_command(&config, &security, &job, &[]).await;
         assert!(success);
         assert!(output.contains("scheduler-ok"));
         assert!(output.contains("status=exit status: 0"));
@@ -579,7 +1067,7 @@ mod tests {
         let job = test_job("ls definitely_missing_file_for_scheduler_test");
         let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
 
-        let (success, output) = run_job_command(&config, &security, &job).await;
+        let (success, output) = run_job_command(&config, &security, &job, &[]).await;
         assert!(!success);
         assert!(output.contains("definitely_missing_file_for_scheduler_test"));
         assert!(output.contains("status=exit status:"));
@@ -594,7 +1082,8 @@ mod tests {
         let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
 
         let (success, output) =
-            run_job_command_with_timeout(&config, &security, &job, Duration::from_millis(50)).await;
+            run_job_command_with_timeout(&config, &security, &job, &[], Duration::from_millis(50))
+                .await;
         assert!(!success);
         assert!(output.contains("job timed out after"));
     }
@@ -607,7 +1096,7 @@ mod tests {
         let job = test_job("curl https://evil.example");
         let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
 
-        let (success, output) = run_job_command(&config, &security, &job).await;
+        let (success, output) = run_job_command(&config, &security, &job, &[]).await;
         assert!(!success);
         assert!(output.contains("blocked by security policy"));
         assert!(output.contains("command not allowed"));
@@ -621,7 +1110,7 @@ mod tests {
         let job = test_job("cat /etc/passwd");
         let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
 
-        let (success, output) = run_job_command(&config, &security, &job).await;
+        let (success, output) = run_job_command(&config, &security, &job, &[]).await;
         assert!(!success);
         assert!(output.contains("blocked by security policy"));
         assert!(output.contains("forbidden path argument"));
@@ -636,7 +1125,7 @@ mod tests {
         let job = test_job("echo should-not-run");
         let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
 
-        let (success, output) = run_job_command(&config, &security, &job).await;
+        let (success, output) = run_job_command(&config, &security, &job, &[]).await;
         assert!(!success);
         assert!(output.contains("blocked by security policy"));
         assert!(output.contains("read-only"));
@@ -650,7 +1139,7 @@ mod tests {
         let job = test_job("echo should-not-run");
         let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
 
-        let (success, output) = run_job_command(&config, &security, &job).await;
+        let (success, output) = run_job_command(&config, &security, &job, &[]).await;
         assert!(!success);
         assert!(output.contains("blocked by security policy"));
         assert!(output.contains("rate limit exceeded"));
@@ -673,7 +1162,7 @@ mod tests {
         .unwrap();
         let job = test_job("sh ./retry-once.sh");
 
-        let (success, output) = execute_job_with_retry(&config, &security, &job).await;
+        let (success, output) = execute_job_with_retry(&config, &security, &job, &[]).await;
         assert!(success);
         assert!(output.contains("recovered"));
     }
@@ -688,11 +1177,177 @@ mod tests {
 
         let job = test_job("ls always_missing_for_retry_test");
 
-        let (success, output) = execute_job_with_retry(&config, &security, &job).await;
+        let (success, output) = execute_job_with_retry(&config, &security, &job, &[]).await;
         assert!(!success);
         assert!(output.contains("always_missing_for_retry_test"));
     }
 
+    #[tokio::test]
+    async fn job_policy_overrides_scheduler_retries() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp).await;
+        config.reliability.scheduler_retries = 1;
+        config.reliability.provider_backoff_ms = 1;
+        config.autonomy.allowed_commands = vec!["sh".into()];
+        let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+
+        tokio::fs::write(
+            config.workspace_dir.join("retry-once.sh"),
+            "#!/bin/sh\nif [ -f retry-ok.flag ]; then\n  exit 0\nfi\ntouch retry-ok.flag\nexit 1\n",
+        )
+        .await
+        .unwrap();
+        let mut job = test_job("sh ./retry-once.sh");
+        job.policy.max_retries = Some(0);
+
+        let (success, _) = execute_job_with_retry(&config, &security, &job, &[]).await;
+        assert!(!success, "policy disabled the retry");
+    }
+
+    #[tokio::test]
+    async fn claim_due_runs_applies_misfire_policy() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp).await;
+        let now = Utc::now();
+        let job = cron::add_shell_job(
+            &config,
+            None,
+            cron::Schedule::Every { every_ms: 60_000 },
+            "echo missed",
+        )
+        .unwrap();
+        let mut skip = cron::get_job(&config, &job.id).unwrap();
+        skip.policy.misfire = cron::MisfirePolicy::Skip;
+        skip.next_run = now - ChronoDuration::hours(1);
+
+        let mut catch_up = skip.clone();
+        catch_up.policy.misfire = cron::MisfirePolicy::RunAll;
+
+        let runs = claim_due_runs(&config, vec![catch_up], now);
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].repeat, cron::MAX_CATCH_UP_RUNS);
+
+        assert!(claim_due_runs(&config, vec![skip], now).is_empty());
+        let history = cron::list_runs(&config, &job.id, 10).unwrap();
+        assert_eq!(history[0].status, "skipped");
+        assert!(cron::get_job(&config, &job.id).unwrap().next_run > now);
+    }
+
+    #[tokio::test]
+    async fn dispatch_run_drops_overlapping_run() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp).await;
+        let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+        let job = cron::add_job(&config, "*/5 * * * *", "echo overlap").unwrap();
+
+        let (ticket, _) = RUNNING
+            .admit(
+                &job.id,
+                cron::ConcurrencyPolicy::NoOverlap,
+                AbortHandle::new_pair().0,
+                PendingRun::once(job.clone(), Vec::new()),
+            )
+            .unwrap();
+        let outcome = dispatch_run(
+            &config,
+            &security,
+            PendingRun::once(job.clone(), Vec::new()),
+            "scheduler-overlap-test",
+        )
+        .await;
+        assert!(outcome.is_none());
+        assert!(cron::list_runs(&config, &job.id, 10).unwrap().is_empty());
+        assert!(RUNNING.finish(&job.id, ticket).is_none());
+    }
+
+    #[tokio::test]
+    async fn max_concurrent_holds_across_overlapping_ticks() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp).await;
+        config.scheduler.max_concurrent = 1;
+        config.autonomy.allowed_commands.push("sleep".into());
+        let security = Arc::new(SecurityPolicy::from_config(
+            &config.autonomy,
+            &config.workspace_dir,
+        ));
+        let first = cron::add_job(&config, "*/5 * * * *", "sleep 1").unwrap();
+        let second = cron::add_job(&config, "*/5 * * * *", "sleep 1").unwrap();
+        let permits = run_permits(&config);
+
+        // Two ticks whose batches are still running at the same time.
+        tokio::join!(
+            process_jobs(
+                &config,
+                &security,
+                vec![PendingRun::once(first.clone(), Vec::new())],
+                "scheduler-permits-test",
+                &permits,
+            ),
+            process_jobs(
+                &config,
+                &security,
+                vec![PendingRun::once(second.clone(), Vec::new())],
+                "scheduler-permits-test",
+                &permits,
+            ),
+        );
+
+        let a = cron::list_runs(&config, &first.id, 1).unwrap().remove(0);
+        let b = cron::list_runs(&config, &second.id, 1).unwrap().remove(0);
+        assert_eq!(a.status, "ok");
+        assert_eq!(b.status, "ok");
+        assert!(
+            a.finished_at <= b.started_at || b.finished_at <= a.started_at,
+            "runs from separate ticks overlapped despite max_concurrent = 1"
+        );
+    }
+
+    #[tokio::test]
+    async fn dependent_job_skips_while_already_running() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp).await;
+        let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+        let upstream = cron::add_job(&config, "*/5 * * * *", "echo upstream").unwrap();
+        let dependent = cron::add_shell_job(
+            &config,
+            None,
+            crate::cron::Schedule::After {
+                job_id: upstream.id.clone(),
+                on: cron::RunCondition::Success,
+            },
+            "echo dependent",
+        )
+        .unwrap();
+
+        let (ticket, _) = RUNNING
+            .admit(
+                &dependent.id,
+                cron::ConcurrencyPolicy::NoOverlap,
+                AbortHandle::new_pair().0,
+                PendingRun::once(dependent.clone(), Vec::new()),
+            )
+            .unwrap();
+        run_dependent_jobs(
+            &config,
+            &security,
+            &upstream,
+            true,
+            "upstream",
+            0,
+            "scheduler-dependent-overlap-test",
+        )
+        .await;
+
+        assert!(cron::list_runs(&config, &dependent.id, 10)
+            .unwrap()
+            .is_empty());
+        assert!(cron::get_job(&config, &dependent.id)
+            .unwrap()
+            .last_run
+            .is_none());
+        assert!(RUNNING.finish(&dependent.id, ticket).is_none());
+    }
+
     #[tokio::test]
     async fn run_agent_job_returns_error_without_provider_key() {
         let tmp = TempDir::new().unwrap();
@@ -702,7 +1357,7 @@ mod tests {
         job.prompt = Some("Say hello".into());
         let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
 
-        let (success, output) = run_agent_job(&config, &security, &job).await;
+        let (success, output) = run_agent_job(&config, &security, &job, &[]).await;
         assert!(!success);
         assert!(output.contains("agent job failed:"));
     }
@@ -717,7 +1372,7 @@ mod tests {
         job.prompt = Some("Say hello".into());
         let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
 
-        let (success, output) = run_agent_job(&config, &security, &job).await;
+        let (success, output) = run_agent_job(&config, &security, &job, &[]).await;
         assert!(!success);
         assert!(output.contains("blocked by security policy"));
         assert!(output.contains("read-only"));
@@ -733,24 +1388,20 @@ mod tests {
         job.prompt = Some("Say hello".into());
         let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
 
-        let (success, output) = run_agent_job(&config, &security, &job).await;
+        let (success, output) = run_agent_job(&config, &security, &job, &[]).await;
         assert!(!success);
         assert!(output.contains("blocked by security policy"));
         assert!(output.contains("rate limit exceeded"));
     }
 
     #[tokio::test]
-    async fn process_due_jobs_marks_component_ok_even_when_idle() {
+    async fn poll_tick_marks_component_ok_even_when_idle() {
         let tmp = TempDir::new().unwrap();
         let config = test_config(&tmp).await;
-        let security = Arc::new(SecurityPolicy::from_config(
-            &config.autonomy,
-            &config.workspace_dir,
-        ));
         let component = unique_component("scheduler-idle");
 
         crate::health::mark_component_error(&component, "pre-existing error");
-        process_due_jobs(&config, &security, Vec::new(), &component).await;
+        assert!(poll_tick(&config, &component, &mut FileWatcher::new()).is_empty());
 
         let snapshot = crate::health::snapshot_json();
         let entry = &snapshot["components"][component.as_str()];
@@ -760,10 +1411,16 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn process_due_jobs_failure_does_not_mark_component_unhealthy() {
+    async fn process_jobs_failure_does_not_mark_component_unhealthy() {
         let tmp = TempDir::new().unwrap();
         let config = test_config(&tmp).await;
-        let job = test_job("ls definitely_missing_file_for_scheduler_component_health_test");
+        let mut job = cron::add_job(
+            &config,
+            "*/5 * * * *",
+            "ls definitely_missing_file_for_scheduler_component_health_test",
+        )
+        .unwrap();
+        job.next_run = Utc::now();
         let security = Arc::new(SecurityPolicy::from_config(
             &config.autonomy,
             &config.workspace_dir,
@@ -771,7 +1428,11 @@ mod tests {
         let component = unique_component("scheduler-fail");
 
         crate::health::mark_component_ok(&component);
-        process_due_jobs(&config, &security, vec![job], &component).await;
+        let runs = claim_due_runs(&config, vec![job.clone()], Utc::now());
+        assert_eq!(runs.len(), 1);
+        process_jobs(&config, &security, runs, &component, &run_permits(&config)).await;
+        let history = cron::list_runs(&config, &job.id, 10).unwrap();
+        assert_eq!(history[0].status, "error");
 
         let snapshot = crate::health::snapshot_json();
         let entry = &snapshot["components"][component.as_str()];
@@ -863,4 +1524,126 @@ mod tests {
         let err = deliver_if_configured(&config, &job, "x").await.unwrap_err();
         assert!(err.to_string().contains("unsupported delivery channel"));
     }
+
+    #[tokio::test]
+    async fn workflow_runs_steps_in_order_and_records_each_step() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp).await;
+        config.autonomy.allowed_commands = vec!["echo".into(), "ls".into()];
+        let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+
+        let step = |id: &str, command: &str, deps: &[&str], run_if: cron::RunCondition| {
+            crate::cron::WorkflowStep {
+                id: id.into(),
+                job_type: JobType::Shell,
+                command: command.into(),
+                prompt: None,
+                model: None,
+                depends_on: deps.iter().map(|d| (*d).to_string()).collect(),
+                run_if,
+            }
+        };
+        let job = cron::add_workflow_job(
+            &config,
+            Some("wf".into()),
+            crate::cron::Schedule::Cron {
+                expr: "0 2 * * *".into(),
+                tz: None,
+            },
+            vec![
+                step("fetch", "echo fetched", &[], cron::RunCondition::Success),
+                step(
+                    "fail",
+                    "ls missing_workflow_step_file",
+                    &["fetch"],
+                    cron::RunCondition::Success,
+                ),
+                step(
+                    "publish",
+                    "echo publish",
+                    &["fail"],
+                    cron::RunCondition::Success,
+                ),
+                step(
+                    "alert",
+                    "echo \"$ZEROCLAW_UPSTREAM_IDS\"",
+                    &["fail"],
+                    cron::RunCondition::Failure,
+                ),
+            ],
+            None,
+        )
+        .unwrap();
+
+        let (success, output) = execute_job_with_retry(&config, &security, &job, &[]).await;
+        assert!(!success);
+        assert!(output.contains("step fetch: ok"));
+        assert!(output.contains("step fail: error"));
+        assert!(output.contains("step publish: skipped"));
+        assert!(output.contains("step alert: ok"));
+
+        let runs = cron::list_runs(&config, &job.id, 10).unwrap();
+        let alert = runs
+            .iter()
+            .find(|r| r.step_id.as_deref() == Some("alert"))
+            .unwrap();
+        assert!(alert.output.as_deref().unwrap_or_default().contains("fail"));
+        let publish = runs
+            .iter()
+            .find(|r| r.step_id.as_deref() == Some("publish"))
+            .unwrap();
+        assert_eq!(publish.status, "skipped");
+    }
+
+    #[tokio::test]
+    async fn dependent_jobs_receive_upstream_output() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp).await;
+        let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+
+        let upstream = cron::add_job(&config, "*/5 * * * *", "echo upstream-data").unwrap();
+        let on_success = cron::add_shell_job(
+            &config,
+            None,
+            crate::cron::Schedule::After {
+                job_id: upstream.id.clone(),
+                on: cron::RunCondition::Success,
+            },
+            "echo \"got:$ZEROCLAW_UPSTREAM_OUTPUT\"",
+        )
+        .unwrap();
+        let on_failure = cron::add_shell_job(
+            &config,
+            None,
+            crate::cron::Schedule::After {
+                job_id: upstream.id.clone(),
+                on: cron::RunCondition::Failure,
+            },
+            "echo should-not-run",
+        )
+        .unwrap();
+
+        run_dependent_jobs(
+            &config,
+            &security,
+            &upstream,
+            true,
+            "upstream-data",
+            0,
+            "scheduler-dependent-test",
+        )
+        .await;
+
+        let ran = cron::get_job(&config, &on_success.id).unwrap();
+        assert_eq!(ran.last_status.as_deref(), Some("ok"));
+        assert!(ran
+            .last_output
+            .as_deref()
+            .unwrap_or_default()
+            .contains("got:upstream-data"));
+        assert!(ran.next_run > Utc::now() + ChronoDuration::days(365));
+
+        let skipped = cron::get_job(&config, &on_failure.id).unwrap();
+        assert!(skipped.last_run.is_none());
+    }
 }
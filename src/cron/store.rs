@@ -1,7 +1,8 @@
 use crate::config::Config;
 use crate::cron::{
-    next_run_for_schedule, schedule_cron_expression, validate_schedule, CronJob, CronJobPatch,
-    CronRun, DeliveryConfig, JobType, Schedule, SessionTarget,
+    next_run_for_schedule, schedule_cron_expression, validate_schedule, validate_workflow, CronJob,
+    CronJobPatch, CronRun, DeliveryConfig, JobPolicy, JobType, Schedule, SessionTarget,
+    WorkflowStep,
 };
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
@@ -11,6 +12,9 @@ use uuid::Uuid;
 
 const MAX_CRON_OUTPUT_BYTES: usize = 16 * 1024;
 const TRUNCATED_OUTPUT_MARKER: &str = "\n...[truncated]";
+const JOB_COLUMNS: &str = "id, expression, command, schedule, job_type, prompt, name, session_target, model,
+                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output,
+                    steps, policy";
 
 impl rusqlite::types::FromSql for JobType {
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
@@ -35,6 +39,7 @@ pub fn add_shell_job(
 ) -> Result<CronJob> {
     let now = Utc::now();
     validate_schedule(&schedule, now)?;
+    validate_dependency(config, None, &schedule)?;
     let next_run = next_run_for_schedule(&schedule, now)?;
     let id = Uuid::new_v4().to_string();
     let expression = schedule_cron_expression(&schedule).unwrap_or_default();
@@ -77,6 +82,7 @@ pub fn add_agent_job(
 ) -> Result<CronJob> {
     let now = Utc::now();
     validate_schedule(&schedule, now)?;
+    validate_dependency(config, None, &schedule)?;
     let next_run = next_run_for_schedule(&schedule, now)?;
     let id = Uuid::new_v4().to_string();
     let expression = schedule_cron_expression(&schedule).unwrap_or_default();
@@ -110,13 +116,52 @@ pub fn add_agent_job(
     get_job(config, &id)
 }
 
+pub fn add_workflow_job(
+    config: &Config,
+    name: Option<String>,
+    schedule: Schedule,
+    steps: Vec<WorkflowStep>,
+    delivery: Option<DeliveryConfig>,
+) -> Result<CronJob> {
+    let now = Utc::now();
+    validate_schedule(&schedule, now)?;
+    validate_dependency(config, None, &schedule)?;
+    validate_workflow(&steps)?;
+    let next_run = next_run_for_schedule(&schedule, now)?;
+    let id = Uuid::new_v4().to_string();
+    let expression = schedule_cron_expression(&schedule).unwrap_or_default();
+    let schedule_json = serde_json::to_string(&schedule)?;
+    let delivery = delivery.unwrap_or_default();
+
+    with_connection(config, |conn| {
+        conn.execute(
+            "INSERT INTO cron_jobs (
+                id, expression, command, schedule, job_type, prompt, name, session_target, model,
+                enabled, delivery, delete_after_run, created_at, next_run, steps
+             ) VALUES (?1, ?2, '', ?3, 'workflow', NULL, ?4, 'isolated', NULL, 1, ?5, 0, ?6, ?7, ?8)",
+            params![
+                id,
+                expression,
+                schedule_json,
+                name,
+                serde_json::to_string(&delivery)?,
+                now.to_rfc3339(),
+                next_run.to_rfc3339(),
+                serde_json::to_string(&steps)?,
+            ],
+        )
+        .context("Failed to insert cron workflow job")?;
+        Ok(())
+    })?;
+
+    get_job(config, &id)
+}
+
 pub fn list_jobs(config: &Config) -> Result<Vec<CronJob>> {
     with_connection(config, |conn| {
-        let mut stmt = conn.prepare(
-            "SELECT id, expression, command, schedule, job_type, prompt, name, session_target, model,
-                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output
-             FROM cron_jobs ORDER BY next_run ASC",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {JOB_COLUMNS} FROM cron_jobs ORDER BY next_run ASC"
+        ))?;
 
         let rows = stmt.query_map([], map_cron_job_row)?;
 
@@ -130,11 +175,9 @@ pub fn list_jobs(config: &Config) -> Result<Vec<CronJob>> {
 
 pub fn get_job(config: &Config, job_id: &str) -> Result<CronJob> {
     with_connection(config, |conn| {
-        let mut stmt = conn.prepare(
-            "SELECT id, expression, command, schedule, job_type, prompt, name, session_target, model,
-                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output
-             FROM cron_jobs WHERE id = ?1",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {JOB_COLUMNS} FROM cron_jobs WHERE id = ?1"
+        ))?;
 
         let mut rows = stmt.query(params![job_id])?;
         if let Some(row) = rows.next()? {
@@ -163,14 +206,13 @@ pub fn due_jobs(config: &Config, now: DateTime<Utc>) -> Result<Vec<CronJob>> {
     let lim = i64::try_from(config.scheduler.max_tasks.max(1))
         .context("Scheduler max_tasks overflows i64")?;
     with_connection(config, |conn| {
-        let mut stmt = conn.prepare(
-            "SELECT id, expression, command, schedule, job_type, prompt, name, session_target, model,
-                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output
-             FROM cron_jobs
-             WHERE enabled = 1 AND next_run <= ?1
-             ORDER BY next_run ASC
-             LIMIT ?2",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {JOB_COLUMNS}
+                 FROM cron_jobs
+                 WHERE enabled = 1 AND next_run <= ?1
+                 ORDER BY next_run ASC
+                 LIMIT ?2"
+        ))?;
 
         let rows = stmt.query_map(params![now.to_rfc3339(), lim], map_cron_job_row)?;
 
@@ -182,12 +224,53 @@ pub fn due_jobs(config: &Config, now: DateTime<Utc>) -> Result<Vec<CronJob>> {
     })
 }
 
+/// Enabled jobs whose `Schedule::After` points at `upstream_id`.
+pub fn dependent_jobs(config: &Config, upstream_id: &str) -> Result<Vec<CronJob>> {
+    Ok(list_jobs(config)?
+        .into_iter()
+        .filter(|job| {
+            job.enabled
+                && matches!(&job.schedule, Schedule::After { job_id, .. } if job_id == upstream_id)
+        })
+        .collect())
+}
+
+/// Ensure a dependency schedule points at an existing job and does not close
+/// a cycle back to `job_id` (the job being created or updated).
+fn validate_dependency(config: &Config, job_id: Option<&str>, schedule: &Schedule) -> Result<()> {
+    let Schedule::After {
+        job_id: upstream, ..
+    } = schedule
+    else {
+        return Ok(());
+    };
+
+    let mut visited = std::collections::HashSet::new();
+    let mut current = upstream.clone();
+    loop {
+        if Some(current.as_str()) == job_id {
+            anyhow::bail!("Invalid schedule: dependency on '{upstream}' would create a cycle");
+        }
+        if !visited.insert(current.clone()) {
+            // Pre-existing cycle further upstream; it cannot include this job.
+            return Ok(());
+        }
+        let upstream_job = get_job(config, &current)
+            .with_context(|| format!("Invalid schedule: upstream job '{current}' not found"))?;
+        match upstream_job.schedule {
+            Schedule::After { job_id: next, .. } => current = next,
+            _ => return Ok(()),
+        }
+    }
+}
+
 pub fn update_job(config: &Config, job_id: &str, patch: CronJobPatch) -> Result<CronJob> {
     let mut job = get_job(config, job_id)?;
     let mut schedule_changed = false;
 
     if let Some(schedule) = patch.schedule {
         validate_schedule(&schedule, Utc::now())?;
+        validate_dependency(config, Some(job_id), &schedule)?;
         job.schedule = schedule;
         job.expression = schedule_cron_expression(&job.schedule).unwrap_or_default();
         schedule_changed = true;
@@ -216,6 +299,19 @@ pub fn update_job(config: &Config, job_id: &str, patch: CronJobPatch) -> Result<
     if let Some(delete_after_run) = patch.delete_after_run {
         job.delete_after_run = delete_after_run;
     }
+    if let Some(steps) = patch.steps {
+        if job.job_type != JobType::Workflow {
+            anyhow::bail!("Cron job '{job_id}' is not a workflow; 'steps' cannot be set");
+        }
+        validate_workflow(&steps)?;
+        job.steps = steps;
+    }
+    if let Some(policy) = patch.policy {
+        if policy.timeout_secs == Some(0) {
+            anyhow::bail!("Invalid policy: timeout_secs must be > 0");
+        }
+        job.policy = policy;
+    }
 
     if schedule_changed {
         job.next_run = next_run_for_schedule(&job.schedule, Utc::now())?;
@@ -226,8 +322,8 @@ pub fn update_job(config: &Config, job_id: &str, patch: CronJobPatch) -> Result<
             "UPDATE cron_jobs
              SET expression = ?1, command = ?2, schedule = ?3, job_type = ?4, prompt = ?5, name = ?6,
                  session_target = ?7, model = ?8, enabled = ?9, delivery = ?10, delete_after_run = ?11,
-                 next_run = ?12
-             WHERE id = ?13",
+                 next_run = ?12, steps = ?13, policy = ?14
+             WHERE id = ?15",
             params![
                 job.expression,
                 job.command,
@@ -241,6 +337,8 @@ pub fn update_job(config: &Config, job_id: &str, patch: CronJobPatch) -> Result<
                 serde_json::to_string(&job.delivery)?,
                 if job.delete_after_run { 1 } else { 0 },
                 job.next_run.to_rfc3339(),
+                encode_steps(&job.steps)?,
+                encode_policy(&job.policy)?,
                 job.id,
             ],
         )
@@ -272,6 +370,19 @@ pub fn record_last_run(
     })
 }
 
+/// Move a job's `next_run` without touching its run state; the scheduler
+/// claims the next occurrence this way before a due job starts running.
+pub fn set_next_run(config: &Config, job_id: &str, next_run: DateTime<Utc>) -> Result<()> {
+    with_connection(config, |conn| {
+        conn.execute(
+            "UPDATE cron_jobs SET next_run = ?1 WHERE id = ?2",
+            params![next_run.to_rfc3339(), job_id],
+        )
+        .context("Failed to update cron job next run")?;
+        Ok(())
+    })
+}
+
 pub fn reschedule_after_run(
     config: &Config,
     job: &CronJob,
@@ -309,6 +420,54 @@ pub fn record_run(
     status: &str,
     output: Option<&str>,
     duration_ms: i64,
+) -> Result<()> {
+    insert_run(
+        config,
+        job_id,
+        None,
+        started_at,
+        finished_at,
+        status,
+        output,
+        duration_ms,
+    )
+}
+
+/// Record the outcome of a single workflow step. Step rows share the job's
+/// run history (and its retention limit) with whole-job rows.
+#[allow(clippy::too_many_arguments)]
+pub fn record_step_run(
+    config: &Config,
+    job_id: &str,
+    step_id: &str,
+    started_at: DateTime<Utc>,
+    finished_at: DateTime<Utc>,
+    status: &str,
+    output: Option<&str>,
+    duration_ms: i64,
+) -> Result<()> {
+    insert_run(
+        config,
+        job_id,
+        Some(step_id),
+        started_at,
+        finished_at,
+        status,
+        output,
+        duration_ms,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn insert_run(
+    config: &Config,
+    job_id: &str,
+    step_id: Option<&str>,
+    started_at: DateTime<Utc>,
+    finished_at: DateTime<Utc>,
+    status: &str,
+    output: Option<&str>,
+    duration_ms: i64,
 ) -> Result<()> {
     let bounded_output = output.map(truncate_cron_output);
     with_connection(config, |conn| {
@@ -318,10 +477,11 @@ pub fn record_run(
         let tx = conn.unchecked_transaction()?;
 
         tx.execute(
-            "INSERT INTO cron_runs (job_id, started_at, finished_at, status, output, duration_ms)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            "INSERT INTO cron_runs (job_id, step_id, started_at, finished_at, status, output, duration_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
             params![
                 job_id,
+                step_id,
                 started_at.to_rfc3339(),
                 finished_at.to_rfc3339(),
                 status,
@@ -374,7 +534,7 @@ pub fn list_runs(config: &Config, job_id: &str, limit: usize) -> Result<Vec<Cron
     with_connection(config, |conn| {
         let lim = i64::try_from(limit.max(1)).context("Run history limit overflow")?;
         let mut stmt = conn.prepare(
-            "SELECT id, job_id, started_at, finished_at, status, output, duration_ms
+            "SELECT id, job_id, started_at, finished_at, status, output, duration_ms, step_id
              FROM cron_runs
              WHERE job_id = ?1
              ORDER BY started_at DESC, id DESC
@@ -392,6 +552,7 @@ pub fn list_runs(config: &Config, job_id: &str, limit: usize) -> Result<Vec<Cron
                 status: row.get(4)?,
                 output: row.get(5)?,
                 duration_ms: row.get(6)?,
+                step_id: row.get(7)?,
             })
         })?;
 
@@ -447,6 +608,10 @@ fn map_cron_job_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<CronJob> {
         },
         last_status: row.get(15)?,
         last_output: row.get(16)?,
+        steps: decode_steps(row.get::<_, Option<String>>(17)?.as_deref())
+            .map_err(sql_conversion_error)?,
+        policy: decode_policy(row.get::<_, Option<String>>(18)?.as_deref())
+            .map_err(sql_conversion_error)?,
     })
 }
 
@@ -480,8 +645,40 @@ fn decode_delivery(delivery_raw: Option<&str>) -> Result<DeliveryConfig> {
     Ok(DeliveryConfig::default())
 }
 
-fn add_column_if_missing(conn: &Connection, name: &str, sql_type: &str) -> Result<()> {
-    let mut stmt = conn.prepare("PRAGMA table_info(cron_jobs)")?;
+fn decode_steps(steps_raw: Option<&str>) -> Result<Vec<WorkflowStep>> {
+    match steps_raw.map(str::trim) {
+        Some(raw) if !raw.is_empty() => serde_json::from_str(raw)
+            .with_context(|| format!("Failed to parse cron workflow steps JSON: {raw}")),
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn encode_steps(steps: &[WorkflowStep]) -> Result<Option<String>> {
+    if steps.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(serde_json::to_string(steps)?))
+    }
+}
+
+fn decode_policy(policy_raw: Option<&str>) -> Result<JobPolicy> {
+    match policy_raw.map(str::trim) {
+        Some(raw) if !raw.is_empty() => serde_json::from_str(raw)
+            .with_context(|| format!("Failed to parse cron job policy JSON: {raw}")),
+        _ => Ok(JobPolicy::default()),
+    }
+}
+
+fn encode_policy(policy: &JobPolicy) -> Result<Option<String>> {
+    if *policy == JobPolicy::default() {
+        Ok(None)
+    } else {
+        Ok(Some(serde_json::to_string(policy)?))
+    }
+}
+
+fn add_column_if_missing(conn: &Connection, table: &str, name: &str, sql_type: &str) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
     let mut rows = stmt.query([])?;
     while let Some(row) = rows.next()? {
         let col_name: String = row.get(1)?;
@@ -496,17 +693,17 @@ fn add_column_if_missing(conn: &Connection, name: &str, sql_type: &str) -> Resul
     // Tolerate "duplicate column name" errors to handle the race where
     // another process adds the column between our PRAGMA check and ALTER.
     match conn.execute(
-        &format!("ALTER TABLE cron_jobs ADD COLUMN {name} {sql_type}"),
+        &format!("ALTER TABLE {table} ADD COLUMN {name} {sql_type}"),
         [],
     ) {
         Ok(_) => Ok(()),
         Err(rusqlite::Error::SqliteFailure(err, Some(ref msg)))
             if msg.contains("duplicate column name") =>
         {
-            tracing::debug!("Column cron_jobs.{name} already exists (concurrent migration): {err}");
+            tracing::debug!("Column {table}.{name} already exists (concurrent migration): {err}");
             Ok(())
         }
-        Err(e) => Err(e).with_context(|| format!("Failed to add cron_jobs.{name}")),
+        Err(e) => Err(e).with_context(|| format!("Failed to add {table}.{name}")),
     }
 }
 
@@ -539,13 +736,16 @@ fn with_connection<T>(config: &Config, f: impl FnOnce(&Connection) -> Result<T>)
             next_run         TEXT NOT NULL,
             last_run         TEXT,
             last_status      TEXT,
-            last_output      TEXT
+            last_output      TEXT,
+            steps            TEXT,
+            policy           TEXT
         );
         CREATE INDEX IF NOT EXISTS idx_cron_jobs_next_run ON cron_jobs(next_run);
 
         CREATE TABLE IF NOT EXISTS cron_runs (
             id          INTEGER PRIMARY KEY AUTOINCREMENT,
             job_id      TEXT NOT NULL,
+            step_id     TEXT,
             started_at  TEXT NOT NULL,
             finished_at TEXT NOT NULL,
             status      TEXT NOT NULL,
@@ -559,15 +759,33 @@ fn with_connection<T>(config: &Config, f: impl FnOnce(&Connection) -> Result<T>)
     )
     .context("Failed to initialize cron schema")?;
 
-    add_column_if_missing(&conn, "schedule", "TEXT")?;
-    add_column_if_missing(&conn, "job_type", "TEXT NOT NULL DEFAULT 'shell'")?;
-    add_column_if_missing(&conn, "prompt", "TEXT")?;
-    add_column_if_missing(&conn, "name", "TEXT")?;
-    add_column_if_missing(&conn, "session_target", "TEXT NOT NULL DEFAULT 'isolated'")?;
-    add_column_if_missing(&conn, "model", "TEXT")?;
-    add_column_if_missing(&conn, "enabled", "INTEGER NOT NULL DEFAULT 1")?;
-    add_column_if_missing(&conn, "delivery", "TEXT")?;
-    add_column_if_missing(&conn, "delete_after_run", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(&conn, "cron_jobs", "schedule", "TEXT")?;
+    add_column_if_missing(
+        &conn,
+        "cron_jobs",
+        "job_type",
+        "TEXT NOT NULL DEFAULT 'shell'",
+    )?;
+    add_column_if_missing(&conn, "cron_jobs", "prompt", "TEXT")?;
+    add_column_if_missing(&conn, "cron_jobs", "name", "TEXT")?;
+    add_column_if_missing(
+        &conn,
+        "cron_jobs",
+        "session_target",
+        "TEXT NOT NULL DEFAULT 'isolated'",
+    )?;
+    add_column_if_missing(&conn, "cron_jobs", "model", "TEXT")?;
+    add_column_if_missing(&conn, "cron_jobs", "enabled", "INTEGER NOT NULL DEFAULT 1")?;
+    add_column_if_missing(&conn, "cron_jobs", "delivery", "TEXT")?;
+    add_column_if_missing(
+        &conn,
+        "cron_jobs",
+        "delete_after_run",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+    add_column_if_missing(&conn, "cron_jobs", "steps", "TEXT")?;
+    add_column_if_missing(&conn, "cron_jobs", "policy", "TEXT")?;
+    add_column_if_missing(&conn, "cron_runs", "step_id", "TEXT")?;
 
     f(&conn)
 }
@@ -833,4 +1051,131 @@ mod tests {
         assert!(last_output.ends_with(TRUNCATED_OUTPUT_MARKER));
         assert!(last_output.len() <= MAX_CRON_OUTPUT_BYTES);
     }
+
+    #[test]
+    fn dependency_schedule_rejects_missing_upstream_and_cycles() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+
+        let missing = add_shell_job(
+            &config,
+            None,
+            Schedule::After {
+                job_id: "missing".into(),
+                on: crate::cron::RunCondition::Success,
+            },
+            "echo never",
+        );
+        assert!(missing.is_err());
+
+        let first = add_job(&config, "*/5 * * * *", "echo first").unwrap();
+        let second = add_shell_job(
+            &config,
+            None,
+            Schedule::After {
+                job_id: first.id.clone(),
+                on: crate::cron::RunCondition::Always,
+            },
+            "echo second",
+        )
+        .unwrap();
+        assert_eq!(dependent_jobs(&config, &first.id).unwrap().len(), 1);
+
+        let cycle = update_job(
+            &config,
+            &first.id,
+            CronJobPatch {
+                schedule: Some(Schedule::After {
+                    job_id: second.id.clone(),
+                    on: crate::cron::RunCondition::Success,
+                }),
+                ..CronJobPatch::default()
+            },
+        );
+        assert!(cycle.unwrap_err().to_string().contains("cycle"));
+    }
+
+    #[test]
+    fn workflow_steps_roundtrip_and_reject_non_workflow_patch() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let step = WorkflowStep {
+            id: "only".into(),
+            job_type: JobType::Shell,
+            command: "echo only".into(),
+            prompt: None,
+            model: None,
+            depends_on: Vec::new(),
+            run_if: crate::cron::RunCondition::Success,
+        };
+
+        let job = add_workflow_job(
+            &config,
+            None,
+            Schedule::Every { every_ms: 60_000 },
+            vec![step.clone()],
+            None,
+        )
+        .unwrap();
+        assert_eq!(get_job(&config, &job.id).unwrap().steps, vec![step.clone()]);
+
+        let shell = add_job(&config, "*/5 * * * *", "echo shell").unwrap();
+        assert!(get_job(&config, &shell.id).unwrap().steps.is_empty());
+        let err = update_job(
+            &config,
+            &shell.id,
+            CronJobPatch {
+                steps: Some(vec![step]),
+                ..CronJobPatch::default()
+            },
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("not a workflow"));
+    }
+
+    #[test]
+    fn job_policy_roundtrips_and_set_next_run_leaves_run_state() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let job = add_job(&config, "*/5 * * * *", "echo policy").unwrap();
+        assert_eq!(job.policy, JobPolicy::default());
+
+        let policy = JobPolicy {
+            misfire: crate::cron::MisfirePolicy::Skip,
+            concurrency: crate::cron::ConcurrencyPolicy::Queue,
+            timeout_secs: Some(15),
+            max_retries: Some(0),
+        };
+        let updated = update_job(
+            &config,
+            &job.id,
+            CronJobPatch {
+                policy: Some(policy),
+                ..CronJobPatch::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(updated.policy, policy);
+
+        let zero_timeout = JobPolicy {
+            timeout_secs: Some(0),
+            ..policy
+        };
+        assert!(update_job(
+            &config,
+            &job.id,
+            CronJobPatch {
+                policy: Some(zero_timeout),
+                ..CronJobPatch::default()
+            },
+        )
+        .is_err());
+
+        let next = Utc::now() + ChronoDuration::hours(3);
+        set_next_run(&config, &job.id, next).unwrap();
+        let stored = get_job(&config, &job.id).unwrap();
+        assert_eq!(stored.next_run.timestamp(), next.timestamp());
+        assert!(stored.last_run.is_none());
+        assert_eq!(stored.policy, policy);
+    }
 }
@@ -7,6 +7,7 @@ pub enum JobType {
     #[default]
     Shell,
     Agent,
+    Workflow,
 }
 
 impl From<JobType> for &'static str {
@@ -14,6 +15,7 @@ impl From<JobType> for &'static str {
         match value {
             JobType::Shell => "shell",
             JobType::Agent => "agent",
+            JobType::Workflow => "workflow",
         }
     }
 }
@@ -25,8 +27,9 @@ impl TryFrom<&str> for JobType {
         match value.to_lowercase().as_str() {
             "shell" => Ok(JobType::Shell),
             "agent" => Ok(JobType::Agent),
+            "workflow" => Ok(JobType::Workflow),
             _ => Err(format!(
-                "Invalid job type '{}'. Expected one of: 'shell', 'agent'",
+                "Invalid job type '{}'. Expected one of: 'shell', 'agent', 'workflow'",
                 value
             )),
         }
@@ -72,6 +75,210 @@ pub enum Schedule {
     Every {
         every_ms: u64,
     },
+    /// Fires when the upstream job finishes with an outcome matching `on`.
+    After {
+        job_id: String,
+        #[serde(default)]
+        on: RunCondition,
+    },
+    /// Fires on an authenticated `POST /cron/{job_id}` to the gateway.
+    Webhook {
+        /// SHA-256 (hex) of the per-job token; the plaintext is shown once.
+        #[serde(default)]
+        token_hash: String,
+    },
+    /// Fires when files matching `glob` (workspace-relative) change.
+    Watch {
+        glob: String,
+    },
+}
+
+impl Schedule {
+    /// Whether the job is fired by an event rather than the clock.
+    pub fn is_event_driven(&self) -> bool {
+        matches!(
+            self,
+            Self::After { .. } | Self::Webhook { .. } | Self::Watch { .. }
+        )
+    }
+}
+
+/// Upstream outcome required for a dependent job or workflow step to run.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RunCondition {
+    #[default]
+    Success,
+    Failure,
+    Always,
+}
+
+impl RunCondition {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failure => "failure",
+            Self::Always => "always",
+        }
+    }
+
+    /// Whether a dependent should run given the outcomes of its upstreams.
+    pub(crate) fn is_satisfied(self, upstream_ok: &[bool]) -> bool {
+        match self {
+            Self::Success => upstream_ok.iter().all(|ok| *ok),
+            Self::Failure => upstream_ok.iter().any(|ok| !*ok),
+            Self::Always => true,
+        }
+    }
+}
+
+impl TryFrom<&str> for RunCondition {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "success" => Ok(Self::Success),
+            "failure" => Ok(Self::Failure),
+            "always" => Ok(Self::Always),
+            _ => Err(format!(
+                "Invalid run condition '{}'. Expected one of: 'success', 'failure', 'always'",
+                value
+            )),
+        }
+    }
+}
+
+/// One step of a workflow job. Steps form a DAG through `depends_on`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorkflowStep {
+    pub id: String,
+    #[serde(default)]
+    pub job_type: JobType,
+    #[serde(default)]
+    pub command: String,
+    #[serde(default)]
+    pub prompt: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub run_if: RunCondition,
+}
+
+/// File format accepted by `cron add-workflow`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowDefinition {
+    pub steps: Vec<WorkflowStep>,
+}
+
+/// What the scheduler does with occurrences missed while the daemon was down
+/// (or the job was late by more than the misfire grace window).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MisfirePolicy {
+    /// Run once, however many occurrences were missed.
+    #[default]
+    RunOnce,
+    /// Run once per missed occurrence (bounded).
+    RunAll,
+    /// Drop missed occurrences and wait for the next one.
+    Skip,
+}
+
+/// What happens when a job comes due while a previous run is still going.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ConcurrencyPolicy {
+    /// Drop the new occurrence.
+    #[default]
+    NoOverlap,
+    /// Run the new occurrence after the current one (at most one pending).
+    Queue,
+    /// Cancel the current run and start the new one.
+    Replace,
+}
+
+impl MisfirePolicy {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::RunOnce => "run_once",
+            Self::RunAll => "run_all",
+            Self::Skip => "skip",
+        }
+    }
+}
+
+impl ConcurrencyPolicy {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::NoOverlap => "no_overlap",
+            Self::Queue => "queue",
+            Self::Replace => "replace",
+        }
+    }
+}
+
+impl TryFrom<&str> for MisfirePolicy {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().replace('-', "_").as_str() {
+            "run_once" => Ok(Self::RunOnce),
+            "run_all" => Ok(Self::RunAll),
+            "skip" => Ok(Self::Skip),
+            _ => Err(format!(
+                "Invalid misfire policy '{}'. Expected one of: 'run_once', 'run_all', 'skip'",
+                value
+            )),
+        }
+    }
+}
+
+impl TryFrom<&str> for ConcurrencyPolicy {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().replace('-', "_").as_str() {
+            "no_overlap" => Ok(Self::NoOverlap),
+            "queue" => Ok(Self::Queue),
+            "replace" => Ok(Self::Replace),
+            _ => Err(format!(
+                "Invalid concurrency policy '{}'. Expected one of: 'no_overlap', 'queue', 'replace'",
+                value
+            )),
+        }
+    }
+}
+
+/// Per-job execution policy. Unset timeout/retries fall back to the
+/// scheduler defaults (`reliability.scheduler_retries`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct JobPolicy {
+    pub misfire: MisfirePolicy,
+    pub concurrency: ConcurrencyPolicy,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+}
+
+impl JobPolicy {
+    /// One-line summary for `cron list`.
+    pub(crate) fn describe(&self) -> String {
+        let timeout = self
+            .timeout_secs
+            .map_or_else(|| "default".to_string(), |secs| format!("{secs}s"));
+        let retries = self
+            .max_retries
+            .map_or_else(|| "default".to_string(), |n| n.to_string());
+        format!(
+            "misfire={} concurrency={} timeout={timeout} retries={retries}",
+            self.misfire.as_str(),
+            self.concurrency.as_str()
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -120,12 +327,19 @@ pub struct CronJob {
     pub last_run: Option<DateTime<Utc>>,
     pub last_status: Option<String>,
     pub last_output: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<WorkflowStep>,
+    #[serde(default)]
+    pub policy: JobPolicy,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CronRun {
     pub id: i64,
     pub job_id: String,
+    /// Workflow step this row belongs to; `None` for whole-job runs.
+    #[serde(default)]
+    pub step_id: Option<String>,
     pub started_at: DateTime<Utc>,
     pub finished_at: DateTime<Utc>,
     pub status: String,
@@ -144,11 +358,13 @@ pub struct CronJobPatch {
     pub model: Option<String>,
     pub session_target: Option<SessionTarget>,
     pub delete_after_run: Option<bool>,
+    pub steps: Option<Vec<WorkflowStep>>,
+    pub policy: Option<JobPolicy>,
 }
 
 #[cfg(test)]
 mod tests {
-    use super::JobType;
+    use super::{ConcurrencyPolicy, JobPolicy, JobType, MisfirePolicy, RunCondition};
 
     #[test]
     fn job_type_try_from_accepts_known_values_case_insensitive() {
@@ -156,6 +372,7 @@ mod tests {
         assert_eq!(JobType::try_from("SHELL").unwrap(), JobType::Shell);
         assert_eq!(JobType::try_from("agent").unwrap(), JobType::Agent);
         assert_eq!(JobType::try_from("AgEnT").unwrap(), JobType::Agent);
+        assert_eq!(JobType::try_from("Workflow").unwrap(), JobType::Workflow);
     }
 
     #[test]
@@ -163,4 +380,39 @@ mod tests {
         assert!(JobType::try_from("").is_err());
         assert!(JobType::try_from("unknown").is_err());
     }
+
+    #[test]
+    fn run_condition_matches_upstream_outcomes() {
+        assert!(RunCondition::Success.is_satisfied(&[true, true]));
+        assert!(!RunCondition::Success.is_satisfied(&[true, false]));
+        assert!(RunCondition::Failure.is_satisfied(&[true, false]));
+        assert!(!RunCondition::Failure.is_satisfied(&[true]));
+        assert!(RunCondition::Always.is_satisfied(&[false]));
+    }
+
+    #[test]
+    fn job_policy_defaults_and_parses_cli_names() {
+        let policy: JobPolicy = serde_json::from_str("{}").unwrap();
+        assert_eq!(policy, JobPolicy::default());
+        assert_eq!(policy.misfire, MisfirePolicy::RunOnce);
+        assert_eq!(policy.concurrency, ConcurrencyPolicy::NoOverlap);
+
+        let policy: JobPolicy = serde_json::from_str(
+            r#"{"misfire":"run_all","concurrency":"replace","timeout_secs":30}"#,
+        )
+        .unwrap();
+        assert_eq!(policy.misfire, MisfirePolicy::RunAll);
+        assert_eq!(policy.concurrency, ConcurrencyPolicy::Replace);
+        assert_eq!(policy.timeout_secs, Some(30));
+        assert_eq!(
+            policy.describe(),
+            "misfire=run_all concurrency=replace timeout=30s retries=default"
+        );
+
+        assert_eq!(
+            ConcurrencyPolicy::try_from("no-overlap").unwrap(),
+            ConcurrencyPolicy::NoOverlap
+        );
+        assert!(MisfirePolicy::try_from("sometimes").is_err());
+    }
 }
@@ -0,0 +1,219 @@
+use crate::cron::{CronJob, Schedule};
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Upper bound on files tracked per watch job, so a broad glob over a large
+/// workspace cannot turn every scheduler tick into a full tree scan.
+const MAX_WATCHED_FILES: usize = 5_000;
+
+/// Workspace-relative path → (modified time, length).
+type WatchSnapshot = BTreeMap<String, (Option<SystemTime>, u64)>;
+
+pub fn validate_watch_glob(pattern: &str) -> Result<()> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        anyhow::bail!("Invalid schedule: 'watch' requires a glob");
+    }
+    if pattern.starts_with('/') || pattern.starts_with('\\') {
+        anyhow::bail!("Invalid schedule: watch glob must be relative to the workspace");
+    }
+    if pattern.split(['/', '\\']).any(|part| part == "..") {
+        anyhow::bail!("Invalid schedule: path traversal ('..') is not allowed in watch globs");
+    }
+    glob::Pattern::new(pattern)
+        .with_context(|| format!("Invalid schedule: bad watch glob '{pattern}'"))?;
+    Ok(())
+}
+
+fn snapshot(workspace: &Path, pattern: &str) -> Result<WatchSnapshot> {
+    let workspace_canon = std::fs::canonicalize(workspace)
+        .with_context(|| format!("Cannot resolve workspace: {}", workspace.display()))?;
+    let full_pattern = workspace_canon.join(pattern.trim());
+    let entries = glob::glob(&full_pattern.to_string_lossy())
+        .with_context(|| format!("Invalid watch glob: {pattern}"))?;
+
+    let mut files = WatchSnapshot::new();
+    for path in entries.flatten() {
+        // Resolve symlinks and keep only regular files inside the workspace.
+        let Ok(resolved) = std::fs::canonicalize(&path) else {
+            continue;
+        };
+        if !resolved.starts_with(&workspace_canon) {
+            continue;
+        }
+        let Ok(meta) = std::fs::metadata(&resolved) else {
+            continue;
+        };
+        if !meta.is_file() {
+            continue;
+        }
+        let rel = path
+            .strip_prefix(&workspace_canon)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string();
+        files.insert(rel, (meta.modified().ok(), meta.len()));
+        if files.len() >= MAX_WATCHED_FILES {
+            tracing::warn!(
+                "Cron watch glob '{pattern}' matches more than {MAX_WATCHED_FILES} files; \
+                 only the first {MAX_WATCHED_FILES} are tracked"
+            );
+            break;
+        }
+    }
+    Ok(files)
+}
+
+fn diff(old: &WatchSnapshot, new: &WatchSnapshot) -> Vec<String> {
+    let mut changes = Vec::new();
+    for (path, stat) in new {
+        match old.get(path) {
+            None => changes.push(format!("added: {path}")),
+            Some(prev) if prev != stat => changes.push(format!("modified: {path}")),
+            Some(_) => {}
+        }
+    }
+    for path in old.keys() {
+        if !new.contains_key(path) {
+            changes.push(format!("removed: {path}"));
+        }
+    }
+    changes
+}
+
+/// Polling watcher for `Schedule::Watch` jobs. The first poll of a job only
+/// records a baseline; later polls report the files that changed since.
+#[derive(Default)]
+pub struct FileWatcher {
+    snapshots: HashMap<String, (String, WatchSnapshot)>,
+}
+
+impl FileWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the watch jobs among `jobs` whose files changed, each with a
+    /// list of `added:`/`modified:`/`removed:` lines.
+    pub fn poll(&mut self, workspace: &Path, jobs: &[CronJob]) -> Vec<(CronJob, Vec<String>)> {
+        let mut fired = Vec::new();
+        let mut live = Vec::new();
+
+        for job in jobs {
+            let Schedule::Watch { glob } = &job.schedule else {
+                continue;
+            };
+            if !job.enabled {
+                continue;
+            }
+            live.push(job.id.clone());
+
+            let current = match snapshot(workspace, glob) {
+                Ok(current) => current,
+                Err(e) => {
+                    tracing::warn!("Cron watch job '{}' scan failed: {e}", job.id);
+                    continue;
+                }
+            };
+
+            match self.snapshots.get(&job.id) {
+                Some((prev_glob, prev)) if prev_glob == glob => {
+                    let changes = diff(prev, &current);
+                    if !changes.is_empty() {
+                        fired.push((job.clone(), changes));
+                    }
+                }
+                _ => {}
+            }
+            self.snapshots
+                .insert(job.id.clone(), (glob.clone(), current));
+        }
+
+        self.snapshots.retain(|id, _| live.contains(id));
+        fired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cron::{DeliveryConfig, JobType, SessionTarget};
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    fn watch_job(glob: &str) -> CronJob {
+        CronJob {
+            id: "watch-job".into(),
+            expression: String::new(),
+            schedule: Schedule::Watch { glob: glob.into() },
+            command: "echo changed".into(),
+            prompt: None,
+            name: None,
+            job_type: JobType::Shell,
+            session_target: SessionTarget::Isolated,
+            model: None,
+            enabled: true,
+            delivery: DeliveryConfig::default(),
+            delete_after_run: false,
+            created_at: Utc::now(),
+            next_run: Utc::now(),
+            last_run: None,
+            last_status: None,
+            last_output: None,
+            steps: Vec::new(),
+            policy: crate::cron::JobPolicy::default(),
+        }
+    }
+
+    #[test]
+    fn validate_rejects_absolute_and_traversal_globs() {
+        assert!(validate_watch_glob("inbox/*.csv").is_ok());
+        assert!(validate_watch_glob("").is_err());
+        assert!(validate_watch_glob("/etc/*").is_err());
+        assert!(validate_watch_glob("../outside/*").is_err());
+        assert!(validate_watch_glob("a/[").is_err());
+    }
+
+    #[test]
+    fn poll_reports_changes_after_baseline() {
+        let tmp = TempDir::new().unwrap();
+        let inbox = tmp.path().join("inbox");
+        std::fs::create_dir_all(&inbox).unwrap();
+        std::fs::write(inbox.join("a.csv"), "1").unwrap();
+        std::fs::write(inbox.join("ignored.txt"), "x").unwrap();
+
+        let jobs = vec![watch_job("inbox/*.csv")];
+        let mut watcher = FileWatcher::new();
+        assert!(watcher.poll(tmp.path(), &jobs).is_empty(), "baseline");
+        assert!(watcher.poll(tmp.path(), &jobs).is_empty(), "no change");
+
+        std::fs::write(inbox.join("a.csv"), "12").unwrap();
+        std::fs::write(inbox.join("b.csv"), "2").unwrap();
+        std::fs::write(inbox.join("ignored.txt"), "xy").unwrap();
+        let fired = watcher.poll(tmp.path(), &jobs);
+        assert_eq!(fired.len(), 1);
+        let changes = &fired[0].1;
+        assert!(changes.contains(&"added: inbox/b.csv".to_string()));
+        assert!(changes.contains(&"modified: inbox/a.csv".to_string()));
+        assert_eq!(changes.len(), 2);
+
+        std::fs::remove_file(inbox.join("b.csv")).unwrap();
+        let fired = watcher.poll(tmp.path(), &jobs);
+        assert_eq!(fired[0].1, vec!["removed: inbox/b.csv".to_string()]);
+    }
+
+    #[test]
+    fn poll_forgets_removed_or_disabled_jobs() {
+        let tmp = TempDir::new().unwrap();
+        let mut watcher = FileWatcher::new();
+        let mut job = watch_job("*.md");
+        watcher.poll(tmp.path(), std::slice::from_ref(&job));
+        assert_eq!(watcher.snapshots.len(), 1);
+
+        job.enabled = false;
+        watcher.poll(tmp.path(), &[job]);
+        assert!(watcher.snapshots.is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+use crate::cron::{JobType, WorkflowStep};
+use anyhow::Result;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Validate a workflow definition: unique step ids, known dependencies,
+/// runnable step payloads and no dependency cycles.
+pub fn validate_workflow(steps: &[WorkflowStep]) -> Result<()> {
+    workflow_order(steps).map(|_| ())
+}
+
+/// Return step indices in dependency order (Kahn's algorithm). Steps that
+/// become ready at the same time keep their definition order.
+pub fn workflow_order(steps: &[WorkflowStep]) -> Result<Vec<usize>> {
+    if steps.is_empty() {
+        anyhow::bail!("Invalid workflow: at least one step is required");
+    }
+
+    let mut index_by_id = HashMap::with_capacity(steps.len());
+    for (idx, step) in steps.iter().enumerate() {
+        let id = step.id.trim();
+        if id.is_empty() {
+            anyhow::bail!("Invalid workflow: step #{} has an empty id", idx + 1);
+        }
+        if index_by_id.insert(id, idx).is_some() {
+            anyhow::bail!("Invalid workflow: duplicate step id '{id}'");
+        }
+        match step.job_type {
+            JobType::Shell if step.command.trim().is_empty() => {
+                anyhow::bail!("Invalid workflow: shell step '{id}' is missing 'command'")
+            }
+            JobType::Agent
+                if step
+                    .prompt
+                    .as_deref()
+                    .is_none_or(|prompt| prompt.trim().is_empty()) =>
+            {
+                anyhow::bail!("Invalid workflow: agent step '{id}' is missing 'prompt'")
+            }
+            JobType::Workflow => {
+                anyhow::bail!("Invalid workflow: step '{id}' cannot itself be a workflow")
+            }
+            _ => {}
+        }
+    }
+
+    let mut indegree = vec![0usize; steps.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); steps.len()];
+    for (idx, step) in steps.iter().enumerate() {
+        let mut seen = HashSet::new();
+        for dep in &step.depends_on {
+            let dep = dep.trim();
+            let Some(&dep_idx) = index_by_id.get(dep) else {
+                anyhow::bail!(
+                    "Invalid workflow: step '{}' depends on unknown step '{dep}'",
+                    step.id
+                );
+            };
+            if dep_idx == idx {
+                anyhow::bail!("Invalid workflow: step '{}' depends on itself", step.id);
+            }
+            if seen.insert(dep_idx) {
+                indegree[idx] += 1;
+                dependents[dep_idx].push(idx);
+            }
+        }
+    }
+
+    let mut ready: VecDeque<usize> = (0..steps.len()).filter(|i| indegree[*i] == 0).collect();
+    let mut order = Vec::with_capacity(steps.len());
+    while let Some(idx) = ready.pop_front() {
+        order.push(idx);
+        for &next in &dependents[idx] {
+            indegree[next] -= 1;
+            if indegree[next] == 0 {
+                ready.push_back(next);
+            }
+        }
+    }
+
+    if order.len() != steps.len() {
+        let cyclic: Vec<&str> = (0..steps.len())
+            .filter(|i| indegree[*i] > 0)
+            .map(|i| steps[i].id.as_str())
+            .collect();
+        anyhow::bail!(
+            "Invalid workflow: dependency cycle between steps {}",
+            cyclic.join(", ")
+        );
+    }
+
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cron::RunCondition;
+
+    fn shell(id: &str, deps: &[&str]) -> WorkflowStep {
+        WorkflowStep {
+            id: id.into(),
+            job_type: JobType::Shell,
+            command: format!("echo {id}"),
+            prompt: None,
+            model: None,
+            depends_on: deps.iter().map(|d| (*d).to_string()).collect(),
+            run_if: RunCondition::Success,
+        }
+    }
+
+    #[test]
+    fn orders_steps_by_dependencies() {
+        let steps = vec![
+            shell("report", &["build", "test"]),
+            shell("build", &[]),
+            shell("test", &["build"]),
+        ];
+        let order: Vec<&str> = workflow_order(&steps)
+            .unwrap()
+            .into_iter()
+            .map(|i| steps[i].id.as_str())
+            .collect();
+        assert_eq!(order, vec!["build", "test", "report"]);
+    }
+
+    #[test]
+    fn rejects_cycles_unknown_deps_and_duplicates() {
+        let cycle = vec![shell("a", &["b"]), shell("b", &["a"])];
+        let err = validate_workflow(&cycle).unwrap_err().to_string();
+        assert!(err.contains("cycle"), "{err}");
+
+        let unknown = vec![shell("a", &["missing"])];
+        let err = validate_workflow(&unknown).unwrap_err().to_string();
+        assert!(err.contains("unknown step 'missing'"), "{err}");
+
+        let dup = vec![shell("a", &[]), shell("a", &[])];
+        let err = validate_workflow(&dup).unwrap_err().to_string();
+        assert!(err.contains("duplicate step id"), "{err}");
+
+        assert!(validate_workflow(&[]).is_err());
+    }
+
+    #[test]
+    fn rejects_incomplete_step_payloads() {
+        let mut agent = shell("ask", &[]);
+        agent.job_type = JobType::Agent;
+        let err = validate_workflow(&[agent]).unwrap_err().to_string();
+        assert!(err.contains("missing 'prompt'"), "{err}");
+
+        let mut nested = shell("nested", &[]);
+        nested.job_type = JobType::Workflow;
+        assert!(validate_workflow(&[nested]).is_err());
+    }
+}
@@ -17,19 +17,14 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
 
     crate::health::mark_component_ok("daemon");
 
-    if config.heartbeat.enabled {
-        let _ =
-            crate::heartbeat::engine::HeartbeatEngine::ensure_heartbeat_file(&config.workspace_dir)
-                .await;
-    }
-
     let mut handles: Vec<JoinHandle<()>> = vec![spawn_state_writer(config.clone())];
 
     {
+        // One gateway serves every profile under `/profiles/<name>/`.
         let gateway_cfg = config.clone();
         let gateway_host = host.clone();
         handles.push(spawn_component_supervisor(
-            "gateway",
+            "gateway".to_string(),
             initial_backoff,
             max_backoff,
             move || {
@@ -40,56 +35,17 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         ));
     }
 
-    {
-        if has_supervised_channels(&config) {
-            let channels_cfg = config.clone();
-            handles.push(spawn_component_supervisor(
-                "channels",
-                initial_backoff,
-                max_backoff,
-                move || {
-                    let cfg = channels_cfg.clone();
-                    async move { crate::channels::start_channels(cfg).await }
-                },
-            ));
-        } else {
-            crate::health::mark_component_ok("channels");
-            tracing::info!("No real-time channels configured; channel supervisor disabled");
-        }
-    }
-
-    if config.heartbeat.enabled {
-        let heartbeat_cfg = config.clone();
-        handles.push(spawn_component_supervisor(
-            "heartbeat",
-            initial_backoff,
-            max_backoff,
-            move || {
-                let cfg = heartbeat_cfg.clone();
-                async move { run_heartbeat_worker(cfg).await }
-            },
-        ));
-    }
-
-    if config.cron.enabled {
-        let scheduler_cfg = config.clone();
-        handles.push(spawn_component_supervisor(
-            "scheduler",
-            initial_backoff,
-            max_backoff,
-            move || {
-                let cfg = scheduler_cfg.clone();
-                async move { crate::cron::scheduler::run(cfg).await }
-            },
-        ));
-    } else {
-        crate::health::mark_component_ok("scheduler");
-        tracing::info!("Cron disabled; scheduler supervisor not started");
+    for agent in config.agent_configs() {
+        handles.extend(spawn_agent_components(&agent, initial_backoff, max_backoff).await);
     }
 
     println!("🧠 ZeroClaw daemon started");
     println!("   Gateway:  http://{host}:{port}");
     println!("   Components: gateway, channels, heartbeat, scheduler");
+    if !config.profiles.is_empty() {
+        let names: Vec<&str> = config.profiles.iter().map(|p| p.name.as_str()).collect();
+        println!("   Profiles: {}", names.join(", "));
+    }
     println!("   Ctrl+C to stop");
 
     tokio::signal::ctrl_c().await?;
@@ -136,8 +92,95 @@ fn spawn_state_writer(config: Config) -> JoinHandle<()> {
     })
 }
 
+/// Supervise the channels, heartbeat, consolidation and scheduler of one
+/// agent (the top-level config or a `[[profiles]]` entry).
+async fn spawn_agent_components(
+    agent: &Config,
+    initial_backoff: u64,
+    max_backoff: u64,
+) -> Vec<JoinHandle<()>> {
+    let mut handles = Vec::new();
+    if agent.profile.is_some() {
+        if let Err(e) = tokio::fs::create_dir_all(&agent.workspace_dir).await {
+            tracing::warn!(
+                "Failed to create workspace {}: {e}",
+                agent.workspace_dir.display()
+            );
+        }
+    }
+
+    if has_supervised_channels(agent) {
+        let channels_cfg = agent.clone();
+        handles.push(spawn_component_supervisor(
+            agent.health_component("channels"),
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = channels_cfg.clone();
+                async move { crate::channels::start_channels(cfg).await }
+            },
+        ));
+    } else {
+        crate::health::mark_component_ok(&agent.health_component("channels"));
+        tracing::info!(
+            profile = agent.profile.as_deref().unwrap_or("default"),
+            "No real-time channels configured; channel supervisor disabled"
+        );
+    }
+
+    if agent.heartbeat.enabled {
+        let _ =
+            crate::heartbeat::engine::HeartbeatEngine::ensure_heartbeat_file(&agent.workspace_dir)
+                .await;
+        let heartbeat_cfg = agent.clone();
+        handles.push(spawn_component_supervisor(
+            agent.health_component("heartbeat"),
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = heartbeat_cfg.clone();
+                async move { run_heartbeat_worker(cfg).await }
+            },
+        ));
+    }
+
+    if agent.memory.consolidation.enabled {
+        let consolidation_cfg = agent.clone();
+        handles.push(spawn_component_supervisor(
+            agent.health_component("memory-consolidation"),
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = consolidation_cfg.clone();
+                async move { crate::memory::consolidation::run_worker(cfg).await }
+            },
+        ));
+    }
+
+    if agent.cron.enabled {
+        let scheduler_cfg = agent.clone();
+        handles.push(spawn_component_supervisor(
+            agent.health_component("scheduler"),
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = scheduler_cfg.clone();
+                async move { crate::cron::scheduler::run(cfg).await }
+            },
+        ));
+    } else {
+        crate::health::mark_component_ok(&agent.health_component("scheduler"));
+        tracing::info!(
+            profile = agent.profile.as_deref().unwrap_or("default"),
+            "Cron disabled; scheduler supervisor not started"
+        );
+    }
+
+    handles
+}
+
 fn spawn_component_supervisor<F, Fut>(
-    name: &'static str,
+    name: String,
     initial_backoff_secs: u64,
     max_backoff_secs: u64,
     mut run_component: F,
@@ -151,21 +194,21 @@ where
         let max_backoff = max_backoff_secs.max(backoff);
 
         loop {
-            crate::health::mark_component_ok(name);
+            crate::health::mark_component_ok(&name);
             match run_component().await {
                 Ok(()) => {
-                    crate::health::mark_component_error(name, "component exited unexpectedly");
+                    crate::health::mark_component_error(&name, "component exited unexpectedly");
                     tracing::warn!("Daemon component '{name}' exited unexpectedly");
                     // Clean exit — reset backoff since the component ran successfully
                     backoff = initial_backoff_secs.max(1);
                 }
                 Err(e) => {
-                    crate::health::mark_component_error(name, e.to_string());
+                    crate::health::mark_component_error(&name, e.to_string());
                     tracing::error!("Daemon component '{name}' failed: {e}");
                 }
             }
 
-            crate::health::bump_component_restart(name);
+            crate::health::bump_component_restart(&name);
             tokio::time::sleep(Duration::from_secs(backoff)).await;
             // Double backoff AFTER sleeping so first error uses initial_backoff
             backoff = backoff.saturating_mul(2).min(max_backoff);
@@ -182,6 +225,7 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
         observer,
     );
 
+    let component = config.health_component("heartbeat");
     let interval_mins = config.heartbeat.interval_minutes.max(5);
     let mut interval = tokio::time::interval(Duration::from_secs(u64::from(interval_mins) * 60));
 
@@ -196,13 +240,20 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
         for task in tasks {
             let prompt = format!("[Heartbeat Task] {task}");
             let temp = config.default_temperature;
-            if let Err(e) =
-                crate::agent::run(config.clone(), Some(prompt), None, None, temp, vec![]).await
+            if let Err(e) = Box::pin(crate::agent::run(
+                config.clone(),
+                Some(prompt),
+                None,
+                None,
+                temp,
+                vec![],
+            ))
+            .await
             {
-                crate::health::mark_component_error("heartbeat", e.to_string());
+                crate::health::mark_component_error(&component, e.to_string());
                 tracing::warn!("Heartbeat task failed: {e}");
             } else {
-                crate::health::mark_component_ok("heartbeat");
+                crate::health::mark_component_ok(&component);
             }
         }
     }
@@ -227,6 +278,9 @@ fn has_supervised_channels(config: &Config) -> bool {
         linq,
         nextcloud_talk,
         qq,
+        websocket,
+        xmpp,
+        zulip,
         ..
     } = &config.channels_config;
 
@@ -245,6 +299,9 @@ fn has_supervised_channels(config: &Config) -> bool {
         || linq.is_some()
         || nextcloud_talk.is_some()
         || qq.is_some()
+        || websocket.is_some()
+        || xmpp.is_some()
+        || zulip.is_some()
 }
 
 #[cfg(test)]
@@ -273,7 +330,7 @@ mod tests {
 
     #[tokio::test]
     async fn supervisor_marks_error_and_restart_on_failure() {
-        let handle = spawn_component_supervisor("daemon-test-fail", 1, 1, || async {
+        let handle = spawn_component_supervisor("daemon-test-fail".to_string(), 1, 1, || async {
             anyhow::bail!("boom")
         });
 
@@ -293,7 +350,8 @@ mod tests {
 
     #[tokio::test]
     async fn supervisor_marks_unexpected_exit_as_error() {
-        let handle = spawn_component_supervisor("daemon-test-exit", 1, 1, || async { Ok(()) });
+        let handle =
+            spawn_component_supervisor("daemon-test-exit".to_string(), 1, 1, || async { Ok(()) });
 
         tokio::time::sleep(Duration::from_millis(50)).await;
         handle.abort();
@@ -1,9 +1,11 @@
 //! CLI handlers for deploy commands.
 
 use crate::config::Config;
+use crate::deploy::history::{DeploymentHistory, DeploymentOutcome, DeploymentRecord};
 use crate::deploy::remote::{DeploymentConfig, DeploymentMode, DeploymentStatus, RemoteDeployer};
 use anyhow::{bail, Context, Result};
 use std::collections::HashMap;
+use std::fmt::Write as _;
 use std::path::PathBuf;
 use tracing::info;
 
@@ -12,23 +14,29 @@ pub async fn handle_command(
     deploy_command: crate::deploy::DeployCommands,
     config: &Config,
 ) -> Result<()> {
-    let deploy_config = crate::config::DeployConfig::default();
-    let targets = load_deploy_config(config, &deploy_config)?;
+    let deploy_config = config.deploy.clone();
+    let targets = load_deploy_config(config)?;
+    let history = DeploymentHistory::for_workspace(&config.workspace_dir);
 
     match deploy_command {
-        crate::deploy::DeployCommands::Deploy { server } => {
-            handle_deploy(&server, &targets, deploy_config.clone()).await
+        crate::deploy::DeployCommands::Deploy { server, version } => {
+            handle_deploy(&server, version, &targets, &deploy_config, history).await
+        }
+        crate::deploy::DeployCommands::Status { server } => {
+            handle_status(&server, &targets, &history)
         }
-        crate::deploy::DeployCommands::Status { server } => handle_status(&server, &targets).await,
         crate::deploy::DeployCommands::HealthCheck { server } => {
-            handle_health_check(&server, &targets).await
+            handle_health_check(&server, &targets, &deploy_config).await
         }
         crate::deploy::DeployCommands::List => handle_list(&targets),
+        crate::deploy::DeployCommands::History { server, limit } => {
+            handle_history(server.as_deref(), limit, &history)
+        }
         crate::deploy::DeployCommands::Rollback { server } => {
-            handle_rollback(&server, &targets).await
+            handle_rollback(&server, &targets, &deploy_config, history).await
         }
         crate::deploy::DeployCommands::Update { server, version } => {
-            handle_update(&server, version, &targets, deploy_config.clone()).await
+            handle_update(&server, version, &targets, &deploy_config, history).await
         }
         crate::deploy::DeployCommands::SyncConfig { server } => {
             handle_sync_config(&server, config).await
@@ -40,10 +48,7 @@ pub async fn handle_command(
 }
 
 /// Load deploy configuration from config.
-fn load_deploy_config(
-    config: &Config,
-    _deploy_cfg: &crate::config::DeployConfig,
-) -> Result<Vec<crate::deploy::remote::DeploymentTarget>> {
+fn load_deploy_config(config: &Config) -> Result<Vec<crate::deploy::remote::DeploymentTarget>> {
     if config.deploy.servers.is_empty() {
         bail!(
             "No deployment targets configured. \
@@ -124,50 +129,85 @@ fn create_deployment_config(
         working_dir: PathBuf::from(&settings.working_dir),
         auto_start: settings.auto_start,
         health_check_interval: std::time::Duration::from_secs(settings.health_check_interval_secs),
+        health_check_retries: settings.health_check_retries,
+        health_command: settings.health_command.clone(),
+        restart_command: settings.restart_command.clone(),
         restart_on_failure: settings.restart_on_failure,
         max_restarts: settings.max_restarts,
         use_sudo: settings.use_sudo,
     }
 }
 
-/// Handle deploy command.
-async fn handle_deploy(
-    server_id: &str,
+/// Build a deployer for all configured targets using the `[deploy]` settings.
+fn build_deployer(
     targets: &[crate::deploy::remote::DeploymentTarget],
-    deploy_config: crate::config::DeployConfig,
-) -> Result<()> {
-    let target = find_target(server_id, targets)?;
-
-    info!("Starting deployment to {} ({})...", server_id, target.host);
-
+    deploy_config: &crate::config::DeployConfig,
+    history: Option<DeploymentHistory>,
+    version: &str,
+) -> RemoteDeployer {
     let mode = parse_deployment_mode(&deploy_config.settings.mode);
     let mut deployer = RemoteDeployer::new(mode);
-    deployer.register_target(target.clone());
+    if let Some(history) = history {
+        deployer = deployer.with_history(history);
+    }
+    for target in targets {
+        deployer.register_target(target.clone());
+    }
+    deployer.set_config(create_deployment_config(&deploy_config.settings, version));
+    deployer
+}
 
-    let dep_config = create_deployment_config(&deploy_config.settings, "latest");
-    deployer.set_config(dep_config);
+/// Handle deploy command: a health-gated rollout across one or more servers.
+async fn handle_deploy(
+    server_ids: &[String],
+    version: Option<String>,
+    targets: &[crate::deploy::remote::DeploymentTarget],
+    deploy_config: &crate::config::DeployConfig,
+    history: DeploymentHistory,
+) -> Result<()> {
+    for server_id in server_ids {
+        find_target(server_id, targets)?;
+    }
+    let version = version.unwrap_or_else(|| env!("CARGO_PKG_VERSION").to_string());
 
-    deployer
-        .deploy(server_id, "zerospider")
+    info!(
+        "Starting deployment of {} to {}...",
+        version,
+        server_ids.join(", ")
+    );
+
+    let mut deployer = build_deployer(targets, deploy_config, Some(history), &version);
+    let records = deployer
+        .rollout(server_ids, "zerospider")
         .await
         .context("Deployment failed")?;
 
-    println!("✅ Deployed successfully to {}", server_id);
+    for record in &records {
+        println!(
+            "✅ Deployed {} to {} (sha256 {})",
+            record.version,
+            record.target_id,
+            record.sha256.as_deref().unwrap_or("n/a")
+        );
+    }
 
     Ok(())
 }
 
-/// Handle status command.
-async fn handle_status(
+/// Handle status command, answered from the local deployment history.
+fn handle_status(
     server_id: &str,
     targets: &[crate::deploy::remote::DeploymentTarget],
+    history: &DeploymentHistory,
 ) -> Result<()> {
-    let target = find_target(server_id, targets)?;
+    find_target(server_id, targets)?;
 
     info!("Checking deployment status for {}...", server_id);
 
-    let mut deployer = RemoteDeployer::new(DeploymentMode::Direct);
-    deployer.register_target(target.clone());
+    let mut deployer = RemoteDeployer::new(DeploymentMode::Direct).with_history(history.clone());
+    for target in targets {
+        deployer.register_target(target.clone());
+    }
 
     let status = deployer
         .get_status(server_id)
@@ -175,36 +215,78 @@ async fn handle_status(
 
     println!("📊 Deployment Status for {}", server_id);
     print_status(status);
+    if let Some(last) = history.for_target(server_id)?.last() {
+        println!("   Last change: {}", describe_record(last));
+    }
 
     Ok(())
 }
 
 /// Print deployment status.
 fn print_status(status: &DeploymentStatus) {
-    println!("   Status: {:?}", status.target_id);
+    println!("   Target: {}", status.target_id);
     println!("   Deployed: {}", status.deployed);
     if let Some(version) = &status.version {
         println!("   Version: {}", version);
     }
-    println!("   Running: {}", status.running);
-    if status.healthy() {
-        println!("   Health: ✅ Healthy");
-    } else {
-        println!("   Health: ❌ Unhealthy");
+}
+
+/// One-line summary of a history entry.
+fn describe_record(record: &DeploymentRecord) -> String {
+    let outcome = match record.outcome {
+        DeploymentOutcome::Deployed => "deployed",
+        DeploymentOutcome::RolledBack => "rolled back to",
+        DeploymentOutcome::Failed => "failed to deploy",
+    };
+    let mut line = format!(
+        "{} {} {} {} on {} by {}",
+        record.deployed_at.format("%Y-%m-%d %H:%M:%S UTC"),
+        record.target_id,
+        outcome,
+        record.version,
+        record.host,
+        record.deployed_by
+    );
+    if let Some(detail) = &record.detail {
+        let _ = write!(line, " ({detail})");
     }
+    line
+}
+
+/// Handle history command.
+fn handle_history(
+    server_id: Option<&str>,
+    limit: usize,
+    history: &DeploymentHistory,
+) -> Result<()> {
+    let records = match server_id {
+        Some(server_id) => history.for_target(server_id)?,
+        None => history.load()?,
+    };
+    if records.is_empty() {
+        println!("No deployments recorded.");
+        return Ok(());
+    }
+
+    println!("📜 Deployment History ({} total):", records.len());
+    for record in records.iter().rev().take(limit) {
+        println!("  {}", describe_record(record));
+    }
+
+    Ok(())
 }
 
 /// Handle health-check command.
 async fn handle_health_check(
     server_id: &str,
     targets: &[crate::deploy::remote::DeploymentTarget],
+    deploy_config: &crate::config::DeployConfig,
 ) -> Result<()> {
-    let target = find_target(server_id, targets)?;
+    find_target(server_id, targets)?;
 
     info!("Running health check for {}...", server_id);
 
-    let mut deployer = RemoteDeployer::new(DeploymentMode::Direct);
-    deployer.register_target(target.clone());
+    let mut deployer = build_deployer(targets, deploy_config, None, env!("CARGO_PKG_VERSION"));
 
     let healthy = deployer
         .health_check(server_id)
@@ -248,24 +330,30 @@ fn handle_list(targets: &[crate::deploy::remote::DeploymentTarget]) -> Result<()
     Ok(())
 }
 
-/// Handle rollback command.
+/// Handle rollback command: switch back to the previously deployed release.
 async fn handle_rollback(
     server_id: &str,
     targets: &[crate::deploy::remote::DeploymentTarget],
+    deploy_config: &crate::config::DeployConfig,
+    history: DeploymentHistory,
 ) -> Result<()> {
-    let target = find_target(server_id, targets)?;
+    find_target(server_id, targets)?;
 
     info!("Rolling back deployment on {}...", server_id);
 
-    let mut deployer = RemoteDeployer::new(DeploymentMode::Direct);
-    deployer.register_target(target.clone());
+    let mut deployer = build_deployer(
+        targets,
+        deploy_config,
+        Some(history),
+        env!("CARGO_PKG_VERSION"),
+    );
 
-    deployer
-        .rollback(server_id)
+    let record = deployer
+        .rollback(server_id, "zerospider")
         .await
         .context("Rollback failed")?;
 
-    println!("✅ Rolled back successfully on {}", server_id);
+    println!("✅ Rolled back {} to {}", server_id, record.version);
 
     Ok(())
 }
@@ -275,41 +363,17 @@ async fn handle_update(
     server_id: &str,
     version: Option<String>,
     targets: &[crate::deploy::remote::DeploymentTarget],
-    deploy_config: crate::config::DeployConfig,
+    deploy_config: &crate::config::DeployConfig,
+    history: DeploymentHistory,
 ) -> Result<()> {
-    let target = find_target(server_id, targets)?;
-
-    let version = version.unwrap_or_else(|| "latest".to_string());
-
-    info!("Updating {} to version {}...", server_id, version);
-
-    // For now, update is essentially the same as deploy
-    // In a more sophisticated implementation, this would:
-    // 1. Check current version
-    // 2. Download new binary
-    // 3. Perform rolling update
-
-    println!(
-        "ℹ️  Update will deploy version {} to {}",
-        version, server_id
-    );
-    println!("   This is equivalent to a deploy operation with the specified version.");
-
-    let mode = parse_deployment_mode(&deploy_config.settings.mode);
-    let mut deployer = RemoteDeployer::new(mode);
-    deployer.register_target(target.clone());
-
-    let dep_config = create_deployment_config(&deploy_config.settings, &version);
-    deployer.set_config(dep_config);
-
-    deployer
-        .deploy(server_id, "zerospider")
-        .await
-        .context("Update failed")?;
-
-    println!("✅ Updated successfully to {} on {}", version, server_id);
-
-    Ok(())
+    handle_deploy(
+        &[server_id.to_string()],
+        version,
+        targets,
+        deploy_config,
+        history,
+    )
+    .await
 }
 
 /// Handle sync-config command.
@@ -0,0 +1,204 @@
+//! Local deployment ledger.
+//!
+//! Every deploy, rollback and failed rollout is appended to a JSON file on
+//! the operator's machine so `deploy status`, `deploy history` and
+//! `deploy rollback` survive across CLI invocations.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Ledger location relative to the workspace.
+const HISTORY_FILE: &str = "state/deployments.json";
+
+/// What happened to a target in one ledger entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeploymentOutcome {
+    /// The release was switched in and passed its health gate.
+    Deployed,
+    /// The target was switched back to an earlier release.
+    RolledBack,
+    /// The deploy failed before or during the switch.
+    Failed,
+}
+
+/// One ledger entry: who deployed which version to which target, and when.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentRecord {
+    pub target_id: String,
+    pub host: String,
+    pub version: String,
+    /// Release directory name on the target, or the image tag for docker.
+    #[serde(default)]
+    pub release: Option<String>,
+    /// SHA-256 of the uploaded binary, as verified on the target.
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Release that was live before this entry, if any.
+    #[serde(default)]
+    pub previous_release: Option<String>,
+    pub deployed_by: String,
+    pub deployed_at: DateTime<Utc>,
+    pub outcome: DeploymentOutcome,
+    #[serde(default)]
+    pub detail: Option<String>,
+}
+
+/// Append-only deployment ledger stored as a JSON array.
+#[derive(Debug, Clone)]
+pub struct DeploymentHistory {
+    path: PathBuf,
+}
+
+impl DeploymentHistory {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Ledger kept under the workspace's `state/` directory.
+    pub fn for_workspace(workspace_dir: &Path) -> Self {
+        Self::new(workspace_dir.join(HISTORY_FILE))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// All records, oldest first.
+    pub fn load(&self) -> Result<Vec<DeploymentRecord>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let raw = fs::read_to_string(&self.path)?;
+        serde_json::from_str(&raw)
+            .with_context(|| format!("Invalid deployment history at {}", self.path.display()))
+    }
+
+    pub fn append(&self, record: DeploymentRecord) -> Result<()> {
+        let mut records = self.load()?;
+        records.push(record);
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write-then-rename so an interrupted CLI never truncates the ledger.
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(&records)?)?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    /// Records for one target, oldest first.
+    pub fn for_target(&self, target_id: &str) -> Result<Vec<DeploymentRecord>> {
+        Ok(self
+            .load()?
+            .into_iter()
+            .filter(|record| record.target_id == target_id)
+            .collect())
+    }
+
+    /// The entry describing what is currently live on `target_id`.
+    pub fn live(&self, target_id: &str) -> Result<Option<DeploymentRecord>> {
+        Ok(self
+            .for_target(target_id)?
+            .into_iter()
+            .rev()
+            .find(|record| record.outcome != DeploymentOutcome::Failed))
+    }
+
+    /// The most recent successful deploy whose release differs from the
+    /// live one: the release `deploy rollback` returns to.
+    pub fn rollback_candidate(&self, target_id: &str) -> Result<Option<DeploymentRecord>> {
+        let records = self.for_target(target_id)?;
+        let Some(live_idx) = records
+            .iter()
+            .rposition(|record| record.outcome != DeploymentOutcome::Failed)
+        else {
+            return Ok(None);
+        };
+        let live = &records[live_idx];
+        // A release we already rolled away from is not a candidate again.
+        let mut abandoned = HashSet::new();
+        for record in records[..=live_idx].iter().rev() {
+            match record.outcome {
+                DeploymentOutcome::RolledBack => {
+                    abandoned.extend(record.previous_release.clone());
+                }
+                DeploymentOutcome::Deployed
+                    if record.release != live.release
+                        && !record
+                            .release
+                            .as_ref()
+                            .is_some_and(|release| abandoned.contains(release)) =>
+                {
+                    return Ok(Some(record.clone()));
+                }
+                _ => {}
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// `user@host` of the operator running the deploy.
+pub fn operator() -> String {
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".into());
+    let host =
+        hostname::get().map_or_else(|_| "unknown".into(), |h| h.to_string_lossy().to_string());
+    format!("{user}@{host}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn record(version: &str, outcome: DeploymentOutcome) -> DeploymentRecord {
+        DeploymentRecord {
+            target_id: "prod".into(),
+            host: "10.0.0.1".into(),
+            version: version.into(),
+            release: Some(version.into()),
+            sha256: None,
+            previous_release: None,
+            deployed_by: "ops@laptop".into(),
+            deployed_at: Utc::now(),
+            outcome,
+            detail: None,
+        }
+    }
+
+    #[test]
+    fn history_persists_and_picks_rollback_candidate() {
+        let tmp = TempDir::new().unwrap();
+        let history = DeploymentHistory::for_workspace(tmp.path());
+        assert!(history.live("prod").unwrap().is_none());
+
+        history
+            .append(record("1.0.0", DeploymentOutcome::Deployed))
+            .unwrap();
+        history
+            .append(record("1.1.0", DeploymentOutcome::Deployed))
+            .unwrap();
+        history
+            .append(record("1.2.0", DeploymentOutcome::Failed))
+            .unwrap();
+
+        let reopened = DeploymentHistory::for_workspace(tmp.path());
+        assert_eq!(reopened.load().unwrap().len(), 3);
+        assert_eq!(reopened.live("prod").unwrap().unwrap().version, "1.1.0");
+        assert_eq!(
+            reopened
+                .rollback_candidate("prod")
+                .unwrap()
+                .unwrap()
+                .version,
+            "1.0.0"
+        );
+    }
+}
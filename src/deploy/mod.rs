@@ -1,6 +1,7 @@
 //! Deployment module for remote server management.
 
 pub mod cli;
+pub mod history;
 pub mod remote;
 
 pub use remote::{
@@ -11,11 +12,15 @@ pub use remote::{
 /// DeployCommands for deploy subcommands.
 #[derive(clap::Parser, Debug)]
 pub enum DeployCommands {
-    /// Deploy ZeroClaw to a remote server
+    /// Deploy ZeroClaw to one or more servers, in order, stopping at the
+    /// first server that fails its health check
     Deploy {
-        /// Server ID to deploy to
-        #[arg(short, long)]
-        server: String,
+        /// Server ID to deploy to (repeat for a rollout across servers)
+        #[arg(short, long, required = true)]
+        server: Vec<String>,
+        /// Release version (default: this build's version)
+        #[arg(long)]
+        version: Option<String>,
     },
     /// Show deployment status for a server
     Status {
@@ -31,6 +36,15 @@ pub enum DeployCommands {
     },
     /// List all configured deployment targets
     List,
+    /// Show recorded deployments, newest first
+    History {
+        /// Only show deployments to this server
+        #[arg(short, long)]
+        server: Option<String>,
+        /// Maximum number of entries to show
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
     /// Rollback to previous deployment
     Rollback {
         /// Server ID to rollback
@@ -42,7 +56,7 @@ pub enum DeployCommands {
         /// Server ID to update
         #[arg(short, long)]
         server: String,
-        /// Version to deploy (default: latest)
+        /// Version to deploy (default: this build's version)
         #[arg(long)]
         version: Option<String>,
     },
@@ -1,13 +1,19 @@
 //! Controlled remote deployment module.
 //!
 //! Provides secure remote server deployment capabilities:
-//! - SSH-based deployment
-//! - Configuration-driven setup
-//! - Health monitoring
-//! - Rollback support
-
+//! - SSH-based deployment into versioned release directories
+//! - SHA-256 verification of the uploaded binary
+//! - Atomic `current` symlink switch
+//! - Health-gated rollout with automatic rollback
+//! - Persistent deployment history (see [`super::history`])
+
+use super::history::{operator, DeploymentHistory, DeploymentOutcome, DeploymentRecord};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
 
 #[derive(Debug, Clone)]
@@ -53,12 +59,15 @@ pub struct DeploymentConfig {
     pub name: String,
     pub version: String,
     pub local_binary: PathBuf, // Local binary path (auto-detected)
-    pub binary_path: PathBuf,  // Remote binary path
+    pub binary_path: PathBuf,  // Remote binary path (symlink into the live release)
     pub config_path: Option<PathBuf>,
     pub env_vars: HashMap<String, String>,
-    pub working_dir: PathBuf,
+    pub working_dir: PathBuf, // Holds `releases/<version>/` and the `current` symlink
     pub auto_start: bool,
     pub health_check_interval: Duration,
+    pub health_check_retries: u32,
+    pub health_command: Option<String>, // Overrides the per-mode health probe
+    pub restart_command: Option<String>, // Overrides the per-mode restart
     pub restart_on_failure: bool,
     pub max_restarts: u32,
     pub use_sudo: bool, // Whether to use sudo for remote commands
@@ -73,7 +82,7 @@ impl Default for DeploymentConfig {
 
         Self {
             name: "zerospider".to_string(),
-            version: "latest".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
             local_binary,
             binary_path: PathBuf::from("/usr/local/bin/zerospider"),
             config_path: None,
@@ -81,6 +90,9 @@ impl Default for DeploymentConfig {
             working_dir: PathBuf::from("/opt/zerospider"),
             auto_start: true,
             health_check_interval: Duration::from_secs(30),
+            health_check_retries: 3,
+            health_command: None,
+            restart_command: None,
             restart_on_failure: true,
             max_restarts: 3,
             use_sudo: true, // Default to true for systemd/docker modes
@@ -143,11 +155,165 @@ pub enum DeploymentMode {
     Systemd,
 }
 
+/// How commands and files reach a target.
+///
+/// [`SshExecutor`] is the production transport; tests substitute a local
+/// shell so the release layout can be exercised without a remote host.
+#[async_trait]
+pub trait RemoteExecutor: Send + Sync {
+    /// Run a shell command on the target and return its stdout.
+    async fn run(
+        &self,
+        target: &DeploymentTarget,
+        command: &str,
+        timeout: Duration,
+    ) -> Result<String>;
+
+    /// Copy a local file to `remote` on the target.
+    async fn upload(&self, target: &DeploymentTarget, local: &Path, remote: &Path) -> Result<()>;
+}
+
+/// Runs commands over `ssh` and uploads with `scp`.
+pub struct SshExecutor;
+
+impl SshExecutor {
+    fn common_args(target: &DeploymentTarget, port_flag: &str) -> Vec<String> {
+        let mut args = vec![
+            "-o".to_string(),
+            "StrictHostKeyChecking=no".to_string(),
+            port_flag.to_string(),
+            target.port.to_string(),
+        ];
+        if let Some(key) = &target.ssh_key_path {
+            args.push("-i".to_string());
+            args.push(key.display().to_string());
+        }
+        args
+    }
+}
+
+#[async_trait]
+impl RemoteExecutor for SshExecutor {
+    async fn run(
+        &self,
+        target: &DeploymentTarget,
+        command: &str,
+        timeout: Duration,
+    ) -> Result<String> {
+        let ssh_target = format!("{}@{}:{}", target.user, target.host, target.port);
+
+        tracing::info!(target = %ssh_target, command = %command, "Executing remote command");
+
+        let output = tokio::time::timeout(
+            timeout,
+            tokio::process::Command::new("ssh")
+                .args(Self::common_args(target, "-p"))
+                .arg(format!("{}@{}", target.user, target.host))
+                .arg(command)
+                .output(),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("Command timeout: {}", e))?
+        .map_err(|e| anyhow::anyhow!("SSH execution failed: {}", e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Command failed: {}", stderr);
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    async fn upload(&self, target: &DeploymentTarget, local: &Path, remote: &Path) -> Result<()> {
+        let output = tokio::process::Command::new("scp")
+            .args(Self::common_args(target, "-P"))
+            .arg(local)
+            .arg(format!(
+                "{}@{}:{}",
+                target.user,
+                target.host,
+                remote.display()
+            ))
+            .output()
+            .await
+            .context("scp execution failed")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Upload failed: {}", stderr);
+        }
+
+        Ok(())
+    }
+}
+
+/// Paths of one release on the target.
+///
+/// ```text
+/// <working_dir>/releases/<version>/<binary>   uploaded, verified binary
+/// <working_dir>/current -> releases/<version> switched atomically
+/// <binary_path> -> <working_dir>/current/<binary>
+/// ```
+struct ReleaseLayout {
+    releases_dir: PathBuf,
+    current: PathBuf,
+    binary_name: String,
+}
+
+impl ReleaseLayout {
+    fn new(config: &DeploymentConfig) -> Self {
+        Self {
+            releases_dir: config.working_dir.join("releases"),
+            current: config.working_dir.join("current"),
+            binary_name: config
+                .binary_path
+                .file_name()
+                .map_or_else(|| config.name.clone(), |n| n.to_string_lossy().into_owned()),
+        }
+    }
+
+    fn release_dir(&self, release: &str) -> PathBuf {
+        self.releases_dir.join(release)
+    }
+}
+
+/// Quote `value` for a POSIX shell.
+fn sh_quote(value: impl AsRef<str>) -> String {
+    format!("'{}'", value.as_ref().replace('\'', r"'\''"))
+}
+
+fn path_arg(path: &Path) -> String {
+    sh_quote(path.to_string_lossy())
+}
+
+/// Release names become directory names and shell arguments, so keep them
+/// to a conservative character set.
+fn validate_version(version: &str) -> Result<()> {
+    let valid = !version.is_empty()
+        && version != "."
+        && version != ".."
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+'));
+    if !valid {
+        bail!("Invalid release version '{version}': use letters, digits, '.', '-', '_' or '+'");
+    }
+    Ok(())
+}
+
+fn sha256_file(path: &Path) -> Result<String> {
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("Failed to read local binary {}", path.display()))?;
+    Ok(hex::encode(Sha256::digest(&bytes)))
+}
+
 pub struct RemoteDeployer {
     targets: HashMap<String, DeploymentTarget>,
     configs: HashMap<String, DeploymentConfig>,
     statuses: HashMap<String, DeploymentStatus>,
     mode: DeploymentMode,
+    executor: Arc<dyn RemoteExecutor>,
+    history: Option<DeploymentHistory>,
 }
 
 impl RemoteDeployer {
@@ -157,17 +323,35 @@ impl RemoteDeployer {
             configs: HashMap::new(),
             statuses: HashMap::new(),
             mode,
+            executor: Arc::new(SshExecutor),
+            history: None,
         }
     }
 
+    /// Replace the SSH transport (used by tests).
+    pub fn with_executor(mut self, executor: Arc<dyn RemoteExecutor>) -> Self {
+        self.executor = executor;
+        self
+    }
+
+    /// Record deployments in `history` and restore target status from it.
+    pub fn with_history(mut self, history: DeploymentHistory) -> Self {
+        self.history = Some(history);
+        self
+    }
+
     pub fn register_target(&mut self, target: DeploymentTarget) {
         let id = target.id.clone();
+        let live = self
+            .history
+            .as_ref()
+            .and_then(|history| history.live(&id).ok().flatten());
         self.statuses.insert(
             id.clone(),
             DeploymentStatus {
                 target_id: id.clone(),
-                deployed: false,
-                version: None,
+                deployed: live.is_some(),
+                version: live.map(|record| record.version),
                 running: false,
                 last_health_check: None,
                 uptime: None,
@@ -181,177 +365,370 @@ impl RemoteDeployer {
         self.configs.insert(config.name.clone(), config);
     }
 
-    pub async fn deploy(&mut self, target_id: &str, config_name: &str) -> anyhow::Result<()> {
-        let target = self
-            .targets
+    fn target(&self, target_id: &str) -> Result<DeploymentTarget> {
+        self.targets
             .get(target_id)
-            .ok_or_else(|| anyhow::anyhow!("Target not found: {}", target_id))?
-            .clone();
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Target not found: {}", target_id))
+    }
 
-        let config = self
-            .configs
+    fn config(&self, config_name: &str) -> Result<DeploymentConfig> {
+        self.configs
             .get(config_name)
-            .ok_or_else(|| anyhow::anyhow!("Config not found: {}", config_name))?
-            .clone();
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Config not found: {}", config_name))
+    }
 
-        let steps = self.build_deployment_steps(&target, &config);
+    /// Deploy `config.version` to one target, gated on its health check.
+    ///
+    /// A release that fails the health gate is switched back to the release
+    /// that was live before, and the error is returned.
+    pub async fn deploy(&mut self, target_id: &str, config_name: &str) -> Result<DeploymentRecord> {
+        let target = self.target(target_id)?;
+        let config = self.config(config_name)?;
+        validate_version(&config.version)?;
+
+        let previous = self.live_release(&target, &config).await?;
+        let mut record = self.new_record(&target, &config.version, DeploymentOutcome::Deployed);
+        record.previous_release = previous.clone();
+
+        if let Err(e) = self.install_and_switch(&target, &config, &mut record).await {
+            tracing::error!(target = %target_id, error = %e, "Deployment failed");
+            record.outcome = DeploymentOutcome::Failed;
+            record.detail = Some(format!("{e:#}"));
+            self.record(record)?;
+            return Err(e);
+        }
 
-        for step in steps {
-            match self.execute_step(&target, &step).await {
-                Ok(_) => {
-                    tracing::info!(step = %step.name, target = %target_id, "Step completed");
-                }
-                Err(e) => {
-                    tracing::error!(step = %step.name, target = %target_id, error = %e, "Step failed");
+        if !self.health_gate(&target, &config).await {
+            record.outcome = DeploymentOutcome::Failed;
+            record.detail = Some("health check failed after switch".into());
+            self.record(record)?;
+
+            let Some(previous) = previous else {
+                self.set_status(target_id, None, false);
+                bail!(
+                    "Health check failed on {target_id} after deploying {} and no earlier release exists to roll back to",
+                    config.version
+                );
+            };
+            tracing::warn!(target = %target_id, release = %previous, "Health check failed, rolling back");
+            self.activate(&target, &config, &previous)
+                .await
+                .with_context(|| format!("Automatic rollback to {previous} failed"))?;
+            let mut rollback = self.new_record(&target, &previous, DeploymentOutcome::RolledBack);
+            rollback.previous_release = Some(config.version.clone());
+            rollback.detail = Some("automatic rollback after failed health check".into());
+            self.record(rollback)?;
+            self.set_status(target_id, Some(previous.clone()), false);
+            bail!(
+                "Health check failed on {target_id} after deploying {}; rolled back to {previous}",
+                config.version
+            );
+        }
 
-                    if let Some(rollback) = &step.rollback_command {
-                        tracing::info!(target = %target_id, "Executing rollback");
-                        let _ = self.execute_raw(&target, rollback).await;
-                    }
+        self.set_status(target_id, Some(config.version.clone()), config.auto_start);
+        self.record(record.clone())?;
+        Ok(record)
+    }
 
-                    return Err(e);
-                }
+    /// Deploy to each target in order, stopping at the first one that fails.
+    ///
+    /// Targets already updated keep the new release; the failing target is
+    /// rolled back by [`Self::deploy`].
+    pub async fn rollout(
+        &mut self,
+        target_ids: &[String],
+        config_name: &str,
+    ) -> Result<Vec<DeploymentRecord>> {
+        let mut deployed = Vec::with_capacity(target_ids.len());
+        for target_id in target_ids {
+            match self.deploy(target_id, config_name).await {
+                Ok(record) => deployed.push(record),
+                Err(e) => bail!(
+                    "Rollout halted at {target_id} after {} of {} target(s): {e:#}",
+                    deployed.len(),
+                    target_ids.len()
+                ),
             }
         }
+        Ok(deployed)
+    }
 
-        if let Some(status) = self.statuses.get_mut(target_id) {
-            status.deployed = true;
-            status.version = Some(config.version.clone());
-            status.running = config.auto_start;
+    /// Upload, verify and switch to `config.version`.
+    async fn install_and_switch(
+        &self,
+        target: &DeploymentTarget,
+        config: &DeploymentConfig,
+        record: &mut DeploymentRecord,
+    ) -> Result<()> {
+        if matches!(self.mode, DeploymentMode::Docker) {
+            return self.activate(target, config, &config.version).await;
         }
 
-        Ok(())
+        let layout = ReleaseLayout::new(config);
+        let release_dir = layout.release_dir(&config.version);
+        let local_sha = sha256_file(&config.local_binary)?;
+        let staging = PathBuf::from(format!(
+            "/tmp/{}-{}-{}.upload",
+            layout.binary_name,
+            config.version,
+            std::process::id()
+        ));
+
+        self.executor
+            .upload(target, &config.local_binary, &staging)
+            .await
+            .context("Failed to upload binary")?;
+
+        let output = self
+            .run(
+                target,
+                &format!("sha256sum {}", path_arg(&staging)),
+                Duration::from_secs(60),
+            )
+            .await?;
+        let remote_sha = output.split_whitespace().next().unwrap_or_default();
+        if remote_sha != local_sha {
+            let _ = self
+                .run(
+                    target,
+                    &format!("rm -f {}", path_arg(&staging)),
+                    Duration::from_secs(60),
+                )
+                .await;
+            bail!("Checksum mismatch after upload: expected {local_sha}, target has {remote_sha}");
+        }
+        record.sha256 = Some(local_sha);
+
+        let install = format!(
+            "mkdir -p {dir} && chmod 755 {staging} && mv -f {staging} {bin}",
+            dir = path_arg(&release_dir),
+            staging = path_arg(&staging),
+            bin = path_arg(&release_dir.join(&layout.binary_name)),
+        );
+        self.execute_step(
+            target,
+            &DeploymentStep::new("install_release", self.privileged(config, &install)),
+        )
+        .await?;
+
+        self.activate(target, config, &config.version).await
     }
 
-    fn build_deployment_steps(
+    /// Make `release` the live release and (re)start the service.
+    async fn activate(
         &self,
         target: &DeploymentTarget,
         config: &DeploymentConfig,
-    ) -> Vec<DeploymentStep> {
-        let sudo_prefix = if config.use_sudo { "sudo" } else { "" };
-        match self.mode {
-            DeploymentMode::Direct => vec![
-                DeploymentStep::new(
-                    "create_dir",
-                    format!("{} mkdir -p {}", sudo_prefix, config.working_dir.display()),
-                )
-                .with_rollback(format!("rm -rf {}", config.working_dir.display())),
-                DeploymentStep::new(
-                    "upload_binary",
-                    format!(
-                        "scp {} {}@{}:{}",
-                        config.local_binary.display(),
-                        target.user,
-                        target.host,
-                        config.binary_path.display()
-                    ),
-                ),
-                DeploymentStep::new(
-                    "make_executable",
-                    format!("{} chmod +x {}", sudo_prefix, config.binary_path.display()),
-                ),
-                DeploymentStep::new(
-                    "start_service",
-                    format!(
-                        "cd {} && {} {} &",
-                        config.working_dir.display(),
-                        sudo_prefix,
-                        config.binary_path.display()
-                    ),
-                ),
-            ],
-            DeploymentMode::Docker => vec![
+        release: &str,
+    ) -> Result<()> {
+        for step in self.activation_steps(config, release) {
+            self.execute_step(target, &step)
+                .await
+                .with_context(|| format!("Step '{}' failed on {}", step.name, target.id))?;
+            tracing::info!(step = %step.name, target = %target.id, "Step completed");
+        }
+        Ok(())
+    }
+
+    fn activation_steps(&self, config: &DeploymentConfig, release: &str) -> Vec<DeploymentStep> {
+        let mut steps = Vec::new();
+        if let DeploymentMode::Docker = self.mode {
+            let sudo_prefix = if config.use_sudo { "sudo " } else { "" };
+            steps.push(
                 DeploymentStep::new(
                     "pull_image",
-                    format!("docker pull zerospider:{}", config.version),
-                ),
-                DeploymentStep::new(
-                    "stop_existing",
-                    "docker stop zerospider || true".to_string(),
-                ),
-                DeploymentStep::new(
-                    "remove_existing",
-                    "docker rm zerospider || true".to_string(),
-                ),
-                DeploymentStep::new(
-                    "run_container",
-                    format!(
-                        "docker run -d --name zerospider -p 8080:8080 zerospider:{}",
-                        config.version
-                    ),
-                ),
-            ],
-            DeploymentMode::Systemd => vec![
-                DeploymentStep::new(
-                    "upload_binary",
-                    format!(
-                        "scp {} {}@{}:{}",
-                        config.local_binary.display(),
-                        target.user,
-                        target.host,
-                        config.binary_path.display()
-                    ),
-                ),
-                DeploymentStep::new(
-                    "install_service",
-                    format!("{} systemctl daemon-reload", sudo_prefix).to_string(),
-                ),
-                DeploymentStep::new(
-                    "enable_service",
-                    format!("{} systemctl enable zerospider", sudo_prefix).to_string(),
+                    format!("{sudo_prefix}docker pull zerospider:{release}"),
+                )
+                .with_timeout(Duration::from_secs(600)),
+            );
+            steps.push(DeploymentStep::new(
+                "replace_container",
+                format!(
+                    "{sudo_prefix}docker stop zerospider || true; {sudo_prefix}docker rm zerospider || true; \
+                     {sudo_prefix}docker run -d --name zerospider -p 8080:8080 zerospider:{release}"
                 ),
-                DeploymentStep::new(
-                    "start_service",
-                    format!("{} systemctl start zerospider", sudo_prefix).to_string(),
+            ));
+            return steps;
+        }
+
+        let layout = ReleaseLayout::new(config);
+        let current = path_arg(&layout.current);
+        let current_next = path_arg(&layout.current.with_extension("next"));
+        let binary = path_arg(&config.binary_path);
+        let binary_next = path_arg(&config.binary_path.with_extension("next"));
+        let binary_parent = config
+            .binary_path
+            .parent()
+            .map_or_else(|| "/".to_string(), path_arg);
+
+        // `mv -T` is a rename(2), so readers see either the old or the new
+        // release and never a missing link.
+        let switch = format!(
+            "test -x {bin} && ln -sfn {dir} {current_next} && mv -Tf {current_next} {current} \
+             && mkdir -p {binary_parent} && ln -sfn {link} {binary_next} && mv -Tf {binary_next} {binary}",
+            bin = path_arg(&layout.release_dir(release).join(&layout.binary_name)),
+            dir = path_arg(&layout.release_dir(release)),
+            link = path_arg(&layout.current.join(&layout.binary_name)),
+        );
+        steps.push(DeploymentStep::new(
+            "switch_release",
+            self.privileged(config, &switch),
+        ));
+
+        if config.auto_start {
+            let restart = config.restart_command.clone().unwrap_or_else(|| match self.mode {
+                DeploymentMode::Systemd => "systemctl daemon-reload && systemctl enable zerospider \
+                     && systemctl restart zerospider"
+                    .to_string(),
+                _ => format!(
+                    "pkill -x {name} || true; cd {wd} && nohup {binary} daemon >> zerospider.log 2>&1 < /dev/null &",
+                    name = sh_quote(&layout.binary_name),
+                    wd = path_arg(&config.working_dir),
                 ),
-            ],
+            });
+            steps.push(DeploymentStep::new(
+                "restart_service",
+                self.privileged(config, &restart),
+            ));
+        }
+        steps
+    }
+
+    /// Wrap `command` in `sudo sh -c` when the config asks for it.
+    fn privileged(&self, config: &DeploymentConfig, command: &str) -> String {
+        if config.use_sudo {
+            format!("sudo sh -c {}", sh_quote(command))
+        } else {
+            command.to_string()
         }
     }
 
-    async fn execute_step(
+    /// The release the target currently serves, if any.
+    async fn live_release(
         &self,
         target: &DeploymentTarget,
-        step: &DeploymentStep,
-    ) -> anyhow::Result<()> {
-        self.execute_raw(target, &step.command).await
+        config: &DeploymentConfig,
+    ) -> Result<Option<String>> {
+        if let DeploymentMode::Docker = self.mode {
+            return Ok(self
+                .history
+                .as_ref()
+                .and_then(|history| history.live(&target.id).ok().flatten())
+                .map(|record| record.version));
+        }
+        let layout = ReleaseLayout::new(config);
+        let output = self
+            .run(
+                target,
+                &format!("readlink {} || true", path_arg(&layout.current)),
+                Duration::from_secs(60),
+            )
+            .await?;
+        Ok(Path::new(output.trim())
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned()))
     }
 
-    async fn execute_raw(&self, target: &DeploymentTarget, command: &str) -> anyhow::Result<()> {
-        let ssh_target = format!("{}@{}:{}", target.user, target.host, target.port);
+    fn health_command(&self, config: &DeploymentConfig) -> String {
+        if let Some(command) = &config.health_command {
+            return command.clone();
+        }
+        match self.mode {
+            DeploymentMode::Direct => "pgrep -x zerospider > /dev/null".to_string(),
+            DeploymentMode::Systemd => "systemctl is-active --quiet zerospider".to_string(),
+            DeploymentMode::Docker => {
+                "docker inspect -f '{{.State.Running}}' zerospider | grep -q true".to_string()
+            }
+        }
+    }
 
-        tracing::info!(target = %ssh_target, command = %command, "Executing remote command");
+    /// Probe up to `health_check_retries` times, `health_check_interval` apart.
+    async fn health_gate(&self, target: &DeploymentTarget, config: &DeploymentConfig) -> bool {
+        if !config.auto_start && config.health_command.is_none() {
+            // Nothing was started, so there is nothing to probe.
+            return true;
+        }
+        let command = self.health_command(config);
+        let attempts = config.health_check_retries.max(1);
+        for attempt in 1..=attempts {
+            if attempt > 1 {
+                tokio::time::sleep(config.health_check_interval).await;
+            }
+            match self.run(target, &command, Duration::from_secs(60)).await {
+                Ok(_) => return true,
+                Err(e) => {
+                    tracing::warn!(target = %target.id, attempt, error = %e, "Health check failed");
+                }
+            }
+        }
+        false
+    }
 
-        let output = tokio::time::timeout(
-            Duration::from_secs(60),
-            tokio::process::Command::new("ssh")
-                .arg("-o")
-                .arg("StrictHostKeyChecking=no")
-                .arg("-p")
-                .arg(target.port.to_string())
-                .arg(format!("{}@{}", target.user, target.host))
-                .arg(command)
-                .output(),
-        )
-        .await
-        .map_err(|e| anyhow::anyhow!("Command timeout: {}", e))?
-        .map_err(|e| anyhow::anyhow!("SSH execution failed: {}", e))?;
+    async fn execute_step(&self, target: &DeploymentTarget, step: &DeploymentStep) -> Result<()> {
+        self.executor
+            .run(target, &step.command, step.timeout)
+            .await
+            .map(|_| ())
+    }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Command failed: {}", stderr);
+    async fn run(
+        &self,
+        target: &DeploymentTarget,
+        command: &str,
+        timeout: Duration,
+    ) -> Result<String> {
+        self.executor.run(target, command, timeout).await
+    }
+
+    fn new_record(
+        &self,
+        target: &DeploymentTarget,
+        version: &str,
+        outcome: DeploymentOutcome,
+    ) -> DeploymentRecord {
+        DeploymentRecord {
+            target_id: target.id.clone(),
+            host: target.host.clone(),
+            version: version.to_string(),
+            release: Some(version.to_string()),
+            sha256: None,
+            previous_release: None,
+            deployed_by: operator(),
+            deployed_at: chrono::Utc::now(),
+            outcome,
+            detail: None,
         }
+    }
 
-        Ok(())
+    fn record(&self, record: DeploymentRecord) -> Result<()> {
+        match &self.history {
+            Some(history) => history
+                .append(record)
+                .with_context(|| format!("Failed to write {}", history.path().display())),
+            None => Ok(()),
+        }
     }
 
-    pub async fn health_check(&mut self, target_id: &str) -> anyhow::Result<bool> {
-        let target = self
-            .targets
-            .get(target_id)
-            .ok_or_else(|| anyhow::anyhow!("Target not found: {}", target_id))?;
+    fn set_status(&mut self, target_id: &str, version: Option<String>, running: bool) {
+        if let Some(status) = self.statuses.get_mut(target_id) {
+            status.deployed = version.is_some();
+            status.version = version;
+            status.running = running;
+        }
+    }
+
+    pub async fn health_check(&mut self, target_id: &str) -> Result<bool> {
+        let target = self.target(target_id)?;
+        let config = self.configs.values().next().cloned().unwrap_or_default();
 
         let result = self
-            .execute_raw(target, "pgrep -x zerospider > /dev/null")
+            .run(
+                &target,
+                &self.health_command(&config),
+                Duration::from_secs(60),
+            )
             .await;
 
         if let Some(status) = self.statuses.get_mut(target_id) {
@@ -362,36 +739,33 @@ impl RemoteDeployer {
         Ok(result.is_ok())
     }
 
-    pub async fn rollback(&mut self, target_id: &str) -> anyhow::Result<()> {
-        let target = self
-            .targets
-            .get(target_id)
-            .ok_or_else(|| anyhow::anyhow!("Target not found: {}", target_id))?;
-
-        let rollback_steps = match self.mode {
-            DeploymentMode::Direct => vec!["pkill -x zerospider || true", "rm -rf /opt/zerospider"],
-            DeploymentMode::Docker => {
-                vec![
-                    "docker stop zerospider || true",
-                    "docker rm zerospider || true",
-                ]
-            }
-            DeploymentMode::Systemd => vec![
-                "systemctl stop zerospider || true",
-                "systemctl disable zerospider || true",
-            ],
-        };
-
-        for step in rollback_steps {
-            let _ = self.execute_raw(target, step).await;
-        }
-
-        if let Some(status) = self.statuses.get_mut(target_id) {
-            status.deployed = false;
-            status.running = false;
-        }
-
-        Ok(())
+    /// Switch the target back to the release deployed before the live one,
+    /// as recorded in the deployment history.
+    pub async fn rollback(
+        &mut self,
+        target_id: &str,
+        config_name: &str,
+    ) -> Result<DeploymentRecord> {
+        let target = self.target(target_id)?;
+        let config = self.config(config_name)?;
+        let history = self
+            .history
+            .as_ref()
+            .context("Rollback needs a deployment history")?;
+        let live = history.live(target_id)?;
+        let candidate = history.rollback_candidate(target_id)?.ok_or_else(|| {
+            anyhow::anyhow!("No earlier release of {target_id} recorded to roll back to")
+        })?;
+
+        self.activate(&target, &config, &candidate.version).await?;
+
+        let mut record =
+            self.new_record(&target, &candidate.version, DeploymentOutcome::RolledBack);
+        record.sha256 = candidate.sha256;
+        record.previous_release = live.and_then(|record| record.release);
+        self.record(record.clone())?;
+        self.set_status(target_id, Some(candidate.version), config.auto_start);
+        Ok(record)
     }
 
     pub fn get_status(&self, target_id: &str) -> Option<&DeploymentStatus> {
@@ -437,7 +811,263 @@ mod tests {
         assert_eq!(step.timeout, Duration::from_secs(120));
         assert!(step.rollback_command.is_some());
     }
+
+    /// Local SSH stand-in: every target is a directory under `hosts/`, and
+    /// paths under `remote_root` in commands are rebased onto it.
+    struct LocalShell {
+        remote_root: String,
+        hosts_dir: PathBuf,
+        corrupt_uploads: bool,
+    }
+
+    impl LocalShell {
+        fn rebase(&self, target: &DeploymentTarget, text: &str) -> String {
+            let host_root = self.hosts_dir.join(&target.host);
+            text.replace(&self.remote_root, &host_root.to_string_lossy())
+        }
+    }
+
+    #[async_trait]
+    impl RemoteExecutor for LocalShell {
+        async fn run(
+            &self,
+            target: &DeploymentTarget,
+            command: &str,
+            timeout: Duration,
+        ) -> Result<String> {
+            let output = tokio::time::timeout(
+                timeout,
+                tokio::process::Command::new("sh")
+                    .arg("-c")
+                    .arg(self.rebase(target, command))
+                    .output(),
+            )
+            .await??;
+            if !output.status.success() {
+                bail!(
+                    "Command failed: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        }
+
+        async fn upload(
+            &self,
+            target: &DeploymentTarget,
+            local: &Path,
+            remote: &Path,
+        ) -> Result<()> {
+            let remote = PathBuf::from(self.rebase(target, &remote.to_string_lossy()));
+            if self.corrupt_uploads {
+                tokio::fs::write(&remote, b"truncated").await?;
+            } else {
+                tokio::fs::copy(local, &remote).await?;
+            }
+            Ok(())
+        }
+    }
+
+    struct Fixture {
+        _tmp: tempfile::TempDir,
+        remote_root: PathBuf,
+        hosts_dir: PathBuf,
+        local_binary: PathBuf,
+        history: DeploymentHistory,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let tmp = tempfile::TempDir::new().unwrap();
+            let hosts_dir = tmp.path().join("hosts");
+            for host in ["host-a", "host-b", "host-c"] {
+                std::fs::create_dir_all(hosts_dir.join(host)).unwrap();
+            }
+            let fixture = Self {
+                remote_root: tmp.path().join("remote"),
+                hosts_dir,
+                local_binary: tmp.path().join("zerospider"),
+                history: DeploymentHistory::for_workspace(&tmp.path().join("workspace")),
+                _tmp: tmp,
+            };
+            fixture.build("healthy v1");
+            fixture
+        }
+
+        fn build(&self, marker: &str) {
+            std::fs::write(&self.local_binary, format!("#!/bin/sh\n# {marker}\n")).unwrap();
+        }
+
+        fn host(&self, host: &str) -> PathBuf {
+            self.hosts_dir.join(host)
+        }
+
+        fn deployer(&self, corrupt_uploads: bool) -> RemoteDeployer {
+            let executor = LocalShell {
+                remote_root: self.remote_root.to_string_lossy().into_owned(),
+                hosts_dir: self.hosts_dir.clone(),
+                corrupt_uploads,
+            };
+            let mut deployer = RemoteDeployer::new(DeploymentMode::Direct)
+                .with_executor(Arc::new(executor))
+                .with_history(self.history.clone());
+            for (id, host) in [("a", "host-a"), ("b", "host-b"), ("c", "host-c")] {
+                deployer.register_target(DeploymentTarget::new(id, host, "deploy"));
+            }
+            deployer
+        }
+
+        fn config(&self, version: &str) -> DeploymentConfig {
+            let binary_path = self.remote_root.join("bin/zerospider");
+            DeploymentConfig {
+                version: version.into(),
+                local_binary: self.local_binary.clone(),
+                binary_path: binary_path.clone(),
+                working_dir: self.remote_root.join("srv"),
+                health_check_interval: Duration::ZERO,
+                health_check_retries: 2,
+                health_command: Some(format!(
+                    "test ! -f {root}/unhealthy && grep -q healthy {bin}",
+                    root = self.remote_root.display(),
+                    bin = binary_path.display()
+                )),
+                restart_command: Some("true".into()),
+                use_sudo: false,
+                ..DeploymentConfig::default()
+            }
+        }
+
+        fn live_release(&self, host: &str) -> Option<String> {
+            std::fs::read_link(self.host(host).join("srv/current"))
+                .ok()
+                .and_then(|link| link.file_name().map(|n| n.to_string_lossy().into_owned()))
+        }
+
+        fn outcomes(&self, target_id: &str) -> Vec<(String, DeploymentOutcome)> {
+            self.history
+                .for_target(target_id)
+                .unwrap()
+                .into_iter()
+                .map(|record| (record.version, record.outcome))
+                .collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn deploy_verifies_and_switches_release_then_rolls_back() {
+        let fixture = Fixture::new();
+        let mut deployer = fixture.deployer(false);
+
+        deployer.set_config(fixture.config("1.0.0"));
+        let record = deployer.deploy("a", "zerospider").await.unwrap();
+        assert_eq!(
+            record.sha256.as_deref(),
+            Some(sha256_file(&fixture.local_binary).unwrap().as_str())
+        );
+        assert_eq!(fixture.live_release("host-a").as_deref(), Some("1.0.0"));
+        let served =
+            std::fs::read_to_string(fixture.host("host-a").join("bin/zerospider")).unwrap();
+        assert!(served.contains("healthy v1"));
+
+        fixture.build("healthy v2");
+        deployer.set_config(fixture.config("1.1.0"));
+        deployer.deploy("a", "zerospider").await.unwrap();
+        assert_eq!(fixture.live_release("host-a").as_deref(), Some("1.1.0"));
+        assert!(fixture
+            .host("host-a")
+            .join("srv/releases/1.0.0/zerospider")
+            .exists());
+
+        let record = deployer.rollback("a", "zerospider").await.unwrap();
+        assert_eq!(record.version, "1.0.0");
+        assert_eq!(record.previous_release.as_deref(), Some("1.1.0"));
+        assert_eq!(fixture.live_release("host-a").as_deref(), Some("1.0.0"));
+        assert!(deployer.rollback("a", "zerospider").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn unhealthy_release_is_rolled_back_and_history_survives() {
+        let fixture = Fixture::new();
+        let mut deployer = fixture.deployer(false);
+        deployer.set_config(fixture.config("1.0.0"));
+        deployer.deploy("a", "zerospider").await.unwrap();
+
+        fixture.build("broken v2");
+        deployer.set_config(fixture.config("2.0.0"));
+        let err = deployer.deploy("a", "zerospider").await.unwrap_err();
+        assert!(err.to_string().contains("rolled back to 1.0.0"), "{err}");
+        assert_eq!(fixture.live_release("host-a").as_deref(), Some("1.0.0"));
+        assert_eq!(
+            fixture.outcomes("a"),
+            vec![
+                ("1.0.0".to_string(), DeploymentOutcome::Deployed),
+                ("2.0.0".to_string(), DeploymentOutcome::Failed),
+                ("1.0.0".to_string(), DeploymentOutcome::RolledBack),
+            ]
+        );
+
+        let reopened = fixture.deployer(false);
+        let status = reopened.get_status("a").unwrap();
+        assert!(status.deployed);
+        assert_eq!(status.version.as_deref(), Some("1.0.0"));
+        assert!(!reopened.get_status("b").unwrap().deployed);
+    }
+
+    #[tokio::test]
+    async fn checksum_mismatch_never_switches() {
+        let fixture = Fixture::new();
+        let mut deployer = fixture.deployer(true);
+        deployer.set_config(fixture.config("1.0.0"));
+
+        let err = deployer.deploy("a", "zerospider").await.unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"), "{err}");
+        assert_eq!(fixture.live_release("host-a"), None);
+        assert_eq!(
+            fixture.outcomes("a"),
+            vec![("1.0.0".to_string(), DeploymentOutcome::Failed)]
+        );
+    }
+
+    #[tokio::test]
+    async fn rollout_halts_at_first_unhealthy_target() {
+        let fixture = Fixture::new();
+        let mut deployer = fixture.deployer(false);
+        let targets: Vec<String> = ["a", "b", "c"].map(String::from).to_vec();
+        deployer.set_config(fixture.config("1.0.0"));
+        assert_eq!(
+            deployer
+                .rollout(&targets, "zerospider")
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+
+        std::fs::write(fixture.host("host-b").join("unhealthy"), "").unwrap();
+        fixture.build("healthy v2");
+        deployer.set_config(fixture.config("2.0.0"));
+        let err = deployer.rollout(&targets, "zerospider").await.unwrap_err();
+        assert!(
+            err.to_string().contains("halted at b after 1 of 3"),
+            "{err}"
+        );
+
+        assert_eq!(fixture.live_release("host-a").as_deref(), Some("2.0.0"));
+        assert_eq!(fixture.live_release("host-b").as_deref(), Some("1.0.0"));
+        assert_eq!(fixture.live_release("host-c").as_deref(), Some("1.0.0"));
+        assert_eq!(fixture.outcomes("c").len(), 1);
+    }
+
+    #[test]
+    fn version_must_be_path_and_shell_safe() {
+        assert!(validate_version("1.2.3-rc.1+build_7").is_ok());
+        for bad in ["", "..", "1.0/../../etc", "1.0; rm -rf /", "v 1"] {
+            assert!(validate_version(bad).is_err(), "{bad}");
+        }
+        assert_eq!(sh_quote("it's"), r"'it'\''s'");
+    }
 }
+
 #[test]
 fn test_remote_deployer_new() {
     let deployer = RemoteDeployer::new(DeploymentMode::Direct);
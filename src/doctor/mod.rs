@@ -1,6 +1,7 @@
 use crate::config::Config;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use serde::Serialize;
 use std::io::Write;
 use std::path::Path;
 
@@ -8,43 +9,62 @@ const DAEMON_STALE_SECONDS: i64 = 30;
 const SCHEDULER_STALE_SECONDS: i64 = 120;
 const CHANNEL_STALE_SECONDS: i64 = 300;
 const COMMAND_VERSION_PREVIEW_CHARS: usize = 60;
+/// Subdirectories `zeroclaw onboard` scaffolds in the workspace.
+const WORKSPACE_SUBDIRS: [&str; 5] = ["sessions", "memory", "state", "cron", "skills"];
+/// Version of the `--format json` document; bumped on breaking changes.
+const REPORT_VERSION: u32 = 1;
 
 // ── Diagnostic item ──────────────────────────────────────────────
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 enum Severity {
     Ok,
     Warn,
     Error,
 }
 
+/// One check result.
+///
+/// `id` is stable across releases so automation can match on it: a
+/// `<category>.<check>` name, optionally followed by `:<subject>` for checks
+/// that run once per item (e.g. `environment.command:git`).
+#[derive(Debug, Clone, Serialize)]
 struct DiagItem {
+    id: String,
     severity: Severity,
-    category: &'static str,
+    category: String,
     message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fix: Option<Fix>,
 }
 
 impl DiagItem {
-    fn ok(category: &'static str, msg: impl Into<String>) -> Self {
+    fn new(severity: Severity, id: impl Into<String>, msg: impl Into<String>) -> Self {
+        let id = id.into();
+        let category = id.split(['.', ':']).next().unwrap_or_default().to_string();
         Self {
-            severity: Severity::Ok,
+            id,
+            severity,
             category,
             message: msg.into(),
+            fix: None,
         }
     }
-    fn warn(category: &'static str, msg: impl Into<String>) -> Self {
-        Self {
-            severity: Severity::Warn,
-            category,
-            message: msg.into(),
-        }
+    fn ok(id: impl Into<String>, msg: impl Into<String>) -> Self {
+        Self::new(Severity::Ok, id, msg)
     }
-    fn error(category: &'static str, msg: impl Into<String>) -> Self {
-        Self {
-            severity: Severity::Error,
-            category,
-            message: msg.into(),
-        }
+    fn warn(id: impl Into<String>, msg: impl Into<String>) -> Self {
+        Self::new(Severity::Warn, id, msg)
+    }
+    fn error(id: impl Into<String>, msg: impl Into<String>) -> Self {
+        Self::new(Severity::Error, id, msg)
+    }
+
+    /// Mark the finding as repairable by `doctor --fix`.
+    fn with_fix(mut self, fix: Fix) -> Self {
+        self.fix = Some(fix);
+        self
     }
 
     fn icon(&self) -> &'static str {
@@ -56,47 +76,383 @@ impl DiagItem {
     }
 }
 
+// ── Remediations ─────────────────────────────────────────────────
+
+/// Safe, idempotent remediations applied by `doctor --fix`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+enum Fix {
+    #[serde(rename = "workspace.create_dirs")]
+    CreateWorkspaceDirs,
+    #[serde(rename = "workspace.init_skills")]
+    InitSkillsDir,
+    #[serde(rename = "secrets.encrypt_plaintext")]
+    EncryptPlaintextSecrets,
+    #[serde(rename = "memory.reindex")]
+    ReindexMemory,
+    #[serde(rename = "daemon.prune_state")]
+    PruneDaemonState,
+}
+
+impl Fix {
+    fn id(self) -> &'static str {
+        match self {
+            Self::CreateWorkspaceDirs => "workspace.create_dirs",
+            Self::InitSkillsDir => "workspace.init_skills",
+            Self::EncryptPlaintextSecrets => "secrets.encrypt_plaintext",
+            Self::ReindexMemory => "memory.reindex",
+            Self::PruneDaemonState => "daemon.prune_state",
+        }
+    }
+
+    fn describe(self, config: &Config) -> String {
+        match self {
+            Self::CreateWorkspaceDirs => format!(
+                "create {} and its {} subdirectories",
+                config.workspace_dir.display(),
+                WORKSPACE_SUBDIRS.join("/")
+            ),
+            Self::InitSkillsDir => format!(
+                "initialize {}",
+                crate::skills::skills_dir(&config.workspace_dir).display()
+            ),
+            Self::EncryptPlaintextSecrets => format!(
+                "encrypt plaintext secrets in {} (backup kept as .bak)",
+                config.config_path.display()
+            ),
+            Self::ReindexMemory => "rebuild the memory FTS index and missing embeddings".into(),
+            Self::PruneDaemonState => format!(
+                "remove stale {}",
+                crate::daemon::state_file_path(config).display()
+            ),
+        }
+    }
+
+    async fn apply(self, config: &Config) -> Result<String> {
+        match self {
+            Self::CreateWorkspaceDirs => {
+                for dir in WORKSPACE_SUBDIRS {
+                    std::fs::create_dir_all(config.workspace_dir.join(dir))?;
+                }
+                Ok("workspace directories created".into())
+            }
+            Self::InitSkillsDir => {
+                crate::skills::init_skills_dir(&config.workspace_dir)?;
+                Ok("skills directory initialized".into())
+            }
+            Self::EncryptPlaintextSecrets => {
+                let count = encrypt_plaintext_secrets(config)?;
+                Ok(format!("{count} secret(s) encrypted"))
+            }
+            Self::ReindexMemory => {
+                let memory = crate::memory::open_sqlite_memory(
+                    &config.memory,
+                    &config.embedding_routes,
+                    &config.workspace_dir,
+                    config.api_key.as_deref(),
+                )?;
+                let embedded = memory.reindex().await?;
+                Ok(format!("FTS index rebuilt, {embedded} memories embedded"))
+            }
+            Self::PruneDaemonState => {
+                std::fs::remove_file(crate::daemon::state_file_path(config))?;
+                Ok("stale daemon state removed".into())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum FixStatus {
+    Planned,
+    Applied,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct FixOutcome {
+    id: &'static str,
+    description: String,
+    status: FixStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+// ── Options and report ───────────────────────────────────────────
+
+/// Output format for `zeroclaw doctor`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum DoctorFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Lowest severity that makes `zeroclaw doctor` exit non-zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum FailOn {
+    #[default]
+    Error,
+    Warn,
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DoctorOptions {
+    pub format: DoctorFormat,
+    /// Apply the remediations attached to failing checks.
+    pub fix: bool,
+    /// With `fix`: report the remediations without applying them.
+    pub dry_run: bool,
+    pub fail_on: FailOn,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct Summary {
+    ok: usize,
+    warn: usize,
+    error: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct DoctorReport {
+    version: u32,
+    summary: Summary,
+    checks: Vec<DiagItem>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<FixOutcome>,
+}
+
+impl DoctorReport {
+    fn new(checks: Vec<DiagItem>, fixes: Vec<FixOutcome>) -> Self {
+        let mut summary = Summary::default();
+        for item in &checks {
+            match item.severity {
+                Severity::Ok => summary.ok += 1,
+                Severity::Warn => summary.warn += 1,
+                Severity::Error => summary.error += 1,
+            }
+        }
+        Self {
+            version: REPORT_VERSION,
+            summary,
+            checks,
+            fixes,
+        }
+    }
+
+    /// Exit policy: fail on errors (or warnings, per `fail_on`) left after
+    /// any fixes, and on any fix that could not be applied.
+    fn exit_status(&self, fail_on: FailOn) -> Result<()> {
+        let failed_fixes = self
+            .fixes
+            .iter()
+            .filter(|fix| fix.status == FixStatus::Failed)
+            .count();
+        if failed_fixes > 0 {
+            anyhow::bail!("doctor: {failed_fixes} fix(es) failed");
+        }
+        let failing = match fail_on {
+            FailOn::Error => self.summary.error > 0,
+            FailOn::Warn => self.summary.error + self.summary.warn > 0,
+            FailOn::Never => false,
+        };
+        if failing {
+            anyhow::bail!(
+                "doctor: {} error(s), {} warning(s)",
+                self.summary.error,
+                self.summary.warn
+            );
+        }
+        Ok(())
+    }
+}
+
 // ── Public entry point ───────────────────────────────────────────
 
-pub fn run(config: &Config) -> Result<()> {
+pub async fn run(config: &Config, options: DoctorOptions) -> Result<()> {
+    let mut items = collect_checks(config);
+    let mut fixes = Vec::new();
+
+    if options.fix {
+        for fix in planned_fixes(&items) {
+            let description = fix.describe(config);
+            let (status, detail) = if options.dry_run {
+                (FixStatus::Planned, None)
+            } else {
+                match fix.apply(config).await {
+                    Ok(detail) => (FixStatus::Applied, Some(detail)),
+                    Err(e) => (FixStatus::Failed, Some(format_error_chain(&e))),
+                }
+            };
+            fixes.push(FixOutcome {
+                id: fix.id(),
+                description,
+                status,
+                detail,
+            });
+        }
+        // Report the state after remediation, not before.
+        if fixes.iter().any(|fix| fix.status != FixStatus::Planned) {
+            items = collect_checks(config);
+        }
+    }
+
+    let report = DoctorReport::new(items, fixes);
+    match options.format {
+        DoctorFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        DoctorFormat::Text => print_report("🩺 ZeroClaw Doctor (enhanced)", &report, options),
+    }
+    report.exit_status(options.fail_on)
+}
+
+/// `zeroclaw config validate <file>`: load an arbitrary config file, run
+/// `Config::validate` and the config semantic checks, and report them like
+/// `doctor` does. Workspace, daemon and environment checks are skipped: they
+/// describe this machine, not the file.
+pub fn validate_file(path: &Path, options: DoctorOptions) -> Result<()> {
+    let report = DoctorReport::new(validation_checks(path), Vec::new());
+    match options.format {
+        DoctorFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        DoctorFormat::Text => print_report(
+            &format!("🔎 Config validation: {}", path.display()),
+            &report,
+            options,
+        ),
+    }
+    report.exit_status(options.fail_on)
+}
+
+fn validation_checks(path: &Path) -> Vec<DiagItem> {
+    use crate::config::migrate::{file_version, CURRENT_CONFIG_VERSION};
+
+    let mut items = Vec::new();
+    match file_version(path) {
+        Ok(version) if version == CURRENT_CONFIG_VERSION => items.push(DiagItem::ok(
+            "config.version",
+            format!("config_version {version} is current"),
+        )),
+        Ok(version) if version < CURRENT_CONFIG_VERSION => items.push(DiagItem::warn(
+            "config.version",
+            format!(
+                "config_version {version} is older than {CURRENT_CONFIG_VERSION} — run `zeroclaw config migrate {}`",
+                path.display()
+            ),
+        )),
+        Ok(version) => items.push(DiagItem::error(
+            "config.version",
+            format!("config_version {version} is newer than this build supports ({CURRENT_CONFIG_VERSION})"),
+        )),
+        Err(e) => {
+            items.push(DiagItem::error("config.parse", format_error_chain(&e)));
+            return items;
+        }
+    }
+
+    let profile = crate::config::layers::selected_profile();
+    let config = match Config::load_file(path, profile.as_deref()) {
+        Ok((config, ignored)) => {
+            for key in ignored {
+                items.push(DiagItem::warn(
+                    format!("config.unknown_key:{key}"),
+                    format!("unknown key \"{key}\" is ignored"),
+                ));
+            }
+            config
+        }
+        Err(e) => {
+            items.push(DiagItem::error("config.load", format_error_chain(&e)));
+            return items;
+        }
+    };
+
+    match config.validate() {
+        Ok(()) => items.push(DiagItem::ok("config.validate", "config values are valid")),
+        Err(e) => items.push(DiagItem::error("config.validate", format_error_chain(&e))),
+    }
+    check_config_semantics(&config, &mut items);
+    items
+}
+
+fn collect_checks(config: &Config) -> Vec<DiagItem> {
     let mut items: Vec<DiagItem> = Vec::new();
 
     check_config_semantics(config, &mut items);
     check_workspace(config, &mut items);
+    check_secrets(config, &mut items);
+    check_memory(config, &mut items);
     check_daemon_state(config, &mut items);
     check_environment(&mut items);
+    items
+}
+
+/// Distinct remediations attached to non-ok checks, in check order.
+fn planned_fixes(items: &[DiagItem]) -> Vec<Fix> {
+    let mut fixes = Vec::new();
+    for fix in items
+        .iter()
+        .filter(|item| item.severity != Severity::Ok)
+        .filter_map(|item| item.fix)
+    {
+        if !fixes.contains(&fix) {
+            fixes.push(fix);
+        }
+    }
+    fixes
+}
 
-    // Print report
-    println!("🩺 ZeroClaw Doctor (enhanced)");
+fn print_report(title: &str, report: &DoctorReport, options: DoctorOptions) {
+    println!("{title}");
     println!();
 
     let mut current_cat = "";
-    for item in &items {
+    for item in &report.checks {
         if item.category != current_cat {
-            current_cat = item.category;
+            current_cat = &item.category;
             println!("  [{current_cat}]");
         }
         println!("    {} {}", item.icon(), item.message);
     }
 
-    let errors = items
-        .iter()
-        .filter(|i| i.severity == Severity::Error)
-        .count();
-    let warns = items
-        .iter()
-        .filter(|i| i.severity == Severity::Warn)
-        .count();
-    let oks = items.iter().filter(|i| i.severity == Severity::Ok).count();
+    if !report.fixes.is_empty() {
+        println!();
+        println!(
+            "  {}",
+            if options.dry_run {
+                "Fixes (dry run, nothing changed):"
+            } else {
+                "Fixes:"
+            }
+        );
+        for fix in &report.fixes {
+            let icon = match fix.status {
+                FixStatus::Planned => "🔧",
+                FixStatus::Applied => "✅",
+                FixStatus::Failed => "❌",
+            };
+            match &fix.detail {
+                Some(detail) => println!("    {icon} {} — {detail}", fix.description),
+                None => println!("    {icon} {}", fix.description),
+            }
+        }
+    }
 
+    let Summary { ok, warn, error } = report.summary;
     println!();
-    println!("  Summary: {oks} ok, {warns} warnings, {errors} errors");
+    println!("  Summary: {ok} ok, {warn} warnings, {error} errors");
 
-    if errors > 0 {
+    let fixable = report
+        .checks
+        .iter()
+        .any(|item| item.severity != Severity::Ok && item.fix.is_some());
+    if fixable && !options.fix {
+        println!(
+            "  🔧 Some findings can be repaired: run `zeroclaw doctor --fix --dry-run` to preview."
+        );
+    }
+    if error > 0 {
         println!("  💡 Fix the errors above, then run `zeroclaw doctor` again.");
     }
-
-    Ok(())
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -228,17 +584,15 @@ pub fn run_models(config: &Config, provider_override: Option<&str>, use_cache: b
 // ── Config semantic validation ───────────────────────────────────
 
 fn check_config_semantics(config: &Config, items: &mut Vec<DiagItem>) {
-    let cat = "config";
-
     // Config file exists
     if config.config_path.exists() {
         items.push(DiagItem::ok(
-            cat,
+            "config.file",
             format!("config file: {}", config.config_path.display()),
         ));
     } else {
         items.push(DiagItem::error(
-            cat,
+            "config.file",
             format!("config file not found: {}", config.config_path.display()),
         ));
     }
@@ -247,26 +601,29 @@ fn check_config_semantics(config: &Config, items: &mut Vec<DiagItem>) {
     if let Some(ref provider) = config.default_provider {
         if let Some(reason) = provider_validation_error(provider) {
             items.push(DiagItem::error(
-                cat,
+                "config.default_provider",
                 format!("default provider \"{provider}\" is invalid: {reason}"),
             ));
         } else {
             items.push(DiagItem::ok(
-                cat,
+                "config.default_provider",
                 format!("provider \"{provider}\" is valid"),
             ));
         }
     } else {
-        items.push(DiagItem::error(cat, "no default_provider configured"));
+        items.push(DiagItem::error(
+            "config.default_provider",
+            "no default_provider configured",
+        ));
     }
 
     // API key presence
     if config.default_provider.as_deref() != Some("ollama") {
         if config.api_key.is_some() {
-            items.push(DiagItem::ok(cat, "API key configured"));
+            items.push(DiagItem::ok("config.api_key", "API key configured"));
         } else {
             items.push(DiagItem::warn(
-                cat,
+                "config.api_key",
                 "no api_key set (may rely on env vars or provider defaults)",
             ));
         }
@@ -275,20 +632,23 @@ fn check_config_semantics(config: &Config, items: &mut Vec<DiagItem>) {
     // Model configured
     if config.default_model.is_some() {
         items.push(DiagItem::ok(
-            cat,
+            "config.default_model",
             format!(
                 "default model: {}",
                 config.default_model.as_deref().unwrap_or("?")
             ),
         ));
     } else {
-        items.push(DiagItem::warn(cat, "no default_model configured"));
+        items.push(DiagItem::warn(
+            "config.default_model",
+            "no default_model configured",
+        ));
     }
 
     // Temperature range
     if config.default_temperature >= 0.0 && config.default_temperature <= 2.0 {
         items.push(DiagItem::ok(
-            cat,
+            "config.temperature",
             format!(
                 "temperature {:.1} (valid range 0.0–2.0)",
                 config.default_temperature
@@ -296,7 +656,7 @@ fn check_config_semantics(config: &Config, items: &mut Vec<DiagItem>) {
         ));
     } else {
         items.push(DiagItem::error(
-            cat,
+            "config.temperature",
             format!(
                 "temperature {:.1} is out of range (expected 0.0–2.0)",
                 config.default_temperature
@@ -307,16 +667,22 @@ fn check_config_semantics(config: &Config, items: &mut Vec<DiagItem>) {
     // Gateway port range
     let port = config.gateway.port;
     if port > 0 {
-        items.push(DiagItem::ok(cat, format!("gateway port: {port}")));
+        items.push(DiagItem::ok(
+            "config.gateway_port",
+            format!("gateway port: {port}"),
+        ));
     } else {
-        items.push(DiagItem::error(cat, "gateway port is 0 (invalid)"));
+        items.push(DiagItem::error(
+            "config.gateway_port",
+            "gateway port is 0 (invalid)",
+        ));
     }
 
     // Reliability: fallback providers
     for fb in &config.reliability.fallback_providers {
         if let Some(reason) = provider_validation_error(fb) {
             items.push(DiagItem::warn(
-                cat,
+                format!("config.fallback_provider:{fb}"),
                 format!("fallback provider \"{fb}\" is invalid: {reason}"),
             ));
         }
@@ -325,11 +691,14 @@ fn check_config_semantics(config: &Config, items: &mut Vec<DiagItem>) {
     // Model routes validation
     for route in &config.model_routes {
         if route.hint.is_empty() {
-            items.push(DiagItem::warn(cat, "model route with empty hint"));
+            items.push(DiagItem::warn(
+                "config.model_route",
+                "model route with empty hint",
+            ));
         }
         if let Some(reason) = provider_validation_error(&route.provider) {
             items.push(DiagItem::warn(
-                cat,
+                format!("config.model_route:{}", route.hint),
                 format!(
                     "model route \"{}\" uses invalid provider \"{}\": {}",
                     route.hint, route.provider, reason
@@ -338,20 +707,34 @@ fn check_config_semantics(config: &Config, items: &mut Vec<DiagItem>) {
         }
         if route.model.is_empty() {
             items.push(DiagItem::warn(
-                cat,
+                format!("config.model_route:{}", route.hint),
                 format!("model route \"{}\" has empty model", route.hint),
             ));
         }
     }
 
+    // Memory embedding provider: an unknown name fails memory startup
+    if let Some(reason) = embedding_provider_validation_error(&config.memory.embedding_provider) {
+        items.push(DiagItem::error(
+            "config.embedding_provider",
+            format!(
+                "memory.embedding_provider \"{}\" is invalid: {}",
+                config.memory.embedding_provider, reason
+            ),
+        ));
+    }
+
     // Embedding routes validation
     for route in &config.embedding_routes {
         if route.hint.trim().is_empty() {
-            items.push(DiagItem::warn(cat, "embedding route with empty hint"));
+            items.push(DiagItem::warn(
+                "config.embedding_route",
+                "embedding route with empty hint",
+            ));
         }
         if let Some(reason) = embedding_provider_validation_error(&route.provider) {
             items.push(DiagItem::warn(
-                cat,
+                format!("config.embedding_route:{}", route.hint),
                 format!(
                     "embedding route \"{}\" uses invalid provider \"{}\": {}",
                     route.hint, route.provider, reason
@@ -360,13 +743,13 @@ fn check_config_semantics(config: &Config, items: &mut Vec<DiagItem>) {
         }
         if route.model.trim().is_empty() {
             items.push(DiagItem::warn(
-                cat,
+                format!("config.embedding_route:{}", route.hint),
                 format!("embedding route \"{}\" has empty model", route.hint),
             ));
         }
         if route.dimensions.is_some_and(|value| value == 0) {
             items.push(DiagItem::warn(
-                cat,
+                format!("config.embedding_route:{}", route.hint),
                 format!(
                     "embedding route \"{}\" has invalid dimensions=0",
                     route.hint
@@ -388,7 +771,7 @@ fn check_config_semantics(config: &Config, items: &mut Vec<DiagItem>) {
             .any(|route| route.hint.trim() == hint)
         {
             items.push(DiagItem::warn(
-                cat,
+                "config.embedding_model_hint",
                 format!(
                     "memory.embedding_model uses hint \"{hint}\" but no matching [[embedding_routes]] entry exists"
                 ),
@@ -411,10 +794,13 @@ fn check_config_semantics(config: &Config, items: &mut Vec<DiagItem>) {
         || cc.webhook.is_some();
 
     if has_channel {
-        items.push(DiagItem::ok(cat, "at least one channel configured"));
+        items.push(DiagItem::ok(
+            "config.channels",
+            "at least one channel configured",
+        ));
     } else {
         items.push(DiagItem::warn(
-            cat,
+            "config.channels",
             "no channels configured — run `zeroclaw onboard` to set one up",
         ));
     }
@@ -426,7 +812,7 @@ fn check_config_semantics(config: &Config, items: &mut Vec<DiagItem>) {
         let agent = config.agents.get(name).unwrap();
         if let Some(reason) = provider_validation_error(&agent.provider) {
             items.push(DiagItem::warn(
-                cat,
+                format!("config.delegate_agent:{name}"),
                 format!(
                     "agent \"{name}\" uses invalid provider \"{}\": {}",
                     agent.provider, reason
@@ -451,26 +837,31 @@ fn provider_validation_error(name: &str) -> Option<String> {
 
 fn embedding_provider_validation_error(name: &str) -> Option<String> {
     let normalized = name.trim();
-    if normalized.eq_ignore_ascii_case("none") || normalized.eq_ignore_ascii_case("openai") {
-        return None;
-    }
-
-    let Some(url) = normalized.strip_prefix("custom:") else {
-        return Some("supported values: none, openai, custom:<url>".into());
+    let Some((kind, url)) = ["custom", "ollama"].into_iter().find_map(|kind| {
+        normalized
+            .strip_prefix(kind)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .map(|url| (kind, url))
+    }) else {
+        return crate::memory::embeddings::create_embedding_provider(normalized, None, "model", 1)
+            .err()
+            .map(|err| err.to_string());
     };
 
     let url = url.trim();
     if url.is_empty() {
-        return Some("custom provider requires a non-empty URL after 'custom:'".into());
+        return Some(format!(
+            "{kind} provider requires a non-empty URL after '{kind}:'"
+        ));
     }
 
     match reqwest::Url::parse(url) {
         Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => None,
         Ok(parsed) => Some(format!(
-            "custom provider URL must use http/https, got '{}'",
+            "{kind} provider URL must use http/https, got '{}'",
             parsed.scheme()
         )),
-        Err(err) => Some(format!("invalid custom provider URL: {err}")),
+        Err(err) => Some(format!("invalid {kind} provider URL: {err}")),
     }
 }
 
@@ -482,14 +873,17 @@ fn check_workspace(config: &Config, items: &mut Vec<DiagItem>) {
 
     if ws.exists() {
         items.push(DiagItem::ok(
-            cat,
+            "workspace.exists",
             format!("directory exists: {}", ws.display()),
         ));
     } else {
-        items.push(DiagItem::error(
-            cat,
-            format!("directory missing: {}", ws.display()),
-        ));
+        items.push(
+            DiagItem::error(
+                "workspace.exists",
+                format!("directory missing: {}", ws.display()),
+            )
+            .with_fix(Fix::CreateWorkspaceDirs),
+        );
         return;
     }
 
@@ -505,16 +899,16 @@ fn check_workspace(config: &Config, items: &mut Vec<DiagItem>) {
             drop(probe_file);
             let _ = std::fs::remove_file(&probe);
             match write_result {
-                Ok(()) => items.push(DiagItem::ok(cat, "directory is writable")),
+                Ok(()) => items.push(DiagItem::ok("workspace.writable", "directory is writable")),
                 Err(e) => items.push(DiagItem::error(
-                    cat,
+                    "workspace.writable",
                     format!("directory write probe failed: {e}"),
                 )),
             }
         }
         Err(e) => {
             items.push(DiagItem::error(
-                cat,
+                "workspace.writable",
                 format!("directory is not writable: {e}"),
             ));
         }
@@ -524,12 +918,12 @@ fn check_workspace(config: &Config, items: &mut Vec<DiagItem>) {
     if let Some(avail_mb) = disk_available_mb(ws) {
         if avail_mb >= 100 {
             items.push(DiagItem::ok(
-                cat,
+                "workspace.disk_space",
                 format!("disk space: {avail_mb} MB available"),
             ));
         } else {
             items.push(DiagItem::warn(
-                cat,
+                "workspace.disk_space",
                 format!("low disk space: only {avail_mb} MB available"),
             ));
         }
@@ -538,6 +932,249 @@ fn check_workspace(config: &Config, items: &mut Vec<DiagItem>) {
     // Key workspace files
     check_file_exists(ws, "SOUL.md", false, cat, items);
     check_file_exists(ws, "AGENTS.md", false, cat, items);
+
+    // Scaffolded subdirectories (skills has its own check below)
+    let missing: Vec<&str> = WORKSPACE_SUBDIRS
+        .into_iter()
+        .filter(|dir| *dir != "skills" && !ws.join(dir).is_dir())
+        .collect();
+    if missing.is_empty() {
+        items.push(DiagItem::ok(
+            "workspace.subdirs",
+            "workspace subdirectories present",
+        ));
+    } else {
+        items.push(
+            DiagItem::warn(
+                "workspace.subdirs",
+                format!("missing workspace subdirectories: {}", missing.join(", ")),
+            )
+            .with_fix(Fix::CreateWorkspaceDirs),
+        );
+    }
+
+    let skills = crate::skills::skills_dir(ws);
+    if skills.join("README.md").is_file() {
+        items.push(DiagItem::ok(
+            "workspace.skills",
+            "skills directory initialized",
+        ));
+    } else {
+        items.push(
+            DiagItem::warn(
+                "workspace.skills",
+                format!("skills directory not initialized: {}", skills.display()),
+            )
+            .with_fix(Fix::InitSkillsDir),
+        );
+    }
+}
+
+// ── Secrets ──────────────────────────────────────────────────────
+
+fn check_secrets(config: &Config, items: &mut Vec<DiagItem>) {
+    let Some(dir) = config.config_path.parent() else {
+        return;
+    };
+    let key_path = dir.join(".secret_key");
+    let kdf_path = dir.join(".secret_key.kdf");
+
+    if !config.secrets.encrypt {
+        items.push(DiagItem::warn(
+            "secrets.encryption",
+            "encryption disabled (secrets.encrypt = false) — API keys are stored as plaintext",
+        ));
+    } else if kdf_path.exists() {
+        items.push(DiagItem::ok(
+            "secrets.key",
+            "key derived from passphrase (Argon2id)",
+        ));
+        if key_path.exists() {
+            items.push(DiagItem::warn(
+                "secrets.stale_key_file",
+                format!(
+                    "stale key file {} sits next to passphrase parameters — delete it",
+                    key_path.display()
+                ),
+            ));
+        }
+    } else if key_path.exists() {
+        if config.secrets.key_source == crate::config::SecretKeySource::Passphrase {
+            items.push(DiagItem::warn(
+                "secrets.key",
+                "key_source = \"passphrase\" but secrets are still under .secret_key — run `zeroclaw secrets rotate`",
+            ));
+        } else {
+            items.push(DiagItem::ok("secrets.key", "key file present"));
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if let Ok(meta) = std::fs::metadata(&key_path) {
+                let mode = meta.permissions().mode() & 0o777;
+                if mode & 0o077 != 0 {
+                    items.push(DiagItem::warn(
+                        "secrets.key_permissions",
+                        format!("key file is readable by other users (mode {mode:o}) — chmod 600"),
+                    ));
+                }
+            }
+        }
+    }
+
+    let Ok(raw) = std::fs::read_to_string(&config.config_path) else {
+        return;
+    };
+    let Ok(table) = raw.parse::<toml::Table>() else {
+        return;
+    };
+    let plaintext = plaintext_secret_paths(&table);
+    if plaintext.is_empty() {
+        items.push(DiagItem::ok(
+            "secrets.plaintext",
+            "no plaintext secrets in config.toml",
+        ));
+    }
+    for path in plaintext {
+        let item = DiagItem::warn(
+            format!("secrets.plaintext:{path}"),
+            format!("plaintext secret in config.toml: {path} — move it to an environment variable or re-save with encryption on"),
+        );
+        // Only fields the config loader decrypts can be migrated in place.
+        items.push(if config.secrets.encrypt && is_managed_secret_path(&path) {
+            item.with_fix(Fix::EncryptPlaintextSecrets)
+        } else {
+            item
+        });
+    }
+}
+
+/// Whether `path` (as reported by [`plaintext_secret_paths`]) is a field the
+/// config loader decrypts, so encrypting it in place keeps the config loadable.
+fn is_managed_secret_path(path: &str) -> bool {
+    matches!(
+        secret_path_segments(path).as_slice(),
+        ["api_key"]
+            | ["composio", "api_key"]
+            | ["browser", "computer_use", "api_key"]
+            | ["web_search", "brave_api_key"]
+            | ["storage", "provider", "config", "db_url"]
+            | ["agents" | "profiles", _, "api_key"]
+            | ["peripherals", "boards", _, "shared_secret"]
+    )
+}
+
+/// `model_routes[0].api_key` → `["model_routes", "0", "api_key"]`.
+fn secret_path_segments(path: &str) -> Vec<&str> {
+    path.split(['.', '['])
+        .map(|segment| segment.trim_end_matches(']'))
+        .collect()
+}
+
+/// Encrypt every managed plaintext secret in config.toml with the configured
+/// `SecretStore`, keeping the previous file as `<name>.bak`.
+fn encrypt_plaintext_secrets(config: &Config) -> Result<usize> {
+    let path = &config.config_path;
+    let dir = path
+        .parent()
+        .context("Config path must have a parent directory")?;
+    let table: toml::Table = std::fs::read_to_string(path)?.parse()?;
+    let store = crate::security::SecretStore::from_config(dir, &config.secrets);
+
+    fn encrypt_at(
+        value: &mut toml::Value,
+        segments: &[&str],
+        store: &crate::security::SecretStore,
+    ) -> Result<bool> {
+        let Some((head, rest)) = segments.split_first() else {
+            let toml::Value::String(plaintext) = value else {
+                return Ok(false);
+            };
+            *plaintext = store.encrypt(plaintext)?;
+            return Ok(true);
+        };
+        let child = match value {
+            toml::Value::Table(table) => table.get_mut(*head),
+            toml::Value::Array(values) => {
+                head.parse::<usize>().ok().and_then(|i| values.get_mut(i))
+            }
+            _ => None,
+        };
+        match child {
+            Some(child) => encrypt_at(child, rest, store),
+            None => Ok(false),
+        }
+    }
+
+    let paths = plaintext_secret_paths(&table);
+    let mut root = toml::Value::Table(table);
+    let mut count = 0;
+    for secret_path in paths.iter().filter(|path| is_managed_secret_path(path)) {
+        if encrypt_at(&mut root, &secret_path_segments(secret_path), &store)? {
+            count += 1;
+        }
+    }
+    if count == 0 {
+        return Ok(0);
+    }
+
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("config.toml");
+    std::fs::copy(path, dir.join(format!("{file_name}.bak")))?;
+    let tmp = dir.join(format!(".{file_name}.doctor-tmp"));
+    std::fs::write(&tmp, toml::to_string_pretty(&root)?)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(count)
+}
+
+/// Dotted paths of secret-looking string values in config.toml that are not
+/// `enc2:`/`enc:` ciphertext.
+fn plaintext_secret_paths(table: &toml::Table) -> Vec<String> {
+    fn is_secret_name(name: &str) -> bool {
+        let name = name.to_ascii_lowercase();
+        [
+            "api_key",
+            "apikey",
+            "token",
+            "secret",
+            "password",
+            "db_url",
+            "private_key",
+        ]
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+    }
+
+    fn walk(prefix: &str, value: &toml::Value, name: &str, out: &mut Vec<String>) {
+        match value {
+            toml::Value::String(s)
+                if is_secret_name(name)
+                    && !s.trim().is_empty()
+                    && !crate::security::SecretStore::is_encrypted(s) =>
+            {
+                out.push(prefix.to_string());
+            }
+            toml::Value::Table(table) => {
+                for (key, child) in table {
+                    walk(&format!("{prefix}.{key}"), child, key, out);
+                }
+            }
+            toml::Value::Array(values) => {
+                for (i, child) in values.iter().enumerate() {
+                    walk(&format!("{prefix}[{i}]"), child, name, out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut out = Vec::new();
+    for (key, value) in table {
+        walk(key, value, key, &mut out);
+    }
+    out
 }
 
 fn check_file_exists(
@@ -549,11 +1186,20 @@ fn check_file_exists(
 ) {
     let path = base.join(name);
     if path.is_file() {
-        items.push(DiagItem::ok(cat, format!("{name} present")));
+        items.push(DiagItem::ok(
+            format!("{cat}.file:{name}"),
+            format!("{name} present"),
+        ));
     } else if required {
-        items.push(DiagItem::error(cat, format!("{name} missing")));
+        items.push(DiagItem::error(
+            format!("{cat}.file:{name}"),
+            format!("{name} missing"),
+        ));
     } else {
-        items.push(DiagItem::warn(cat, format!("{name} not found (optional)")));
+        items.push(DiagItem::warn(
+            format!("{cat}.file:{name}"),
+            format!("{name} not found (optional)"),
+        ));
     }
 }
 
@@ -587,15 +1233,77 @@ fn workspace_probe_path(workspace_dir: &Path) -> std::path::PathBuf {
     ))
 }
 
+// ── Memory index ─────────────────────────────────────────────────
+
+fn check_memory(config: &Config, items: &mut Vec<DiagItem>) {
+    let backend = crate::memory::effective_memory_backend_name(
+        &config.memory.backend,
+        Some(&config.storage.provider.config),
+    );
+    if !matches!(
+        crate::memory::classify_memory_backend(&backend),
+        crate::memory::MemoryBackendKind::Sqlite | crate::memory::MemoryBackendKind::Lucid
+    ) {
+        return;
+    }
+    let db_path = config.workspace_dir.join("memory").join("brain.db");
+    if !db_path.exists() {
+        return;
+    }
+    let conn = match rusqlite::Connection::open(&db_path) {
+        Ok(conn) => conn,
+        Err(e) => {
+            items.push(DiagItem::error(
+                "memory.database",
+                format!("cannot open {}: {e}", db_path.display()),
+            ));
+            return;
+        }
+    };
+
+    // `integrity-check` with rank=1 also compares the index to its content table.
+    match conn.execute(
+        "INSERT INTO memories_fts(memories_fts, rank) VALUES('integrity-check', 1)",
+        [],
+    ) {
+        Ok(_) => items.push(DiagItem::ok("memory.fts", "full-text index consistent")),
+        Err(e) => items.push(
+            DiagItem::warn("memory.fts", format!("full-text index out of sync: {e}"))
+                .with_fix(Fix::ReindexMemory),
+        ),
+    }
+
+    if config.memory.embedding_provider.trim() == "none" {
+        return;
+    }
+    match conn.query_row(
+        "SELECT COUNT(*) FROM memories WHERE embedding IS NULL",
+        [],
+        |row| row.get::<_, i64>(0),
+    ) {
+        Ok(0) => items.push(DiagItem::ok("memory.embeddings", "all memories embedded")),
+        Ok(missing) => items.push(
+            DiagItem::warn(
+                "memory.embeddings",
+                format!("{missing} memories have no embedding"),
+            )
+            .with_fix(Fix::ReindexMemory),
+        ),
+        Err(e) => items.push(DiagItem::warn(
+            "memory.embeddings",
+            format!("cannot count embeddings: {e}"),
+        )),
+    }
+}
+
 // ── Daemon state (original logic, preserved) ─────────────────────
 
 fn check_daemon_state(config: &Config, items: &mut Vec<DiagItem>) {
-    let cat = "daemon";
     let state_file = crate::daemon::state_file_path(config);
 
     if !state_file.exists() {
         items.push(DiagItem::error(
-            cat,
+            "daemon.state_file",
             format!(
                 "state file not found: {} — is the daemon running?",
                 state_file.display()
@@ -607,7 +1315,10 @@ fn check_daemon_state(config: &Config, items: &mut Vec<DiagItem>) {
     let raw = match std::fs::read_to_string(&state_file) {
         Ok(r) => r,
         Err(e) => {
-            items.push(DiagItem::error(cat, format!("cannot read state file: {e}")));
+            items.push(DiagItem::error(
+                "daemon.state_file",
+                format!("cannot read state file: {e}"),
+            ));
             return;
         }
     };
@@ -615,7 +1326,10 @@ fn check_daemon_state(config: &Config, items: &mut Vec<DiagItem>) {
     let snapshot: serde_json::Value = match serde_json::from_str(&raw) {
         Ok(v) => v,
         Err(e) => {
-            items.push(DiagItem::error(cat, format!("invalid state JSON: {e}")));
+            items.push(
+                DiagItem::error("daemon.state_file", format!("invalid state JSON: {e}"))
+                    .with_fix(Fix::PruneDaemonState),
+            );
             return;
         }
     };
@@ -631,18 +1345,24 @@ fn check_daemon_state(config: &Config, items: &mut Vec<DiagItem>) {
             .signed_duration_since(ts.with_timezone(&Utc))
             .num_seconds();
         if age <= DAEMON_STALE_SECONDS {
-            items.push(DiagItem::ok(cat, format!("heartbeat fresh ({age}s ago)")));
-        } else {
-            items.push(DiagItem::error(
-                cat,
-                format!("heartbeat stale ({age}s ago)"),
+            items.push(DiagItem::ok(
+                "daemon.heartbeat",
+                format!("heartbeat fresh ({age}s ago)"),
             ));
+        } else {
+            items.push(
+                DiagItem::error("daemon.heartbeat", format!("heartbeat stale ({age}s ago)"))
+                    .with_fix(Fix::PruneDaemonState),
+            );
         }
     } else {
-        items.push(DiagItem::error(
-            cat,
-            format!("invalid daemon timestamp: {updated_at}"),
-        ));
+        items.push(
+            DiagItem::error(
+                "daemon.heartbeat",
+                format!("invalid daemon timestamp: {updated_at}"),
+            )
+            .with_fix(Fix::PruneDaemonState),
+        );
     }
 
     // Components
@@ -666,17 +1386,20 @@ fn check_daemon_state(config: &Config, items: &mut Vec<DiagItem>) {
 
             if scheduler_ok && scheduler_age <= SCHEDULER_STALE_SECONDS {
                 items.push(DiagItem::ok(
-                    cat,
+                    "daemon.scheduler",
                     format!("scheduler healthy (last ok {scheduler_age}s ago)"),
                 ));
             } else {
                 items.push(DiagItem::error(
-                    cat,
+                    "daemon.scheduler",
                     format!("scheduler unhealthy (ok={scheduler_ok}, age={scheduler_age}s)"),
                 ));
             }
         } else {
-            items.push(DiagItem::warn(cat, "scheduler component not tracked yet"));
+            items.push(DiagItem::warn(
+                "daemon.scheduler",
+                "scheduler component not tracked yet",
+            ));
         }
 
         // Channels
@@ -700,21 +1423,27 @@ fn check_daemon_state(config: &Config, items: &mut Vec<DiagItem>) {
                 });
 
             if status_ok && age <= CHANNEL_STALE_SECONDS {
-                items.push(DiagItem::ok(cat, format!("{name} fresh ({age}s ago)")));
+                items.push(DiagItem::ok(
+                    format!("daemon.component:{name}"),
+                    format!("{name} fresh ({age}s ago)"),
+                ));
             } else {
                 stale += 1;
                 items.push(DiagItem::error(
-                    cat,
+                    format!("daemon.component:{name}"),
                     format!("{name} stale (ok={status_ok}, age={age}s)"),
                 ));
             }
         }
 
         if channel_count == 0 {
-            items.push(DiagItem::warn(cat, "no channel components tracked yet"));
+            items.push(DiagItem::warn(
+                "daemon.channels",
+                "no channel components tracked yet",
+            ));
         } else if stale > 0 {
             items.push(DiagItem::warn(
-                cat,
+                "daemon.channels",
                 format!("{channel_count} channels, {stale} stale"),
             ));
         }
@@ -732,17 +1461,17 @@ fn check_environment(items: &mut Vec<DiagItem>) {
     // Shell
     let shell = std::env::var("SHELL").unwrap_or_default();
     if shell.is_empty() {
-        items.push(DiagItem::warn(cat, "$SHELL not set"));
+        items.push(DiagItem::warn("environment.shell", "$SHELL not set"));
     } else {
-        items.push(DiagItem::ok(cat, format!("shell: {shell}")));
+        items.push(DiagItem::ok("environment.shell", format!("shell: {shell}")));
     }
 
     // HOME
     if std::env::var("HOME").is_ok() || std::env::var("USERPROFILE").is_ok() {
-        items.push(DiagItem::ok(cat, "home directory env set"));
+        items.push(DiagItem::ok("environment.home", "home directory env set"));
     } else {
         items.push(DiagItem::error(
-            cat,
+            "environment.home",
             "neither $HOME nor $USERPROFILE is set",
         ));
     }
@@ -762,16 +1491,22 @@ fn check_command_available(cmd: &str, args: &[&str], cat: &'static str, items: &
             let ver = String::from_utf8_lossy(&output.stdout);
             let first_line = ver.lines().next().unwrap_or("").trim();
             let display = truncate_for_display(first_line, COMMAND_VERSION_PREVIEW_CHARS);
-            items.push(DiagItem::ok(cat, format!("{cmd}: {display}")));
+            items.push(DiagItem::ok(
+                format!("{cat}.command:{cmd}"),
+                format!("{cmd}: {display}"),
+            ));
         }
         Ok(_) => {
             items.push(DiagItem::warn(
-                cat,
+                format!("{cat}.command:{cmd}"),
                 format!("{cmd} found but returned non-zero"),
             ));
         }
         Err(_) => {
-            items.push(DiagItem::warn(cat, format!("{cmd} not found in PATH")));
+            items.push(DiagItem::warn(
+                format!("{cat}.command:{cmd}"),
+                format!("{cmd} not found in PATH"),
+            ));
         }
     }
 }
@@ -828,6 +1563,38 @@ mod tests {
         assert!(invalid_unknown.contains("Unknown provider"));
     }
 
+    #[test]
+    fn plaintext_secret_paths_flags_only_unencrypted_secrets() {
+        let table: toml::Table = toml::from_str(
+            r#"
+api_key = "enc2:aabbccddeeff00112233445566778899"
+default_model = "gpt-4o"
+max_tokens = 4096
+
+[channels_config.telegram]
+bot_token = "123456:ABCDEF"
+allowed_users = ["alice"]
+
+[[model_routes]]
+hint = "fast"
+api_key = "sk-plaintext"
+
+[gateway]
+require_pairing = true
+"#,
+        )
+        .unwrap();
+
+        let paths = plaintext_secret_paths(&table);
+        assert_eq!(
+            paths,
+            vec![
+                "channels_config.telegram.bot_token".to_string(),
+                "model_routes[0].api_key".to_string(),
+            ]
+        );
+    }
+
     #[test]
     fn diag_item_icons() {
         assert_eq!(DiagItem::ok("t", "m").icon(), "✅");
@@ -961,6 +1728,7 @@ mod tests {
             provider: "groq".into(),
             model: String::new(),
             api_key: None,
+            reasoning: None,
         }];
         let mut items = Vec::new();
         check_config_semantics(&config, &mut items);
@@ -1076,6 +1844,7 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                reasoning: None,
             },
         );
         config.agents.insert(
@@ -1090,6 +1859,7 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                reasoning: None,
             },
         );
 
@@ -1106,4 +1876,235 @@ mod tests {
         assert!(agent_messages[0].contains("agent \"alpha\""));
         assert!(agent_messages[1].contains("agent \"zeta\""));
     }
+
+    fn config_in(tmp: &TempDir) -> Config {
+        Config {
+            workspace_dir: tmp.path().join("workspace"),
+            config_path: tmp.path().join("config.toml"),
+            ..Config::default()
+        }
+    }
+
+    fn find<'a>(items: &'a [DiagItem], id: &str) -> &'a DiagItem {
+        items
+            .iter()
+            .find(|item| item.id == id)
+            .unwrap_or_else(|| panic!("no check {id}"))
+    }
+
+    #[test]
+    fn diag_item_ids_carry_category_and_serialize_stably() {
+        let item =
+            DiagItem::warn("secrets.plaintext:api_key", "m").with_fix(Fix::EncryptPlaintextSecrets);
+        assert_eq!(item.category, "secrets");
+        let report = DoctorReport::new(
+            vec![item, DiagItem::ok("environment.shell", "m")],
+            Vec::new(),
+        );
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["version"], 1);
+        assert_eq!(json["summary"]["warn"], 1);
+        assert_eq!(json["checks"][0]["id"], "secrets.plaintext:api_key");
+        assert_eq!(json["checks"][0]["severity"], "warn");
+        assert_eq!(json["checks"][0]["fix"], "secrets.encrypt_plaintext");
+        assert!(json["checks"][1].get("fix").is_none());
+    }
+
+    #[test]
+    fn exit_status_follows_fail_on_policy() {
+        let report = DoctorReport::new(vec![DiagItem::warn("config.channels", "m")], Vec::new());
+        assert!(report.exit_status(FailOn::Error).is_ok());
+        assert!(report.exit_status(FailOn::Warn).is_err());
+
+        let report = DoctorReport::new(vec![DiagItem::error("daemon.heartbeat", "m")], Vec::new());
+        assert!(report.exit_status(FailOn::Error).is_err());
+        assert!(report.exit_status(FailOn::Never).is_ok());
+
+        let failed_fix = FixOutcome {
+            id: Fix::ReindexMemory.id(),
+            description: String::new(),
+            status: FixStatus::Failed,
+            detail: None,
+        };
+        let report = DoctorReport::new(Vec::new(), vec![failed_fix]);
+        assert!(report.exit_status(FailOn::Never).is_err());
+    }
+
+    #[tokio::test]
+    async fn fix_creates_workspace_and_skills_dir() {
+        let tmp = TempDir::new().unwrap();
+        let config = config_in(&tmp);
+
+        let mut items = Vec::new();
+        check_workspace(&config, &mut items);
+        assert_eq!(find(&items, "workspace.exists").severity, Severity::Error);
+        assert_eq!(planned_fixes(&items), vec![Fix::CreateWorkspaceDirs]);
+
+        Fix::CreateWorkspaceDirs.apply(&config).await.unwrap();
+        let mut items = Vec::new();
+        check_workspace(&config, &mut items);
+        assert_eq!(find(&items, "workspace.subdirs").severity, Severity::Ok);
+        assert_eq!(planned_fixes(&items), vec![Fix::InitSkillsDir]);
+
+        Fix::InitSkillsDir.apply(&config).await.unwrap();
+        let mut items = Vec::new();
+        check_workspace(&config, &mut items);
+        assert_eq!(find(&items, "workspace.skills").severity, Severity::Ok);
+        assert!(planned_fixes(&items).is_empty());
+    }
+
+    #[tokio::test]
+    async fn fix_encrypts_only_secrets_the_loader_decrypts() {
+        let tmp = TempDir::new().unwrap();
+        let config = config_in(&tmp);
+        std::fs::write(
+            &config.config_path,
+            r#"
+api_key = "sk-plaintext"
+
+[channels_config.telegram]
+bot_token = "123456:ABCDEF"
+allowed_users = ["alice"]
+
+[[model_routes]]
+hint = "fast"
+provider = "groq"
+model = "llama"
+api_key = "sk-route"
+"#,
+        )
+        .unwrap();
+
+        let mut items = Vec::new();
+        check_secrets(&config, &mut items);
+        assert_eq!(
+            find(&items, "secrets.plaintext:api_key").fix,
+            Some(Fix::EncryptPlaintextSecrets)
+        );
+        assert_eq!(
+            find(
+                &items,
+                "secrets.plaintext:channels_config.telegram.bot_token"
+            )
+            .fix,
+            None
+        );
+        assert_eq!(
+            find(&items, "secrets.plaintext:model_routes[0].api_key").fix,
+            None
+        );
+
+        Fix::EncryptPlaintextSecrets.apply(&config).await.unwrap();
+        assert!(tmp.path().join("config.toml.bak").exists());
+        let table: toml::Table = std::fs::read_to_string(&config.config_path)
+            .unwrap()
+            .parse()
+            .unwrap();
+        let encrypted = table["api_key"].as_str().unwrap();
+        let store = crate::security::SecretStore::from_config(tmp.path(), &config.secrets);
+        assert_eq!(store.decrypt(encrypted).unwrap(), "sk-plaintext");
+        assert_eq!(
+            plaintext_secret_paths(&table),
+            vec![
+                "channels_config.telegram.bot_token".to_string(),
+                "model_routes[0].api_key".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn fix_rebuilds_out_of_sync_memory_index() {
+        use crate::memory::{Memory, MemoryCategory, SqliteMemory};
+
+        let tmp = TempDir::new().unwrap();
+        let config = config_in(&tmp);
+        let memory = SqliteMemory::new(&config.workspace_dir).unwrap();
+        memory
+            .store(
+                "trip",
+                "User is planning a trip to Porto",
+                MemoryCategory::Core,
+                None,
+            )
+            .await
+            .unwrap();
+        drop(memory);
+
+        let mut items = Vec::new();
+        check_memory(&config, &mut items);
+        assert_eq!(find(&items, "memory.fts").severity, Severity::Ok);
+
+        let conn =
+            rusqlite::Connection::open(config.workspace_dir.join("memory/brain.db")).unwrap();
+        conn.execute(
+            "INSERT INTO memories_fts(memories_fts) VALUES('delete-all')",
+            [],
+        )
+        .unwrap();
+        drop(conn);
+
+        let mut items = Vec::new();
+        check_memory(&config, &mut items);
+        assert_eq!(find(&items, "memory.fts").severity, Severity::Warn);
+        assert_eq!(planned_fixes(&items), vec![Fix::ReindexMemory]);
+
+        Fix::ReindexMemory.apply(&config).await.unwrap();
+        let mut items = Vec::new();
+        check_memory(&config, &mut items);
+        assert_eq!(find(&items, "memory.fts").severity, Severity::Ok);
+    }
+
+    #[tokio::test]
+    async fn fix_prunes_stale_daemon_state() {
+        let tmp = TempDir::new().unwrap();
+        let config = config_in(&tmp);
+        let state = crate::daemon::state_file_path(&config);
+        std::fs::write(
+            &state,
+            r#"{"updated_at":"2020-01-01T00:00:00Z","components":{}}"#,
+        )
+        .unwrap();
+
+        let mut items = Vec::new();
+        check_daemon_state(&config, &mut items);
+        assert_eq!(planned_fixes(&items), vec![Fix::PruneDaemonState]);
+
+        Fix::PruneDaemonState.apply(&config).await.unwrap();
+        assert!(!state.exists());
+    }
+
+    #[test]
+    fn validation_checks_report_version_unknown_keys_and_invalid_values() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("candidate.toml");
+        std::fs::write(
+            &path,
+            r#"
+default_temperature = 0.7
+default_provider = "totally-fake"
+mystery_key = true
+
+[gateway]
+host = ""
+"#,
+        )
+        .unwrap();
+
+        let items = validation_checks(&path);
+        assert_eq!(find(&items, "config.version").severity, Severity::Warn);
+        assert_eq!(
+            find(&items, "config.unknown_key:mystery_key").severity,
+            Severity::Warn
+        );
+        assert_eq!(find(&items, "config.validate").severity, Severity::Error);
+        assert_eq!(
+            find(&items, "config.default_provider").severity,
+            Severity::Error
+        );
+
+        std::fs::write(&path, "not toml = [").unwrap();
+        let items = validation_checks(&path);
+        assert_eq!(items.len(), 1);
+        assert_eq!(find(&items, "config.parse").severity, Severity::Error);
+    }
 }
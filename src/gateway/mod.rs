@@ -11,6 +11,7 @@
 use crate::channels::{Channel, LinqChannel, NextcloudTalkChannel, SendMessage, WhatsAppChannel};
 use crate::config::Config;
 use crate::memory::{self, Memory, MemoryCategory};
+use crate::observability::TraceContext;
 use crate::providers::{self, ChatMessage, Provider, ProviderCapabilityError};
 use crate::runtime;
 use crate::security::pairing::{constant_time_eq, is_public_bind, PairingGuard};
@@ -20,7 +21,7 @@ use crate::util::truncate_with_ellipsis;
 use anyhow::{Context, Result};
 use axum::{
     body::Bytes,
-    extract::{ConnectInfo, Query, State},
+    extract::{ConnectInfo, Path, Query, State},
     http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Json},
     routing::{get, post},
@@ -62,6 +63,23 @@ fn nextcloud_talk_memory_key(msg: &crate::channels::traits::ChannelMessage) -> S
     format!("nextcloud_talk_{}_{}", msg.sender, msg.id)
 }
 
+/// Memory scopes a gateway message from `sender` on `channel` may write,
+/// following the `[memory.scopes]` rule for that channel.
+fn gateway_memory_access(
+    state: &AppState,
+    channel: &str,
+    sender: &str,
+    thread: &str,
+) -> memory::MemoryAccess {
+    let config = state.config.lock();
+    memory::MemoryAccess::for_message(
+        config.memory.scopes.rule_for(channel),
+        channel,
+        sender,
+        thread,
+    )
+}
+
 fn hash_webhook_secret(value: &str) -> String {
     use sha2::{Digest, Sha256};
 
@@ -73,7 +91,7 @@ fn hash_webhook_secret(value: &str) -> String {
 const RATE_LIMITER_SWEEP_INTERVAL_SECS: u64 = 300; // 5 minutes
 
 #[derive(Debug)]
-struct SlidingWindowRateLimiter {
+pub(crate) struct SlidingWindowRateLimiter {
     limit_per_window: u32,
     window: Duration,
     max_keys: usize,
@@ -81,7 +99,7 @@ struct SlidingWindowRateLimiter {
 }
 
 impl SlidingWindowRateLimiter {
-    fn new(limit_per_window: u32, window: Duration, max_keys: usize) -> Self {
+    pub(crate) fn new(limit_per_window: u32, window: Duration, max_keys: usize) -> Self {
         Self {
             limit_per_window,
             window,
@@ -97,7 +115,20 @@ impl SlidingWindowRateLimiter {
         });
     }
 
-    fn allow(&self, key: &str) -> bool {
+    /// Whether `key` has used up its window, without recording a request.
+    pub(crate) fn is_limited(&self, key: &str) -> bool {
+        if self.limit_per_window == 0 {
+            return false;
+        }
+        let cutoff = Instant::now()
+            .checked_sub(self.window)
+            .unwrap_or_else(Instant::now);
+        self.requests.lock().0.get(key).is_some_and(|timestamps| {
+            timestamps.iter().filter(|t| **t > cutoff).count() >= self.limit_per_window as usize
+        })
+    }
+
+    pub(crate) fn allow(&self, key: &str) -> bool {
         if self.limit_per_window == 0 {
             return true;
         }
@@ -307,13 +338,147 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
              [gateway] allow_public_bind = true in config.toml (NOT recommended)."
         );
     }
-    let config_state = Arc::new(Mutex::new(config.clone()));
-
     let addr: SocketAddr = format!("{host}:{port}").parse()?;
     let listener = tokio::net::TcpListener::bind(addr).await?;
     let actual_port = listener.local_addr()?.port();
     let display_addr = format!("{host}:{actual_port}");
 
+    // ── Pairing guard ──────────────────────────────────────
+    // Pairing, rate limits and idempotency are shared by every profile.
+    let pairing = Arc::new(PairingGuard::new(
+        config.gateway.require_pairing,
+        &config.gateway.paired_tokens,
+    ));
+    let rate_limit_max_keys = normalize_max_keys(
+        config.gateway.rate_limit_max_keys,
+        RATE_LIMIT_MAX_KEYS_DEFAULT,
+    );
+    let rate_limiter = Arc::new(GatewayRateLimiter::new(
+        config.gateway.pair_rate_limit_per_minute,
+        config.gateway.webhook_rate_limit_per_minute,
+        rate_limit_max_keys,
+    ));
+    let idempotency_max_keys = normalize_max_keys(
+        config.gateway.idempotency_max_keys,
+        IDEMPOTENCY_MAX_KEYS_DEFAULT,
+    );
+    let idempotency_store = Arc::new(IdempotencyStore::new(
+        Duration::from_secs(config.gateway.idempotency_ttl_secs.max(1)),
+        idempotency_max_keys,
+    ));
+    let guards = GatewayGuards {
+        pairing,
+        rate_limiter,
+        idempotency_store,
+    };
+
+    let state = build_agent_state(&config, &guards)?;
+    let mut profile_states = Vec::with_capacity(config.profiles.len());
+    for profile in &config.profiles {
+        let agent = config.for_profile(profile);
+        tokio::fs::create_dir_all(&agent.workspace_dir).await?;
+        profile_states.push((profile.name.clone(), build_agent_state(&agent, &guards)?));
+    }
+
+    // ── Tunnel ────────────────────────────────────────────────
+    let tunnel = crate::tunnel::create_tunnel(&config.tunnel)?;
+    let mut tunnel_url: Option<String> = None;
+
+    if let Some(ref tun) = tunnel {
+        println!("🔗 Starting {} tunnel...", tun.name());
+        match tun.start(host, actual_port).await {
+            Ok(url) => {
+                println!("🌐 Tunnel active: {url}");
+                tunnel_url = Some(url);
+            }
+            Err(e) => {
+                println!("⚠️  Tunnel failed to start: {e}");
+                println!("   Falling back to local-only mode.");
+            }
+        }
+    }
+
+    println!("🦀 ZeroClaw Gateway listening on http://{display_addr}");
+    if let Some(ref url) = tunnel_url {
+        println!("  🌐 Public URL: {url}");
+    }
+    println!("  POST /pair      — pair a new client (X-Pairing-Code header)");
+    println!("  POST /webhook   — {{\"message\": \"your prompt\"}}");
+    if state.whatsapp.is_some() {
+        println!("  GET  /whatsapp  — Meta webhook verification");
+        println!("  POST /whatsapp  — WhatsApp message webhook");
+    }
+    if state.linq.is_some() {
+        println!("  POST /linq      — Linq message webhook (iMessage/RCS/SMS)");
+    }
+    if state.nextcloud_talk.is_some() {
+        println!("  POST /nextcloud-talk — Nextcloud Talk bot webhook");
+    }
+    if config.cron.enabled {
+        println!("  POST /cron/<id> — trigger a webhook cron job (Bearer <job token>)");
+    }
+    println!("  GET  /health    — health check");
+    println!("  GET  /metrics   — Prometheus metrics");
+    println!("  GET  /dashboard — monitoring dashboard (cost, events, status)");
+    if !profile_states.is_empty() {
+        let names: Vec<&str> = profile_states
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        println!(
+            "  /profiles/<name>/... — the routes above for profile {}",
+            names.join(", ")
+        );
+    }
+    if let Some(code) = guards.pairing.pairing_code() {
+        println!();
+        println!("  🔐 PAIRING REQUIRED — use this one-time code:");
+        println!("     ┌──────────────┐");
+        println!("     │  {code}  │");
+        println!("     └──────────────┘");
+        println!("     Send: POST /pair with header X-Pairing-Code: {code}");
+    } else if guards.pairing.require_pairing() {
+        println!("  🔒 Pairing: ACTIVE (bearer token required)");
+    } else {
+        println!("  ⚠️  Pairing: DISABLED (all requests accepted)");
+    }
+    println!("  Press Ctrl+C to stop.\n");
+
+    crate::health::mark_component_ok("gateway");
+
+    let mut app = agent_routes().with_state(state);
+    for (name, profile_state) in profile_states {
+        app = app.nest(
+            &format!("/profiles/{name}"),
+            agent_routes().with_state(profile_state),
+        );
+    }
+    let app =
+        app.layer(RequestBodyLimitLayer::new(MAX_BODY_SIZE))
+            .layer(TimeoutLayer::with_status_code(
+                StatusCode::REQUEST_TIMEOUT,
+                Duration::from_secs(REQUEST_TIMEOUT_SECS),
+            ));
+
+    // Run the server
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Auth and abuse controls shared by every agent the gateway serves.
+struct GatewayGuards {
+    pairing: Arc<PairingGuard>,
+    rate_limiter: Arc<GatewayRateLimiter>,
+    idempotency_store: Arc<IdempotencyStore>,
+}
+
+/// Handler state for one agent (the top-level config or a profile).
+fn build_agent_state(config: &Config, guards: &GatewayGuards) -> Result<AppState> {
     let provider_name = config
         .default_provider
         .as_deref()
@@ -333,7 +498,8 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             auth_profile_override: None,
             zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
             secrets_encrypt: config.secrets.encrypt,
-            reasoning_enabled: config.runtime.reasoning_enabled,
+            reasoning: config.runtime.effective_reasoning(),
+            cassette: config.cassette.clone(),
         },
     )?);
     let model = config.default_model.clone().unwrap_or_else(|| {
@@ -376,7 +542,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         &config.workspace_dir,
         &config.agents,
         config.api_key.as_deref(),
-        &config,
+        config,
     ));
     // Extract webhook secret for authentication
     let webhook_secret_hash: Option<Arc<str>> =
@@ -484,83 +650,6 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             })
             .map(Arc::from);
 
-    // ── Pairing guard ──────────────────────────────────────
-    let pairing = Arc::new(PairingGuard::new(
-        config.gateway.require_pairing,
-        &config.gateway.paired_tokens,
-    ));
-    let rate_limit_max_keys = normalize_max_keys(
-        config.gateway.rate_limit_max_keys,
-        RATE_LIMIT_MAX_KEYS_DEFAULT,
-    );
-    let rate_limiter = Arc::new(GatewayRateLimiter::new(
-        config.gateway.pair_rate_limit_per_minute,
-        config.gateway.webhook_rate_limit_per_minute,
-        rate_limit_max_keys,
-    ));
-    let idempotency_max_keys = normalize_max_keys(
-        config.gateway.idempotency_max_keys,
-        IDEMPOTENCY_MAX_KEYS_DEFAULT,
-    );
-    let idempotency_store = Arc::new(IdempotencyStore::new(
-        Duration::from_secs(config.gateway.idempotency_ttl_secs.max(1)),
-        idempotency_max_keys,
-    ));
-
-    // ── Tunnel ────────────────────────────────────────────────
-    let tunnel = crate::tunnel::create_tunnel(&config.tunnel)?;
-    let mut tunnel_url: Option<String> = None;
-
-    if let Some(ref tun) = tunnel {
-        println!("🔗 Starting {} tunnel...", tun.name());
-        match tun.start(host, actual_port).await {
-            Ok(url) => {
-                println!("🌐 Tunnel active: {url}");
-                tunnel_url = Some(url);
-            }
-            Err(e) => {
-                println!("⚠️  Tunnel failed to start: {e}");
-                println!("   Falling back to local-only mode.");
-            }
-        }
-    }
-
-    println!("🦀 ZeroClaw Gateway listening on http://{display_addr}");
-    if let Some(ref url) = tunnel_url {
-        println!("  🌐 Public URL: {url}");
-    }
-    println!("  POST /pair      — pair a new client (X-Pairing-Code header)");
-    println!("  POST /webhook   — {{\"message\": \"your prompt\"}}");
-    if whatsapp_channel.is_some() {
-        println!("  GET  /whatsapp  — Meta webhook verification");
-        println!("  POST /whatsapp  — WhatsApp message webhook");
-    }
-    if linq_channel.is_some() {
-        println!("  POST /linq      — Linq message webhook (iMessage/RCS/SMS)");
-    }
-    if nextcloud_talk_channel.is_some() {
-        println!("  POST /nextcloud-talk — Nextcloud Talk bot webhook");
-    }
-    println!("  GET  /health    — health check");
-    println!("  GET  /metrics   — Prometheus metrics");
-    println!("  GET  /dashboard — monitoring dashboard (cost, events, status)");
-    if let Some(code) = pairing.pairing_code() {
-        println!();
-        println!("  🔐 PAIRING REQUIRED — use this one-time code:");
-        println!("     ┌──────────────┐");
-        println!("     │  {code}  │");
-        println!("     └──────────────┘");
-        println!("     Send: POST /pair with header X-Pairing-Code: {code}");
-    } else if pairing.require_pairing() {
-        println!("  🔒 Pairing: ACTIVE (bearer token required)");
-    } else {
-        println!("  ⚠️  Pairing: DISABLED (all requests accepted)");
-    }
-    println!("  Press Ctrl+C to stop.\n");
-
-    crate::health::mark_component_ok("gateway");
-
-    // Build shared state
     let observer: Arc<dyn crate::observability::Observer> =
         Arc::from(crate::observability::create_observer(&config.observability));
 
@@ -569,18 +658,18 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .ok()
         .map(Arc::new);
 
-    let state = AppState {
-        config: config_state,
+    Ok(AppState {
+        config: Arc::new(Mutex::new(config.clone())),
         provider,
         model,
         temperature,
         mem,
         auto_save: config.memory.auto_save,
         webhook_secret_hash,
-        pairing,
+        pairing: Arc::clone(&guards.pairing),
         trust_forwarded_headers: config.gateway.trust_forwarded_headers,
-        rate_limiter,
-        idempotency_store,
+        rate_limiter: Arc::clone(&guards.rate_limiter),
+        idempotency_store: Arc::clone(&guards.idempotency_store),
         whatsapp: whatsapp_channel,
         whatsapp_app_secret,
         linq: linq_channel,
@@ -589,10 +678,13 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         nextcloud_talk_webhook_secret,
         observer,
         cost_tracker,
-    };
+    })
+}
 
-    // Build router with middleware
-    let app = Router::new()
+/// Routes served for each agent: at the root for the top-level config and
+/// under `/profiles/<name>` for every `[[profiles]]` entry.
+fn agent_routes() -> Router<AppState> {
+    Router::new()
         .route("/health", get(handle_health))
         .route("/metrics", get(handle_metrics))
         .route("/dashboard", get(handle_dashboard))
@@ -603,21 +695,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .route("/whatsapp", post(handle_whatsapp_message))
         .route("/linq", post(handle_linq_webhook))
         .route("/nextcloud-talk", post(handle_nextcloud_talk_webhook))
-        .with_state(state)
-        .layer(RequestBodyLimitLayer::new(MAX_BODY_SIZE))
-        .layer(TimeoutLayer::with_status_code(
-            StatusCode::REQUEST_TIMEOUT,
-            Duration::from_secs(REQUEST_TIMEOUT_SECS),
-        ));
-
-    // Run the server
-    axum::serve(
-        listener,
-        app.into_make_service_with_connect_info::<SocketAddr>(),
-    )
-    .await?;
-
-    Ok(())
+        .route("/cron/{job_id}", post(handle_cron_webhook))
 }
 
 // ══════════════════════════════════════════════════════════════════════════════
@@ -870,6 +948,14 @@ async fn run_gateway_chat_with_multimodal(
         .await
 }
 
+/// Trace context from a W3C `traceparent` request header, if present and valid.
+fn trace_context_from_headers(headers: &HeaderMap) -> Option<TraceContext> {
+    headers
+        .get("traceparent")
+        .and_then(|v| v.to_str().ok())
+        .and_then(TraceContext::from_traceparent)
+}
+
 /// Webhook request body
 #[derive(serde::Deserialize)]
 pub struct WebhookBody {
@@ -961,10 +1047,12 @@ async fn handle_webhook(
     let message = &webhook_body.message;
 
     if state.auto_save {
+        // Webhook callers have no platform identity; the client key stands in.
+        let access = gateway_memory_access(&state, "webhook", &rate_key, &rate_key);
         let key = webhook_memory_key();
         let _ = state
             .mem
-            .store(&key, message, MemoryCategory::Conversation, None)
+            .store_scoped(&key, message, MemoryCategory::Conversation, &access.write)
             .await;
     }
 
@@ -991,18 +1079,44 @@ async fn handle_webhook(
             messages_count: 1,
         });
 
-    match run_gateway_chat_with_multimodal(&state, &provider_label, message).await {
+    // Continue the caller's trace when it sent a W3C `traceparent` header.
+    let turn_span = trace_context_from_headers(&headers)
+        .map_or_else(TraceContext::root, |remote| remote.child());
+    let llm_span = turn_span.child();
+    let result = llm_span
+        .scope(run_gateway_chat_with_multimodal(
+            &state,
+            &provider_label,
+            message,
+        ))
+        .await;
+    turn_span.sync_scope(|| {
+        state
+            .observer
+            .record_event(&crate::observability::ObserverEvent::TurnEnd {
+                source: "webhook".to_string(),
+                duration: started_at.elapsed(),
+                success: result.is_ok(),
+            });
+    });
+
+    match result {
         Ok(response) => {
             let duration = started_at.elapsed();
-            state
-                .observer
-                .record_event(&crate::observability::ObserverEvent::LlmResponse {
-                    provider: provider_label.clone(),
-                    model: model_label.clone(),
-                    duration,
-                    success: true,
-                    error_message: None,
-                });
+            llm_span.sync_scope(|| {
+                state
+                    .observer
+                    .record_event(&crate::observability::ObserverEvent::LlmResponse {
+                        provider: provider_label.clone(),
+                        model: model_label.clone(),
+                        duration,
+                        success: true,
+                        error_message: None,
+                        input_tokens: None,
+                        output_tokens: None,
+                        cost_usd: None,
+                    });
+            });
             state.observer.record_metric(
                 &crate::observability::traits::ObserverMetric::RequestLatency(duration),
             );
@@ -1023,15 +1137,20 @@ async fn handle_webhook(
             let duration = started_at.elapsed();
             let sanitized = providers::sanitize_api_error(&e.to_string());
 
-            state
-                .observer
-                .record_event(&crate::observability::ObserverEvent::LlmResponse {
-                    provider: provider_label.clone(),
-                    model: model_label.clone(),
-                    duration,
-                    success: false,
-                    error_message: Some(sanitized.clone()),
-                });
+            llm_span.sync_scope(|| {
+                state
+                    .observer
+                    .record_event(&crate::observability::ObserverEvent::LlmResponse {
+                        provider: provider_label.clone(),
+                        model: model_label.clone(),
+                        duration,
+                        success: false,
+                        error_message: Some(sanitized.clone()),
+                        input_tokens: None,
+                        output_tokens: None,
+                        cost_usd: None,
+                    });
+            });
             state.observer.record_metric(
                 &crate::observability::traits::ObserverMetric::RequestLatency(duration),
             );
@@ -1190,10 +1309,17 @@ async fn handle_whatsapp_message(
 
         // Auto-save to memory
         if state.auto_save {
+            let thread = msg.thread_ts.as_deref().unwrap_or(&msg.reply_target);
+            let access = gateway_memory_access(&state, &msg.channel, &msg.sender, thread);
             let key = whatsapp_memory_key(msg);
             let _ = state
                 .mem
-                .store(&key, &msg.content, MemoryCategory::Conversation, None)
+                .store_scoped(
+                    &key,
+                    &msg.content,
+                    MemoryCategory::Conversation,
+                    &access.write,
+                )
                 .await;
         }
 
@@ -1303,10 +1429,17 @@ async fn handle_linq_webhook(
 
         // Auto-save to memory
         if state.auto_save {
+            let thread = msg.thread_ts.as_deref().unwrap_or(&msg.reply_target);
+            let access = gateway_memory_access(&state, &msg.channel, &msg.sender, thread);
             let key = linq_memory_key(msg);
             let _ = state
                 .mem
-                .store(&key, &msg.content, MemoryCategory::Conversation, None)
+                .store_scoped(
+                    &key,
+                    &msg.content,
+                    MemoryCategory::Conversation,
+                    &access.write,
+                )
                 .await;
         }
 
@@ -1415,10 +1548,17 @@ async fn handle_nextcloud_talk_webhook(
         );
 
         if state.auto_save {
+            let thread = msg.thread_ts.as_deref().unwrap_or(&msg.reply_target);
+            let access = gateway_memory_access(&state, &msg.channel, &msg.sender, thread);
             let key = nextcloud_talk_memory_key(msg);
             let _ = state
                 .mem
-                .store(&key, &msg.content, MemoryCategory::Conversation, None)
+                .store_scoped(
+                    &key,
+                    &msg.content,
+                    MemoryCategory::Conversation,
+                    &access.write,
+                )
                 .await;
         }
 
@@ -1446,6 +1586,92 @@ async fn handle_nextcloud_talk_webhook(
     (StatusCode::OK, Json(serde_json::json!({"status": "ok"})))
 }
 
+/// POST /cron/{job_id} — fire a webhook-triggered cron job
+async fn handle_cron_webhook(
+    State(state): State<AppState>,
+    ConnectInfo(peer_addr): ConnectInfo<SocketAddr>,
+    Path(job_id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let rate_key =
+        client_key_from_request(Some(peer_addr), &headers, state.trust_forwarded_headers);
+    if !state.rate_limiter.allow_webhook(&rate_key) {
+        tracing::warn!("/cron webhook rate limit exceeded");
+        let err = serde_json::json!({
+            "error": "Too many webhook requests. Please retry later.",
+            "retry_after": RATE_LIMIT_WINDOW_SECS,
+        });
+        return (StatusCode::TOO_MANY_REQUESTS, Json(err));
+    }
+
+    let config = state.config.lock().clone();
+    let not_found = || {
+        let err = serde_json::json!({"error": "Cron webhook not found"});
+        (StatusCode::NOT_FOUND, Json(err))
+    };
+    if !config.cron.enabled {
+        return not_found();
+    }
+    let job = match crate::cron::get_job(&config, &job_id) {
+        Ok(job) if matches!(job.schedule, crate::cron::Schedule::Webhook { .. }) => job,
+        Ok(_) => return not_found(),
+        Err(e) => {
+            tracing::debug!("Cron webhook lookup failed: {e}");
+            return not_found();
+        }
+    };
+
+    // Header only: a query-string token would end up in proxy and access logs.
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|auth| auth.strip_prefix("Bearer "))
+        .map(str::trim)
+        .unwrap_or("");
+    if !crate::cron::verify_webhook_token(&job.schedule, token) {
+        tracing::warn!("Cron webhook: rejected request for job {job_id} — invalid token");
+        let err =
+            serde_json::json!({"error": "Unauthorized — invalid or missing cron webhook token"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+
+    if !job.enabled {
+        let err = serde_json::json!({"error": "Cron job is disabled"});
+        return (StatusCode::CONFLICT, Json(err));
+    }
+
+    let payload = if body.iter().all(u8::is_ascii_whitespace) {
+        serde_json::Value::Null
+    } else {
+        match serde_json::from_slice::<serde_json::Value>(&body) {
+            Ok(value) => value,
+            Err(e) => {
+                tracing::warn!("Cron webhook JSON parse error: {e}");
+                let err = serde_json::json!({"error": "Invalid JSON body"});
+                return (StatusCode::BAD_REQUEST, Json(err));
+            }
+        }
+    };
+
+    let input = if payload.is_null() {
+        String::new()
+    } else {
+        payload.to_string()
+    };
+    let job_run = async move {
+        crate::cron::scheduler::run_triggered_job(&config, &job, "webhook", input).await;
+    };
+    // The job outlives this request, so hand the caller's trace over explicitly.
+    match trace_context_from_headers(&headers) {
+        Some(trace) => tokio::spawn(trace.scope(job_run)),
+        None => tokio::spawn(job_run),
+    };
+
+    let body = serde_json::json!({"status": "accepted", "job_id": job_id});
+    (StatusCode::ACCEPTED, Json(body))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2023,11 +2249,60 @@ mod tests {
         let keys = tracking_impl.keys.lock().clone();
         assert_eq!(keys.len(), 2);
         assert_ne!(keys[0], keys[1]);
-        assert!(keys[0].starts_with("webhook_msg_"));
-        assert!(keys[1].starts_with("webhook_msg_"));
+        assert!(memory::unscoped_key(&keys[0]).starts_with("webhook_msg_"));
+        assert!(memory::unscoped_key(&keys[1]).starts_with("webhook_msg_"));
         assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 2);
     }
 
+    #[tokio::test]
+    async fn webhook_autosave_is_not_recalled_by_other_senders() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let sqlite = Arc::new(crate::memory::SqliteMemory::new(tmp.path()).unwrap());
+        let memory: Arc<dyn Memory> = sqlite.clone();
+        let state = AppState {
+            config: Arc::new(Mutex::new(Config::default())),
+            provider: Arc::new(MockProvider::default()),
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: memory,
+            auto_save: true,
+            webhook_secret_hash: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            trust_forwarded_headers: false,
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            linq: None,
+            linq_signing_secret: None,
+            nextcloud_talk: None,
+            nextcloud_talk_webhook_secret: None,
+            observer: Arc::new(crate::observability::NoopObserver),
+            cost_tracker: None,
+        };
+
+        let alice_peer = ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 40_000)));
+        let body = Ok(Json(WebhookBody {
+            message: "My locker code is 4812".into(),
+        }));
+        let response = handle_webhook(State(state.clone()), alice_peer, HeaderMap::new(), body)
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let rule = crate::config::MemoryScopeRule::default();
+        let alice = memory::MemoryAccess::for_message(&rule, "webhook", "10.0.0.1", "10.0.0.1");
+        let bob = memory::MemoryAccess::for_message(&rule, "webhook", "10.0.0.2", "10.0.0.2");
+        let for_bob = sqlite.recall_scoped("locker", 10, &bob.read).await.unwrap();
+        assert!(for_bob.is_empty());
+        let for_alice = sqlite
+            .recall_scoped("locker", 10, &alice.read)
+            .await
+            .unwrap();
+        assert_eq!(for_alice.len(), 1);
+        assert!(for_alice[0].content.contains("4812"));
+    }
+
     #[test]
     fn webhook_secret_hash_is_deterministic_and_nonempty() {
         let secret_a = generate_test_secret();
@@ -2529,4 +2804,84 @@ mod tests {
         assert!(!keys.contains_key("old-key"));
         assert!(keys.contains_key("new-key"));
     }
+
+    #[tokio::test]
+    async fn cron_webhook_requires_matching_token_and_enabled_job() {
+        let temp = tempfile::tempdir().unwrap();
+        let config = Config {
+            workspace_dir: temp.path().join("workspace"),
+            config_path: temp.path().join("config.toml"),
+            ..Config::default()
+        };
+        std::fs::create_dir_all(&config.workspace_dir).unwrap();
+
+        let mut schedule = crate::cron::Schedule::Webhook {
+            token_hash: String::new(),
+        };
+        let token = crate::cron::issue_webhook_token(&mut schedule).unwrap();
+        let job = crate::cron::add_shell_job(&config, None, schedule, "echo hook").unwrap();
+        crate::cron::update_job(
+            &config,
+            &job.id,
+            crate::cron::CronJobPatch {
+                enabled: Some(false),
+                ..crate::cron::CronJobPatch::default()
+            },
+        )
+        .unwrap();
+
+        let state = AppState {
+            config: Arc::new(Mutex::new(config)),
+            provider: Arc::new(MockProvider::default()),
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: Arc::new(MockMemory),
+            auto_save: false,
+            webhook_secret_hash: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            trust_forwarded_headers: false,
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            linq: None,
+            linq_signing_secret: None,
+            nextcloud_talk: None,
+            nextcloud_talk_webhook_secret: None,
+            observer: Arc::new(crate::observability::NoopObserver),
+            cost_tracker: None,
+        };
+
+        let call = |id: &str, token: Option<&str>| {
+            let state = state.clone();
+            let id = id.to_string();
+            let mut headers = HeaderMap::new();
+            if let Some(token) = token {
+                headers.insert(
+                    header::AUTHORIZATION,
+                    HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
+                );
+            }
+            async move {
+                handle_cron_webhook(
+                    State(state),
+                    test_connect_info(),
+                    Path(id),
+                    headers,
+                    Bytes::new(),
+                )
+                .await
+                .into_response()
+                .status()
+            }
+        };
+
+        assert_eq!(call("missing", Some(&token)).await, StatusCode::NOT_FOUND);
+        assert_eq!(call(&job.id, None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            call(&job.id, Some("zcw_wrong")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(call(&job.id, Some(&token)).await, StatusCode::CONFLICT);
+    }
 }
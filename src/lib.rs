@@ -242,6 +242,100 @@ Examples:
         /// Command to run
         command: String,
     },
+    /// Add a task that runs when another task finishes
+    #[command(long_about = "\
+Add a task that runs when another task finishes.
+
+The upstream task's output is exported to the command as \
+ZEROCLAW_UPSTREAM_OUTPUT (with ZEROCLAW_UPSTREAM_STATUS and \
+ZEROCLAW_UPSTREAM_IDS). Use --on to choose which upstream outcome \
+triggers the task: success (default), failure or always.
+
+Examples:
+  zeroclaw cron add-after <task-id> 'sh ./publish.sh'
+  zeroclaw cron add-after <task-id> --on failure 'sh ./alert.sh'")]
+    AddAfter {
+        /// Upstream task ID
+        upstream_id: String,
+        /// Upstream outcome that triggers this task (success, failure, always)
+        #[arg(long, default_value = "success")]
+        on: String,
+        /// Command to run
+        command: String,
+    },
+    /// Add a task triggered by POST /cron/<id> on the gateway
+    #[command(long_about = "\
+Add a task triggered by an HTTP request to the gateway.
+
+A bearer token is generated and printed once; only its hash is \
+stored. Send it as an 'Authorization: Bearer <token>' header with \
+POST /cron/<task-id>; query-string tokens are not accepted. The \
+JSON request body is exported to the command as \
+ZEROCLAW_UPSTREAM_OUTPUT.
+
+Examples:
+  zeroclaw cron add-webhook 'sh ./deploy.sh' --name deploy
+  curl -X POST -H 'Authorization: Bearer <token>' \\
+    -d '{\"ref\":\"main\"}' http://127.0.0.1:3000/cron/<task-id>")]
+    AddWebhook {
+        /// Command to run
+        command: String,
+        /// Optional task name
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// Add a task that runs when workspace files matching a glob change
+    #[command(long_about = "\
+Add a task that runs when workspace files matching a glob change.
+
+The scheduler polls matching files on every tick and runs the task \
+once per batch of changes. The changed paths are exported to the \
+command as ZEROCLAW_UPSTREAM_OUTPUT, one 'added:', 'modified:' or \
+'removed:' line per file. Globs are relative to the workspace.
+
+Examples:
+  zeroclaw cron add-watch 'inbox/*.csv' 'sh ./import.sh'")]
+    AddWatch {
+        /// Workspace-relative glob to watch
+        glob: String,
+        /// Command to run
+        command: String,
+    },
+    /// Add a multi-step workflow from a JSON or TOML definition file
+    #[command(long_about = "\
+Add a multi-step workflow scheduled as a single task.
+
+The definition file lists steps (shell commands or agent prompts) \
+that may depend on each other. A step runs once its dependencies \
+finish and its run_if condition (success, failure, always) holds; \
+dependency output is passed along. Each step is recorded separately \
+in the task's run history.
+
+Example definition (TOML):
+  [[steps]]
+  id = \"build\"
+  command = \"sh ./build.sh\"
+
+  [[steps]]
+  id = \"summarize\"
+  job_type = \"agent\"
+  prompt = \"Summarize the build log\"
+  depends_on = [\"build\"]
+
+Examples:
+  zeroclaw cron add-workflow '0 2 * * *' nightly.toml --name nightly")]
+    AddWorkflow {
+        /// Cron expression
+        expression: String,
+        /// Path to the workflow definition (.toml or .json)
+        file: std::path::PathBuf,
+        /// Optional IANA timezone (e.g. America/Los_Angeles)
+        #[arg(long)]
+        tz: Option<String>,
+        /// Optional workflow name
+        #[arg(long)]
+        name: Option<String>,
+    },
     /// Remove a scheduled task
     Remove {
         /// Task ID
@@ -273,6 +367,37 @@ Examples:
         #[arg(long)]
         name: Option<String>,
     },
+    /// Set a task's misfire, concurrency, timeout and retry policy
+    #[command(long_about = "\
+Set how a scheduled task handles missed runs, overlapping runs, \
+timeouts and retries. Only the options you pass are changed.
+
+--misfire decides what happens to occurrences missed while the daemon \
+was down: run_once (default), run_all (one run per missed occurrence, \
+capped) or skip. --concurrency decides what happens when the task comes \
+due while it is still running: no_overlap (default, drop the new run), \
+queue (run it afterwards) or replace (cancel the running one). \
+--timeout-secs and --max-retries override the scheduler defaults.
+
+Examples:
+  zeroclaw cron policy <task-id> --misfire skip --concurrency queue
+  zeroclaw cron policy <task-id> --timeout-secs 600 --max-retries 0")]
+    Policy {
+        /// Task ID
+        id: String,
+        /// Missed-run policy (run_once, run_all, skip)
+        #[arg(long)]
+        misfire: Option<String>,
+        /// Overlap policy (no_overlap, queue, replace)
+        #[arg(long)]
+        concurrency: Option<String>,
+        /// Per-run timeout in seconds
+        #[arg(long)]
+        timeout_secs: Option<u64>,
+        /// Retries after a failed run
+        #[arg(long)]
+        max_retries: Option<u32>,
+    },
     /// Pause a scheduled task
     Pause {
         /// Task ID
@@ -322,6 +447,52 @@ pub enum MemoryCommands {
         #[arg(long)]
         yes: bool,
     },
+    /// Merge related daily/conversation entries into core facts now
+    Consolidate,
+}
+
+/// Flight recorder subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum TraceCommands {
+    /// List recorded turns, newest first
+    List {
+        /// Maximum number of records to display
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+    /// Show the timeline of a recorded turn
+    Show {
+        /// Trace ID (or a unique prefix)
+        id: String,
+        /// Print the raw JSONL record instead of a summary
+        #[arg(long)]
+        raw: bool,
+    },
+    /// Re-send a recorded turn's first provider request to another model
+    #[command(long_about = "\
+Re-send a recorded turn's first provider request to another model.
+
+The exact messages recorded for the turn's first LLM call are sent \
+again, without tools, and the reply is printed next to the reply \
+recorded for the original model. Tools are never executed during a \
+replay.
+
+Examples:
+  zeroclaw trace replay 4bf92f35 --model gpt-4o
+  zeroclaw trace replay 4bf92f35 --provider anthropic --model claude-sonnet-4")]
+    Replay {
+        /// Trace ID (or a unique prefix)
+        id: String,
+        /// Model to replay against
+        #[arg(long)]
+        model: String,
+        /// Provider to replay against (defaults to the recorded provider)
+        #[arg(long)]
+        provider: Option<String>,
+        /// Temperature override (defaults to the recorded temperature)
+        #[arg(long)]
+        temperature: Option<f64>,
+    },
 }
 
 /// Integration subcommands
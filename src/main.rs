@@ -33,11 +33,12 @@
     dead_code
 )]
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use dialoguer::{Input, Password};
 use serde::{Deserialize, Serialize};
 use std::io::Write;
+use std::path::PathBuf;
 use tracing::{info, warn};
 use tracing_subscriber::{fmt, EnvFilter};
 
@@ -87,7 +88,7 @@ mod util;
 use config::Config;
 
 // Re-export so binary's hardware/peripherals modules can use zerospider::HardwareCommands etc.
-pub use zerospider::{HardwareCommands, PeripheralCommands};
+pub use zerospider::{HardwareCommands, PeripheralCommands, TraceCommands};
 
 /// `ZeroSpider` - Protocol-driven autonomous AI agent runtime.
 #[derive(Parser, Debug)]
@@ -99,6 +100,10 @@ struct Cli {
     #[arg(long, global = true)]
     config_dir: Option<String>,
 
+    /// Overlay the `[profile.<name>]` table from config.toml (e.g. dev, staging, prod)
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -258,9 +263,41 @@ Examples:
     },
 
     /// Run diagnostics for daemon/scheduler/channel freshness
+    #[command(long_about = "\
+Run diagnostics for config, workspace, secrets, memory, daemon and environment.
+
+Each check has a stable ID (e.g. `workspace.exists`, `secrets.plaintext:api_key`) \
+and a severity (ok, warn, error). Exits non-zero when any check at or above \
+--fail-on remains, or when a fix fails.
+
+--fix applies safe remediations: create missing workspace directories, \
+initialize the skills directory, encrypt plaintext secrets, rebuild the \
+memory index, and remove stale daemon state. Add --dry-run to preview.
+
+Examples:
+  zeroclaw doctor
+  zeroclaw doctor --format json
+  zeroclaw doctor --fix --dry-run
+  zeroclaw doctor --fix --fail-on warn")]
     Doctor {
         #[command(subcommand)]
         doctor_command: Option<DoctorCommands>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = doctor::DoctorFormat::Text)]
+        format: doctor::DoctorFormat,
+
+        /// Apply safe remediations for failing checks
+        #[arg(long)]
+        fix: bool,
+
+        /// With --fix, list the remediations without applying them
+        #[arg(long, requires = "fix")]
+        dry_run: bool,
+
+        /// Lowest severity that causes a non-zero exit code
+        #[arg(long, value_enum, default_value_t = doctor::FailOn::Error)]
+        fail_on: doctor::FailOn,
     },
 
     /// Show system status (full details)
@@ -343,6 +380,25 @@ Examples:
         auth_command: AuthCommands,
     },
 
+    /// Manage the encrypted secret store
+    #[command(long_about = "\
+Manage the encrypted secret store.
+
+'rotate' re-encrypts every stored secret (config.toml and auth \
+profiles) under a newly generated key. The new key follows \
+[secrets].key_source, so rotating is also how an install switches \
+between a key file and a passphrase. Set ZEROCLAW_SECRETS_NEW_PASSPHRASE \
+to change the passphrase; otherwise the current one is kept with a \
+fresh salt.
+
+Examples:
+  zeroclaw secrets rotate
+  ZEROCLAW_SECRETS_NEW_PASSPHRASE=... zeroclaw secrets rotate")]
+    Secrets {
+        #[command(subcommand)]
+        secrets_command: SecretsCommands,
+    },
+
     /// Discover and introspect USB hardware
     #[command(long_about = "\
 Discover and introspect USB hardware.
@@ -398,17 +454,48 @@ Examples:
         memory_command: MemoryCommands,
     },
 
+    /// Inspect flight recorder traces (list, show, replay)
+    #[command(long_about = "\
+Inspect turns captured by the flight recorder.
+
+Requires [observability.flight_recorder] enabled = true. Each record \
+holds the assembled prompt, injected memory context, every provider \
+request/response and tool call of one turn, with credentials \
+redacted. 'replay' re-sends a recorded request to another model for \
+comparison.
+
+Examples:
+  zeroclaw trace list
+  zeroclaw trace show 4bf92f35
+  zeroclaw trace show 4bf92f35 --raw
+  zeroclaw trace replay 4bf92f35 --model gpt-4o")]
+    Trace {
+        #[command(subcommand)]
+        trace_command: TraceCommands,
+    },
+
     /// Manage configuration
     #[command(long_about = "\
 Manage ZeroClaw configuration.
 
 Inspect and export configuration settings. Use 'schema' to dump \
 the full JSON Schema for the config file, which documents every \
-available key, type, and default value.
+available key, type, and default value. Use 'show --resolved' to see \
+the effective config after includes, --profile overlays, ${...} \
+interpolation and environment overrides, with the origin of each value.
+
+'validate' and 'diff' work on arbitrary files, e.g. a candidate config \
+before it is rolled out. 'migrate' upgrades an older file to the current \
+config_version, keeping the original as <file>.v<N>.bak.
 
 Examples:
   zeroclaw config schema              # print JSON Schema to stdout
-  zeroclaw config schema > schema.json")]
+  zeroclaw config schema > schema.json
+  zeroclaw config show                # config.toml with secrets masked
+  zeroclaw --profile prod config show --resolved
+  zeroclaw config validate staging.toml --format json
+  zeroclaw config diff ~/.zeroclaw/config.toml staging.toml
+  zeroclaw config migrate --dry-run")]
     Config {
         #[command(subcommand)]
         config_command: ConfigCommands,
@@ -451,6 +538,41 @@ Examples:
 enum ConfigCommands {
     /// Dump the full configuration JSON Schema to stdout
     Schema,
+    /// Print config.toml with secrets masked
+    Show {
+        /// Print the merged effective config with the origin of each value
+        #[arg(long)]
+        resolved: bool,
+    },
+    /// Check a config file without loading it as the active config
+    Validate {
+        /// Config file to check
+        file: PathBuf,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = doctor::DoctorFormat::Text)]
+        format: doctor::DoctorFormat,
+
+        /// Lowest severity that makes the command exit non-zero
+        #[arg(long, value_enum, default_value_t = doctor::FailOn::Error)]
+        fail_on: doctor::FailOn,
+    },
+    /// Show semantic differences between two config files
+    Diff {
+        /// Baseline config file
+        left: PathBuf,
+        /// Config file compared against the baseline
+        right: PathBuf,
+    },
+    /// Upgrade a config file to the current config_version (keeps a backup)
+    Migrate {
+        /// Config file to upgrade (default: the active config.toml)
+        file: Option<PathBuf>,
+
+        /// Report the migration steps without rewriting the file
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -536,6 +658,12 @@ enum AuthCommands {
     Status,
 }
 
+#[derive(Subcommand, Debug)]
+enum SecretsCommands {
+    /// Re-encrypt every stored secret under a newly generated key
+    Rotate,
+}
+
 #[derive(Subcommand, Debug)]
 enum MigrateCommands {
     /// Import memory from an `OpenClaw` workspace into this `ZeroClaw` workspace
@@ -585,6 +713,44 @@ enum CronCommands {
         /// Command to run
         command: String,
     },
+    /// Add a task that runs when another task finishes
+    AddAfter {
+        /// Upstream task ID
+        upstream_id: String,
+        /// Upstream outcome that triggers this task (success, failure, always)
+        #[arg(long, default_value = "success")]
+        on: String,
+        /// Command to run
+        command: String,
+    },
+    /// Add a task triggered by POST /cron/<id> on the gateway
+    AddWebhook {
+        /// Command to run
+        command: String,
+        /// Optional task name
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// Add a task that runs when workspace files matching a glob change
+    AddWatch {
+        /// Workspace-relative glob to watch
+        glob: String,
+        /// Command to run
+        command: String,
+    },
+    /// Add a multi-step workflow from a JSON or TOML definition file
+    AddWorkflow {
+        /// Cron expression
+        expression: String,
+        /// Path to the workflow definition (.toml or .json)
+        file: std::path::PathBuf,
+        /// Optional IANA timezone (e.g. America/Los_Angeles)
+        #[arg(long)]
+        tz: Option<String>,
+        /// Optional workflow name
+        #[arg(long)]
+        name: Option<String>,
+    },
     /// Remove a scheduled task
     Remove {
         /// Task ID
@@ -607,6 +773,23 @@ enum CronCommands {
         #[arg(long)]
         name: Option<String>,
     },
+    /// Set a task's misfire, concurrency, timeout and retry policy
+    Policy {
+        /// Task ID
+        id: String,
+        /// Missed-run policy (run_once, run_all, skip)
+        #[arg(long)]
+        misfire: Option<String>,
+        /// Overlap policy (no_overlap, queue, replace)
+        #[arg(long)]
+        concurrency: Option<String>,
+        /// Per-run timeout in seconds
+        #[arg(long)]
+        timeout_secs: Option<u64>,
+        /// Retries after a failed run
+        #[arg(long)]
+        max_retries: Option<u32>,
+    },
     /// Pause a scheduled task
     Pause {
         /// Task ID
@@ -718,6 +901,8 @@ enum MemoryCommands {
         #[arg(long)]
         yes: bool,
     },
+    /// Merge related daily/conversation entries into core facts now
+    Consolidate,
 }
 
 #[derive(Subcommand, Debug)]
@@ -747,6 +932,12 @@ async fn main() -> Result<()> {
         }
         std::env::set_var("ZEROCLAW_CONFIG_DIR", config_dir);
     }
+    if let Some(profile) = &cli.profile {
+        if profile.trim().is_empty() {
+            bail!("--profile cannot be empty");
+        }
+        std::env::set_var(config::layers::PROFILE_ENV, profile);
+    }
 
     // Completions must remain stdout-only and should not load config or initialize logging.
     // This avoids warnings/log lines corrupting sourced completion scripts.
@@ -756,11 +947,25 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Machine-readable output owns stdout, so log lines go to stderr instead.
+    let log_writer = if matches!(
+        &cli.command,
+        Commands::Doctor {
+            format: doctor::DoctorFormat::Json,
+            ..
+        } | Commands::Config { .. }
+    ) {
+        fmt::writer::BoxMakeWriter::new(std::io::stderr)
+    } else {
+        fmt::writer::BoxMakeWriter::new(std::io::stdout)
+    };
+
     // Initialize logging - respects RUST_LOG env var, defaults to INFO
     let subscriber = fmt::Subscriber::builder()
         .with_env_filter(
             EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
         )
+        .with_writer(log_writer)
         .finish();
 
     tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
@@ -819,6 +1024,43 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Checks on explicit files must work even when the active config is broken.
+    match &cli.command {
+        Commands::Config {
+            config_command:
+                ConfigCommands::Validate {
+                    file,
+                    format,
+                    fail_on,
+                },
+        } => {
+            return doctor::validate_file(
+                file,
+                doctor::DoctorOptions {
+                    format: *format,
+                    fail_on: *fail_on,
+                    ..doctor::DoctorOptions::default()
+                },
+            );
+        }
+        Commands::Config {
+            config_command: ConfigCommands::Diff { left, right },
+        } => {
+            let profile = config::layers::selected_profile();
+            let (left_config, _) = Config::load_file(left, profile.as_deref())?;
+            let (right_config, _) = Config::load_file(right, profile.as_deref())?;
+            let changes = config::diff::diff(&left_config, &right_config)?;
+            if changes.is_empty() {
+                println!("No semantic differences.");
+            }
+            for change in changes {
+                println!("{change}");
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
     // All other commands need config loaded first
     let mut config = Config::load_or_init().await?;
     config.apply_env_overrides();
@@ -833,9 +1075,16 @@ async fn main() -> Result<()> {
             model,
             temperature,
             peripheral,
-        } => agent::run(config, message, provider, model, temperature, peripheral)
-            .await
-            .map(|_| ()),
+        } => Box::pin(agent::run(
+            config,
+            message,
+            provider,
+            model,
+            temperature,
+            peripheral,
+        ))
+        .await
+        .map(|_| ()),
 
         Commands::Gateway { port, host } => {
             let port = port.unwrap_or(config.gateway.port);
@@ -998,7 +1247,13 @@ async fn main() -> Result<()> {
             service::handle_command(&service_command, &config, init_system)
         }
 
-        Commands::Doctor { doctor_command } => match doctor_command {
+        Commands::Doctor {
+            doctor_command,
+            format,
+            fix,
+            dry_run,
+            fail_on,
+        } => match doctor_command {
             Some(DoctorCommands::Models {
                 provider,
                 use_cache,
@@ -1010,7 +1265,15 @@ async fn main() -> Result<()> {
                 .await
                 .map_err(|e| anyhow::anyhow!("doctor models task failed: {e}"))?
             }
-            None => doctor::run(&config),
+            None => {
+                let options = doctor::DoctorOptions {
+                    format,
+                    fix,
+                    dry_run,
+                    fail_on,
+                };
+                doctor::run(&config, options).await
+            }
         },
 
         Commands::Channel { channel_command } => match channel_command {
@@ -1033,8 +1296,14 @@ async fn main() -> Result<()> {
             memory::cli::handle_command(memory_command, &config).await
         }
 
+        Commands::Trace { trace_command } => {
+            observability::trace_cli::handle_command(trace_command, &config).await
+        }
+
         Commands::Auth { auth_command } => handle_auth_command(auth_command, &config).await,
 
+        Commands::Secrets { secrets_command } => handle_secrets_command(secrets_command, &config),
+
         Commands::Hardware { hardware_command } => {
             hardware::handle_command(hardware_command.clone(), &config)
         }
@@ -1055,6 +1324,58 @@ async fn main() -> Result<()> {
                 );
                 Ok(())
             }
+            ConfigCommands::Show { resolved: false } => {
+                let raw = std::fs::read_to_string(&config.config_path)
+                    .with_context(|| format!("Failed to read {}", config.config_path.display()))?;
+                let mut table = toml::Value::Table(raw.parse()?);
+                config::layers::mask_secrets(&mut table);
+                print!("{}", toml::to_string_pretty(&table)?);
+                Ok(())
+            }
+            ConfigCommands::Validate { .. } | ConfigCommands::Diff { .. } => unreachable!(),
+            ConfigCommands::Migrate { file, dry_run } => {
+                let path = file.unwrap_or_else(|| config.config_path.clone());
+                let report = config::migrate::migrate_file(
+                    &path,
+                    if dry_run {
+                        config::migrate::MigrationWrite::DryRun
+                    } else {
+                        config::migrate::MigrationWrite::Always
+                    },
+                )?;
+                let to = config::migrate::CURRENT_CONFIG_VERSION;
+                if report.upgrade.from == to {
+                    println!("{} is already at config_version {to}.", path.display());
+                    return Ok(());
+                }
+                println!(
+                    "{}: config_version {} -> {to}{}",
+                    path.display(),
+                    report.upgrade.from,
+                    if dry_run { " (dry run)" } else { "" }
+                );
+                for step in &report.upgrade.applied {
+                    println!("  - {step}");
+                }
+                if let Some(backup) = &report.backup {
+                    println!("Backup: {}", backup.display());
+                }
+                Ok(())
+            }
+            ConfigCommands::Show { resolved: true } => {
+                let (loaded, layers) = Config::load_or_init_layered().await?;
+                let mut resolved = loaded.clone();
+                resolved.apply_env_overrides();
+                print!(
+                    "{}",
+                    config::layers::render_resolved(
+                        layers.as_ref(),
+                        &toml::Table::try_from(&loaded)?,
+                        &toml::Table::try_from(&resolved)?,
+                    )
+                );
+                Ok(())
+            }
         },
     }
 }
@@ -1104,9 +1425,9 @@ fn pending_openai_login_path(config: &Config) -> std::path::PathBuf {
 }
 
 fn pending_openai_secret_store(config: &Config) -> security::secrets::SecretStore {
-    security::secrets::SecretStore::new(
+    security::secrets::SecretStore::from_config(
         &auth::state_dir_from_config(config),
-        config.secrets.encrypt,
+        &config.secrets,
     )
 }
 
@@ -1227,6 +1548,52 @@ fn format_expiry(profile: &auth::profiles::AuthProfile) -> String {
     }
 }
 
+fn handle_secrets_command(secrets_command: SecretsCommands, config: &Config) -> Result<()> {
+    match secrets_command {
+        SecretsCommands::Rotate => {
+            if !config.secrets.encrypt {
+                bail!("Secret encryption is disabled (secrets.encrypt = false); nothing to rotate");
+            }
+            let state_dir = auth::state_dir_from_config(config);
+            let files = vec![
+                config.config_path.clone(),
+                auth::AuthService::from_config(config)
+                    .profiles_path()
+                    .to_path_buf(),
+                pending_openai_login_path(config),
+            ];
+
+            let mut new_passphrase = std::env::var(security::secrets::NEW_PASSPHRASE_ENV)
+                .ok()
+                .filter(|value| !value.is_empty());
+            if new_passphrase.is_none()
+                && config.secrets.key_source == config::SecretKeySource::Passphrase
+                && std::io::IsTerminal::is_terminal(&std::io::stdin())
+            {
+                let entered = Password::new()
+                    .with_prompt("New passphrase (leave empty to keep the current one)")
+                    .with_confirmation("Confirm new passphrase", "Passphrases do not match")
+                    .allow_empty_password(true)
+                    .interact()?;
+                new_passphrase = Some(entered).filter(|value| !value.is_empty());
+            }
+
+            let report = security::secrets::SecretStore::from_config(&state_dir, &config.secrets)
+                .rotate(&files, new_passphrase)?;
+
+            let source = match report.key_source {
+                config::SecretKeySource::File => "key file",
+                config::SecretKeySource::Passphrase => "passphrase (Argon2id)",
+            };
+            println!("✅ Secret key rotated — now protected by {source}");
+            for (path, count) in &report.files {
+                println!("  {} — {count} secret(s) re-encrypted", path.display());
+            }
+            Ok(())
+        }
+    }
+}
+
 #[allow(clippy::too_many_lines)]
 async fn handle_auth_command(auth_command: AuthCommands, config: &Config) -> Result<()> {
     let auth_service = auth::AuthService::from_config(config);
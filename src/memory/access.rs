@@ -0,0 +1,171 @@
+//! Memory scopes a turn may read and write.
+//!
+//! Channel turns run inside [`MemoryAccess::scope`], built from the
+//! `[memory.scopes]` rule for the channel and the message's sender and
+//! thread. Memory context loading and the memory tools consult
+//! [`MemoryAccess::current`]; without one (CLI sessions) memory is unscoped.
+
+use super::traits::{Memory, MemoryCategory, MemoryEntry, MemoryScope};
+use crate::config::{MemoryScopeKind, MemoryScopeRule};
+use std::future::Future;
+
+tokio::task_local! {
+    static CURRENT: MemoryAccess;
+}
+
+/// Scopes readable and writable by one conversation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryAccess {
+    pub read: Vec<MemoryScope>,
+    pub write: MemoryScope,
+}
+
+impl MemoryAccess {
+    /// Access for a message from `sender` in `thread` on `channel`.
+    pub fn for_message(rule: &MemoryScopeRule, channel: &str, sender: &str, thread: &str) -> Self {
+        let resolve = |kind: MemoryScopeKind| match kind {
+            MemoryScopeKind::Global => MemoryScope::Global,
+            MemoryScopeKind::Channel => MemoryScope::Channel(channel.to_string()),
+            MemoryScopeKind::Sender => MemoryScope::Sender {
+                channel: channel.to_string(),
+                sender: sender.to_string(),
+            },
+            MemoryScopeKind::Thread => MemoryScope::Thread {
+                channel: channel.to_string(),
+                thread: thread.to_string(),
+            },
+        };
+        let mut read: Vec<MemoryScope> = Vec::with_capacity(rule.read.len());
+        for scope in rule.read.iter().copied().map(resolve) {
+            if !read.contains(&scope) {
+                read.push(scope);
+            }
+        }
+        Self {
+            read,
+            write: resolve(rule.write),
+        }
+    }
+
+    /// Access of the conversation the current task runs for, if any.
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(Clone::clone).ok()
+    }
+
+    /// Run `future` with this access as the current one.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT.scope(self, future).await
+    }
+}
+
+/// Recall within the current access, or across all memory when unscoped.
+pub async fn recall_current(
+    memory: &dyn Memory,
+    query: &str,
+    limit: usize,
+) -> anyhow::Result<Vec<MemoryEntry>> {
+    match MemoryAccess::current() {
+        Some(access) => memory.recall_scoped(query, limit, &access.read).await,
+        None => memory.recall(query, limit, None).await,
+    }
+}
+
+/// Store into the current access's write scope, or globally when unscoped.
+pub async fn store_current(
+    memory: &dyn Memory,
+    key: &str,
+    content: &str,
+    category: MemoryCategory,
+) -> anyhow::Result<()> {
+    match MemoryAccess::current() {
+        Some(access) => {
+            memory
+                .store_scoped(key, content, category, &access.write)
+                .await
+        }
+        None => memory.store(key, content, category, None).await,
+    }
+}
+
+/// Key `key` resolves to under the current access.
+pub fn current_storage_key(key: &str) -> String {
+    MemoryAccess::current().map_or_else(|| key.to_string(), |access| access.write.storage_key(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SqliteMemory;
+    use tempfile::TempDir;
+
+    fn sender_rule() -> MemoryScopeRule {
+        MemoryScopeRule::default()
+    }
+
+    #[test]
+    fn for_message_resolves_rule_kinds() {
+        let rule = MemoryScopeRule {
+            read: vec![
+                MemoryScopeKind::Global,
+                MemoryScopeKind::Thread,
+                MemoryScopeKind::Global,
+            ],
+            write: MemoryScopeKind::Channel,
+        };
+        let access = MemoryAccess::for_message(&rule, "slack", "U1", "C1");
+        assert_eq!(
+            access.read,
+            vec![
+                MemoryScope::Global,
+                MemoryScope::Thread {
+                    channel: "slack".into(),
+                    thread: "C1".into()
+                }
+            ]
+        );
+        assert_eq!(access.write, MemoryScope::Channel("slack".into()));
+    }
+
+    #[tokio::test]
+    async fn senders_do_not_see_each_others_memories() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        mem.store("team", "Team standup is at 10", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let alice = MemoryAccess::for_message(&sender_rule(), "telegram", "alice", "group");
+        let bob = MemoryAccess::for_message(&sender_rule(), "telegram", "bob", "group");
+        alice
+            .clone()
+            .scope(store_current(
+                &mem,
+                "note",
+                "Alice's secret is swordfish",
+                MemoryCategory::Conversation,
+            ))
+            .await
+            .unwrap();
+        bob.clone()
+            .scope(store_current(
+                &mem,
+                "note",
+                "Bob's secret is marlin",
+                MemoryCategory::Conversation,
+            ))
+            .await
+            .unwrap();
+
+        let seen_by_bob = bob
+            .scope(recall_current(&mem, "secret standup", 10))
+            .await
+            .unwrap();
+        let contents: Vec<&str> = seen_by_bob.iter().map(|e| e.content.as_str()).collect();
+        assert!(contents.contains(&"Bob's secret is marlin"), "{contents:?}");
+        assert!(!contents.iter().any(|c| c.contains("swordfish")));
+
+        // Unscoped (CLI) recall still sees everything.
+        assert_eq!(recall_current(&mem, "secret", 10).await.unwrap().len(), 2);
+        assert_eq!(mem.count().await.unwrap(), 3, "equal keys did not collide");
+    }
+}
@@ -23,6 +23,7 @@ pub async fn handle_command(command: crate::MemoryCommands, config: &Config) ->
         crate::MemoryCommands::Clear { key, category, yes } => {
             handle_clear(config, key, category, yes).await
         }
+        crate::MemoryCommands::Consolidate => handle_consolidate(config).await,
     }
 }
 
@@ -286,6 +287,49 @@ async fn handle_clear_key(mem: &dyn Memory, key: &str, yes: bool) -> Result<()>
     Ok(())
 }
 
+async fn handle_consolidate(config: &Config) -> Result<()> {
+    // Consolidated facts need embeddings like any other write, so use the
+    // full factory rather than the lightweight CLI backend.
+    let mem = super::create_memory_with_storage_and_routes(
+        &config.memory,
+        &config.embedding_routes,
+        Some(&config.storage.provider.config),
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+    )?;
+    let (provider, model) = super::consolidation::create_consolidation_provider(config)?;
+
+    println!("Consolidating memories with {model}...");
+    let report = super::consolidation::consolidate(
+        mem.as_ref(),
+        provider.as_ref(),
+        &model,
+        &config.memory.consolidation,
+        &config.workspace_dir,
+    )
+    .await?;
+
+    println!(
+        "{} Considered {} entries in {} clusters: {} facts written, {} entries superseded.",
+        style("✓").green().bold(),
+        report.entries_considered,
+        report.clusters,
+        report.facts_written,
+        report.entries_superseded,
+    );
+    if report.failed_clusters > 0 {
+        println!(
+            "  {} clusters skipped (model error or invalid reply); see logs.",
+            report.failed_clusters
+        );
+    }
+    if !config.memory.consolidation.enabled {
+        println!("  Set [memory.consolidation] enabled = true to rank recall by decay.");
+    }
+
+    Ok(())
+}
+
 fn parse_category(s: &str) -> MemoryCategory {
     match s.trim().to_ascii_lowercase().as_str() {
         "core" => MemoryCategory::Core,
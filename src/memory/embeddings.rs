@@ -1,4 +1,9 @@
 use async_trait::async_trait;
+use futures_util::{stream, StreamExt, TryStreamExt};
+use tokio::sync::Semaphore;
+
+const OLLAMA_DEFAULT_BASE_URL: &str = "http://localhost:11434";
+const GEMINI_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
 
 /// Trait for embedding providers — convert text to vectors
 #[async_trait]
@@ -6,6 +11,11 @@ pub trait EmbeddingProvider: Send + Sync {
     /// Provider name
     fn name(&self) -> &str;
 
+    /// Embedding model identifier (empty when not applicable)
+    fn model(&self) -> &str {
+        ""
+    }
+
     /// Embedding dimensions
     fn dimensions(&self) -> usize;
 
@@ -99,6 +109,10 @@ impl EmbeddingProvider for OpenAiEmbedding {
         "openai"
     }
 
+    fn model(&self) -> &str {
+        &self.model
+    }
+
     fn dimensions(&self) -> usize {
         self.dims
     }
@@ -140,54 +154,331 @@ impl EmbeddingProvider for OpenAiEmbedding {
                 .get("embedding")
                 .and_then(|e| e.as_array())
                 .ok_or_else(|| anyhow::anyhow!("Invalid embedding item"))?;
+            embeddings.push(json_to_vector(embedding));
+        }
+
+        Ok(embeddings)
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn json_to_vector(values: &[serde_json::Value]) -> Vec<f32> {
+    values
+        .iter()
+        .filter_map(|v| v.as_f64().map(|f| f as f32))
+        .collect()
+}
+
+// ── Ollama embedding provider ────────────────────────────────
+
+/// Local Ollama server via `POST /api/embed` (batched input).
+pub struct OllamaEmbedding {
+    base_url: String,
+    model: String,
+    dims: usize,
+}
+
+impl OllamaEmbedding {
+    pub fn new(base_url: &str, model: &str, dims: usize) -> Self {
+        let base_url = base_url.trim().trim_end_matches('/');
+        Self {
+            base_url: if base_url.is_empty() {
+                OLLAMA_DEFAULT_BASE_URL.to_string()
+            } else {
+                base_url.to_string()
+            },
+            model: model.to_string(),
+            dims,
+        }
+    }
+
+    fn embed_url(&self) -> String {
+        format!("{}/api/embed", self.base_url)
+    }
+
+    fn parse_response(json: &serde_json::Value) -> anyhow::Result<Vec<Vec<f32>>> {
+        let embeddings = json
+            .get("embeddings")
+            .and_then(|e| e.as_array())
+            .ok_or_else(|| {
+                anyhow::anyhow!("Invalid Ollama embed response: missing 'embeddings'")
+            })?;
+        embeddings
+            .iter()
+            .map(|item| {
+                item.as_array()
+                    .map(|values| json_to_vector(values))
+                    .ok_or_else(|| anyhow::anyhow!("Invalid Ollama embedding item"))
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbedding {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let body = serde_json::json!({
+            "model": self.model,
+            "input": texts,
+        });
+
+        let resp = crate::config::build_runtime_proxy_client("memory.embeddings")
+            .post(self.embed_url())
+            .json(&body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Ollama embedding error {status}: {text}");
+        }
+
+        let json: serde_json::Value = resp.json().await?;
+        Self::parse_response(&json)
+    }
+}
+
+// ── Gemini embedding provider ────────────────────────────────
+
+/// Google Gemini via `embedContent` (single text) or `batchEmbedContents`.
+pub struct GeminiEmbedding {
+    api_key: String,
+    model: String,
+    dims: usize,
+}
 
-            #[allow(clippy::cast_possible_truncation)]
-            let vec: Vec<f32> = embedding
+impl GeminiEmbedding {
+    pub fn new(api_key: &str, model: &str, dims: usize) -> Self {
+        Self {
+            api_key: api_key.to_string(),
+            model: model.trim().trim_start_matches("models/").to_string(),
+            dims,
+        }
+    }
+
+    fn content_request(&self, text: &str) -> serde_json::Value {
+        let mut request = serde_json::json!({
+            "model": format!("models/{}", self.model),
+            "content": { "parts": [{ "text": text }] },
+        });
+        if self.dims > 0 {
+            request["outputDimensionality"] = self.dims.into();
+        }
+        request
+    }
+
+    fn request_for(&self, texts: &[&str]) -> (String, serde_json::Value) {
+        if let [text] = texts {
+            (
+                format!("{GEMINI_BASE_URL}/models/{}:embedContent", self.model),
+                self.content_request(text),
+            )
+        } else {
+            let requests: Vec<serde_json::Value> = texts
                 .iter()
-                .filter_map(|v| v.as_f64().map(|f| f as f32))
+                .map(|text| self.content_request(text))
                 .collect();
+            (
+                format!("{GEMINI_BASE_URL}/models/{}:batchEmbedContents", self.model),
+                serde_json::json!({ "requests": requests }),
+            )
+        }
+    }
 
-            embeddings.push(vec);
+    fn parse_response(json: &serde_json::Value) -> anyhow::Result<Vec<Vec<f32>>> {
+        let values = |item: &serde_json::Value| {
+            item.get("values")
+                .and_then(|v| v.as_array())
+                .map(|values| json_to_vector(values))
+                .ok_or_else(|| anyhow::anyhow!("Invalid Gemini embedding item"))
+        };
+        if let Some(single) = json.get("embedding") {
+            return Ok(vec![values(single)?]);
         }
+        json.get("embeddings")
+            .and_then(|e| e.as_array())
+            .ok_or_else(|| {
+                anyhow::anyhow!("Invalid Gemini embedding response: missing 'embeddings'")
+            })?
+            .iter()
+            .map(values)
+            .collect()
+    }
+}
 
-        Ok(embeddings)
+#[async_trait]
+impl EmbeddingProvider for GeminiEmbedding {
+    fn name(&self) -> &str {
+        "gemini"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let (url, body) = self.request_for(texts);
+        let resp = crate::config::build_runtime_proxy_client("memory.embeddings")
+            .post(url)
+            .header("x-goog-api-key", &self.api_key)
+            .json(&body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Gemini embedding error {status}: {text}");
+        }
+
+        let json: serde_json::Value = resp.json().await?;
+        Self::parse_response(&json)
+    }
+}
+
+// ── Batching / concurrency limits ────────────────────────────
+
+/// Splits large `embed` calls into batches and caps in-flight requests.
+///
+/// The semaphore is shared by every call on this provider, so concurrent
+/// stores and recalls also stay within `max_concurrency`.
+pub struct BatchedEmbedding {
+    inner: Box<dyn EmbeddingProvider>,
+    batch_size: usize,
+    permits: Semaphore,
+    max_concurrency: usize,
+}
+
+impl BatchedEmbedding {
+    pub fn new(
+        inner: Box<dyn EmbeddingProvider>,
+        batch_size: usize,
+        max_concurrency: usize,
+    ) -> Self {
+        let max_concurrency = max_concurrency.max(1);
+        Self {
+            inner,
+            batch_size: batch_size.max(1),
+            permits: Semaphore::new(max_concurrency),
+            max_concurrency,
+        }
+    }
+
+    async fn embed_batch(&self, batch: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let _permit = self.permits.acquire().await?;
+        let vectors = self.inner.embed(batch).await?;
+        if vectors.len() != batch.len() {
+            anyhow::bail!(
+                "Embedding provider '{}' returned {} vectors for {} inputs",
+                self.inner.name(),
+                vectors.len(),
+                batch.len()
+            );
+        }
+        Ok(vectors)
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for BatchedEmbedding {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn dimensions(&self) -> usize {
+        self.inner.dimensions()
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let requests: Vec<_> = texts
+            .chunks(self.batch_size)
+            .map(|batch| self.embed_batch(batch))
+            .collect();
+        let batches: Vec<Vec<Vec<f32>>> = stream::iter(requests)
+            .buffered(self.max_concurrency)
+            .try_collect()
+            .await?;
+        Ok(batches.into_iter().flatten().collect())
     }
 }
 
 // ── Factory ──────────────────────────────────────────────────
 
+/// Build an embedding provider by name.
+///
+/// Unknown names are an error rather than a silent keyword-only fallback;
+/// use `"none"` to disable embeddings explicitly.
 pub fn create_embedding_provider(
     provider: &str,
     api_key: Option<&str>,
     model: &str,
     dims: usize,
-) -> Box<dyn EmbeddingProvider> {
-    match provider {
-        "openai" => {
-            let key = api_key.unwrap_or("");
-            Box::new(OpenAiEmbedding::new(
-                "https://api.openai.com",
-                key,
-                model,
-                dims,
-            ))
+) -> anyhow::Result<Box<dyn EmbeddingProvider>> {
+    let key = api_key.unwrap_or("");
+    let provider: Box<dyn EmbeddingProvider> = match provider.trim() {
+        "" | "none" => Box::new(NoopEmbedding),
+        "openai" => Box::new(OpenAiEmbedding::new(
+            "https://api.openai.com",
+            key,
+            model,
+            dims,
+        )),
+        "openrouter" => Box::new(OpenAiEmbedding::new(
+            "https://openrouter.ai/api/v1",
+            key,
+            model,
+            dims,
+        )),
+        "ollama" => Box::new(OllamaEmbedding::new(OLLAMA_DEFAULT_BASE_URL, model, dims)),
+        "gemini" | "google" => {
+            let key = api_key
+                .map(str::to_string)
+                .or_else(|| std::env::var("GEMINI_API_KEY").ok())
+                .unwrap_or_default();
+            Box::new(GeminiEmbedding::new(&key, model, dims))
         }
-        "openrouter" => {
-            let key = api_key.unwrap_or("");
-            Box::new(OpenAiEmbedding::new(
-                "https://openrouter.ai/api/v1",
-                key,
-                model,
-                dims,
-            ))
+        name if name.starts_with("ollama:") => {
+            let base_url = name.strip_prefix("ollama:").unwrap_or("");
+            Box::new(OllamaEmbedding::new(base_url, model, dims))
         }
         name if name.starts_with("custom:") => {
             let base_url = name.strip_prefix("custom:").unwrap_or("");
-            let key = api_key.unwrap_or("");
             Box::new(OpenAiEmbedding::new(base_url, key, model, dims))
         }
-        _ => Box::new(NoopEmbedding),
-    }
+        other => anyhow::bail!(
+            "Unknown embedding provider '{other}'. Supported: none, openai, openrouter, ollama, ollama:<url>, gemini, custom:<url>"
+        ),
+    };
+    Ok(provider)
 }
 
 #[cfg(test)]
@@ -210,13 +501,14 @@ mod tests {
 
     #[test]
     fn factory_none() {
-        let p = create_embedding_provider("none", None, "model", 1536);
+        let p = create_embedding_provider("none", None, "model", 1536).unwrap();
         assert_eq!(p.name(), "none");
     }
 
     #[test]
     fn factory_openai() {
-        let p = create_embedding_provider("openai", Some("key"), "text-embedding-3-small", 1536);
+        let p = create_embedding_provider("openai", Some("key"), "text-embedding-3-small", 1536)
+            .unwrap();
         assert_eq!(p.name(), "openai");
         assert_eq!(p.dimensions(), 1536);
     }
@@ -228,14 +520,16 @@ mod tests {
             Some("sk-or-test"),
             "openai/text-embedding-3-small",
             1536,
-        );
+        )
+        .unwrap();
         assert_eq!(p.name(), "openai"); // uses OpenAiEmbedding internally
         assert_eq!(p.dimensions(), 1536);
     }
 
     #[test]
     fn factory_custom_url() {
-        let p = create_embedding_provider("custom:http://localhost:1234", None, "model", 768);
+        let p =
+            create_embedding_provider("custom:http://localhost:1234", None, "model", 768).unwrap();
         assert_eq!(p.name(), "openai"); // uses OpenAiEmbedding internally
         assert_eq!(p.dimensions(), 768);
     }
@@ -266,26 +560,143 @@ mod tests {
 
     #[test]
     fn factory_empty_string_returns_noop() {
-        let p = create_embedding_provider("", None, "model", 1536);
+        let p = create_embedding_provider("", None, "model", 1536).unwrap();
         assert_eq!(p.name(), "none");
     }
 
     #[test]
-    fn factory_unknown_provider_returns_noop() {
-        let p = create_embedding_provider("cohere", None, "model", 1536);
-        assert_eq!(p.name(), "none");
+    fn factory_unknown_provider_is_an_error() {
+        let err = create_embedding_provider("cohere", None, "model", 1536)
+            .err()
+            .expect("unknown provider must not fall back to noop");
+        assert!(err
+            .to_string()
+            .contains("Unknown embedding provider 'cohere'"));
+    }
+
+    #[test]
+    fn factory_ollama_and_gemini() {
+        let p = create_embedding_provider("ollama", None, "nomic-embed-text", 768).unwrap();
+        assert_eq!(p.name(), "ollama");
+        assert_eq!(p.model(), "nomic-embed-text");
+
+        let p = create_embedding_provider("ollama:http://gpu-box:11434/", None, "m", 768).unwrap();
+        assert_eq!(p.name(), "ollama");
+
+        let p = create_embedding_provider("gemini", Some("key"), "models/text-embedding-004", 768)
+            .unwrap();
+        assert_eq!(p.name(), "gemini");
+        assert_eq!(p.model(), "text-embedding-004");
+    }
+
+    #[test]
+    fn ollama_embed_url_and_response() {
+        let p = OllamaEmbedding::new("http://localhost:11434/", "m", 3);
+        assert_eq!(p.embed_url(), "http://localhost:11434/api/embed");
+        assert_eq!(
+            OllamaEmbedding::new("", "m", 3).embed_url(),
+            "http://localhost:11434/api/embed"
+        );
+
+        let json = serde_json::json!({
+            "model": "m",
+            "embeddings": [[0.1, 0.2, 0.3], [0.4, 0.5, 0.6]]
+        });
+        let vectors = OllamaEmbedding::parse_response(&json).unwrap();
+        assert_eq!(vectors.len(), 2);
+        assert_eq!(vectors[1], vec![0.4, 0.5, 0.6]);
+        assert!(OllamaEmbedding::parse_response(&serde_json::json!({})).is_err());
+    }
+
+    #[test]
+    fn gemini_uses_embed_content_for_one_text_and_batch_otherwise() {
+        let p = GeminiEmbedding::new("key", "text-embedding-004", 256);
+
+        let (url, body) = p.request_for(&["one"]);
+        assert!(url.ends_with("/models/text-embedding-004:embedContent"));
+        assert_eq!(body["content"]["parts"][0]["text"], "one");
+        assert_eq!(body["outputDimensionality"], 256);
+
+        let (url, body) = p.request_for(&["a", "b"]);
+        assert!(url.ends_with("/models/text-embedding-004:batchEmbedContents"));
+        assert_eq!(body["requests"].as_array().unwrap().len(), 2);
+        assert_eq!(body["requests"][1]["model"], "models/text-embedding-004");
+    }
+
+    #[test]
+    fn gemini_parses_single_and_batch_responses() {
+        let single = serde_json::json!({ "embedding": { "values": [1.0, 2.0] } });
+        assert_eq!(
+            GeminiEmbedding::parse_response(&single).unwrap(),
+            vec![vec![1.0, 2.0]]
+        );
+
+        let batch = serde_json::json!({
+            "embeddings": [{ "values": [1.0] }, { "values": [2.0] }]
+        });
+        assert_eq!(
+            GeminiEmbedding::parse_response(&batch).unwrap(),
+            vec![vec![1.0], vec![2.0]]
+        );
+    }
+
+    #[derive(Default)]
+    struct ProbeStats {
+        in_flight: std::sync::atomic::AtomicUsize,
+        peak: std::sync::atomic::AtomicUsize,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    /// Returns `[len(text)]` per input and records overlapping `embed` calls.
+    struct ProbeEmbedding(std::sync::Arc<ProbeStats>);
+
+    #[async_trait]
+    impl EmbeddingProvider for ProbeEmbedding {
+        fn name(&self) -> &str {
+            "probe"
+        }
+
+        fn dimensions(&self) -> usize {
+            1
+        }
+
+        async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            use std::sync::atomic::Ordering;
+            let stats = &self.0;
+            stats.calls.fetch_add(1, Ordering::SeqCst);
+            let now = stats.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            stats.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            stats.in_flight.fetch_sub(1, Ordering::SeqCst);
+            #[allow(clippy::cast_precision_loss)]
+            Ok(texts.iter().map(|t| vec![t.len() as f32]).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn batched_embedding_splits_preserves_order_and_limits_concurrency() {
+        use std::sync::atomic::Ordering;
+        let stats = std::sync::Arc::new(ProbeStats::default());
+        let batched = BatchedEmbedding::new(Box::new(ProbeEmbedding(stats.clone())), 2, 2);
+        let texts = ["a", "bb", "ccc", "dddd", "eeeee", "ffffff", "g"];
+
+        let vectors = batched.embed(&texts).await.unwrap();
+        let lengths: Vec<f32> = vectors.iter().map(|v| v[0]).collect();
+        assert_eq!(lengths, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 1.0]);
+        assert_eq!(stats.calls.load(Ordering::SeqCst), 4);
+        assert_eq!(stats.peak.load(Ordering::SeqCst), 2);
     }
 
     #[test]
     fn factory_custom_empty_url() {
         // "custom:" with no URL — should still construct without panic
-        let p = create_embedding_provider("custom:", None, "model", 768);
+        let p = create_embedding_provider("custom:", None, "model", 768).unwrap();
         assert_eq!(p.name(), "openai");
     }
 
     #[test]
     fn factory_openai_no_api_key() {
-        let p = create_embedding_provider("openai", None, "text-embedding-3-small", 1536);
+        let p = create_embedding_provider("openai", None, "text-embedding-3-small", 1536).unwrap();
         assert_eq!(p.name(), "openai");
         assert_eq!(p.dimensions(), 1536);
     }
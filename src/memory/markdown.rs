@@ -1,4 +1,4 @@
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::traits::{unscoped_key, Memory, MemoryCategory, MemoryEntry};
 use async_trait::async_trait;
 use chrono::Local;
 use std::path::{Path, PathBuf};
@@ -89,13 +89,22 @@ impl MarkdownMemory {
                     content: clean.to_string(),
                     category: category.clone(),
                     timestamp: filename.to_string(),
-                    session_id: None,
+                    session_id: Self::stored_scope(clean),
                     score: None,
                 }
             })
             .collect()
     }
 
+    /// Scope of a line written by `store` for a scoped key
+    /// (`**sender:telegram:alice/key**: content`); keys are not kept, so
+    /// recall needs this to tell scopes apart.
+    fn stored_scope(line: &str) -> Option<String> {
+        let (key, _) = line.strip_prefix("**")?.split_once("**: ")?;
+        let scope = key.strip_suffix(unscoped_key(key))?.strip_suffix('/')?;
+        (!scope.is_empty()).then(|| scope.to_string())
+    }
+
     async fn read_all_entries(&self) -> anyhow::Result<Vec<MemoryEntry>> {
         let mut entries = Vec::new();
 
@@ -229,6 +238,7 @@ impl Memory for MarkdownMemory {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::memory::MemoryScope;
     use tempfile::TempDir;
 
     fn temp_workspace() -> (TempDir, MarkdownMemory) {
@@ -330,6 +340,36 @@ mod tests {
         assert!(daily.iter().all(|e| e.category == MemoryCategory::Daily));
     }
 
+    #[tokio::test]
+    async fn markdown_recall_scoped_keeps_scopes_apart() {
+        let (_tmp, mem) = temp_workspace();
+        let alice = MemoryScope::Sender {
+            channel: "telegram".into(),
+            sender: "alice".into(),
+        };
+        let bob = MemoryScope::Sender {
+            channel: "telegram".into(),
+            sender: "bob".into(),
+        };
+        mem.store_scoped("pet", "Alice has a cat", MemoryCategory::Daily, &alice)
+            .await
+            .unwrap();
+        mem.store("shared", "Everyone likes cats", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let for_bob = mem
+            .recall_scoped("cat", 10, &[MemoryScope::Global, bob])
+            .await
+            .unwrap();
+        assert_eq!(for_bob.len(), 1);
+        assert!(for_bob[0].content.contains("Everyone"));
+
+        let for_alice = mem.recall_scoped("cat", 10, &[alice]).await.unwrap();
+        assert_eq!(for_alice.len(), 1);
+        assert!(for_alice[0].content.contains("Alice has a cat"));
+    }
+
     #[tokio::test]
     async fn markdown_forget_is_noop() {
         let (_tmp, mem) = temp_workspace();
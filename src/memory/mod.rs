@@ -1,6 +1,8 @@
+pub mod access;
 pub mod backend;
 pub mod chunker;
 pub mod cli;
+pub mod consolidation;
 pub mod embeddings;
 pub mod hygiene;
 pub mod lucid;
@@ -14,6 +16,7 @@ pub mod sqlite;
 pub mod traits;
 pub mod vector;
 
+pub use access::MemoryAccess;
 #[allow(unused_imports)]
 pub use backend::{
     classify_memory_backend, default_memory_backend_key, memory_backend_profile,
@@ -28,7 +31,7 @@ pub use response_cache::ResponseCache;
 pub use sqlite::SqliteMemory;
 pub use traits::Memory;
 #[allow(unused_imports)]
-pub use traits::{MemoryCategory, MemoryEntry};
+pub use traits::{unscoped_key, MemoryCategory, MemoryEntry, MemoryScope};
 
 use crate::config::{EmbeddingRouteConfig, MemoryConfig, StorageProviderConfig};
 #[cfg(feature = "memory-postgres")]
@@ -82,7 +85,7 @@ pub fn effective_memory_backend_name(
 /// Legacy auto-save key used for model-authored assistant summaries.
 /// These entries are treated as untrusted context and should not be re-injected.
 pub fn is_assistant_autosave_key(key: &str) -> bool {
-    let normalized = key.trim().to_ascii_lowercase();
+    let normalized = unscoped_key(key.trim()).to_ascii_lowercase();
     normalized == "assistant_resp" || normalized.starts_with("assistant_resp_")
 }
 
@@ -238,31 +241,6 @@ pub fn create_memory_with_storage_and_routes(
         }
     }
 
-    fn build_sqlite_memory(
-        config: &MemoryConfig,
-        workspace_dir: &Path,
-        resolved_embedding: &ResolvedEmbeddingConfig,
-    ) -> anyhow::Result<SqliteMemory> {
-        let embedder: Arc<dyn embeddings::EmbeddingProvider> =
-            Arc::from(embeddings::create_embedding_provider(
-                &resolved_embedding.provider,
-                resolved_embedding.api_key.as_deref(),
-                &resolved_embedding.model,
-                resolved_embedding.dimensions,
-            ));
-
-        #[allow(clippy::cast_possible_truncation)]
-        let mem = SqliteMemory::with_embedder(
-            workspace_dir,
-            embedder,
-            config.vector_weight as f32,
-            config.keyword_weight as f32,
-            config.embedding_cache_size,
-            config.sqlite_open_timeout_secs,
-        )?;
-        Ok(mem)
-    }
-
     #[cfg(feature = "memory-postgres")]
     fn build_postgres_memory(
         storage_provider: Option<&StorageProviderConfig>,
@@ -296,13 +274,87 @@ pub fn create_memory_with_storage_and_routes(
         );
     }
 
-    create_memory_with_builders(
+    let memory = create_memory_with_builders(
         &backend_name,
         workspace_dir,
         || build_sqlite_memory(config, workspace_dir, &resolved_embedding),
         || build_postgres_memory(storage_provider),
         "",
-    )
+    )?;
+
+    // Consolidation supersedes entries and assigns importance; recall must
+    // honour both.
+    if config.consolidation.enabled && !matches!(backend_kind, MemoryBackendKind::None) {
+        return Ok(Box::new(consolidation::DecayRankedMemory::new(
+            memory,
+            workspace_dir,
+            config.consolidation.decay_half_life_days,
+        )));
+    }
+    Ok(memory)
+}
+
+fn build_embedder(
+    config: &MemoryConfig,
+    resolved_embedding: &ResolvedEmbeddingConfig,
+) -> anyhow::Result<Arc<dyn embeddings::EmbeddingProvider>> {
+    let embedder = embeddings::create_embedding_provider(
+        &resolved_embedding.provider,
+        resolved_embedding.api_key.as_deref(),
+        &resolved_embedding.model,
+        resolved_embedding.dimensions,
+    )?;
+    Ok(if embedder.dimensions() == 0 {
+        Arc::from(embedder)
+    } else {
+        Arc::new(embeddings::BatchedEmbedding::new(
+            embedder,
+            config.embedding_batch_size,
+            config.embedding_max_concurrency,
+        ))
+    })
+}
+
+/// The embedder memory would use, for other subsystems that rank text by
+/// similarity (e.g. tool selection).
+pub fn create_embedder(
+    config: &MemoryConfig,
+    embedding_routes: &[EmbeddingRouteConfig],
+    api_key: Option<&str>,
+) -> anyhow::Result<Arc<dyn embeddings::EmbeddingProvider>> {
+    let resolved_embedding = resolve_embedding_config(config, embedding_routes, api_key);
+    build_embedder(config, &resolved_embedding)
+}
+
+fn build_sqlite_memory(
+    config: &MemoryConfig,
+    workspace_dir: &Path,
+    resolved_embedding: &ResolvedEmbeddingConfig,
+) -> anyhow::Result<SqliteMemory> {
+    let embedder = build_embedder(config, resolved_embedding)?;
+
+    #[allow(clippy::cast_possible_truncation)]
+    let mem = SqliteMemory::with_embedder(
+        workspace_dir,
+        embedder,
+        config.vector_weight as f32,
+        config.keyword_weight as f32,
+        config.embedding_cache_size,
+        config.sqlite_open_timeout_secs,
+    )?;
+    Ok(mem)
+}
+
+/// Open the SQLite store directly, with the embedder the runtime would use,
+/// for maintenance such as `doctor --fix` reindexing.
+pub fn open_sqlite_memory(
+    config: &MemoryConfig,
+    embedding_routes: &[EmbeddingRouteConfig],
+    workspace_dir: &Path,
+    api_key: Option<&str>,
+) -> anyhow::Result<SqliteMemory> {
+    let resolved_embedding = resolve_embedding_config(config, embedding_routes, api_key);
+    build_sqlite_memory(config, workspace_dir, &resolved_embedding)
 }
 
 pub fn create_memory_for_migration(
@@ -5,9 +5,10 @@ use anyhow::Context;
 use async_trait::async_trait;
 use chrono::Local;
 use parking_lot::Mutex;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use std::fmt::Write as _;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
 use std::sync::Arc;
 use std::thread;
@@ -17,6 +18,12 @@ use uuid::Uuid;
 /// Maximum allowed open timeout (seconds) to avoid unreasonable waits.
 const SQLITE_OPEN_TIMEOUT_CAP_SECS: u64 = 300;
 
+/// `memory_meta` key for the embedder that produced the stored vectors.
+const EMBEDDING_SIGNATURE_KEY: &str = "embedding_signature";
+
+/// Rows loaded per round when backfilling embeddings.
+const BACKFILL_CHUNK: usize = 256;
+
 /// SQLite-backed persistent memory — the brain
 ///
 /// Full-stack search engine:
@@ -25,6 +32,8 @@ const SQLITE_OPEN_TIMEOUT_CAP_SECS: u64 = 300;
 /// - **Hybrid Merge**: weighted fusion of vector + keyword results
 /// - **Embedding Cache**: LRU-evicted cache to avoid redundant API calls
 /// - **Safe Reindex**: temp DB → seed → sync → atomic swap → rollback
+/// - **Model tracking**: vectors are dropped and re-embedded when the
+///   embedding provider, model or dimensions change
 pub struct SqliteMemory {
     conn: Arc<Mutex<Connection>>,
     db_path: PathBuf,
@@ -32,6 +41,8 @@ pub struct SqliteMemory {
     vector_weight: f32,
     keyword_weight: f32,
     cache_max: usize,
+    /// Rows without vectors are embedded before the next recall.
+    backfill_pending: AtomicBool,
 }
 
 impl SqliteMemory {
@@ -82,6 +93,7 @@ impl SqliteMemory {
         )?;
 
         Self::init_schema(&conn)?;
+        let backfill_pending = Self::sync_embedding_signature(&conn, embedder.as_ref())?;
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
@@ -90,9 +102,62 @@ impl SqliteMemory {
             vector_weight,
             keyword_weight,
             cache_max,
+            backfill_pending: AtomicBool::new(backfill_pending),
         })
     }
 
+    /// Compare the configured embedder with the one that produced the stored
+    /// vectors. Vectors from a different provider, model or dimension count
+    /// are not comparable, so they are cleared (along with the content-hash
+    /// cache) and re-embedded lazily. Returns whether a backfill is needed.
+    fn sync_embedding_signature(
+        conn: &Connection,
+        embedder: &dyn EmbeddingProvider,
+    ) -> anyhow::Result<bool> {
+        if embedder.dimensions() == 0 {
+            return Ok(false); // Keyword-only: keep existing vectors untouched
+        }
+
+        let signature = format!(
+            "{}:{}:{}",
+            embedder.name(),
+            embedder.model(),
+            embedder.dimensions()
+        );
+        let stored: Option<String> = conn
+            .query_row(
+                "SELECT value FROM memory_meta WHERE key = ?1",
+                params![EMBEDDING_SIGNATURE_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        if let Some(previous) = stored.as_deref().filter(|prev| *prev != signature) {
+            tracing::info!(
+                previous,
+                current = signature.as_str(),
+                "Embedding model changed; stored memories will be re-embedded"
+            );
+            conn.execute_batch(
+                "UPDATE memories SET embedding = NULL;
+                 DELETE FROM embedding_cache;",
+            )?;
+        }
+        if stored.as_deref() != Some(signature.as_str()) {
+            conn.execute(
+                "INSERT OR REPLACE INTO memory_meta (key, value) VALUES (?1, ?2)",
+                params![EMBEDDING_SIGNATURE_KEY, signature],
+            )?;
+        }
+
+        let missing: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM memories WHERE embedding IS NULL)",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(missing)
+    }
+
     /// Open SQLite connection, optionally with a timeout (for locked/slow storage).
     fn open_connection(
         db_path: &Path,
@@ -168,7 +233,13 @@ impl SqliteMemory {
                 created_at   TEXT NOT NULL,
                 accessed_at  TEXT NOT NULL
             );
-            CREATE INDEX IF NOT EXISTS idx_cache_accessed ON embedding_cache(accessed_at);",
+            CREATE INDEX IF NOT EXISTS idx_cache_accessed ON embedding_cache(accessed_at);
+
+            -- Backend metadata (embedding signature, ...)
+            CREATE TABLE IF NOT EXISTS memory_meta (
+                key   TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );",
         )?;
 
         // Migration: add session_id column if not present (safe to run repeatedly)
@@ -377,7 +448,6 @@ impl SqliteMemory {
     }
 
     /// Safe reindex: rebuild FTS5 + embeddings with rollback on failure
-    #[allow(dead_code)]
     pub async fn reindex(&self) -> anyhow::Result<usize> {
         // Step 1: Rebuild FTS5
         {
@@ -391,42 +461,85 @@ impl SqliteMemory {
         }
 
         // Step 2: Re-embed all memories that lack embeddings
+        self.embed_missing().await
+    }
+
+    /// Embed every memory that has no vector, in batches. Stops at the first
+    /// embedding error so an unreachable backend costs one request, not one
+    /// per row.
+    async fn embed_missing(&self) -> anyhow::Result<usize> {
         if self.embedder.dimensions() == 0 {
             return Ok(0);
         }
 
-        let conn = self.conn.clone();
-        let entries: Vec<(String, String)> = tokio::task::spawn_blocking(move || {
-            let conn = conn.lock();
-            let mut stmt =
-                conn.prepare("SELECT id, content FROM memories WHERE embedding IS NULL")?;
-            let rows = stmt.query_map([], |row| {
-                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-            })?;
-            Ok::<_, anyhow::Error>(rows.filter_map(std::result::Result::ok).collect())
-        })
-        .await??;
-
         let mut count = 0;
-        for (id, content) in &entries {
-            if let Ok(Some(emb)) = self.get_or_compute_embedding(content).await {
-                let bytes = vector::vec_to_bytes(&emb);
-                let conn = self.conn.clone();
-                let id = id.clone();
-                tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
-                    let conn = conn.lock();
-                    conn.execute(
+        loop {
+            let conn = self.conn.clone();
+            let entries: Vec<(String, String)> = tokio::task::spawn_blocking(move || {
+                let conn = conn.lock();
+                let mut stmt = conn
+                    .prepare("SELECT id, content FROM memories WHERE embedding IS NULL LIMIT ?1")?;
+                let rows = stmt.query_map(params![BACKFILL_CHUNK], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                })?;
+                Ok::<_, anyhow::Error>(rows.filter_map(std::result::Result::ok).collect())
+            })
+            .await??;
+            if entries.is_empty() {
+                return Ok(count);
+            }
+
+            let texts: Vec<&str> = entries
+                .iter()
+                .map(|(_, content)| content.as_str())
+                .collect();
+            let vectors = self.embedder.embed(&texts).await?;
+            if vectors.len() != entries.len() {
+                anyhow::bail!(
+                    "embedding provider returned {} vectors for {} memories",
+                    vectors.len(),
+                    entries.len()
+                );
+            }
+
+            let updates: Vec<(String, Vec<u8>)> = entries
+                .into_iter()
+                .zip(&vectors)
+                .map(|((id, _), emb)| (id, vector::vec_to_bytes(emb)))
+                .collect();
+            count += updates.len();
+            let conn = self.conn.clone();
+            tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+                let mut conn = conn.lock();
+                let tx = conn.transaction()?;
+                for (id, bytes) in &updates {
+                    tx.execute(
                         "UPDATE memories SET embedding = ?1 WHERE id = ?2",
                         params![bytes, id],
                     )?;
-                    Ok(())
-                })
-                .await??;
-                count += 1;
-            }
+                }
+                tx.commit()?;
+                Ok(())
+            })
+            .await??;
         }
+    }
 
-        Ok(count)
+    /// Run a pending backfill (after an embedding model change or when rows
+    /// were stored while embeddings were disabled) until one succeeds; a
+    /// failed attempt is retried on the next recall.
+    async fn backfill_if_pending(&self) {
+        if !self.backfill_pending.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        match self.embed_missing().await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!(count, "Re-embedded stored memories"),
+            Err(e) => {
+                self.backfill_pending.store(true, Ordering::SeqCst);
+                tracing::warn!("memory embedding backfill failed, will retry: {e}");
+            }
+        }
     }
 }
 
@@ -486,6 +599,8 @@ impl Memory for SqliteMemory {
             return Ok(Vec::new());
         }
 
+        self.backfill_if_pending().await;
+
         // Compute query embedding (async, before blocking work)
         let query_embedding = self.get_or_compute_embedding(query).await?;
 
@@ -1586,6 +1701,154 @@ mod tests {
         assert_eq!(results.len(), 1);
     }
 
+    /// Deterministic embedder: one-hot-ish vectors of a fixed size, counting
+    /// how many texts it was asked to embed. The first `failures` calls fail.
+    struct FixedEmbedding {
+        model: &'static str,
+        dims: usize,
+        embedded: Arc<std::sync::atomic::AtomicUsize>,
+        failures: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for FixedEmbedding {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        fn model(&self) -> &str {
+            self.model
+        }
+
+        fn dimensions(&self) -> usize {
+            self.dims
+        }
+
+        async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            let failing = self
+                .failures
+                .fetch_update(
+                    std::sync::atomic::Ordering::SeqCst,
+                    std::sync::atomic::Ordering::SeqCst,
+                    |left| left.checked_sub(1),
+                )
+                .is_ok();
+            anyhow::ensure!(!failing, "embedding backend unavailable");
+            self.embedded
+                .fetch_add(texts.len(), std::sync::atomic::Ordering::SeqCst);
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let mut v = vec![0.0; self.dims];
+                    v[text.len() % self.dims] = 1.0;
+                    v
+                })
+                .collect())
+        }
+    }
+
+    fn sqlite_with(
+        dir: &Path,
+        model: &'static str,
+        dims: usize,
+    ) -> (SqliteMemory, Arc<std::sync::atomic::AtomicUsize>) {
+        failing_sqlite_with(dir, model, dims, 0)
+    }
+
+    fn failing_sqlite_with(
+        dir: &Path,
+        model: &'static str,
+        dims: usize,
+        failures: usize,
+    ) -> (SqliteMemory, Arc<std::sync::atomic::AtomicUsize>) {
+        let embedded = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let embedder = Arc::new(FixedEmbedding {
+            model,
+            dims,
+            embedded: embedded.clone(),
+            failures: std::sync::atomic::AtomicUsize::new(failures),
+        });
+        let mem = SqliteMemory::with_embedder(dir, embedder, 0.7, 0.3, 100, None).unwrap();
+        (mem, embedded)
+    }
+
+    fn stored_vector_lengths(mem: &SqliteMemory) -> Vec<Option<usize>> {
+        let conn = mem.conn.lock();
+        let mut stmt = conn
+            .prepare("SELECT embedding FROM memories ORDER BY key")
+            .unwrap();
+        stmt.query_map([], |row| row.get::<_, Option<Vec<u8>>>(0))
+            .unwrap()
+            .map(|blob| blob.unwrap().map(|b| vector::bytes_to_vec(&b).len()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn embedding_model_change_reembeds_stored_memories() {
+        let tmp = TempDir::new().unwrap();
+        {
+            let (mem, _) = sqlite_with(tmp.path(), "small", 4);
+            mem.store("a", "alpha", MemoryCategory::Core, None)
+                .await
+                .unwrap();
+            mem.store("b", "beta text", MemoryCategory::Core, None)
+                .await
+                .unwrap();
+            assert_eq!(stored_vector_lengths(&mem), vec![Some(4), Some(4)]);
+        }
+
+        // Same signature: nothing is invalidated.
+        {
+            let (mem, embedded) = sqlite_with(tmp.path(), "small", 4);
+            mem.recall("gamma", 5, None).await.unwrap();
+            assert_eq!(embedded.load(std::sync::atomic::Ordering::SeqCst), 1);
+        }
+
+        // New model and dimensions: vectors are cleared, then rebuilt on recall.
+        let (mem, embedded) = sqlite_with(tmp.path(), "large", 8);
+        assert_eq!(stored_vector_lengths(&mem), vec![None, None]);
+        let results = mem.recall("alpha", 5, None).await.unwrap();
+        assert!(!results.is_empty());
+        assert_eq!(stored_vector_lengths(&mem), vec![Some(8), Some(8)]);
+        // Two backfilled memories plus the query itself.
+        assert_eq!(embedded.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn failed_backfill_is_retried_on_next_recall() {
+        let tmp = TempDir::new().unwrap();
+        {
+            let (mem, _) = sqlite_with(tmp.path(), "small", 4);
+            mem.store("a", "alpha", MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+
+        // The backfill's embedding call fails once; the query embedding after
+        // it succeeds.
+        let (mem, _) = failing_sqlite_with(tmp.path(), "large", 8, 1);
+        mem.recall("alpha", 5, None).await.unwrap();
+        assert_eq!(stored_vector_lengths(&mem), vec![None]);
+
+        mem.recall("alpha", 5, None).await.unwrap();
+        assert_eq!(stored_vector_lengths(&mem), vec![Some(8)]);
+    }
+
+    #[tokio::test]
+    async fn enabling_embeddings_backfills_keyword_only_rows() {
+        let tmp = TempDir::new().unwrap();
+        {
+            let mem = SqliteMemory::new(tmp.path()).unwrap();
+            mem.store("a", "stored without vectors", MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+
+        let (mem, _) = sqlite_with(tmp.path(), "small", 4);
+        assert_eq!(mem.reindex().await.unwrap(), 1);
+        assert_eq!(stored_vector_lengths(&mem), vec![Some(4)]);
+    }
+
     // ── Edge cases: content_hash ─────────────────────────────────
 
     #[test]
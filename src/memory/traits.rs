@@ -38,6 +38,97 @@ impl std::fmt::Display for MemoryCategory {
     }
 }
 
+/// Namespace a memory lives in.
+///
+/// Scoped entries carry [`MemoryScope::key`] as their `session_id` and a
+/// scope-prefixed storage key, so equal keys in different scopes never
+/// overwrite each other. Entries with neither (including everything stored
+/// before scopes existed) are global.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MemoryScope {
+    /// Shared by every conversation.
+    Global,
+    /// Every conversation on one channel.
+    Channel(String),
+    /// One sender on one channel.
+    Sender { channel: String, sender: String },
+    /// One thread (or chat, when the platform has no threads) on one channel.
+    Thread { channel: String, thread: String },
+}
+
+/// Separates the scope from the caller's key in a scoped storage key.
+const SCOPE_KEY_SEPARATOR: char = '/';
+
+/// Candidates first fetched per requested entry when filtering recall by scope.
+const SCOPED_RECALL_OVERFETCH: usize = 4;
+
+fn escape_scope_part(part: &str) -> String {
+    part.replace('%', "%25").replace(SCOPE_KEY_SEPARATOR, "%2F")
+}
+
+impl MemoryScope {
+    /// Scope identifier stored as the entry's `session_id`; `None` for global.
+    pub fn key(&self) -> Option<String> {
+        match self {
+            Self::Global => None,
+            Self::Channel(channel) => Some(format!("channel:{}", escape_scope_part(channel))),
+            Self::Sender { channel, sender } => Some(format!(
+                "sender:{}:{}",
+                escape_scope_part(channel),
+                escape_scope_part(sender)
+            )),
+            Self::Thread { channel, thread } => Some(format!(
+                "thread:{}:{}",
+                escape_scope_part(channel),
+                escape_scope_part(thread)
+            )),
+        }
+    }
+
+    /// Key `key` is stored under in this scope.
+    pub fn storage_key(&self, key: &str) -> String {
+        scoped_storage_key(self.key().as_deref(), key)
+    }
+
+    /// Scope identifier of a stored entry; `None` for global entries.
+    ///
+    /// Falls back to the storage-key prefix for backends that do not persist
+    /// `session_id`.
+    pub fn key_of(entry: &MemoryEntry) -> Option<String> {
+        entry
+            .session_id
+            .clone()
+            .or_else(|| split_scoped_key(&entry.key).map(|(scope, _)| scope.to_string()))
+    }
+
+    /// Whether `entry` belongs to this scope.
+    pub fn contains(&self, entry: &MemoryEntry) -> bool {
+        Self::key_of(entry) == self.key()
+    }
+}
+
+fn split_scoped_key(key: &str) -> Option<(&str, &str)> {
+    let (scope, rest) = key.split_once(SCOPE_KEY_SEPARATOR)?;
+    ["channel:", "sender:", "thread:"]
+        .iter()
+        .any(|prefix| scope.starts_with(prefix))
+        .then_some((scope, rest))
+}
+
+/// Key `key` is stored under in the scope identified by `scope_key`
+/// (as returned by [`MemoryScope::key`] or [`MemoryScope::key_of`]).
+pub(crate) fn scoped_storage_key(scope_key: Option<&str>, key: &str) -> String {
+    match scope_key {
+        Some(scope) => format!("{scope}{SCOPE_KEY_SEPARATOR}{key}"),
+        None => key.to_string(),
+    }
+}
+
+/// The caller's key with any scope prefix removed (for display).
+pub fn unscoped_key(key: &str) -> &str {
+    split_scoped_key(key).map_or(key, |(_, rest)| rest)
+}
+
 /// Core memory trait — implement for any persistence backend
 #[async_trait]
 pub trait Memory: Send + Sync {
@@ -79,6 +170,52 @@ pub trait Memory: Send + Sync {
 
     /// Health check
     async fn health_check(&self) -> bool;
+
+    /// Store a memory in `scope` (see [`MemoryScope`]).
+    async fn store_scoped(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        scope: &MemoryScope,
+    ) -> anyhow::Result<()> {
+        self.store(
+            &scope.storage_key(key),
+            content,
+            category,
+            scope.key().as_deref(),
+        )
+        .await
+    }
+
+    /// Recall memories that belong to any of `scopes`.
+    async fn recall_scoped(
+        &self,
+        query: &str,
+        limit: usize,
+        scopes: &[MemoryScope],
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if scopes.is_empty() {
+            return Ok(Vec::new());
+        }
+        // Backends filter by at most one session and global entries have
+        // none, so over-fetch unfiltered and keep what the scopes cover,
+        // widening the window until enough match or the backend runs dry.
+        let mut fetch = limit.max(1).saturating_mul(SCOPED_RECALL_OVERFETCH);
+        loop {
+            let candidates = self.recall(query, fetch, None).await?;
+            let exhausted = candidates.len() < fetch || fetch == usize::MAX;
+            let matched: Vec<MemoryEntry> = candidates
+                .into_iter()
+                .filter(|entry| scopes.iter().any(|scope| scope.contains(entry)))
+                .take(limit)
+                .collect();
+            if matched.len() >= limit || exhausted {
+                return Ok(matched);
+            }
+            fetch = fetch.saturating_mul(2);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -129,4 +266,88 @@ mod tests {
         assert_eq!(parsed.session_id.as_deref(), Some("session-abc"));
         assert_eq!(parsed.score, Some(0.98));
     }
+
+    fn entry(key: &str, session_id: Option<&str>) -> MemoryEntry {
+        MemoryEntry {
+            id: key.into(),
+            key: key.into(),
+            content: String::new(),
+            category: MemoryCategory::Conversation,
+            timestamp: String::new(),
+            session_id: session_id.map(String::from),
+            score: None,
+        }
+    }
+
+    #[test]
+    fn memory_scope_keys_namespace_storage() {
+        let alice = MemoryScope::Sender {
+            channel: "telegram".into(),
+            sender: "al/ice".into(),
+        };
+        assert_eq!(alice.key().as_deref(), Some("sender:telegram:al%2Fice"));
+        let stored = alice.storage_key("favorite_color");
+        assert_eq!(stored, "sender:telegram:al%2Fice/favorite_color");
+        assert_eq!(unscoped_key(&stored), "favorite_color");
+        assert_eq!(unscoped_key("notes/today"), "notes/today");
+        assert_eq!(MemoryScope::Global.storage_key("k"), "k");
+    }
+
+    #[test]
+    fn memory_scope_contains_matches_session_or_key_prefix() {
+        let alice = MemoryScope::Sender {
+            channel: "telegram".into(),
+            sender: "alice".into(),
+        };
+        let bob = MemoryScope::Sender {
+            channel: "telegram".into(),
+            sender: "bob".into(),
+        };
+        let stored = entry("k", Some("sender:telegram:alice"));
+        assert!(alice.contains(&stored));
+        assert!(!bob.contains(&stored));
+        assert!(!MemoryScope::Global.contains(&stored));
+
+        // Backends that drop session_id still keep the key prefix.
+        let markdown = entry("sender:telegram:alice/k", None);
+        assert!(alice.contains(&markdown));
+        assert!(!MemoryScope::Global.contains(&markdown));
+
+        assert!(MemoryScope::Global.contains(&entry("legacy", None)));
+    }
+
+    #[tokio::test]
+    async fn recall_scoped_finds_matches_ranked_behind_other_scopes() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mem = crate::memory::SqliteMemory::new(tmp.path()).unwrap();
+        let alice = MemoryScope::Sender {
+            channel: "telegram".into(),
+            sender: "alice".into(),
+        };
+        let bob = MemoryScope::Sender {
+            channel: "telegram".into(),
+            sender: "bob".into(),
+        };
+        let limit = 2;
+        for i in 0..limit * SCOPED_RECALL_OVERFETCH * 3 {
+            mem.store_scoped(&format!("cat-{i}"), "cat", MemoryCategory::Core, &bob)
+                .await
+                .unwrap();
+        }
+        mem.store_scoped(
+            "pet",
+            "Alice adopted a cat from the shelter near the old harbour last spring",
+            MemoryCategory::Core,
+            &alice,
+        )
+        .await
+        .unwrap();
+
+        let recalled = mem
+            .recall_scoped("cat", limit, std::slice::from_ref(&alice))
+            .await
+            .unwrap();
+        assert_eq!(recalled.len(), 1);
+        assert!(alice.contains(&recalled[0]));
+    }
 }
@@ -0,0 +1,491 @@
+//! Local flight recorder: one JSONL file per trace for post-mortem debugging.
+//!
+//! Unlike the other backends this one consumes content events, so a record
+//! holds the assembled prompt, every provider request/response and every
+//! tool's arguments and output. Strings are passed through [`Redactor`]
+//! before they hit disk, and old records are pruned against the configured
+//! count and size caps whenever a turn ends.
+//!
+//! Records are read back by `zeroclaw trace list|show|replay`.
+
+use super::traits::{Observer, ObserverEvent, ObserverMetric};
+use super::TraceContext;
+use crate::config::FlightRecorderConfig;
+use anyhow::{bail, Context, Result};
+use parking_lot::Mutex;
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use std::any::Any;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::{Duration, SystemTime};
+
+const RECORD_EXTENSION: &str = "jsonl";
+const REDACTED: &str = "[REDACTED]";
+
+static SENSITIVE_KEY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)token|api[_-]?key|password|secret|user[_-]?key|bearer|credential|authorization",
+    )
+    .unwrap()
+});
+
+/// Built-in value patterns and their replacements.
+static BUILTIN_PATTERNS: LazyLock<Vec<(Regex, &'static str)>> = LazyLock::new(|| {
+    vec![
+        (
+            Regex::new(
+                r#"(?i)\b(token|api[_-]?key|password|secret|user[_-]?key|credential)(["']?\s*[:=]\s*["']?)[^\s"',;]{8,}"#,
+            )
+            .unwrap(),
+            "${1}${2}[REDACTED]",
+        ),
+        (
+            Regex::new(r"(?i)\bbearer\s+[a-z0-9._~+/=-]{8,}").unwrap(),
+            "Bearer [REDACTED]",
+        ),
+        (
+            Regex::new(r"\b(?:sk|xox[abpr]|ghp|gho|glpat)-[A-Za-z0-9_-]{16,}").unwrap(),
+            REDACTED,
+        ),
+    ]
+});
+
+/// Scrubs credentials and operator-configured patterns from recorded payloads.
+pub struct Redactor {
+    extra: Vec<Regex>,
+}
+
+impl Redactor {
+    /// Build a redactor from extra patterns. Invalid patterns are skipped with
+    /// a warning so a typo never disables recording altogether.
+    pub fn new(patterns: &[String]) -> Self {
+        let extra = patterns
+            .iter()
+            .filter_map(|pattern| match Regex::new(pattern) {
+                Ok(re) => Some(re),
+                Err(e) => {
+                    tracing::warn!(pattern = %pattern, "Ignoring invalid flight recorder redact pattern: {e}");
+                    None
+                }
+            })
+            .collect();
+        Self { extra }
+    }
+
+    /// Redact a single string.
+    pub fn redact_str(&self, input: &str) -> String {
+        let mut out = input.to_string();
+        for (re, replacement) in BUILTIN_PATTERNS.iter() {
+            out = re.replace_all(&out, *replacement).into_owned();
+        }
+        for re in &self.extra {
+            out = re.replace_all(&out, REDACTED).into_owned();
+        }
+        out
+    }
+
+    /// Redact every string in `value`. String values under sensitive object
+    /// keys (`api_key`, `password`, ...) are replaced outright.
+    pub fn redact_value(&self, value: &mut Value) {
+        match value {
+            Value::String(s) => *s = self.redact_str(s),
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact_value(item)),
+            Value::Object(map) => {
+                for (key, item) in map.iter_mut() {
+                    if item.is_string() && SENSITIVE_KEY_REGEX.is_match(key) {
+                        *item = Value::String(REDACTED.into());
+                    } else {
+                        self.redact_value(item);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Observer that appends content events to `<dir>/<trace-id>.jsonl`.
+///
+/// Only events recorded inside a [`TraceContext`] scope are kept; writes are
+/// synchronous appends, which is acceptable for an opt-in debugging aid.
+pub struct FlightRecorderObserver {
+    dir: PathBuf,
+    max_turns: usize,
+    max_total_bytes: u64,
+    redactor: Redactor,
+    write_lock: Mutex<()>,
+}
+
+impl FlightRecorderObserver {
+    pub fn new(config: &FlightRecorderConfig) -> Self {
+        Self {
+            dir: record_dir(config),
+            max_turns: config.max_turns,
+            max_total_bytes: config.max_total_mb.saturating_mul(1024 * 1024),
+            redactor: Redactor::new(&config.redact_patterns),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    fn append(&self, ctx: &TraceContext, kind: &str, fields: Value) -> Result<()> {
+        let mut line = Map::new();
+        line.insert("ts".into(), json!(chrono::Utc::now().to_rfc3339()));
+        line.insert("event".into(), json!(kind));
+        line.insert("span_id".into(), json!(format!("{:016x}", ctx.span_id)));
+        line.insert(
+            "parent_span_id".into(),
+            json!(ctx.parent_span_id.map(|id| format!("{id:016x}"))),
+        );
+        if let Value::Object(fields) = fields {
+            line.extend(fields);
+        }
+        let mut line = Value::Object(line);
+        self.redactor.redact_value(&mut line);
+
+        let _guard = self.write_lock.lock();
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let path = record_path(&self.dir, &format!("{:032x}", ctx.trace_id));
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        writeln!(file, "{line}")?;
+        Ok(())
+    }
+
+    /// Drop the oldest records until both caps hold. The record of the turn
+    /// that just ended (`keep`) always survives.
+    fn prune(&self, keep: &str) -> Result<()> {
+        let _guard = self.write_lock.lock();
+        let mut records = list_records(&self.dir)?;
+        let mut total: u64 = records.iter().map(|r| r.size).sum();
+        let mut count = records.len();
+        // `list_records` is newest first.
+        while count > self.max_turns.max(1) || total > self.max_total_bytes {
+            let Some(oldest) = records.pop() else { break };
+            if oldest.id == keep {
+                continue;
+            }
+            fs::remove_file(&oldest.path)?;
+            total = total.saturating_sub(oldest.size);
+            count -= 1;
+        }
+        Ok(())
+    }
+}
+
+impl Observer for FlightRecorderObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        let Some(ctx) = TraceContext::current() else {
+            return;
+        };
+        let (kind, fields) = match event {
+            ObserverEvent::PromptAssembled {
+                source,
+                system_prompt,
+                memory_context,
+                user_message,
+            } => (
+                "prompt",
+                json!({
+                    "source": source,
+                    "system_prompt": system_prompt,
+                    "memory_context": memory_context,
+                    "user_message": user_message,
+                }),
+            ),
+            ObserverEvent::LlmExchange {
+                provider,
+                model,
+                temperature,
+                messages,
+                response,
+                tool_calls,
+                error_message,
+                duration,
+            } => (
+                "llm",
+                json!({
+                    "provider": provider,
+                    "model": model,
+                    "temperature": temperature,
+                    "messages": messages,
+                    "response": response,
+                    "tool_calls": tool_calls,
+                    "error": error_message,
+                    "duration_ms": millis(*duration),
+                }),
+            ),
+            ObserverEvent::LlmResponse {
+                input_tokens,
+                output_tokens,
+                cost_usd,
+                ..
+            } => (
+                "usage",
+                json!({
+                    "input_tokens": input_tokens,
+                    "output_tokens": output_tokens,
+                    "cost_usd": cost_usd,
+                }),
+            ),
+            ObserverEvent::ToolExchange {
+                tool,
+                arguments,
+                output,
+                success,
+                duration,
+            } => (
+                "tool",
+                json!({
+                    "tool": tool,
+                    "arguments": arguments,
+                    "output": output,
+                    "success": success,
+                    "duration_ms": millis(*duration),
+                }),
+            ),
+            ObserverEvent::Error { component, message } => (
+                "error",
+                json!({ "component": component, "message": message }),
+            ),
+            ObserverEvent::TurnEnd {
+                source,
+                duration,
+                success,
+            } => (
+                "turn_end",
+                json!({
+                    "source": source,
+                    "success": success,
+                    "duration_ms": millis(*duration),
+                }),
+            ),
+            _ => return,
+        };
+
+        if let Err(e) = self.append(&ctx, kind, fields) {
+            tracing::warn!("Flight recorder write failed: {e}");
+        }
+        if matches!(event, ObserverEvent::TurnEnd { .. }) {
+            if let Err(e) = self.prune(&format!("{:032x}", ctx.trace_id)) {
+                tracing::warn!("Flight recorder pruning failed: {e}");
+            }
+        }
+    }
+
+    fn record_metric(&self, _metric: &ObserverMetric) {}
+
+    fn wants_content(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> &str {
+        "flight-recorder"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+// ── Reading records back ─────────────────────────────────────────
+
+/// Directory records are written to, with `~` expanded.
+pub fn record_dir(config: &FlightRecorderConfig) -> PathBuf {
+    PathBuf::from(shellexpand::tilde(&config.dir).into_owned())
+}
+
+fn record_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{id}.{RECORD_EXTENSION}"))
+}
+
+/// One record file on disk.
+#[derive(Debug, Clone)]
+pub struct RecordFile {
+    /// Trace ID (file stem).
+    pub id: String,
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: SystemTime,
+}
+
+/// List record files, newest first. A missing directory is an empty list.
+pub fn list_records(dir: &Path) -> Result<Vec<RecordFile>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", dir.display())),
+    };
+
+    let mut records = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(RECORD_EXTENSION) {
+            continue;
+        }
+        let Some(id) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let meta = fs::metadata(&path)?;
+        records.push(RecordFile {
+            id: id.to_string(),
+            size: meta.len(),
+            modified: meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            path,
+        });
+    }
+    records.sort_by(|a, b| b.modified.cmp(&a.modified).then_with(|| b.id.cmp(&a.id)));
+    Ok(records)
+}
+
+/// Resolve a trace ID or unique prefix of one to its record file.
+pub fn find_record(dir: &Path, id: &str) -> Result<RecordFile> {
+    let id = id.trim().to_ascii_lowercase();
+    if id.is_empty() {
+        bail!("Trace ID must not be empty");
+    }
+    let mut matches: Vec<RecordFile> = list_records(dir)?
+        .into_iter()
+        .filter(|r| r.id.starts_with(&id))
+        .collect();
+    match matches.len() {
+        0 => bail!("No trace record matching '{id}' in {}", dir.display()),
+        1 => Ok(matches.remove(0)),
+        n => bail!("Trace ID prefix '{id}' is ambiguous ({n} matches)"),
+    }
+}
+
+/// Parse every line of a record. Malformed lines (e.g. a torn final write)
+/// are skipped.
+pub fn read_events(path: &Path) -> Result<Vec<Value>> {
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(raw
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::ChatMessage;
+    use tempfile::TempDir;
+
+    fn recorder(tmp: &TempDir, max_turns: usize) -> FlightRecorderObserver {
+        FlightRecorderObserver::new(&FlightRecorderConfig {
+            enabled: true,
+            dir: tmp.path().to_string_lossy().into_owned(),
+            max_turns,
+            max_total_mb: 10,
+            redact_patterns: vec![r"\b\d{4}-\d{4}-\d{4}-\d{4}\b".into()],
+        })
+    }
+
+    fn record_turn(obs: &FlightRecorderObserver) -> TraceContext {
+        let turn = TraceContext::root();
+        turn.sync_scope(|| {
+            obs.record_event(&ObserverEvent::PromptAssembled {
+                source: "telegram".into(),
+                system_prompt: "You are helpful".into(),
+                memory_context: "[Memory context]\n- likes tea\n".into(),
+                user_message: "my card is 1234-5678-9012-3456".into(),
+            });
+        });
+        turn.child().sync_scope(|| {
+            obs.record_event(&ObserverEvent::LlmExchange {
+                provider: "openrouter".into(),
+                model: "test-model".into(),
+                temperature: 0.7,
+                messages: vec![ChatMessage::user("hi")],
+                response: Some("hello".into()),
+                tool_calls: Vec::new(),
+                error_message: None,
+                duration: Duration::from_millis(12),
+            });
+        });
+        turn.child().sync_scope(|| {
+            obs.record_event(&ObserverEvent::ToolExchange {
+                tool: "http_request".into(),
+                arguments: json!({"url": "https://x", "api_key": "abc"}),
+                output: "Authorization: Bearer abcdefghijklmnop".into(),
+                success: true,
+                duration: Duration::from_millis(3),
+            });
+        });
+        turn.sync_scope(|| {
+            obs.record_event(&ObserverEvent::TurnEnd {
+                source: "telegram".into(),
+                duration: Duration::from_millis(20),
+                success: true,
+            });
+        });
+        turn
+    }
+
+    #[test]
+    fn writes_one_redacted_record_per_trace() {
+        let tmp = TempDir::new().unwrap();
+        let obs = recorder(&tmp, 10);
+        let turn = record_turn(&obs);
+
+        let record = find_record(tmp.path(), &format!("{:032x}", turn.trace_id)).unwrap();
+        let events = read_events(&record.path).unwrap();
+        let kinds: Vec<_> = events
+            .iter()
+            .map(|e| e["event"].as_str().unwrap())
+            .collect();
+        assert_eq!(kinds, ["prompt", "llm", "tool", "turn_end"]);
+        assert_eq!(events[0]["user_message"], "my card is [REDACTED]");
+        assert_eq!(events[1]["messages"][0]["content"], "hi");
+        assert_eq!(events[2]["arguments"]["api_key"], "[REDACTED]");
+        assert_eq!(events[2]["output"], "Authorization: Bearer [REDACTED]");
+        assert!(events[3]["parent_span_id"].is_null());
+    }
+
+    #[test]
+    fn ignores_events_outside_a_trace() {
+        let tmp = TempDir::new().unwrap();
+        let obs = recorder(&tmp, 10);
+        obs.record_event(&ObserverEvent::Error {
+            component: "test".into(),
+            message: "boom".into(),
+        });
+        assert!(list_records(tmp.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn prunes_oldest_records_past_max_turns() {
+        let tmp = TempDir::new().unwrap();
+        let obs = recorder(&tmp, 2);
+        let mut last = None;
+        for _ in 0..4 {
+            last = Some(record_turn(&obs));
+        }
+        let records = list_records(tmp.path()).unwrap();
+        assert_eq!(records.len(), 2);
+        let last = format!("{:032x}", last.unwrap().trace_id);
+        assert!(records.iter().any(|r| r.id == last));
+    }
+
+    #[test]
+    fn redacts_builtin_credential_patterns() {
+        let redactor = Redactor::new(&["[".into()]);
+        assert_eq!(
+            redactor.redact_str("password=hunter2hunter2"),
+            "password=[REDACTED]"
+        );
+        assert_eq!(
+            redactor.redact_str("key sk-abcdefghijklmnopqrstuv"),
+            "key [REDACTED]"
+        );
+        assert_eq!(redactor.redact_str("plain text"), "plain text");
+    }
+}
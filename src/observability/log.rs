@@ -3,11 +3,21 @@ use std::any::Any;
 use tracing::info;
 
 /// Log-based observer — uses tracing, zero external deps
-pub struct LogObserver;
+pub struct LogObserver {
+    log_reasoning: bool,
+}
 
 impl LogObserver {
     pub fn new() -> Self {
-        Self
+        Self {
+            log_reasoning: false,
+        }
+    }
+
+    /// Include reasoning text in the log instead of just its size.
+    pub fn with_reasoning(mut self, log_reasoning: bool) -> Self {
+        self.log_reasoning = log_reasoning;
+        self
     }
 }
 
@@ -41,6 +51,29 @@ impl Observer for LogObserver {
             ObserverEvent::TurnComplete => {
                 info!("turn.complete");
             }
+            ObserverEvent::TurnEnd {
+                source,
+                duration,
+                success,
+            } => {
+                let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+                info!(source = %source, duration_ms = ms, success = success, "turn.end");
+            }
+            ObserverEvent::Reasoning {
+                provider,
+                model,
+                text,
+            } => {
+                if self.log_reasoning {
+                    info!(provider = %provider, model = %model, reasoning = %text, "llm.reasoning");
+                } else {
+                    info!(provider = %provider, model = %model, chars = text.chars().count(), "llm.reasoning");
+                }
+            }
+            // Content events are for the flight recorder, not the log.
+            ObserverEvent::PromptAssembled { .. }
+            | ObserverEvent::LlmExchange { .. }
+            | ObserverEvent::ToolExchange { .. } => {}
             ObserverEvent::ChannelMessage { channel, direction } => {
                 info!(channel = %channel, direction = %direction, "channel.message");
             }
@@ -68,6 +101,9 @@ impl Observer for LogObserver {
                 duration,
                 success,
                 error_message,
+                input_tokens,
+                output_tokens,
+                cost_usd,
             } => {
                 let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
                 info!(
@@ -76,6 +112,9 @@ impl Observer for LogObserver {
                     duration_ms = ms,
                     success = success,
                     error = ?error_message,
+                    input_tokens = ?input_tokens,
+                    output_tokens = ?output_tokens,
+                    cost_usd = ?cost_usd,
                     "llm.response"
                 );
             }
@@ -145,6 +184,11 @@ mod tests {
             duration: Duration::from_millis(10),
             success: false,
         });
+        obs.record_event(&ObserverEvent::TurnEnd {
+            source: "telegram".into(),
+            duration: Duration::from_millis(20),
+            success: true,
+        });
         obs.record_event(&ObserverEvent::ChannelMessage {
             channel: "telegram".into(),
             direction: "outbound".into(),
@@ -1,9 +1,12 @@
+pub mod flight_recorder;
 pub mod log;
 pub mod multi;
 pub mod noop;
 #[cfg(feature = "observability-otel")]
 pub mod otel;
 pub mod prometheus;
+pub mod trace_cli;
+pub mod trace_context;
 pub mod traits;
 pub mod verbose;
 
@@ -11,20 +14,36 @@ pub mod verbose;
 pub use self::log::LogObserver;
 #[allow(unused_imports)]
 pub use self::multi::MultiObserver;
+pub use flight_recorder::FlightRecorderObserver;
 pub use noop::NoopObserver;
 #[cfg(feature = "observability-otel")]
 pub use otel::OtelObserver;
 pub use prometheus::PrometheusObserver;
+pub use trace_context::TraceContext;
 pub use traits::{Observer, ObserverEvent};
 #[allow(unused_imports)]
-pub use verbose::VerboseObserver;
+pub use verbose::{ReasoningEchoObserver, VerboseObserver};
 
 use crate::config::ObservabilityConfig;
 
 /// Factory: create the right observer from config
+///
+/// When the flight recorder is enabled it runs alongside the selected backend.
 pub fn create_observer(config: &ObservabilityConfig) -> Box<dyn Observer> {
+    let backend = create_backend_observer(config);
+    if config.flight_recorder.enabled {
+        Box::new(MultiObserver::new(vec![
+            backend,
+            Box::new(FlightRecorderObserver::new(&config.flight_recorder)),
+        ]))
+    } else {
+        backend
+    }
+}
+
+fn create_backend_observer(config: &ObservabilityConfig) -> Box<dyn Observer> {
     match config.backend.as_str() {
-        "log" => Box::new(LogObserver::new()),
+        "log" => Box::new(LogObserver::new().with_reasoning(config.log_reasoning)),
         "prometheus" => Box::new(PrometheusObserver::new()),
         "otel" | "opentelemetry" | "otlp" => {
             #[cfg(feature = "observability-otel")]
@@ -112,6 +131,8 @@ mod tests {
             backend: "otel".into(),
             otel_endpoint: Some("http://127.0.0.1:19999".into()),
             otel_service_name: Some("test".into()),
+            log_reasoning: false,
+            ..ObservabilityConfig::default()
         };
         let expected = if cfg!(feature = "observability-otel") {
             "otel"
@@ -127,6 +148,8 @@ mod tests {
             backend: "opentelemetry".into(),
             otel_endpoint: Some("http://127.0.0.1:19999".into()),
             otel_service_name: Some("test".into()),
+            log_reasoning: false,
+            ..ObservabilityConfig::default()
         };
         let expected = if cfg!(feature = "observability-otel") {
             "otel"
@@ -142,6 +165,8 @@ mod tests {
             backend: "otlp".into(),
             otel_endpoint: Some("http://127.0.0.1:19999".into()),
             otel_service_name: Some("test".into()),
+            log_reasoning: false,
+            ..ObservabilityConfig::default()
         };
         let expected = if cfg!(feature = "observability-otel") {
             "otel"
@@ -169,6 +194,22 @@ mod tests {
         assert_eq!(create_observer(&cfg).name(), "noop");
     }
 
+    #[test]
+    fn factory_adds_flight_recorder_when_enabled() {
+        let cfg = ObservabilityConfig {
+            backend: "log".into(),
+            flight_recorder: crate::config::FlightRecorderConfig {
+                enabled: true,
+                ..crate::config::FlightRecorderConfig::default()
+            },
+            ..ObservabilityConfig::default()
+        };
+        let observer = create_observer(&cfg);
+        assert_eq!(observer.name(), "multi");
+        assert!(observer.wants_content());
+        assert!(!create_observer(&ObservabilityConfig::default()).wants_content());
+    }
+
     #[test]
     fn factory_garbage_falls_back_to_noop() {
         let cfg = ObservabilityConfig {
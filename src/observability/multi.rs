@@ -31,6 +31,10 @@ impl Observer for MultiObserver {
         }
     }
 
+    fn wants_content(&self) -> bool {
+        self.observers.iter().any(|obs| obs.wants_content())
+    }
+
     fn name(&self) -> &str {
         "multi"
     }
@@ -1,12 +1,16 @@
+use super::trace_context::TraceContext;
 use super::traits::{Observer, ObserverEvent, ObserverMetric};
 use opentelemetry::metrics::{Counter, Gauge, Histogram};
-use opentelemetry::trace::{Span, SpanKind, Status, Tracer};
-use opentelemetry::{global, KeyValue};
+use opentelemetry::trace::{
+    Span, SpanBuilder, SpanContext, SpanId, SpanKind, Status, TraceContextExt, TraceFlags, TraceId,
+    TraceState, Tracer, TracerProvider as _,
+};
+use opentelemetry::{global, Context, KeyValue};
 use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::metrics::SdkMeterProvider;
 use opentelemetry_sdk::trace::SdkTracerProvider;
 use std::any::Any;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 /// OpenTelemetry-backed observer — exports traces and metrics via OTLP.
 pub struct OtelObserver {
@@ -170,9 +174,73 @@ impl OtelObserver {
     }
 }
 
+/// Parent context for a span owned by `ctx`, plus the IDs the span itself
+/// should use.
+fn span_ids(ctx: TraceContext) -> (TraceId, SpanId, Context) {
+    let trace_id = TraceId::from_bytes(ctx.trace_id.to_be_bytes());
+    let span_id = SpanId::from_bytes(ctx.span_id.to_be_bytes());
+    let parent_cx = match ctx.parent_span_id {
+        Some(parent) => {
+            let flags = if ctx.sampled {
+                TraceFlags::SAMPLED
+            } else {
+                TraceFlags::default()
+            };
+            Context::new().with_remote_span_context(SpanContext::new(
+                trace_id,
+                SpanId::from_bytes(parent.to_be_bytes()),
+                flags,
+                true,
+                TraceState::default(),
+            ))
+        }
+        None => Context::new(),
+    };
+    (trace_id, span_id, parent_cx)
+}
+
+impl OtelObserver {
+    /// Export a finished span that started `duration` ago.
+    ///
+    /// `ctx` is the span's own trace context; its parent link ties the span
+    /// into the turn hierarchy. Without a context the span is a lone root.
+    fn export_span(
+        &self,
+        name: &'static str,
+        ctx: Option<TraceContext>,
+        duration: Duration,
+        attributes: Vec<KeyValue>,
+        status: Status,
+    ) {
+        // Use this observer's own provider so `flush` covers every span it
+        // exported, even when another observer replaced the global one.
+        let tracer = self.tracer_provider.tracer("zeroclaw");
+        let end_time = SystemTime::now();
+        let start_time = end_time.checked_sub(duration).unwrap_or(end_time);
+        let mut builder = SpanBuilder::from_name(name)
+            .with_kind(SpanKind::Internal)
+            .with_start_time(start_time)
+            .with_attributes(attributes);
+
+        let mut span = match ctx {
+            Some(ctx) => {
+                let (trace_id, span_id, parent_cx) = span_ids(ctx);
+                builder = builder.with_trace_id(trace_id).with_span_id(span_id);
+                tracer.build_with_context(builder, &parent_cx)
+            }
+            None => tracer.build(builder),
+        };
+        span.set_status(status);
+        span.end_with_timestamp(end_time);
+    }
+}
+
 impl Observer for OtelObserver {
     fn record_event(&self, event: &ObserverEvent) {
-        let tracer = global::tracer("zeroclaw");
+        // Span-closing events are recorded inside the scope of the span they
+        // close; point-in-time spans hang off whatever span is current.
+        let owned = TraceContext::current();
+        let nested = owned.map(|ctx| ctx.child());
 
         match event {
             ObserverEvent::AgentStart { provider, model } => {
@@ -186,13 +254,42 @@ impl Observer for OtelObserver {
             }
             ObserverEvent::LlmRequest { .. }
             | ObserverEvent::ToolCallStart { .. }
+            | ObserverEvent::Reasoning { .. }
+            | ObserverEvent::PromptAssembled { .. }
+            | ObserverEvent::LlmExchange { .. }
+            | ObserverEvent::ToolExchange { .. }
             | ObserverEvent::TurnComplete => {}
+            ObserverEvent::TurnEnd {
+                source,
+                duration,
+                success,
+            } => {
+                let status = if *success {
+                    Status::Ok
+                } else {
+                    Status::error("")
+                };
+                self.export_span(
+                    "agent.turn",
+                    owned,
+                    *duration,
+                    vec![
+                        KeyValue::new("turn.source", source.clone()),
+                        KeyValue::new("turn.success", *success),
+                        KeyValue::new("duration_s", duration.as_secs_f64()),
+                    ],
+                    status,
+                );
+            }
             ObserverEvent::LlmResponse {
                 provider,
                 model,
                 duration,
                 success,
                 error_message: _,
+                input_tokens,
+                output_tokens,
+                cost_usd,
             } => {
                 let secs = duration.as_secs_f64();
                 let attrs = [
@@ -203,27 +300,27 @@ impl Observer for OtelObserver {
                 self.llm_calls.add(1, &attrs);
                 self.llm_duration.record(secs, &attrs);
 
-                // Create a completed span for visibility in trace backends.
-                let start_time = SystemTime::now()
-                    .checked_sub(*duration)
-                    .unwrap_or(SystemTime::now());
-                let mut span = tracer.build(
-                    opentelemetry::trace::SpanBuilder::from_name("llm.call")
-                        .with_kind(SpanKind::Internal)
-                        .with_start_time(start_time)
-                        .with_attributes(vec![
-                            KeyValue::new("provider", provider.clone()),
-                            KeyValue::new("model", model.clone()),
-                            KeyValue::new("success", *success),
-                            KeyValue::new("duration_s", secs),
-                        ]),
-                );
-                if *success {
-                    span.set_status(Status::Ok);
-                } else {
-                    span.set_status(Status::error(""));
+                let mut span_attrs = vec![
+                    KeyValue::new("provider", provider.clone()),
+                    KeyValue::new("model", model.clone()),
+                    KeyValue::new("success", *success),
+                    KeyValue::new("duration_s", secs),
+                ];
+                if let Some(t) = input_tokens {
+                    span_attrs.push(KeyValue::new("input_tokens", *t as i64));
+                }
+                if let Some(t) = output_tokens {
+                    span_attrs.push(KeyValue::new("output_tokens", *t as i64));
                 }
-                span.end();
+                if let Some(c) = cost_usd {
+                    span_attrs.push(KeyValue::new("cost_usd", *c));
+                }
+                let status = if *success {
+                    Status::Ok
+                } else {
+                    Status::error("")
+                };
+                self.export_span("llm.call", owned, *duration, span_attrs, status);
             }
             ObserverEvent::AgentEnd {
                 provider,
@@ -233,28 +330,24 @@ impl Observer for OtelObserver {
                 cost_usd,
             } => {
                 let secs = duration.as_secs_f64();
-                let start_time = SystemTime::now()
-                    .checked_sub(*duration)
-                    .unwrap_or(SystemTime::now());
-
-                // Create a completed span with correct timing
-                let mut span = tracer.build(
-                    opentelemetry::trace::SpanBuilder::from_name("agent.invocation")
-                        .with_kind(SpanKind::Internal)
-                        .with_start_time(start_time)
-                        .with_attributes(vec![
-                            KeyValue::new("provider", provider.clone()),
-                            KeyValue::new("model", model.clone()),
-                            KeyValue::new("duration_s", secs),
-                        ]),
-                );
+                let mut span_attrs = vec![
+                    KeyValue::new("provider", provider.clone()),
+                    KeyValue::new("model", model.clone()),
+                    KeyValue::new("duration_s", secs),
+                ];
                 if let Some(t) = tokens_used {
-                    span.set_attribute(KeyValue::new("tokens_used", *t as i64));
+                    span_attrs.push(KeyValue::new("tokens_used", *t as i64));
                 }
                 if let Some(c) = cost_usd {
-                    span.set_attribute(KeyValue::new("cost_usd", *c));
+                    span_attrs.push(KeyValue::new("cost_usd", *c));
                 }
-                span.end();
+                self.export_span(
+                    "agent.invocation",
+                    nested,
+                    *duration,
+                    span_attrs,
+                    Status::Unset,
+                );
 
                 self.agent_duration.record(
                     secs,
@@ -272,28 +365,22 @@ impl Observer for OtelObserver {
                 success,
             } => {
                 let secs = duration.as_secs_f64();
-                let start_time = SystemTime::now()
-                    .checked_sub(*duration)
-                    .unwrap_or(SystemTime::now());
-
                 let status = if *success {
                     Status::Ok
                 } else {
                     Status::error("")
                 };
-
-                let mut span = tracer.build(
-                    opentelemetry::trace::SpanBuilder::from_name("tool.call")
-                        .with_kind(SpanKind::Internal)
-                        .with_start_time(start_time)
-                        .with_attributes(vec![
-                            KeyValue::new("tool.name", tool.clone()),
-                            KeyValue::new("tool.success", *success),
-                            KeyValue::new("duration_s", secs),
-                        ]),
+                self.export_span(
+                    "tool.call",
+                    owned,
+                    *duration,
+                    vec![
+                        KeyValue::new("tool.name", tool.clone()),
+                        KeyValue::new("tool.success", *success),
+                        KeyValue::new("duration_s", secs),
+                    ],
+                    status,
                 );
-                span.set_status(status);
-                span.end();
 
                 let attrs = [
                     KeyValue::new("tool", tool.clone()),
@@ -317,16 +404,16 @@ impl Observer for OtelObserver {
             }
             ObserverEvent::Error { component, message } => {
                 // Create an error span for visibility in trace backends
-                let mut span = tracer.build(
-                    opentelemetry::trace::SpanBuilder::from_name("error")
-                        .with_kind(SpanKind::Internal)
-                        .with_attributes(vec![
-                            KeyValue::new("component", component.clone()),
-                            KeyValue::new("error.message", message.clone()),
-                        ]),
+                self.export_span(
+                    "error",
+                    nested,
+                    Duration::ZERO,
+                    vec![
+                        KeyValue::new("component", component.clone()),
+                        KeyValue::new("error.message", message.clone()),
+                    ],
+                    Status::error(message.clone()),
                 );
-                span.set_status(Status::error(message.clone()));
-                span.end();
 
                 self.errors
                     .add(1, &[KeyValue::new("component", component.clone())]);
@@ -411,6 +498,9 @@ mod tests {
             duration: Duration::from_millis(250),
             success: true,
             error_message: None,
+            input_tokens: None,
+            output_tokens: None,
+            cost_usd: None,
         });
         obs.record_event(&ObserverEvent::AgentEnd {
             provider: "openrouter".into(),
@@ -451,6 +541,54 @@ mod tests {
         });
     }
 
+    #[test]
+    fn span_ids_link_child_to_parent() {
+        let root = TraceContext::root();
+        let child = root.child();
+        let (trace_id, span_id, parent_cx) = span_ids(child);
+        assert_eq!(trace_id, TraceId::from_bytes(root.trace_id.to_be_bytes()));
+        assert_eq!(span_id, SpanId::from_bytes(child.span_id.to_be_bytes()));
+        assert_eq!(
+            parent_cx.span().span_context().span_id(),
+            SpanId::from_bytes(root.span_id.to_be_bytes())
+        );
+
+        let (_, _, root_cx) = span_ids(root);
+        assert!(!root_cx.has_active_span());
+    }
+
+    #[test]
+    fn records_nested_turn_without_panic() {
+        let obs = test_observer();
+        let turn = TraceContext::root();
+        turn.sync_scope(|| {
+            turn.child().sync_scope(|| {
+                obs.record_event(&ObserverEvent::LlmResponse {
+                    provider: "openrouter".into(),
+                    model: "claude-sonnet".into(),
+                    duration: Duration::from_millis(30),
+                    success: true,
+                    error_message: None,
+                    input_tokens: Some(120),
+                    output_tokens: Some(40),
+                    cost_usd: Some(0.001),
+                });
+            });
+            turn.child().sync_scope(|| {
+                obs.record_event(&ObserverEvent::ToolCall {
+                    tool: "shell".into(),
+                    duration: Duration::from_millis(5),
+                    success: true,
+                });
+            });
+            obs.record_event(&ObserverEvent::TurnEnd {
+                source: "telegram".into(),
+                duration: Duration::from_millis(50),
+                success: true,
+            });
+        });
+    }
+
     #[test]
     fn records_all_metrics_without_panic() {
         let obs = test_observer();
@@ -489,6 +627,9 @@ mod tests {
             duration: Duration::from_millis(0),
             success: false,
             error_message: Some("404 Not Found".into()),
+            input_tokens: None,
+            output_tokens: None,
+            cost_usd: None,
         });
     }
 
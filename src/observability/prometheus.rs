@@ -168,6 +168,11 @@ impl Observer for PrometheusObserver {
             }
             ObserverEvent::ToolCallStart { tool: _ }
             | ObserverEvent::TurnComplete
+            | ObserverEvent::TurnEnd { .. }
+            | ObserverEvent::Reasoning { .. }
+            | ObserverEvent::PromptAssembled { .. }
+            | ObserverEvent::LlmExchange { .. }
+            | ObserverEvent::ToolExchange { .. }
             | ObserverEvent::LlmRequest { .. }
             | ObserverEvent::LlmResponse { .. } => {}
             ObserverEvent::ToolCall {
@@ -0,0 +1,307 @@
+use super::flight_recorder::{find_record, list_records, read_events, record_dir};
+use crate::config::Config;
+use crate::providers::{self, ChatMessage};
+use crate::util::truncate_with_ellipsis;
+use anyhow::{bail, Context, Result};
+use console::style;
+use serde_json::Value;
+
+/// Handle `zeroclaw trace <subcommand>` CLI commands.
+pub async fn handle_command(command: crate::TraceCommands, config: &Config) -> Result<()> {
+    match command {
+        crate::TraceCommands::List { limit } => handle_list(config, limit),
+        crate::TraceCommands::Show { id, raw } => handle_show(config, &id, raw),
+        crate::TraceCommands::Replay {
+            id,
+            model,
+            provider,
+            temperature,
+        } => handle_replay(config, &id, &model, provider.as_deref(), temperature).await,
+    }
+}
+
+/// One-line overview of a record, built from its events.
+#[derive(Debug, Default, PartialEq)]
+struct TurnSummary {
+    started_at: String,
+    source: String,
+    model: String,
+    llm_calls: usize,
+    tool_calls: usize,
+    duration_ms: Option<u64>,
+    success: Option<bool>,
+}
+
+fn summarize(events: &[Value]) -> TurnSummary {
+    let mut summary = TurnSummary::default();
+    for event in events {
+        if summary.started_at.is_empty() {
+            summary.started_at = str_field(event, "ts").to_string();
+        }
+        match str_field(event, "event") {
+            "prompt" => summary.source = str_field(event, "source").to_string(),
+            "llm" => {
+                summary.llm_calls += 1;
+                if summary.model.is_empty() {
+                    summary.model = str_field(event, "model").to_string();
+                }
+            }
+            "tool" => summary.tool_calls += 1,
+            "turn_end" => {
+                if summary.source.is_empty() {
+                    summary.source = str_field(event, "source").to_string();
+                }
+                summary.duration_ms = event["duration_ms"].as_u64();
+                summary.success = event["success"].as_bool();
+            }
+            _ => {}
+        }
+    }
+    summary
+}
+
+fn str_field<'a>(event: &'a Value, key: &str) -> &'a str {
+    event[key].as_str().unwrap_or_default()
+}
+
+fn handle_list(config: &Config, limit: usize) -> Result<()> {
+    let dir = record_dir(&config.observability.flight_recorder);
+    let records = list_records(&dir)?;
+    if records.is_empty() {
+        println!("No trace records in {}.", dir.display());
+        if !config.observability.flight_recorder.enabled {
+            println!("Enable recording with [observability.flight_recorder] enabled = true.");
+        }
+        return Ok(());
+    }
+
+    println!(
+        "Trace records ({} total, showing {}):\n",
+        records.len(),
+        records.len().min(limit)
+    );
+    for record in records.iter().take(limit) {
+        let summary = summarize(&read_events(&record.path)?);
+        let outcome = match summary.success {
+            Some(true) => style("ok").green(),
+            Some(false) => style("failed").red(),
+            None => style("open").yellow(),
+        };
+        println!(
+            "- {} [{}] {}",
+            style(&record.id).white().bold(),
+            outcome,
+            summary.started_at
+        );
+        println!(
+            "    source={} model={} llm_calls={} tool_calls={} duration_ms={}",
+            or_dash(&summary.source),
+            or_dash(&summary.model),
+            summary.llm_calls,
+            summary.tool_calls,
+            summary
+                .duration_ms
+                .map_or_else(|| "-".to_string(), |ms| ms.to_string())
+        );
+    }
+    Ok(())
+}
+
+fn or_dash(s: &str) -> &str {
+    if s.is_empty() {
+        "-"
+    } else {
+        s
+    }
+}
+
+fn handle_show(config: &Config, id: &str, raw: bool) -> Result<()> {
+    let dir = record_dir(&config.observability.flight_recorder);
+    let record = find_record(&dir, id)?;
+    if raw {
+        print!("{}", std::fs::read_to_string(&record.path)?);
+        return Ok(());
+    }
+
+    println!("Trace {}\n", style(&record.id).white().bold());
+    for event in read_events(&record.path)? {
+        let ts = str_field(&event, "ts");
+        match str_field(&event, "event") {
+            "prompt" => {
+                println!("{ts} prompt  source={}", str_field(&event, "source"));
+                print_block("system", str_field(&event, "system_prompt"));
+                print_block("memory", str_field(&event, "memory_context"));
+                print_block("user", str_field(&event, "user_message"));
+            }
+            "llm" => {
+                println!(
+                    "{ts} llm     {}/{} messages={} duration_ms={}",
+                    str_field(&event, "provider"),
+                    str_field(&event, "model"),
+                    event["messages"].as_array().map_or(0, Vec::len),
+                    event["duration_ms"]
+                );
+                if let Some(error) = event["error"].as_str() {
+                    print_block("error", error);
+                } else {
+                    print_block("response", str_field(&event, "response"));
+                }
+                for call in event["tool_calls"].as_array().into_iter().flatten() {
+                    print_block(
+                        "tool_call",
+                        &format!(
+                            "{} {}",
+                            str_field(call, "name"),
+                            str_field(call, "arguments")
+                        ),
+                    );
+                }
+            }
+            "usage" => println!(
+                "{ts} usage   input_tokens={} output_tokens={} cost_usd={}",
+                event["input_tokens"], event["output_tokens"], event["cost_usd"]
+            ),
+            "tool" => {
+                println!(
+                    "{ts} tool    {} success={} duration_ms={}",
+                    str_field(&event, "tool"),
+                    event["success"],
+                    event["duration_ms"]
+                );
+                print_block("args", &event["arguments"].to_string());
+                print_block("output", str_field(&event, "output"));
+            }
+            "error" => println!(
+                "{ts} error   {}: {}",
+                str_field(&event, "component"),
+                str_field(&event, "message")
+            ),
+            "turn_end" => println!(
+                "{ts} end     source={} success={} duration_ms={}",
+                str_field(&event, "source"),
+                event["success"],
+                event["duration_ms"]
+            ),
+            other => println!("{ts} {other}"),
+        }
+    }
+    Ok(())
+}
+
+fn print_block(label: &str, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    println!("    {label}: {}", truncate_with_ellipsis(text, 400));
+}
+
+async fn handle_replay(
+    config: &Config,
+    id: &str,
+    model: &str,
+    provider_override: Option<&str>,
+    temperature_override: Option<f64>,
+) -> Result<()> {
+    let dir = record_dir(&config.observability.flight_recorder);
+    let record = find_record(&dir, id)?;
+    let events = read_events(&record.path)?;
+    let Some(first_call) = events.iter().find(|e| str_field(e, "event") == "llm") else {
+        bail!(
+            "Trace {} has no recorded provider call to replay",
+            record.id
+        );
+    };
+    let messages: Vec<ChatMessage> = serde_json::from_value(first_call["messages"].clone())
+        .context("Recorded provider request is malformed")?;
+    let provider_name = provider_override.unwrap_or_else(|| str_field(first_call, "provider"));
+    if provider_name.is_empty() {
+        bail!(
+            "Trace {} does not name a provider; pass --provider",
+            record.id
+        );
+    }
+    let temperature = temperature_override
+        .or_else(|| first_call["temperature"].as_f64())
+        .unwrap_or(config.default_temperature);
+
+    // The configured key belongs to the default provider; any other provider
+    // resolves its own credential from the environment.
+    let api_key = if config.default_provider.as_deref() == Some(provider_name) {
+        config.api_key.as_deref()
+    } else {
+        None
+    };
+    let options = providers::ProviderRuntimeOptions {
+        auth_profile_override: None,
+        zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
+        secrets_encrypt: config.secrets.encrypt,
+        reasoning: config.runtime.effective_reasoning(),
+        cassette: config.cassette.clone(),
+    };
+    let provider = providers::create_resilient_provider_with_options(
+        provider_name,
+        api_key,
+        config.api_url.as_deref(),
+        &config.reliability,
+        &options,
+    )?;
+
+    println!(
+        "Replaying {} ({} messages) against {provider_name}/{model}...\n",
+        style(&record.id).white().bold(),
+        messages.len()
+    );
+    let replayed = provider
+        .chat_with_history(&messages, model, temperature)
+        .await?;
+
+    println!(
+        "{} ({}/{}):",
+        style("Recorded").bold(),
+        str_field(first_call, "provider"),
+        str_field(first_call, "model")
+    );
+    match first_call["error"].as_str() {
+        Some(error) => println!("[error] {error}\n"),
+        None => println!("{}\n", str_field(first_call, "response")),
+    }
+    println!("{} ({provider_name}/{model}):", style("Replay").bold());
+    println!("{replayed}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn summarize_collects_source_model_and_outcome() {
+        let events = vec![
+            json!({"ts": "t0", "event": "prompt", "source": "telegram"}),
+            json!({"ts": "t1", "event": "llm", "model": "m1"}),
+            json!({"ts": "t2", "event": "tool", "tool": "shell"}),
+            json!({"ts": "t3", "event": "llm", "model": "m2"}),
+            json!({"ts": "t4", "event": "turn_end", "source": "telegram", "success": true, "duration_ms": 42}),
+        ];
+        assert_eq!(
+            summarize(&events),
+            TurnSummary {
+                started_at: "t0".into(),
+                source: "telegram".into(),
+                model: "m1".into(),
+                llm_calls: 2,
+                tool_calls: 1,
+                duration_ms: Some(42),
+                success: Some(true),
+            }
+        );
+    }
+
+    #[test]
+    fn summarize_marks_unfinished_turns_open() {
+        let summary = summarize(&[json!({"ts": "t0", "event": "llm", "model": "m"})]);
+        assert_eq!(summary.success, None);
+        assert_eq!(summary.source, "");
+    }
+}
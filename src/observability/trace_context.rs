@@ -0,0 +1,190 @@
+//! Trace context carried through a single agent turn.
+//!
+//! A turn (an inbound channel message, a webhook call, a cron run) opens a
+//! root [`TraceContext`] and runs inside [`TraceContext::scope`]. Provider
+//! calls, tool executions and delegated sub-agents open child contexts the
+//! same way, so backends that read [`TraceContext::current`] while recording
+//! an event can attach it to the right parent span.
+//!
+//! Events that close a span (`LlmResponse`, `ToolCall`, `TurnEnd`) are
+//! recorded inside the scope of the context that span owns: the context's
+//! `span_id` identifies the span and `parent_span_id` its parent.
+
+use std::future::Future;
+
+tokio::task_local! {
+    static CURRENT: TraceContext;
+}
+
+/// W3C trace-context identifiers for one span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceContext {
+    pub trace_id: u128,
+    pub span_id: u64,
+    /// Span this one is nested under; `None` for a trace root.
+    pub parent_span_id: Option<u64>,
+    pub sampled: bool,
+}
+
+impl TraceContext {
+    /// Start a new trace.
+    pub fn root() -> Self {
+        Self {
+            trace_id: non_zero(rand::random()),
+            span_id: non_zero(rand::random()),
+            parent_span_id: None,
+            sampled: true,
+        }
+    }
+
+    /// Context for a span nested directly under this one.
+    pub fn child(&self) -> Self {
+        Self {
+            trace_id: self.trace_id,
+            span_id: non_zero(rand::random()),
+            parent_span_id: Some(self.span_id),
+            sampled: self.sampled,
+        }
+    }
+
+    /// Context of the span the current task is running in, if any.
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(|ctx| *ctx).ok()
+    }
+
+    /// Child of the current span, or a new trace when there is none.
+    pub fn child_of_current() -> Self {
+        Self::current().map_or_else(Self::root, |ctx| ctx.child())
+    }
+
+    /// Parse a W3C `traceparent` header (`00-<trace-id>-<parent-id>-<flags>`).
+    ///
+    /// The returned context stands for the remote caller's span; use
+    /// [`child`](Self::child) to open the local span under it.
+    pub fn from_traceparent(header: &str) -> Option<Self> {
+        let mut parts = header.trim().split('-');
+        let version = parts.next()?;
+        let trace_id = parts.next()?;
+        let span_id = parts.next()?;
+        let flags = parts.next()?;
+        // Version 00 has exactly four fields; later versions may append more.
+        if version.len() != 2 || version == "ff" || (version == "00" && parts.next().is_some()) {
+            return None;
+        }
+        if trace_id.len() != 32 || span_id.len() != 16 || flags.len() != 2 {
+            return None;
+        }
+        if ![version, trace_id, span_id, flags].iter().all(|field| {
+            field
+                .bytes()
+                .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+        }) {
+            return None;
+        }
+
+        let trace_id = u128::from_str_radix(trace_id, 16).ok()?;
+        let span_id = u64::from_str_radix(span_id, 16).ok()?;
+        let flags = u8::from_str_radix(flags, 16).ok()?;
+        if trace_id == 0 || span_id == 0 {
+            return None;
+        }
+        Some(Self {
+            trace_id,
+            span_id,
+            parent_span_id: None,
+            sampled: flags & 0x01 != 0,
+        })
+    }
+
+    /// Render this span as a W3C `traceparent` header value.
+    pub fn traceparent(&self) -> String {
+        format!(
+            "00-{:032x}-{:016x}-{:02x}",
+            self.trace_id,
+            self.span_id,
+            u8::from(self.sampled)
+        )
+    }
+
+    /// Run `future` with this context as the current span.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT.scope(self, future).await
+    }
+
+    /// Run `f` with this context as the current span.
+    pub fn sync_scope<R>(self, f: impl FnOnce() -> R) -> R {
+        CURRENT.sync_scope(self, f)
+    }
+}
+
+/// All-zero IDs are invalid in W3C trace context.
+fn non_zero<T: PartialEq + From<u8>>(id: T) -> T {
+    if id == T::from(0) {
+        T::from(1)
+    } else {
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn traceparent_roundtrip() {
+        let header = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let ctx = TraceContext::from_traceparent(header).unwrap();
+        assert_eq!(ctx.trace_id, 0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736);
+        assert_eq!(ctx.span_id, 0x00f0_67aa_0ba9_02b7);
+        assert!(ctx.sampled);
+        assert_eq!(ctx.traceparent(), header);
+    }
+
+    #[test]
+    fn traceparent_rejects_malformed_headers() {
+        for header in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+        ] {
+            assert!(
+                TraceContext::from_traceparent(header).is_none(),
+                "accepted {header:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn traceparent_accepts_future_versions_with_extra_fields() {
+        let header = "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00-extra";
+        let ctx = TraceContext::from_traceparent(header).unwrap();
+        assert!(!ctx.sampled);
+    }
+
+    #[test]
+    fn child_shares_trace_and_points_at_parent() {
+        let root = TraceContext::root();
+        let child = root.child();
+        assert_eq!(child.trace_id, root.trace_id);
+        assert_eq!(child.parent_span_id, Some(root.span_id));
+        assert_ne!(child.span_id, root.span_id);
+    }
+
+    #[tokio::test]
+    async fn scope_sets_current_context() {
+        assert!(TraceContext::current().is_none());
+        let root = TraceContext::root();
+        root.scope(async {
+            assert_eq!(TraceContext::current(), Some(root));
+            let child = TraceContext::child_of_current();
+            assert_eq!(child.parent_span_id, Some(root.span_id));
+            child.sync_scope(|| assert_eq!(TraceContext::current(), Some(child)));
+        })
+        .await;
+        assert!(TraceContext::current().is_none());
+    }
+}
@@ -20,12 +20,27 @@ pub enum ObserverEvent {
         messages_count: usize,
     },
     /// Result of a single LLM provider call.
+    ///
+    /// Token counts are present when the provider reports usage; `cost_usd`
+    /// when the model is also in the pricing table.
     LlmResponse {
         provider: String,
         model: String,
         duration: Duration,
         success: bool,
         error_message: Option<String>,
+        input_tokens: Option<u64>,
+        output_tokens: Option<u64>,
+        cost_usd: Option<f64>,
+    },
+    /// Reasoning ("thinking") text returned with an LLM response.
+    ///
+    /// Unlike other events this carries model output, so backends should only
+    /// persist it when the operator opted in (`observability.log_reasoning`).
+    Reasoning {
+        provider: String,
+        model: String,
+        text: String,
     },
     /// The agent session has finished.
     ///
@@ -47,6 +62,49 @@ pub enum ObserverEvent {
     },
     /// The agent produced a final answer for the current user message.
     TurnComplete,
+    /// A traced unit of work (channel message, webhook call, cron run) ended.
+    ///
+    /// Recorded inside the turn's [`TraceContext`](super::TraceContext) scope
+    /// so trace backends can emit the parent span that LLM and tool spans
+    /// nest under.
+    TurnEnd {
+        /// What started the turn (channel name, `"webhook"`, `"cron"`).
+        source: String,
+        duration: Duration,
+        success: bool,
+    },
+    /// The prompt for a turn was assembled (content event).
+    ///
+    /// Content events carry prompt and tool payloads verbatim and are only
+    /// emitted when [`Observer::wants_content`] returns `true`.
+    PromptAssembled {
+        /// What started the turn (channel name, `"cli"`).
+        source: String,
+        system_prompt: String,
+        /// Recalled memory prepended to the user message; empty when none.
+        memory_context: String,
+        user_message: String,
+    },
+    /// Full request and response of one provider call (content event).
+    LlmExchange {
+        provider: String,
+        model: String,
+        temperature: f64,
+        messages: Vec<crate::providers::ChatMessage>,
+        /// Response text; `None` when the call failed.
+        response: Option<String>,
+        tool_calls: Vec<crate::providers::ToolCall>,
+        error_message: Option<String>,
+        duration: Duration,
+    },
+    /// Arguments and result of one tool execution (content event).
+    ToolExchange {
+        tool: String,
+        arguments: serde_json::Value,
+        output: String,
+        success: bool,
+        duration: Duration,
+    },
     /// A message was sent or received through a channel.
     ChannelMessage {
         /// Channel name (e.g., `"telegram"`, `"discord"`).
@@ -111,6 +169,15 @@ pub trait Observer: Send + Sync + 'static {
     /// that write synchronously.
     fn flush(&self) {}
 
+    /// Whether this backend consumes content events (`PromptAssembled`,
+    /// `LlmExchange`, `ToolExchange`).
+    ///
+    /// Emit sites check this before cloning prompts and payloads, so the
+    /// default of `false` keeps them off the hot path.
+    fn wants_content(&self) -> bool {
+        false
+    }
+
     /// Return the human-readable name of this observer backend.
     ///
     /// Used in logs and diagnostics (e.g., `"console"`, `"prometheus"`,
@@ -63,6 +63,30 @@ impl Observer for VerboseObserver {
     }
 }
 
+/// Prints model reasoning to stderr, dimmed, for interactive sessions with
+/// `[runtime] show_reasoning` enabled. Every other event is ignored, so it is
+/// meant to sit next to the configured backend in a [`super::MultiObserver`].
+pub struct ReasoningEchoObserver;
+
+impl Observer for ReasoningEchoObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        if let ObserverEvent::Reasoning { text, .. } = event {
+            eprintln!("{}", console::style(text.trim()).dim());
+        }
+    }
+
+    #[inline(always)]
+    fn record_metric(&self, _metric: &ObserverMetric) {}
+
+    fn name(&self) -> &str {
+        "reasoning-echo"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -87,6 +111,9 @@ mod tests {
             duration: Duration::from_millis(12),
             success: true,
             error_message: None,
+            input_tokens: None,
+            output_tokens: None,
+            cost_usd: None,
         });
         obs.record_event(&ObserverEvent::ToolCallStart {
             tool: "shell".into(),
@@ -98,4 +125,16 @@ mod tests {
         });
         obs.record_event(&ObserverEvent::TurnComplete);
     }
+
+    #[test]
+    fn reasoning_echo_handles_reasoning_and_ignores_the_rest() {
+        let obs = ReasoningEchoObserver;
+        assert_eq!(obs.name(), "reasoning-echo");
+        obs.record_event(&ObserverEvent::Reasoning {
+            provider: "anthropic".into(),
+            model: "claude".into(),
+            text: "Check the disk first.".into(),
+        });
+        obs.record_event(&ObserverEvent::TurnComplete);
+    }
 }
@@ -1,10 +1,12 @@
 use crate::config::schema::{
     DingTalkConfig, IrcConfig, LarkReceiveMode, LinqConfig, QQConfig, StreamMode, WhatsAppConfig,
+    XmppConfig, ZulipConfig,
 };
 use crate::config::{
     AutonomyConfig, BrowserConfig, ChannelsConfig, ComposioConfig, Config, DiscordConfig,
     HeartbeatConfig, IMessageConfig, LarkConfig, MatrixConfig, MemoryConfig, ObservabilityConfig,
-    RuntimeConfig, SecretsConfig, SlackConfig, StorageConfig, TelegramConfig, WebhookConfig,
+    RuntimeConfig, SecretsConfig, SecurityConfig, SlackConfig, StorageConfig, TelegramConfig,
+    WebhookConfig,
 };
 use crate::hardware::{self, HardwareConfig};
 use crate::memory::{
@@ -78,6 +80,9 @@ fn has_launchable_channels(channels: &ChannelsConfig) -> bool {
         dingtalk,
         linq,
         qq,
+        websocket,
+        xmpp,
+        zulip,
         ..
     } = channels;
 
@@ -95,6 +100,9 @@ fn has_launchable_channels(channels: &ChannelsConfig) -> bool {
         || dingtalk.is_some()
         || linq.is_some()
         || qq.is_some()
+        || websocket.is_some()
+        || xmpp.is_some()
+        || zulip.is_some()
 }
 
 // ── Main wizard entry point ──────────────────────────────────────
@@ -160,11 +168,13 @@ pub async fn run_wizard(force: bool) -> Result<Config> {
         autonomy: AutonomyConfig::default(),
         runtime: RuntimeConfig::default(),
         reliability: crate::config::ReliabilityConfig::default(),
+        cassette: crate::config::CassetteConfig::default(),
         scheduler: crate::config::schema::SchedulerConfig::default(),
         agent: crate::config::schema::AgentConfig::default(),
         skills: crate::config::SkillsConfig::default(),
         model_routes: Vec::new(),
         embedding_routes: Vec::new(),
+        security: SecurityConfig::default(),
         heartbeat: HeartbeatConfig::default(),
         cron: crate::config::CronConfig::default(),
         channels_config,
@@ -185,6 +195,8 @@ pub async fn run_wizard(force: bool) -> Result<Config> {
         deploy: crate::config::DeployConfig::default(),
         agents: std::collections::HashMap::new(),
         hardware: hardware_config,
+        profiles: Vec::new(),
+        profile: None,
         query_classification: crate::config::QueryClassificationConfig::default(),
     };
 
@@ -310,6 +322,8 @@ fn memory_config_defaults_for_backend(backend: &str) -> MemoryConfig {
         embedding_provider: "none".to_string(),
         embedding_model: "text-embedding-3-small".to_string(),
         embedding_dimensions: 1536,
+        embedding_batch_size: 64,
+        embedding_max_concurrency: 4,
         vector_weight: 0.7,
         keyword_weight: 0.3,
         min_relevance_score: 0.4,
@@ -326,6 +340,8 @@ fn memory_config_defaults_for_backend(backend: &str) -> MemoryConfig {
         snapshot_on_hygiene: false,
         auto_hydrate: true,
         sqlite_open_timeout_secs: None,
+        scopes: crate::config::MemoryScopesConfig::default(),
+        consolidation: crate::config::MemoryConsolidationConfig::default(),
     }
 }
 
@@ -404,11 +420,13 @@ async fn run_quick_setup_with_home(
         autonomy: AutonomyConfig::default(),
         runtime: RuntimeConfig::default(),
         reliability: crate::config::ReliabilityConfig::default(),
+        cassette: crate::config::CassetteConfig::default(),
         scheduler: crate::config::schema::SchedulerConfig::default(),
         agent: crate::config::schema::AgentConfig::default(),
         skills: crate::config::SkillsConfig::default(),
         model_routes: Vec::new(),
         embedding_routes: Vec::new(),
+        security: SecurityConfig::default(),
         heartbeat: HeartbeatConfig::default(),
         cron: crate::config::CronConfig::default(),
         channels_config: ChannelsConfig::default(),
@@ -429,6 +447,8 @@ async fn run_quick_setup_with_home(
         deploy: crate::config::DeployConfig::default(),
         agents: std::collections::HashMap::new(),
         hardware: crate::config::HardwareConfig::default(),
+        profiles: Vec::new(),
+        profile: None,
         query_classification: crate::config::QueryClassificationConfig::default(),
     };
 
@@ -2460,7 +2480,10 @@ fn setup_tool_mode() -> Result<(ComposioConfig, SecretsConfig)> {
         .default(true)
         .interact()?;
 
-    let secrets_config = SecretsConfig { encrypt };
+    let secrets_config = SecretsConfig {
+        encrypt,
+        ..SecretsConfig::default()
+    };
 
     if encrypt {
         println!(
@@ -2827,6 +2850,8 @@ fn setup_channels() -> Result<ChannelsConfig> {
         DingTalk,
         QqOfficial,
         LarkFeishu,
+        Xmpp,
+        Zulip,
         Done,
     }
     let menu_choices = [
@@ -2842,6 +2867,8 @@ fn setup_channels() -> Result<ChannelsConfig> {
         ChannelMenuChoice::DingTalk,
         ChannelMenuChoice::QqOfficial,
         ChannelMenuChoice::LarkFeishu,
+        ChannelMenuChoice::Xmpp,
+        ChannelMenuChoice::Zulip,
         ChannelMenuChoice::Done,
     ];
 
@@ -2945,6 +2972,22 @@ fn setup_channels() -> Result<ChannelsConfig> {
                         "— Lark/Feishu Bot"
                     }
                 ),
+                ChannelMenuChoice::Xmpp => format!(
+                    "XMPP       {}",
+                    if config.xmpp.is_some() {
+                        "✅ configured"
+                    } else {
+                        "— Jabber chats and rooms"
+                    }
+                ),
+                ChannelMenuChoice::Zulip => format!(
+                    "Zulip      {}",
+                    if config.zulip.is_some() {
+                        "✅ connected"
+                    } else {
+                        "— Zulip bot"
+                    }
+                ),
                 ChannelMenuChoice::Done => "Done — finish setup".to_string(),
             })
             .collect();
@@ -4215,6 +4258,196 @@ fn setup_channels() -> Result<ChannelsConfig> {
                     port,
                 });
             }
+            ChannelMenuChoice::Xmpp => {
+                // ── XMPP ──
+                println!();
+                println!(
+                    "  {} {}",
+                    style("XMPP Setup").white().bold(),
+                    style("— Jabber direct chats and multi-user rooms").dim()
+                );
+                print_bullet("1. Create an account for the bot on your XMPP server");
+                print_bullet("2. Optionally invite it to the rooms it should join");
+                println!();
+
+                let jid: String = Input::new()
+                    .with_prompt("  Bot JID (e.g. bot@example.com)")
+                    .interact_text()?;
+                let jid = jid.trim().to_string();
+
+                if jid.is_empty() || !jid.contains('@') {
+                    println!("  {} Skipped — a full JID is required", style("→").dim());
+                    continue;
+                }
+
+                let password: String = Input::new().with_prompt("  Password").interact_text()?;
+
+                let server: String = Input::new()
+                    .with_prompt("  Server host (Enter to use the JID domain)")
+                    .allow_empty(true)
+                    .interact_text()?;
+
+                let rooms_str: String = Input::new()
+                    .with_prompt("  Rooms to join (comma-separated, Enter for none)")
+                    .allow_empty(true)
+                    .interact_text()?;
+                let rooms: Vec<String> = rooms_str
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+
+                let users_str: String = Input::new()
+                    .with_prompt("  Allowed JIDs or rooms (comma-separated, '*' for all)")
+                    .allow_empty(true)
+                    .interact_text()?;
+                let allowed_users: Vec<String> = users_str
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+
+                let mention_only = !rooms.is_empty()
+                    && Confirm::new()
+                        .with_prompt("  Only respond in rooms when mentioned?")
+                        .default(true)
+                        .interact()?;
+
+                if allowed_users.is_empty() {
+                    println!(
+                        "  {} No users allowlisted — XMPP inbound messages will be denied until you add JIDs or '*'.",
+                        style("⚠").yellow().bold()
+                    );
+                }
+
+                println!(
+                    "  {} XMPP configured as {}",
+                    style("✅").green().bold(),
+                    style(&jid).cyan()
+                );
+
+                config.xmpp = Some(XmppConfig {
+                    jid,
+                    password: password.trim().to_string(),
+                    server: if server.trim().is_empty() {
+                        None
+                    } else {
+                        Some(server.trim().to_string())
+                    },
+                    port: 5222,
+                    rooms,
+                    nickname: None,
+                    allowed_users,
+                    mention_only,
+                    starttls: true,
+                });
+            }
+            ChannelMenuChoice::Zulip => {
+                // ── Zulip ──
+                println!();
+                println!(
+                    "  {} {}",
+                    style("Zulip Setup").white().bold(),
+                    style("— talk to ZeroClaw from Zulip streams and DMs").dim()
+                );
+                print_bullet("1. In Zulip, open Settings → Personal → Bots and add a Generic bot");
+                print_bullet("2. Copy the bot email and API key");
+                print_bullet("3. Subscribe the bot to the streams it should answer in");
+                println!();
+
+                let url: String = Input::new()
+                    .with_prompt("  Zulip server URL (e.g. https://chat.example.com)")
+                    .interact_text()?;
+                let url = url.trim().trim_end_matches('/').to_string();
+
+                if url.is_empty() {
+                    println!("  {} Skipped", style("→").dim());
+                    continue;
+                }
+
+                let bot_email: String = Input::new().with_prompt("  Bot email").interact_text()?;
+                let bot_email = bot_email.trim().to_string();
+                let api_key: String = Input::new().with_prompt("  API key").interact_text()?;
+                let api_key = api_key.trim().to_string();
+
+                // Test connection (run entirely in separate thread — Response must be used/dropped there)
+                print!("  {} Testing connection... ", style("⏳").dim());
+                let endpoint = format!("{url}/api/v1/users/me");
+                let email_clone = bot_email.clone();
+                let key_clone = api_key.clone();
+                let thread_result = std::thread::spawn(move || {
+                    let client = reqwest::blocking::Client::new();
+                    let resp = client
+                        .get(endpoint)
+                        .basic_auth(email_clone, Some(key_clone))
+                        .send()?;
+                    let ok = resp.status().is_success();
+                    let data: serde_json::Value = resp.json().unwrap_or_default();
+                    let name = data
+                        .get("full_name")
+                        .and_then(serde_json::Value::as_str)
+                        .unwrap_or("unknown")
+                        .to_string();
+                    Ok::<_, reqwest::Error>((ok, name))
+                })
+                .join();
+                match thread_result {
+                    Ok(Ok((true, name))) => {
+                        println!(
+                            "\r  {} Connected as {name}        ",
+                            style("✅").green().bold()
+                        );
+                    }
+                    _ => {
+                        println!(
+                            "\r  {} Connection failed — check the URL, email and API key",
+                            style("❌").red().bold()
+                        );
+                        continue;
+                    }
+                }
+
+                let streams_str: String = Input::new()
+                    .with_prompt("  Streams to respond in (comma-separated, Enter for all)")
+                    .allow_empty(true)
+                    .interact_text()?;
+                let streams: Vec<String> = streams_str
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+
+                let users_str: String = Input::new()
+                    .with_prompt("  Allowed sender emails (comma-separated, '*' for all)")
+                    .allow_empty(true)
+                    .interact_text()?;
+                let allowed_users: Vec<String> = users_str
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+
+                let mention_only = Confirm::new()
+                    .with_prompt("  Only respond in streams when @-mentioned?")
+                    .default(true)
+                    .interact()?;
+
+                if allowed_users.is_empty() {
+                    println!(
+                        "  {} No users allowlisted — Zulip inbound messages will be denied until you add emails or '*'.",
+                        style("⚠").yellow().bold()
+                    );
+                }
+
+                config.zulip = Some(ZulipConfig {
+                    url,
+                    bot_email,
+                    api_key,
+                    streams,
+                    allowed_users,
+                    mention_only,
+                });
+            }
             ChannelMenuChoice::Done => break,
         }
         println!();
@@ -4261,6 +4494,12 @@ fn setup_channels() -> Result<ChannelsConfig> {
     if config.lark.is_some() {
         active.push("Lark");
     }
+    if config.xmpp.is_some() {
+        active.push("XMPP");
+    }
+    if config.zulip.is_some() {
+        active.push("Zulip");
+    }
 
     println!(
         "  {} Channels: {}",
@@ -4593,6 +4832,9 @@ fn scaffold_workspace(workspace_dir: &Path, ctx: &ProjectContext) -> Result<()>
          - **file_write** — Write file contents\n\
            - Use when: applying focused edits, scaffolding files, or updating docs/code.\n\
            - Don't use when: unsure about side effects or when the file should remain user-owned.\n\
+         - **file_edit** — Edit part of an existing file (search/replace, line range, or unified diff)\n\
+           - Use when: changing existing code or docs; pass the hash from file_read so stale edits are rejected.\n\
+           - Don't use when: creating a new file (use file_write).\n\
          - **memory_store** — Save to memory\n\
            - Use when: preserving durable preferences, decisions, or key context.\n\
            - Don't use when: info is transient, noisy, or sensitive without explicit need.\n\
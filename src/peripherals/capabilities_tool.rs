@@ -1,6 +1,6 @@
 //! Hardware capabilities tool — Phase C: query device for reported GPIO pins.
 
-use super::serial::SerialTransport;
+use super::protocol::DeviceTransport;
 use crate::tools::traits::{Tool, ToolResult};
 use async_trait::async_trait;
 use serde_json::json;
@@ -8,12 +8,12 @@ use std::sync::Arc;
 
 /// Tool: query device capabilities (GPIO pins, LED pin) from firmware.
 pub struct HardwareCapabilitiesTool {
-    /// (board_name, transport) for each serial board.
-    boards: Vec<(String, Arc<SerialTransport>)>,
+    /// (board_name, transport) for each board speaking the device protocol.
+    boards: Vec<(String, Arc<DeviceTransport>)>,
 }
 
 impl HardwareCapabilitiesTool {
-    pub(crate) fn new(boards: Vec<(String, Arc<SerialTransport>)>) -> Self {
+    pub(crate) fn new(boards: Vec<(String, Arc<DeviceTransport>)>) -> Self {
         Self { boards }
     }
 }
@@ -84,7 +84,8 @@ impl Tool for HardwareCapabilitiesTool {
             if filter.is_some() {
                 "No matching board or capabilities not supported.".to_string()
             } else {
-                "No serial boards configured or capabilities not supported.".to_string()
+                "No serial or simulated boards configured or capabilities not supported."
+                    .to_string()
             }
         } else {
             outputs.join("\n")
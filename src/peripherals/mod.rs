@@ -3,6 +3,10 @@
 //! Peripherals extend the agent with physical capabilities. See
 //! `docs/hardware-peripherals-design.md` for the full design.
 
+pub mod capabilities_tool;
+pub mod network;
+pub mod protocol;
+pub mod simulated;
 pub mod traits;
 
 #[cfg(feature = "hardware")]
@@ -13,8 +17,6 @@ pub mod arduino_flash;
 #[cfg(feature = "hardware")]
 pub mod arduino_upload;
 #[cfg(feature = "hardware")]
-pub mod capabilities_tool;
-#[cfg(feature = "hardware")]
 pub mod nucleo_flash;
 #[cfg(feature = "hardware")]
 pub mod uno_q_bridge;
@@ -27,10 +29,11 @@ pub mod rpi;
 pub use traits::Peripheral;
 
 use crate::config::{Config, PeripheralBoardConfig, PeripheralsConfig};
-#[cfg(feature = "hardware")]
 use crate::tools::HardwareMemoryMapTool;
 use crate::tools::Tool;
 use anyhow::Result;
+use protocol::DeviceTransport;
+use std::sync::Arc;
 
 /// List configured boards from config (no connection yet).
 pub fn list_configured_boards(config: &PeripheralsConfig) -> Vec<&PeripheralBoardConfig> {
@@ -94,6 +97,9 @@ pub async fn handle_command(cmd: crate::PeripheralCommands, config: &Config) ->
                 transport: transport.to_string(),
                 path: path_opt,
                 baud: 115_200,
+                allowed_hosts: Vec::new(),
+                shared_secret: None,
+                simulation: None,
             });
             cfg.save().await?;
             println!("Added {} at {}. Restart daemon to apply.", board, path);
@@ -135,74 +141,55 @@ pub async fn handle_command(cmd: crate::PeripheralCommands, config: &Config) ->
 }
 
 /// Create and connect peripherals from config, returning their tools.
-/// Returns empty vec if peripherals disabled or hardware feature off.
-#[cfg(feature = "hardware")]
+/// Returns empty vec if peripherals disabled. Serial, native and bridge
+/// boards need the hardware feature; network and simulated boards are always
+/// available.
 pub async fn create_peripheral_tools(config: &PeripheralsConfig) -> Result<Vec<Box<dyn Tool>>> {
     if !config.enabled || config.boards.is_empty() {
         return Ok(Vec::new());
     }
 
     let mut tools: Vec<Box<dyn Tool>> = Vec::new();
-    let mut serial_transports: Vec<(String, std::sync::Arc<serial::SerialTransport>)> = Vec::new();
+    let mut device_transports: Vec<(String, Arc<DeviceTransport>)> = Vec::new();
 
     for board in &config.boards {
-        // Arduino Uno Q: Bridge transport (socket to local Bridge app)
-        if board.transport == "bridge" && (board.board == "arduino-uno-q" || board.board == "uno-q")
-        {
-            tools.push(Box::new(uno_q_bridge::UnoQGpioReadTool));
-            tools.push(Box::new(uno_q_bridge::UnoQGpioWriteTool));
-            tracing::info!(board = %board.board, "Uno Q Bridge GPIO tools added");
-            continue;
-        }
-
-        // Native transport: RPi GPIO (Linux only)
-        #[cfg(all(feature = "peripheral-rpi", target_os = "linux"))]
-        if board.transport == "native"
-            && (board.board == "rpi-gpio" || board.board == "raspberry-pi")
-        {
-            match rpi::RpiGpioPeripheral::connect_from_config(board).await {
+        // Simulated board: in-process or pseudo-terminal, no device needed
+        if board.board == simulated::BOARD_TYPE {
+            match simulated::SimulatedPeripheral::connect_from_config(board).await {
                 Ok(peripheral) => {
+                    device_transports.push((board.board.clone(), peripheral.transport()));
                     tools.extend(peripheral.tools());
-                    tracing::info!(board = %board.board, "RPi GPIO peripheral connected");
+                    tracing::info!(board = %board.board, "Simulated peripheral connected");
                 }
                 Err(e) => {
-                    tracing::warn!("Failed to connect RPi GPIO {}: {}", board.board, e);
+                    tracing::warn!("Failed to start simulated board: {}", e);
                 }
             }
             continue;
         }
 
-        // Serial transport (STM32, ESP32, Arduino, etc.)
-        if board.transport != "serial" {
-            continue;
-        }
-        if board.path.is_none() {
-            tracing::warn!("Skipping serial board {}: no path", board.board);
-            continue;
-        }
-
-        match serial::SerialPeripheral::connect(board).await {
-            Ok(peripheral) => {
-                let mut p = peripheral;
-                if p.connect().await.is_err() {
-                    tracing::warn!("Peripheral {} connect warning (continuing)", p.name());
+        // Network transport: Wi-Fi boards (ESP32) over TCP or WebSocket
+        if board.transport == "tcp" || board.transport == "websocket" {
+            match network::NetworkPeripheral::connect_from_config(board).await {
+                Ok(peripheral) => {
+                    device_transports.push((board.board.clone(), peripheral.transport()));
+                    tools.extend(peripheral.tools());
+                    tracing::info!(board = %board.board, "Network peripheral configured");
                 }
-                serial_transports.push((board.board.clone(), p.transport()));
-                tools.extend(p.tools());
-                if board.board == "arduino-uno" {
-                    if let Some(ref path) = board.path {
-                        tools.push(Box::new(arduino_upload::ArduinoUploadTool::new(
-                            path.clone(),
-                        )));
-                        tracing::info!("Arduino upload tool added (port: {})", path);
-                    }
+                Err(e) => {
+                    tracing::warn!("Failed to configure {}: {}", board.board, e);
                 }
-                tracing::info!(board = %board.board, "Serial peripheral connected");
-            }
-            Err(e) => {
-                tracing::warn!("Failed to connect {}: {}", board.board, e);
             }
+            continue;
         }
+
+        #[cfg(feature = "hardware")]
+        connect_hardware_board(board, &mut tools, &mut device_transports).await;
+        #[cfg(not(feature = "hardware"))]
+        tracing::warn!(
+            "Skipping board {}: requires the 'hardware' feature",
+            board.board
+        );
     }
 
     // Phase B: Add hardware tools when any boards configured
@@ -212,24 +199,83 @@ pub async fn create_peripheral_tools(config: &PeripheralsConfig) -> Result<Vec<B
         tools.push(Box::new(crate::tools::HardwareBoardInfoTool::new(
             board_names.clone(),
         )));
-        tools.push(Box::new(crate::tools::HardwareMemoryReadTool::new(
-            board_names,
-        )));
+        tools.push(Box::new(
+            crate::tools::HardwareMemoryReadTool::new(board_names)
+                .with_transports(device_transports.clone()),
+        ));
     }
 
-    // Phase C: Add hardware_capabilities tool when any serial boards
-    if !serial_transports.is_empty() {
+    // Phase C: Add hardware_capabilities tool when any protocol boards
+    if !device_transports.is_empty() {
         tools.push(Box::new(capabilities_tool::HardwareCapabilitiesTool::new(
-            serial_transports,
+            device_transports,
         )));
     }
 
     Ok(tools)
 }
 
-#[cfg(not(feature = "hardware"))]
-pub async fn create_peripheral_tools(_config: &PeripheralsConfig) -> Result<Vec<Box<dyn Tool>>> {
-    Ok(Vec::new())
+/// Connect one physical board, adding its tools (and serial transport).
+#[cfg(feature = "hardware")]
+async fn connect_hardware_board(
+    board: &PeripheralBoardConfig,
+    tools: &mut Vec<Box<dyn Tool>>,
+    device_transports: &mut Vec<(String, Arc<DeviceTransport>)>,
+) {
+    // Arduino Uno Q: Bridge transport (socket to local Bridge app)
+    if board.transport == "bridge" && (board.board == "arduino-uno-q" || board.board == "uno-q") {
+        tools.push(Box::new(uno_q_bridge::UnoQGpioReadTool));
+        tools.push(Box::new(uno_q_bridge::UnoQGpioWriteTool));
+        tracing::info!(board = %board.board, "Uno Q Bridge GPIO tools added");
+        return;
+    }
+
+    // Native transport: RPi GPIO (Linux only)
+    #[cfg(all(feature = "peripheral-rpi", target_os = "linux"))]
+    if board.transport == "native" && (board.board == "rpi-gpio" || board.board == "raspberry-pi") {
+        match rpi::RpiGpioPeripheral::connect_from_config(board).await {
+            Ok(peripheral) => {
+                tools.extend(peripheral.tools());
+                tracing::info!(board = %board.board, "RPi GPIO peripheral connected");
+            }
+            Err(e) => {
+                tracing::warn!("Failed to connect RPi GPIO {}: {}", board.board, e);
+            }
+        }
+        return;
+    }
+
+    // Serial transport (STM32, ESP32, Arduino, etc.)
+    if board.transport != "serial" {
+        return;
+    }
+    if board.path.is_none() {
+        tracing::warn!("Skipping serial board {}: no path", board.board);
+        return;
+    }
+
+    match serial::SerialPeripheral::connect(board).await {
+        Ok(peripheral) => {
+            let mut p = peripheral;
+            if p.connect().await.is_err() {
+                tracing::warn!("Peripheral {} connect warning (continuing)", p.name());
+            }
+            device_transports.push((board.board.clone(), p.transport()));
+            tools.extend(p.tools());
+            if board.board == "arduino-uno" {
+                if let Some(ref path) = board.path {
+                    tools.push(Box::new(arduino_upload::ArduinoUploadTool::new(
+                        path.clone(),
+                    )));
+                    tracing::info!("Arduino upload tool added (port: {})", path);
+                }
+            }
+            tracing::info!(board = %board.board, "Serial peripheral connected");
+        }
+        Err(e) => {
+            tracing::warn!("Failed to connect {}: {}", board.board, e);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -246,6 +292,9 @@ mod tests {
                 transport: "serial".into(),
                 path: Some("/dev/ttyACM0".into()),
                 baud: 115_200,
+                allowed_hosts: Vec::new(),
+                shared_secret: None,
+                simulation: None,
             }],
             datasheet_dir: None,
         };
@@ -266,12 +315,18 @@ mod tests {
                     transport: "serial".into(),
                     path: Some("/dev/ttyACM0".into()),
                     baud: 115_200,
+                    allowed_hosts: Vec::new(),
+                    shared_secret: None,
+                    simulation: None,
                 },
                 PeripheralBoardConfig {
                     board: "rpi-gpio".into(),
                     transport: "native".into(),
                     path: None,
                     baud: 115_200,
+                    allowed_hosts: Vec::new(),
+                    shared_secret: None,
+                    simulation: None,
                 },
             ],
             datasheet_dir: None,
@@ -309,4 +364,26 @@ mod tests {
             "disabled peripherals should produce no tools"
         );
     }
+
+    #[tokio::test]
+    async fn create_peripheral_tools_starts_simulated_board() {
+        let config = PeripheralsConfig {
+            enabled: true,
+            boards: vec![PeripheralBoardConfig {
+                board: "simulated".into(),
+                transport: "in-process".into(),
+                path: None,
+                baud: 115_200,
+                allowed_hosts: Vec::new(),
+                shared_secret: None,
+                simulation: None,
+            }],
+            datasheet_dir: None,
+        };
+        let tools = create_peripheral_tools(&config).await.unwrap();
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert!(names.contains(&"gpio_write"));
+        assert!(names.contains(&"sensor_read"));
+        assert!(names.contains(&"hardware_capabilities"));
+    }
 }
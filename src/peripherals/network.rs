@@ -0,0 +1,471 @@
+//! Network peripheral — Wi-Fi boards (e.g. ESP32) over TCP or WebSocket.
+//!
+//! Speaks the same newline-JSON protocol as [`super::serial`]; over
+//! WebSocket each request and reply is one text frame. Every connection
+//! starts with a mutual HMAC-SHA256 challenge on the board's pre-shared key:
+//!
+//! ```text
+//! host  → {"id":"1","cmd":"hello","args":{}}
+//! board → {"id":"1","ok":true,"result":"<board nonce>"}
+//! host  → {"id":"2","cmd":"auth","args":{"nonce":"<host nonce>","mac":"<HMAC(key, "host:" + board nonce)>"}}
+//! board → {"id":"2","ok":true,"result":"<HMAC(key, "board:" + host nonce)>"}
+//! ```
+//!
+//! Links reconnect lazily with exponential backoff after a failure.
+
+use super::protocol::{
+    send_request, ConnectFuture, Connector, DeviceStream, DeviceTransport, GpioReadTool,
+    GpioWriteTool,
+};
+use super::traits::Peripheral;
+use crate::config::PeripheralBoardConfig;
+use crate::tools::traits::Tool;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::Sha256;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+/// Where a network board is reached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Endpoint {
+    Tcp { addr: String },
+    WebSocket { url: String },
+}
+
+impl Endpoint {
+    /// Parse `path` for `transport` ("tcp" or "websocket"), returning the
+    /// endpoint and the host to check against the allowlist.
+    fn parse(transport: &str, path: &str) -> Result<(Self, String)> {
+        match transport {
+            "tcp" => {
+                let (host, port) = path
+                    .rsplit_once(':')
+                    .with_context(|| format!("TCP board address must be host:port, got {path}"))?;
+                port.parse::<u16>()
+                    .with_context(|| format!("Invalid port in {path}"))?;
+                let host = host.trim_start_matches('[').trim_end_matches(']');
+                Ok((
+                    Self::Tcp {
+                        addr: path.to_string(),
+                    },
+                    host.to_string(),
+                ))
+            }
+            "websocket" => {
+                let url = reqwest::Url::parse(path)
+                    .with_context(|| format!("Invalid WebSocket URL {path}"))?;
+                if !matches!(url.scheme(), "ws" | "wss") {
+                    bail!("WebSocket board URL must use ws:// or wss://, got {path}");
+                }
+                let host = url
+                    .host_str()
+                    .with_context(|| format!("WebSocket URL has no host: {path}"))?
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .to_string();
+                Ok((
+                    Self::WebSocket {
+                        url: path.to_string(),
+                    },
+                    host,
+                ))
+            }
+            other => bail!("Not a network transport: {other}"),
+        }
+    }
+}
+
+fn is_host_allowed(host: &str, allowed: &[String]) -> bool {
+    allowed.iter().any(|a| {
+        a.trim()
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .eq_ignore_ascii_case(host)
+    })
+}
+
+fn handshake_mac(secret: &str, role: &str, nonce: &str) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+    mac.update(role.as_bytes());
+    mac.update(b":");
+    mac.update(nonce.as_bytes());
+    mac
+}
+
+/// Run the host side of the pre-shared-key handshake on a fresh stream.
+async fn handshake(stream: &mut dyn DeviceStream, secret: &str) -> Result<()> {
+    let hello = send_request(stream, "hello", json!({})).await?;
+    let board_nonce = hello["result"].as_str().unwrap_or_default();
+    if hello["ok"].as_bool() != Some(true) || board_nonce.is_empty() {
+        bail!("Board did not send a handshake challenge");
+    }
+
+    let host_nonce = hex::encode(rand::random::<[u8; 16]>());
+    let mac = hex::encode(
+        handshake_mac(secret, "host", board_nonce)
+            .finalize()
+            .into_bytes(),
+    );
+    let auth = send_request(stream, "auth", json!({ "nonce": host_nonce, "mac": mac })).await?;
+    if auth["ok"].as_bool() != Some(true) {
+        bail!("Board rejected the shared secret");
+    }
+    let board_mac = hex::decode(auth["result"].as_str().unwrap_or_default()).unwrap_or_default();
+    if handshake_mac(secret, "board", &host_nonce)
+        .verify_slice(&board_mac)
+        .is_err()
+    {
+        bail!("Board failed to prove the shared secret");
+    }
+    Ok(())
+}
+
+/// Bridge a WebSocket to a line stream: one text frame per line.
+fn websocket_lines<S>(ws: WebSocketStream<S>) -> Box<dyn DeviceStream>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (host, pump) = tokio::io::duplex(8192);
+    tokio::spawn(async move {
+        let (reader, mut writer) = tokio::io::split(pump);
+        let mut lines = BufReader::new(reader).lines();
+        let (mut sink, mut frames) = ws.split();
+        loop {
+            tokio::select! {
+                line = lines.next_line() => {
+                    let Ok(Some(line)) = line else { break };
+                    if sink.send(Message::Text(line.into())).await.is_err() {
+                        break;
+                    }
+                }
+                frame = frames.next() => {
+                    let text = match frame {
+                        Some(Ok(Message::Text(text))) => text.to_string(),
+                        Some(Ok(Message::Binary(bytes))) => String::from_utf8_lossy(&bytes).into_owned(),
+                        Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                        Some(Ok(_)) => continue,
+                    };
+                    let line = format!("{}\n", text.trim_end());
+                    if writer.write_all(line.as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+        let _ = sink.close().await;
+    });
+    Box::new(host)
+}
+
+struct NetworkConnector {
+    endpoint: Endpoint,
+    secret: String,
+}
+
+impl Connector for NetworkConnector {
+    fn connect(&self) -> ConnectFuture {
+        let endpoint = self.endpoint.clone();
+        let secret = self.secret.clone();
+        Box::pin(async move {
+            let mut stream: Box<dyn DeviceStream> = match endpoint {
+                Endpoint::Tcp { addr } => {
+                    let stream = tokio::net::TcpStream::connect(&addr)
+                        .await
+                        .with_context(|| format!("Failed to connect to {addr}"))?;
+                    stream.set_nodelay(true)?;
+                    Box::new(stream)
+                }
+                Endpoint::WebSocket { url } => {
+                    let (ws, _) = tokio_tungstenite::connect_async(&url)
+                        .await
+                        .with_context(|| format!("Failed to connect to {url}"))?;
+                    websocket_lines(ws)
+                }
+            };
+            handshake(&mut *stream, &secret).await?;
+            Ok(stream)
+        })
+    }
+}
+
+/// ESP32 and other Wi-Fi boards over TCP or WebSocket.
+pub struct NetworkPeripheral {
+    name: String,
+    board_type: String,
+    transport: Arc<DeviceTransport>,
+}
+
+impl NetworkPeripheral {
+    /// Validate config and connect. An unreachable board is not an error:
+    /// its tools reconnect with backoff on use.
+    pub async fn connect_from_config(config: &PeripheralBoardConfig) -> Result<Self> {
+        let path = config
+            .path
+            .as_deref()
+            .with_context(|| format!("{} board requires path", config.transport))?;
+        let (endpoint, host) = Endpoint::parse(&config.transport, path)?;
+        if !is_host_allowed(&host, &config.allowed_hosts) {
+            bail!(
+                "Host {host} is not in allowed_hosts for board {}. Add it to [[peripherals.boards]] allowed_hosts.",
+                config.board
+            );
+        }
+        let secret = config
+            .shared_secret
+            .clone()
+            .filter(|s| !s.trim().is_empty())
+            .with_context(|| {
+                format!(
+                    "{} board {} requires shared_secret",
+                    config.transport, config.board
+                )
+            })?;
+
+        let transport = Arc::new(DeviceTransport::reconnecting(NetworkConnector {
+            endpoint,
+            secret,
+        }));
+        if let Err(e) = transport.ensure_connected().await {
+            tracing::warn!(board = %config.board, "Board at {path} not reachable yet: {e:#}");
+        }
+
+        Ok(Self {
+            name: format!("{}-{}", config.board, host),
+            board_type: config.board.clone(),
+            transport,
+        })
+    }
+
+    /// Expose transport for capabilities and memory read tools.
+    pub(crate) fn transport(&self) -> Arc<DeviceTransport> {
+        self.transport.clone()
+    }
+}
+
+#[async_trait]
+impl Peripheral for NetworkPeripheral {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn board_type(&self) -> &str {
+        &self.board_type
+    }
+
+    async fn connect(&mut self) -> Result<()> {
+        self.transport.ensure_connected().await
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn health_check(&self) -> bool {
+        self.transport.ping().await
+    }
+
+    fn tools(&self) -> Vec<Box<dyn Tool>> {
+        vec![
+            Box::new(GpioReadTool::new(self.transport.clone())),
+            Box::new(GpioWriteTool::new(self.transport.clone())),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SimulatedBoardConfig;
+    use crate::peripherals::simulated::SimulatedBoard;
+    use serde_json::Value;
+    use tokio::net::TcpListener;
+
+    const SECRET: &str = "s3cret";
+
+    /// Board side of the handshake.
+    async fn accept_handshake(stream: &mut dyn DeviceStream, secret: &str) -> bool {
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+
+        reader.read_line(&mut line).await.unwrap();
+        let hello: Value = serde_json::from_str(&line).unwrap();
+        let reply = json!({"id": hello["id"], "ok": true, "result": "board-nonce"});
+        reader
+            .get_mut()
+            .write_all(format!("{reply}\n").as_bytes())
+            .await
+            .unwrap();
+
+        line.clear();
+        reader.read_line(&mut line).await.unwrap();
+        let auth: Value = serde_json::from_str(&line).unwrap();
+        let mac = hex::decode(auth["args"]["mac"].as_str().unwrap()).unwrap();
+        let ok = handshake_mac(secret, "host", "board-nonce")
+            .verify_slice(&mac)
+            .is_ok();
+        let proof = hex::encode(
+            handshake_mac(secret, "board", auth["args"]["nonce"].as_str().unwrap())
+                .finalize()
+                .into_bytes(),
+        );
+        let reply = json!({"id": auth["id"], "ok": ok, "result": proof});
+        reader
+            .get_mut()
+            .write_all(format!("{reply}\n").as_bytes())
+            .await
+            .unwrap();
+        ok
+    }
+
+    /// Serve a simulated board on TCP behind the handshake.
+    async fn spawn_tcp_board(board: SimulatedBoard, secret: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                if accept_handshake(&mut stream, secret).await {
+                    board.serve(stream);
+                }
+            }
+        });
+        addr
+    }
+
+    fn board_config(transport: &str, path: &str, secret: &str) -> PeripheralBoardConfig {
+        PeripheralBoardConfig {
+            board: "esp32".into(),
+            transport: transport.into(),
+            path: Some(path.into()),
+            allowed_hosts: vec!["127.0.0.1".into()],
+            shared_secret: Some(secret.into()),
+            ..PeripheralBoardConfig::default()
+        }
+    }
+
+    #[test]
+    fn endpoint_parses_tcp_and_websocket_hosts() {
+        let (endpoint, host) = Endpoint::parse("tcp", "192.168.1.50:7070").unwrap();
+        assert_eq!(
+            endpoint,
+            Endpoint::Tcp {
+                addr: "192.168.1.50:7070".into()
+            }
+        );
+        assert_eq!(host, "192.168.1.50");
+
+        let (_, host) = Endpoint::parse("websocket", "wss://ESP32.local:7070/zc").unwrap();
+        assert_eq!(host, "esp32.local");
+        assert!(is_host_allowed(&host, &["esp32.local".into()]));
+
+        assert!(Endpoint::parse("tcp", "no-port").is_err());
+        assert!(Endpoint::parse("websocket", "http://esp32.local").is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_hosts_outside_allowlist_and_missing_secret() {
+        let mut config = board_config("tcp", "10.0.0.9:7070", SECRET);
+        let err = NetworkPeripheral::connect_from_config(&config)
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("not in allowed_hosts"), "{err}");
+
+        config.path = Some("127.0.0.1:7070".into());
+        config.shared_secret = None;
+        let err = NetworkPeripheral::connect_from_config(&config)
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("requires shared_secret"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn tcp_board_handshakes_and_serves_tools() {
+        let board = SimulatedBoard::new(&SimulatedBoardConfig::default());
+        let addr = spawn_tcp_board(board.clone(), SECRET).await;
+        let peripheral =
+            NetworkPeripheral::connect_from_config(&board_config("tcp", &addr, SECRET))
+                .await
+                .unwrap();
+
+        assert!(peripheral.health_check().await);
+        let result = peripheral.tools()[1]
+            .execute(json!({"pin": 4, "value": 1}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(board.gpio(4), Some(1));
+    }
+
+    #[tokio::test]
+    async fn wrong_secret_fails_handshake() {
+        let board = SimulatedBoard::new(&SimulatedBoardConfig::default());
+        let addr = spawn_tcp_board(board.clone(), SECRET).await;
+        let peripheral =
+            NetworkPeripheral::connect_from_config(&board_config("tcp", &addr, "wrong"))
+                .await
+                .unwrap();
+        let err = peripheral.transport().ensure_connected().await;
+        // Still inside the backoff window from the failed attempt at startup.
+        assert!(err.unwrap_err().to_string().contains("Device offline"));
+        assert!(board.received_commands().is_empty());
+    }
+
+    #[tokio::test]
+    async fn reconnects_after_link_drops() {
+        let board = SimulatedBoard::new(&SimulatedBoardConfig::default());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server_board = board.clone();
+        tokio::spawn(async move {
+            // First connection drops right after the handshake.
+            let (mut first, _) = listener.accept().await.unwrap();
+            assert!(accept_handshake(&mut first, SECRET).await);
+            drop(first);
+            let (mut second, _) = listener.accept().await.unwrap();
+            assert!(accept_handshake(&mut second, SECRET).await);
+            server_board.serve(second);
+        });
+
+        let peripheral =
+            NetworkPeripheral::connect_from_config(&board_config("tcp", &addr, SECRET))
+                .await
+                .unwrap();
+        assert!(!peripheral.health_check().await, "dropped link fails once");
+        assert!(peripheral.health_check().await, "next request reconnects");
+    }
+
+    #[tokio::test]
+    async fn websocket_board_exchanges_one_frame_per_message() {
+        let board = SimulatedBoard::new(&SimulatedBoardConfig::default());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_board = board.clone();
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            let mut lines = websocket_lines(ws);
+            assert!(accept_handshake(&mut *lines, SECRET).await);
+            server_board.serve(lines);
+        });
+
+        let url = format!("ws://{addr}/zeroclaw");
+        let peripheral =
+            NetworkPeripheral::connect_from_config(&board_config("websocket", &url, SECRET))
+                .await
+                .unwrap();
+        let result = peripheral.tools()[0]
+            .execute(json!({"pin": 13}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.output, "0");
+    }
+}
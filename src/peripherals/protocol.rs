@@ -0,0 +1,405 @@
+//! Device command protocol shared by serial firmware and the simulated board.
+//!
+//! Protocol: newline-delimited JSON.
+//! Request:  {"id":"1","cmd":"gpio_write","args":{"pin":13,"value":1}}
+//! Response: {"id":"1","ok":true,"result":"done"}
+
+use crate::tools::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+/// Default timeout for a request/response round trip.
+pub(crate) const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// First delay after a failed reconnect; doubles per failure.
+const RECONNECT_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Upper bound on the reconnect delay.
+const RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Byte stream a device is reached over (serial port, pty, in-process pipe).
+pub(crate) trait DeviceStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> DeviceStream for T {}
+
+pub(crate) type ConnectFuture =
+    Pin<Box<dyn Future<Output = anyhow::Result<Box<dyn DeviceStream>>> + Send>>;
+
+/// Opens (and authenticates) a fresh stream for links that can drop, such
+/// as network boards.
+pub(crate) trait Connector: Send + Sync {
+    fn connect(&self) -> ConnectFuture;
+}
+
+/// JSON request/response over a device stream.
+pub(crate) async fn send_request<S: AsyncRead + AsyncWrite + Unpin + ?Sized>(
+    port: &mut S,
+    cmd: &str,
+    args: Value,
+) -> anyhow::Result<Value> {
+    static ID: AtomicU64 = AtomicU64::new(0);
+    let id = ID.fetch_add(1, Ordering::Relaxed);
+    let id_str = id.to_string();
+
+    let req = json!({
+        "id": id_str,
+        "cmd": cmd,
+        "args": args
+    });
+    let line = format!("{}\n", req);
+
+    port.write_all(line.as_bytes()).await?;
+    port.flush().await?;
+
+    let mut buf = Vec::new();
+    let mut b = [0u8; 1];
+    while port.read_exact(&mut b).await.is_ok() {
+        if b[0] == b'\n' {
+            break;
+        }
+        buf.push(b[0]);
+    }
+    let line_str = String::from_utf8_lossy(&buf);
+    let resp: Value = serde_json::from_str(line_str.trim())?;
+    let resp_id = resp["id"].as_str().unwrap_or("");
+    if resp_id != id_str {
+        anyhow::bail!("Response id mismatch: expected {}, got {}", id_str, resp_id);
+    }
+    Ok(resp)
+}
+
+/// Shared device transport for tools. Pub(crate) for capabilities tool.
+pub(crate) struct DeviceTransport {
+    port: Mutex<Link>,
+    connector: Option<Box<dyn Connector>>,
+    timeout: Duration,
+}
+
+#[derive(Default)]
+struct Link {
+    stream: Option<Box<dyn DeviceStream>>,
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl DeviceTransport {
+    pub(crate) fn new(stream: impl DeviceStream + 'static) -> Self {
+        Self {
+            port: Mutex::new(Link {
+                stream: Some(Box::new(stream)),
+                ..Link::default()
+            }),
+            connector: None,
+            timeout: DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+
+    /// Transport that connects lazily and reconnects with exponential
+    /// backoff after the link fails.
+    pub(crate) fn reconnecting(connector: impl Connector + 'static) -> Self {
+        Self {
+            port: Mutex::new(Link::default()),
+            connector: Some(Box::new(connector)),
+            timeout: DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+
+    pub(crate) fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Connect now instead of on the first request (reconnecting transports).
+    pub(crate) async fn ensure_connected(&self) -> anyhow::Result<()> {
+        let mut link = self.port.lock().await;
+        self.connect_link(&mut link).await
+    }
+
+    async fn connect_link(&self, link: &mut Link) -> anyhow::Result<()> {
+        if link.stream.is_some() {
+            return Ok(());
+        }
+        let Some(connector) = &self.connector else {
+            anyhow::bail!("Device link closed");
+        };
+        if let Some(retry_at) = link.retry_at {
+            let now = Instant::now();
+            if now < retry_at {
+                anyhow::bail!(
+                    "Device offline; next reconnect attempt in {}ms",
+                    (retry_at - now).as_millis()
+                );
+            }
+        }
+        match tokio::time::timeout(self.timeout, connector.connect()).await {
+            Ok(Ok(stream)) => {
+                link.stream = Some(stream);
+                link.failures = 0;
+                link.retry_at = None;
+                Ok(())
+            }
+            outcome => {
+                let backoff = RECONNECT_INITIAL_BACKOFF
+                    .saturating_mul(1 << link.failures.min(16))
+                    .min(RECONNECT_MAX_BACKOFF);
+                link.failures = link.failures.saturating_add(1);
+                link.retry_at = Some(Instant::now() + backoff);
+                let err = match outcome {
+                    Ok(Err(e)) => e,
+                    _ => anyhow::anyhow!("timed out after {}ms", self.timeout.as_millis()),
+                };
+                Err(err.context(format!(
+                    "Device connect failed; retrying in {}ms",
+                    backoff.as_millis()
+                )))
+            }
+        }
+    }
+
+    pub(crate) async fn request(&self, cmd: &str, args: Value) -> anyhow::Result<ToolResult> {
+        let mut link = self.port.lock().await;
+        self.connect_link(&mut link).await?;
+        let Some(port) = link.stream.as_mut() else {
+            anyhow::bail!("Device link closed");
+        };
+        let outcome = tokio::time::timeout(self.timeout, send_request(&mut **port, cmd, args))
+            .await
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Device request timed out after {}ms",
+                    self.timeout.as_millis()
+                )
+            })
+            .and_then(|r| r);
+        let resp = match outcome {
+            Ok(resp) => resp,
+            Err(e) => {
+                // A reconnecting link starts fresh rather than reading a stale reply.
+                if self.connector.is_some() {
+                    link.stream = None;
+                }
+                return Err(e);
+            }
+        };
+
+        let ok = resp["ok"].as_bool().unwrap_or(false);
+        let result = resp["result"]
+            .as_str()
+            .map(String::from)
+            .unwrap_or_else(|| resp["result"].to_string());
+        let error = resp["error"].as_str().map(String::from);
+
+        Ok(ToolResult {
+            success: ok,
+            output: result,
+            error,
+        })
+    }
+
+    /// Phase C: fetch capabilities from device (gpio pins, led_pin).
+    pub async fn capabilities(&self) -> anyhow::Result<ToolResult> {
+        self.request("capabilities", json!({})).await
+    }
+
+    pub(crate) async fn ping(&self) -> bool {
+        self.request("ping", json!({}))
+            .await
+            .map(|r| r.success)
+            .unwrap_or(false)
+    }
+}
+
+/// Tool: read GPIO pin value.
+pub(crate) struct GpioReadTool {
+    transport: Arc<DeviceTransport>,
+}
+
+impl GpioReadTool {
+    pub(crate) fn new(transport: Arc<DeviceTransport>) -> Self {
+        Self { transport }
+    }
+}
+
+#[async_trait]
+impl Tool for GpioReadTool {
+    fn name(&self) -> &str {
+        "gpio_read"
+    }
+
+    fn description(&self) -> &str {
+        "Read the value (0 or 1) of a GPIO pin on a connected peripheral (e.g. STM32 Nucleo)"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "pin": {
+                    "type": "integer",
+                    "description": "GPIO pin number (e.g. 13 for LED on Nucleo)"
+                }
+            },
+            "required": ["pin"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let pin = args
+            .get("pin")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'pin' parameter"))?;
+        self.transport
+            .request("gpio_read", json!({ "pin": pin }))
+            .await
+    }
+}
+
+/// Tool: write GPIO pin value.
+pub(crate) struct GpioWriteTool {
+    transport: Arc<DeviceTransport>,
+}
+
+impl GpioWriteTool {
+    pub(crate) fn new(transport: Arc<DeviceTransport>) -> Self {
+        Self { transport }
+    }
+}
+
+#[async_trait]
+impl Tool for GpioWriteTool {
+    fn name(&self) -> &str {
+        "gpio_write"
+    }
+
+    fn description(&self) -> &str {
+        "Set a GPIO pin high (1) or low (0) on a connected peripheral (e.g. turn on/off LED)"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "pin": {
+                    "type": "integer",
+                    "description": "GPIO pin number"
+                },
+                "value": {
+                    "type": "integer",
+                    "description": "0 for low, 1 for high"
+                }
+            },
+            "required": ["pin", "value"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let pin = args
+            .get("pin")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'pin' parameter"))?;
+        let value = args
+            .get("value")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'value' parameter"))?;
+        self.transport
+            .request("gpio_write", json!({ "pin": pin, "value": value }))
+            .await
+    }
+}
+
+/// Tool: read an ADC channel.
+pub(crate) struct AdcReadTool {
+    transport: Arc<DeviceTransport>,
+}
+
+impl AdcReadTool {
+    pub(crate) fn new(transport: Arc<DeviceTransport>) -> Self {
+        Self { transport }
+    }
+}
+
+#[async_trait]
+impl Tool for AdcReadTool {
+    fn name(&self) -> &str {
+        "adc_read"
+    }
+
+    fn description(&self) -> &str {
+        "Read the raw value of an analog (ADC) channel on a connected peripheral"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "channel": {
+                    "type": "integer",
+                    "description": "ADC channel number"
+                }
+            },
+            "required": ["channel"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let channel = args
+            .get("channel")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'channel' parameter"))?;
+        self.transport
+            .request("adc_read", json!({ "channel": channel }))
+            .await
+    }
+}
+
+/// Tool: read a named sensor.
+pub(crate) struct SensorReadTool {
+    transport: Arc<DeviceTransport>,
+}
+
+impl SensorReadTool {
+    pub(crate) fn new(transport: Arc<DeviceTransport>) -> Self {
+        Self { transport }
+    }
+}
+
+#[async_trait]
+impl Tool for SensorReadTool {
+    fn name(&self) -> &str {
+        "sensor_read"
+    }
+
+    fn description(&self) -> &str {
+        "Read a named sensor (e.g. temperature, humidity) on a connected peripheral"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "sensor": {
+                    "type": "string",
+                    "description": "Sensor name as reported by hardware_capabilities"
+                }
+            },
+            "required": ["sensor"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let sensor = args
+            .get("sensor")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'sensor' parameter"))?;
+        self.transport
+            .request("sensor_read", json!({ "sensor": sensor }))
+            .await
+    }
+}
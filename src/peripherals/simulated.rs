@@ -0,0 +1,617 @@
+//! Simulated board — the device protocol without a device.
+//!
+//! Answers the same newline-JSON commands as the serial firmware (`ping`,
+//! `capabilities`, `gpio_read`, `gpio_write`, plus `adc_read`,
+//! `sensor_read` and `memory_read`) from scriptable in-memory state, either
+//! over an in-process pipe or, with the `hardware` feature on Unix, over a
+//! pseudo-terminal so the real serial stack is exercised too. Faults
+//! (timeouts, malformed replies, wrong ids, error replies) can be scripted
+//! in config or injected from tests.
+
+use super::protocol::{
+    AdcReadTool, DeviceStream, DeviceTransport, GpioReadTool, GpioWriteTool, SensorReadTool,
+};
+use super::traits::Peripheral;
+use crate::config::{
+    PeripheralBoardConfig, SimulatedBoardConfig, SimulatedFaultConfig, SimulatedFaultKind,
+};
+use crate::tools::traits::Tool;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// Board type identifier used in `[[peripherals.boards]]`.
+pub const BOARD_TYPE: &str = "simulated";
+
+/// Base address of the simulated RAM served by `memory_read`.
+pub const SIMULATED_RAM_BASE: u64 = 0x2000_0000;
+
+const SIMULATED_RAM_SIZE: usize = 256;
+
+struct BoardState {
+    gpio_pins: Vec<u8>,
+    led_pin: u8,
+    gpio: BTreeMap<u8, u8>,
+    adc: Vec<u16>,
+    sensors: BTreeMap<String, f64>,
+    memory: Vec<u8>,
+    faults: VecDeque<SimulatedFaultConfig>,
+    received: Vec<String>,
+}
+
+impl BoardState {
+    /// Consume the next scripted fault that applies to `cmd`.
+    fn take_fault(&mut self, cmd: &str) -> Option<SimulatedFaultConfig> {
+        let index = self
+            .faults
+            .iter()
+            .position(|f| f.command.as_deref().map_or(true, |c| c == cmd))?;
+        let fault = &mut self.faults[index];
+        let taken = fault.clone();
+        fault.count = fault.count.saturating_sub(1);
+        if fault.count == 0 {
+            self.faults.remove(index);
+        }
+        Some(taken)
+    }
+}
+
+/// Handle to a simulated board's state. Clones share the same board.
+#[derive(Clone)]
+pub struct SimulatedBoard {
+    state: Arc<Mutex<BoardState>>,
+    latency: Duration,
+    timeout: Duration,
+}
+
+impl SimulatedBoard {
+    pub fn new(config: &SimulatedBoardConfig) -> Self {
+        let gpio = config
+            .gpio_pins
+            .iter()
+            .map(|&pin| (pin, u8::from(config.gpio_high.contains(&pin))))
+            .collect();
+        Self {
+            state: Arc::new(Mutex::new(BoardState {
+                gpio_pins: config.gpio_pins.clone(),
+                led_pin: config.led_pin,
+                gpio,
+                adc: config.adc.clone(),
+                sensors: config.sensors.clone(),
+                memory: vec![0; SIMULATED_RAM_SIZE],
+                faults: config
+                    .faults
+                    .iter()
+                    .filter(|f| f.count > 0)
+                    .cloned()
+                    .collect(),
+                received: Vec::new(),
+            })),
+            latency: Duration::from_millis(config.latency_ms),
+            timeout: Duration::from_millis(config.timeout_ms),
+        }
+    }
+
+    /// Current level of `pin`, or `None` if the board has no such pin.
+    pub fn gpio(&self, pin: u8) -> Option<u8> {
+        self.state.lock().gpio.get(&pin).copied()
+    }
+
+    /// Drive an input pin (adds the pin if the board did not report it).
+    pub fn set_gpio(&self, pin: u8, value: u8) {
+        let mut state = self.state.lock();
+        if !state.gpio_pins.contains(&pin) {
+            state.gpio_pins.push(pin);
+        }
+        state.gpio.insert(pin, u8::from(value != 0));
+    }
+
+    /// Set the raw reading of an ADC channel.
+    pub fn set_adc(&self, channel: usize, value: u16) {
+        let mut state = self.state.lock();
+        if state.adc.len() <= channel {
+            state.adc.resize(channel + 1, 0);
+        }
+        state.adc[channel] = value;
+    }
+
+    /// Set a named sensor reading.
+    pub fn set_sensor(&self, name: &str, value: f64) {
+        self.state.lock().sensors.insert(name.to_string(), value);
+    }
+
+    /// Write bytes into simulated RAM at `offset` from [`SIMULATED_RAM_BASE`].
+    pub fn write_memory(&self, offset: usize, bytes: &[u8]) {
+        let mut state = self.state.lock();
+        let end = (offset + bytes.len()).min(state.memory.len());
+        if offset < end {
+            state.memory[offset..end].copy_from_slice(&bytes[..end - offset]);
+        }
+    }
+
+    /// Queue a fault behind any already scripted.
+    pub fn inject_fault(&self, fault: SimulatedFaultConfig) {
+        if fault.count > 0 {
+            self.state.lock().faults.push_back(fault);
+        }
+    }
+
+    /// Commands received so far, in order.
+    pub fn received_commands(&self) -> Vec<String> {
+        self.state.lock().received.clone()
+    }
+
+    /// Reply line for one request line, or `None` when the board stays silent.
+    fn reply(&self, line: &str) -> Option<String> {
+        let Ok(request) = serde_json::from_str::<Value>(line) else {
+            return Some(error_reply("0", "Invalid JSON"));
+        };
+        let id = request["id"].as_str().unwrap_or("0");
+        let cmd = request["cmd"].as_str().unwrap_or_default();
+        let args = &request["args"];
+
+        let mut state = self.state.lock();
+        state.received.push(cmd.to_string());
+        if let Some(fault) = state.take_fault(cmd) {
+            return match fault.kind {
+                SimulatedFaultKind::Timeout => None,
+                SimulatedFaultKind::Malformed => Some(format!("{{\"id\":\"{id}\",\"ok\":tru")),
+                SimulatedFaultKind::WrongId => Some(ok_reply(&format!("{id}-stale"), "done")),
+                SimulatedFaultKind::Error => Some(error_reply(
+                    id,
+                    fault.message.as_deref().unwrap_or("Injected fault"),
+                )),
+            };
+        }
+
+        let reply = match cmd {
+            "ping" => ok_reply(id, "pong"),
+            "capabilities" => {
+                let caps = json!({
+                    "gpio": state.gpio_pins,
+                    "led_pin": state.led_pin,
+                    "adc": (0..state.adc.len()).collect::<Vec<_>>(),
+                    "sensors": state.sensors.keys().collect::<Vec<_>>(),
+                });
+                ok_reply(id, &caps.to_string())
+            }
+            "gpio_read" => match pin_arg(args).and_then(|pin| state.gpio.get(&pin)) {
+                Some(value) => ok_reply(id, &value.to_string()),
+                None => error_reply(id, &format!("Invalid pin {}", args["pin"])),
+            },
+            "gpio_write" => match pin_arg(args).filter(|pin| state.gpio.contains_key(pin)) {
+                Some(pin) => {
+                    let value = u8::from(args["value"].as_u64().unwrap_or(0) != 0);
+                    state.gpio.insert(pin, value);
+                    ok_reply(id, "done")
+                }
+                None => error_reply(id, &format!("Invalid pin {}", args["pin"])),
+            },
+            "adc_read" => match args["channel"]
+                .as_u64()
+                .and_then(|ch| state.adc.get(usize::try_from(ch).ok()?))
+            {
+                Some(value) => ok_reply(id, &value.to_string()),
+                None => error_reply(id, &format!("Invalid channel {}", args["channel"])),
+            },
+            "sensor_read" => {
+                let name = args["sensor"].as_str().unwrap_or_default();
+                match state.sensors.get(name) {
+                    Some(value) => ok_reply(id, &value.to_string()),
+                    None => error_reply(id, &format!("Unknown sensor {name}")),
+                }
+            }
+            "memory_read" => match memory_range(args, state.memory.len()) {
+                Some(range) => {
+                    let mut hex = String::with_capacity(range.len() * 2);
+                    for byte in &state.memory[range] {
+                        let _ = write!(hex, "{byte:02x}");
+                    }
+                    ok_reply(id, &hex)
+                }
+                None => error_reply(id, "Address out of range"),
+            },
+            _ => error_reply(id, "Unknown command"),
+        };
+        Some(reply)
+    }
+
+    /// Answer requests arriving on `stream` until it closes.
+    pub(crate) fn serve(&self, stream: impl DeviceStream + 'static) -> tokio::task::JoinHandle<()> {
+        let board = self.clone();
+        tokio::spawn(async move {
+            let (reader, mut writer) = tokio::io::split(stream);
+            let mut lines = BufReader::new(reader).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                if !board.latency.is_zero() {
+                    tokio::time::sleep(board.latency).await;
+                }
+                let Some(reply) = board.reply(line) else {
+                    continue;
+                };
+                if writer
+                    .write_all(format!("{reply}\n").as_bytes())
+                    .await
+                    .is_err()
+                    || writer.flush().await.is_err()
+                {
+                    break;
+                }
+            }
+        })
+    }
+
+    /// Connect a host transport to this board over an in-process pipe.
+    pub(crate) fn connect_in_process(&self) -> DeviceTransport {
+        let (host, device) = tokio::io::duplex(4096);
+        self.serve(device);
+        DeviceTransport::new(host).with_timeout(self.timeout)
+    }
+
+    /// Serve this board on a pseudo-terminal and open its other end through
+    /// the regular serial stack.
+    #[cfg(all(feature = "hardware", unix))]
+    pub(crate) fn connect_pty(&self, baud: u32) -> anyhow::Result<(DeviceTransport, String)> {
+        let (master, slave, path) = pty::open()?;
+        let transport = super::serial::open_transport(&path, baud)?.with_timeout(self.timeout);
+        let board = self.clone();
+        tokio::spawn(async move {
+            // Hold the device side open for as long as the board is served.
+            let _slave = slave;
+            let _ = board.serve(tokio::fs::File::from_std(master)).await;
+        });
+        Ok((transport, path))
+    }
+}
+
+fn ok_reply(id: &str, result: &str) -> String {
+    json!({ "id": id, "ok": true, "result": result }).to_string()
+}
+
+fn error_reply(id: &str, error: &str) -> String {
+    json!({ "id": id, "ok": false, "result": "", "error": error }).to_string()
+}
+
+fn pin_arg(args: &Value) -> Option<u8> {
+    args["pin"].as_u64().and_then(|pin| u8::try_from(pin).ok())
+}
+
+/// Byte range of simulated RAM addressed by `memory_read` arguments.
+fn memory_range(args: &Value, size: usize) -> Option<std::ops::Range<usize>> {
+    let address = args["address"].as_u64().unwrap_or(SIMULATED_RAM_BASE);
+    let offset = usize::try_from(address.checked_sub(SIMULATED_RAM_BASE)?).ok()?;
+    let length = usize::try_from(args["length"].as_u64().unwrap_or(128)).ok()?;
+    let end = offset.checked_add(length)?;
+    (length > 0 && end <= size).then_some(offset..end)
+}
+
+#[cfg(all(feature = "hardware", unix))]
+mod pty {
+    use std::ffi::CStr;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+    /// Open a raw-mode pseudo-terminal: (master, slave, slave path).
+    pub(super) fn open() -> anyhow::Result<(std::fs::File, OwnedFd, String)> {
+        let mut master = -1;
+        let mut slave = -1;
+        // SAFETY: openpty only writes the two out-params; null name, termios
+        // and winsize pointers are documented as allowed.
+        let rc = unsafe {
+            libc::openpty(
+                &raw mut master,
+                &raw mut slave,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        if rc != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        // SAFETY: both descriptors were just returned by openpty and are owned here.
+        let (master, slave) = unsafe {
+            (
+                std::fs::File::from_raw_fd(master),
+                OwnedFd::from_raw_fd(slave),
+            )
+        };
+
+        let mut name = [0 as libc::c_char; 128];
+        // SAFETY: `name` is a writable buffer of the given length and `slave`
+        // is a valid terminal descriptor; termios is plain data.
+        let path = unsafe {
+            if libc::ttyname_r(slave.as_raw_fd(), name.as_mut_ptr(), name.len()) != 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+            let mut termios: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(slave.as_raw_fd(), &raw mut termios) == 0 {
+                libc::cfmakeraw(&raw mut termios);
+                libc::tcsetattr(slave.as_raw_fd(), libc::TCSANOW, &raw const termios);
+            }
+            CStr::from_ptr(name.as_ptr()).to_string_lossy().into_owned()
+        };
+        Ok((master, slave, path))
+    }
+}
+
+/// Simulated board exposed as a peripheral.
+pub struct SimulatedPeripheral {
+    name: String,
+    board: SimulatedBoard,
+    transport: Arc<DeviceTransport>,
+}
+
+impl SimulatedPeripheral {
+    /// Start a simulated board from config and connect to it.
+    #[allow(clippy::unused_async)]
+    pub async fn connect_from_config(config: &PeripheralBoardConfig) -> anyhow::Result<Self> {
+        let board = SimulatedBoard::new(&config.simulation.clone().unwrap_or_default());
+        let (transport, name) = if config.transport == "pty" {
+            #[cfg(all(feature = "hardware", unix))]
+            {
+                let (transport, path) = board.connect_pty(config.baud)?;
+                tracing::info!(path = %path, "Simulated board serving on pseudo-terminal");
+                (
+                    transport,
+                    format!("{BOARD_TYPE}-{}", path.replace('/', "_")),
+                )
+            }
+            #[cfg(not(all(feature = "hardware", unix)))]
+            anyhow::bail!("Simulated board over pty requires the 'hardware' feature on Unix");
+        } else {
+            (
+                board.connect_in_process(),
+                format!("{BOARD_TYPE}-in-process"),
+            )
+        };
+        Ok(Self {
+            name,
+            board,
+            transport: Arc::new(transport),
+        })
+    }
+
+    /// Handle for scripting the board's state.
+    pub fn board(&self) -> &SimulatedBoard {
+        &self.board
+    }
+
+    /// Expose transport for capabilities and memory read tools.
+    pub(crate) fn transport(&self) -> Arc<DeviceTransport> {
+        self.transport.clone()
+    }
+}
+
+#[async_trait]
+impl Peripheral for SimulatedPeripheral {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn board_type(&self) -> &str {
+        BOARD_TYPE
+    }
+
+    async fn connect(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn health_check(&self) -> bool {
+        self.transport.ping().await
+    }
+
+    fn tools(&self) -> Vec<Box<dyn Tool>> {
+        vec![
+            Box::new(GpioReadTool::new(self.transport.clone())),
+            Box::new(GpioWriteTool::new(self.transport.clone())),
+            Box::new(AdcReadTool::new(self.transport.clone())),
+            Box::new(SensorReadTool::new(self.transport.clone())),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fault(command: Option<&str>, kind: SimulatedFaultKind) -> SimulatedFaultConfig {
+        SimulatedFaultConfig {
+            command: command.map(String::from),
+            kind,
+            count: 1,
+            message: None,
+        }
+    }
+
+    fn board_with(config: SimulatedBoardConfig) -> (SimulatedBoard, DeviceTransport) {
+        let board = SimulatedBoard::new(&config);
+        let transport = board.connect_in_process();
+        (board, transport)
+    }
+
+    #[tokio::test]
+    async fn gpio_write_then_read_round_trips() {
+        let (board, transport) = board_with(SimulatedBoardConfig {
+            gpio_high: vec![2],
+            ..SimulatedBoardConfig::default()
+        });
+
+        let read = transport
+            .request("gpio_read", json!({"pin": 2}))
+            .await
+            .unwrap();
+        assert!(read.success);
+        assert_eq!(read.output, "1");
+
+        let write = transport
+            .request("gpio_write", json!({"pin": 13, "value": 1}))
+            .await
+            .unwrap();
+        assert_eq!(write.output, "done");
+        assert_eq!(board.gpio(13), Some(1));
+
+        let invalid = transport
+            .request("gpio_read", json!({"pin": 40}))
+            .await
+            .unwrap();
+        assert!(!invalid.success);
+        assert_eq!(invalid.error.as_deref(), Some("Invalid pin 40"));
+        assert_eq!(
+            board.received_commands(),
+            ["gpio_read", "gpio_write", "gpio_read"]
+        );
+    }
+
+    #[tokio::test]
+    async fn scripted_adc_sensor_and_capabilities() {
+        let (board, transport) = board_with(SimulatedBoardConfig {
+            adc: vec![512],
+            sensors: BTreeMap::from([("temperature".to_string(), 21.5)]),
+            ..SimulatedBoardConfig::default()
+        });
+        board.set_adc(1, 1023);
+
+        let adc = transport
+            .request("adc_read", json!({"channel": 1}))
+            .await
+            .unwrap();
+        assert_eq!(adc.output, "1023");
+        let temp = transport
+            .request("sensor_read", json!({"sensor": "temperature"}))
+            .await
+            .unwrap();
+        assert_eq!(temp.output, "21.5");
+
+        let caps = transport.capabilities().await.unwrap();
+        let caps: Value = serde_json::from_str(&caps.output).unwrap();
+        assert_eq!(caps["led_pin"], 13);
+        assert_eq!(caps["adc"], json!([0, 1]));
+        assert_eq!(caps["sensors"], json!(["temperature"]));
+    }
+
+    #[tokio::test]
+    async fn memory_read_returns_hex_bytes() {
+        let (board, transport) = board_with(SimulatedBoardConfig::default());
+        board.write_memory(4, &[0xde, 0xad]);
+        let read = transport
+            .request(
+                "memory_read",
+                json!({"address": SIMULATED_RAM_BASE + 4, "length": 3}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(read.output, "dead00");
+
+        let out_of_range = transport
+            .request("memory_read", json!({"address": 0x10, "length": 4}))
+            .await
+            .unwrap();
+        assert!(!out_of_range.success);
+    }
+
+    #[tokio::test]
+    async fn injected_faults_surface_as_transport_errors() {
+        let (board, transport) = board_with(SimulatedBoardConfig {
+            timeout_ms: 50,
+            faults: vec![fault(Some("gpio_read"), SimulatedFaultKind::Timeout)],
+            ..SimulatedBoardConfig::default()
+        });
+
+        // Faults scoped to another command leave this one alone.
+        assert!(transport.ping().await);
+        let err = transport
+            .request("gpio_read", json!({"pin": 1}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{err}");
+
+        board.inject_fault(fault(None, SimulatedFaultKind::Malformed));
+        assert!(transport.request("ping", json!({})).await.is_err());
+
+        board.inject_fault(fault(None, SimulatedFaultKind::WrongId));
+        let err = transport.request("ping", json!({})).await.unwrap_err();
+        assert!(err.to_string().contains("id mismatch"), "{err}");
+
+        board.inject_fault(SimulatedFaultConfig {
+            message: Some("brownout".into()),
+            ..fault(None, SimulatedFaultKind::Error)
+        });
+        let reply = transport.request("ping", json!({})).await.unwrap();
+        assert_eq!(reply.error.as_deref(), Some("brownout"));
+
+        // Every fault was consumed once; the board is healthy again.
+        assert!(transport.ping().await);
+    }
+
+    #[tokio::test]
+    async fn peripheral_from_config_exposes_protocol_tools() {
+        let config = PeripheralBoardConfig {
+            board: BOARD_TYPE.into(),
+            transport: "in-process".into(),
+            ..PeripheralBoardConfig::default()
+        };
+        let peripheral = SimulatedPeripheral::connect_from_config(&config)
+            .await
+            .unwrap();
+        assert!(peripheral.health_check().await);
+        let names: Vec<String> = peripheral
+            .tools()
+            .iter()
+            .map(|t| t.name().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            ["gpio_read", "gpio_write", "adc_read", "sensor_read"]
+        );
+
+        let write = &peripheral.tools()[1];
+        let result = write.execute(json!({"pin": 5, "value": 1})).await.unwrap();
+        assert!(result.success);
+        assert_eq!(peripheral.board().gpio(5), Some(1));
+    }
+
+    #[tokio::test]
+    async fn hardware_memory_read_uses_board_transport() {
+        use crate::tools::HardwareMemoryReadTool;
+
+        let board = SimulatedBoard::new(&SimulatedBoardConfig::default());
+        board.write_memory(0, b"OK");
+        let tool = HardwareMemoryReadTool::new(vec![BOARD_TYPE.into()]).with_transports(vec![(
+            BOARD_TYPE.into(),
+            Arc::new(board.connect_in_process()),
+        )]);
+        let result = tool
+            .execute(json!({"address": "0x20000000", "length": 4}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("0x20000000  4F 4B 00 00"));
+    }
+
+    #[cfg(all(feature = "hardware", unix))]
+    #[tokio::test]
+    async fn serves_protocol_over_pty() {
+        let board = SimulatedBoard::new(&SimulatedBoardConfig::default());
+        let (transport, path) = board.connect_pty(115_200).unwrap();
+        assert!(path.starts_with("/dev/"));
+        let write = transport
+            .request("gpio_write", json!({"pin": 3, "value": 1}))
+            .await
+            .unwrap();
+        assert!(write.success);
+        assert_eq!(board.gpio(3), Some(1));
+    }
+}
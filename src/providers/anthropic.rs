@@ -1,15 +1,27 @@
+use crate::providers::reasoning::ReasoningLevel;
+use crate::providers::structured;
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, ToolCall as ProviderToolCall,
+    ChatUsage, Provider, ReasoningBlock, ResponseFormat, ToolCall as ProviderToolCall,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
+const STRUCTURED_OUTPUT_TOOL_DESCRIPTION: &str =
+    "Return the final answer. The input must match the required response schema.";
+
+/// Output token cap for the visible reply; thinking budgets are added on top.
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+/// Smallest `budget_tokens` the Messages API accepts.
+const MIN_THINKING_BUDGET_TOKENS: u32 = 1024;
+
 pub struct AnthropicProvider {
     credential: Option<String>,
     base_url: String,
+    reasoning: Option<ReasoningLevel>,
 }
 
 #[derive(Debug, Serialize)]
@@ -20,6 +32,16 @@ struct ChatRequest {
     system: Option<String>,
     messages: Vec<Message>,
     temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<ThinkingConfig>,
+}
+
+/// Extended thinking request block.
+#[derive(Debug, Clone, Serialize)]
+struct ThinkingConfig {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    budget_tokens: u32,
 }
 
 #[derive(Debug, Serialize)]
@@ -51,6 +73,10 @@ struct NativeChatRequest<'a> {
     temperature: f64,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<NativeToolSpec<'a>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<ThinkingConfig>,
 }
 
 #[derive(Debug, Serialize)]
@@ -62,6 +88,10 @@ struct NativeMessage {
 #[derive(Debug, Serialize)]
 #[serde(tag = "type")]
 enum NativeContentOut {
+    #[serde(rename = "thinking")]
+    Thinking { thinking: String, signature: String },
+    #[serde(rename = "redacted_thinking")]
+    RedactedThinking { data: String },
     #[serde(rename = "text")]
     Text {
         text: String,
@@ -128,6 +158,16 @@ struct SystemBlock {
 struct NativeChatResponse {
     #[serde(default)]
     content: Vec<NativeContentIn>,
+    #[serde(default)]
+    usage: Option<NativeUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -142,6 +182,12 @@ struct NativeContentIn {
     name: Option<String>,
     #[serde(default)]
     input: Option<serde_json::Value>,
+    #[serde(default)]
+    thinking: Option<String>,
+    #[serde(default)]
+    signature: Option<String>,
+    #[serde(default)]
+    data: Option<String>,
 }
 
 impl AnthropicProvider {
@@ -160,6 +206,39 @@ impl AnthropicProvider {
                 .filter(|k| !k.is_empty())
                 .map(ToString::to_string),
             base_url,
+            reasoning: None,
+        }
+    }
+
+    /// Default reasoning level, mapped onto extended thinking.
+    pub fn with_reasoning(mut self, reasoning: Option<ReasoningLevel>) -> Self {
+        self.reasoning = reasoning;
+        self
+    }
+
+    fn thinking_config(reasoning: Option<ReasoningLevel>) -> Option<ThinkingConfig> {
+        reasoning
+            .filter(|level| level.is_enabled())
+            .map(|level| ThinkingConfig {
+                kind: "enabled",
+                budget_tokens: level.budget_tokens().max(MIN_THINKING_BUDGET_TOKENS),
+            })
+    }
+
+    /// `max_tokens` has to exceed the thinking budget, so the budget is added
+    /// to the usual reply allowance.
+    fn max_tokens_for(thinking: Option<&ThinkingConfig>) -> u32 {
+        thinking.map_or(DEFAULT_MAX_TOKENS, |config| {
+            config.budget_tokens.saturating_add(DEFAULT_MAX_TOKENS)
+        })
+    }
+
+    /// Extended thinking only accepts the default temperature of 1.
+    fn temperature_for(thinking: Option<&ThinkingConfig>, temperature: f64) -> f64 {
+        if thinking.is_some() {
+            1.0
+        } else {
+            temperature
         }
     }
 
@@ -200,7 +279,9 @@ impl AnthropicProvider {
                     | NativeContentOut::ToolResult { cache_control, .. } => {
                         *cache_control = Some(CacheControl::ephemeral());
                     }
-                    NativeContentOut::ToolUse { .. } => {}
+                    NativeContentOut::ToolUse { .. }
+                    | NativeContentOut::Thinking { .. }
+                    | NativeContentOut::RedactedThinking { .. } => {}
                 }
             }
         }
@@ -229,13 +310,53 @@ impl AnthropicProvider {
         Some(native_tools)
     }
 
+    /// The response format to enforce through a forced tool call, if any.
+    ///
+    /// Anthropic tool inputs must be objects, and forcing a tool would keep the
+    /// model from calling the caller's own tools, so other cases fall back to
+    /// prompt-guided output. Extended thinking rejects forced tool choice too.
+    fn forced_output_format<'a>(
+        request: &ProviderChatRequest<'a>,
+        thinking: bool,
+    ) -> Option<&'a ResponseFormat> {
+        if thinking {
+            return None;
+        }
+        let format = request.response_format?;
+        let has_tools = request.tools.is_some_and(|tools| !tools.is_empty());
+        let object_schema = format
+            .schema
+            .get("type")
+            .and_then(serde_json::Value::as_str)
+            == Some("object");
+        (!has_tools && object_schema).then_some(format)
+    }
+
     fn parse_assistant_tool_call_message(content: &str) -> Option<Vec<NativeContentOut>> {
         let value = serde_json::from_str::<serde_json::Value>(content).ok()?;
         let tool_calls = value
             .get("tool_calls")
             .and_then(|v| serde_json::from_value::<Vec<ProviderToolCall>>(v.clone()).ok())?;
 
-        let mut blocks = Vec::new();
+        // Signed thinking blocks go first; the API rejects tool results whose
+        // preceding tool_use turn lost its thinking.
+        let mut blocks: Vec<NativeContentOut> = value
+            .get("reasoning")
+            .and_then(|v| serde_json::from_value::<Vec<ReasoningBlock>>(v.clone()).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|block| {
+                let signature = block.signature?;
+                Some(if block.redacted {
+                    NativeContentOut::RedactedThinking { data: signature }
+                } else {
+                    NativeContentOut::Thinking {
+                        thinking: block.text,
+                        signature,
+                    }
+                })
+            })
+            .collect();
         if let Some(text) = value
             .get("content")
             .and_then(serde_json::Value::as_str)
@@ -361,6 +482,7 @@ impl AnthropicProvider {
     fn parse_native_response(response: NativeChatResponse) -> ProviderChatResponse {
         let mut text_parts = Vec::new();
         let mut tool_calls = Vec::new();
+        let mut reasoning = Vec::new();
 
         for block in response.content {
             match block.kind.as_str() {
@@ -385,6 +507,16 @@ impl AnthropicProvider {
                         arguments: arguments.to_string(),
                     });
                 }
+                "thinking" => reasoning.push(ReasoningBlock {
+                    text: block.thinking.unwrap_or_default(),
+                    signature: block.signature,
+                    redacted: false,
+                }),
+                "redacted_thinking" => reasoning.push(ReasoningBlock {
+                    text: String::new(),
+                    signature: block.data,
+                    redacted: true,
+                }),
                 _ => {}
             }
         }
@@ -396,6 +528,11 @@ impl AnthropicProvider {
                 Some(text_parts.join("\n"))
             },
             tool_calls,
+            reasoning,
+            usage: response.usage.map(|u| ChatUsage {
+                input_tokens: u.input_tokens,
+                output_tokens: u.output_tokens,
+            }),
         }
     }
 
@@ -419,15 +556,17 @@ impl Provider for AnthropicProvider {
             )
         })?;
 
+        let thinking = Self::thinking_config(self.reasoning);
         let request = ChatRequest {
             model: model.to_string(),
-            max_tokens: 4096,
+            max_tokens: Self::max_tokens_for(thinking.as_ref()),
             system: system_prompt.map(ToString::to_string),
             messages: vec![Message {
                 role: "user".to_string(),
                 content: message.to_string(),
             }],
-            temperature,
+            temperature: Self::temperature_for(thinking.as_ref(), temperature),
+            thinking,
         };
 
         let mut request = self
@@ -461,20 +600,45 @@ impl Provider for AnthropicProvider {
             )
         })?;
 
-        let (system_prompt, mut messages) = Self::convert_messages(request.messages);
+        // Structured output is requested by forcing a single tool whose input
+        // schema is the response schema; the tool input becomes the reply.
+        let thinking = Self::thinking_config(request.reasoning.or(self.reasoning));
+        let forced_output = Self::forced_output_format(&request, thinking.is_some());
+        let output_tool_name = forced_output.map(ResponseFormat::api_name);
+        let guided_messages = structured::prompt_guided_messages(
+            request.messages,
+            request.response_format.filter(|_| forced_output.is_none()),
+        );
+
+        let (system_prompt, mut messages) = Self::convert_messages(&guided_messages);
 
         // Auto-cache last message if conversation is long
         if Self::should_cache_conversation(request.messages) {
             Self::apply_cache_to_last_message(&mut messages);
         }
 
+        let (tools, tool_choice) = match (forced_output, output_tool_name.as_deref()) {
+            (Some(format), Some(name)) => (
+                Some(vec![NativeToolSpec {
+                    name,
+                    description: STRUCTURED_OUTPUT_TOOL_DESCRIPTION,
+                    input_schema: &format.schema,
+                    cache_control: None,
+                }]),
+                Some(serde_json::json!({"type": "tool", "name": name})),
+            ),
+            _ => (Self::convert_tools(request.tools), None),
+        };
+
         let native_request = NativeChatRequest {
             model: model.to_string(),
-            max_tokens: 4096,
+            max_tokens: Self::max_tokens_for(thinking.as_ref()),
             system: system_prompt,
             messages,
-            temperature,
-            tools: Self::convert_tools(request.tools),
+            temperature: Self::temperature_for(thinking.as_ref(), temperature),
+            tools,
+            tool_choice,
+            thinking,
         };
 
         let req = self
@@ -490,7 +654,13 @@ impl Provider for AnthropicProvider {
         }
 
         let native_response: NativeChatResponse = response.json().await?;
-        Ok(Self::parse_native_response(native_response))
+        let mut parsed = Self::parse_native_response(native_response);
+        if let Some(name) = output_tool_name {
+            if let Some(index) = parsed.tool_calls.iter().position(|call| call.name == name) {
+                parsed.text = Some(parsed.tool_calls.remove(index).arguments);
+            }
+        }
+        Ok(parsed)
     }
 
     fn supports_native_tools(&self) -> bool {
@@ -540,6 +710,8 @@ impl Provider for AnthropicProvider {
             } else {
                 Some(&tool_specs)
             },
+            response_format: None,
+            reasoning: None,
         };
         self.chat(request, model, temperature).await
     }
@@ -708,6 +880,7 @@ mod tests {
                 content: "hello".to_string(),
             }],
             temperature: 0.7,
+            thinking: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(
@@ -729,6 +902,7 @@ mod tests {
                 content: "hello".to_string(),
             }],
             temperature: 0.7,
+            thinking: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains("\"system\":\"You are ZeroClaw\""));
@@ -769,6 +943,7 @@ mod tests {
                 system: None,
                 messages: vec![],
                 temperature: temp,
+                thinking: None,
             };
             let json = serde_json::to_string(&req).unwrap();
             assert!(json.contains(&format!("{temp}")));
@@ -1139,6 +1314,8 @@ mod tests {
             }],
             temperature: 0.7,
             tools: None,
+            tool_choice: None,
+            thinking: None,
         };
 
         let json = serde_json::to_string(&req).unwrap();
@@ -1228,6 +1405,7 @@ mod tests {
         let provider = AnthropicProvider {
             credential: Some("test-key".to_string()),
             base_url: format!("http://{addr}"),
+            reasoning: None,
         };
 
         // Multi-turn conversation: system → user (Go code) → assistant (code response) → user (follow-up)
@@ -1315,4 +1493,168 @@ mod tests {
 
         server_handle.abort();
     }
+
+    #[tokio::test]
+    async fn chat_forces_output_tool_for_response_format() {
+        use axum::{routing::post, Json, Router};
+        use std::sync::{Arc, Mutex};
+        use tokio::net::TcpListener;
+
+        let captured: Arc<Mutex<Option<serde_json::Value>>> = Arc::new(Mutex::new(None));
+        let captured_clone = captured.clone();
+        let app = Router::new().route(
+            "/v1/messages",
+            post(move |Json(body): Json<serde_json::Value>| {
+                let cap = captured_clone.clone();
+                async move {
+                    *cap.lock().unwrap() = Some(body);
+                    Json(serde_json::json!({
+                        "content": [{
+                            "type": "tool_use",
+                            "id": "toolu_1",
+                            "name": "verdict",
+                            "input": {"spam": false}
+                        }]
+                    }))
+                }
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_handle = tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let provider =
+            AnthropicProvider::with_base_url(Some("test-key"), Some(&format!("http://{addr}")));
+        let format = ResponseFormat::json_schema(
+            "verdict",
+            serde_json::json!({
+                "type": "object",
+                "properties": {"spam": {"type": "boolean"}},
+                "required": ["spam"]
+            }),
+        );
+        let messages = vec![ChatMessage::user("Is this spam?")];
+        let response = provider
+            .chat(
+                ProviderChatRequest {
+                    messages: &messages,
+                    tools: None,
+                    response_format: Some(&format),
+                    reasoning: None,
+                },
+                "claude-sonnet-4-5",
+                0.0,
+            )
+            .await
+            .unwrap();
+
+        assert!(response.tool_calls.is_empty());
+        assert_eq!(response.text.as_deref(), Some(r#"{"spam":false}"#));
+
+        let body = captured.lock().unwrap().take().unwrap();
+        assert_eq!(body["tool_choice"]["type"], "tool");
+        assert_eq!(body["tool_choice"]["name"], "verdict");
+        assert_eq!(body["tools"][0]["input_schema"]["required"][0], "spam");
+        server_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn chat_requests_extended_thinking_and_keeps_signed_blocks() {
+        use axum::{routing::post, Json, Router};
+        use std::sync::{Arc, Mutex};
+        use tokio::net::TcpListener;
+
+        let captured: Arc<Mutex<Option<serde_json::Value>>> = Arc::new(Mutex::new(None));
+        let captured_clone = captured.clone();
+        let app = Router::new().route(
+            "/v1/messages",
+            post(move |Json(body): Json<serde_json::Value>| {
+                let cap = captured_clone.clone();
+                async move {
+                    *cap.lock().unwrap() = Some(body);
+                    Json(serde_json::json!({
+                        "content": [
+                            {"type": "thinking", "thinking": "Check the disk first.", "signature": "sig-1"},
+                            {"type": "redacted_thinking", "data": "opaque"},
+                            {"type": "tool_use", "id": "toolu_1", "name": "shell", "input": {"command": "df"}}
+                        ]
+                    }))
+                }
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_handle = tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let provider =
+            AnthropicProvider::with_base_url(Some("test-key"), Some(&format!("http://{addr}")))
+                .with_reasoning(Some(ReasoningLevel::OFF));
+        let messages = vec![ChatMessage::user("How full is the disk?")];
+        let response = provider
+            .chat(
+                ProviderChatRequest {
+                    messages: &messages,
+                    tools: None,
+                    response_format: None,
+                    reasoning: Some(ReasoningLevel::Budget(2000)),
+                },
+                "claude-sonnet-4-5",
+                0.2,
+            )
+            .await
+            .unwrap();
+
+        let body = captured.lock().unwrap().take().unwrap();
+        assert_eq!(body["thinking"]["type"], "enabled");
+        assert_eq!(body["thinking"]["budget_tokens"], 2000);
+        assert_eq!(body["max_tokens"], 2000 + DEFAULT_MAX_TOKENS);
+        assert_eq!(body["temperature"], 1.0);
+
+        assert_eq!(response.tool_calls.len(), 1);
+        assert_eq!(response.reasoning.len(), 2);
+        assert_eq!(response.reasoning[0].signature.as_deref(), Some("sig-1"));
+        assert!(response.reasoning[1].redacted);
+        assert_eq!(
+            response.reasoning_text().as_deref(),
+            Some("Check the disk first.")
+        );
+        server_handle.abort();
+    }
+
+    #[test]
+    fn convert_messages_replays_signed_thinking_before_tool_use() {
+        let assistant = serde_json::json!({
+            "content": "",
+            "tool_calls": [{"id": "toolu_1", "name": "shell", "arguments": "{}"}],
+            "reasoning": [
+                {"text": "Check the disk first.", "signature": "sig-1"},
+                {"text": "unsigned thoughts are dropped"},
+                {"signature": "opaque", "redacted": true}
+            ]
+        });
+        let messages = vec![
+            ChatMessage::user("How full is the disk?"),
+            ChatMessage::assistant(assistant.to_string()),
+        ];
+
+        let (_, native) = AnthropicProvider::convert_messages(&messages);
+        let json = serde_json::to_value(&native[1].content).unwrap();
+        assert_eq!(json[0]["type"], "thinking");
+        assert_eq!(json[0]["signature"], "sig-1");
+        assert_eq!(json[1]["type"], "redacted_thinking");
+        assert_eq!(json[1]["data"], "opaque");
+        assert_eq!(json[2]["type"], "tool_use");
+    }
+
+    #[test]
+    fn thinking_is_omitted_when_reasoning_is_off() {
+        assert!(AnthropicProvider::thinking_config(None).is_none());
+        assert!(AnthropicProvider::thinking_config(Some(ReasoningLevel::OFF)).is_none());
+        let low = AnthropicProvider::thinking_config(Some(ReasoningLevel::Budget(10))).unwrap();
+        assert_eq!(low.budget_tokens, MIN_THINKING_BUDGET_TOKENS);
+    }
 }
@@ -4,6 +4,7 @@
 //! via environment variables. SigV4 signing is implemented manually
 //! using hmac/sha2 crates — no AWS SDK dependency.
 
+use crate::providers::structured;
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
     Provider, ProviderCapabilities, ToolCall as ProviderToolCall, ToolsPayload,
@@ -668,6 +669,8 @@ impl BedrockProvider {
                 Some(text_parts.join("\n"))
             },
             tool_calls,
+            reasoning: Vec::new(),
+            usage: None,
         }
     }
 
@@ -841,7 +844,10 @@ impl Provider for BedrockProvider {
     ) -> anyhow::Result<ProviderChatResponse> {
         let credentials = self.require_credentials()?;
 
-        let (system_blocks, mut converse_messages) = Self::convert_messages(request.messages);
+        // No native structured-output mode here; describe the schema in the prompt.
+        let messages =
+            structured::prompt_guided_messages(request.messages, request.response_format);
+        let (system_blocks, mut converse_messages) = Self::convert_messages(&messages);
 
         // Apply cachePoint to system if large.
         let system = system_blocks.map(|mut blocks| {
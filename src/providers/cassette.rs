@@ -0,0 +1,910 @@
+//! Record/replay provider wrappers for deterministic, offline tests.
+//!
+//! With `[cassette] mode = "record"` the configured provider is wrapped in a
+//! [`RecordingProvider`], which forwards every call and appends the exchange to
+//! a JSON-lines cassette file. With `mode = "replay"` a [`ReplayProvider`]
+//! answers from that file and no real provider is constructed at all, so
+//! tool-calling conversations run without credentials or network.
+//!
+//! Exchanges are looked up by a hash of the normalized request: call kind,
+//! model, non-system messages and tool names. System prompts are left out of
+//! the key because they embed the current time, host name and workspace path;
+//! timestamps and UUIDs inside the remaining messages are masked for the same
+//! reason. Identical requests recorded more than once are served in recording
+//! order. In strict mode an unmatched request is an error; otherwise the next
+//! unused exchange of the same kind is served.
+
+use super::traits::{
+    ChatMessage, ChatRequest, ChatResponse, Provider, ProviderCapabilities, StreamChunk,
+    StreamError, StreamOptions, StreamResult, ToolsPayload,
+};
+use crate::config::{CassetteConfig, CassetteMode};
+use crate::tools::ToolSpec;
+use anyhow::Context;
+use async_trait::async_trait;
+use futures_util::{stream, StreamExt};
+use parking_lot::Mutex;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+
+/// Substrings that change between otherwise identical runs.
+static VOLATILE_PATTERNS: LazyLock<Vec<(Regex, &'static str)>> = LazyLock::new(|| {
+    vec![
+        (
+            Regex::new(
+                r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}(?::\d{2}(?:\.\d+)?)?(?:Z|[+-]\d{2}:?\d{2})?",
+            )
+            .unwrap(),
+            "<timestamp>",
+        ),
+        (
+            Regex::new(r"(?i)\b[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b")
+                .unwrap(),
+            "<uuid>",
+        ),
+    ]
+});
+
+/// Call kinds used in request keys.
+const CALL_TEXT: &str = "text";
+const CALL_CHAT: &str = "chat";
+const CALL_CHAT_WITH_TOOLS: &str = "chat_with_tools";
+const CALL_STREAM: &str = "stream";
+
+/// Wrap the provider produced by `build` according to `[cassette]`.
+///
+/// Replay mode never calls `build`, so it works without provider credentials.
+pub fn wrap_provider(
+    config: &CassetteConfig,
+    build: impl FnOnce() -> anyhow::Result<Box<dyn Provider>>,
+) -> anyhow::Result<Box<dyn Provider>> {
+    let path = || {
+        config.path.as_deref().context(
+            "cassette.path (or ZEROCLAW_CASSETTE) must be set when cassette.mode is record or replay",
+        )
+    };
+    match config.mode {
+        CassetteMode::Off => build(),
+        CassetteMode::Record => {
+            let path = path()?;
+            Ok(Box::new(RecordingProvider::new(build()?, path)))
+        }
+        CassetteMode::Replay => Ok(Box::new(ReplayProvider::open(path()?, config.strict)?)),
+    }
+}
+
+/// One line of a cassette file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum CassetteLine {
+    /// Capabilities of the recorded provider, written before its first exchange.
+    Provider(RecordedCapabilities),
+    Exchange(Exchange),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct RecordedCapabilities {
+    native_tool_calling: bool,
+    vision: bool,
+    streaming: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Exchange {
+    key: String,
+    /// Normalized request, kept for readability and mismatch diagnostics.
+    request: Value,
+    reply: Reply,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Reply {
+    Text(String),
+    Response(ChatResponse),
+    Stream(Vec<RecordedChunk>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedChunk {
+    delta: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    is_final: bool,
+    #[serde(default)]
+    token_count: usize,
+}
+
+impl From<&StreamChunk> for RecordedChunk {
+    fn from(chunk: &StreamChunk) -> Self {
+        Self {
+            delta: chunk.delta.clone(),
+            is_final: chunk.is_final,
+            token_count: chunk.token_count,
+        }
+    }
+}
+
+impl From<RecordedChunk> for StreamChunk {
+    fn from(chunk: RecordedChunk) -> Self {
+        Self {
+            delta: chunk.delta,
+            is_final: chunk.is_final,
+            token_count: chunk.token_count,
+        }
+    }
+}
+
+impl Reply {
+    fn into_text(self) -> String {
+        match self {
+            Self::Text(text) => text,
+            Self::Response(response) => response.text.unwrap_or_default(),
+            Self::Stream(chunks) => chunks.into_iter().map(|chunk| chunk.delta).collect(),
+        }
+    }
+
+    fn into_response(self) -> ChatResponse {
+        match self {
+            Self::Response(response) => response,
+            other => ChatResponse {
+                text: Some(other.into_text()),
+                tool_calls: Vec::new(),
+                reasoning: Vec::new(),
+                usage: None,
+            },
+        }
+    }
+
+    fn into_chunks(self) -> Vec<StreamChunk> {
+        match self {
+            Self::Stream(chunks) => chunks.into_iter().map(StreamChunk::from).collect(),
+            other => vec![
+                StreamChunk::delta(other.into_text()),
+                StreamChunk::final_chunk(),
+            ],
+        }
+    }
+}
+
+/// A request reduced to the parts that identify it across runs.
+struct NormalizedRequest {
+    key: String,
+    body: Value,
+}
+
+impl NormalizedRequest {
+    fn new(
+        call: &str,
+        model: &str,
+        messages: &[ChatMessage],
+        tools: Vec<String>,
+        response_format: Option<&str>,
+    ) -> Self {
+        let messages: Vec<Value> = messages
+            .iter()
+            .filter(|message| message.role != "system")
+            .map(|message| {
+                json!({
+                    "role": message.role,
+                    "content": mask_volatile(message.content.trim()),
+                })
+            })
+            .collect();
+        let mut body = json!({ "call": call, "model": model, "messages": messages });
+        if !tools.is_empty() {
+            body["tools"] = json!(tools);
+        }
+        if let Some(name) = response_format {
+            body["response_format"] = json!(name);
+        }
+        let digest = Sha256::digest(body.to_string().as_bytes());
+        Self {
+            key: hex::encode(&digest[..16]),
+            body,
+        }
+    }
+
+    fn for_chat(request: &ChatRequest<'_>, model: &str) -> Self {
+        let tools = request
+            .tools
+            .map(|tools| tools.iter().map(|tool| tool.name.clone()).collect())
+            .unwrap_or_default();
+        Self::new(
+            CALL_CHAT,
+            model,
+            request.messages,
+            tools,
+            request.response_format.map(|format| format.name.as_str()),
+        )
+    }
+
+    fn for_tools(messages: &[ChatMessage], tools: &[Value], model: &str) -> Self {
+        let names = tools
+            .iter()
+            .map(|tool| {
+                tool.pointer("/function/name")
+                    .or_else(|| tool.get("name"))
+                    .and_then(Value::as_str)
+                    .map_or_else(|| tool.to_string(), str::to_string)
+            })
+            .collect();
+        Self::new(CALL_CHAT_WITH_TOOLS, model, messages, names, None)
+    }
+
+    fn for_system(message: &str, model: &str, call: &str) -> Self {
+        Self::new(call, model, &[ChatMessage::user(message)], Vec::new(), None)
+    }
+
+    fn call(&self) -> &str {
+        self.body["call"].as_str().unwrap_or_default()
+    }
+
+    fn into_exchange(self, reply: Reply) -> Exchange {
+        Exchange {
+            key: self.key,
+            request: self.body,
+            reply,
+        }
+    }
+}
+
+fn mask_volatile(text: &str) -> String {
+    VOLATILE_PATTERNS
+        .iter()
+        .fold(text.to_string(), |text, (pattern, placeholder)| {
+            pattern.replace_all(&text, *placeholder).into_owned()
+        })
+}
+
+/// Appends exchanges to a cassette file.
+struct CassetteWriter {
+    path: PathBuf,
+    capabilities: RecordedCapabilities,
+    /// Whether this writer has emitted its provider line yet.
+    header_written: Mutex<bool>,
+}
+
+impl CassetteWriter {
+    fn append(&self, exchange: Exchange) {
+        if let Err(error) = self.try_append(exchange) {
+            tracing::warn!(
+                path = %self.path.display(),
+                "Failed to write provider cassette: {error:#}"
+            );
+        }
+    }
+
+    fn try_append(&self, exchange: Exchange) -> anyhow::Result<()> {
+        let mut header_written = self.header_written.lock();
+        let mut out = String::new();
+        if !*header_written {
+            out.push_str(&serde_json::to_string(&CassetteLine::Provider(
+                self.capabilities,
+            ))?);
+            out.push('\n');
+        }
+        out.push_str(&serde_json::to_string(&CassetteLine::Exchange(exchange))?);
+        out.push('\n');
+
+        if let Some(parent) = self.path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        // One write per batch of lines so concurrent recorders sharing a
+        // cassette never interleave within a line.
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(out.as_bytes())?;
+        *header_written = true;
+        Ok(())
+    }
+}
+
+/// Forwards to an inner provider and records every successful exchange.
+pub struct RecordingProvider {
+    inner: Box<dyn Provider>,
+    writer: Arc<CassetteWriter>,
+}
+
+impl RecordingProvider {
+    pub fn new(inner: Box<dyn Provider>, path: impl Into<PathBuf>) -> Self {
+        let capabilities = RecordedCapabilities {
+            native_tool_calling: inner.supports_native_tools(),
+            vision: inner.supports_vision(),
+            streaming: inner.supports_streaming(),
+        };
+        Self {
+            inner,
+            writer: Arc::new(CassetteWriter {
+                path: path.into(),
+                capabilities,
+                header_written: Mutex::new(false),
+            }),
+        }
+    }
+
+    fn record_stream(
+        &self,
+        request: NormalizedRequest,
+        inner: stream::BoxStream<'static, StreamResult<StreamChunk>>,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let writer = Arc::clone(&self.writer);
+        let mut request = Some(request);
+        let mut chunks = Vec::new();
+        let mut failed = false;
+        inner
+            .map(move |item| {
+                match &item {
+                    Ok(chunk) => {
+                        chunks.push(RecordedChunk::from(chunk));
+                        if chunk.is_final && !failed {
+                            if let Some(request) = request.take() {
+                                let chunks = std::mem::take(&mut chunks);
+                                writer.append(request.into_exchange(Reply::Stream(chunks)));
+                            }
+                        }
+                    }
+                    Err(_) => failed = true,
+                }
+                item
+            })
+            .boxed()
+    }
+}
+
+#[async_trait]
+impl Provider for RecordingProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn convert_tools(&self, tools: &[ToolSpec]) -> ToolsPayload {
+        self.inner.convert_tools(tools)
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        self.inner.supports_native_tools()
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.inner.supports_vision()
+    }
+
+    async fn warmup(&self) -> anyhow::Result<()> {
+        self.inner.warmup().await
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let text = self
+            .inner
+            .chat_with_system(system_prompt, message, model, temperature)
+            .await?;
+        let request = NormalizedRequest::for_system(message, model, CALL_TEXT);
+        self.writer
+            .append(request.into_exchange(Reply::Text(text.clone())));
+        Ok(text)
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let text = self
+            .inner
+            .chat_with_history(messages, model, temperature)
+            .await?;
+        let request = NormalizedRequest::new(CALL_TEXT, model, messages, Vec::new(), None);
+        self.writer
+            .append(request.into_exchange(Reply::Text(text.clone())));
+        Ok(text)
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let normalized = NormalizedRequest::for_chat(&request, model);
+        let response = self.inner.chat(request, model, temperature).await?;
+        self.writer
+            .append(normalized.into_exchange(Reply::Response(response.clone())));
+        Ok(response)
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[Value],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let response = self
+            .inner
+            .chat_with_tools(messages, tools, model, temperature)
+            .await?;
+        let request = NormalizedRequest::for_tools(messages, tools, model);
+        self.writer
+            .append(request.into_exchange(Reply::Response(response.clone())));
+        Ok(response)
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn stream_chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let request = NormalizedRequest::for_system(message, model, CALL_STREAM);
+        let inner =
+            self.inner
+                .stream_chat_with_system(system_prompt, message, model, temperature, options);
+        self.record_stream(request, inner)
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let request = NormalizedRequest::new(CALL_STREAM, model, messages, Vec::new(), None);
+        let inner = self
+            .inner
+            .stream_chat_with_history(messages, model, temperature, options);
+        self.record_stream(request, inner)
+    }
+}
+
+/// Serves recorded exchanges from a cassette file.
+pub struct ReplayProvider {
+    path: PathBuf,
+    strict: bool,
+    capabilities: RecordedCapabilities,
+    exchanges: Vec<Exchange>,
+    /// Which exchanges have been served, indexed like `exchanges`.
+    used: Mutex<Vec<bool>>,
+}
+
+impl ReplayProvider {
+    pub fn open(path: &Path, strict: bool) -> anyhow::Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read provider cassette {}", path.display()))?;
+        let mut capabilities = RecordedCapabilities::default();
+        let mut exchanges = Vec::new();
+        for (index, line) in raw.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let line: CassetteLine = serde_json::from_str(line).with_context(|| {
+                format!("Invalid cassette entry at {}:{}", path.display(), index + 1)
+            })?;
+            match line {
+                CassetteLine::Provider(recorded) => capabilities = recorded,
+                CassetteLine::Exchange(exchange) => exchanges.push(exchange),
+            }
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            strict,
+            capabilities,
+            used: Mutex::new(vec![false; exchanges.len()]),
+            exchanges,
+        })
+    }
+
+    fn take(&self, request: &NormalizedRequest) -> anyhow::Result<Reply> {
+        let mut used = self.used.lock();
+        let matching = |index: &usize| self.exchanges[*index].key == request.key;
+
+        if let Some(index) = (0..self.exchanges.len()).find(|i| !used[*i] && matching(i)) {
+            used[index] = true;
+            return Ok(self.exchanges[index].reply.clone());
+        }
+        // Recorded, but asked for more often than during recording.
+        if let Some(index) = (0..self.exchanges.len()).rev().find(matching) {
+            return Ok(self.exchanges[index].reply.clone());
+        }
+
+        if !self.strict {
+            let same_call = |i: &usize| self.exchanges[*i].request["call"] == request.call();
+            if let Some(index) = (0..self.exchanges.len()).find(|i| !used[*i] && same_call(i)) {
+                tracing::debug!(
+                    key = request.key.as_str(),
+                    recorded = self.exchanges[index].key.as_str(),
+                    "Cassette miss; serving next unused exchange"
+                );
+                used[index] = true;
+                return Ok(self.exchanges[index].reply.clone());
+            }
+        }
+
+        let last_message = request.body["messages"]
+            .as_array()
+            .and_then(|messages| messages.last())
+            .and_then(|message| message["content"].as_str())
+            .unwrap_or_default();
+        anyhow::bail!(
+            "No recorded exchange in cassette {} for {} request {} (last message: {:?}). \
+             Re-record with ZEROCLAW_CASSETTE_MODE=record",
+            self.path.display(),
+            request.call(),
+            request.key,
+            crate::util::truncate_with_ellipsis(last_message, 120)
+        )
+    }
+}
+
+#[async_trait]
+impl Provider for ReplayProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            native_tool_calling: self.capabilities.native_tool_calling,
+            vision: self.capabilities.vision,
+        }
+    }
+
+    async fn chat_with_system(
+        &self,
+        _system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        _temperature: f64,
+    ) -> anyhow::Result<String> {
+        let request = NormalizedRequest::for_system(message, model, CALL_TEXT);
+        Ok(self.take(&request)?.into_text())
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        _temperature: f64,
+    ) -> anyhow::Result<String> {
+        let request = NormalizedRequest::new(CALL_TEXT, model, messages, Vec::new(), None);
+        Ok(self.take(&request)?.into_text())
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        _temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let request = NormalizedRequest::for_chat(&request, model);
+        Ok(self.take(&request)?.into_response())
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[Value],
+        model: &str,
+        _temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let request = NormalizedRequest::for_tools(messages, tools, model);
+        Ok(self.take(&request)?.into_response())
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.capabilities.streaming
+    }
+
+    fn stream_chat_with_system(
+        &self,
+        _system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        _temperature: f64,
+        _options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.replay_stream(&NormalizedRequest::for_system(message, model, CALL_STREAM))
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        _temperature: f64,
+        _options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let request = NormalizedRequest::new(CALL_STREAM, model, messages, Vec::new(), None);
+        self.replay_stream(&request)
+    }
+}
+
+impl ReplayProvider {
+    fn replay_stream(
+        &self,
+        request: &NormalizedRequest,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        match self.take(request) {
+            Ok(reply) => stream::iter(reply.into_chunks().into_iter().map(Ok)).boxed(),
+            Err(error) => {
+                stream::once(async move { Err(StreamError::Provider(format!("{error:#}"))) })
+                    .boxed()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::ToolCall;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Answers every call with a numbered reply so replays are distinguishable.
+    struct CountingProvider {
+        calls: AtomicUsize,
+    }
+
+    impl CountingProvider {
+        fn new() -> Self {
+            Self {
+                calls: AtomicUsize::new(0),
+            }
+        }
+
+        fn next(&self) -> usize {
+            self.calls.fetch_add(1, Ordering::SeqCst) + 1
+        }
+    }
+
+    #[async_trait]
+    impl Provider for CountingProvider {
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                native_tool_calling: true,
+                vision: false,
+            }
+        }
+
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok(format!("reply {} to {message}", self.next()))
+        }
+
+        async fn chat(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            Ok(ChatResponse {
+                text: Some(format!("reply {}", self.next())),
+                tool_calls: vec![ToolCall {
+                    id: "call_1".into(),
+                    name: "shell".into(),
+                    arguments: r#"{"command":"ls"}"#.into(),
+                }],
+                reasoning: Vec::new(),
+                usage: None,
+            })
+        }
+
+        fn supports_streaming(&self) -> bool {
+            true
+        }
+
+        fn stream_chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+            _options: StreamOptions,
+        ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+            stream::iter(vec![
+                Ok(StreamChunk::delta("Hel")),
+                Ok(StreamChunk::delta("lo")),
+                Ok(StreamChunk::final_chunk()),
+            ])
+            .boxed()
+        }
+    }
+
+    fn chat_request(messages: &[ChatMessage]) -> ChatRequest<'_> {
+        ChatRequest {
+            messages,
+            tools: None,
+            response_format: None,
+            reasoning: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn replay_serves_recorded_exchanges_without_inner_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cassettes/session.jsonl");
+        let recorder = RecordingProvider::new(Box::new(CountingProvider::new()), &path);
+
+        let messages = [ChatMessage::system("sys"), ChatMessage::user("list files")];
+        let recorded = recorder
+            .chat(chat_request(&messages), "model-a", 0.7)
+            .await
+            .unwrap();
+        let recorded_text = recorder
+            .chat_with_system(None, "hello", "model-a", 0.7)
+            .await
+            .unwrap();
+
+        let replay = ReplayProvider::open(&path, true).unwrap();
+        assert!(replay.supports_native_tools());
+        let replayed = replay
+            .chat(chat_request(&messages), "model-a", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(replayed.text, recorded.text);
+        assert_eq!(replayed.tool_calls.len(), 1);
+        assert_eq!(replayed.tool_calls[0].name, "shell");
+        assert_eq!(
+            replay
+                .chat_with_system(Some("other system"), "hello", "model-a", 0.0)
+                .await
+                .unwrap(),
+            recorded_text
+        );
+    }
+
+    #[tokio::test]
+    async fn key_ignores_system_prompt_timestamps_and_uuids() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let recorder = RecordingProvider::new(Box::new(CountingProvider::new()), &path);
+        let recorded_messages = [
+            ChatMessage::system("Now: 2026-01-01 10:00:00 on host-a"),
+            ChatMessage::user("run 0f8fad5b-d9cb-469f-a165-70867728950e at 2026-01-01T10:00:00Z"),
+        ];
+        recorder
+            .chat(chat_request(&recorded_messages), "m", 0.7)
+            .await
+            .unwrap();
+
+        let replay = ReplayProvider::open(&path, true).unwrap();
+        let later_messages = [
+            ChatMessage::system("Now: 2026-03-09 18:30:12 on ci-runner"),
+            ChatMessage::user("run 7c9e6679-7425-40de-944b-e07fc1f90ae7 at 2026-03-09T18:30:12Z"),
+        ];
+        let response = replay
+            .chat(chat_request(&later_messages), "m", 0.7)
+            .await
+            .unwrap();
+        assert_eq!(response.text.as_deref(), Some("reply 1"));
+    }
+
+    #[tokio::test]
+    async fn repeated_requests_replay_in_recording_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let recorder = RecordingProvider::new(Box::new(CountingProvider::new()), &path);
+        for _ in 0..2 {
+            recorder
+                .chat_with_system(None, "ping", "m", 0.7)
+                .await
+                .unwrap();
+        }
+
+        let replay = ReplayProvider::open(&path, true).unwrap();
+        let first = replay
+            .chat_with_system(None, "ping", "m", 0.7)
+            .await
+            .unwrap();
+        let second = replay
+            .chat_with_system(None, "ping", "m", 0.7)
+            .await
+            .unwrap();
+        let third = replay
+            .chat_with_system(None, "ping", "m", 0.7)
+            .await
+            .unwrap();
+        assert_eq!(first, "reply 1 to ping");
+        assert_eq!(second, "reply 2 to ping");
+        assert_eq!(third, second);
+    }
+
+    #[tokio::test]
+    async fn strict_replay_rejects_unmatched_requests_and_lenient_falls_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let recorder = RecordingProvider::new(Box::new(CountingProvider::new()), &path);
+        recorder
+            .chat_with_system(None, "original prompt", "m", 0.7)
+            .await
+            .unwrap();
+
+        let strict = ReplayProvider::open(&path, true).unwrap();
+        let error = strict
+            .chat_with_system(None, "edited prompt", "m", 0.7)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("No recorded exchange"), "{error}");
+        assert!(error.contains("edited prompt"), "{error}");
+
+        let lenient = ReplayProvider::open(&path, false).unwrap();
+        assert_eq!(
+            lenient
+                .chat_with_system(None, "edited prompt", "m", 0.7)
+                .await
+                .unwrap(),
+            "reply 1 to original prompt"
+        );
+    }
+
+    #[tokio::test]
+    async fn streaming_chunks_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let recorder = RecordingProvider::new(Box::new(CountingProvider::new()), &path);
+        let recorded: Vec<String> = recorder
+            .stream_chat_with_system(None, "greet", "m", 0.7, StreamOptions::new(true))
+            .map(|chunk| chunk.unwrap().delta)
+            .collect()
+            .await;
+
+        let replay = ReplayProvider::open(&path, true).unwrap();
+        assert!(replay.supports_streaming());
+        let chunks: Vec<StreamChunk> = replay
+            .stream_chat_with_system(None, "greet", "m", 0.7, StreamOptions::new(true))
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        let replayed: Vec<String> = chunks.iter().map(|chunk| chunk.delta.clone()).collect();
+        assert_eq!(replayed, recorded);
+        assert!(chunks.last().unwrap().is_final);
+    }
+
+    #[test]
+    fn wrap_provider_replays_without_building_and_requires_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("empty.jsonl");
+        std::fs::write(&path, "").unwrap();
+
+        let config = CassetteConfig {
+            mode: CassetteMode::Replay,
+            path: Some(path),
+            strict: true,
+        };
+        let provider = wrap_provider(&config, || panic!("replay must not build a provider"));
+        assert!(provider.is_ok());
+
+        let missing_path = CassetteConfig {
+            mode: CassetteMode::Record,
+            path: None,
+            strict: true,
+        };
+        let error = wrap_provider(&missing_path, || Ok(Box::new(CountingProvider::new())))
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("cassette.path"));
+    }
+}
@@ -2,9 +2,11 @@
 //! Most LLM APIs follow the same `/v1/chat/completions` format.
 //! This module provides a single implementation that works for all of them.
 
+use crate::providers::structured;
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, StreamChunk, StreamError, StreamOptions, StreamResult, ToolCall as ProviderToolCall,
+    ChatUsage, Provider, StreamChunk, StreamError, StreamOptions, StreamResult,
+    ToolCall as ProviderToolCall,
 };
 use async_trait::async_trait;
 use futures_util::{stream, StreamExt};
@@ -272,6 +274,25 @@ struct Message {
 #[derive(Debug, Deserialize)]
 struct ApiChatResponse {
     choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<ApiUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiUsage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
+}
+
+impl ApiUsage {
+    fn into_chat_usage(self) -> ChatUsage {
+        ChatUsage {
+            input_tokens: self.prompt_tokens,
+            output_tokens: self.completion_tokens,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -926,7 +947,12 @@ impl OpenAiCompatibleProvider {
             })
             .collect::<Vec<_>>();
 
-        ProviderChatResponse { text, tool_calls }
+        ProviderChatResponse {
+            text,
+            tool_calls,
+            reasoning: Vec::new(),
+            usage: None,
+        }
     }
 
     fn is_native_tool_schema_unsupported(
@@ -1276,6 +1302,8 @@ impl Provider for OpenAiCompatibleProvider {
                 return Ok(ProviderChatResponse {
                     text: Some(text),
                     tool_calls: vec![],
+                    reasoning: Vec::new(),
+                    usage: None,
                 });
             }
         };
@@ -1286,6 +1314,7 @@ impl Provider for OpenAiCompatibleProvider {
 
         let body = response.text().await?;
         let chat_response = parse_chat_response_body(&self.name, &body)?;
+        let usage = chat_response.usage.map(ApiUsage::into_chat_usage);
         let choice = chat_response
             .choices
             .into_iter()
@@ -1310,7 +1339,12 @@ impl Provider for OpenAiCompatibleProvider {
             })
             .collect::<Vec<_>>();
 
-        Ok(ProviderChatResponse { text, tool_calls })
+        Ok(ProviderChatResponse {
+            text,
+            tool_calls,
+            reasoning: Vec::new(),
+            usage,
+        })
     }
 
     async fn chat(
@@ -1327,10 +1361,13 @@ impl Provider for OpenAiCompatibleProvider {
         })?;
 
         let tools = Self::convert_tool_specs(request.tools);
+        // No native structured-output mode here; describe the schema in the prompt.
+        let messages =
+            structured::prompt_guided_messages(request.messages, request.response_format);
         let effective_messages = if self.merge_system_into_user {
-            Self::flatten_system_messages(request.messages)
+            Self::flatten_system_messages(&messages)
         } else {
-            request.messages.to_vec()
+            messages.to_vec()
         };
         let native_request = NativeChatRequest {
             model: model.to_string(),
@@ -1359,7 +1396,8 @@ impl Provider for OpenAiCompatibleProvider {
                         .await
                         .map(|text| ProviderChatResponse {
                             text: Some(text),
-                            tool_calls: vec![],
+                            tool_calls: vec![], reasoning: Vec::new(),
+                            usage: None,
                         })
                         .map_err(|responses_err| {
                             anyhow::anyhow!(
@@ -1388,6 +1426,8 @@ impl Provider for OpenAiCompatibleProvider {
                 return Ok(ProviderChatResponse {
                     text: Some(text),
                     tool_calls: vec![],
+                    reasoning: Vec::new(),
+                    usage: None,
                 });
             }
 
@@ -1397,7 +1437,8 @@ impl Provider for OpenAiCompatibleProvider {
                     .await
                     .map(|text| ProviderChatResponse {
                         text: Some(text),
-                        tool_calls: vec![],
+                        tool_calls: vec![], reasoning: Vec::new(),
+                        usage: None,
                     })
                     .map_err(|responses_err| {
                         anyhow::anyhow!(
@@ -1411,6 +1452,7 @@ impl Provider for OpenAiCompatibleProvider {
         }
 
         let native_response: ApiChatResponse = response.json().await?;
+        let usage = native_response.usage.map(ApiUsage::into_chat_usage);
         let message = native_response
             .choices
             .into_iter()
@@ -1418,7 +1460,9 @@ impl Provider for OpenAiCompatibleProvider {
             .map(|choice| choice.message)
             .ok_or_else(|| anyhow::anyhow!("No response from {}", self.name))?;
 
-        Ok(Self::parse_native_response(message))
+        let mut parsed = Self::parse_native_response(message);
+        parsed.usage = usage;
+        Ok(parsed)
     }
 
     fn supports_native_tools(&self) -> bool {
@@ -11,6 +11,7 @@
 //! GitHub could change or revoke this at any time, which would break all
 //! third-party integrations simultaneously.
 
+use crate::providers::structured;
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
     Provider, ToolCall as ProviderToolCall,
@@ -363,6 +364,8 @@ impl CopilotProvider {
         Ok(ProviderChatResponse {
             text: choice.message.content,
             tool_calls,
+            reasoning: Vec::new(),
+            usage: None,
         })
     }
 
@@ -629,8 +632,11 @@ impl Provider for CopilotProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ProviderChatResponse> {
+        // No native structured-output mode here; describe the schema in the prompt.
+        let messages =
+            structured::prompt_guided_messages(request.messages, request.response_format);
         self.send_chat_request(
-            Self::convert_messages(request.messages),
+            Self::convert_messages(&messages),
             request.tools,
             model,
             temperature,
@@ -2,17 +2,40 @@
 //! - Direct API key (`GEMINI_API_KEY` env var or config)
 //! - Gemini CLI OAuth tokens (reuse existing ~/.gemini/ authentication)
 //! - Google Cloud ADC (`GOOGLE_APPLICATION_CREDENTIALS`)
-
-use crate::providers::traits::{ChatMessage, Provider};
+//!
+//! Native function calling (`functionDeclarations` / `functionCall` /
+//! `functionResponse`), inline image parts and `streamGenerateContent` SSE
+//! streaming behave the same on the public API and on cloudcode-pa.
+
+use crate::multimodal;
+use crate::providers::reasoning::ReasoningLevel;
+use crate::providers::structured;
+use crate::providers::traits::{
+    ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
+    Provider, ProviderCapabilities, ReasoningBlock, ResponseFormat, StreamChunk, StreamError,
+    StreamOptions, StreamResult, ToolCall as ProviderToolCall, ToolsPayload,
+};
+use crate::tools::{SchemaCleanr, ToolSpec};
 use async_trait::async_trait;
 use directories::UserDirs;
+use futures_util::{stream, StreamExt};
+use parking_lot::Mutex;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
+/// Upper bound on remembered thought signatures before the cache is reset.
+const MAX_THOUGHT_SIGNATURES: usize = 512;
+
 /// Gemini provider supporting multiple authentication methods.
 pub struct GeminiProvider {
     auth: Option<GeminiAuth>,
+    /// `thoughtSignature`s returned next to `functionCall` parts, keyed by the
+    /// tool call id handed to the agent loop. Thinking models reject a
+    /// follow-up turn whose function calls come back without them.
+    thought_signatures: Mutex<HashMap<String, String>>,
+    reasoning: Option<ReasoningLevel>,
 }
 
 /// Resolved credential — the variant determines both the HTTP auth method
@@ -63,6 +86,8 @@ struct GenerateContentRequest {
     contents: Vec<Content>,
     #[serde(rename = "systemInstruction", skip_serializing_if = "Option::is_none")]
     system_instruction: Option<Content>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<GeminiTool>>,
     #[serde(rename = "generationConfig")]
     generation_config: GenerationConfig,
 }
@@ -85,6 +110,8 @@ struct InternalGenerateContentRequest {
     contents: Vec<Content>,
     #[serde(rename = "systemInstruction", skip_serializing_if = "Option::is_none")]
     system_instruction: Option<Content>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<GeminiTool>>,
     #[serde(rename = "generationConfig")]
     generation_config: GenerationConfig,
 }
@@ -97,8 +124,59 @@ struct Content {
 }
 
 #[derive(Debug, Serialize, Clone)]
-struct Part {
-    text: String,
+#[serde(untagged)]
+enum Part {
+    Text {
+        text: String,
+    },
+    InlineData {
+        #[serde(rename = "inlineData")]
+        inline_data: InlineData,
+    },
+    FunctionCall {
+        #[serde(rename = "functionCall")]
+        function_call: FunctionCall,
+        #[serde(rename = "thoughtSignature", skip_serializing_if = "Option::is_none")]
+        thought_signature: Option<String>,
+    },
+    FunctionResponse {
+        #[serde(rename = "functionResponse")]
+        function_response: FunctionResponse,
+    },
+}
+
+impl Part {
+    fn text(text: impl Into<String>) -> Self {
+        Part::Text { text: text.into() }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct InlineData {
+    #[serde(rename = "mimeType")]
+    mime_type: String,
+    data: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct FunctionCall {
+    name: String,
+    #[serde(default)]
+    args: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct FunctionResponse {
+    name: String,
+    response: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct GeminiTool {
+    #[serde(rename = "functionDeclarations")]
+    function_declarations: Vec<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -106,6 +184,32 @@ struct GenerationConfig {
     temperature: f64,
     #[serde(rename = "maxOutputTokens")]
     max_output_tokens: u32,
+    #[serde(rename = "responseMimeType", skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<String>,
+    #[serde(rename = "responseSchema", skip_serializing_if = "Option::is_none")]
+    response_schema: Option<serde_json::Value>,
+    #[serde(rename = "thinkingConfig", skip_serializing_if = "Option::is_none")]
+    thinking_config: Option<ThinkingConfig>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct ThinkingConfig {
+    #[serde(rename = "thinkingBudget")]
+    thinking_budget: u32,
+    #[serde(rename = "includeThoughts")]
+    include_thoughts: bool,
+}
+
+impl ThinkingConfig {
+    /// A zero budget disables thinking on models that allow it; otherwise
+    /// thought summaries are requested so they can be surfaced as reasoning.
+    fn from_level(level: ReasoningLevel) -> Self {
+        let thinking_budget = level.budget_tokens();
+        Self {
+            thinking_budget,
+            include_thoughts: thinking_budget > 0,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -118,17 +222,27 @@ struct GenerateContentResponse {
 
 #[derive(Debug, Deserialize)]
 struct Candidate {
+    /// Absent when the candidate was blocked before producing output.
+    #[serde(default)]
     content: CandidateContent,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 struct CandidateContent {
+    #[serde(default)]
     parts: Vec<ResponsePart>,
 }
 
 #[derive(Debug, Deserialize)]
 struct ResponsePart {
     text: Option<String>,
+    /// Set on thinking-model summaries, which are not part of the answer.
+    #[serde(default)]
+    thought: bool,
+    #[serde(rename = "functionCall")]
+    function_call: Option<FunctionCall>,
+    #[serde(rename = "thoughtSignature")]
+    thought_signature: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -147,6 +261,40 @@ impl GenerateContentResponse {
             other => other,
         }
     }
+
+    /// Unwrap the cloudcode-pa envelope and surface API errors from either layer.
+    fn into_checked(self) -> Result<Self, String> {
+        if let Some(err) = &self.error {
+            return Err(format!("Gemini API error: {}", err.message));
+        }
+        let effective = self.into_effective_response();
+        if let Some(err) = &effective.error {
+            return Err(format!("Gemini API error: {}", err.message));
+        }
+        Ok(effective)
+    }
+
+    /// Parts of the first candidate, or nothing when the model returned none.
+    fn into_parts(self) -> Vec<ResponsePart> {
+        self.candidates
+            .and_then(|c| c.into_iter().next())
+            .map(|c| c.content.parts)
+            .unwrap_or_default()
+    }
+}
+
+/// Concatenate the answer text of `parts`, skipping thought summaries.
+fn collect_text(parts: &[ResponsePart]) -> Option<String> {
+    let text: String = parts
+        .iter()
+        .filter(|p| !p.thought)
+        .filter_map(|p| p.text.as_deref())
+        .collect();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
 }
 
 // ══════════════════════════════════════════════════════════════════════════════
@@ -167,6 +315,12 @@ const CLOUDCODE_PA_ENDPOINT: &str = "https://cloudcode-pa.googleapis.com/v1inter
 /// Public API endpoint for API key users.
 const PUBLIC_API_ENDPOINT: &str = "https://generativelanguage.googleapis.com/v1beta";
 
+const MISSING_AUTH_MESSAGE: &str = "Gemini API key not found. Options:\n\
+     1. Set GEMINI_API_KEY env var\n\
+     2. Run `gemini` CLI to authenticate (tokens will be reused)\n\
+     3. Get an API key from https://aistudio.google.com/app/apikey\n\
+     4. Run `zeroclaw onboard` to configure";
+
 impl GeminiProvider {
     /// Create a new Gemini provider.
     ///
@@ -183,11 +337,23 @@ impl GeminiProvider {
             .or_else(|| Self::load_non_empty_env("GOOGLE_API_KEY").map(GeminiAuth::EnvGoogleKey))
             .or_else(|| Self::try_load_gemini_cli_token().map(GeminiAuth::OAuthToken));
 
+        Self::with_auth(resolved_auth)
+    }
+
+    fn with_auth(auth: Option<GeminiAuth>) -> Self {
         Self {
-            auth: resolved_auth,
+            auth,
+            thought_signatures: Mutex::new(HashMap::new()),
+            reasoning: None,
         }
     }
 
+    /// Default reasoning level, sent as `thinkingConfig.thinkingBudget`.
+    pub fn with_reasoning(mut self, reasoning: Option<ReasoningLevel>) -> Self {
+        self.reasoning = reasoning;
+        self
+    }
+
     fn normalize_non_empty(value: &str) -> Option<String> {
         let trimmed = value.trim();
         if trimmed.is_empty() {
@@ -282,22 +448,45 @@ impl GeminiProvider {
     /// "400 Bad Request: API key not valid" errors.
     /// See: https://github.com/google-gemini/gemini-cli/issues/19200
     fn build_generate_content_url(model: &str, auth: &GeminiAuth) -> String {
+        Self::build_method_url(model, auth, false)
+    }
+
+    /// Same as [`Self::build_generate_content_url`] but for
+    /// `streamGenerateContent`, which needs `alt=sse` to emit Server-Sent Events.
+    fn build_stream_generate_content_url(model: &str, auth: &GeminiAuth) -> String {
+        Self::build_method_url(model, auth, true)
+    }
+
+    fn build_method_url(model: &str, auth: &GeminiAuth, stream: bool) -> String {
+        let (method, sse_param) = if stream {
+            ("streamGenerateContent", Some("alt=sse"))
+        } else {
+            ("generateContent", None)
+        };
+
         match auth {
             GeminiAuth::OAuthToken(_) => {
                 // OAuth tokens from Gemini CLI are scoped for the internal
                 // Code Assist API. The model is passed in the request body,
                 // not the URL path.
-                format!("{CLOUDCODE_PA_ENDPOINT}:generateContent")
+                match sse_param {
+                    Some(param) => format!("{CLOUDCODE_PA_ENDPOINT}:{method}?{param}"),
+                    None => format!("{CLOUDCODE_PA_ENDPOINT}:{method}"),
+                }
             }
             _ => {
                 let model_name = Self::format_model_name(model);
-                let base_url = format!("{PUBLIC_API_ENDPOINT}/{model_name}:generateContent");
+                let mut url = format!("{PUBLIC_API_ENDPOINT}/{model_name}:{method}");
 
+                let mut params: Vec<String> = sse_param.map(str::to_string).into_iter().collect();
                 if auth.is_api_key() {
-                    format!("{base_url}?key={}", auth.credential())
-                } else {
-                    base_url
+                    params.push(format!("key={}", auth.credential()));
                 }
+                if !params.is_empty() {
+                    url.push('?');
+                    url.push_str(&params.join("&"));
+                }
+                url
             }
         }
     }
@@ -317,7 +506,7 @@ impl GeminiProvider {
         match auth {
             GeminiAuth::OAuthToken(token) => {
                 // Internal Code Assist API uses a wrapped payload shape:
-                // { model, project?, user_prompt_id?, request: { contents, systemInstruction?, generationConfig } }
+                // { model, project?, user_prompt_id?, request: { contents, systemInstruction?, tools?, generationConfig } }
                 let internal_request = InternalGenerateContentEnvelope {
                     model: Self::format_internal_model_name(model),
                     project: Self::resolve_oauth_project_id(),
@@ -325,6 +514,7 @@ impl GeminiProvider {
                     request: InternalGenerateContentRequest {
                         contents: request.contents.clone(),
                         system_instruction: request.system_instruction.clone(),
+                        tools: request.tools.clone(),
                         generation_config: request.generation_config.clone(),
                     },
                 };
@@ -352,39 +542,312 @@ impl GeminiProvider {
         }
         None
     }
-}
 
-impl GeminiProvider {
-    async fn send_generate_content(
+    /// Build `functionDeclarations` entries, stripping JSON Schema keywords
+    /// Gemini rejects. Tools without parameters omit the field entirely, since
+    /// an empty OBJECT schema is refused as well.
+    fn function_declarations(tools: &[ToolSpec]) -> Vec<serde_json::Value> {
+        tools
+            .iter()
+            .map(|tool| {
+                let mut declaration = serde_json::json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                });
+                let parameters = SchemaCleanr::clean_for_gemini(tool.parameters.clone());
+                let has_properties = parameters
+                    .get("properties")
+                    .and_then(serde_json::Value::as_object)
+                    .is_some_and(|props| !props.is_empty());
+                if has_properties {
+                    declaration["parameters"] = parameters;
+                }
+                declaration
+            })
+            .collect()
+    }
+
+    /// Split a user message into text and `inlineData` parts.
+    ///
+    /// Only `data:` URIs can be inlined; `prepare_messages_for_provider`
+    /// normalizes local and remote images to that form before they get here.
+    /// Anything else is kept as its marker so the reference is not lost.
+    fn user_parts(content: &str) -> Vec<Part> {
+        let (cleaned, image_refs) = multimodal::parse_image_markers(content);
+        if image_refs.is_empty() {
+            return vec![Part::text(content)];
+        }
+
+        let mut parts = Vec::with_capacity(image_refs.len() + 1);
+        if !cleaned.is_empty() {
+            parts.push(Part::text(cleaned));
+        }
+        for reference in image_refs {
+            match Self::inline_image(&reference) {
+                Some(inline_data) => parts.push(Part::InlineData { inline_data }),
+                None => parts.push(Part::text(format!("[IMAGE:{reference}]"))),
+            }
+        }
+        parts
+    }
+
+    fn inline_image(reference: &str) -> Option<InlineData> {
+        let (meta, data) = reference.strip_prefix("data:")?.split_once(',')?;
+        let mime_type = meta.strip_suffix(";base64")?.trim();
+        let data = data.trim();
+        if mime_type.is_empty() || data.is_empty() {
+            return None;
+        }
+        Some(InlineData {
+            mime_type: mime_type.to_string(),
+            data: data.to_string(),
+        })
+    }
+
+    /// Rebuild `functionCall` parts from an assistant history entry written by
+    /// the agent loop (`{"content": ..., "tool_calls": [...]}`).
+    fn assistant_tool_call_parts(
         &self,
+        content: &str,
+        call_names: &mut HashMap<String, String>,
+    ) -> Option<Vec<Part>> {
+        let value = serde_json::from_str::<serde_json::Value>(content).ok()?;
+        let tool_calls = value
+            .get("tool_calls")
+            .and_then(|v| serde_json::from_value::<Vec<ProviderToolCall>>(v.clone()).ok())?;
+
+        let mut parts = Vec::with_capacity(tool_calls.len() + 1);
+        if let Some(text) = value
+            .get("content")
+            .and_then(serde_json::Value::as_str)
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+        {
+            parts.push(Part::text(text));
+        }
+
+        let signatures = self.thought_signatures.lock();
+        for call in tool_calls {
+            let args = serde_json::from_str::<serde_json::Value>(&call.arguments)
+                .ok()
+                .filter(serde_json::Value::is_object)
+                .unwrap_or_else(|| serde_json::json!({}));
+            call_names.insert(call.id.clone(), call.name.clone());
+            parts.push(Part::FunctionCall {
+                thought_signature: signatures.get(&call.id).cloned(),
+                function_call: FunctionCall {
+                    name: call.name,
+                    args,
+                    id: None,
+                },
+            });
+        }
+
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts)
+        }
+    }
+
+    /// Turn a `{"tool_call_id", "content"}` tool message into a
+    /// `functionResponse`. Gemini pairs responses with calls by function name,
+    /// so the id is resolved through the calls seen earlier in the history.
+    fn function_response_part(content: &str, call_names: &HashMap<String, String>) -> Option<Part> {
+        let value = serde_json::from_str::<serde_json::Value>(content).ok()?;
+        let tool_call_id = value.get("tool_call_id")?.as_str()?;
+        let result = value
+            .get("content")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("");
+        let name = call_names
+            .get(tool_call_id)
+            .cloned()
+            .unwrap_or_else(|| tool_call_id.to_string());
+        Some(Part::FunctionResponse {
+            function_response: FunctionResponse {
+                name,
+                response: serde_json::json!({ "content": result }),
+            },
+        })
+    }
+
+    fn convert_messages(&self, messages: &[ChatMessage]) -> (Option<Content>, Vec<Content>) {
+        let mut system_parts: Vec<&str> = Vec::new();
+        let mut contents: Vec<Content> = Vec::new();
+        let mut call_names: HashMap<String, String> = HashMap::new();
+
+        for msg in messages {
+            match msg.role.as_str() {
+                "system" => {
+                    system_parts.push(&msg.content);
+                }
+                "user" => {
+                    contents.push(Content {
+                        role: Some("user".to_string()),
+                        parts: Self::user_parts(&msg.content),
+                    });
+                }
+                "assistant" => {
+                    // Gemini API uses "model" role instead of "assistant"
+                    let parts = self
+                        .assistant_tool_call_parts(&msg.content, &mut call_names)
+                        .unwrap_or_else(|| vec![Part::text(msg.content.clone())]);
+                    contents.push(Content {
+                        role: Some("model".to_string()),
+                        parts,
+                    });
+                }
+                "tool" => {
+                    let Some(part) = Self::function_response_part(&msg.content, &call_names) else {
+                        contents.push(Content {
+                            role: Some("user".to_string()),
+                            parts: vec![Part::text(msg.content.clone())],
+                        });
+                        continue;
+                    };
+                    // Every response to one model turn must travel in a single content.
+                    let previous_responses = contents.last_mut().filter(|c| {
+                        c.role.as_deref() == Some("user")
+                            && c.parts
+                                .iter()
+                                .all(|p| matches!(p, Part::FunctionResponse { .. }))
+                    });
+                    match previous_responses {
+                        Some(content) => content.parts.push(part),
+                        None => contents.push(Content {
+                            role: Some("user".to_string()),
+                            parts: vec![part],
+                        }),
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let system_instruction = if system_parts.is_empty() {
+            None
+        } else {
+            Some(Content {
+                role: None,
+                parts: vec![Part::text(system_parts.join("\n\n"))],
+            })
+        };
+
+        (system_instruction, contents)
+    }
+
+    /// Map response parts to text plus tool calls, remembering thought
+    /// signatures so the calls can be replayed on the next turn.
+    fn parse_chat_response(&self, parts: Vec<ResponsePart>) -> ProviderChatResponse {
+        let text = collect_text(&parts);
+        let mut tool_calls = Vec::new();
+        let mut reasoning = Vec::new();
+
+        for part in parts {
+            if part.thought {
+                if let Some(text) = part.text.filter(|t| !t.trim().is_empty()) {
+                    reasoning.push(ReasoningBlock {
+                        text,
+                        ..ReasoningBlock::default()
+                    });
+                }
+                continue;
+            }
+            let Some(call) = part.function_call else {
+                continue;
+            };
+            if call.name.is_empty() {
+                continue;
+            }
+            let id = call
+                .id
+                .filter(|id| !id.is_empty())
+                .unwrap_or_else(|| format!("call_{}", uuid::Uuid::new_v4().simple()));
+            if let Some(signature) = part.thought_signature {
+                let mut signatures = self.thought_signatures.lock();
+                if signatures.len() >= MAX_THOUGHT_SIGNATURES {
+                    signatures.clear();
+                }
+                signatures.insert(id.clone(), signature);
+            }
+            let arguments = if call.args.is_object() {
+                call.args
+            } else {
+                serde_json::json!({})
+            };
+            tool_calls.push(ProviderToolCall {
+                id,
+                name: call.name,
+                arguments: arguments.to_string(),
+            });
+        }
+
+        ProviderChatResponse {
+            text,
+            tool_calls,
+            reasoning,
+            usage: None,
+        }
+    }
+
+    fn generate_content_request(
         contents: Vec<Content>,
         system_instruction: Option<Content>,
-        model: &str,
+        tools: Option<Vec<GeminiTool>>,
+        response_format: Option<&ResponseFormat>,
+        reasoning: Option<ReasoningLevel>,
         temperature: f64,
-    ) -> anyhow::Result<String> {
-        let auth = self.auth.as_ref().ok_or_else(|| {
-            anyhow::anyhow!(
-                "Gemini API key not found. Options:\n\
-                 1. Set GEMINI_API_KEY env var\n\
-                 2. Run `gemini` CLI to authenticate (tokens will be reused)\n\
-                 3. Get an API key from https://aistudio.google.com/app/apikey\n\
-                 4. Run `zeroclaw onboard` to configure"
-            )
-        })?;
-
-        let request = GenerateContentRequest {
+    ) -> GenerateContentRequest {
+        GenerateContentRequest {
             contents,
             system_instruction,
+            tools,
             generation_config: GenerationConfig {
                 temperature,
                 max_output_tokens: 8192,
+                response_mime_type: response_format.map(|_| "application/json".to_string()),
+                response_schema: response_format
+                    .map(|format| SchemaCleanr::clean_for_gemini(format.schema.clone())),
+                thinking_config: reasoning.map(ThinkingConfig::from_level),
             },
+        }
+    }
+
+    /// Parse one SSE line from `streamGenerateContent?alt=sse`. Every `data:`
+    /// payload is a complete `GenerateContentResponse` carrying the next
+    /// slice of text.
+    fn parse_sse_line(line: &str) -> StreamResult<Option<String>> {
+        let Some(data) = line.trim().strip_prefix("data:") else {
+            return Ok(None);
         };
+        let data = data.trim();
+        if data.is_empty() {
+            return Ok(None);
+        }
+
+        let response: GenerateContentResponse =
+            serde_json::from_str(data).map_err(StreamError::Json)?;
+        let response = response.into_checked().map_err(StreamError::Provider)?;
+        Ok(collect_text(&response.into_parts()))
+    }
+}
+
+impl GeminiProvider {
+    async fn send_generate_content(
+        &self,
+        request: &GenerateContentRequest,
+        model: &str,
+    ) -> anyhow::Result<Vec<ResponsePart>> {
+        let auth = self
+            .auth
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!(MISSING_AUTH_MESSAGE))?;
 
         let url = Self::build_generate_content_url(model, auth);
 
         let response = self
-            .build_generate_content_request(auth, &url, &request, model)
+            .build_generate_content_request(auth, &url, request, model)
             .send()
             .await?;
 
@@ -395,25 +858,45 @@ impl GeminiProvider {
         }
 
         let result: GenerateContentResponse = response.json().await?;
-        if let Some(err) = &result.error {
-            anyhow::bail!("Gemini API error: {}", err.message);
-        }
-        let result = result.into_effective_response();
-        if let Some(err) = result.error {
-            anyhow::bail!("Gemini API error: {}", err.message);
-        }
+        let result = result.into_checked().map_err(anyhow::Error::msg)?;
+        Ok(result.into_parts())
+    }
 
-        result
-            .candidates
-            .and_then(|c| c.into_iter().next())
-            .and_then(|c| c.content.parts.into_iter().next())
-            .and_then(|p| p.text)
-            .ok_or_else(|| anyhow::anyhow!("No response from Gemini"))
+    async fn send_for_text(
+        &self,
+        contents: Vec<Content>,
+        system_instruction: Option<Content>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let request = Self::generate_content_request(
+            contents,
+            system_instruction,
+            None,
+            None,
+            self.reasoning,
+            temperature,
+        );
+        let parts = self.send_generate_content(&request, model).await?;
+        collect_text(&parts).ok_or_else(|| anyhow::anyhow!("No response from Gemini"))
     }
 }
 
 #[async_trait]
 impl Provider for GeminiProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            native_tool_calling: true,
+            vision: true,
+        }
+    }
+
+    fn convert_tools(&self, tools: &[ToolSpec]) -> ToolsPayload {
+        ToolsPayload::Gemini {
+            function_declarations: Self::function_declarations(tools),
+        }
+    }
+
     async fn chat_with_system(
         &self,
         system_prompt: Option<&str>,
@@ -423,19 +906,15 @@ impl Provider for GeminiProvider {
     ) -> anyhow::Result<String> {
         let system_instruction = system_prompt.map(|sys| Content {
             role: None,
-            parts: vec![Part {
-                text: sys.to_string(),
-            }],
+            parts: vec![Part::text(sys)],
         });
 
         let contents = vec![Content {
             role: Some("user".to_string()),
-            parts: vec![Part {
-                text: message.to_string(),
-            }],
+            parts: Self::user_parts(message),
         }];
 
-        self.send_generate_content(contents, system_instruction, model, temperature)
+        self.send_for_text(contents, system_instruction, model, temperature)
             .await
     }
 
@@ -445,48 +924,204 @@ impl Provider for GeminiProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let mut system_parts: Vec<&str> = Vec::new();
-        let mut contents: Vec<Content> = Vec::new();
+        let (system_instruction, contents) = self.convert_messages(messages);
+        self.send_for_text(contents, system_instruction, model, temperature)
+            .await
+    }
 
-        for msg in messages {
-            match msg.role.as_str() {
-                "system" => {
-                    system_parts.push(&msg.content);
-                }
-                "user" => {
-                    contents.push(Content {
-                        role: Some("user".to_string()),
-                        parts: vec![Part {
-                            text: msg.content.clone(),
-                        }],
-                    });
-                }
-                "assistant" => {
-                    // Gemini API uses "model" role instead of "assistant"
-                    contents.push(Content {
-                        role: Some("model".to_string()),
-                        parts: vec![Part {
-                            text: msg.content.clone(),
-                        }],
-                    });
-                }
-                _ => {}
-            }
+    async fn chat(
+        &self,
+        request: ProviderChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ProviderChatResponse> {
+        // `responseSchema` cannot be combined with function calling, so a
+        // request carrying tools describes the schema in the prompt instead.
+        let has_tools = request.tools.is_some_and(|specs| !specs.is_empty());
+        let native_format = request.response_format.filter(|_| !has_tools);
+        let messages = structured::prompt_guided_messages(
+            request.messages,
+            request.response_format.filter(|_| has_tools),
+        );
+        let (system_instruction, contents) = self.convert_messages(&messages);
+        let tools = request
+            .tools
+            .filter(|specs| !specs.is_empty())
+            .map(|specs| {
+                vec![GeminiTool {
+                    function_declarations: Self::function_declarations(specs),
+                }]
+            });
+
+        let body = Self::generate_content_request(
+            contents,
+            system_instruction,
+            tools,
+            native_format,
+            request.reasoning.or(self.reasoning),
+            temperature,
+        );
+        let parts = self.send_generate_content(&body, model).await?;
+        let response = self.parse_chat_response(parts);
+        if response.text.is_none() && response.tool_calls.is_empty() {
+            anyhow::bail!("No response from Gemini");
         }
+        Ok(response)
+    }
 
-        let system_instruction = if system_parts.is_empty() {
-            None
-        } else {
-            Some(Content {
-                role: None,
-                parts: vec![Part {
-                    text: system_parts.join("\n\n"),
-                }],
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ProviderChatResponse> {
+        // Accept OpenAI-format tool JSON and reuse `chat()` for the native path.
+        let tool_specs: Vec<ToolSpec> = tools
+            .iter()
+            .filter_map(|t| {
+                let func = t.get("function").unwrap_or(t);
+                let name = func.get("name").and_then(|n| n.as_str()).or_else(|| {
+                    tracing::warn!("Skipping tool with missing or non-string 'name'");
+                    None
+                })?;
+                Some(ToolSpec {
+                    name: name.to_string(),
+                    description: func
+                        .get("description")
+                        .and_then(|d| d.as_str())
+                        .unwrap_or("")
+                        .to_string(),
+                    parameters: func
+                        .get("parameters")
+                        .cloned()
+                        .unwrap_or(serde_json::json!({"type": "object"})),
+                })
             })
+            .collect();
+
+        let request = ProviderChatRequest {
+            messages,
+            tools: Some(&tool_specs),
+            response_format: None,
+            reasoning: None,
         };
+        self.chat(request, model, temperature).await
+    }
 
-        self.send_generate_content(contents, system_instruction, model, temperature)
-            .await
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn stream_chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let mut messages = Vec::with_capacity(2);
+        if let Some(sys) = system_prompt {
+            messages.push(ChatMessage::system(sys));
+        }
+        messages.push(ChatMessage::user(message));
+        self.stream_chat_with_history(&messages, model, temperature, options)
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let Some(auth) = self.auth.as_ref() else {
+            return stream::once(async {
+                Err(StreamError::Provider(MISSING_AUTH_MESSAGE.to_string()))
+            })
+            .boxed();
+        };
+
+        let (system_instruction, contents) = self.convert_messages(messages);
+        let request = Self::generate_content_request(
+            contents,
+            system_instruction,
+            None,
+            None,
+            self.reasoning,
+            temperature,
+        );
+        let url = Self::build_stream_generate_content_url(model, auth);
+        let request_builder = self
+            .build_generate_content_request(auth, &url, &request, model)
+            .header(reqwest::header::ACCEPT, "text/event-stream");
+
+        let (tx, rx) = tokio::sync::mpsc::channel::<StreamResult<StreamChunk>>(100);
+
+        tokio::spawn(async move {
+            let response = match request_builder.send().await {
+                Ok(r) => r,
+                Err(e) => {
+                    let _ = tx.send(Err(StreamError::Http(e))).await;
+                    return;
+                }
+            };
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                let _ = tx
+                    .send(Err(StreamError::Provider(format!(
+                        "Gemini API error ({status}): {error_text}"
+                    ))))
+                    .await;
+                return;
+            }
+
+            // Buffer raw bytes so multi-byte characters split across network
+            // chunks are decoded only once the whole line has arrived.
+            let mut buffer: Vec<u8> = Vec::new();
+            let mut bytes_stream = response.bytes_stream();
+
+            while let Some(item) = bytes_stream.next().await {
+                let bytes = match item {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        let _ = tx.send(Err(StreamError::Http(e))).await;
+                        return;
+                    }
+                };
+                buffer.extend_from_slice(&bytes);
+
+                while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+                    let line: Vec<u8> = buffer.drain(..=pos).collect();
+                    match Self::parse_sse_line(&String::from_utf8_lossy(&line)) {
+                        Ok(Some(text)) => {
+                            let mut chunk = StreamChunk::delta(text);
+                            if options.count_tokens {
+                                chunk = chunk.with_token_estimate();
+                            }
+                            if tx.send(Ok(chunk)).await.is_err() {
+                                return; // Receiver dropped
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            let _ = tx.send(Err(e)).await;
+                            return;
+                        }
+                    }
+                }
+            }
+
+            let _ = tx.send(Ok(StreamChunk::final_chunk())).await;
+        });
+
+        stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|chunk| (chunk, rx))
+        })
+        .boxed()
     }
 
     async fn warmup(&self) -> anyhow::Result<()> {
@@ -565,23 +1200,19 @@ mod tests {
 
     #[test]
     fn auth_source_explicit_key() {
-        let provider = GeminiProvider {
-            auth: Some(GeminiAuth::ExplicitKey("key".into())),
-        };
+        let provider = GeminiProvider::with_auth(Some(GeminiAuth::ExplicitKey("key".into())));
         assert_eq!(provider.auth_source(), "config");
     }
 
     #[test]
     fn auth_source_none_without_credentials() {
-        let provider = GeminiProvider { auth: None };
+        let provider = GeminiProvider::with_auth(None);
         assert_eq!(provider.auth_source(), "none");
     }
 
     #[test]
     fn auth_source_oauth() {
-        let provider = GeminiProvider {
-            auth: Some(GeminiAuth::OAuthToken("ya29.mock".into())),
-        };
+        let provider = GeminiProvider::with_auth(Some(GeminiAuth::OAuthToken("ya29.mock".into())));
         assert_eq!(provider.auth_source(), "Gemini CLI OAuth");
     }
 
@@ -632,22 +1263,25 @@ mod tests {
 
     #[test]
     fn oauth_request_uses_bearer_auth_header() {
-        let provider = GeminiProvider {
-            auth: Some(GeminiAuth::OAuthToken("ya29.mock-token".into())),
-        };
+        let provider =
+            GeminiProvider::with_auth(Some(GeminiAuth::OAuthToken("ya29.mock-token".into())));
         let auth = GeminiAuth::OAuthToken("ya29.mock-token".into());
         let url = GeminiProvider::build_generate_content_url("gemini-2.0-flash", &auth);
         let body = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".into()),
-                parts: vec![Part {
+                parts: vec![Part::Text {
                     text: "hello".into(),
                 }],
             }],
             system_instruction: None,
+            tools: None,
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
+                response_mime_type: None,
+                response_schema: None,
+                thinking_config: None,
             },
         };
 
@@ -667,22 +1301,25 @@ mod tests {
 
     #[test]
     fn oauth_request_wraps_payload_in_request_envelope() {
-        let provider = GeminiProvider {
-            auth: Some(GeminiAuth::OAuthToken("ya29.mock-token".into())),
-        };
+        let provider =
+            GeminiProvider::with_auth(Some(GeminiAuth::OAuthToken("ya29.mock-token".into())));
         let auth = GeminiAuth::OAuthToken("ya29.mock-token".into());
         let url = GeminiProvider::build_generate_content_url("gemini-2.0-flash", &auth);
         let body = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".into()),
-                parts: vec![Part {
+                parts: vec![Part::Text {
                     text: "hello".into(),
                 }],
             }],
             system_instruction: None,
+            tools: None,
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
+                response_mime_type: None,
+                response_schema: None,
+                thinking_config: None,
             },
         };
 
@@ -705,22 +1342,25 @@ mod tests {
 
     #[test]
     fn api_key_request_does_not_set_bearer_header() {
-        let provider = GeminiProvider {
-            auth: Some(GeminiAuth::ExplicitKey("api-key-123".into())),
-        };
+        let provider =
+            GeminiProvider::with_auth(Some(GeminiAuth::ExplicitKey("api-key-123".into())));
         let auth = GeminiAuth::ExplicitKey("api-key-123".into());
         let url = GeminiProvider::build_generate_content_url("gemini-2.0-flash", &auth);
         let body = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".into()),
-                parts: vec![Part {
+                parts: vec![Part::Text {
                     text: "hello".into(),
                 }],
             }],
             system_instruction: None,
+            tools: None,
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
+                response_mime_type: None,
+                response_schema: None,
+                thinking_config: None,
             },
         };
 
@@ -737,19 +1377,23 @@ mod tests {
         let request = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".to_string()),
-                parts: vec![Part {
+                parts: vec![Part::Text {
                     text: "Hello".to_string(),
                 }],
             }],
             system_instruction: Some(Content {
                 role: None,
-                parts: vec![Part {
+                parts: vec![Part::Text {
                     text: "You are helpful".to_string(),
                 }],
             }),
+            tools: None,
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
+                response_mime_type: None,
+                response_schema: None,
+                thinking_config: None,
             },
         };
 
@@ -771,14 +1415,18 @@ mod tests {
             request: InternalGenerateContentRequest {
                 contents: vec![Content {
                     role: Some("user".to_string()),
-                    parts: vec![Part {
+                    parts: vec![Part::Text {
                         text: "Hello".to_string(),
                     }],
                 }],
                 system_instruction: None,
+                tools: None,
                 generation_config: GenerationConfig {
                     temperature: 0.7,
                     max_output_tokens: 8192,
+                    response_mime_type: None,
+                    response_schema: None,
+                    thinking_config: None,
                 },
             },
         };
@@ -864,17 +1512,337 @@ mod tests {
 
     #[tokio::test]
     async fn warmup_without_key_is_noop() {
-        let provider = GeminiProvider { auth: None };
+        let provider = GeminiProvider::with_auth(None);
         let result = provider.warmup().await;
         assert!(result.is_ok());
     }
 
     #[tokio::test]
     async fn warmup_oauth_is_noop() {
-        let provider = GeminiProvider {
-            auth: Some(GeminiAuth::OAuthToken("ya29.mock-token".into())),
-        };
+        let provider =
+            GeminiProvider::with_auth(Some(GeminiAuth::OAuthToken("ya29.mock-token".into())));
         let result = provider.warmup().await;
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn capabilities_include_native_tools_and_vision() {
+        let provider = GeminiProvider::with_auth(None);
+        let caps = <GeminiProvider as Provider>::capabilities(&provider);
+        assert!(caps.native_tool_calling);
+        assert!(caps.vision);
+        assert!(provider.supports_streaming());
+    }
+
+    #[test]
+    fn convert_tools_emits_cleaned_function_declarations() {
+        let provider = GeminiProvider::with_auth(None);
+        let tools = vec![
+            ToolSpec {
+                name: "shell".into(),
+                description: "Run a command".into(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "additionalProperties": false,
+                    "properties": {"command": {"type": "string"}},
+                    "required": ["command"]
+                }),
+            },
+            ToolSpec {
+                name: "now".into(),
+                description: "Current time".into(),
+                parameters: serde_json::json!({"type": "object", "properties": {}}),
+            },
+        ];
+
+        let ToolsPayload::Gemini {
+            function_declarations,
+        } = provider.convert_tools(&tools)
+        else {
+            panic!("expected Gemini payload");
+        };
+        assert_eq!(function_declarations.len(), 2);
+        assert_eq!(function_declarations[0]["name"], "shell");
+        assert!(function_declarations[0]["parameters"]
+            .get("additionalProperties")
+            .is_none());
+        assert_eq!(
+            function_declarations[0]["parameters"]["properties"]["command"]["type"],
+            "string"
+        );
+        assert!(function_declarations[1].get("parameters").is_none());
+    }
+
+    #[test]
+    fn user_parts_inline_data_uri_images() {
+        let parts = GeminiProvider::user_parts(
+            "What is this? [IMAGE:data:image/png;base64,iVBORw0KGgo=] [IMAGE:/tmp/cat.jpg]",
+        );
+        let json = serde_json::to_value(&parts).unwrap();
+        assert_eq!(json[0]["text"], "What is this?");
+        assert_eq!(json[1]["inlineData"]["mimeType"], "image/png");
+        assert_eq!(json[1]["inlineData"]["data"], "iVBORw0KGgo=");
+        assert_eq!(json[2]["text"], "[IMAGE:/tmp/cat.jpg]");
+    }
+
+    #[test]
+    fn convert_messages_round_trips_tool_calls_and_results() {
+        let provider = GeminiProvider::with_auth(None);
+        provider
+            .thought_signatures
+            .lock()
+            .insert("call_1".into(), "sig-abc".into());
+        let messages = vec![
+            ChatMessage::system("be brief"),
+            ChatMessage::user("list files and check the date"),
+            ChatMessage::assistant(
+                serde_json::json!({
+                    "content": "Checking.",
+                    "tool_calls": [
+                        {"id": "call_1", "name": "shell", "arguments": "{\"command\":\"ls\"}"},
+                        {"id": "call_2", "name": "now", "arguments": ""}
+                    ]
+                })
+                .to_string(),
+            ),
+            ChatMessage::tool(
+                serde_json::json!({"tool_call_id": "call_1", "content": "a.txt"}).to_string(),
+            ),
+            ChatMessage::tool(
+                serde_json::json!({"tool_call_id": "call_2", "content": "Monday"}).to_string(),
+            ),
+        ];
+
+        let (system, contents) = provider.convert_messages(&messages);
+        assert!(system.is_some());
+        let json = serde_json::to_value(&contents).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 3);
+
+        let model = &json[1];
+        assert_eq!(model["role"], "model");
+        assert_eq!(model["parts"][0]["text"], "Checking.");
+        assert_eq!(model["parts"][1]["functionCall"]["name"], "shell");
+        assert_eq!(model["parts"][1]["functionCall"]["args"]["command"], "ls");
+        assert_eq!(model["parts"][1]["thoughtSignature"], "sig-abc");
+        assert_eq!(
+            model["parts"][2]["functionCall"]["args"],
+            serde_json::json!({})
+        );
+        assert!(model["parts"][2].get("thoughtSignature").is_none());
+
+        let responses = &json[2];
+        assert_eq!(responses["role"], "user");
+        assert_eq!(responses["parts"][0]["functionResponse"]["name"], "shell");
+        assert_eq!(
+            responses["parts"][0]["functionResponse"]["response"]["content"],
+            "a.txt"
+        );
+        assert_eq!(responses["parts"][1]["functionResponse"]["name"], "now");
+    }
+
+    #[test]
+    fn parse_chat_response_extracts_function_calls_and_signatures() {
+        let provider = GeminiProvider::with_auth(None);
+        let json = r#"{
+            "candidates": [{
+                "content": {
+                    "parts": [
+                        {"text": "thinking...", "thought": true},
+                        {"text": "Let me look."},
+                        {"functionCall": {"name": "shell", "args": {"command": "ls"}}, "thoughtSignature": "sig-1"}
+                    ]
+                }
+            }]
+        }"#;
+        let response: GenerateContentResponse = serde_json::from_str(json).unwrap();
+        let parsed = provider.parse_chat_response(response.into_parts());
+
+        assert_eq!(parsed.text.as_deref(), Some("Let me look."));
+        assert_eq!(parsed.tool_calls.len(), 1);
+        let call = &parsed.tool_calls[0];
+        assert_eq!(call.name, "shell");
+        assert!(call.id.starts_with("call_"));
+        assert_eq!(call.arguments, r#"{"command":"ls"}"#);
+        assert_eq!(
+            provider
+                .thought_signatures
+                .lock()
+                .get(&call.id)
+                .map(String::as_str),
+            Some("sig-1")
+        );
+    }
+
+    #[test]
+    fn oauth_request_envelope_carries_tools() {
+        let provider = GeminiProvider::with_auth(Some(GeminiAuth::OAuthToken("ya29.t".into())));
+        let auth = GeminiAuth::OAuthToken("ya29.t".into());
+        let url = GeminiProvider::build_generate_content_url("gemini-2.5-pro", &auth);
+        let body = GeminiProvider::generate_content_request(
+            vec![Content {
+                role: Some("user".into()),
+                parts: vec![Part::text("hi")],
+            }],
+            None,
+            Some(vec![GeminiTool {
+                function_declarations: vec![serde_json::json!({"name": "shell"})],
+            }]),
+            None,
+            None,
+            0.2,
+        );
+
+        let request = provider
+            .build_generate_content_request(&auth, &url, &body, "gemini-2.5-pro")
+            .build()
+            .unwrap();
+        let json: serde_json::Value =
+            serde_json::from_slice(request.body().and_then(|b| b.as_bytes()).unwrap()).unwrap();
+        assert_eq!(
+            json["request"]["tools"][0]["functionDeclarations"][0]["name"],
+            "shell"
+        );
+    }
+
+    #[test]
+    fn stream_urls_request_sse_for_both_auth_paths() {
+        let key = GeminiAuth::ExplicitKey("k".into());
+        assert_eq!(
+            GeminiProvider::build_stream_generate_content_url("gemini-2.0-flash", &key),
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.0-flash:streamGenerateContent?alt=sse&key=k"
+        );
+        let oauth = GeminiAuth::OAuthToken("ya29.t".into());
+        assert_eq!(
+            GeminiProvider::build_stream_generate_content_url("gemini-2.0-flash", &oauth),
+            "https://cloudcode-pa.googleapis.com/v1internal:streamGenerateContent?alt=sse"
+        );
+    }
+
+    #[test]
+    fn parse_sse_line_handles_public_and_internal_payloads() {
+        let public = r#"data: {"candidates":[{"content":{"parts":[{"text":"Hel"}]}}]}"#;
+        assert_eq!(
+            GeminiProvider::parse_sse_line(public).unwrap().as_deref(),
+            Some("Hel")
+        );
+
+        let internal =
+            r#"data: {"response":{"candidates":[{"content":{"parts":[{"text":"lo"}]}}]}}"#;
+        assert_eq!(
+            GeminiProvider::parse_sse_line(internal).unwrap().as_deref(),
+            Some("lo")
+        );
+
+        let finish = r#"data: {"candidates":[{"finishReason":"STOP"}]}"#;
+        assert!(GeminiProvider::parse_sse_line(finish).unwrap().is_none());
+        assert!(GeminiProvider::parse_sse_line("").unwrap().is_none());
+
+        let error = r#"data: {"error":{"message":"quota exceeded"}}"#;
+        assert!(matches!(
+            GeminiProvider::parse_sse_line(error),
+            Err(StreamError::Provider(msg)) if msg.contains("quota exceeded")
+        ));
+    }
+
+    #[tokio::test]
+    async fn stream_without_auth_yields_error() {
+        let provider = GeminiProvider::with_auth(None);
+        let mut stream = provider.stream_chat_with_system(
+            None,
+            "hi",
+            "gemini-2.0-flash",
+            0.7,
+            StreamOptions::new(true),
+        );
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(StreamError::Provider(_)))
+        ));
+    }
+
+    #[test]
+    fn response_format_sets_json_mime_type_and_cleaned_schema() {
+        let format = ResponseFormat::json_schema(
+            "verdict",
+            serde_json::json!({
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {"spam": {"type": "boolean"}}
+            }),
+        );
+        let request = GeminiProvider::generate_content_request(
+            Vec::new(),
+            None,
+            None,
+            Some(&format),
+            None,
+            0.0,
+        );
+        let json = serde_json::to_value(&request).unwrap();
+        let config = &json["generationConfig"];
+        assert_eq!(config["responseMimeType"], "application/json");
+        assert_eq!(
+            config["responseSchema"]["properties"]["spam"]["type"],
+            "boolean"
+        );
+        assert!(config["responseSchema"]
+            .get("additionalProperties")
+            .is_none());
+    }
+
+    #[test]
+    fn reasoning_level_maps_to_thinking_budget() {
+        let high = GeminiProvider::generate_content_request(
+            Vec::new(),
+            None,
+            None,
+            None,
+            Some(ReasoningLevel::Budget(4096)),
+            0.0,
+        );
+        let json = serde_json::to_value(&high).unwrap();
+        let thinking = &json["generationConfig"]["thinkingConfig"];
+        assert_eq!(thinking["thinkingBudget"], 4096);
+        assert_eq!(thinking["includeThoughts"], true);
+
+        let off = GeminiProvider::generate_content_request(
+            Vec::new(),
+            None,
+            None,
+            None,
+            Some(ReasoningLevel::OFF),
+            0.0,
+        );
+        let json = serde_json::to_value(&off).unwrap();
+        assert_eq!(
+            json["generationConfig"]["thinkingConfig"]["thinkingBudget"],
+            0
+        );
+        assert_eq!(
+            json["generationConfig"]["thinkingConfig"]["includeThoughts"],
+            false
+        );
+    }
+
+    #[test]
+    fn parse_chat_response_collects_thought_summaries_as_reasoning() {
+        let provider = GeminiProvider::with_auth(None);
+        let json = r#"{
+            "candidates": [{
+                "content": {
+                    "parts": [
+                        {"text": "Weighing both options.", "thought": true},
+                        {"text": "Option B."}
+                    ]
+                }
+            }]
+        }"#;
+        let response: GenerateContentResponse = serde_json::from_str(json).unwrap();
+        let parsed = provider.parse_chat_response(response.into_parts());
+        assert_eq!(parsed.text.as_deref(), Some("Option B."));
+        assert_eq!(
+            parsed.reasoning_text().as_deref(),
+            Some("Weighing both options.")
+        );
+    }
 }
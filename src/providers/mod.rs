@@ -20,6 +20,7 @@
 
 pub mod anthropic;
 pub mod bedrock;
+pub mod cassette;
 pub mod compatible;
 pub mod copilot;
 pub mod gemini;
@@ -27,17 +28,21 @@ pub mod ollama;
 pub mod openai;
 pub mod openai_codex;
 pub mod openrouter;
+pub mod reasoning;
 pub mod reliable;
 pub mod router;
+pub mod structured;
 pub mod traits;
 
 #[cfg(feature = "ai-protocol")]
 pub mod protocol_adapter;
 
+#[allow(unused_imports)]
+pub use reasoning::{ReasoningEffort, ReasoningLevel};
 #[allow(unused_imports)]
 pub use traits::{
-    ChatMessage, ChatRequest, ChatResponse, ConversationMessage, Provider, ProviderCapabilityError,
-    ToolCall, ToolResultMessage,
+    ChatMessage, ChatRequest, ChatResponse, ChatUsage, ConversationMessage, Provider,
+    ProviderCapabilityError, ReasoningBlock, ResponseFormat, ToolCall, ToolResultMessage,
 };
 
 use compatible::{AuthStyle, OpenAiCompatibleProvider};
@@ -697,7 +702,10 @@ pub struct ProviderRuntimeOptions {
     pub auth_profile_override: Option<String>,
     pub zeroclaw_dir: Option<PathBuf>,
     pub secrets_encrypt: bool,
-    pub reasoning_enabled: Option<bool>,
+    /// Default reasoning level for providers that expose a reasoning control.
+    pub reasoning: Option<ReasoningLevel>,
+    /// Record/replay settings applied by the resilient and routed factories.
+    pub cassette: crate::config::CassetteConfig,
 }
 
 impl Default for ProviderRuntimeOptions {
@@ -706,7 +714,8 @@ impl Default for ProviderRuntimeOptions {
             auth_profile_override: None,
             zeroclaw_dir: None,
             secrets_encrypt: true,
-            reasoning_enabled: None,
+            reasoning: None,
+            cassette: crate::config::CassetteConfig::default(),
         }
     }
 }
@@ -1025,17 +1034,21 @@ fn create_provider_with_url_and_options(
     match name {
         // ── Primary providers (custom implementations) ───────
         "openrouter" => Ok(Box::new(openrouter::OpenRouterProvider::new(key))),
-        "anthropic" => Ok(Box::new(anthropic::AnthropicProvider::new(key))),
-        "openai" => Ok(Box::new(openai::OpenAiProvider::with_base_url(api_url, key))),
+        "anthropic" => Ok(Box::new(
+            anthropic::AnthropicProvider::new(key).with_reasoning(options.reasoning),
+        )),
+        "openai" => Ok(Box::new(
+            openai::OpenAiProvider::with_base_url(api_url, key).with_reasoning(options.reasoning),
+        )),
         // Ollama uses api_url for custom base URL (e.g. remote Ollama instance)
         "ollama" => Ok(Box::new(ollama::OllamaProvider::new_with_reasoning(
             api_url,
             key,
-            options.reasoning_enabled,
+            options.reasoning.map(ReasoningLevel::is_enabled),
         ))),
-        "gemini" | "google" | "google-gemini" => {
-            Ok(Box::new(gemini::GeminiProvider::new(key)))
-        }
+        "gemini" | "google" | "google-gemini" => Ok(Box::new(
+            gemini::GeminiProvider::new(key).with_reasoning(options.reasoning),
+        )),
 
         // ── OpenAI-compatible providers ──────────────────────
         "venice" => Ok(Box::new(OpenAiCompatibleProvider::new(
@@ -1224,10 +1237,10 @@ fn create_provider_with_url_and_options(
                 "Anthropic-custom provider",
                 "anthropic-custom:https://your-api.com",
             )?;
-            Ok(Box::new(anthropic::AnthropicProvider::with_base_url(
-                key,
-                Some(&base_url),
-            )))
+            Ok(Box::new(
+                anthropic::AnthropicProvider::with_base_url(key, Some(&base_url))
+                    .with_reasoning(options.reasoning),
+            ))
         }
 
         _ => anyhow::bail!(
@@ -1262,6 +1275,18 @@ pub fn create_resilient_provider_with_options(
     api_url: Option<&str>,
     reliability: &crate::config::ReliabilityConfig,
     options: &ProviderRuntimeOptions,
+) -> anyhow::Result<Box<dyn Provider>> {
+    cassette::wrap_provider(&options.cassette, || {
+        build_resilient_provider(primary_name, api_key, api_url, reliability, options)
+    })
+}
+
+fn build_resilient_provider(
+    primary_name: &str,
+    api_key: Option<&str>,
+    api_url: Option<&str>,
+    reliability: &crate::config::ReliabilityConfig,
+    options: &ProviderRuntimeOptions,
 ) -> anyhow::Result<Box<dyn Provider>> {
     let mut providers: Vec<(String, Box<dyn Provider>)> = Vec::new();
 
@@ -1304,7 +1329,8 @@ pub fn create_resilient_provider_with_options(
         reliability.provider_backoff_ms,
     )
     .with_api_keys(reliability.api_keys.clone())
-    .with_model_fallbacks(reliability.model_fallbacks.clone());
+    .with_model_fallbacks(reliability.model_fallbacks.clone())
+    .with_structured_output_repairs(reliability.structured_output_repairs);
 
     Ok(Box::new(reliable))
 }
@@ -1341,14 +1367,31 @@ pub fn create_routed_provider_with_options(
     default_model: &str,
     options: &ProviderRuntimeOptions,
 ) -> anyhow::Result<Box<dyn Provider>> {
-    if model_routes.is_empty() {
-        return create_resilient_provider_with_options(
+    // Record or replay at the router, so one cassette covers every route.
+    cassette::wrap_provider(&options.cassette, || {
+        build_routed_provider(
             primary_name,
             api_key,
             api_url,
             reliability,
+            model_routes,
+            default_model,
             options,
-        );
+        )
+    })
+}
+
+fn build_routed_provider(
+    primary_name: &str,
+    api_key: Option<&str>,
+    api_url: Option<&str>,
+    reliability: &crate::config::ReliabilityConfig,
+    model_routes: &[crate::config::ModelRouteConfig],
+    default_model: &str,
+    options: &ProviderRuntimeOptions,
+) -> anyhow::Result<Box<dyn Provider>> {
+    if model_routes.is_empty() {
+        return build_resilient_provider(primary_name, api_key, api_url, reliability, options);
     }
 
     // Collect unique provider names needed
@@ -1374,7 +1417,7 @@ pub fn create_routed_provider_with_options(
         let key = routed_credential.or(api_key);
         // Only use api_url for the primary provider
         let url = if name == primary_name { api_url } else { None };
-        match create_resilient_provider_with_options(name, key, url, reliability, options) {
+        match build_resilient_provider(name, key, url, reliability, options) {
             Ok(provider) => providers.push((name.clone(), provider)),
             Err(e) => {
                 if name == primary_name {
@@ -1397,6 +1440,7 @@ pub fn create_routed_provider_with_options(
                 router::Route {
                     provider_name: r.provider.clone(),
                     model: r.model.clone(),
+                    reasoning: r.reasoning,
                 },
             )
         })
@@ -2338,6 +2382,7 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            structured_output_repairs: 2,
         };
 
         let provider = create_resilient_provider(
@@ -2377,6 +2422,7 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            structured_output_repairs: 2,
         };
 
         // Primary uses a ZAI key; fallbacks (lmstudio, ollama) should NOT
@@ -2399,6 +2445,7 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            structured_output_repairs: 2,
         };
 
         let provider =
@@ -2425,6 +2472,7 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            structured_output_repairs: 2,
         };
 
         let provider = create_resilient_provider("zai", Some("zai-test-key"), None, &reliability);
@@ -1,6 +1,6 @@
 use crate::multimodal;
 use crate::providers::traits::{
-    ChatMessage, ChatResponse, Provider, ProviderCapabilities, ToolCall,
+    ChatMessage, ChatResponse, Provider, ProviderCapabilities, ReasoningBlock, ToolCall,
 };
 use async_trait::async_trait;
 use reqwest::Client;
@@ -25,6 +25,9 @@ struct ChatRequest {
     think: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<serde_json::Value>>,
+    /// JSON schema constraining the reply (structured output).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize)]
@@ -178,6 +181,7 @@ impl OllamaProvider {
             options: Options { temperature },
             think: self.reasoning_enabled,
             tools: tools.map(|t| t.to_vec()),
+            format: None,
         }
     }
 
@@ -308,6 +312,109 @@ impl OllamaProvider {
             .collect()
     }
 
+    /// Native `/api/chat` round trip returning structured tool calls.
+    /// `format` carries a JSON schema for structured output; `think` overrides
+    /// the configured reasoning toggle for this call.
+    async fn chat_native(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        format: Option<&serde_json::Value>,
+        think: Option<bool>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let (normalized_model, should_auth) = self.resolve_request_details(model)?;
+
+        let api_messages = self.convert_messages(messages);
+
+        // Tools arrive pre-formatted in OpenAI/Ollama-compatible JSON from
+        // tools_to_openai_format() in loop_.rs — pass them through directly.
+        let tools_opt = if tools.is_empty() { None } else { Some(tools) };
+
+        let response = self
+            .send_request(
+                api_messages,
+                &normalized_model,
+                temperature,
+                should_auth,
+                tools_opt,
+                format,
+                think,
+            )
+            .await?;
+
+        let reasoning: Vec<ReasoningBlock> = response
+            .message
+            .thinking
+            .iter()
+            .filter(|thinking| !thinking.trim().is_empty())
+            .map(|thinking| ReasoningBlock {
+                text: thinking.clone(),
+                ..ReasoningBlock::default()
+            })
+            .collect();
+
+        // Native tool calls returned by the model.
+        if !response.message.tool_calls.is_empty() {
+            let tool_calls: Vec<ToolCall> = response
+                .message
+                .tool_calls
+                .iter()
+                .map(|tc| {
+                    let (name, args) = self.extract_tool_name_and_args(tc);
+                    ToolCall {
+                        id: tc
+                            .id
+                            .clone()
+                            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+                        name,
+                        arguments: serde_json::to_string(&args)
+                            .unwrap_or_else(|_| "{}".to_string()),
+                    }
+                })
+                .collect();
+            let text = if response.message.content.is_empty() {
+                None
+            } else {
+                Some(response.message.content)
+            };
+            return Ok(ChatResponse {
+                text,
+                tool_calls,
+                reasoning,
+                usage: None,
+            });
+        }
+
+        // Plain text response.
+        let content = response.message.content;
+        if content.is_empty() {
+            if let Some(thinking) = &response.message.thinking {
+                tracing::warn!(
+                    "Ollama returned empty content with only thinking: '{}'. Model may have stopped prematurely.",
+                    if thinking.len() > 100 { &thinking[..100] } else { thinking }
+                );
+                return Ok(ChatResponse {
+                    text: Some(format!(
+                        "I was thinking about this: {}... but I didn't complete my response. Could you try asking again?",
+                        if thinking.len() > 200 { &thinking[..200] } else { thinking }
+                    )),
+                    tool_calls: vec![],
+                    reasoning,
+                    usage: None,
+                });
+            }
+            tracing::warn!("Ollama returned empty content with no tool calls");
+        }
+        Ok(ChatResponse {
+            text: Some(content),
+            tool_calls: vec![],
+            reasoning,
+            usage: None,
+        })
+    }
+
     /// Send a request to Ollama and get the parsed response.
     /// Pass `tools` to enable native function-calling for models that support it.
     async fn send_request(
@@ -317,8 +424,14 @@ impl OllamaProvider {
         temperature: f64,
         should_auth: bool,
         tools: Option<&[serde_json::Value]>,
+        format: Option<&serde_json::Value>,
+        think: Option<bool>,
     ) -> anyhow::Result<ApiChatResponse> {
-        let request = self.build_chat_request(messages, model, temperature, tools);
+        let mut request = self.build_chat_request(messages, model, temperature, tools);
+        request.format = format.cloned();
+        if think.is_some() {
+            request.think = think;
+        }
 
         let url = format!("{}/api/chat", self.base_url);
 
@@ -490,7 +603,15 @@ impl Provider for OllamaProvider {
         });
 
         let response = self
-            .send_request(messages, &normalized_model, temperature, should_auth, None)
+            .send_request(
+                messages,
+                &normalized_model,
+                temperature,
+                should_auth,
+                None,
+                None,
+                None,
+            )
             .await?;
 
         // If model returned tool calls, format them for loop_.rs's parse_tool_calls
@@ -540,6 +661,8 @@ impl Provider for OllamaProvider {
                 temperature,
                 should_auth,
                 None,
+                None,
+                None,
             )
             .await?;
 
@@ -582,73 +705,8 @@ impl Provider for OllamaProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
-        let (normalized_model, should_auth) = self.resolve_request_details(model)?;
-
-        let api_messages = self.convert_messages(messages);
-
-        // Tools arrive pre-formatted in OpenAI/Ollama-compatible JSON from
-        // tools_to_openai_format() in loop_.rs — pass them through directly.
-        let tools_opt = if tools.is_empty() { None } else { Some(tools) };
-
-        let response = self
-            .send_request(
-                api_messages,
-                &normalized_model,
-                temperature,
-                should_auth,
-                tools_opt,
-            )
-            .await?;
-
-        // Native tool calls returned by the model.
-        if !response.message.tool_calls.is_empty() {
-            let tool_calls: Vec<ToolCall> = response
-                .message
-                .tool_calls
-                .iter()
-                .map(|tc| {
-                    let (name, args) = self.extract_tool_name_and_args(tc);
-                    ToolCall {
-                        id: tc
-                            .id
-                            .clone()
-                            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
-                        name,
-                        arguments: serde_json::to_string(&args)
-                            .unwrap_or_else(|_| "{}".to_string()),
-                    }
-                })
-                .collect();
-            let text = if response.message.content.is_empty() {
-                None
-            } else {
-                Some(response.message.content)
-            };
-            return Ok(ChatResponse { text, tool_calls });
-        }
-
-        // Plain text response.
-        let content = response.message.content;
-        if content.is_empty() {
-            if let Some(thinking) = &response.message.thinking {
-                tracing::warn!(
-                    "Ollama returned empty content with only thinking: '{}'. Model may have stopped prematurely.",
-                    if thinking.len() > 100 { &thinking[..100] } else { thinking }
-                );
-                return Ok(ChatResponse {
-                    text: Some(format!(
-                        "I was thinking about this: {}... but I didn't complete my response. Could you try asking again?",
-                        if thinking.len() > 200 { &thinking[..200] } else { thinking }
-                    )),
-                    tool_calls: vec![],
-                });
-            }
-            tracing::warn!("Ollama returned empty content with no tool calls");
-        }
-        Ok(ChatResponse {
-            text: Some(content),
-            tool_calls: vec![],
-        })
+        self.chat_native(messages, tools, None, None, model, temperature)
+            .await
     }
 
     fn supports_native_tools(&self) -> bool {
@@ -681,11 +739,31 @@ impl Provider for OllamaProvider {
                     })
                     .collect();
                 return self
-                    .chat_with_tools(request.messages, &tools, model, temperature)
+                    .chat_native(
+                        request.messages,
+                        &tools,
+                        request.response_format.map(|f| &f.schema),
+                        request.reasoning.map(|level| level.is_enabled()),
+                        model,
+                        temperature,
+                    )
                     .await;
             }
         }
 
+        if request.response_format.is_some() || request.reasoning.is_some() {
+            return self
+                .chat_native(
+                    request.messages,
+                    &[],
+                    request.response_format.map(|f| &f.schema),
+                    request.reasoning.map(|level| level.is_enabled()),
+                    model,
+                    temperature,
+                )
+                .await;
+        }
+
         // No tools — fall back to plain text chat.
         let text = self
             .chat_with_history(request.messages, model, temperature)
@@ -693,6 +771,8 @@ impl Provider for OllamaProvider {
         Ok(ChatResponse {
             text: Some(text),
             tool_calls: vec![],
+            reasoning: Vec::new(),
+            usage: None,
         })
     }
 }
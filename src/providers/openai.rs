@@ -1,6 +1,8 @@
+use crate::providers::reasoning::{ReasoningEffort, ReasoningLevel};
+use crate::providers::structured;
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, ToolCall as ProviderToolCall,
+    ChatUsage, Provider, ReasoningBlock, ToolCall as ProviderToolCall,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
@@ -10,6 +12,7 @@ use serde::{Deserialize, Serialize};
 pub struct OpenAiProvider {
     base_url: String,
     credential: Option<String>,
+    reasoning: Option<ReasoningLevel>,
 }
 
 #[derive(Debug, Serialize)]
@@ -17,6 +20,8 @@ struct ChatRequest {
     model: String,
     messages: Vec<Message>,
     temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<&'static str>,
 }
 
 #[derive(Debug, Serialize)]
@@ -62,6 +67,10 @@ struct NativeChatRequest {
     tools: Option<Vec<NativeToolSpec>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_choice: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<&'static str>,
 }
 
 #[derive(Debug, Serialize)]
@@ -121,6 +130,25 @@ struct NativeFunctionCall {
 #[derive(Debug, Deserialize)]
 struct NativeChatResponse {
     choices: Vec<NativeChoice>,
+    #[serde(default)]
+    usage: Option<NativeUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeUsage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
+}
+
+impl NativeUsage {
+    fn into_chat_usage(self) -> ChatUsage {
+        ChatUsage {
+            input_tokens: self.prompt_tokens,
+            output_tokens: self.completion_tokens,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -146,6 +174,20 @@ impl NativeResponseMessage {
             _ => self.reasoning_content.clone(),
         }
     }
+
+    /// `reasoning_content` alongside a real answer is kept as reasoning; when
+    /// it stands in for empty content it is already the reply text.
+    fn reasoning(&self) -> Vec<ReasoningBlock> {
+        let has_content = self.content.as_ref().is_some_and(|c| !c.is_empty());
+        self.reasoning_content
+            .iter()
+            .filter(|text| has_content && !text.trim().is_empty())
+            .map(|text| ReasoningBlock {
+                text: text.clone(),
+                ..ReasoningBlock::default()
+            })
+            .collect()
+    }
 }
 
 impl OpenAiProvider {
@@ -161,6 +203,22 @@ impl OpenAiProvider {
                 .map(|u| u.trim_end_matches('/').to_string())
                 .unwrap_or_else(|| "https://api.openai.com/v1".to_string()),
             credential: credential.map(ToString::to_string),
+            reasoning: None,
+        }
+    }
+
+    /// Default reasoning level, sent as `reasoning_effort`.
+    pub fn with_reasoning(mut self, reasoning: Option<ReasoningLevel>) -> Self {
+        self.reasoning = reasoning;
+        self
+    }
+
+    /// OpenAI reasoning models cannot switch reasoning off entirely, so `off`
+    /// leaves the parameter out and keeps the model default.
+    fn reasoning_effort(reasoning: Option<ReasoningLevel>) -> Option<&'static str> {
+        match reasoning?.effort() {
+            ReasoningEffort::Off => None,
+            effort => Some(effort.as_str()),
         }
     }
 
@@ -249,6 +307,7 @@ impl OpenAiProvider {
 
     fn parse_native_response(message: NativeResponseMessage) -> ProviderChatResponse {
         let text = message.effective_content();
+        let reasoning = message.reasoning();
         let tool_calls = message
             .tool_calls
             .unwrap_or_default()
@@ -260,7 +319,12 @@ impl OpenAiProvider {
             })
             .collect::<Vec<_>>();
 
-        ProviderChatResponse { text, tool_calls }
+        ProviderChatResponse {
+            text,
+            tool_calls,
+            reasoning,
+            usage: None,
+        }
     }
 
     fn http_client(&self) -> Client {
@@ -299,6 +363,7 @@ impl Provider for OpenAiProvider {
             model: model.to_string(),
             messages,
             temperature,
+            reasoning_effort: Self::reasoning_effort(self.reasoning),
         };
 
         let response = self
@@ -340,6 +405,10 @@ impl Provider for OpenAiProvider {
             temperature,
             tool_choice: tools.as_ref().map(|_| "auto".to_string()),
             tools,
+            response_format: request
+                .response_format
+                .map(structured::openai_response_format),
+            reasoning_effort: Self::reasoning_effort(request.reasoning.or(self.reasoning)),
         };
 
         let response = self
@@ -355,13 +424,16 @@ impl Provider for OpenAiProvider {
         }
 
         let native_response: NativeChatResponse = response.json().await?;
+        let usage = native_response.usage.map(NativeUsage::into_chat_usage);
         let message = native_response
             .choices
             .into_iter()
             .next()
             .map(|c| c.message)
             .ok_or_else(|| anyhow::anyhow!("No response from OpenAI"))?;
-        Ok(Self::parse_native_response(message))
+        let mut parsed = Self::parse_native_response(message);
+        parsed.usage = usage;
+        Ok(parsed)
     }
 
     fn supports_native_tools(&self) -> bool {
@@ -397,6 +469,8 @@ impl Provider for OpenAiProvider {
             temperature,
             tool_choice: native_tools.as_ref().map(|_| "auto".to_string()),
             tools: native_tools,
+            response_format: None,
+            reasoning_effort: Self::reasoning_effort(self.reasoning),
         };
 
         let response = self
@@ -412,13 +486,16 @@ impl Provider for OpenAiProvider {
         }
 
         let native_response: NativeChatResponse = response.json().await?;
+        let usage = native_response.usage.map(NativeUsage::into_chat_usage);
         let message = native_response
             .choices
             .into_iter()
             .next()
             .map(|c| c.message)
             .ok_or_else(|| anyhow::anyhow!("No response from OpenAI"))?;
-        Ok(Self::parse_native_response(message))
+        let mut parsed = Self::parse_native_response(message);
+        parsed.usage = usage;
+        Ok(parsed)
     }
 
     async fn warmup(&self) -> anyhow::Result<()> {
@@ -488,6 +565,7 @@ mod tests {
                 },
             ],
             temperature: 0.7,
+            reasoning_effort: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains("\"role\":\"system\""));
@@ -504,6 +582,7 @@ mod tests {
                 content: "hello".to_string(),
             }],
             temperature: 0.0,
+            reasoning_effort: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(!json.contains("system"));
@@ -605,6 +684,33 @@ mod tests {
         assert_eq!(msg.effective_content(), Some("Real answer".to_string()));
     }
 
+    #[test]
+    fn native_response_keeps_reasoning_content_next_to_answer() {
+        let json = r#"{"choices":[{"message":{"content":"Real answer","reasoning_content":"Worked it out"}}]}"#;
+        let resp: NativeChatResponse = serde_json::from_str(json).unwrap();
+        let parsed =
+            OpenAiProvider::parse_native_response(resp.choices.into_iter().next().unwrap().message);
+        assert_eq!(parsed.text.as_deref(), Some("Real answer"));
+        assert_eq!(parsed.reasoning_text().as_deref(), Some("Worked it out"));
+    }
+
+    #[test]
+    fn reasoning_effort_maps_levels_and_omits_off() {
+        assert_eq!(OpenAiProvider::reasoning_effort(None), None);
+        assert_eq!(
+            OpenAiProvider::reasoning_effort(Some(ReasoningLevel::OFF)),
+            None
+        );
+        assert_eq!(
+            OpenAiProvider::reasoning_effort(Some(ReasoningLevel::Budget(30_000))),
+            Some("high")
+        );
+        assert_eq!(
+            OpenAiProvider::reasoning_effort(Some(ReasoningLevel::Effort(ReasoningEffort::Low))),
+            Some("low")
+        );
+    }
+
     #[tokio::test]
     async fn chat_with_tools_fails_without_key() {
         let p = OpenAiProvider::new(None);
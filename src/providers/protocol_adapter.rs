@@ -8,6 +8,7 @@
 //! - `tools_json()` for tool/function calling
 //! - `Error::is_retryable()` / `retry_after()` for automatic retries on rate limits
 
+use crate::providers::structured;
 use crate::providers::traits::{
     ChatMessage, ChatRequest, ChatResponse, Provider, ProviderCapabilities, StreamChunk,
     StreamOptions, StreamResult, ToolCall, ToolsPayload,
@@ -176,7 +177,10 @@ impl Provider for ProtocolBackedProvider {
         _model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
-        let converted = Self::convert_messages(request.messages);
+        // No native structured-output mode here; describe the schema in the prompt.
+        let messages =
+            structured::prompt_guided_messages(request.messages, request.response_format);
+        let converted = Self::convert_messages(&messages);
 
         let tools = request.tools.map(|tools| {
             tools
@@ -210,6 +214,7 @@ impl Provider for ProtocolBackedProvider {
                     arguments: tc.arguments.to_string(),
                 })
                 .collect(),
+            reasoning: Vec::new(),
         })
     }
 
@@ -244,6 +249,7 @@ impl Provider for ProtocolBackedProvider {
                     arguments: tc.arguments.to_string(),
                 })
                 .collect(),
+            reasoning: Vec::new(),
         })
     }
 
@@ -0,0 +1,174 @@
+//! Provider-neutral reasoning ("thinking") controls.
+//!
+//! Operators pick a [`ReasoningLevel`] globally (`[runtime] reasoning`), per
+//! `[[model_routes]]` hint, or per delegate agent. Providers translate it into
+//! their own knob: Anthropic `thinking.budget_tokens`, OpenAI
+//! `reasoning_effort`, Gemini `thinkingConfig.thinkingBudget` and Ollama `think`.
+//! Providers without a reasoning control ignore it.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Requested reasoning depth: a named effort or an explicit token budget.
+///
+/// Deserializes from `"off"`, `"low"`, `"medium"`, `"high"` or an integer
+/// number of thinking tokens (`0` means off).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ReasoningLevel {
+    Effort(ReasoningEffort),
+    Budget(u32),
+}
+
+/// Named reasoning effort.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ReasoningEffort {
+    Off,
+    Low,
+    Medium,
+    High,
+}
+
+/// Thinking budgets used when a named effort has to become a token count.
+const LOW_BUDGET_TOKENS: u32 = 2048;
+const MEDIUM_BUDGET_TOKENS: u32 = 8192;
+const HIGH_BUDGET_TOKENS: u32 = 24_576;
+
+impl ReasoningLevel {
+    pub const OFF: Self = Self::Effort(ReasoningEffort::Off);
+
+    /// Mapping for the legacy boolean `runtime.reasoning_enabled` toggle.
+    pub fn from_enabled(enabled: bool) -> Self {
+        if enabled {
+            Self::Effort(ReasoningEffort::Medium)
+        } else {
+            Self::OFF
+        }
+    }
+
+    pub fn is_enabled(self) -> bool {
+        self.effort() != ReasoningEffort::Off
+    }
+
+    /// Thinking token budget; `0` when reasoning is off.
+    pub fn budget_tokens(self) -> u32 {
+        match self {
+            Self::Budget(tokens) => tokens,
+            Self::Effort(ReasoningEffort::Off) => 0,
+            Self::Effort(ReasoningEffort::Low) => LOW_BUDGET_TOKENS,
+            Self::Effort(ReasoningEffort::Medium) => MEDIUM_BUDGET_TOKENS,
+            Self::Effort(ReasoningEffort::High) => HIGH_BUDGET_TOKENS,
+        }
+    }
+
+    /// Named effort; explicit budgets are bucketed to the nearest level.
+    pub fn effort(self) -> ReasoningEffort {
+        match self {
+            Self::Effort(effort) => effort,
+            Self::Budget(0) => ReasoningEffort::Off,
+            Self::Budget(tokens) if tokens <= LOW_BUDGET_TOKENS => ReasoningEffort::Low,
+            Self::Budget(tokens) if tokens <= MEDIUM_BUDGET_TOKENS => ReasoningEffort::Medium,
+            Self::Budget(_) => ReasoningEffort::High,
+        }
+    }
+}
+
+impl ReasoningEffort {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+impl fmt::Display for ReasoningLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Effort(effort) => f.write_str(effort.as_str()),
+            Self::Budget(tokens) => write!(f, "{tokens}"),
+        }
+    }
+}
+
+impl FromStr for ReasoningLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        if let Ok(tokens) = value.parse::<u32>() {
+            return Ok(Self::Budget(tokens));
+        }
+        let effort = match value.to_ascii_lowercase().as_str() {
+            "off" | "none" | "false" => ReasoningEffort::Off,
+            "low" => ReasoningEffort::Low,
+            "medium" | "true" => ReasoningEffort::Medium,
+            "high" => ReasoningEffort::High,
+            other => anyhow::bail!(
+                "invalid reasoning level '{other}': expected off, low, medium, high or a token budget"
+            ),
+        };
+        Ok(Self::Effort(effort))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Wrapper {
+        reasoning: ReasoningLevel,
+    }
+
+    #[test]
+    fn deserializes_named_effort_and_budget() {
+        let named: Wrapper = toml::from_str(r#"reasoning = "high""#).unwrap();
+        assert_eq!(
+            named.reasoning,
+            ReasoningLevel::Effort(ReasoningEffort::High)
+        );
+
+        let budget: Wrapper = toml::from_str("reasoning = 4096").unwrap();
+        assert_eq!(budget.reasoning, ReasoningLevel::Budget(4096));
+
+        assert!(toml::from_str::<Wrapper>(r#"reasoning = "extreme""#).is_err());
+    }
+
+    #[test]
+    fn budgets_bucket_into_efforts() {
+        assert_eq!(ReasoningLevel::Budget(0).effort(), ReasoningEffort::Off);
+        assert_eq!(ReasoningLevel::Budget(1024).effort(), ReasoningEffort::Low);
+        assert_eq!(
+            ReasoningLevel::Budget(8192).effort(),
+            ReasoningEffort::Medium
+        );
+        assert_eq!(
+            ReasoningLevel::Budget(50_000).effort(),
+            ReasoningEffort::High
+        );
+        assert_eq!(ReasoningLevel::OFF.budget_tokens(), 0);
+        assert!(!ReasoningLevel::Budget(0).is_enabled());
+        assert!(ReasoningLevel::from_enabled(true).is_enabled());
+    }
+
+    #[test]
+    fn parses_env_style_values() {
+        assert_eq!(
+            "HIGH".parse::<ReasoningLevel>().unwrap(),
+            ReasoningLevel::Effort(ReasoningEffort::High)
+        );
+        assert_eq!(
+            "2048".parse::<ReasoningLevel>().unwrap(),
+            ReasoningLevel::Budget(2048)
+        );
+        assert!("lots".parse::<ReasoningLevel>().is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use super::structured;
 use super::traits::{
     ChatMessage, ChatRequest, ChatResponse, StreamChunk, StreamOptions, StreamResult,
 };
@@ -221,6 +222,9 @@ fn push_failure(
 // Loop invariant: `failures` accumulates every failed attempt so the final
 // error message gives operators a complete diagnostic trail.
 
+/// Repair round-trips for invalid structured output unless configured otherwise.
+const DEFAULT_STRUCTURED_OUTPUT_REPAIRS: u32 = 2;
+
 /// Provider wrapper with retry, fallback, auth rotation, and model failover.
 pub struct ReliableProvider {
     providers: Vec<(String, Box<dyn Provider>)>,
@@ -231,6 +235,8 @@ pub struct ReliableProvider {
     key_index: AtomicUsize,
     /// Per-model fallback chains: model_name → [fallback_model_1, fallback_model_2, ...]
     model_fallbacks: HashMap<String, Vec<String>>,
+    /// Repair round-trips allowed when structured output fails validation.
+    structured_output_repairs: u32,
 }
 
 impl ReliableProvider {
@@ -246,6 +252,7 @@ impl ReliableProvider {
             api_keys: Vec::new(),
             key_index: AtomicUsize::new(0),
             model_fallbacks: HashMap::new(),
+            structured_output_repairs: DEFAULT_STRUCTURED_OUTPUT_REPAIRS,
         }
     }
 
@@ -261,6 +268,12 @@ impl ReliableProvider {
         self
     }
 
+    /// Set how many times a reply failing its response schema is sent back for repair.
+    pub fn with_structured_output_repairs(mut self, repairs: u32) -> Self {
+        self.structured_output_repairs = repairs;
+        self
+    }
+
     /// Build the list of models to try: [original, fallback1, fallback2, ...]
     fn model_chain<'a>(&'a self, model: &'a str) -> Vec<&'a str> {
         let mut chain = vec![model];
@@ -288,43 +301,23 @@ impl ReliableProvider {
             base
         }
     }
-}
 
-#[async_trait]
-impl Provider for ReliableProvider {
-    async fn warmup(&self) -> anyhow::Result<()> {
-        for (name, provider) in &self.providers {
-            tracing::info!(provider = name, "Warming up provider connection pool");
-            if provider.warmup().await.is_err() {
-                tracing::warn!(provider = name, "Warmup failed (non-fatal)");
-            }
-        }
-        Ok(())
-    }
-
-    async fn chat_with_system(
+    /// Run `chat` across the model chain, provider chain and retry loop.
+    async fn chat_with_failover(
         &self,
-        system_prompt: Option<&str>,
-        message: &str,
+        request: ChatRequest<'_>,
         model: &str,
         temperature: f64,
-    ) -> anyhow::Result<String> {
+    ) -> anyhow::Result<ChatResponse> {
         let models = self.model_chain(model);
         let mut failures = Vec::new();
 
-        // Outer: model fallback chain. Middle: provider priority. Inner: retries.
-        // Each iteration: attempt one (provider, model) call. On success, return
-        // immediately. On non-retryable error, break to next provider. On
-        // retryable error, sleep with exponential backoff and retry.
         for current_model in &models {
             for (provider_name, provider) in &self.providers {
                 let mut backoff_ms = self.base_backoff_ms;
 
                 for attempt in 0..=self.max_retries {
-                    match provider
-                        .chat_with_system(system_prompt, message, current_model, temperature)
-                        .await
-                    {
+                    match provider.chat(request, current_model, temperature).await {
                         Ok(resp) => {
                             if attempt > 0 || *current_model != model {
                                 tracing::info!(
@@ -354,16 +347,12 @@ impl Provider for ReliableProvider {
                                 &error_detail,
                             );
 
-                            // Rate-limit with rotatable keys: cycle to the next API key
-                            // so the retry hits a different quota bucket.
                             if rate_limited && !non_retryable_rate_limit {
                                 if let Some(new_key) = self.rotate_key() {
-                                    tracing::warn!(
+                                    tracing::info!(
                                         provider = provider_name,
                                         error = %error_detail,
-                                        "Rate limited; key rotation selected key ending ...{} \
-                                         but cannot apply (Provider trait has no set_api_key). \
-                                         Retrying with original key.",
+                                        "Rate limited, rotated API key (key ending ...{})",
                                         &new_key[new_key.len().saturating_sub(4)..]
                                     );
                                 }
@@ -411,14 +400,6 @@ impl Provider for ReliableProvider {
                     "Exhausted retries, trying next provider/model"
                 );
             }
-
-            if *current_model != model {
-                tracing::warn!(
-                    original_model = model,
-                    fallback_model = *current_model,
-                    "Model fallback exhausted all providers, trying next fallback model"
-                );
-            }
         }
 
         anyhow::bail!(
@@ -426,23 +407,41 @@ impl Provider for ReliableProvider {
             failures.join("\n")
         )
     }
+}
 
-    async fn chat_with_history(
+#[async_trait]
+impl Provider for ReliableProvider {
+    async fn warmup(&self) -> anyhow::Result<()> {
+        for (name, provider) in &self.providers {
+            tracing::info!(provider = name, "Warming up provider connection pool");
+            if provider.warmup().await.is_err() {
+                tracing::warn!(provider = name, "Warmup failed (non-fatal)");
+            }
+        }
+        Ok(())
+    }
+
+    async fn chat_with_system(
         &self,
-        messages: &[ChatMessage],
+        system_prompt: Option<&str>,
+        message: &str,
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
         let models = self.model_chain(model);
         let mut failures = Vec::new();
 
+        // Outer: model fallback chain. Middle: provider priority. Inner: retries.
+        // Each iteration: attempt one (provider, model) call. On success, return
+        // immediately. On non-retryable error, break to next provider. On
+        // retryable error, sleep with exponential backoff and retry.
         for current_model in &models {
             for (provider_name, provider) in &self.providers {
                 let mut backoff_ms = self.base_backoff_ms;
 
                 for attempt in 0..=self.max_retries {
                     match provider
-                        .chat_with_history(messages, current_model, temperature)
+                        .chat_with_system(system_prompt, message, current_model, temperature)
                         .await
                     {
                         Ok(resp) => {
@@ -474,6 +473,8 @@ impl Provider for ReliableProvider {
                                 &error_detail,
                             );
 
+                            // Rate-limit with rotatable keys: cycle to the next API key
+                            // so the retry hits a different quota bucket.
                             if rate_limited && !non_retryable_rate_limit {
                                 if let Some(new_key) = self.rotate_key() {
                                     tracing::warn!(
@@ -529,6 +530,14 @@ impl Provider for ReliableProvider {
                     "Exhausted retries, trying next provider/model"
                 );
             }
+
+            if *current_model != model {
+                tracing::warn!(
+                    original_model = model,
+                    fallback_model = *current_model,
+                    "Model fallback exhausted all providers, trying next fallback model"
+                );
+            }
         }
 
         anyhow::bail!(
@@ -537,12 +546,12 @@ impl Provider for ReliableProvider {
         )
     }
 
-    async fn chat(
+    async fn chat_with_history(
         &self,
-        request: ChatRequest<'_>,
+        messages: &[ChatMessage],
         model: &str,
         temperature: f64,
-    ) -> anyhow::Result<ChatResponse> {
+    ) -> anyhow::Result<String> {
         let models = self.model_chain(model);
         let mut failures = Vec::new();
 
@@ -551,11 +560,10 @@ impl Provider for ReliableProvider {
                 let mut backoff_ms = self.base_backoff_ms;
 
                 for attempt in 0..=self.max_retries {
-                    let req = ChatRequest {
-                        messages: request.messages,
-                        tools: request.tools,
-                    };
-                    match provider.chat(req, current_model, temperature).await {
+                    match provider
+                        .chat_with_history(messages, current_model, temperature)
+                        .await
+                    {
                         Ok(resp) => {
                             if attempt > 0 || *current_model != model {
                                 tracing::info!(
@@ -587,10 +595,12 @@ impl Provider for ReliableProvider {
 
                             if rate_limited && !non_retryable_rate_limit {
                                 if let Some(new_key) = self.rotate_key() {
-                                    tracing::info!(
+                                    tracing::warn!(
                                         provider = provider_name,
                                         error = %error_detail,
-                                        "Rate limited, rotated API key (key ending ...{})",
+                                        "Rate limited; key rotation selected key ending ...{} \
+                                         but cannot apply (Provider trait has no set_api_key). \
+                                         Retrying with original key.",
                                         &new_key[new_key.len().saturating_sub(4)..]
                                     );
                                 }
@@ -646,6 +656,59 @@ impl Provider for ReliableProvider {
         )
     }
 
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let Some(format) = request.response_format else {
+            return self.chat_with_failover(request, model, temperature).await;
+        };
+
+        // Validate structured output and, when it does not conform, show the
+        // model its reply together with the violations and ask again.
+        let mut messages = request.messages.to_vec();
+        let mut repairs = 0;
+        loop {
+            let attempt = ChatRequest {
+                messages: &messages,
+                ..request
+            };
+            let mut response = self.chat_with_failover(attempt, model, temperature).await?;
+            if response.has_tool_calls() {
+                // The schema applies to the final answer, not intermediate tool turns.
+                return Ok(response);
+            }
+
+            let text = response.text_or_empty().to_string();
+            match format.validate(&text) {
+                Ok(value) => {
+                    response.text = Some(value.to_string());
+                    return Ok(response);
+                }
+                Err(e) if repairs < self.structured_output_repairs => {
+                    repairs += 1;
+                    tracing::warn!(
+                        model,
+                        schema = format.name.as_str(),
+                        repair = repairs,
+                        error = %e,
+                        "Structured output failed validation, asking model to repair"
+                    );
+                    messages.push(ChatMessage::assistant(text));
+                    messages.push(ChatMessage::user(structured::repair_prompt(&e)));
+                }
+                Err(e) => {
+                    anyhow::bail!(
+                        "Structured output for schema '{}' failed validation after {repairs} repair attempt(s): {e}",
+                        format.name
+                    );
+                }
+            }
+        }
+    }
+
     fn supports_native_tools(&self) -> bool {
         self.providers
             .first()
@@ -1659,6 +1722,8 @@ mod tests {
             Ok(ChatResponse {
                 text: Some(self.response_text.to_string()),
                 tool_calls: self.tool_calls.clone(),
+                reasoning: Vec::new(),
+                usage: None,
             })
         }
     }
@@ -1690,6 +1755,8 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            response_format: None,
+            reasoning: None,
         };
         let result = provider.chat(request, "test-model", 0.0).await.unwrap();
 
@@ -1726,6 +1793,8 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            response_format: None,
+            reasoning: None,
         };
         let result = provider.chat(request, "test-model", 0.0).await.unwrap();
 
@@ -1797,6 +1866,8 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            response_format: None,
+            reasoning: None,
         };
         let err = provider
             .chat(request, "test", 0.0)
@@ -1850,6 +1921,8 @@ mod tests {
             Ok(ChatResponse {
                 text: Some(self.response_text.to_string()),
                 tool_calls: vec![],
+                reasoning: Vec::new(),
+                usage: None,
             })
         }
     }
@@ -1911,6 +1984,8 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            response_format: None,
+            reasoning: None,
         };
         let result = provider.chat(request, "claude-opus", 0.0).await.unwrap();
         assert_eq!(result.text.as_deref(), Some("ok from sonnet"));
@@ -1959,6 +2034,8 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            response_format: None,
+            reasoning: None,
         };
         let result = provider.chat(request, "test", 0.0).await.unwrap();
         assert_eq!(result.text.as_deref(), Some("from fallback"));
@@ -1966,4 +2043,117 @@ mod tests {
         assert_eq!(primary_calls.load(Ordering::SeqCst), 1);
         assert_eq!(fallback_calls.load(Ordering::SeqCst), 1);
     }
+
+    /// Replies with each scripted answer in turn and records the conversation it saw.
+    struct ScriptedReplies {
+        replies: parking_lot::Mutex<Vec<&'static str>>,
+        seen: parking_lot::Mutex<Vec<Vec<ChatMessage>>>,
+    }
+
+    #[async_trait]
+    impl Provider for Arc<ScriptedReplies> {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            unreachable!("chat() is used")
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            self.seen.lock().push(request.messages.to_vec());
+            Ok(ChatResponse {
+                text: Some(self.replies.lock().remove(0).to_string()),
+                tool_calls: vec![],
+                reasoning: Vec::new(),
+                usage: None,
+            })
+        }
+    }
+
+    fn verdict_format() -> super::super::traits::ResponseFormat {
+        super::super::traits::ResponseFormat::json_schema(
+            "verdict",
+            serde_json::json!({
+                "type": "object",
+                "properties": {"spam": {"type": "boolean"}},
+                "required": ["spam"]
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn chat_repairs_structured_output_that_fails_validation() {
+        let mock = Arc::new(ScriptedReplies {
+            replies: parking_lot::Mutex::new(vec![
+                "probably not spam",
+                "```json\n{\"spam\": false}\n```",
+            ]),
+            seen: parking_lot::Mutex::new(Vec::new()),
+        });
+        let provider = ReliableProvider::new(
+            vec![(
+                "primary".into(),
+                Box::new(Arc::clone(&mock)) as Box<dyn Provider>,
+            )],
+            0,
+            1,
+        );
+
+        let format = verdict_format();
+        let messages = vec![ChatMessage::user("classify this")];
+        let request = ChatRequest {
+            messages: &messages,
+            tools: None,
+            response_format: Some(&format),
+            reasoning: None,
+        };
+        let result = provider.chat(request, "test", 0.0).await.unwrap();
+
+        assert_eq!(result.text.as_deref(), Some(r#"{"spam":false}"#));
+        let seen = mock.seen.lock();
+        assert_eq!(seen.len(), 2);
+        let repair_turn = &seen[1];
+        assert_eq!(repair_turn.len(), 3);
+        assert_eq!(repair_turn[1].content, "probably not spam");
+        assert!(repair_turn[2].content.contains("rejected"));
+    }
+
+    #[tokio::test]
+    async fn chat_gives_up_after_configured_structured_output_repairs() {
+        let mock = Arc::new(ScriptedReplies {
+            replies: parking_lot::Mutex::new(vec!["{}", "{}"]),
+            seen: parking_lot::Mutex::new(Vec::new()),
+        });
+        let provider = ReliableProvider::new(
+            vec![(
+                "primary".into(),
+                Box::new(Arc::clone(&mock)) as Box<dyn Provider>,
+            )],
+            0,
+            1,
+        )
+        .with_structured_output_repairs(1);
+
+        let format = verdict_format();
+        let messages = vec![ChatMessage::user("classify this")];
+        let request = ChatRequest {
+            messages: &messages,
+            tools: None,
+            response_format: Some(&format),
+            reasoning: None,
+        };
+        let err = provider.chat(request, "test", 0.0).await.unwrap_err();
+
+        assert!(err.to_string().contains("after 1 repair attempt(s)"));
+        assert!(err.to_string().contains("missing required property `spam`"));
+        assert_eq!(mock.seen.lock().len(), 2);
+    }
 }
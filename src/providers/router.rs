@@ -1,3 +1,4 @@
+use super::reasoning::ReasoningLevel;
 use super::traits::{ChatMessage, ChatRequest, ChatResponse};
 use super::Provider;
 use async_trait::async_trait;
@@ -8,6 +9,8 @@ use std::collections::HashMap;
 pub struct Route {
     pub provider_name: String,
     pub model: String,
+    /// Reasoning level applied to requests resolved through this route.
+    pub reasoning: Option<ReasoningLevel>,
 }
 
 /// Multi-model router — routes requests to different provider+model combos
@@ -20,6 +23,7 @@ pub struct Route {
 /// This wraps multiple pre-created providers and selects the right one per request.
 pub struct RouterProvider {
     routes: HashMap<String, (usize, String)>, // hint → (provider_index, model)
+    route_reasoning: HashMap<String, ReasoningLevel>,
     providers: Vec<(String, Box<dyn Provider>)>,
     default_index: usize,
     default_model: String,
@@ -42,6 +46,11 @@ impl RouterProvider {
             .map(|(i, (name, _))| (name.as_str(), i))
             .collect();
 
+        let route_reasoning: HashMap<String, ReasoningLevel> = routes
+            .iter()
+            .filter_map(|(hint, route)| route.reasoning.map(|level| (hint.clone(), level)))
+            .collect();
+
         // Resolve routes to provider indices
         let resolved_routes: HashMap<String, (usize, String)> = routes
             .into_iter()
@@ -63,6 +72,7 @@ impl RouterProvider {
 
         Self {
             routes: resolved_routes,
+            route_reasoning,
             providers,
             default_index: 0,
             default_model,
@@ -88,6 +98,12 @@ impl RouterProvider {
         // Not a hint or hint not found — use default provider with the model as-is
         (self.default_index, model.to_string())
     }
+
+    /// Reasoning level configured on the route a `hint:` model resolves to.
+    fn resolve_reasoning(&self, model: &str) -> Option<ReasoningLevel> {
+        let hint = model.strip_prefix("hint:")?;
+        self.route_reasoning.get(hint).copied()
+    }
 }
 
 #[async_trait]
@@ -134,6 +150,10 @@ impl Provider for RouterProvider {
     ) -> anyhow::Result<ChatResponse> {
         let (provider_idx, resolved_model) = self.resolve(model);
         let (_, provider) = &self.providers[provider_idx];
+        let request = ChatRequest {
+            reasoning: request.reasoning.or_else(|| self.resolve_reasoning(model)),
+            ..request
+        };
         provider.chat(request, &resolved_model, temperature).await
     }
 
@@ -248,6 +268,7 @@ mod tests {
                     Route {
                         provider_name: provider_name.to_string(),
                         model: model.to_string(),
+                        reasoning: None,
                     },
                 )
             })
@@ -461,4 +482,85 @@ mod tests {
         assert_eq!(mocks[1].last_model(), "claude-opus");
         assert_eq!(mocks[0].call_count(), 0);
     }
+
+    #[tokio::test]
+    async fn chat_applies_route_reasoning_unless_request_overrides() {
+        struct ReasoningProbe {
+            seen: parking_lot::Mutex<Vec<Option<ReasoningLevel>>>,
+        }
+
+        #[async_trait]
+        impl Provider for Arc<ReasoningProbe> {
+            async fn chat_with_system(
+                &self,
+                _system_prompt: Option<&str>,
+                _message: &str,
+                _model: &str,
+                _temperature: f64,
+            ) -> anyhow::Result<String> {
+                Ok("ok".to_string())
+            }
+
+            async fn chat(
+                &self,
+                request: ChatRequest<'_>,
+                _model: &str,
+                _temperature: f64,
+            ) -> anyhow::Result<ChatResponse> {
+                self.seen.lock().push(request.reasoning);
+                Ok(ChatResponse {
+                    text: Some("ok".to_string()),
+                    tool_calls: Vec::new(),
+                    reasoning: Vec::new(),
+                    usage: None,
+                })
+            }
+        }
+
+        let probe = Arc::new(ReasoningProbe {
+            seen: parking_lot::Mutex::new(Vec::new()),
+        });
+        let deep = ReasoningLevel::Budget(16_000);
+        let router = RouterProvider::new(
+            vec![(
+                "default".into(),
+                Box::new(Arc::clone(&probe)) as Box<dyn Provider>,
+            )],
+            vec![(
+                "deep".into(),
+                Route {
+                    provider_name: "default".into(),
+                    model: "big-model".into(),
+                    reasoning: Some(deep),
+                },
+            )],
+            "model".into(),
+        );
+
+        let messages = vec![ChatMessage::user("think hard")];
+        let request = ChatRequest {
+            messages: &messages,
+            tools: None,
+            response_format: None,
+            reasoning: None,
+        };
+        router.chat(request, "hint:deep", 0.5).await.unwrap();
+        router.chat(request, "plain-model", 0.5).await.unwrap();
+        router
+            .chat(
+                ChatRequest {
+                    reasoning: Some(ReasoningLevel::OFF),
+                    ..request
+                },
+                "hint:deep",
+                0.5,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *probe.seen.lock(),
+            vec![Some(deep), None, Some(ReasoningLevel::OFF)]
+        );
+    }
 }
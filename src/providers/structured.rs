@@ -0,0 +1,489 @@
+//! Structured output: JSON-schema constrained replies.
+//!
+//! Callers attach a [`ResponseFormat`] to a [`ChatRequest`](super::ChatRequest).
+//! Providers with a native mechanism (OpenAI `response_format`, Anthropic
+//! forced tool use, Gemini `responseSchema`, Ollama `format`) map it onto
+//! their API; everything else gets the schema appended to the system prompt
+//! via [`prompt_guided_messages`]. Replies are checked with [`validate_reply`],
+//! which covers the JSON Schema subset tool and output schemas use in practice.
+
+use super::traits::{ChatMessage, ResponseFormat};
+use serde_json::Value;
+use std::borrow::Cow;
+
+/// Stop collecting violations after this many; the first few are enough to
+/// steer a repair attempt.
+const MAX_REPORTED_ERRORS: usize = 8;
+
+/// Guard against cyclic `$ref` chains.
+const MAX_REF_DEPTH: usize = 32;
+
+/// Instruction block for providers without a native structured-output mode.
+pub fn instructions(format: &ResponseFormat) -> String {
+    let schema =
+        serde_json::to_string_pretty(&format.schema).unwrap_or_else(|_| format.schema.to_string());
+    format!(
+        "Respond with a single JSON value that conforms to the JSON Schema below. \
+         Do not wrap it in Markdown code fences and do not add any other text.\n\n\
+         Schema `{}`:\n{schema}",
+        format.api_name()
+    )
+}
+
+/// Append [`instructions`] to the system prompt (or prepend a system message)
+/// when `format` is set. Borrows `messages` unchanged otherwise.
+pub fn prompt_guided_messages<'a>(
+    messages: &'a [ChatMessage],
+    format: Option<&ResponseFormat>,
+) -> Cow<'a, [ChatMessage]> {
+    let Some(format) = format else {
+        return Cow::Borrowed(messages);
+    };
+
+    let instructions = instructions(format);
+    let mut guided = messages.to_vec();
+    if let Some(system) = guided.iter_mut().find(|m| m.role == "system") {
+        if !system.content.is_empty() {
+            system.content.push_str("\n\n");
+        }
+        system.content.push_str(&instructions);
+    } else {
+        guided.insert(0, ChatMessage::system(instructions));
+    }
+    Cow::Owned(guided)
+}
+
+/// OpenAI Chat Completions `response_format` payload.
+pub fn openai_response_format(format: &ResponseFormat) -> Value {
+    serde_json::json!({
+        "type": "json_schema",
+        "json_schema": {
+            "name": format.api_name(),
+            "schema": format.schema,
+            // Strict mode rejects schemas that leave `additionalProperties`
+            // open; validation below catches drift instead.
+            "strict": false,
+        }
+    })
+}
+
+/// Follow-up user message asking the model to fix a reply that failed validation.
+pub fn repair_prompt(error: &anyhow::Error) -> String {
+    format!(
+        "Your previous reply was rejected: {error}. Reply again with only a JSON value \
+         that satisfies the required schema."
+    )
+}
+
+/// Parse `text` as JSON and check it against `schema`.
+///
+/// Tolerates a Markdown code fence or prose around a single JSON object or
+/// array, since models add them even when told not to.
+pub fn validate_reply(text: &str, schema: &Value) -> anyhow::Result<Value> {
+    let value = match serde_json::from_str::<Value>(text.trim()) {
+        Ok(value) => value,
+        Err(parse_error) => {
+            let candidate = extract_json(text)
+                .ok_or_else(|| anyhow::anyhow!("reply is not JSON ({parse_error})"))?;
+            serde_json::from_str(candidate)
+                .map_err(|e| anyhow::anyhow!("reply is not valid JSON ({e})"))?
+        }
+    };
+
+    validate(&value, schema).map_err(|errors| {
+        anyhow::anyhow!("reply does not match the schema: {}", errors.join("; "))
+    })?;
+    Ok(value)
+}
+
+/// Locate the JSON payload inside a fenced block or surrounding prose.
+fn extract_json(text: &str) -> Option<&str> {
+    let trimmed = text.trim();
+
+    if let Some(start) = trimmed.find("```") {
+        let body = &trimmed[start + 3..];
+        let body = body
+            .strip_prefix("json")
+            .or_else(|| body.strip_prefix("JSON"))
+            .unwrap_or(body);
+        if let Some(end) = body.find("```") {
+            return Some(body[..end].trim());
+        }
+    }
+
+    let start = trimmed.find(['{', '['])?;
+    let end = trimmed.rfind(['}', ']'])?;
+    (end > start).then(|| &trimmed[start..=end])
+}
+
+/// Check `value` against `schema`, returning every violation found (up to a cap).
+pub fn validate(value: &Value, schema: &Value) -> Result<(), Vec<String>> {
+    let mut validator = Validator {
+        root: schema,
+        errors: Vec::new(),
+    };
+    validator.check(value, schema, "$", 0);
+    if validator.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(validator.errors)
+    }
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    errors: Vec<String>,
+}
+
+impl<'a> Validator<'a> {
+    fn fail(&mut self, path: &str, message: impl std::fmt::Display) {
+        if self.errors.len() < MAX_REPORTED_ERRORS {
+            self.errors.push(format!("{path}: {message}"));
+        }
+    }
+
+    fn matches(&self, value: &Value, schema: &'a Value, path: &str, depth: usize) -> bool {
+        let mut probe = Validator {
+            root: self.root,
+            errors: Vec::new(),
+        };
+        probe.check(value, schema, path, depth);
+        probe.errors.is_empty()
+    }
+
+    fn resolve(&self, reference: &str) -> Option<&'a Value> {
+        let pointer = reference.strip_prefix('#')?;
+        if pointer.is_empty() {
+            Some(self.root)
+        } else {
+            self.root.pointer(pointer)
+        }
+    }
+
+    fn check(&mut self, value: &Value, schema: &'a Value, path: &str, depth: usize) {
+        if self.errors.len() >= MAX_REPORTED_ERRORS {
+            return;
+        }
+        let schema = match schema {
+            Value::Bool(false) => return self.fail(path, "no value is allowed here"),
+            Value::Object(schema) => schema,
+            // `true` and anything malformed accept every value.
+            _ => return,
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            if depth >= MAX_REF_DEPTH {
+                return self.fail(path, "schema `$ref` nesting is too deep");
+            }
+            match self.resolve(reference) {
+                Some(target) => self.check(value, target, path, depth + 1),
+                None => self.fail(path, format!("cannot resolve `$ref` {reference}")),
+            }
+            return;
+        }
+
+        if value.is_null() && schema.get("nullable").and_then(Value::as_bool) == Some(true) {
+            return;
+        }
+
+        if let Some(expected) = schema.get("type") {
+            let allowed: Vec<&str> = match expected {
+                Value::String(kind) => vec![kind.as_str()],
+                Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            if !allowed.is_empty() && !allowed.iter().any(|kind| type_matches(value, kind)) {
+                return self.fail(
+                    path,
+                    format!(
+                        "expected {}, found {}",
+                        allowed.join(" or "),
+                        kind_of(value)
+                    ),
+                );
+            }
+        }
+
+        if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+            if !options.contains(value) {
+                self.fail(
+                    path,
+                    format!("{value} is not one of {}", Value::from(options.clone())),
+                );
+            }
+        }
+        if let Some(constant) = schema.get("const") {
+            if constant != value {
+                self.fail(path, format!("expected {constant}"));
+            }
+        }
+
+        for sub in schema
+            .get("allOf")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            self.check(value, sub, path, depth + 1);
+        }
+        if let Some(options) = schema.get("anyOf").and_then(Value::as_array) {
+            if !options
+                .iter()
+                .any(|sub| self.matches(value, sub, path, depth + 1))
+            {
+                self.fail(path, "does not match any of the allowed schemas (anyOf)");
+            }
+        }
+        if let Some(options) = schema.get("oneOf").and_then(Value::as_array) {
+            let matched = options
+                .iter()
+                .filter(|sub| self.matches(value, sub, path, depth + 1))
+                .count();
+            if matched != 1 {
+                self.fail(
+                    path,
+                    format!("matches {matched} schemas, expected exactly one (oneOf)"),
+                );
+            }
+        }
+
+        match value {
+            Value::String(text) => self.check_string(text, schema, path),
+            Value::Number(_) => self.check_number(value, schema, path),
+            Value::Array(items) => self.check_array(items, schema, path, depth),
+            Value::Object(fields) => self.check_object(fields, schema, path, depth),
+            _ => {}
+        }
+    }
+
+    fn check_string(&mut self, text: &str, schema: &serde_json::Map<String, Value>, path: &str) {
+        let length = text.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+            if length < min {
+                self.fail(path, format!("string shorter than {min} characters"));
+            }
+        }
+        if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+            if length > max {
+                self.fail(path, format!("string longer than {max} characters"));
+            }
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+            if let Ok(regex) = regex::Regex::new(pattern) {
+                if !regex.is_match(text) {
+                    self.fail(path, format!("string does not match pattern `{pattern}`"));
+                }
+            }
+        }
+    }
+
+    fn check_number(&mut self, value: &Value, schema: &serde_json::Map<String, Value>, path: &str) {
+        let Some(number) = value.as_f64() else {
+            return;
+        };
+        let bound = |key: &str| schema.get(key).and_then(Value::as_f64);
+        if let Some(min) = bound("minimum") {
+            if number < min {
+                self.fail(path, format!("{number} is below the minimum {min}"));
+            }
+        }
+        if let Some(max) = bound("maximum") {
+            if number > max {
+                self.fail(path, format!("{number} is above the maximum {max}"));
+            }
+        }
+        if let Some(min) = bound("exclusiveMinimum") {
+            if number <= min {
+                self.fail(path, format!("{number} must be greater than {min}"));
+            }
+        }
+        if let Some(max) = bound("exclusiveMaximum") {
+            if number >= max {
+                self.fail(path, format!("{number} must be less than {max}"));
+            }
+        }
+    }
+
+    fn check_array(
+        &mut self,
+        items: &[Value],
+        schema: &'a serde_json::Map<String, Value>,
+        path: &str,
+        depth: usize,
+    ) {
+        let count = items.len() as u64;
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+            if count < min {
+                self.fail(
+                    path,
+                    format!("expected at least {min} items, found {count}"),
+                );
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+            if count > max {
+                self.fail(path, format!("expected at most {max} items, found {count}"));
+            }
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (index, item) in items.iter().enumerate() {
+                self.check(item, item_schema, &format!("{path}[{index}]"), depth + 1);
+            }
+        }
+    }
+
+    fn check_object(
+        &mut self,
+        fields: &serde_json::Map<String, Value>,
+        schema: &'a serde_json::Map<String, Value>,
+        path: &str,
+        depth: usize,
+    ) {
+        for required in schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            if !fields.contains_key(required) {
+                self.fail(path, format!("missing required property `{required}`"));
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let additional = schema.get("additionalProperties");
+        for (key, field) in fields {
+            let field_path = format!("{path}.{key}");
+            match properties.and_then(|p| p.get(key)) {
+                Some(field_schema) => self.check(field, field_schema, &field_path, depth + 1),
+                None => match additional {
+                    Some(Value::Bool(false)) => {
+                        self.fail(path, format!("unexpected property `{key}`"));
+                    }
+                    Some(extra_schema @ Value::Object(_)) => {
+                        self.check(field, extra_schema, &field_path, depth + 1);
+                    }
+                    _ => {}
+                },
+            }
+        }
+    }
+}
+
+fn type_matches(value: &Value, kind: &str) -> bool {
+    match kind {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+fn kind_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ticket_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "title": {"type": "string", "minLength": 1},
+                "priority": {"type": "string", "enum": ["low", "high"]},
+                "tags": {"type": "array", "items": {"type": "string"}, "maxItems": 3},
+                "owner": {"$ref": "#/$defs/person"}
+            },
+            "required": ["title", "priority"],
+            "additionalProperties": false,
+            "$defs": {
+                "person": {"type": "object", "properties": {"id": {"type": "integer"}}, "required": ["id"]}
+            }
+        })
+    }
+
+    #[test]
+    fn accepts_conforming_reply() {
+        let value = validate_reply(
+            r#"{"title": "Disk full", "priority": "high", "tags": ["ops"], "owner": {"id": 7}}"#,
+            &ticket_schema(),
+        )
+        .unwrap();
+        assert_eq!(value["owner"]["id"], 7);
+    }
+
+    #[test]
+    fn extracts_json_from_code_fence_and_prose() {
+        let fenced = "Here you go:\n```json\n{\"title\": \"x\", \"priority\": \"low\"}\n```";
+        assert!(validate_reply(fenced, &ticket_schema()).is_ok());
+
+        let prose = "Sure! {\"title\": \"x\", \"priority\": \"low\"} Hope that helps.";
+        assert!(validate_reply(prose, &ticket_schema()).is_ok());
+    }
+
+    #[test]
+    fn reports_every_violation_with_paths() {
+        let value = json!({"priority": "urgent", "tags": ["a", 2], "owner": {}, "extra": true});
+        let errors = validate(&value, &ticket_schema()).unwrap_err();
+        let joined = errors.join("\n");
+        assert!(joined.contains("$: missing required property `title`"));
+        assert!(joined.contains("$.priority: \"urgent\" is not one of"));
+        assert!(joined.contains("$.tags[1]: expected string, found number"));
+        assert!(joined.contains("$.owner: missing required property `id`"));
+        assert!(joined.contains("$: unexpected property `extra`"));
+    }
+
+    #[test]
+    fn rejects_non_json_reply() {
+        let err = validate_reply("I cannot do that.", &ticket_schema()).unwrap_err();
+        assert!(err.to_string().contains("not JSON"));
+    }
+
+    #[test]
+    fn combinators_and_numeric_bounds() {
+        let schema = json!({
+            "anyOf": [{"type": "integer", "minimum": 1, "maximum": 5}, {"type": "null"}]
+        });
+        assert!(validate(&json!(3), &schema).is_ok());
+        assert!(validate(&Value::Null, &schema).is_ok());
+        assert!(validate(&json!(9), &schema).is_err());
+        assert!(validate(&json!(2.5), &schema).is_err());
+    }
+
+    #[test]
+    fn prompt_guided_messages_extends_system_prompt() {
+        let format = ResponseFormat::json_schema("ticket", ticket_schema());
+        let messages = vec![
+            ChatMessage::system("be terse"),
+            ChatMessage::user("file it"),
+        ];
+
+        let guided = prompt_guided_messages(&messages, Some(&format));
+        assert_eq!(guided.len(), 2);
+        assert!(guided[0].content.starts_with("be terse\n\n"));
+        assert!(guided[0].content.contains("Schema `ticket`"));
+
+        let untouched = prompt_guided_messages(&messages, None);
+        assert!(matches!(untouched, Cow::Borrowed(_)));
+
+        let without_system = prompt_guided_messages(&messages[1..], Some(&format));
+        assert_eq!(without_system[0].role, "system");
+    }
+}
@@ -1,3 +1,4 @@
+use super::reasoning::ReasoningLevel;
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use futures_util::{stream, StreamExt};
@@ -49,13 +50,42 @@ pub struct ToolCall {
     pub arguments: String,
 }
 
+/// A block of model reasoning ("thinking") returned alongside a reply.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReasoningBlock {
+    /// Readable reasoning text; empty for redacted blocks.
+    #[serde(default)]
+    pub text: String,
+    /// Opaque provider token that must be sent back with the block
+    /// (Anthropic thinking signature, or the payload of a redacted block).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// The provider withheld the reasoning text.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub redacted: bool,
+}
+
 /// An LLM response that may contain text, tool calls, or both.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatResponse {
     /// Text content of the response (may be empty if only tool calls).
     pub text: Option<String>,
     /// Tool calls requested by the LLM.
+    #[serde(default)]
     pub tool_calls: Vec<ToolCall>,
+    /// Reasoning blocks, when the provider returns them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reasoning: Vec<ReasoningBlock>,
+    /// Token counts, when the provider reports them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<ChatUsage>,
+}
+
+/// Token counts reported by a provider for one call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
 }
 
 impl ChatResponse {
@@ -68,6 +98,17 @@ impl ChatResponse {
     pub fn text_or_empty(&self) -> &str {
         self.text.as_deref().unwrap_or("")
     }
+
+    /// Readable reasoning text joined across blocks, if any.
+    pub fn reasoning_text(&self) -> Option<String> {
+        let parts: Vec<&str> = self
+            .reasoning
+            .iter()
+            .map(|block| block.text.trim())
+            .filter(|text| !text.is_empty())
+            .collect();
+        (!parts.is_empty()).then(|| parts.join("\n\n"))
+    }
 }
 
 /// Request payload for provider chat calls.
@@ -75,6 +116,54 @@ impl ChatResponse {
 pub struct ChatRequest<'a> {
     pub messages: &'a [ChatMessage],
     pub tools: Option<&'a [ToolSpec]>,
+    /// Constrain the reply to JSON matching a schema.
+    pub response_format: Option<&'a ResponseFormat>,
+    /// Override the provider's configured reasoning level for this call.
+    pub reasoning: Option<ReasoningLevel>,
+}
+
+/// JSON-schema constraint for a reply. See [`super::structured`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseFormat {
+    /// Short identifier, surfaced as the schema or tool name by providers that need one.
+    pub name: String,
+    /// JSON Schema the reply must satisfy.
+    pub schema: serde_json::Value,
+}
+
+impl ResponseFormat {
+    pub fn json_schema(name: impl Into<String>, schema: serde_json::Value) -> Self {
+        Self {
+            name: name.into(),
+            schema,
+        }
+    }
+
+    /// `name` reduced to `[A-Za-z0-9_-]{1,64}`, the form OpenAI and Anthropic accept.
+    pub fn api_name(&self) -> String {
+        let name: String = self
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .take(64)
+            .collect();
+        if name.is_empty() {
+            "response".to_string()
+        } else {
+            name
+        }
+    }
+
+    /// Parse `text` as JSON and check it against the schema.
+    pub fn validate(&self, text: &str) -> anyhow::Result<serde_json::Value> {
+        super::structured::validate_reply(text, &self.schema)
+    }
 }
 
 /// A tool result to feed back to the LLM.
@@ -94,6 +183,10 @@ pub enum ConversationMessage {
     AssistantToolCalls {
         text: Option<String>,
         tool_calls: Vec<ToolCall>,
+        /// Reasoning that preceded the calls; some providers (Anthropic) reject
+        /// tool results unless the signed thinking blocks are sent back.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        reasoning: Vec<ReasoningBlock>,
     },
     /// Results of tool executions, fed back to the LLM.
     ToolResults(Vec<ToolResultMessage>),
@@ -311,6 +404,10 @@ pub trait Provider: Send + Sync {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
+        // Without a native structured-output mode, describe the schema in the prompt.
+        let messages =
+            super::structured::prompt_guided_messages(request.messages, request.response_format);
+
         // If tools are provided but provider doesn't support native tools,
         // inject tool instructions into system prompt as fallback.
         if let Some(tools) = request.tools {
@@ -323,7 +420,7 @@ pub trait Provider: Send + Sync {
                         )
                     }
                 };
-                let mut modified_messages = request.messages.to_vec();
+                let mut modified_messages = messages.to_vec();
 
                 // Inject tool instructions into an existing system message.
                 // If none exists, prepend one to the conversation.
@@ -344,16 +441,20 @@ pub trait Provider: Send + Sync {
                 return Ok(ChatResponse {
                     text: Some(text),
                     tool_calls: Vec::new(),
+                    reasoning: Vec::new(),
+                    usage: None,
                 });
             }
         }
 
         let text = self
-            .chat_with_history(request.messages, model, temperature)
+            .chat_with_history(&messages, model, temperature)
             .await?;
         Ok(ChatResponse {
             text: Some(text),
             tool_calls: Vec::new(),
+            reasoning: Vec::new(),
+            usage: None,
         })
     }
 
@@ -387,6 +488,8 @@ pub trait Provider: Send + Sync {
         Ok(ChatResponse {
             text: Some(text),
             tool_calls: Vec::new(),
+            reasoning: Vec::new(),
+            usage: None,
         })
     }
 
@@ -510,6 +613,8 @@ mod tests {
         let empty = ChatResponse {
             text: None,
             tool_calls: vec![],
+            reasoning: Vec::new(),
+            usage: None,
         };
         assert!(!empty.has_tool_calls());
         assert_eq!(empty.text_or_empty(), "");
@@ -521,6 +626,8 @@ mod tests {
                 name: "shell".into(),
                 arguments: "{}".into(),
             }],
+            reasoning: Vec::new(),
+            usage: None,
         };
         assert!(with_tools.has_tool_calls());
         assert_eq!(with_tools.text_or_empty(), "Let me check");
@@ -731,6 +838,8 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::user("Hello")],
             tools: Some(&tools),
+            response_format: None,
+            reasoning: None,
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -748,6 +857,8 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::user("Hello")],
             tools: None,
+            response_format: None,
+            reasoning: None,
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -848,6 +959,8 @@ mod tests {
                 ChatMessage::system("BASE_SYSTEM_PROMPT"),
             ],
             tools: Some(&tools),
+            response_format: None,
+            reasoning: None,
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -870,6 +983,8 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::system("BASE"), ChatMessage::user("Hello")],
             tools: Some(&tools),
+            response_format: None,
+            reasoning: None,
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -892,6 +1007,8 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::user("Hello")],
             tools: Some(&tools),
+            response_format: None,
+            reasoning: None,
         };
 
         let err = provider.chat(request, "model", 0.7).await.unwrap_err();
@@ -162,6 +162,20 @@ pub struct CommandExecutionLog<'a> {
     pub duration_ms: u64,
 }
 
+/// Structured file modification details for audit logging.
+#[derive(Debug, Clone)]
+pub struct FileChangeLog<'a> {
+    pub tool: &'a str,
+    pub path: &'a str,
+    /// Edit kind, e.g. `search_replace`, `line_range`, `patch`.
+    pub operation: &'a str,
+    pub before_hash: &'a str,
+    pub after_hash: &'a str,
+    pub success: bool,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
 impl AuditLogger {
     /// Create a new audit logger
     pub fn new(config: AuditConfig, zeroclaw_dir: PathBuf) -> Result<Self> {
@@ -210,6 +224,24 @@ impl AuditLogger {
         self.log(&event)
     }
 
+    /// Log a file modification made by a tool.
+    pub fn log_file_change(&self, entry: FileChangeLog<'_>) -> Result<()> {
+        let event = AuditEvent::new(AuditEventType::FileAccess)
+            .with_actor(entry.tool.to_string(), None, None)
+            .with_action(
+                format!(
+                    "{} {} ({} -> {})",
+                    entry.operation, entry.path, entry.before_hash, entry.after_hash
+                ),
+                "medium".to_string(),
+                false,
+                true,
+            )
+            .with_result(entry.success, None, entry.duration_ms, entry.error);
+
+        self.log(&event)
+    }
+
     /// Backward-compatible helper to log a command execution event.
     #[allow(clippy::too_many_arguments)]
     pub fn log_command(
@@ -16,15 +16,29 @@
 //
 // For sovereign users who prefer plaintext, `secrets.encrypt = false` disables this.
 //
+// Passphrase mode (`secrets.key_source = "passphrase"`): the key is derived
+// with Argon2id from a passphrase read from `ZEROCLAW_SECRETS_PASSPHRASE`, the
+// systemd credential `zeroclaw-secrets-passphrase`, or stdin. Only the salt,
+// cost parameters and a check value are written, to `.secret_key.kdf`, so a
+// copy of the config directory is useless without the passphrase. The presence
+// of that file is what selects passphrase mode when decrypting.
+//
 // Migration: values with the legacy `enc:` prefix (XOR cipher) are decrypted
 // using the old algorithm for backward compatibility. New encryptions always
 // produce `enc2:` (ChaCha20-Poly1305).
 
+use crate::config::SecretKeySource;
 use anyhow::{Context, Result};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
 use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
 use chacha20poly1305::{AeadCore, ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
 
 /// Length of the random encryption key in bytes (256-bit, matches `ChaCha20`).
 const KEY_LEN: usize = 32;
@@ -32,13 +46,127 @@ const KEY_LEN: usize = 32;
 /// ChaCha20-Poly1305 nonce length in bytes.
 const NONCE_LEN: usize = 12;
 
+/// Argon2id salt length in bytes.
+const SALT_LEN: usize = 16;
+
+/// Argon2id memory cost in KiB (OWASP baseline: 19 MiB, 2 passes, 1 lane).
+/// Tests use a tiny cost so debug builds stay fast.
+#[cfg(not(test))]
+const ARGON2_M_COST_KIB: u32 = 19 * 1024;
+#[cfg(test)]
+const ARGON2_M_COST_KIB: u32 = 64;
+const ARGON2_T_COST: u32 = 2;
+const ARGON2_P_COST: u32 = 1;
+
+/// Known plaintext sealed under a derived key so a wrong passphrase is
+/// reported as such instead of as a corrupt secret.
+const KDF_CHECK_PLAINTEXT: &str = "zeroclaw-secret-store";
+
+/// Environment variable holding the secret store passphrase.
+pub const PASSPHRASE_ENV: &str = "ZEROCLAW_SECRETS_PASSPHRASE";
+
+/// Environment variable holding the replacement passphrase for `secrets rotate`.
+pub const NEW_PASSPHRASE_ENV: &str = "ZEROCLAW_SECRETS_NEW_PASSPHRASE";
+
+/// systemd credential name looked up under `$CREDENTIALS_DIRECTORY`.
+pub const PASSPHRASE_CREDENTIAL: &str = "zeroclaw-secrets-passphrase";
+
+/// Keys derived from a resolved passphrase, by salt. Argon2id is deliberately
+/// slow and config loading decrypts many fields, so derive once per process.
+static DERIVED_KEYS: LazyLock<Mutex<HashMap<String, Vec<u8>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Passphrase resolved from env, credential or stdin for this process.
+static RESOLVED_PASSPHRASE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Passphrase held in memory; kept out of `Debug` output.
+#[derive(Clone)]
+struct Passphrase(String);
+
+impl std::fmt::Debug for Passphrase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Passphrase(***)")
+    }
+}
+
+/// Contents of `.secret_key.kdf`. Nothing here is secret.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KdfParams {
+    algorithm: String,
+    salt: String,
+    m_cost_kib: u32,
+    t_cost: u32,
+    p_cost: u32,
+    /// `enc2:` sealing of [`KDF_CHECK_PLAINTEXT`] under the derived key.
+    check: String,
+}
+
+impl KdfParams {
+    /// Pick a fresh salt and derive a new key from `passphrase`.
+    fn generate(passphrase: &str) -> Result<(Self, Vec<u8>)> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let mut params = Self {
+            algorithm: "argon2id".into(),
+            salt: hex_encode(&salt),
+            m_cost_kib: ARGON2_M_COST_KIB,
+            t_cost: ARGON2_T_COST,
+            p_cost: ARGON2_P_COST,
+            check: String::new(),
+        };
+        let key = params.derive(passphrase)?;
+        params.check = seal(&key, KDF_CHECK_PLAINTEXT)?;
+        Ok((params, key))
+    }
+
+    fn derive(&self, passphrase: &str) -> Result<Vec<u8>> {
+        anyhow::ensure!(
+            self.algorithm == "argon2id",
+            "Unsupported key derivation algorithm '{}'",
+            self.algorithm
+        );
+        let salt = hex_decode(&self.salt).context("Key derivation salt is corrupt")?;
+        let params = Params::new(self.m_cost_kib, self.t_cost, self.p_cost, Some(KEY_LEN))
+            .map_err(|e| anyhow::anyhow!("Invalid Argon2id parameters: {e}"))?;
+        let mut key = vec![0u8; KEY_LEN];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| anyhow::anyhow!("Argon2id key derivation failed: {e}"))?;
+        Ok(key)
+    }
+
+    /// Derive the key and confirm it against the stored check value.
+    fn unlock(&self, passphrase: &str) -> Result<Vec<u8>> {
+        let key = self.derive(passphrase)?;
+        match open(&key, &self.check) {
+            Ok(check) if check == KDF_CHECK_PLAINTEXT => Ok(key),
+            _ => anyhow::bail!("Wrong secret store passphrase"),
+        }
+    }
+}
+
 /// Manages encrypted storage of secrets (API keys, tokens, etc.)
 #[derive(Debug, Clone)]
 pub struct SecretStore {
     /// Path to the key file (`~/.zeroclaw/.secret_key`)
     key_path: PathBuf,
+    /// Path to the passphrase KDF parameters (`~/.zeroclaw/.secret_key.kdf`)
+    kdf_path: PathBuf,
     /// Whether encryption is enabled
     enabled: bool,
+    /// Source used when no key material exists yet (and as the rotation target)
+    key_source: SecretKeySource,
+    /// Explicit passphrase; bypasses env/credential/stdin resolution
+    passphrase: Option<Passphrase>,
+}
+
+/// Outcome of [`SecretStore::rotate`].
+#[derive(Debug, Clone)]
+pub struct RotationReport {
+    /// Key source the secrets are now encrypted under.
+    pub key_source: SecretKeySource,
+    /// Every existing file that was scanned, with the number of values re-encrypted.
+    pub files: Vec<(PathBuf, usize)>,
 }
 
 impl SecretStore {
@@ -46,7 +174,37 @@ impl SecretStore {
     pub fn new(zeroclaw_dir: &Path, enabled: bool) -> Self {
         Self {
             key_path: zeroclaw_dir.join(".secret_key"),
+            kdf_path: zeroclaw_dir.join(".secret_key.kdf"),
             enabled,
+            key_source: SecretKeySource::File,
+            passphrase: None,
+        }
+    }
+
+    /// Create a store that honours `[secrets]`, including `key_source`.
+    pub fn from_config(zeroclaw_dir: &Path, config: &crate::config::SecretsConfig) -> Self {
+        Self {
+            key_source: config.key_source,
+            ..Self::new(zeroclaw_dir, config.encrypt)
+        }
+    }
+
+    /// Use this passphrase instead of resolving one from the environment.
+    #[must_use]
+    pub fn with_passphrase(mut self, passphrase: impl Into<String>) -> Self {
+        self.passphrase = Some(Passphrase(passphrase.into()));
+        self
+    }
+
+    /// Key source currently protecting stored secrets, judged by the key
+    /// material on disk; falls back to the configured source when there is none.
+    pub fn active_key_source(&self) -> SecretKeySource {
+        if self.kdf_path.exists() {
+            SecretKeySource::Passphrase
+        } else if self.key_path.exists() {
+            SecretKeySource::File
+        } else {
+            self.key_source
         }
     }
 
@@ -58,21 +216,8 @@ impl SecretStore {
             return Ok(plaintext.to_string());
         }
 
-        let key_bytes = self.load_or_create_key()?;
-        let key = Key::from_slice(&key_bytes);
-        let cipher = ChaCha20Poly1305::new(key);
-
-        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
-        let ciphertext = cipher
-            .encrypt(&nonce, plaintext.as_bytes())
-            .map_err(|e| anyhow::anyhow!("Encryption failed: {e}"))?;
-
-        // Prepend nonce to ciphertext for storage
-        let mut blob = Vec::with_capacity(NONCE_LEN + ciphertext.len());
-        blob.extend_from_slice(&nonce);
-        blob.extend_from_slice(&ciphertext);
-
-        Ok(format!("enc2:{}", hex_encode(&blob)))
+        let key_bytes = self.key()?;
+        seal(&key_bytes, plaintext)
     }
 
     /// Decrypt a secret.
@@ -83,10 +228,8 @@ impl SecretStore {
     /// **Warning**: Legacy `enc:` values are insecure. Use `decrypt_and_migrate` to
     /// automatically upgrade them to the secure `enc2:` format.
     pub fn decrypt(&self, value: &str) -> Result<String> {
-        if let Some(hex_str) = value.strip_prefix("enc2:") {
-            self.decrypt_chacha20(hex_str)
-        } else if let Some(hex_str) = value.strip_prefix("enc:") {
-            self.decrypt_legacy_xor(hex_str)
+        if Self::is_encrypted(value) {
+            open(&self.key()?, value)
         } else {
             Ok(value.to_string())
         }
@@ -99,18 +242,18 @@ impl SecretStore {
     ///
     /// This allows callers to persist the upgraded value back to config.
     pub fn decrypt_and_migrate(&self, value: &str) -> Result<(String, Option<String>)> {
-        if let Some(hex_str) = value.strip_prefix("enc2:") {
+        if value.starts_with("enc2:") {
             // Already using secure format — no migration needed
-            let plaintext = self.decrypt_chacha20(hex_str)?;
+            let plaintext = self.decrypt(value)?;
             Ok((plaintext, None))
-        } else if let Some(hex_str) = value.strip_prefix("enc:") {
+        } else if value.starts_with("enc:") {
             // Legacy XOR cipher — decrypt and re-encrypt with ChaCha20-Poly1305
             tracing::warn!(
                 "Decrypting legacy XOR-encrypted secret (enc: prefix). \
                  This format is insecure and will be removed in a future release. \
                  The secret will be automatically migrated to enc2: (ChaCha20-Poly1305)."
             );
-            let plaintext = self.decrypt_legacy_xor(hex_str)?;
+            let plaintext = self.decrypt(value)?;
             let migrated = self.encrypt(&plaintext)?;
             Ok((plaintext, Some(migrated)))
         } else {
@@ -124,8 +267,288 @@ impl SecretStore {
         value.starts_with("enc:")
     }
 
-    /// Decrypt using ChaCha20-Poly1305 (current secure format).
-    fn decrypt_chacha20(&self, hex_str: &str) -> Result<String> {
+    /// Check if a value is already encrypted (current or legacy format).
+    pub fn is_encrypted(value: &str) -> bool {
+        value.starts_with("enc2:") || value.starts_with("enc:")
+    }
+
+    /// Check if a value uses the secure `enc2:` format.
+    pub fn is_secure_encrypted(value: &str) -> bool {
+        value.starts_with("enc2:")
+    }
+
+    /// Re-encrypt every `enc2:` (and legacy `enc:`) value found in `files`
+    /// under a newly generated key, then replace the key material.
+    ///
+    /// The new key follows the configured `key_source`, so this is also how an
+    /// install moves between file and passphrase mode. In passphrase mode the
+    /// new key is derived from `new_passphrase`, or from the current passphrase
+    /// with a fresh salt. Files are only written once every value has been
+    /// decrypted successfully; missing files are skipped. If writing a file or
+    /// installing the key fails, every file and key already replaced is put
+    /// back, so the old key keeps working.
+    pub fn rotate(
+        &self,
+        files: &[PathBuf],
+        new_passphrase: Option<String>,
+    ) -> Result<RotationReport> {
+        let old_key = if self.kdf_path.exists() || self.key_path.exists() {
+            Some(self.key()?)
+        } else {
+            None
+        };
+
+        let (new_key, material, staged_path, final_path, stale_path) = match self.key_source {
+            SecretKeySource::File => {
+                let key = generate_random_key();
+                let material = hex_encode(&key);
+                (
+                    key,
+                    material,
+                    sibling(&self.key_path, "new"),
+                    &self.key_path,
+                    &self.kdf_path,
+                )
+            }
+            SecretKeySource::Passphrase => {
+                let passphrase = match new_passphrase {
+                    Some(passphrase) if !passphrase.is_empty() => passphrase,
+                    _ => self.passphrase()?,
+                };
+                let (params, key) = KdfParams::generate(&passphrase)?;
+                let material = serde_json::to_string_pretty(&params)?;
+                remember_passphrase(&passphrase, self.passphrase.is_none());
+                (
+                    key,
+                    material,
+                    sibling(&self.kdf_path, "new"),
+                    &self.kdf_path,
+                    &self.key_path,
+                )
+            }
+        };
+
+        let mut rewrites = Vec::new();
+        let mut report = RotationReport {
+            key_source: self.key_source,
+            files: Vec::new(),
+        };
+        for path in files {
+            if !path.is_file() {
+                continue;
+            }
+            let original = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let (rotated, count) = reencrypt_text(&original, old_key.as_deref(), &new_key)
+                .with_context(|| format!("Failed to re-encrypt secrets in {}", path.display()))?;
+            report.files.push((path.clone(), count));
+            if count > 0 {
+                rewrites.push((path, original, rotated));
+            }
+        }
+
+        // Snapshot the key material so a failure below can restore it.
+        let old_keys: Vec<(&PathBuf, Option<String>)> = [final_path, stale_path]
+            .into_iter()
+            .map(|path| (path, fs::read_to_string(path).ok()))
+            .collect();
+        let mut replaced = Vec::new();
+        let installed = (|| -> Result<()> {
+            write_private(&staged_path, &material)?;
+            for (path, original, contents) in &rewrites {
+                replace_file(path, contents)?;
+                replaced.push((*path, original.as_str()));
+            }
+            fs::rename(&staged_path, final_path).with_context(|| {
+                format!("Failed to install new key at {}", final_path.display())
+            })?;
+            if stale_path.exists() {
+                fs::remove_file(stale_path).with_context(|| {
+                    format!("Failed to remove old key {}", stale_path.display())
+                })?;
+            }
+            Ok(())
+        })();
+        if let Err(e) = installed {
+            for (path, original) in replaced {
+                if let Err(restore) = replace_file(path, original) {
+                    tracing::error!("Failed to restore {}: {restore}", path.display());
+                }
+            }
+            for (path, contents) in old_keys {
+                let restored = match contents {
+                    Some(contents) => write_private(path, &contents),
+                    None if path.exists() => fs::remove_file(path).map_err(Into::into),
+                    None => Ok(()),
+                };
+                if let Err(restore) = restored {
+                    tracing::error!("Failed to restore key {}: {restore}", path.display());
+                }
+            }
+            let _ = fs::remove_file(&staged_path);
+            return Err(e);
+        }
+        if let Ok(mut cache) = DERIVED_KEYS.lock() {
+            cache.clear();
+        }
+
+        Ok(report)
+    }
+
+    /// Load the active key: passphrase-derived when `.secret_key.kdf` exists,
+    /// otherwise the key file (created on first use unless passphrase mode is
+    /// configured, in which case fresh KDF parameters are created instead).
+    fn key(&self) -> Result<Vec<u8>> {
+        if self.kdf_path.exists() {
+            self.unlock_passphrase_key()
+        } else if self.key_source == SecretKeySource::Passphrase && !self.key_path.exists() {
+            self.create_passphrase_key()
+        } else {
+            self.load_or_create_key()
+        }
+    }
+
+    fn passphrase(&self) -> Result<String> {
+        match &self.passphrase {
+            Some(passphrase) => Ok(passphrase.0.clone()),
+            None => resolve_passphrase(),
+        }
+    }
+
+    fn unlock_passphrase_key(&self) -> Result<Vec<u8>> {
+        let raw = fs::read_to_string(&self.kdf_path)
+            .context("Failed to read secret key derivation parameters")?;
+        let params: KdfParams = serde_json::from_str(&raw)
+            .context("Secret key derivation parameter file is corrupt")?;
+
+        // Explicit passphrases bypass the cache so a wrong one is always caught.
+        if let Some(passphrase) = &self.passphrase {
+            return params.unlock(&passphrase.0);
+        }
+        if let Some(key) = DERIVED_KEYS
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get(&params.salt).cloned())
+        {
+            return Ok(key);
+        }
+        let key = params.unlock(&resolve_passphrase()?)?;
+        if let Ok(mut cache) = DERIVED_KEYS.lock() {
+            cache.insert(params.salt.clone(), key.clone());
+        }
+        Ok(key)
+    }
+
+    fn create_passphrase_key(&self) -> Result<Vec<u8>> {
+        let (params, key) = KdfParams::generate(&self.passphrase()?)?;
+        write_private(&self.kdf_path, &serde_json::to_string_pretty(&params)?)?;
+        if self.passphrase.is_none() {
+            if let Ok(mut cache) = DERIVED_KEYS.lock() {
+                cache.insert(params.salt, key.clone());
+            }
+        }
+        Ok(key)
+    }
+
+    /// Load the encryption key from disk, or create one if it doesn't exist.
+    fn load_or_create_key(&self) -> Result<Vec<u8>> {
+        if self.key_path.exists() {
+            let hex_key =
+                fs::read_to_string(&self.key_path).context("Failed to read secret key file")?;
+            hex_decode(hex_key.trim()).context("Secret key file is corrupt")
+        } else {
+            let key = generate_random_key();
+            write_private(&self.key_path, &hex_encode(&key))?;
+            Ok(key)
+        }
+    }
+}
+
+/// Resolve the secret store passphrase: `ZEROCLAW_SECRETS_PASSPHRASE`, then the
+/// `zeroclaw-secrets-passphrase` systemd credential, then stdin (a hidden prompt
+/// on a terminal, otherwise the first line). The result is kept for the process.
+pub fn resolve_passphrase() -> Result<String> {
+    if let Some(passphrase) = RESOLVED_PASSPHRASE
+        .lock()
+        .ok()
+        .and_then(|cached| cached.clone())
+    {
+        return Ok(passphrase);
+    }
+    let passphrase = read_passphrase()?;
+    remember_passphrase(&passphrase, true);
+    Ok(passphrase)
+}
+
+fn remember_passphrase(passphrase: &str, remember: bool) {
+    if remember {
+        if let Ok(mut cached) = RESOLVED_PASSPHRASE.lock() {
+            *cached = Some(passphrase.to_string());
+        }
+    }
+}
+
+fn read_passphrase() -> Result<String> {
+    if let Ok(value) = std::env::var(PASSPHRASE_ENV) {
+        if !value.is_empty() {
+            return Ok(value);
+        }
+    }
+
+    if let Some(dir) = std::env::var_os("CREDENTIALS_DIRECTORY") {
+        let path = Path::new(&dir).join(PASSPHRASE_CREDENTIAL);
+        if path.is_file() {
+            let raw = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read credential {}", path.display()))?;
+            let value = raw.trim_end_matches(['\r', '\n']);
+            if !value.is_empty() {
+                return Ok(value.to_string());
+            }
+        }
+    }
+
+    let stdin = std::io::stdin();
+    let value = if stdin.is_terminal() {
+        dialoguer::Password::new()
+            .with_prompt("Secret store passphrase")
+            .interact()
+            .context("Failed to read secret store passphrase")?
+    } else {
+        let mut line = String::new();
+        stdin
+            .read_line(&mut line)
+            .context("Failed to read secret store passphrase from stdin")?;
+        line.trim_end_matches(['\r', '\n']).to_string()
+    };
+    anyhow::ensure!(
+        !value.is_empty(),
+        "Secret store is passphrase-protected but no passphrase was provided. \
+         Set {PASSPHRASE_ENV}, load the `{PASSPHRASE_CREDENTIAL}` systemd credential, \
+         or pipe it on stdin."
+    );
+    Ok(value)
+}
+
+/// Encrypt under `key`, producing an `enc2:` value.
+fn seal(key: &[u8], plaintext: &str) -> Result<String> {
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|e| anyhow::anyhow!("Encryption failed: {e}"))?;
+
+    // Prepend nonce to ciphertext for storage
+    let mut blob = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+    blob.extend_from_slice(&nonce);
+    blob.extend_from_slice(&ciphertext);
+
+    Ok(format!("enc2:{}", hex_encode(&blob)))
+}
+
+/// Decrypt an `enc2:` (ChaCha20-Poly1305) or legacy `enc:` (XOR) value under `key`.
+fn open(key: &[u8], value: &str) -> Result<String> {
+    if let Some(hex_str) = value.strip_prefix("enc2:") {
         let blob =
             hex_decode(hex_str).context("Failed to decode encrypted secret (corrupt hex)")?;
         anyhow::ensure!(
@@ -135,9 +558,7 @@ impl SecretStore {
 
         let (nonce_bytes, ciphertext) = blob.split_at(NONCE_LEN);
         let nonce = Nonce::from_slice(nonce_bytes);
-        let key_bytes = self.load_or_create_key()?;
-        let key = Key::from_slice(&key_bytes);
-        let cipher = ChaCha20Poly1305::new(key);
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
 
         let plaintext_bytes = cipher
             .decrypt(nonce, ciphertext)
@@ -145,85 +566,111 @@ impl SecretStore {
 
         String::from_utf8(plaintext_bytes)
             .context("Decrypted secret is not valid UTF-8 — corrupt data")
-    }
-
-    /// Decrypt using legacy XOR cipher (insecure, for backward compatibility only).
-    fn decrypt_legacy_xor(&self, hex_str: &str) -> Result<String> {
+    } else if let Some(hex_str) = value.strip_prefix("enc:") {
+        // Legacy XOR cipher (insecure, for backward compatibility only).
         let ciphertext = hex_decode(hex_str)
             .context("Failed to decode legacy encrypted secret (corrupt hex)")?;
-        let key = self.load_or_create_key()?;
-        let plaintext_bytes = xor_cipher(&ciphertext, &key);
+        let plaintext_bytes = xor_cipher(&ciphertext, key);
         String::from_utf8(plaintext_bytes)
             .context("Decrypted legacy secret is not valid UTF-8 — wrong key or corrupt data")
+    } else {
+        Ok(value.to_string())
     }
+}
 
-    /// Check if a value is already encrypted (current or legacy format).
-    pub fn is_encrypted(value: &str) -> bool {
-        value.starts_with("enc2:") || value.starts_with("enc:")
+/// Replace every quoted `enc2:`/`enc:` value in a TOML or JSON document with
+/// its re-encryption under `new_key`. Editing the text in place keeps the
+/// file's formatting and comments intact. Returns the new text and the count.
+fn reencrypt_text(text: &str, old_key: Option<&[u8]>, new_key: &[u8]) -> Result<(String, usize)> {
+    static ENCRYPTED_VALUE: LazyLock<regex::Regex> = LazyLock::new(|| {
+        regex::Regex::new(r#""(enc2?:[0-9a-fA-F]+)"|'(enc2?:[0-9a-fA-F]+)'"#)
+            .expect("valid encrypted value regex")
+    });
+
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut count = 0;
+    for caps in ENCRYPTED_VALUE.captures_iter(text) {
+        let Some(value) = caps.get(1).or_else(|| caps.get(2)) else {
+            continue;
+        };
+        let old_key = old_key.context("Found encrypted values but no secret key exists")?;
+        let plaintext = open(old_key, value.as_str())?;
+        out.push_str(&text[last..value.start()]);
+        out.push_str(&seal(new_key, &plaintext)?);
+        last = value.end();
+        count += 1;
     }
+    out.push_str(&text[last..]);
+    Ok((out, count))
+}
 
-    /// Check if a value uses the secure `enc2:` format.
-    pub fn is_secure_encrypted(value: &str) -> bool {
-        value.starts_with("enc2:")
+/// `path` with `.suffix` appended to its file name.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Replace `path` via a temporary sibling, keeping its permissions.
+fn replace_file(path: &Path, contents: &str) -> Result<()> {
+    let tmp = sibling(path, "rotate.tmp");
+    fs::write(&tmp, contents).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    if let Ok(meta) = fs::metadata(path) {
+        let _ = fs::set_permissions(&tmp, meta.permissions());
     }
+    fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))
+}
 
-    /// Load the encryption key from disk, or create one if it doesn't exist.
-    fn load_or_create_key(&self) -> Result<Vec<u8>> {
-        if self.key_path.exists() {
-            let hex_key =
-                fs::read_to_string(&self.key_path).context("Failed to read secret key file")?;
-            hex_decode(hex_key.trim()).context("Secret key file is corrupt")
-        } else {
-            let key = generate_random_key();
-            if let Some(parent) = self.key_path.parent() {
-                fs::create_dir_all(parent)?;
+/// Write key material and restrict it to the current user.
+fn write_private(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents).context("Failed to write secret key file")?;
+
+    // Set restrictive permissions
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+            .context("Failed to set key file permissions")?;
+    }
+    #[cfg(windows)]
+    {
+        // On Windows, use icacls to restrict permissions to current user only
+        let username = std::env::var("USERNAME").unwrap_or_default();
+        let Some(grant_arg) = build_windows_icacls_grant_arg(&username) else {
+            tracing::warn!(
+                "USERNAME environment variable is empty; \
+                 cannot restrict key file permissions via icacls"
+            );
+            return Ok(());
+        };
+
+        match std::process::Command::new("icacls")
+            .arg(path)
+            .args(["/inheritance:r", "/grant:r"])
+            .arg(grant_arg)
+            .output()
+        {
+            Ok(o) if !o.status.success() => {
+                tracing::warn!(
+                    "Failed to set key file permissions via icacls (exit code {:?})",
+                    o.status.code()
+                );
             }
-            fs::write(&self.key_path, hex_encode(&key))
-                .context("Failed to write secret key file")?;
-
-            // Set restrictive permissions
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                fs::set_permissions(&self.key_path, fs::Permissions::from_mode(0o600))
-                    .context("Failed to set key file permissions")?;
+            Err(e) => {
+                tracing::warn!("Could not set key file permissions: {e}");
             }
-            #[cfg(windows)]
-            {
-                // On Windows, use icacls to restrict permissions to current user only
-                let username = std::env::var("USERNAME").unwrap_or_default();
-                let Some(grant_arg) = build_windows_icacls_grant_arg(&username) else {
-                    tracing::warn!(
-                        "USERNAME environment variable is empty; \
-                         cannot restrict key file permissions via icacls"
-                    );
-                    return Ok(key);
-                };
-
-                match std::process::Command::new("icacls")
-                    .arg(&self.key_path)
-                    .args(["/inheritance:r", "/grant:r"])
-                    .arg(grant_arg)
-                    .output()
-                {
-                    Ok(o) if !o.status.success() => {
-                        tracing::warn!(
-                            "Failed to set key file permissions via icacls (exit code {:?})",
-                            o.status.code()
-                        );
-                    }
-                    Err(e) => {
-                        tracing::warn!("Could not set key file permissions: {e}");
-                    }
-                    _ => {
-                        tracing::debug!("Key file permissions restricted via icacls");
-                    }
-                }
+            _ => {
+                tracing::debug!("Key file permissions restricted via icacls");
             }
-
-            Ok(key)
         }
     }
+
+    Ok(())
 }
 
 /// XOR cipher with repeating key. Same function for encrypt and decrypt.
@@ -848,4 +1295,168 @@ mod tests {
             "Key file must be owner-only (0600)"
         );
     }
+
+    // ── Passphrase mode and rotation ────────────────────────────
+
+    fn passphrase_store(dir: &Path, passphrase: &str) -> SecretStore {
+        SecretStore::from_config(
+            dir,
+            &crate::config::SecretsConfig {
+                encrypt: true,
+                key_source: SecretKeySource::Passphrase,
+            },
+        )
+        .with_passphrase(passphrase)
+    }
+
+    #[test]
+    fn passphrase_store_roundtrip_writes_no_key_file() {
+        let tmp = TempDir::new().unwrap();
+        let store = passphrase_store(tmp.path(), "correct horse");
+
+        let encrypted = store.encrypt("sk-passphrase-secret").unwrap();
+        assert!(encrypted.starts_with("enc2:"));
+        assert!(!store.key_path.exists(), "No key file in passphrase mode");
+        assert!(store.kdf_path.exists());
+        assert_eq!(store.active_key_source(), SecretKeySource::Passphrase);
+
+        // Any store on the directory detects passphrase mode from the KDF file.
+        let reopened = SecretStore::new(tmp.path(), true).with_passphrase("correct horse");
+        assert_eq!(
+            reopened.decrypt(&encrypted).unwrap(),
+            "sk-passphrase-secret"
+        );
+    }
+
+    #[test]
+    fn wrong_passphrase_is_reported() {
+        let tmp = TempDir::new().unwrap();
+        let encrypted = passphrase_store(tmp.path(), "right")
+            .encrypt("secret")
+            .unwrap();
+
+        let err = passphrase_store(tmp.path(), "wrong")
+            .decrypt(&encrypted)
+            .unwrap_err();
+        assert!(err.to_string().contains("Wrong secret store passphrase"));
+    }
+
+    #[test]
+    fn rotate_switches_file_key_to_passphrase_and_keeps_formatting() {
+        let tmp = TempDir::new().unwrap();
+        let file_store = SecretStore::new(tmp.path(), true);
+        let api_key = file_store.encrypt("sk-config").unwrap();
+        let token = file_store.encrypt("oauth-token").unwrap();
+
+        let config_path = tmp.path().join("config.toml");
+        let config = format!("# my config\napi_key = \"{api_key}\"\ndefault_model = \"x\"\n");
+        fs::write(&config_path, &config).unwrap();
+        let profiles_path = tmp.path().join("auth-profiles.json");
+        fs::write(&profiles_path, format!("{{\"token\": \"{token}\"}}")).unwrap();
+        let missing = tmp.path().join("auth-openai-pending.json");
+
+        let report = passphrase_store(tmp.path(), "s3cret")
+            .rotate(&[config_path.clone(), profiles_path.clone(), missing], None)
+            .unwrap();
+        assert_eq!(report.key_source, SecretKeySource::Passphrase);
+        assert_eq!(
+            report.files,
+            vec![(config_path.clone(), 1), (profiles_path.clone(), 1)]
+        );
+        assert!(
+            !file_store.key_path.exists(),
+            "Old key file must be removed"
+        );
+
+        let rotated = fs::read_to_string(&config_path).unwrap();
+        assert!(rotated.starts_with("# my config\napi_key = \"enc2:"));
+        assert!(rotated.ends_with("default_model = \"x\"\n"));
+        assert!(!rotated.contains(&api_key));
+
+        let store = SecretStore::new(tmp.path(), true).with_passphrase("s3cret");
+        let new_api_key = rotated
+            .split('"')
+            .find(|part| part.starts_with("enc2:"))
+            .unwrap();
+        assert_eq!(store.decrypt(new_api_key).unwrap(), "sk-config");
+        let profiles: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&profiles_path).unwrap()).unwrap();
+        assert_eq!(
+            store.decrypt(profiles["token"].as_str().unwrap()).unwrap(),
+            "oauth-token"
+        );
+    }
+
+    #[test]
+    fn rotate_with_new_passphrase_rejects_old_one() {
+        let tmp = TempDir::new().unwrap();
+        let old = passphrase_store(tmp.path(), "old-pass");
+        let secret = old.encrypt("value").unwrap();
+        let path = tmp.path().join("config.toml");
+        fs::write(&path, format!("api_key = '{secret}'\n")).unwrap();
+
+        old.rotate(std::slice::from_ref(&path), Some("new-pass".into()))
+            .unwrap();
+
+        let rotated = fs::read_to_string(&path).unwrap();
+        let value = rotated.split('\'').nth(1).unwrap();
+        assert!(passphrase_store(tmp.path(), "old-pass")
+            .decrypt(value)
+            .is_err());
+        assert_eq!(
+            passphrase_store(tmp.path(), "new-pass")
+                .decrypt(value)
+                .unwrap(),
+            "value"
+        );
+    }
+
+    #[test]
+    fn rotate_leaves_files_untouched_when_a_value_fails_to_decrypt() {
+        let tmp = TempDir::new().unwrap();
+        let store = SecretStore::new(tmp.path(), true);
+        let good = store.encrypt("good").unwrap();
+        let foreign = SecretStore::new(TempDir::new().unwrap().path(), true)
+            .encrypt("foreign")
+            .unwrap();
+        let path = tmp.path().join("config.toml");
+        let original = format!("a = \"{good}\"\nb = \"{foreign}\"\n");
+        fs::write(&path, &original).unwrap();
+        let old_key = fs::read_to_string(&store.key_path).unwrap();
+
+        assert!(store.rotate(std::slice::from_ref(&path), None).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+        assert_eq!(fs::read_to_string(&store.key_path).unwrap(), old_key);
+    }
+
+    #[test]
+    fn rotate_restores_files_and_key_when_a_rewrite_fails() {
+        let tmp = TempDir::new().unwrap();
+        let store = SecretStore::new(tmp.path(), true);
+        let secret = store.encrypt("value").unwrap();
+        let first = tmp.path().join("config.toml");
+        let second = tmp.path().join("auth-profiles.json");
+        let first_original = format!("api_key = \"{secret}\"\n");
+        let second_original = format!("{{\"token\": \"{secret}\"}}");
+        fs::write(&first, &first_original).unwrap();
+        fs::write(&second, &second_original).unwrap();
+        let old_key = fs::read_to_string(&store.key_path).unwrap();
+
+        // A directory where the second file's temp copy goes makes its write
+        // fail after the first file has already been replaced.
+        fs::create_dir(sibling(&second, "rotate.tmp")).unwrap();
+        let err = store
+            .rotate(&[first.clone(), second.clone()], None)
+            .unwrap_err();
+        assert!(err.to_string().contains("rotate.tmp"), "{err}");
+
+        assert_eq!(fs::read_to_string(&first).unwrap(), first_original);
+        assert_eq!(fs::read_to_string(&second).unwrap(), second_original);
+        assert_eq!(fs::read_to_string(&store.key_path).unwrap(), old_key);
+        assert!(!sibling(&store.key_path, "new").exists());
+        assert_eq!(
+            SecretStore::new(tmp.path(), true).decrypt(&secret).unwrap(),
+            "value"
+        );
+    }
 }
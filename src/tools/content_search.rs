@@ -0,0 +1,684 @@
+use super::traits::{Tool, ToolResult};
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use glob::{MatchOptions, Pattern};
+use regex::{Regex, RegexBuilder};
+use serde_json::json;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const DEFAULT_MAX_MATCHES: usize = 100;
+const MAX_MATCHES_LIMIT: usize = 1000;
+const DEFAULT_MAX_BYTES: usize = 32 * 1024;
+const MAX_BYTES_LIMIT: usize = 256 * 1024;
+const MAX_CONTEXT_LINES: usize = 20;
+
+/// Files larger than this are skipped rather than searched.
+const MAX_FILE_SIZE_BYTES: u64 = 2 * 1024 * 1024;
+
+/// A NUL byte within this prefix marks a file as binary.
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+/// Longer lines are cut in the output.
+const MAX_LINE_CHARS: usize = 500;
+
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".ignore"];
+
+const GLOB_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// One line of a `.gitignore`/`.ignore` file.
+struct IgnoreRule {
+    /// Workspace-relative directory holding the ignore file.
+    base: PathBuf,
+    pattern: Pattern,
+    negate: bool,
+    dir_only: bool,
+    /// Contains a `/`, so it matches relative to `base` instead of any name.
+    anchored: bool,
+}
+
+impl IgnoreRule {
+    fn parse(base: &Path, line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negate, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let line = line.strip_prefix('/').unwrap_or(line);
+        Some(Self {
+            base: base.to_path_buf(),
+            pattern: Pattern::new(line).ok()?,
+            negate,
+            dir_only,
+            anchored,
+        })
+    }
+
+    /// `Some(ignored)` when the rule applies to `rel`.
+    fn verdict(&self, rel: &Path, is_dir: bool) -> Option<bool> {
+        if self.dir_only && !is_dir {
+            return None;
+        }
+        let sub = rel.strip_prefix(&self.base).ok()?;
+        let matched = if self.anchored {
+            self.pattern.matches_path_with(sub, GLOB_OPTIONS)
+        } else {
+            sub.file_name().is_some_and(|name| {
+                self.pattern
+                    .matches_with(&name.to_string_lossy(), GLOB_OPTIONS)
+            })
+        };
+        matched.then_some(!self.negate)
+    }
+}
+
+fn load_ignore_rules(dir: &Path, rel_dir: &Path, rules: &mut Vec<IgnoreRule>) {
+    for name in IGNORE_FILES {
+        if let Ok(contents) = std::fs::read_to_string(dir.join(name)) {
+            rules.extend(
+                contents
+                    .lines()
+                    .filter_map(|line| IgnoreRule::parse(rel_dir, line)),
+            );
+        }
+    }
+}
+
+fn is_ignored(rules: &[IgnoreRule], rel: &Path, is_dir: bool) -> bool {
+    // Later rules, and rules from deeper ignore files, take precedence.
+    rules
+        .iter()
+        .rev()
+        .find_map(|rule| rule.verdict(rel, is_dir))
+        .unwrap_or(false)
+}
+
+/// Include/exclude glob: patterns without a `/` match the file name,
+/// others the workspace-relative path.
+fn glob_matches(pattern: &Pattern, rel: &Path) -> bool {
+    if pattern.as_str().contains('/') {
+        pattern.matches_path_with(rel, GLOB_OPTIONS)
+    } else {
+        rel.file_name()
+            .is_some_and(|name| pattern.matches_with(&name.to_string_lossy(), GLOB_OPTIONS))
+    }
+}
+
+#[derive(Default)]
+struct Outcome {
+    output: String,
+    matches: usize,
+    files_matched: usize,
+    files_searched: usize,
+    skipped_large: usize,
+    truncated_matches: bool,
+    truncated_bytes: bool,
+}
+
+struct Search<'a> {
+    security: &'a SecurityPolicy,
+    workspace: PathBuf,
+    regex: Regex,
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+    gitignore: bool,
+    hidden: bool,
+    context: usize,
+    max_matches: usize,
+    max_bytes: usize,
+}
+
+impl Search<'_> {
+    fn run(&self, root: &Path) -> Outcome {
+        let mut outcome = Outcome::default();
+        let mut rules = Vec::new();
+        let rel_root = root.strip_prefix(&self.workspace).unwrap_or(Path::new(""));
+
+        if root.is_file() {
+            self.search_file(root, rel_root, &mut outcome);
+            return outcome;
+        }
+
+        // Ignore files above the search root still apply to it.
+        if self.gitignore {
+            let mut dir = self.workspace.clone();
+            let mut rel_dir = PathBuf::new();
+            for component in rel_root.components() {
+                load_ignore_rules(&dir, &rel_dir, &mut rules);
+                dir.push(component);
+                rel_dir.push(component);
+            }
+        }
+        self.walk(root, rel_root, &mut rules, &mut outcome);
+        outcome
+    }
+
+    fn done(outcome: &Outcome) -> bool {
+        outcome.truncated_matches || outcome.truncated_bytes
+    }
+
+    fn walk(&self, dir: &Path, rel_dir: &Path, rules: &mut Vec<IgnoreRule>, outcome: &mut Outcome) {
+        if !self.security.is_resolved_path_allowed(dir) {
+            return;
+        }
+        let depth = rules.len();
+        if self.gitignore {
+            load_ignore_rules(dir, rel_dir, rules);
+        }
+
+        let mut entries: Vec<_> = match std::fs::read_dir(dir) {
+            Ok(entries) => entries.filter_map(Result::ok).collect(),
+            Err(_) => Vec::new(),
+        };
+        entries.sort_by_key(std::fs::DirEntry::file_name);
+
+        for entry in entries {
+            if Self::done(outcome) {
+                break;
+            }
+            let name = entry.file_name();
+            let name_str = name.to_string_lossy();
+            if name_str == ".git" || (!self.hidden && name_str.starts_with('.')) {
+                continue;
+            }
+            // Symlinks are never followed, so the walk cannot leave `dir`.
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let rel = rel_dir.join(&name);
+            let is_dir = file_type.is_dir();
+            if file_type.is_symlink() || (self.gitignore && is_ignored(rules, &rel, is_dir)) {
+                continue;
+            }
+            if is_dir {
+                self.walk(&entry.path(), &rel, rules, outcome);
+            } else if file_type.is_file() && self.wants(&rel) {
+                self.search_file(&entry.path(), &rel, outcome);
+            }
+        }
+        rules.truncate(depth);
+    }
+
+    fn wants(&self, rel: &Path) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|p| glob_matches(p, rel)))
+            && !self.exclude.iter().any(|p| glob_matches(p, rel))
+    }
+
+    fn search_file(&self, path: &Path, rel: &Path, outcome: &mut Outcome) {
+        if std::fs::metadata(path).is_ok_and(|meta| meta.len() > MAX_FILE_SIZE_BYTES) {
+            outcome.skipped_large += 1;
+            return;
+        }
+        let Ok(bytes) = std::fs::read(path) else {
+            return;
+        };
+        if bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0) {
+            return;
+        }
+        outcome.files_searched += 1;
+        let text = String::from_utf8_lossy(&bytes);
+        let lines: Vec<&str> = text.lines().collect();
+
+        let mut hits: Vec<usize> = Vec::new();
+        for (idx, line) in lines.iter().enumerate() {
+            if self.regex.is_match(line) {
+                if outcome.matches + hits.len() >= self.max_matches {
+                    outcome.truncated_matches = true;
+                    break;
+                }
+                hits.push(idx);
+            }
+        }
+        if hits.is_empty() {
+            return;
+        }
+        outcome.files_matched += 1;
+
+        outcome.matches += hits.len();
+
+        // Merge each match's context window with overlapping neighbours.
+        let mut blocks: Vec<(usize, usize)> = Vec::new();
+        for &hit in &hits {
+            let (start, end) = (
+                hit.saturating_sub(self.context),
+                (hit + self.context).min(lines.len() - 1),
+            );
+            match blocks.last_mut() {
+                Some(last) if start <= last.1 + 1 => last.1 = end,
+                _ => blocks.push((start, end)),
+            }
+        }
+
+        let rel = rel.to_string_lossy();
+        for (start, end) in blocks {
+            if self.context > 0 && !outcome.output.is_empty() {
+                self.push(outcome, "--\n");
+            }
+            for (idx, line) in lines.iter().enumerate().take(end + 1).skip(start) {
+                let marker = if hits.binary_search(&idx).is_ok() {
+                    ':'
+                } else {
+                    '-'
+                };
+                let mut shown: String = line.chars().take(MAX_LINE_CHARS).collect();
+                if shown.len() < line.len() {
+                    shown.push_str(" […]");
+                }
+                self.push(
+                    outcome,
+                    &format!("{rel}{marker}{}{marker}{shown}\n", idx + 1),
+                );
+            }
+        }
+    }
+
+    fn push(&self, outcome: &mut Outcome, text: &str) {
+        if outcome.truncated_bytes {
+            return;
+        }
+        if outcome.output.len() + text.len() > self.max_bytes {
+            outcome.truncated_bytes = true;
+            return;
+        }
+        outcome.output.push_str(text);
+    }
+}
+
+fn failure(error: impl Into<String>) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error.into()),
+    }
+}
+
+fn glob_list(args: &serde_json::Value, key: &str) -> Result<Vec<Pattern>, String> {
+    let Some(value) = args.get(key).filter(|v| !v.is_null()) else {
+        return Ok(Vec::new());
+    };
+    let items: Vec<&str> = match value {
+        serde_json::Value::String(single) => vec![single.as_str()],
+        serde_json::Value::Array(items) => items.iter().filter_map(|v| v.as_str()).collect(),
+        _ => return Err(format!("'{key}' must be a glob or an array of globs")),
+    };
+    items
+        .into_iter()
+        .map(|glob| Pattern::new(glob).map_err(|e| format!("Invalid {key} glob '{glob}': {e}")))
+        .collect()
+}
+
+fn capped(args: &serde_json::Value, key: &str, default: usize, limit: usize) -> usize {
+    args.get(key)
+        .and_then(serde_json::Value::as_u64)
+        .map_or(default, |v| usize::try_from(v).unwrap_or(limit))
+        .min(limit)
+}
+
+/// Search file contents by regex within the workspace, honouring ignore files.
+pub struct ContentSearchTool {
+    security: Arc<SecurityPolicy>,
+}
+
+impl ContentSearchTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self { security }
+    }
+}
+
+#[async_trait]
+impl Tool for ContentSearchTool {
+    fn name(&self) -> &str {
+        "content_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search file contents in the workspace by regex (or literal text), like ripgrep. \
+         Skips .gitignore'd, hidden and binary files by default. \
+         Output lines are 'path:line:text' for matches and 'path-line-text' for context."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "pattern": {
+                    "type": "string",
+                    "description": "Regex to search for (Rust regex syntax), or plain text when literal is true"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "File or directory to search, relative to the workspace (default: whole workspace)"
+                },
+                "literal": {
+                    "type": "boolean",
+                    "description": "Treat pattern as plain text (default: false)"
+                },
+                "case": {
+                    "type": "string",
+                    "enum": ["sensitive", "insensitive", "smart"],
+                    "description": "Case matching; 'smart' is insensitive unless the pattern has uppercase (default: sensitive)"
+                },
+                "include": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Only search files matching these globs, e.g. ['*.rs', 'src/**/*.toml']"
+                },
+                "exclude": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Skip files matching these globs"
+                },
+                "context": {
+                    "type": "integer",
+                    "description": "Lines of context before and after each match (default: 0, max: 20)"
+                },
+                "max_matches": {
+                    "type": "integer",
+                    "description": "Stop after this many matching lines (default: 100, max: 1000)"
+                },
+                "max_bytes": {
+                    "type": "integer",
+                    "description": "Cap on output size in bytes (default: 32768, max: 262144)"
+                },
+                "gitignore": {
+                    "type": "boolean",
+                    "description": "Respect .gitignore and .ignore files (default: true)"
+                },
+                "hidden": {
+                    "type": "boolean",
+                    "description": "Search hidden files and directories (default: false)"
+                }
+            },
+            "required": ["pattern"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let pattern = args
+            .get("pattern")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'pattern' parameter"))?;
+        let path = args.get("path").and_then(|v| v.as_str()).unwrap_or(".");
+        let flag = |key: &str, default: bool| {
+            args.get(key)
+                .and_then(serde_json::Value::as_bool)
+                .unwrap_or(default)
+        };
+
+        let source = if flag("literal", false) {
+            regex::escape(pattern)
+        } else {
+            pattern.to_string()
+        };
+        let case_insensitive = match args.get("case").and_then(|v| v.as_str()) {
+            None | Some("sensitive") => false,
+            Some("insensitive") => true,
+            Some("smart") => !pattern.chars().any(char::is_uppercase),
+            Some(other) => {
+                return Ok(failure(format!(
+                    "Invalid case '{other}': expected sensitive, insensitive or smart"
+                )))
+            }
+        };
+        let regex = match RegexBuilder::new(&source)
+            .case_insensitive(case_insensitive)
+            .build()
+        {
+            Ok(regex) => regex,
+            Err(e) => return Ok(failure(format!("Invalid regex: {e}"))),
+        };
+        let (include, exclude) = match (glob_list(&args, "include"), glob_list(&args, "exclude")) {
+            (Ok(include), Ok(exclude)) => (include, exclude),
+            (Err(e), _) | (_, Err(e)) => return Ok(failure(e)),
+        };
+
+        if self.security.is_rate_limited() {
+            return Ok(failure(
+                "Rate limit exceeded: too many actions in the last hour",
+            ));
+        }
+
+        // Security check: validate path is within workspace
+        if !self.security.is_path_allowed(path) {
+            return Ok(failure(format!(
+                "Path not allowed by security policy: {path}"
+            )));
+        }
+
+        if !self.security.record_action() {
+            return Ok(failure("Rate limit exceeded: action budget exhausted"));
+        }
+
+        let workspace = match tokio::fs::canonicalize(&self.security.workspace_dir).await {
+            Ok(p) => p,
+            Err(e) => return Ok(failure(format!("Cannot resolve workspace directory: {e}"))),
+        };
+        let root = match tokio::fs::canonicalize(workspace.join(path)).await {
+            Ok(p) => p,
+            Err(e) => return Ok(failure(format!("Failed to resolve search path: {e}"))),
+        };
+        if !self.security.is_resolved_path_allowed(&root) {
+            return Ok(failure(format!(
+                "Resolved path escapes workspace: {}",
+                root.display()
+            )));
+        }
+
+        let security = self.security.clone();
+        let context = capped(&args, "context", 0, MAX_CONTEXT_LINES);
+        let max_matches = capped(&args, "max_matches", DEFAULT_MAX_MATCHES, MAX_MATCHES_LIMIT);
+        let max_bytes = capped(&args, "max_bytes", DEFAULT_MAX_BYTES, MAX_BYTES_LIMIT);
+        let gitignore = flag("gitignore", true);
+        let hidden = flag("hidden", false);
+        let outcome = tokio::task::spawn_blocking(move || {
+            Search {
+                security: &security,
+                workspace,
+                regex,
+                include,
+                exclude,
+                gitignore,
+                hidden,
+                context,
+                max_matches,
+                max_bytes,
+            }
+            .run(&root)
+        })
+        .await?;
+
+        if outcome.matches == 0 {
+            return Ok(ToolResult {
+                success: true,
+                output: format!(
+                    "No matches for '{pattern}' ({} files searched).",
+                    outcome.files_searched
+                ),
+                error: None,
+            });
+        }
+
+        let mut output = outcome.output;
+        if outcome.truncated_matches {
+            let _ = write!(
+                output,
+                "\n[Stopped at {max_matches} matches; narrow the pattern or path, or raise max_matches]"
+            );
+        }
+        if outcome.truncated_bytes {
+            let _ = write!(
+                output,
+                "\n[Output truncated at {max_bytes} bytes; narrow the search or raise max_bytes]"
+            );
+        }
+        if outcome.skipped_large > 0 {
+            let _ = write!(
+                output,
+                "\n[Skipped {} files larger than {MAX_FILE_SIZE_BYTES} bytes]",
+                outcome.skipped_large
+            );
+        }
+        let _ = write!(
+            output,
+            "\n\nTotal: {} matches in {} files",
+            outcome.matches, outcome.files_matched
+        );
+
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{AutonomyLevel, SecurityPolicy};
+    use tempfile::TempDir;
+
+    fn test_security(workspace: PathBuf) -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: workspace,
+            ..SecurityPolicy::default()
+        })
+    }
+
+    fn workspace() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let write = |rel: &str, contents: &str| {
+            let path = dir.path().join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        };
+        write(
+            "src/main.rs",
+            "fn main() {\n    let todo = 1;\n    // TODO: ship\n}\n",
+        );
+        write("src/lib.rs", "// todo later\npub fn lib() {}\n");
+        write("notes.md", "TODO list\n");
+        write("target/debug/out.rs", "// TODO generated\n");
+        write(".hidden/secret.rs", "// TODO hidden\n");
+        write("bin.dat", "TODO\0binary");
+        write(".gitignore", "target/\n*.md\n!keep.md\n");
+        write("keep.md", "TODO kept\n");
+        dir
+    }
+
+    async fn search(dir: &TempDir, args: serde_json::Value) -> ToolResult {
+        ContentSearchTool::new(test_security(dir.path().to_path_buf()))
+            .execute(args)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn content_search_name_and_schema() {
+        let tool = ContentSearchTool::new(test_security(std::env::temp_dir()));
+        assert_eq!(tool.name(), "content_search");
+        let schema = tool.parameters_schema();
+        assert!(schema["properties"]["pattern"].is_object());
+        assert_eq!(schema["required"], json!(["pattern"]));
+    }
+
+    #[tokio::test]
+    async fn content_search_respects_ignore_rules_hidden_and_binary() {
+        let dir = workspace();
+        let result = search(&dir, json!({"pattern": "TODO"})).await;
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("keep.md:1:TODO kept"));
+        assert!(result.output.contains("src/main.rs:3:    // TODO: ship"));
+        for skipped in ["notes.md", "target/", ".hidden", "bin.dat", "src/lib.rs"] {
+            assert!(
+                !result.output.contains(skipped),
+                "{skipped}: {}",
+                result.output
+            );
+        }
+        assert!(result.output.ends_with("Total: 2 matches in 2 files"));
+
+        let result = search(
+            &dir,
+            json!({"pattern": "TODO", "gitignore": false, "hidden": true}),
+        )
+        .await;
+        for found in ["notes.md", "target/debug/out.rs", ".hidden/secret.rs"] {
+            assert!(result.output.contains(found), "{found}: {}", result.output);
+        }
+    }
+
+    #[tokio::test]
+    async fn content_search_case_literal_and_globs() {
+        let dir = workspace();
+        let result = search(
+            &dir,
+            json!({"pattern": "todo", "case": "smart", "include": ["*.rs"], "exclude": ["src/lib.rs"]}),
+        )
+        .await;
+        assert!(result.output.contains("src/main.rs:2:"));
+        assert!(result.output.contains("src/main.rs:3:"));
+        assert!(!result.output.contains("src/lib.rs"));
+
+        let result = search(&dir, json!({"pattern": "fn main()", "literal": true})).await;
+        assert!(result.output.contains("src/main.rs:1:fn main() {"));
+
+        let result = search(&dir, json!({"pattern": "fn main("})).await;
+        assert!(result.error.unwrap().contains("Invalid regex"));
+    }
+
+    #[tokio::test]
+    async fn content_search_context_and_caps() {
+        let dir = workspace();
+        let result = search(
+            &dir,
+            json!({"pattern": "let todo", "path": "src/main.rs", "context": 1}),
+        )
+        .await;
+        assert!(result.output.starts_with(
+            "src/main.rs-1-fn main() {\nsrc/main.rs:2:    let todo = 1;\nsrc/main.rs-3-    // TODO: ship\n"
+        ), "{}", result.output);
+
+        let result = search(&dir, json!({"pattern": "TODO", "max_matches": 1})).await;
+        assert!(result.output.contains("Stopped at 1 matches"));
+        assert!(result.output.contains("Total: 1 matches"));
+
+        let result = search(&dir, json!({"pattern": "TODO", "max_bytes": 10})).await;
+        assert!(result.output.contains("Output truncated at 10 bytes"));
+    }
+
+    #[tokio::test]
+    async fn content_search_rejects_paths_outside_workspace() {
+        let dir = workspace();
+        let result = search(&dir, json!({"pattern": "x", "path": "../"})).await;
+        assert!(result.error.unwrap().contains("not allowed"));
+        let result = search(&dir, json!({"pattern": "x", "path": "/etc"})).await;
+        assert!(!result.success);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn content_search_does_not_follow_symlinks() {
+        let dir = workspace();
+        let outside = TempDir::new().unwrap();
+        std::fs::write(outside.path().join("leak.txt"), "TODO outside\n").unwrap();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("link")).unwrap();
+
+        let result = search(&dir, json!({"pattern": "outside"})).await;
+        assert!(result.output.starts_with("No matches"), "{}", result.output);
+    }
+}
@@ -1,6 +1,8 @@
 use super::traits::{Tool, ToolResult};
 use crate::config::Config;
-use crate::cron::{self, DeliveryConfig, JobType, Schedule, SessionTarget};
+use crate::cron::{
+    self, CronJobPatch, DeliveryConfig, JobPolicy, JobType, Schedule, SessionTarget, WorkflowStep,
+};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
@@ -54,7 +56,7 @@ impl Tool for CronAddTool {
     }
 
     fn description(&self) -> &str {
-        "Create a scheduled cron job (shell or agent) with cron/at/every schedules"
+        "Create a scheduled cron job (shell, agent or multi-step workflow) with cron/at/every schedules, chained after another job, or triggered by a gateway webhook or workspace file changes"
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -64,15 +66,24 @@ impl Tool for CronAddTool {
                 "name": { "type": "string" },
                 "schedule": {
                     "type": "object",
-                    "description": "Schedule object: {kind:'cron',expr,tz?} | {kind:'at',at} | {kind:'every',every_ms}"
+                    "description": "Schedule object: {kind:'cron',expr,tz?} | {kind:'at',at} | {kind:'every',every_ms} | {kind:'after',job_id,on?:'success'|'failure'|'always'} | {kind:'webhook'} (token returned once) | {kind:'watch',glob}"
                 },
-                "job_type": { "type": "string", "enum": ["shell", "agent"] },
+                "job_type": { "type": "string", "enum": ["shell", "agent", "workflow"] },
                 "command": { "type": "string" },
                 "prompt": { "type": "string" },
                 "session_target": { "type": "string", "enum": ["isolated", "main"] },
                 "model": { "type": "string" },
                 "delivery": { "type": "object" },
                 "delete_after_run": { "type": "boolean" },
+                "policy": {
+                    "type": "object",
+                    "description": "Execution policy: {misfire?:'run_once'|'run_all'|'skip', concurrency?:'no_overlap'|'queue'|'replace', timeout_secs?, max_retries?}"
+                },
+                "steps": {
+                    "type": "array",
+                    "description": "Workflow steps: [{id, job_type:'shell'|'agent', command?, prompt?, model?, depends_on?:[ids], run_if?:'success'|'failure'|'always'}]",
+                    "items": { "type": "object" }
+                },
                 "approved": {
                     "type": "boolean",
                     "description": "Set true to explicitly approve medium/high-risk shell commands in supervised mode",
@@ -92,7 +103,7 @@ impl Tool for CronAddTool {
             });
         }
 
-        let schedule = match args.get("schedule") {
+        let mut schedule = match args.get("schedule") {
             Some(v) => match serde_json::from_value::<Schedule>(v.clone()) {
                 Ok(schedule) => schedule,
                 Err(e) => {
@@ -112,6 +123,26 @@ impl Tool for CronAddTool {
             }
         };
 
+        // Webhook tokens are always minted here; a caller-supplied hash is ignored.
+        if let Schedule::Webhook { token_hash } = &mut schedule {
+            token_hash.clear();
+        }
+        let webhook_token = cron::issue_webhook_token(&mut schedule);
+
+        let policy = match args.get("policy") {
+            Some(v) => match serde_json::from_value::<JobPolicy>(v.clone()) {
+                Ok(policy) => Some(policy),
+                Err(e) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!("Invalid policy: {e}")),
+                    });
+                }
+            },
+            None => None,
+        };
+
         let name = args
             .get("name")
             .and_then(serde_json::Value::as_str)
@@ -120,6 +151,7 @@ impl Tool for CronAddTool {
         let job_type = match args.get("job_type").and_then(serde_json::Value::as_str) {
             Some("agent") => JobType::Agent,
             Some("shell") => JobType::Shell,
+            Some("workflow") => JobType::Workflow,
             Some(other) => {
                 return Ok(ToolResult {
                     success: false,
@@ -128,7 +160,9 @@ impl Tool for CronAddTool {
                 });
             }
             None => {
-                if args.get("prompt").is_some() {
+                if args.get("steps").is_some() {
+                    JobType::Workflow
+                } else if args.get("prompt").is_some() {
                     JobType::Agent
                 } else {
                     JobType::Shell
@@ -233,21 +267,94 @@ impl Tool for CronAddTool {
                     delete_after_run,
                 )
             }
+            JobType::Workflow => {
+                let steps = match args.get("steps") {
+                    Some(v) => match serde_json::from_value::<Vec<WorkflowStep>>(v.clone()) {
+                        Ok(steps) => steps,
+                        Err(e) => {
+                            return Ok(ToolResult {
+                                success: false,
+                                output: String::new(),
+                                error: Some(format!("Invalid workflow steps: {e}")),
+                            });
+                        }
+                    },
+                    None => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some("Missing 'steps' for workflow job".to_string()),
+                        });
+                    }
+                };
+
+                for step in steps.iter().filter(|s| s.job_type == JobType::Shell) {
+                    if let Err(reason) = self
+                        .security
+                        .validate_command_execution(&step.command, approved)
+                    {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(format!("Step '{}': {reason}", step.id)),
+                        });
+                    }
+                }
+
+                let delivery = match args.get("delivery") {
+                    Some(v) => match serde_json::from_value::<DeliveryConfig>(v.clone()) {
+                        Ok(cfg) => Some(cfg),
+                        Err(e) => {
+                            return Ok(ToolResult {
+                                success: false,
+                                output: String::new(),
+                                error: Some(format!("Invalid delivery config: {e}")),
+                            });
+                        }
+                    },
+                    None => None,
+                };
+
+                if let Some(blocked) = self.enforce_mutation_allowed("cron_add") {
+                    return Ok(blocked);
+                }
+
+                cron::add_workflow_job(&self.config, name, schedule, steps, delivery)
+            }
+        };
+
+        let result = match (result, policy) {
+            (Ok(job), Some(policy)) => {
+                let patch = CronJobPatch {
+                    policy: Some(policy),
+                    ..CronJobPatch::default()
+                };
+                cron::update_job(&self.config, &job.id, patch)
+            }
+            (result, _) => result,
         };
 
         match result {
-            Ok(job) => Ok(ToolResult {
-                success: true,
-                output: serde_json::to_string_pretty(&json!({
+            Ok(job) => {
+                let mut output = json!({
                     "id": job.id,
                     "name": job.name,
                     "job_type": job.job_type,
                     "schedule": job.schedule,
                     "next_run": job.next_run,
-                    "enabled": job.enabled
-                }))?,
-                error: None,
-            }),
+                    "enabled": job.enabled,
+                    "policy": job.policy
+                });
+                if let Some(token) = webhook_token {
+                    output["webhook_path"] = json!(format!("/cron/{}", job.id));
+                    output["webhook_token"] = json!(token);
+                }
+                Ok(ToolResult {
+                    success: true,
+                    output: serde_json::to_string_pretty(&output)?,
+                    error: None,
+                })
+            }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
@@ -438,4 +545,132 @@ mod tests {
             .unwrap_or_default()
             .contains("Missing 'prompt'"));
     }
+
+    #[tokio::test]
+    async fn adds_workflow_and_dependent_jobs() {
+        let tmp = TempDir::new().unwrap();
+        let cfg = test_config(&tmp).await;
+        let tool = CronAddTool::new(cfg.clone(), test_security(&cfg));
+
+        let workflow = tool
+            .execute(json!({
+                "name": "nightly",
+                "schedule": { "kind": "cron", "expr": "0 2 * * *" },
+                "steps": [
+                    { "id": "build", "command": "echo build" },
+                    { "id": "report", "command": "echo report", "depends_on": ["build"] }
+                ]
+            }))
+            .await
+            .unwrap();
+        assert!(workflow.success, "{:?}", workflow.error);
+        let jobs = cron::list_jobs(&cfg).unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].job_type, JobType::Workflow);
+        assert_eq!(jobs[0].steps.len(), 2);
+
+        let dependent = tool
+            .execute(json!({
+                "schedule": { "kind": "after", "job_id": jobs[0].id, "on": "failure" },
+                "command": "echo cleanup"
+            }))
+            .await
+            .unwrap();
+        assert!(dependent.success, "{:?}", dependent.error);
+
+        let missing_upstream = tool
+            .execute(json!({
+                "schedule": { "kind": "after", "job_id": "missing" },
+                "command": "echo cleanup"
+            }))
+            .await
+            .unwrap();
+        assert!(!missing_upstream.success);
+        assert!(missing_upstream
+            .error
+            .unwrap_or_default()
+            .contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn rejects_cyclic_workflow() {
+        let tmp = TempDir::new().unwrap();
+        let cfg = test_config(&tmp).await;
+        let tool = CronAddTool::new(cfg.clone(), test_security(&cfg));
+
+        let result = tool
+            .execute(json!({
+                "schedule": { "kind": "cron", "expr": "0 2 * * *" },
+                "job_type": "workflow",
+                "steps": [
+                    { "id": "a", "command": "echo a", "depends_on": ["b"] },
+                    { "id": "b", "command": "echo b", "depends_on": ["a"] }
+                ]
+            }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap_or_default().contains("cycle"));
+    }
+
+    #[tokio::test]
+    async fn webhook_job_returns_token_once_and_ignores_supplied_hash() {
+        let tmp = TempDir::new().unwrap();
+        let cfg = test_config(&tmp).await;
+        let tool = CronAddTool::new(cfg.clone(), test_security(&cfg));
+        let supplied = "a".repeat(64);
+        let result = tool
+            .execute(json!({
+                "schedule": { "kind": "webhook", "token_hash": supplied },
+                "command": "echo hook"
+            }))
+            .await
+            .unwrap();
+
+        assert!(result.success, "{:?}", result.error);
+        let output: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        let token = output["webhook_token"].as_str().unwrap();
+        let id = output["id"].as_str().unwrap();
+        assert_eq!(output["webhook_path"], format!("/cron/{id}"));
+
+        let job = cron::get_job(&cfg, id).unwrap();
+        assert!(cron::verify_webhook_token(&job.schedule, token));
+        assert_ne!(
+            job.schedule,
+            Schedule::Webhook {
+                token_hash: supplied
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn applies_job_policy() {
+        let tmp = TempDir::new().unwrap();
+        let cfg = test_config(&tmp).await;
+        let tool = CronAddTool::new(cfg.clone(), test_security(&cfg));
+        let result = tool
+            .execute(json!({
+                "schedule": { "kind": "every", "every_ms": 60000 },
+                "command": "echo ok",
+                "policy": { "misfire": "skip", "concurrency": "replace", "timeout_secs": 30 }
+            }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let output: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        let job = cron::get_job(&cfg, output["id"].as_str().unwrap()).unwrap();
+        assert_eq!(job.policy.misfire, cron::MisfirePolicy::Skip);
+        assert_eq!(job.policy.concurrency, cron::ConcurrencyPolicy::Replace);
+        assert_eq!(job.policy.timeout_secs, Some(30));
+
+        let result = tool
+            .execute(json!({
+                "schedule": { "kind": "every", "every_ms": 60000 },
+                "command": "echo ok",
+                "policy": { "misfire": "sometimes" }
+            }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+    }
 }
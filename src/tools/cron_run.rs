@@ -94,11 +94,18 @@ impl Tool for CronRunTool {
             }
         };
 
-        if matches!(job.job_type, JobType::Shell) {
-            if let Err(reason) = self
-                .security
-                .validate_command_execution(&job.command, approved)
-            {
+        let shell_commands: Vec<&str> = match job.job_type {
+            JobType::Shell => vec![job.command.as_str()],
+            JobType::Workflow => job
+                .steps
+                .iter()
+                .filter(|step| step.job_type == JobType::Shell)
+                .map(|step| step.command.as_str())
+                .collect(),
+            JobType::Agent => Vec::new(),
+        };
+        for command in shell_commands {
+            if let Err(reason) = self.security.validate_command_execution(command, approved) {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
@@ -131,6 +138,7 @@ impl Tool for CronRunTool {
             duration_ms,
         );
         let _ = cron::record_last_run(&self.config, &job.id, finished_at, success, &output);
+        cron::scheduler::trigger_dependents(&self.config, &job, success, &output).await;
 
         Ok(ToolResult {
             success,
@@ -1,6 +1,6 @@
 use super::traits::{Tool, ToolResult};
 use crate::config::Config;
-use crate::cron::{self, CronJobPatch};
+use crate::cron::{self, CronJobPatch, Schedule};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
@@ -54,7 +54,7 @@ impl Tool for CronUpdateTool {
     }
 
     fn description(&self) -> &str {
-        "Patch an existing cron job (schedule, command, prompt, enabled, delivery, model, etc.)"
+        "Patch an existing cron job (schedule, command, prompt, enabled, delivery, model, policy, etc.). Setting a webhook schedule returns a new token"
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -104,7 +104,7 @@ impl Tool for CronUpdateTool {
             }
         };
 
-        let patch = match serde_json::from_value::<CronJobPatch>(patch_val) {
+        let mut patch = match serde_json::from_value::<CronJobPatch>(patch_val) {
             Ok(patch) => patch,
             Err(e) => {
                 return Ok(ToolResult {
@@ -133,12 +133,27 @@ impl Tool for CronUpdateTool {
             return Ok(blocked);
         }
 
+        // Switching to (or re-saving) a webhook schedule rotates its token.
+        let webhook_token = patch.schedule.as_mut().and_then(|schedule| {
+            if let Schedule::Webhook { token_hash } = schedule {
+                token_hash.clear();
+            }
+            cron::issue_webhook_token(schedule)
+        });
+
         match cron::update_job(&self.config, job_id, patch) {
-            Ok(job) => Ok(ToolResult {
-                success: true,
-                output: serde_json::to_string_pretty(&job)?,
-                error: None,
-            }),
+            Ok(job) => {
+                let mut output = serde_json::to_value(&job)?;
+                if let Some(token) = webhook_token {
+                    output["webhook_path"] = json!(format!("/cron/{}", job.id));
+                    output["webhook_token"] = json!(token);
+                }
+                Ok(ToolResult {
+                    success: true,
+                    output: serde_json::to_string_pretty(&output)?,
+                    error: None,
+                })
+            }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
@@ -2,6 +2,7 @@ use super::traits::{Tool, ToolResult};
 use crate::agent::loop_::run_tool_call_loop;
 use crate::config::DelegateAgentConfig;
 use crate::observability::traits::{Observer, ObserverEvent, ObserverMetric};
+use crate::observability::TraceContext;
 use crate::providers::{self, ChatMessage, Provider};
 use crate::security::policy::ToolOperation;
 use crate::security::SecurityPolicy;
@@ -9,7 +10,7 @@ use async_trait::async_trait;
 use serde_json::json;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Default timeout for sub-agent provider calls.
 const DELEGATE_TIMEOUT_SECS: u64 = 120;
@@ -33,6 +34,8 @@ pub struct DelegateTool {
     parent_tools: Arc<Vec<Arc<dyn Tool>>>,
     /// Inherited multimodal handling config for sub-agent loops.
     multimodal_config: crate::config::MultimodalConfig,
+    /// Receives sub-agent LLM and tool events.
+    observer: Arc<dyn Observer>,
 }
 
 impl DelegateTool {
@@ -63,6 +66,7 @@ impl DelegateTool {
             depth: 0,
             parent_tools: Arc::new(Vec::new()),
             multimodal_config: crate::config::MultimodalConfig::default(),
+            observer: Arc::new(NoopObserver),
         }
     }
 
@@ -99,6 +103,7 @@ impl DelegateTool {
             depth,
             parent_tools: Arc::new(Vec::new()),
             multimodal_config: crate::config::MultimodalConfig::default(),
+            observer: Arc::new(NoopObserver),
         }
     }
 
@@ -113,6 +118,12 @@ impl DelegateTool {
         self.multimodal_config = config;
         self
     }
+
+    /// Report sub-agent activity to `observer` instead of discarding it.
+    pub fn with_observer(mut self, observer: Arc<dyn Observer>) -> Self {
+        self.observer = observer;
+        self
+    }
 }
 
 #[async_trait]
@@ -248,23 +259,32 @@ impl Tool for DelegateTool {
         #[allow(clippy::option_as_ref_deref)]
         let provider_credential = provider_credential_owned.as_ref().map(String::as_str);
 
-        let provider: Box<dyn Provider> = match providers::create_provider_with_options(
-            &agent_config.provider,
-            provider_credential,
-            &self.provider_runtime_options,
-        ) {
-            Ok(p) => p,
-            Err(e) => {
-                return Ok(ToolResult {
-                    success: false,
-                    output: String::new(),
-                    error: Some(format!(
-                        "Failed to create provider '{}' for agent '{agent_name}': {e}",
-                        agent_config.provider
-                    )),
-                });
-            }
+        let provider_options = providers::ProviderRuntimeOptions {
+            reasoning: agent_config
+                .reasoning
+                .or(self.provider_runtime_options.reasoning),
+            ..self.provider_runtime_options.clone()
         };
+        let provider: Box<dyn Provider> =
+            match providers::cassette::wrap_provider(&provider_options.cassette, || {
+                providers::create_provider_with_options(
+                    &agent_config.provider,
+                    provider_credential,
+                    &provider_options,
+                )
+            }) {
+                Ok(p) => p,
+                Err(e) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!(
+                            "Failed to create provider '{}' for agent '{agent_name}': {e}",
+                            agent_config.provider
+                        )),
+                    });
+                }
+            };
 
         // Build the message
         let full_prompt = if context.is_empty() {
@@ -289,17 +309,36 @@ impl Tool for DelegateTool {
         }
 
         // Wrap the provider call in a timeout to prevent indefinite blocking
+        let llm_span = TraceContext::child_of_current();
+        let started_at = Instant::now();
         let result = tokio::time::timeout(
             Duration::from_secs(DELEGATE_TIMEOUT_SECS),
-            provider.chat_with_system(
+            llm_span.scope(provider.chat_with_system(
                 agent_config.system_prompt.as_deref(),
                 &full_prompt,
                 &agent_config.model,
                 temperature,
-            ),
+            )),
         )
         .await;
 
+        llm_span.sync_scope(|| {
+            self.observer.record_event(&ObserverEvent::LlmResponse {
+                provider: agent_config.provider.clone(),
+                model: agent_config.model.clone(),
+                duration: started_at.elapsed(),
+                success: matches!(result, Ok(Ok(_))),
+                error_message: match &result {
+                    Ok(Err(e)) => Some(providers::sanitize_api_error(&e.to_string())),
+                    Err(_) => Some("timed out".to_string()),
+                    Ok(Ok(_)) => None,
+                },
+                input_tokens: None,
+                output_tokens: None,
+                cost_usd: None,
+            });
+        });
+
         let result = match result {
             Ok(inner) => inner,
             Err(_elapsed) => {
@@ -390,15 +429,14 @@ impl DelegateTool {
         }
         history.push(ChatMessage::user(full_prompt.to_string()));
 
-        let noop_observer = NoopObserver;
-
         let result = tokio::time::timeout(
             Duration::from_secs(DELEGATE_AGENTIC_TIMEOUT_SECS),
             run_tool_call_loop(
                 provider,
                 &mut history,
                 &sub_tools,
-                &noop_observer,
+                None,
+                self.observer.as_ref(),
                 &agent_config.provider,
                 &agent_config.model,
                 temperature,
@@ -517,6 +555,7 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                reasoning: None,
             },
         );
         agents.insert(
@@ -531,6 +570,7 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                reasoning: None,
             },
         );
         agents
@@ -598,6 +638,8 @@ mod tests {
                 Ok(ChatResponse {
                     text: Some("done".to_string()),
                     tool_calls: Vec::new(),
+                    reasoning: Vec::new(),
+                    usage: None,
                 })
             } else {
                 Ok(ChatResponse {
@@ -607,6 +649,8 @@ mod tests {
                         name: "echo_tool".to_string(),
                         arguments: "{\"value\":\"ping\"}".to_string(),
                     }],
+                    reasoning: Vec::new(),
+                    usage: None,
                 })
             }
         }
@@ -639,6 +683,8 @@ mod tests {
                     name: "echo_tool".to_string(),
                     arguments: "{\"value\":\"x\"}".to_string(),
                 }],
+                reasoning: Vec::new(),
+                usage: None,
             })
         }
     }
@@ -678,6 +724,7 @@ mod tests {
             agentic: true,
             allowed_tools,
             max_iterations,
+            reasoning: None,
         }
     }
 
@@ -786,6 +833,7 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                reasoning: None,
             },
         );
         let tool = DelegateTool::new(agents, None, test_security());
@@ -892,6 +940,7 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                reasoning: None,
             },
         );
         let tool = DelegateTool::new(agents, None, test_security());
@@ -927,6 +976,7 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                reasoning: None,
             },
         );
         let tool = DelegateTool::new(agents, None, test_security());
@@ -0,0 +1,933 @@
+use super::traits::{Tool, ToolResult};
+use crate::security::audit::FileChangeLog;
+use crate::security::{AuditLogger, SecurityPolicy};
+use async_trait::async_trait;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::Instant;
+
+const MAX_FILE_SIZE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Unchanged lines shown around the change in the diff preview.
+const PREVIEW_CONTEXT_LINES: usize = 3;
+
+/// Cap on diff preview lines returned to the model.
+const MAX_PREVIEW_LINES: usize = 200;
+
+/// Short content hash reported by `file_read` and checked by `file_edit` to
+/// reject edits based on a stale read.
+pub fn content_hash(contents: &str) -> String {
+    let digest = hex::encode(Sha256::digest(contents.as_bytes()));
+    digest[..16].to_string()
+}
+
+struct Replacement {
+    search: String,
+    replace: String,
+    replace_all: bool,
+}
+
+/// One contiguous block of a unified diff; lines exclude the `+`/`-`/` `
+/// prefix and line terminator.
+struct Hunk {
+    old_start: usize,
+    old: Vec<String>,
+    new: Vec<String>,
+}
+
+enum Edit {
+    SearchReplace(Vec<Replacement>),
+    LineRange {
+        start: usize,
+        end: usize,
+        content: String,
+    },
+    Patch(Vec<Hunk>),
+}
+
+impl Edit {
+    fn from_args(args: &serde_json::Value) -> Result<Self, String> {
+        let given = ["edits", "start_line", "patch"]
+            .into_iter()
+            .filter(|key| args.get(*key).is_some_and(|v| !v.is_null()))
+            .collect::<Vec<_>>();
+        match given.as_slice() {
+            ["edits"] => Self::search_replace(&args["edits"]),
+            ["start_line"] => {
+                let start = line_number(args, "start_line")?;
+                let end = match args.get("end_line") {
+                    Some(v) if !v.is_null() => line_number(args, "end_line")?,
+                    _ => start,
+                };
+                let content = args
+                    .get("content")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Line-range edits need 'content' (use \"\" to delete the lines)")?;
+                Ok(Self::LineRange {
+                    start,
+                    end,
+                    content: content.to_string(),
+                })
+            }
+            ["patch"] => {
+                let patch = args["patch"].as_str().ok_or("'patch' must be a string")?;
+                parse_patch(patch).map(Self::Patch)
+            }
+            [] => Err("Provide one of 'edits', 'start_line' or 'patch'".into()),
+            _ => Err(format!(
+                "Provide only one of 'edits', 'start_line' or 'patch', got: {}",
+                given.join(", ")
+            )),
+        }
+    }
+
+    fn search_replace(edits: &serde_json::Value) -> Result<Self, String> {
+        let edits = edits.as_array().ok_or("'edits' must be an array")?;
+        if edits.is_empty() {
+            return Err("'edits' must not be empty".into());
+        }
+        edits
+            .iter()
+            .enumerate()
+            .map(|(idx, edit)| {
+                let field = |key: &str| {
+                    edit.get(key)
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string)
+                        .ok_or_else(|| format!("edits[{idx}] is missing '{key}'"))
+                };
+                Ok(Replacement {
+                    search: field("search")?,
+                    replace: field("replace")?,
+                    replace_all: edit
+                        .get("replace_all")
+                        .and_then(serde_json::Value::as_bool)
+                        .unwrap_or(false),
+                })
+            })
+            .collect::<Result<_, String>>()
+            .map(Self::SearchReplace)
+    }
+
+    fn operation(&self) -> &'static str {
+        match self {
+            Self::SearchReplace(_) => "search_replace",
+            Self::LineRange { .. } => "line_range",
+            Self::Patch(_) => "patch",
+        }
+    }
+
+    fn apply(&self, original: &str) -> Result<String, String> {
+        match self {
+            Self::SearchReplace(replacements) => apply_replacements(original, replacements),
+            Self::LineRange {
+                start,
+                end,
+                content,
+            } => apply_line_range(original, *start, *end, content),
+            Self::Patch(hunks) => apply_patch(original, hunks),
+        }
+    }
+}
+
+fn line_number(args: &serde_json::Value, key: &str) -> Result<usize, String> {
+    args.get(key)
+        .and_then(serde_json::Value::as_u64)
+        .and_then(|v| usize::try_from(v).ok())
+        .ok_or_else(|| format!("'{key}' must be a non-negative integer"))
+}
+
+fn apply_replacements(original: &str, replacements: &[Replacement]) -> Result<String, String> {
+    let mut text = original.to_string();
+    for (idx, replacement) in replacements.iter().enumerate() {
+        let search = replacement.search.as_str();
+        if search.is_empty() {
+            return Err(format!("edits[{idx}].search must not be empty"));
+        }
+        match text.matches(search).count() {
+            0 => {
+                return Err(format!(
+                    "edits[{idx}].search was not found; re-read the file and copy the text exactly, including whitespace"
+                ))
+            }
+            1 => {}
+            n if !replacement.replace_all => {
+                return Err(format!(
+                    "edits[{idx}].search matches {n} times; include more surrounding lines to make it unique, or set replace_all"
+                ))
+            }
+            _ => {}
+        }
+        text = if replacement.replace_all {
+            text.replace(search, &replacement.replace)
+        } else {
+            text.replacen(search, &replacement.replace, 1)
+        };
+    }
+    Ok(text)
+}
+
+/// Replace lines `start..=end` (1-based); `end == start - 1` inserts before
+/// `start`.
+fn apply_line_range(
+    original: &str,
+    start: usize,
+    end: usize,
+    content: &str,
+) -> Result<String, String> {
+    let lines: Vec<&str> = original.split_inclusive('\n').collect();
+    let total = lines.len();
+    if start == 0 || start > total + 1 || end + 1 < start || end > total {
+        return Err(format!(
+            "Line range {start}-{end} is outside the file (lines 1-{total}); use end_line = start_line - 1 to insert"
+        ));
+    }
+
+    let mut replacement = String::new();
+    // Appending after a final line that lacks a newline.
+    if start > total && total > 0 && !original.ends_with('\n') {
+        replacement.push('\n');
+    }
+    replacement.push_str(content);
+    if !content.is_empty() && !content.ends_with('\n') && (end < total || original.ends_with('\n'))
+    {
+        replacement.push('\n');
+    }
+
+    Ok(format!(
+        "{}{replacement}{}",
+        lines[..start - 1].concat(),
+        lines[end..].concat()
+    ))
+}
+
+/// `start[,count]` from a hunk header; the count defaults to one.
+fn parse_hunk_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+fn parse_patch(patch: &str) -> Result<Vec<Hunk>, String> {
+    let lines: Vec<&str> = patch.lines().collect();
+    let is_file_header = |idx: usize| {
+        lines[idx].starts_with("--- ")
+            && lines
+                .get(idx + 1)
+                .is_some_and(|next| next.starts_with("+++ "))
+    };
+
+    let mut hunks = Vec::new();
+    let mut files = 0;
+    let mut idx = 0;
+    while idx < lines.len() {
+        let line = lines[idx];
+        idx += 1;
+        if is_file_header(idx - 1) {
+            files += 1;
+            if files > 1 {
+                return Err(
+                    "Patch touches more than one file; send one file_edit call per file".into(),
+                );
+            }
+            idx += 1;
+            continue;
+        }
+        let Some(header) = line.strip_prefix("@@ -") else {
+            // `diff --git`, `index` and other preamble lines.
+            continue;
+        };
+        let ((old_start, old_count), (_, new_count)) = header
+            .split_once(" @@")
+            .and_then(|(ranges, _)| ranges.split_once(" +"))
+            .and_then(|(old, new)| Some((parse_hunk_range(old)?, parse_hunk_range(new)?)))
+            .ok_or_else(|| format!("Malformed hunk header: {line}"))?;
+
+        let mut hunk = Hunk {
+            old_start,
+            old: Vec::new(),
+            new: Vec::new(),
+        };
+        let mut trailing_blank = 0;
+        while idx < lines.len()
+            && !lines[idx].starts_with("@@")
+            && !lines[idx].starts_with("diff ")
+            && !is_file_header(idx)
+        {
+            let body = lines[idx];
+            idx += 1;
+            trailing_blank = if body.is_empty() {
+                trailing_blank + 1
+            } else {
+                0
+            };
+            match body.chars().next() {
+                Some('+') => hunk.new.push(body[1..].to_string()),
+                Some('-') => hunk.old.push(body[1..].to_string()),
+                Some(' ') => {
+                    hunk.old.push(body[1..].to_string());
+                    hunk.new.push(body[1..].to_string());
+                }
+                // Editors often strip the single space of empty context lines.
+                None => {
+                    hunk.old.push(String::new());
+                    hunk.new.push(String::new());
+                }
+                // "\ No newline at end of file"
+                Some('\\') => {}
+                Some(_) => return Err(format!("Unexpected line in hunk: {body}")),
+            }
+        }
+        // Blank lines separating the hunk from what follows are not context.
+        while trailing_blank > 0 && hunk.old.len() > old_count && hunk.new.len() > new_count {
+            hunk.old.pop();
+            hunk.new.pop();
+            trailing_blank -= 1;
+        }
+        if hunk.old.len() != old_count || hunk.new.len() != new_count {
+            return Err(format!(
+                "Hunk {} header {line} expects {old_count} old and {new_count} new lines, but its body has {} and {}",
+                hunks.len() + 1,
+                hunk.old.len(),
+                hunk.new.len()
+            ));
+        }
+        hunks.push(hunk);
+    }
+
+    if hunks.is_empty() {
+        return Err("Patch contains no @@ hunks".into());
+    }
+    Ok(hunks)
+}
+
+fn apply_patch(original: &str, hunks: &[Hunk]) -> Result<String, String> {
+    let newline = if original.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut lines: Vec<String> = original.lines().map(str::to_string).collect();
+    let mut offset: isize = 0;
+
+    for (idx, hunk) in hunks.iter().enumerate() {
+        let expected = (hunk.old_start.saturating_sub(1)).saturating_add_signed(offset);
+        let at = find_block(&lines, &hunk.old, expected).ok_or_else(|| {
+            format!(
+                "Hunk {} (@@ -{}) does not match the file; its context or removed lines differ. Re-read the file and regenerate the patch",
+                idx + 1,
+                hunk.old_start
+            )
+        })?;
+        lines.splice(at..at + hunk.old.len(), hunk.new.iter().cloned());
+        #[allow(clippy::cast_possible_wrap)]
+        {
+            offset += hunk.new.len() as isize - hunk.old.len() as isize;
+        }
+    }
+
+    let mut patched = lines.join(newline);
+    if !lines.is_empty() && (original.is_empty() || original.ends_with('\n')) {
+        patched.push_str(newline);
+    }
+    Ok(patched)
+}
+
+/// Where `block` occurs in `lines`: at `expected` if it matches there,
+/// otherwise the occurrence closest to it.
+fn find_block(lines: &[String], block: &[String], expected: usize) -> Option<usize> {
+    if block.is_empty() {
+        return Some(expected.min(lines.len()));
+    }
+    if block.len() > lines.len() {
+        return None;
+    }
+    let matches_at = |at: &usize| lines[*at..].starts_with(block);
+    if expected + block.len() <= lines.len() && matches_at(&expected) {
+        return Some(expected);
+    }
+    (0..=lines.len() - block.len())
+        .filter(matches_at)
+        .min_by_key(|at| at.abs_diff(expected))
+}
+
+/// Single-hunk unified diff spanning every changed line, plus the number of
+/// added and removed lines.
+fn preview(path: &str, before: &str, after: &str) -> (String, usize, usize) {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_changed, new_changed) = (prefix..old.len() - suffix, prefix..new.len() - suffix);
+    let removed = old_changed.len();
+    let added = new_changed.len();
+
+    let start = prefix.saturating_sub(PREVIEW_CONTEXT_LINES);
+    let old_end = (old_changed.end + PREVIEW_CONTEXT_LINES).min(old.len());
+    let new_end = (new_changed.end + PREVIEW_CONTEXT_LINES).min(new.len());
+
+    let mut body: Vec<String> = Vec::new();
+    body.extend(old[start..prefix].iter().map(|line| format!(" {line}")));
+    body.extend(
+        old[old_changed.clone()]
+            .iter()
+            .map(|line| format!("-{line}")),
+    );
+    body.extend(new[new_changed].iter().map(|line| format!("+{line}")));
+    body.extend(
+        old[old_changed.end..old_end]
+            .iter()
+            .map(|line| format!(" {line}")),
+    );
+
+    let mut diff = format!(
+        "--- a/{path}\n+++ b/{path}\n@@ -{},{} +{},{} @@\n",
+        start + 1,
+        old_end - start,
+        start + 1,
+        new_end - start
+    );
+    for line in body.iter().take(MAX_PREVIEW_LINES) {
+        let _ = writeln!(diff, "{line}");
+    }
+    if body.len() > MAX_PREVIEW_LINES {
+        let _ = writeln!(diff, "... ({} more lines)", body.len() - MAX_PREVIEW_LINES);
+    }
+    (diff, added, removed)
+}
+
+fn failure(error: impl Into<String>) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error.into()),
+    }
+}
+
+/// Edit files in place via search/replace blocks, line ranges or unified
+/// diffs, with path sandboxing and stale-read detection.
+pub struct FileEditTool {
+    security: Arc<SecurityPolicy>,
+    audit: Option<Arc<AuditLogger>>,
+}
+
+impl FileEditTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self {
+            security,
+            audit: None,
+        }
+    }
+
+    /// Record every write in the security audit log.
+    pub fn with_audit(mut self, audit: Arc<AuditLogger>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    fn audit_change(&self, entry: FileChangeLog<'_>) {
+        if let Some(audit) = &self.audit {
+            if let Err(e) = audit.log_file_change(entry) {
+                tracing::warn!("Failed to write file_edit audit event: {e}");
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for FileEditTool {
+    fn name(&self) -> &str {
+        "file_edit"
+    }
+
+    fn description(&self) -> &str {
+        "Edit an existing file in the workspace without rewriting it. Use exactly one of: 'edits' (exact search/replace blocks), 'start_line'/'end_line'/'content' (replace a line range), or 'patch' (unified diff). Pass 'expected_hash' from the last file_read so edits on a changed file are rejected; line-range edits require it. Set dry_run to preview the diff."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Relative path to the file within the workspace"
+                },
+                "edits": {
+                    "type": "array",
+                    "description": "Search/replace blocks applied in order; each search must match exactly once unless replace_all is set",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "search": {"type": "string", "description": "Exact text to find"},
+                            "replace": {"type": "string", "description": "Replacement text"},
+                            "replace_all": {"type": "boolean", "description": "Replace every occurrence (default: false)"}
+                        },
+                        "required": ["search", "replace"]
+                    }
+                },
+                "start_line": {
+                    "type": "integer",
+                    "description": "First line to replace (1-based)"
+                },
+                "end_line": {
+                    "type": "integer",
+                    "description": "Last line to replace, inclusive (default: start_line; start_line - 1 inserts before start_line)"
+                },
+                "content": {
+                    "type": "string",
+                    "description": "Replacement text for the line range (empty deletes the lines)"
+                },
+                "patch": {
+                    "type": "string",
+                    "description": "Unified diff for this file, with @@ hunks"
+                },
+                "expected_hash": {
+                    "type": "string",
+                    "description": "Content hash reported by file_read"
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Preview the diff without writing (default: false)"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    #[allow(clippy::too_many_lines)]
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let started = Instant::now();
+        let path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
+        let dry_run = args
+            .get("dry_run")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
+        let expected_hash = args
+            .get("expected_hash")
+            .and_then(|v| v.as_str())
+            .map(str::trim);
+
+        let edit = match Edit::from_args(&args) {
+            Ok(edit) => edit,
+            Err(e) => return Ok(failure(e)),
+        };
+
+        if !dry_run && !self.security.can_act() {
+            return Ok(failure("Action blocked: autonomy is read-only"));
+        }
+
+        if self.security.is_rate_limited() {
+            return Ok(failure(
+                "Rate limit exceeded: too many actions in the last hour",
+            ));
+        }
+
+        // Security check: validate path is within workspace
+        if !self.security.is_path_allowed(path) {
+            return Ok(failure(format!(
+                "Path not allowed by security policy: {path}"
+            )));
+        }
+
+        if !self.security.record_action() {
+            return Ok(failure("Rate limit exceeded: action budget exhausted"));
+        }
+
+        let full_path = self.security.workspace_dir.join(path);
+        let (Some(parent), Some(file_name)) = (full_path.parent(), full_path.file_name()) else {
+            return Ok(failure(
+                "Invalid path: missing parent directory or file name",
+            ));
+        };
+
+        // Resolve the parent to block symlink escapes, then refuse to
+        // follow a symlinked target, as file_write does.
+        let resolved_parent = match tokio::fs::canonicalize(parent).await {
+            Ok(p) => p,
+            Err(e) => return Ok(failure(format!("Failed to resolve file path: {e}"))),
+        };
+        if !self.security.is_resolved_path_allowed(&resolved_parent) {
+            return Ok(failure(format!(
+                "Resolved path escapes workspace: {}",
+                resolved_parent.display()
+            )));
+        }
+        let resolved_target = resolved_parent.join(file_name);
+
+        let meta = match tokio::fs::symlink_metadata(&resolved_target).await {
+            Ok(meta) => meta,
+            Err(e) => {
+                return Ok(failure(format!(
+                    "Failed to read file metadata: {e} (file_edit only edits existing files; use file_write to create one)"
+                )))
+            }
+        };
+        if meta.file_type().is_symlink() {
+            return Ok(failure(format!(
+                "Refusing to edit through symlink: {}",
+                resolved_target.display()
+            )));
+        }
+        if !meta.is_file() {
+            return Ok(failure(format!("Not a regular file: {path}")));
+        }
+        if meta.len() > MAX_FILE_SIZE_BYTES {
+            return Ok(failure(format!(
+                "File too large: {} bytes (limit: {MAX_FILE_SIZE_BYTES} bytes)",
+                meta.len()
+            )));
+        }
+
+        let original = match tokio::fs::read_to_string(&resolved_target).await {
+            Ok(contents) => contents,
+            Err(e) => return Ok(failure(format!("Failed to read file: {e}"))),
+        };
+        let before_hash = content_hash(&original);
+
+        match expected_hash {
+            Some(expected) if expected != before_hash => {
+                return Ok(failure(format!(
+                    "File changed since it was read (expected hash {expected}, now {before_hash}); read it again with file_read and redo the edit"
+                )))
+            }
+            None if matches!(edit, Edit::LineRange { .. }) && !original.is_empty() => {
+                return Ok(failure(
+                    "Line-range edits require 'expected_hash' from file_read, so line numbers match the current file",
+                ))
+            }
+            _ => {}
+        }
+
+        let updated = match edit.apply(&original) {
+            Ok(updated) => updated,
+            Err(e) => return Ok(failure(e)),
+        };
+        if updated == original {
+            return Ok(failure("Edit leaves the file unchanged"));
+        }
+
+        let after_hash = content_hash(&updated);
+        let (diff, added, removed) = preview(path, &original, &updated);
+
+        if dry_run {
+            return Ok(ToolResult {
+                success: true,
+                output: format!(
+                    "Dry run: {path} not modified (+{added} -{removed} lines, hash would become {after_hash})\n{diff}"
+                ),
+                error: None,
+            });
+        }
+
+        // Catch writes that landed while this edit was being prepared.
+        let write_result = match tokio::fs::read_to_string(&resolved_target).await {
+            Ok(current) if current != original => Err(format!(
+                "File changed while the edit was being applied (now {}); read it again and redo the edit",
+                content_hash(&current)
+            )),
+            Ok(_) => write_atomically(&resolved_target, &updated, meta.permissions())
+                .await
+                .map_err(|e| format!("Failed to write file: {e}")),
+            Err(e) => Err(format!("Failed to read file: {e}")),
+        };
+
+        #[allow(clippy::cast_possible_truncation)]
+        let duration_ms = started.elapsed().as_millis() as u64;
+        self.audit_change(FileChangeLog {
+            tool: "file_edit",
+            path,
+            operation: edit.operation(),
+            before_hash: &before_hash,
+            after_hash: &after_hash,
+            success: write_result.is_ok(),
+            duration_ms,
+            error: write_result.as_ref().err().cloned(),
+        });
+
+        match write_result {
+            Ok(()) => Ok(ToolResult {
+                success: true,
+                output: format!(
+                    "Edited {path} (+{added} -{removed} lines). New hash: {after_hash}\n{diff}"
+                ),
+                error: None,
+            }),
+            Err(e) => Ok(failure(e)),
+        }
+    }
+}
+
+/// Write through a sibling temp file and rename, so readers never observe a
+/// half-written file. Keeps the original permissions.
+async fn write_atomically(
+    target: &std::path::Path,
+    contents: &str,
+    permissions: std::fs::Permissions,
+) -> std::io::Result<()> {
+    let file_name = target
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let tmp = target.with_file_name(format!(".{file_name}.file_edit-tmp"));
+    tokio::fs::write(&tmp, contents).await?;
+    tokio::fs::set_permissions(&tmp, permissions).await?;
+    if let Err(e) = tokio::fs::rename(&tmp, target).await {
+        let _ = tokio::fs::remove_file(&tmp).await;
+        return Err(e);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AuditConfig;
+    use crate::security::{AutonomyLevel, SecurityPolicy};
+    use tempfile::TempDir;
+
+    fn test_security(workspace: std::path::PathBuf) -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: workspace,
+            ..SecurityPolicy::default()
+        })
+    }
+
+    async fn workspace_with(contents: &str) -> (TempDir, FileEditTool) {
+        let dir = TempDir::new().unwrap();
+        tokio::fs::write(dir.path().join("src.rs"), contents)
+            .await
+            .unwrap();
+        let tool = FileEditTool::new(test_security(dir.path().to_path_buf()));
+        (dir, tool)
+    }
+
+    async fn read(dir: &TempDir) -> String {
+        tokio::fs::read_to_string(dir.path().join("src.rs"))
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn file_edit_name_and_schema() {
+        let tool = FileEditTool::new(test_security(std::env::temp_dir()));
+        assert_eq!(tool.name(), "file_edit");
+        let schema = tool.parameters_schema();
+        for key in [
+            "path",
+            "edits",
+            "start_line",
+            "patch",
+            "expected_hash",
+            "dry_run",
+        ] {
+            assert!(schema["properties"][key].is_object(), "{key}");
+        }
+    }
+
+    #[tokio::test]
+    async fn search_replace_requires_a_unique_match() {
+        let (dir, tool) = workspace_with("let a = 1;\nlet b = 1;\n").await;
+
+        let result = tool
+            .execute(json!({"path": "src.rs", "edits": [{"search": "= 1", "replace": "= 2"}]}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("matches 2 times"));
+
+        let result = tool
+            .execute(json!({"path": "src.rs", "edits": [
+                {"search": "let b = 1", "replace": "let b = 3"},
+                {"search": "1;", "replace": "2;"}
+            ]}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("+2 -2 lines"));
+        assert_eq!(read(&dir).await, "let a = 2;\nlet b = 3;\n");
+    }
+
+    #[tokio::test]
+    async fn line_range_requires_fresh_hash() {
+        let original = "one\ntwo\nthree\n";
+        let (dir, tool) = workspace_with(original).await;
+
+        let result = tool
+            .execute(json!({"path": "src.rs", "start_line": 2, "content": "TWO"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("require 'expected_hash'"));
+
+        let result = tool
+            .execute(json!({
+                "path": "src.rs", "start_line": 2, "content": "TWO",
+                "expected_hash": content_hash("stale")
+            }))
+            .await
+            .unwrap();
+        assert!(result
+            .error
+            .unwrap()
+            .contains("File changed since it was read"));
+
+        let result = tool
+            .execute(json!({
+                "path": "src.rs", "start_line": 2, "end_line": 3, "content": "TWO",
+                "expected_hash": content_hash(original)
+            }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(read(&dir).await, "one\nTWO\n");
+        assert!(result.output.contains(&content_hash("one\nTWO\n")));
+    }
+
+    #[test]
+    fn line_range_inserts_and_deletes() {
+        assert_eq!(apply_line_range("a\nb\n", 2, 1, "x").unwrap(), "a\nx\nb\n");
+        assert_eq!(apply_line_range("a\nb", 3, 2, "c").unwrap(), "a\nb\nc");
+        assert_eq!(apply_line_range("a\nb\nc\n", 2, 2, "").unwrap(), "a\nc\n");
+        assert!(apply_line_range("a\n", 3, 3, "x").is_err());
+    }
+
+    #[tokio::test]
+    async fn patch_applies_with_line_offset_and_dry_run_leaves_file() {
+        let original = "fn main() {\n    println!(\"hi\");\n}\n\nfn helper() {\n    todo!()\n}\n";
+        let (dir, tool) = workspace_with(original).await;
+        // Hunk header is off by two lines; the context still locates it.
+        let patch = "--- a/src.rs\n+++ b/src.rs\n@@ -7,3 +7,3 @@\n fn helper() {\n-    todo!()\n+    42\n }\n";
+
+        let result = tool
+            .execute(json!({"path": "src.rs", "patch": patch, "dry_run": true}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.starts_with("Dry run"));
+        assert!(result.output.contains("-    todo!()\n+    42\n"));
+        assert_eq!(read(&dir).await, original);
+
+        let result = tool
+            .execute(json!({"path": "src.rs", "patch": patch}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(read(&dir).await.contains("fn helper() {\n    42\n}\n"));
+
+        let result = tool
+            .execute(json!({"path": "src.rs", "patch": patch}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("does not match"));
+    }
+
+    #[test]
+    fn parse_patch_rejects_multi_file_patches() {
+        let patch =
+            "--- a/x\n+++ b/x\n@@ -1 +1 @@\n-a\n+b\n--- a/y\n+++ b/y\n@@ -1 +1 @@\n-c\n+d\n";
+        assert!(parse_patch(patch).is_err());
+        // A removed line that itself starts with "-- " is not a file header.
+        let hunks = parse_patch("@@ -1,2 +1 @@\n--- comment\n keep\n").unwrap();
+        assert_eq!(hunks[0].old, ["-- comment", "keep"]);
+    }
+
+    #[test]
+    fn parse_patch_checks_hunk_header_counts() {
+        let hunks =
+            parse_patch("@@ -3,2 +3,3 @@\n a\n-b\n+c\n+d\n\n\n@@ -9 +10 @@\n-x\n+y\n").unwrap();
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].old, ["a", "b"]);
+        assert_eq!(hunks[0].new, ["a", "c", "d"]);
+
+        let Err(err) = parse_patch("@@ -1,3 +1,3 @@\n a\n-b\n+c\n") else {
+            panic!("short hunk accepted");
+        };
+        assert!(err.contains("expects 3 old and 3 new lines"), "{err}");
+        assert!(parse_patch("@@ -1 +1 @@\n-a\n+b\n+c\n").is_err());
+        assert!(parse_patch("@@ -1,x +1 @@\n-a\n+b\n").is_err());
+    }
+
+    #[tokio::test]
+    async fn file_edit_enforces_security_policy() {
+        let (dir, _) = workspace_with("x\n").await;
+        let edits = json!([{"search": "x", "replace": "y"}]);
+
+        let tool = FileEditTool::new(test_security(dir.path().to_path_buf()));
+        let result = tool
+            .execute(json!({"path": "../escape.rs", "edits": edits}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("not allowed"));
+
+        let readonly = FileEditTool::new(Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            workspace_dir: dir.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        }));
+        let result = readonly
+            .execute(json!({"path": "src.rs", "edits": edits}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("read-only"));
+        let result = readonly
+            .execute(json!({"path": "src.rs", "edits": edits, "dry_run": true}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(read(&dir).await, "x\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn file_edit_refuses_symlinks() {
+        let dir = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        tokio::fs::write(outside.path().join("target.txt"), "x\n")
+            .await
+            .unwrap();
+        std::os::unix::fs::symlink(
+            outside.path().join("target.txt"),
+            dir.path().join("link.txt"),
+        )
+        .unwrap();
+
+        let tool = FileEditTool::new(test_security(dir.path().to_path_buf()));
+        let result = tool
+            .execute(json!({"path": "link.txt", "edits": [{"search": "x", "replace": "y"}]}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("symlink"));
+    }
+
+    #[tokio::test]
+    async fn file_edit_writes_audit_event() {
+        let (_dir, tool) = workspace_with("x\n").await;
+        let audit_dir = TempDir::new().unwrap();
+        let audit =
+            AuditLogger::new(AuditConfig::default(), audit_dir.path().to_path_buf()).unwrap();
+        let tool = tool.with_audit(Arc::new(audit));
+
+        let result = tool
+            .execute(json!({"path": "src.rs", "edits": [{"search": "x", "replace": "y"}]}))
+            .await
+            .unwrap();
+        assert!(result.success);
+
+        let log = std::fs::read_to_string(audit_dir.path().join("audit.log")).unwrap();
+        let event: crate::security::AuditEvent = serde_json::from_str(log.trim()).unwrap();
+        let command = event.action.unwrap().command.unwrap();
+        assert!(command.starts_with("search_replace src.rs"), "{command}");
+        assert!(command.contains(&content_hash("y\n")));
+    }
+}
@@ -1,3 +1,4 @@
+use super::file_edit::content_hash;
 use super::traits::{Tool, ToolResult};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
@@ -24,7 +25,7 @@ impl Tool for FileReadTool {
     }
 
     fn description(&self) -> &str {
-        "Read file contents with line numbers. Supports partial reading via offset and limit."
+        "Read file contents with line numbers. Supports partial reading via offset and limit. Ends with a content hash to pass to file_edit as expected_hash."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -134,11 +135,13 @@ impl Tool for FileReadTool {
             Ok(contents) => {
                 let lines: Vec<&str> = contents.lines().collect();
                 let total = lines.len();
+                // Whole-file hash, passed back to file_edit as `expected_hash`.
+                let hash = content_hash(&contents);
 
                 if total == 0 {
                     return Ok(ToolResult {
                         success: true,
-                        output: String::new(),
+                        output: format!("[Content hash: {hash}]"),
                         error: None,
                     });
                 }
@@ -165,7 +168,9 @@ impl Tool for FileReadTool {
                 if start >= end {
                     return Ok(ToolResult {
                         success: true,
-                        output: format!("[No lines in range, file has {total} lines]"),
+                        output: format!(
+                            "[No lines in range, file has {total} lines]\n[Content hash: {hash}]"
+                        ),
                         error: None,
                     });
                 }
@@ -183,10 +188,9 @@ impl Tool for FileReadTool {
                 } else {
                     format!("\n[{total} lines total]")
                 };
-
                 Ok(ToolResult {
                     success: true,
-                    output: format!("{numbered}{summary}"),
+                    output: format!("{numbered}{summary}\n[Content hash: {hash}]"),
                     error: None,
                 })
             }
@@ -263,6 +267,9 @@ mod tests {
         assert!(result.success);
         assert!(result.output.contains("1: hello world"));
         assert!(result.output.contains("[1 lines total]"));
+        assert!(result
+            .output
+            .ends_with(&format!("[Content hash: {}]", content_hash("hello world"))));
         assert!(result.error.is_none());
 
         let _ = tokio::fs::remove_dir_all(&dir).await;
@@ -368,7 +375,10 @@ mod tests {
         let tool = FileReadTool::new(test_security(dir.clone()));
         let result = tool.execute(json!({"path": "empty.txt"})).await.unwrap();
         assert!(result.success);
-        assert_eq!(result.output, "");
+        assert_eq!(
+            result.output,
+            format!("[Content hash: {}]", content_hash(""))
+        );
 
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
@@ -533,6 +543,9 @@ mod tests {
         assert!(result
             .output
             .contains("[No lines in range, file has 2 lines]"));
+        assert!(result
+            .output
+            .ends_with(&format!("[Content hash: {}]", content_hash("one\ntwo"))));
 
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
@@ -1,11 +1,15 @@
 //! Hardware memory read tool — read actual memory/register values from Nucleo via probe-rs.
 //!
 //! Use when user asks to "read register values", "read memory at address", "dump lower memory", etc.
-//! Requires probe feature and Nucleo connected via USB.
+//! Requires probe feature and Nucleo connected via USB. Boards speaking the
+//! device protocol (e.g. the simulated board) are read with `memory_read`.
 
 use super::traits::{Tool, ToolResult};
+use crate::peripherals::protocol::DeviceTransport;
 use async_trait::async_trait;
 use serde_json::json;
+use std::fmt::Write as _;
+use std::sync::Arc;
 
 /// RAM base for Nucleo-F401RE (STM32F401)
 const NUCLEO_RAM_BASE: u64 = 0x2000_0000;
@@ -13,11 +17,24 @@ const NUCLEO_RAM_BASE: u64 = 0x2000_0000;
 /// Tool: read memory at address from connected Nucleo via probe-rs.
 pub struct HardwareMemoryReadTool {
     boards: Vec<String>,
+    /// (board_name, transport) for boards that answer `memory_read` themselves.
+    transports: Vec<(String, Arc<DeviceTransport>)>,
 }
 
 impl HardwareMemoryReadTool {
     pub fn new(boards: Vec<String>) -> Self {
-        Self { boards }
+        Self {
+            boards,
+            transports: Vec::new(),
+        }
+    }
+
+    pub(crate) fn with_transports(
+        mut self,
+        transports: Vec<(String, Arc<DeviceTransport>)>,
+    ) -> Self {
+        self.transports = transports;
+        self
     }
 
     fn chip_for_board(board: &str) -> Option<&'static str> {
@@ -78,6 +95,21 @@ impl Tool for HardwareMemoryReadTool {
             .or_else(|| self.boards.first().cloned())
             .unwrap_or_else(|| "nucleo-f401re".into());
 
+        let address_str = args
+            .get("address")
+            .and_then(|v| v.as_str())
+            .unwrap_or("0x20000000");
+        let address = parse_hex_address(address_str).unwrap_or(NUCLEO_RAM_BASE);
+
+        let requested_length = args.get("length").and_then(|v| v.as_u64()).unwrap_or(128);
+        let length = usize::try_from(requested_length)
+            .unwrap_or(256)
+            .clamp(1, 256);
+
+        if let Some((_, transport)) = self.transports.iter().find(|(name, _)| *name == board) {
+            return device_read_memory(transport, address, length).await;
+        }
+
         let chip = Self::chip_for_board(&board);
         if chip.is_none() {
             return Ok(ToolResult {
@@ -90,20 +122,9 @@ impl Tool for HardwareMemoryReadTool {
             });
         }
 
-        let address_str = args
-            .get("address")
-            .and_then(|v| v.as_str())
-            .unwrap_or("0x20000000");
-        let _address = parse_hex_address(address_str).unwrap_or(NUCLEO_RAM_BASE);
-
-        let requested_length = args.get("length").and_then(|v| v.as_u64()).unwrap_or(128);
-        let _length = usize::try_from(requested_length)
-            .unwrap_or(256)
-            .clamp(1, 256);
-
         #[cfg(feature = "probe")]
         {
-            match probe_read_memory(chip.unwrap(), _address, _length) {
+            match probe_read_memory(chip.unwrap(), address, length) {
                 Ok(output) => {
                     return Ok(ToolResult {
                         success: true,
@@ -126,6 +147,7 @@ impl Tool for HardwareMemoryReadTool {
 
         #[cfg(not(feature = "probe"))]
         {
+            let _ = (address, length);
             Ok(ToolResult {
                 success: false,
                 output: String::new(),
@@ -138,27 +160,47 @@ impl Tool for HardwareMemoryReadTool {
     }
 }
 
-fn parse_hex_address(s: &str) -> Option<u64> {
-    let s = s.trim().trim_start_matches("0x").trim_start_matches("0X");
-    u64::from_str_radix(s, 16).ok()
+/// Read memory through a board's `memory_read` command (hex string reply).
+async fn device_read_memory(
+    transport: &DeviceTransport,
+    address: u64,
+    length: usize,
+) -> anyhow::Result<ToolResult> {
+    let reply = transport
+        .request(
+            "memory_read",
+            json!({ "address": address, "length": length }),
+        )
+        .await?;
+    if !reply.success {
+        return Ok(reply);
+    }
+    let bytes = decode_hex(reply.output.trim())
+        .ok_or_else(|| anyhow::anyhow!("Device returned malformed memory dump"))?;
+    Ok(ToolResult {
+        success: true,
+        output: format_hex_dump(address, &bytes),
+        error: None,
+    })
 }
 
-#[cfg(feature = "probe")]
-fn probe_read_memory(chip: &str, address: u64, length: usize) -> anyhow::Result<String> {
-    use probe_rs::MemoryInterface;
-    use probe_rs::Session;
-    use probe_rs::SessionConfig;
-
-    let mut session = Session::auto_attach(chip, SessionConfig::default())
-        .map_err(|e| anyhow::anyhow!("{}", e))?;
-
-    let mut core = session.core(0)?;
-    let mut buf = vec![0u8; length];
-    core.read_8(address, &mut buf)
-        .map_err(|e| anyhow::anyhow!("{}", e))?;
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
 
-    // Format as hex dump: address | bytes (16 per line)
-    let mut out = format!("Memory read from 0x{:08X} ({} bytes):\n\n", address, length);
+/// Format bytes as a hex dump: address | bytes (16 per line) | ascii.
+fn format_hex_dump(address: u64, buf: &[u8]) -> String {
+    let mut out = format!(
+        "Memory read from 0x{:08X} ({} bytes):\n\n",
+        address,
+        buf.len()
+    );
     const COLS: usize = 16;
     for (i, chunk) in buf.chunks(COLS).enumerate() {
         let addr = address + (i * COLS) as u64;
@@ -177,7 +219,29 @@ fn probe_read_memory(chip: &str, address: u64, length: usize) -> anyhow::Result<
                 }
             })
             .collect();
-        out.push_str(&format!("0x{:08X}  {:48}  {}\n", addr, hex, ascii));
+        let _ = writeln!(out, "0x{:08X}  {:48}  {}", addr, hex, ascii);
     }
-    Ok(out)
+    out
+}
+
+fn parse_hex_address(s: &str) -> Option<u64> {
+    let s = s.trim().trim_start_matches("0x").trim_start_matches("0X");
+    u64::from_str_radix(s, 16).ok()
+}
+
+#[cfg(feature = "probe")]
+fn probe_read_memory(chip: &str, address: u64, length: usize) -> anyhow::Result<String> {
+    use probe_rs::MemoryInterface;
+    use probe_rs::Session;
+    use probe_rs::SessionConfig;
+
+    let mut session = Session::auto_attach(chip, SessionConfig::default())
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let mut core = session.core(0)?;
+    let mut buf = vec![0u8; length];
+    core.read_8(address, &mut buf)
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    Ok(format_hex_dump(address, &buf))
 }
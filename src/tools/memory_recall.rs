@@ -1,11 +1,12 @@
 use super::traits::{Tool, ToolResult};
-use crate::memory::Memory;
+use crate::memory::{access, unscoped_key, Memory};
 use async_trait::async_trait;
 use serde_json::json;
 use std::fmt::Write;
 use std::sync::Arc;
 
-/// Let the agent search its own memory
+/// Let the agent search its own memory.
+/// In channel conversations only the conversation's readable scopes are searched.
 pub struct MemoryRecallTool {
     memory: Arc<dyn Memory>,
 }
@@ -55,7 +56,7 @@ impl Tool for MemoryRecallTool {
             .and_then(serde_json::Value::as_u64)
             .map_or(5, |v| v as usize);
 
-        match self.memory.recall(query, limit, None).await {
+        match access::recall_current(self.memory.as_ref(), query, limit).await {
             Ok(entries) if entries.is_empty() => Ok(ToolResult {
                 success: true,
                 output: "No memories found matching that query.".into(),
@@ -70,7 +71,9 @@ impl Tool for MemoryRecallTool {
                     let _ = writeln!(
                         output,
                         "- [{}] {}: {}{score}",
-                        entry.category, entry.key, entry.content
+                        entry.category,
+                        unscoped_key(&entry.key),
+                        entry.content
                     );
                 }
                 Ok(ToolResult {
@@ -91,7 +94,7 @@ impl Tool for MemoryRecallTool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::memory::{MemoryCategory, SqliteMemory};
+    use crate::memory::{MemoryAccess, MemoryCategory, SqliteMemory};
     use tempfile::TempDir;
 
     fn seeded_mem() -> (TempDir, Arc<dyn Memory>) {
@@ -149,6 +152,34 @@ mod tests {
         assert!(result.output.contains("Found 3"));
     }
 
+    #[tokio::test]
+    async fn recall_only_searches_callers_scopes() {
+        let (_tmp, mem) = seeded_mem();
+        let rule = crate::config::MemoryScopeRule::default();
+        let alice = MemoryAccess::for_message(&rule, "slack", "alice", "C1");
+        let bob = MemoryAccess::for_message(&rule, "slack", "bob", "C1");
+        mem.store_scoped(
+            "lang",
+            "Alice prefers Rust",
+            MemoryCategory::Core,
+            &alice.write,
+        )
+        .await
+        .unwrap();
+
+        let tool = MemoryRecallTool::new(mem);
+        let for_alice = alice
+            .scope(tool.execute(json!({"query": "Rust"})))
+            .await
+            .unwrap();
+        assert!(for_alice.output.contains("lang: Alice prefers Rust"));
+        let for_bob = bob
+            .scope(tool.execute(json!({"query": "Rust"})))
+            .await
+            .unwrap();
+        assert!(for_bob.output.contains("No memories found"));
+    }
+
     #[tokio::test]
     async fn recall_missing_query() {
         let (_tmp, mem) = seeded_mem();
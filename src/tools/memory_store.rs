@@ -1,12 +1,13 @@
 use super::traits::{Tool, ToolResult};
-use crate::memory::{Memory, MemoryCategory};
+use crate::memory::{access, Memory, MemoryCategory};
 use crate::security::policy::ToolOperation;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
 use std::sync::Arc;
 
-/// Let the agent store memories — its own brain writes
+/// Let the agent store memories — its own brain writes.
+/// In channel conversations the entry lands in the conversation's write scope.
 pub struct MemoryStoreTool {
     memory: Arc<dyn Memory>,
     security: Arc<SecurityPolicy>,
@@ -78,7 +79,7 @@ impl Tool for MemoryStoreTool {
             });
         }
 
-        match self.memory.store(key, content, category, None).await {
+        match access::store_current(self.memory.as_ref(), key, content, category).await {
             Ok(()) => Ok(ToolResult {
                 success: true,
                 output: format!("Stored memory: {key}"),
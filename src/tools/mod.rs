@@ -19,6 +19,7 @@
 pub mod browser;
 pub mod browser_open;
 pub mod composio;
+pub mod content_search;
 pub mod cron_add;
 pub mod cron_list;
 pub mod cron_remove;
@@ -26,6 +27,7 @@ pub mod cron_run;
 pub mod cron_runs;
 pub mod cron_update;
 pub mod delegate;
+pub mod file_edit;
 pub mod file_read;
 pub mod file_write;
 pub mod git_operations;
@@ -44,13 +46,16 @@ pub mod pushover;
 pub mod schedule;
 pub mod schema;
 pub mod screenshot;
+pub mod selection;
 pub mod shell;
+pub mod tool_search;
 pub mod traits;
 pub mod web_search_tool;
 
 pub use browser::{BrowserTool, ComputerUseConfig};
 pub use browser_open::BrowserOpenTool;
 pub use composio::ComposioTool;
+pub use content_search::ContentSearchTool;
 pub use cron_add::CronAddTool;
 pub use cron_list::CronListTool;
 pub use cron_remove::CronRemoveTool;
@@ -58,6 +63,7 @@ pub use cron_run::CronRunTool;
 pub use cron_runs::CronRunsTool;
 pub use cron_update::CronUpdateTool;
 pub use delegate::DelegateTool;
+pub use file_edit::FileEditTool;
 pub use file_read::FileReadTool;
 pub use file_write::FileWriteTool;
 pub use git_operations::GitOperationsTool;
@@ -77,6 +83,7 @@ pub use schedule::ScheduleTool;
 #[allow(unused_imports)]
 pub use schema::{CleaningStrategy, SchemaCleanr};
 pub use screenshot::ScreenshotTool;
+pub use selection::ToolSelector;
 pub use shell::ShellTool;
 pub use traits::Tool;
 #[allow(unused_imports)]
@@ -86,7 +93,7 @@ pub use web_search_tool::WebSearchTool;
 use crate::config::{Config, DelegateAgentConfig};
 use crate::memory::Memory;
 use crate::runtime::{NativeRuntime, RuntimeAdapter};
-use crate::security::SecurityPolicy;
+use crate::security::{AuditLogger, SecurityPolicy};
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -139,7 +146,9 @@ pub fn default_tools_with_runtime(
         Box::new(ShellTool::new(security.clone(), runtime)),
         Box::new(FileReadTool::new(security.clone())),
         Box::new(FileWriteTool::new(security.clone())),
-        Box::new(GlobSearchTool::new(security)),
+        Box::new(FileEditTool::new(security.clone())),
+        Box::new(GlobSearchTool::new(security.clone())),
+        Box::new(ContentSearchTool::new(security)),
     ]
 }
 
@@ -190,11 +199,26 @@ pub fn all_tools_with_runtime(
     fallback_api_key: Option<&str>,
     root_config: &crate::config::Config,
 ) -> Vec<Box<dyn Tool>> {
+    // File edits are audited per `[security.audit]`, relative to config.toml.
+    let mut file_edit = FileEditTool::new(security.clone());
+    let audit_config = &root_config.security.audit;
+    if let Some(zeroclaw_dir) = root_config
+        .config_path
+        .parent()
+        .filter(|_| audit_config.enabled)
+    {
+        match AuditLogger::new(audit_config.clone(), zeroclaw_dir.to_path_buf()) {
+            Ok(audit) => file_edit = file_edit.with_audit(Arc::new(audit)),
+            Err(e) => tracing::warn!("file_edit audit logging unavailable: {e}"),
+        }
+    }
     let mut tool_arcs: Vec<Arc<dyn Tool>> = vec![
         Arc::new(ShellTool::new(security.clone(), runtime)),
         Arc::new(FileReadTool::new(security.clone())),
         Arc::new(FileWriteTool::new(security.clone())),
+        Arc::new(file_edit),
         Arc::new(GlobSearchTool::new(security.clone())),
+        Arc::new(ContentSearchTool::new(security.clone())),
         Arc::new(CronAddTool::new(config.clone(), security.clone())),
         Arc::new(CronListTool::new(config.clone())),
         Arc::new(CronRemoveTool::new(config.clone(), security.clone())),
@@ -301,11 +325,15 @@ pub fn all_tools_with_runtime(
                     .parent()
                     .map(std::path::PathBuf::from),
                 secrets_encrypt: root_config.secrets.encrypt,
-                reasoning_enabled: root_config.runtime.reasoning_enabled,
+                reasoning: root_config.runtime.effective_reasoning(),
+                cassette: root_config.cassette.clone(),
             },
         )
         .with_parent_tools(parent_tools)
-        .with_multimodal_config(root_config.multimodal.clone());
+        .with_multimodal_config(root_config.multimodal.clone())
+        .with_observer(Arc::from(crate::observability::create_observer(
+            &root_config.observability,
+        )));
         tool_arcs.push(Arc::new(delegate_tool));
     }
 
@@ -330,7 +358,7 @@ mod tests {
     fn default_tools_has_expected_count() {
         let security = Arc::new(SecurityPolicy::default());
         let tools = default_tools(security);
-        assert_eq!(tools.len(), 4);
+        assert_eq!(tools.len(), 6);
     }
 
     #[test]
@@ -421,6 +449,7 @@ mod tests {
         assert!(names.contains(&"file_read"));
         assert!(names.contains(&"file_write"));
         assert!(names.contains(&"glob_search"));
+        assert!(names.contains(&"content_search"));
     }
 
     #[test]
@@ -535,6 +564,7 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                reasoning: None,
             },
         );
 
@@ -555,6 +585,52 @@ mod tests {
         assert!(names.contains(&"delegate"));
     }
 
+    #[tokio::test]
+    async fn all_tools_audits_file_edits_per_security_config() {
+        for enabled in [true, false] {
+            let tmp = TempDir::new().unwrap();
+            let mut cfg = test_config(&tmp);
+            cfg.security.audit.enabled = enabled;
+            cfg.security.audit.log_path = "edits.log".into();
+            std::fs::create_dir_all(&cfg.workspace_dir).unwrap();
+            std::fs::write(cfg.workspace_dir.join("notes.txt"), "old\n").unwrap();
+            let security = Arc::new(SecurityPolicy {
+                workspace_dir: cfg.workspace_dir.clone(),
+                ..SecurityPolicy::default()
+            });
+            let mem_cfg = MemoryConfig {
+                backend: "markdown".into(),
+                ..MemoryConfig::default()
+            };
+            let mem: Arc<dyn Memory> =
+                Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None).unwrap());
+
+            let tools = all_tools(
+                Arc::new(Config::default()),
+                &security,
+                mem,
+                None,
+                None,
+                &BrowserConfig::default(),
+                &crate::config::HttpRequestConfig::default(),
+                &cfg.workspace_dir,
+                &HashMap::new(),
+                None,
+                &cfg,
+            );
+            let file_edit = tools.iter().find(|t| t.name() == "file_edit").unwrap();
+            let result = file_edit
+                .execute(serde_json::json!({
+                    "path": "notes.txt",
+                    "edits": [{"search": "old", "replace": "new"}]
+                }))
+                .await
+                .unwrap();
+            assert!(result.success, "{:?}", result.error);
+            assert_eq!(tmp.path().join("edits.log").exists(), enabled);
+        }
+    }
+
     #[test]
     fn all_tools_excludes_delegate_when_no_agents() {
         let tmp = TempDir::new().unwrap();
@@ -0,0 +1,503 @@
+//! Per-turn tool retrieval for large registries.
+//!
+//! With Composio, skills, peripherals and delegate agents enabled, sending
+//! every tool spec on every request costs thousands of tokens and confuses
+//! smaller models. [`ToolSelector`] ranks tool descriptions against the
+//! latest user message and narrows each request to the pinned tools, the
+//! `top_k` most relevant ones and the [`ToolSearchTool`] meta-tool, which
+//! the model calls to discover the rest. Ranking uses the memory embedder
+//! when one is configured and falls back to keyword overlap otherwise.
+//!
+//! Selection only narrows what is *advertised*; execution still resolves
+//! calls against the full registry. Tools activated by `tool_search` stay
+//! with the conversation that searched for them: channel turns run inside
+//! [`in_conversation`], and turns outside one share a single activation set.
+
+use super::tool_search::{ToolSearchTool, TOOL_SEARCH_NAME};
+use super::traits::{Tool, ToolSpec};
+use crate::config::{Config, ToolSelectionConfig};
+use crate::memory::embeddings::EmbeddingProvider;
+use crate::memory::vector::cosine_similarity;
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Conversations whose activated tools are remembered; the least recently
+/// used one is forgotten beyond this.
+const MAX_ACTIVATED_CONVERSATIONS: usize = 256;
+
+tokio::task_local! {
+    static CONVERSATION: String;
+}
+
+/// Run `future` as a turn of `conversation`, so tools it activates through
+/// `tool_search` are advertised only to that conversation.
+pub async fn in_conversation<F: Future>(conversation: String, future: F) -> F::Output {
+    CONVERSATION.scope(conversation, future).await
+}
+
+fn current_conversation() -> String {
+    CONVERSATION.try_with(Clone::clone).unwrap_or_default()
+}
+
+/// Tools returned by `tool_search`, per conversation, most recent last.
+#[derive(Default)]
+struct Activations {
+    clock: u64,
+    conversations: HashMap<String, (u64, VecDeque<String>)>,
+}
+
+impl Activations {
+    fn get(&mut self, conversation: &str) -> Vec<String> {
+        self.clock += 1;
+        let clock = self.clock;
+        self.conversations
+            .get_mut(conversation)
+            .map(|(used, tools)| {
+                *used = clock;
+                tools.iter().cloned().collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn activate(&mut self, conversation: &str, names: &[String], cap: usize) {
+        self.clock += 1;
+        if !self.conversations.contains_key(conversation)
+            && self.conversations.len() >= MAX_ACTIVATED_CONVERSATIONS
+        {
+            let oldest = self
+                .conversations
+                .iter()
+                .min_by_key(|(_, (used, _))| *used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.conversations.remove(&oldest);
+            }
+        }
+        let (used, tools) = self
+            .conversations
+            .entry(conversation.to_string())
+            .or_default();
+        *used = self.clock;
+        for name in names {
+            tools.retain(|active| active != name);
+            tools.push_back(name.clone());
+        }
+        while tools.len() > cap {
+            tools.pop_front();
+        }
+    }
+}
+
+struct CatalogEntry {
+    spec: ToolSpec,
+    /// Lowercased words of the name and description, for keyword ranking.
+    terms: HashSet<String>,
+    name_terms: HashSet<String>,
+}
+
+impl CatalogEntry {
+    fn new(spec: ToolSpec) -> Self {
+        Self {
+            terms: terms(&format!("{} {}", spec.name, spec.description)),
+            name_terms: terms(&spec.name),
+            spec,
+        }
+    }
+
+    fn embedding_text(&self) -> String {
+        format!("{}: {}", self.spec.name, self.spec.description)
+    }
+
+    fn keyword_score(&self, query: &HashSet<String>) -> f32 {
+        let hits: f32 = query
+            .iter()
+            .filter(|term| self.terms.contains(*term))
+            .map(|term| {
+                if self.name_terms.contains(term) {
+                    2.0
+                } else {
+                    1.0
+                }
+            })
+            .sum();
+        #[allow(clippy::cast_precision_loss)]
+        let norm = (self.terms.len().max(1) as f32).sqrt();
+        hits / norm
+    }
+}
+
+fn terms(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.len() > 1)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Chooses which tool specs to send with each request.
+pub struct ToolSelector {
+    config: ToolSelectionConfig,
+    embedder: Arc<dyn EmbeddingProvider>,
+    /// Every registry tool except `tool_search`, in registry order.
+    catalog: Vec<CatalogEntry>,
+    /// Catalog embeddings, computed on first use.
+    vectors: tokio::sync::Mutex<Option<Vec<Vec<f32>>>>,
+    /// The previous query and its embedding; the loop re-selects with the
+    /// same message on every iteration.
+    last_query: Mutex<Option<(String, Vec<f32>)>>,
+    /// Tools returned by `tool_search`, capped at `top_k` per conversation.
+    activated: Mutex<Activations>,
+    embeddings_failed: AtomicBool,
+}
+
+impl ToolSelector {
+    pub fn new(
+        config: ToolSelectionConfig,
+        embedder: Arc<dyn EmbeddingProvider>,
+        tools_registry: &[Box<dyn Tool>],
+    ) -> Self {
+        let catalog = tools_registry
+            .iter()
+            .filter(|tool| tool.name() != TOOL_SEARCH_NAME)
+            .map(|tool| CatalogEntry::new(tool.spec()))
+            .collect();
+        Self {
+            config,
+            embedder,
+            catalog,
+            vectors: tokio::sync::Mutex::new(None),
+            last_query: Mutex::new(None),
+            activated: Mutex::new(Activations::default()),
+            embeddings_failed: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether `name` is always advertised, including in prompt-based tool
+    /// instructions.
+    pub fn is_pinned(&self, name: &str) -> bool {
+        name == TOOL_SEARCH_NAME || self.config.pinned.iter().any(|pinned| pinned == name)
+    }
+
+    /// Most tools a single `tool_search` call returns (`top_k`, at least one).
+    pub fn search_limit(&self) -> usize {
+        self.config.top_k.max(1)
+    }
+
+    /// Specs to send for a turn whose latest user message is `query`: pinned
+    /// tools, tools the current conversation activated by `tool_search`, and
+    /// the `top_k` best matches, in registry order.
+    pub async fn select_specs(
+        &self,
+        tools_registry: &[Box<dyn Tool>],
+        query: &str,
+    ) -> Vec<ToolSpec> {
+        let mut wanted: HashSet<String> = self
+            .activated
+            .lock()
+            .get(&current_conversation())
+            .into_iter()
+            .collect();
+        wanted.extend(
+            self.rank(query, self.config.top_k)
+                .await
+                .into_iter()
+                .map(|idx| self.catalog[idx].spec.name.clone()),
+        );
+        tools_registry
+            .iter()
+            .filter(|tool| self.is_pinned(tool.name()) || wanted.contains(tool.name()))
+            .map(|tool| tool.spec())
+            .collect()
+    }
+
+    /// Best matches for `query` (at most [`Self::search_limit`]), which are
+    /// also advertised on the current conversation's following requests so
+    /// the model can call them.
+    pub async fn search(&self, query: &str, limit: usize) -> Vec<ToolSpec> {
+        let found: Vec<ToolSpec> = self
+            .rank(query, limit.min(self.search_limit()))
+            .await
+            .into_iter()
+            .map(|idx| self.catalog[idx].spec.clone())
+            .collect();
+        let names: Vec<String> = found.iter().map(|spec| spec.name.clone()).collect();
+        self.activated
+            .lock()
+            .activate(&current_conversation(), &names, self.search_limit());
+        found
+    }
+
+    /// Catalog indices of the `limit` best matches, best first.
+    async fn rank(&self, query: &str, limit: usize) -> Vec<usize> {
+        let query = query.trim();
+        if query.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+        let mut scored = match self.embedding_scores(query).await {
+            Some(scores) => scores,
+            None => {
+                let query_terms = terms(query);
+                self.catalog
+                    .iter()
+                    .map(|entry| entry.keyword_score(&query_terms))
+                    .collect()
+            }
+        }
+        .into_iter()
+        .enumerate()
+        .filter(|(_, score)| *score > 0.0)
+        .collect::<Vec<_>>();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.into_iter().take(limit).map(|(idx, _)| idx).collect()
+    }
+
+    /// Cosine similarity of `query` to each catalog entry, or `None` when no
+    /// embedder is configured or it failed.
+    async fn embedding_scores(&self, query: &str) -> Option<Vec<f32>> {
+        if self.embedder.dimensions() == 0 || self.embeddings_failed.load(Ordering::Relaxed) {
+            return None;
+        }
+        match self.try_embedding_scores(query).await {
+            Ok(scores) => Some(scores),
+            Err(error) => {
+                if !self.embeddings_failed.swap(true, Ordering::Relaxed) {
+                    tracing::warn!(
+                        embedder = self.embedder.name(),
+                        "Tool selection embeddings failed, falling back to keyword ranking: {error}"
+                    );
+                }
+                None
+            }
+        }
+    }
+
+    async fn try_embedding_scores(&self, query: &str) -> anyhow::Result<Vec<f32>> {
+        let mut vectors = self.vectors.lock().await;
+        if vectors.is_none() {
+            let texts: Vec<String> = self
+                .catalog
+                .iter()
+                .map(CatalogEntry::embedding_text)
+                .collect();
+            let refs: Vec<&str> = texts.iter().map(String::as_str).collect();
+            let embedded = self.embedder.embed(&refs).await?;
+            anyhow::ensure!(
+                embedded.len() == texts.len(),
+                "embedder returned {} vectors for {} tools",
+                embedded.len(),
+                texts.len()
+            );
+            *vectors = Some(embedded);
+        }
+
+        let cached = self
+            .last_query
+            .lock()
+            .as_ref()
+            .filter(|(text, _)| text == query)
+            .map(|(_, vector)| vector.clone());
+        let query_vector = match cached {
+            Some(vector) => vector,
+            None => {
+                let vector = self.embedder.embed_one(query).await?;
+                *self.last_query.lock() = Some((query.to_string(), vector.clone()));
+                vector
+            }
+        };
+
+        Ok(vectors
+            .iter()
+            .flatten()
+            .map(|vector| cosine_similarity(&query_vector, vector))
+            .collect())
+    }
+}
+
+/// Enable tool selection for `tools_registry` when configured and the
+/// registry is large enough, registering `tool_search` alongside it.
+pub fn attach(
+    config: &Config,
+    tools_registry: &mut Vec<Box<dyn Tool>>,
+) -> Option<Arc<ToolSelector>> {
+    let selection = &config.agent.tool_selection;
+    if !selection.enabled || tools_registry.len() <= selection.min_registry_size {
+        return None;
+    }
+    let embedder = crate::memory::create_embedder(
+        &config.memory,
+        &config.embedding_routes,
+        config.api_key.as_deref(),
+    )
+    .unwrap_or_else(|error| {
+        tracing::warn!("Tool selection embedder unavailable, using keyword ranking: {error}");
+        Arc::new(crate::memory::embeddings::NoopEmbedding)
+    });
+    let selector = Arc::new(ToolSelector::new(
+        selection.clone(),
+        embedder,
+        tools_registry,
+    ));
+    tools_registry.push(Box::new(ToolSearchTool::new(selector.clone())));
+    tracing::info!(
+        tools = tools_registry.len(),
+        top_k = selection.top_k,
+        "Tool selection enabled"
+    );
+    Some(selector)
+}
+
+/// Text of the latest user message, the query for [`ToolSelector::select_specs`].
+pub fn latest_user_message(history: &[crate::providers::ChatMessage]) -> &str {
+    history
+        .iter()
+        .rev()
+        .find(|message| message.role == "user")
+        .map_or("", |message| message.content.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::embeddings::NoopEmbedding;
+    use crate::tools::ToolResult;
+    use async_trait::async_trait;
+    use serde_json::json;
+
+    struct NamedTool(&'static str, &'static str);
+
+    #[async_trait]
+    impl Tool for NamedTool {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            self.1
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            json!({"type": "object"})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+            Ok(ToolResult {
+                success: true,
+                output: String::new(),
+                error: None,
+            })
+        }
+    }
+
+    fn registry() -> Vec<Box<dyn Tool>> {
+        vec![
+            Box::new(NamedTool("shell", "Execute a shell command")),
+            Box::new(NamedTool("cron_add", "Schedule a recurring cron job")),
+            Box::new(NamedTool("pushover", "Send a push notification to a phone")),
+            Box::new(NamedTool(
+                "screenshot",
+                "Capture a screenshot of the display",
+            )),
+            Box::new(NamedTool(
+                "git_operations",
+                "Run git commands on a repository",
+            )),
+        ]
+    }
+
+    fn selector(tools: &[Box<dyn Tool>], top_k: usize) -> ToolSelector {
+        ToolSelector::new(
+            ToolSelectionConfig {
+                enabled: true,
+                top_k,
+                pinned: vec!["shell".into()],
+                min_registry_size: 0,
+            },
+            Arc::new(NoopEmbedding),
+            tools,
+        )
+    }
+
+    fn names(specs: &[ToolSpec]) -> Vec<&str> {
+        specs.iter().map(|spec| spec.name.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn select_specs_keeps_pinned_and_ranks_by_keywords() {
+        let tools = registry();
+        let selector = selector(&tools, 1);
+
+        let specs = selector
+            .select_specs(&tools, "send me a notification when done")
+            .await;
+        assert_eq!(names(&specs), ["shell", "pushover"]);
+
+        let specs = selector.select_specs(&tools, "").await;
+        assert_eq!(names(&specs), ["shell"]);
+    }
+
+    #[tokio::test]
+    async fn search_activates_results_for_later_turns() {
+        let tools = registry();
+        let selector = selector(&tools, 1);
+
+        let found = selector.search("schedule a cron job", 1).await;
+        assert_eq!(names(&found), ["cron_add"]);
+
+        let specs = selector.select_specs(&tools, "commit with git").await;
+        assert_eq!(names(&specs), ["shell", "cron_add", "git_operations"]);
+    }
+
+    #[tokio::test]
+    async fn search_activations_stay_with_their_conversation() {
+        let tools = registry();
+        let selector = selector(&tools, 1);
+
+        let found = in_conversation(
+            "telegram_alice".into(),
+            selector.search("schedule a cron job", 1),
+        )
+        .await;
+        assert_eq!(names(&found), ["cron_add"]);
+
+        let for_alice = in_conversation(
+            "telegram_alice".into(),
+            selector.select_specs(&tools, "take a screenshot"),
+        )
+        .await;
+        assert_eq!(names(&for_alice), ["shell", "cron_add", "screenshot"]);
+
+        let for_bob = in_conversation(
+            "telegram_bob".into(),
+            selector.select_specs(&tools, "take a screenshot"),
+        )
+        .await;
+        assert_eq!(names(&for_bob), ["shell", "screenshot"]);
+    }
+
+    #[tokio::test]
+    async fn search_limit_is_capped_at_top_k() {
+        let tools = registry();
+        let selector = selector(&tools, 1);
+
+        let found = selector.search("send a cron job screenshot", 50).await;
+        assert_eq!(found.len(), 1);
+    }
+
+    #[test]
+    fn attach_requires_enabled_and_large_registry() {
+        let mut config = Config::default();
+        let mut tools = registry();
+        assert!(attach(&config, &mut tools).is_none());
+
+        config.agent.tool_selection.enabled = true;
+        config.agent.tool_selection.min_registry_size = tools.len();
+        assert!(attach(&config, &mut tools).is_none());
+
+        config.agent.tool_selection.min_registry_size = 2;
+        let selector = attach(&config, &mut tools).unwrap();
+        assert_eq!(tools.last().unwrap().name(), TOOL_SEARCH_NAME);
+        assert!(selector.is_pinned(TOOL_SEARCH_NAME));
+    }
+}
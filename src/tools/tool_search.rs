@@ -0,0 +1,121 @@
+use super::selection::ToolSelector;
+use super::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+pub const TOOL_SEARCH_NAME: &str = "tool_search";
+
+const DEFAULT_LIMIT: usize = 5;
+
+/// Let the agent discover tools that were not sent with the current request.
+/// Matches become callable on the following requests.
+pub struct ToolSearchTool {
+    selector: Arc<ToolSelector>,
+}
+
+impl ToolSearchTool {
+    pub fn new(selector: Arc<ToolSelector>) -> Self {
+        Self { selector }
+    }
+}
+
+#[async_trait]
+impl Tool for ToolSearchTool {
+    fn name(&self) -> &str {
+        TOOL_SEARCH_NAME
+    }
+
+    fn description(&self) -> &str {
+        "Find tools for a task when none of the available tools fit. Only a subset of tools is shown each turn; matching tools become available to call afterwards."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "What you need a tool for, e.g. 'send a push notification'"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max tools to return (default: 5, at most the per-turn tool budget)"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'query' parameter"))?;
+
+        #[allow(clippy::cast_possible_truncation)]
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(DEFAULT_LIMIT, |v| v as usize)
+            .min(self.selector.search_limit());
+
+        let found = self.selector.search(query, limit).await;
+        if found.is_empty() {
+            return Ok(ToolResult {
+                success: true,
+                output: "No tools matched that query.".into(),
+                error: None,
+            });
+        }
+
+        let mut output = format!("Found {} tools (now available to call):\n", found.len());
+        for spec in &found {
+            let _ = writeln!(
+                output,
+                "- {}: {}\n  Parameters: {}",
+                spec.name, spec.description, spec.parameters
+            );
+        }
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ToolSelectionConfig;
+    use crate::memory::embeddings::NoopEmbedding;
+    use crate::tools::{FileReadTool, ShellTool};
+
+    #[tokio::test]
+    async fn search_lists_matching_tools_with_parameters() {
+        let security = Arc::new(crate::security::SecurityPolicy::default());
+        let runtime: Arc<dyn crate::runtime::RuntimeAdapter> =
+            Arc::new(crate::runtime::NativeRuntime::new());
+        let tools: Vec<Box<dyn Tool>> = vec![
+            Box::new(ShellTool::new(security.clone(), runtime)),
+            Box::new(FileReadTool::new(security)),
+        ];
+        let selector = Arc::new(ToolSelector::new(
+            ToolSelectionConfig::default(),
+            Arc::new(NoopEmbedding),
+            &tools,
+        ));
+        let tool = ToolSearchTool::new(selector);
+
+        let result = tool.execute(json!({"query": "read a file"})).await.unwrap();
+        assert!(result.success);
+        let first = result.output.lines().nth(1).unwrap_or_default();
+        assert!(first.starts_with("- file_read:"), "{}", result.output);
+        assert!(result.output.contains("Parameters:"));
+
+        let result = tool.execute(json!({"query": "zzz"})).await.unwrap();
+        assert!(result.output.contains("No tools matched"));
+    }
+}
@@ -64,6 +64,7 @@ impl Provider for MockProvider {
             return Ok(ChatResponse {
                 text: Some("done".into()),
                 tool_calls: vec![],
+                reasoning: Vec::new(),
             });
         }
         Ok(guard.remove(0))
@@ -188,6 +189,7 @@ impl Provider for RecordingProvider {
             return Ok(ChatResponse {
                 text: Some("done".into()),
                 tool_calls: vec![],
+                reasoning: Vec::new(),
             });
         }
         Ok(guard.remove(0))
@@ -235,6 +237,7 @@ fn text_response(text: &str) -> ChatResponse {
     ChatResponse {
         text: Some(text.into()),
         tool_calls: vec![],
+        reasoning: Vec::new(),
     }
 }
 
@@ -242,6 +245,7 @@ fn tool_response(calls: Vec<ToolCall>) -> ChatResponse {
     ChatResponse {
         text: Some(String::new()),
         tool_calls: calls,
+        reasoning: Vec::new(),
     }
 }
 
@@ -365,6 +369,7 @@ async fn e2e_xml_dispatcher_tool_call() {
                     .into(),
             ),
             tool_calls: vec![],
+            reasoning: Vec::new(),
         },
         text_response("XML tool executed"),
     ]));
@@ -153,6 +153,7 @@ fn chat_response_text_only() {
     let resp = ChatResponse {
         text: Some("Hello world".into()),
         tool_calls: vec![],
+        reasoning: Vec::new(),
     };
 
     assert_eq!(resp.text_or_empty(), "Hello world");
@@ -168,6 +169,7 @@ fn chat_response_with_tool_calls() {
             name: "echo".into(),
             arguments: "{}".into(),
         }],
+        reasoning: Vec::new(),
     };
 
     assert!(resp.has_tool_calls());
@@ -180,6 +182,7 @@ fn chat_response_text_or_empty_handles_none() {
     let resp = ChatResponse {
         text: None,
         tool_calls: vec![],
+        reasoning: Vec::new(),
     };
 
     assert_eq!(resp.text_or_empty(), "");
@@ -201,6 +204,7 @@ fn chat_response_multiple_tool_calls() {
                 arguments: r#"{"path": "test.txt"}"#.into(),
             },
         ],
+        reasoning: Vec::new(),
     };
 
     assert!(resp.has_tool_calls());
@@ -0,0 +1,382 @@
+//! Integration tests for the XMPP and Zulip channels against local mock servers.
+//!
+//! The XMPP mock speaks just enough of RFC 6120 (plaintext stream on
+//! loopback, SASL PLAIN, resource binding) to exercise the real client; the
+//! Zulip mock implements the `users/me`, `register`, `events` and `messages`
+//! endpoints the channel uses.
+
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use base64::Engine;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot};
+use zerospider::channels::traits::ChannelMessage;
+use zerospider::channels::{Channel, SendMessage, XmppChannel, ZulipChannel};
+use zerospider::config::{XmppConfig, ZulipConfig};
+
+const WAIT: Duration = Duration::from_secs(10);
+
+// ─────────────────────────────────────────────────────────────────────────────
+// XMPP
+// ─────────────────────────────────────────────────────────────────────────────
+
+const STREAM_HEADER: &str = "<?xml version='1.0'?><stream:stream from='localhost' id='s1' \
+    version='1.0' xmlns='jabber:client' xmlns:stream='http://etherx.jabber.org/streams'>";
+
+async fn read_until(sock: &mut TcpStream, buf: &mut String, needle: &str) {
+    let mut chunk = [0_u8; 4096];
+    while !buf.contains(needle) {
+        let n = sock.read(&mut chunk).await.unwrap();
+        assert!(
+            n > 0,
+            "client closed before sending {needle:?}; got {buf:?}"
+        );
+        buf.push_str(std::str::from_utf8(&chunk[..n]).unwrap());
+    }
+}
+
+/// Accept one client, run the login handshake, push two inbound messages and
+/// hand back everything the client wrote afterwards once it replies.
+async fn run_xmpp_mock(
+    listener: TcpListener,
+    auth_tx: oneshot::Sender<String>,
+    reply_tx: oneshot::Sender<String>,
+) {
+    let (mut sock, _) = listener.accept().await.unwrap();
+    let mut buf = String::new();
+
+    read_until(&mut sock, &mut buf, "<stream:stream").await;
+    let features = "<stream:features><mechanisms xmlns='urn:ietf:params:xml:ns:xmpp-sasl'>\
+        <mechanism>SCRAM-SHA-1</mechanism><mechanism>PLAIN</mechanism></mechanisms></stream:features>";
+    sock.write_all(format!("{STREAM_HEADER}{features}").as_bytes())
+        .await
+        .unwrap();
+
+    read_until(&mut sock, &mut buf, "</auth>").await;
+    auth_tx.send(buf.clone()).unwrap();
+    buf.clear();
+    sock.write_all(b"<success xmlns='urn:ietf:params:xml:ns:xmpp-sasl'/>")
+        .await
+        .unwrap();
+
+    read_until(&mut sock, &mut buf, "<stream:stream").await;
+    buf.clear();
+    let features =
+        "<stream:features><bind xmlns='urn:ietf:params:xml:ns:xmpp-bind'/></stream:features>";
+    sock.write_all(format!("{STREAM_HEADER}{features}").as_bytes())
+        .await
+        .unwrap();
+
+    read_until(&mut sock, &mut buf, "</iq>").await;
+    assert!(buf.contains("<resource>zeroclaw</resource>"));
+    buf.clear();
+    sock.write_all(
+        b"<iq type='result' id='bind_1'><bind xmlns='urn:ietf:params:xml:ns:xmpp-bind'>\
+          <jid>bot@localhost/zeroclaw</jid></bind></iq>",
+    )
+    .await
+    .unwrap();
+
+    read_until(&mut sock, &mut buf, "team@conference.localhost/bot").await;
+    buf.clear();
+    sock.write_all(
+        "<message from='mallory@localhost/x' type='chat'><body>ignored</body></message>\
+         <message from='team@conference.localhost/carol' type='groupchat' id='g1'>\
+         <body>no mention</body></message>\
+         <message from='alice@localhost/laptop' type='chat' id='m1'>\
+         <body>ping &amp; pong</body><thread>t-42</thread></message>\
+         <message from='team@conference.localhost/carol' type='groupchat' id='g2'>\
+         <body>bot: deploy status?</body></message>"
+            .as_bytes(),
+    )
+    .await
+    .unwrap();
+
+    read_until(&mut sock, &mut buf, "</body>").await;
+    read_until(&mut sock, &mut buf, "</message>").await;
+    let _ = reply_tx.send(buf);
+}
+
+async fn recv(rx: &mut mpsc::Receiver<ChannelMessage>) -> ChannelMessage {
+    tokio::time::timeout(WAIT, rx.recv())
+        .await
+        .expect("timed out waiting for inbound message")
+        .expect("channel closed")
+}
+
+#[tokio::test]
+async fn xmpp_channel_logs_in_receives_and_replies_in_thread() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (auth_tx, auth_rx) = oneshot::channel();
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let server = tokio::spawn(run_xmpp_mock(listener, auth_tx, reply_tx));
+
+    let channel = Arc::new(XmppChannel::from_config(&XmppConfig {
+        jid: "bot@localhost".into(),
+        password: "secret".into(),
+        server: Some("127.0.0.1".into()),
+        port,
+        rooms: vec!["team@conference.localhost".into()],
+        nickname: None,
+        allowed_users: vec!["alice@localhost".into(), "team@conference.localhost".into()],
+        mention_only: true,
+        starttls: false,
+    }));
+    let (tx, mut rx) = mpsc::channel(8);
+    let listener_task = {
+        let channel = Arc::clone(&channel);
+        tokio::spawn(async move { channel.listen(tx).await })
+    };
+
+    let auth = tokio::time::timeout(WAIT, auth_rx).await.unwrap().unwrap();
+    let expected = base64::engine::general_purpose::STANDARD.encode("\0bot\0secret");
+    assert!(auth.contains("mechanism='PLAIN'"));
+    assert!(auth.contains(&expected));
+
+    let direct = recv(&mut rx).await;
+    assert_eq!(direct.channel, "xmpp");
+    assert_eq!(direct.sender, "alice@localhost");
+    assert_eq!(direct.reply_target, "alice@localhost");
+    assert_eq!(direct.content, "ping & pong");
+    assert_eq!(direct.thread_ts.as_deref(), Some("t-42"));
+
+    let room = recv(&mut rx).await;
+    assert_eq!(room.reply_target, "team@conference.localhost");
+    assert_eq!(room.content, "deploy status?");
+
+    channel
+        .send(&SendMessage::new("a < b", &direct.reply_target).in_thread(direct.thread_ts.clone()))
+        .await
+        .unwrap();
+    let reply = tokio::time::timeout(WAIT, reply_rx).await.unwrap().unwrap();
+    assert!(reply.contains("to='alice@localhost'"));
+    assert!(reply.contains("type='chat'"));
+    assert!(reply.contains("<body>a &lt; b</body>"));
+    assert!(reply.contains("<thread>t-42</thread>"));
+
+    listener_task.abort();
+    server.abort();
+}
+
+#[tokio::test]
+async fn xmpp_channel_refuses_plaintext_to_remote_server() {
+    let channel = XmppChannel::from_config(&XmppConfig {
+        jid: "bot@example.com".into(),
+        password: "secret".into(),
+        server: Some("192.0.2.1".into()),
+        port: 5222,
+        rooms: Vec::new(),
+        nickname: None,
+        allowed_users: vec!["*".into()],
+        mention_only: false,
+        starttls: false,
+    });
+    let (tx, _rx) = mpsc::channel(1);
+    // 192.0.2.0/24 is TEST-NET-1: the guard must trip before any connect.
+    let err = tokio::time::timeout(WAIT, channel.listen(tx))
+        .await
+        .unwrap()
+        .unwrap_err();
+    assert!(err.to_string().contains("loopback"), "{err}");
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Zulip
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Clone, Default)]
+struct ZulipMock {
+    polls: Arc<AtomicUsize>,
+    last_event_ids: Arc<Mutex<Vec<String>>>,
+    sent: Arc<Mutex<Vec<HashMap<String, String>>>>,
+}
+
+fn authorized(headers: &HeaderMap) -> bool {
+    let expected = format!(
+        "Basic {}",
+        base64::engine::general_purpose::STANDARD.encode("bot@example.com:key")
+    );
+    headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v == expected)
+}
+
+fn parse_form(body: &str) -> HashMap<String, String> {
+    body.split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| {
+            let decode = |s: &str| {
+                urlencoding::decode(&s.replace('+', " "))
+                    .unwrap()
+                    .into_owned()
+            };
+            (decode(k), decode(v))
+        })
+        .collect()
+}
+
+async fn users_me(headers: HeaderMap) -> (StatusCode, Json<Value>) {
+    if !authorized(&headers) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"result": "error", "msg": "Invalid API key"})),
+        );
+    }
+    (
+        StatusCode::OK,
+        Json(json!({"result": "success", "user_id": 1, "full_name": "Claw Bot"})),
+    )
+}
+
+async fn register(headers: HeaderMap, body: String) -> Json<Value> {
+    assert!(authorized(&headers));
+    assert!(parse_form(&body)["event_types"].contains("message"));
+    Json(json!({"result": "success", "queue_id": "q-1", "last_event_id": -1}))
+}
+
+async fn events(
+    State(mock): State<ZulipMock>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Json<Value> {
+    assert_eq!(query["queue_id"], "q-1");
+    mock.last_event_ids
+        .lock()
+        .unwrap()
+        .push(query["last_event_id"].clone());
+    if mock.polls.fetch_add(1, Ordering::SeqCst) > 0 {
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        return Json(json!({"result": "success", "events": []}));
+    }
+    let stream_msg = |id: i64, sender_id: i64, email: &str, content: &str, flags: Value| {
+        json!({
+            "type": "message",
+            "id": id,
+            "flags": flags,
+            "message": {
+                "id": 100 + id,
+                "sender_id": sender_id,
+                "sender_email": email,
+                "type": "stream",
+                "display_recipient": "ops",
+                "subject": "deploys",
+                "content": content,
+                "timestamp": 1_700_000_000
+            }
+        })
+    };
+    Json(json!({
+        "result": "success",
+        "events": [
+            stream_msg(0, 7, "alice@example.com", "not for the bot", json!([])),
+            stream_msg(1, 7, "alice@example.com", "@**Claw Bot** status?", json!(["mentioned"])),
+        ]
+    }))
+}
+
+async fn send_message(
+    State(mock): State<ZulipMock>,
+    headers: HeaderMap,
+    body: String,
+) -> Json<Value> {
+    assert!(authorized(&headers));
+    mock.sent.lock().unwrap().push(parse_form(&body));
+    Json(json!({"result": "success", "id": 555}))
+}
+
+async fn start_zulip_mock() -> (String, ZulipMock) {
+    let mock = ZulipMock::default();
+    let app = Router::new()
+        .route("/api/v1/users/me", get(users_me))
+        .route("/api/v1/register", post(register))
+        .route("/api/v1/events", get(events))
+        .route("/api/v1/messages", post(send_message))
+        .with_state(mock.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{addr}"), mock)
+}
+
+fn zulip_channel(url: &str, api_key: &str) -> ZulipChannel {
+    ZulipChannel::from_config(&ZulipConfig {
+        url: url.into(),
+        bot_email: "bot@example.com".into(),
+        api_key: api_key.into(),
+        streams: vec!["ops".into()],
+        allowed_users: vec!["alice@example.com".into()],
+        mention_only: true,
+    })
+}
+
+#[tokio::test]
+async fn zulip_channel_receives_mentions_and_replies_to_topic() {
+    let (url, mock) = start_zulip_mock().await;
+    let channel = Arc::new(zulip_channel(&url, "key"));
+    assert!(channel.health_check().await);
+
+    let (tx, mut rx) = mpsc::channel(8);
+    let listener_task = {
+        let channel = Arc::clone(&channel);
+        tokio::spawn(async move { channel.listen(tx).await })
+    };
+
+    let msg = recv(&mut rx).await;
+    assert_eq!(msg.channel, "zulip");
+    assert_eq!(msg.content, "status?");
+    assert_eq!(msg.reply_target, "stream:ops");
+    assert_eq!(msg.thread_ts.as_deref(), Some("deploys"));
+
+    channel
+        .send(&SendMessage::new("all green", &msg.reply_target).in_thread(msg.thread_ts.clone()))
+        .await
+        .unwrap();
+    channel
+        .send(&SendMessage::new("hi alice", "private:alice@example.com"))
+        .await
+        .unwrap();
+
+    let sent = mock.sent.lock().unwrap().clone();
+    assert_eq!(sent.len(), 2);
+    assert_eq!(sent[0]["type"], "stream");
+    assert_eq!(sent[0]["to"], "ops");
+    assert_eq!(sent[0]["topic"], "deploys");
+    assert_eq!(sent[0]["content"], "all green");
+    assert_eq!(sent[1]["type"], "private");
+    assert_eq!(sent[1]["to"], r#"["alice@example.com"]"#);
+
+    // The second long-poll resumes after the last seen event id.
+    tokio::time::timeout(WAIT, async {
+        while mock.polls.load(Ordering::SeqCst) < 2 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(*mock.last_event_ids.lock().unwrap(), vec!["-1", "1"]);
+    listener_task.abort();
+}
+
+#[tokio::test]
+async fn zulip_channel_reports_bad_credentials() {
+    let (url, _mock) = start_zulip_mock().await;
+    let channel = zulip_channel(&url, "wrong");
+    assert!(!channel.health_check().await);
+
+    let (tx, _rx) = mpsc::channel(1);
+    let err = channel.listen(tx).await.unwrap_err();
+    assert!(err.to_string().contains("Invalid API key"), "{err}");
+}